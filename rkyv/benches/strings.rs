@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use benchlib::{divan, Rng};
+use rkyv::{
+    rancor::Failure,
+    with::{InlineCapacity, Map},
+    Archive, Archived, Serialize,
+};
+
+// Inline capacities to compare. 8 is the default on 32-bit pointer widths.
+const CAPACITIES: &[usize] = &[8, 16, 24, 32];
+
+#[derive(Archive, Serialize)]
+pub struct Names<const N: usize> {
+    #[rkyv(with = Map<InlineCapacity<N>>)]
+    pub names: Vec<String>,
+}
+
+// Generates strings which are between 12 and 24 bytes long.
+fn generate_names<const N: usize>() -> Names<N> {
+    const NAMES: usize = 10_000;
+
+    let mut rng = benchlib::rng();
+    let names = (0..NAMES)
+        .map(|_| {
+            let len = rng.gen_range(12..=24);
+            (0..len).map(|_| rng.gen_range('a'..='z')).collect()
+        })
+        .collect();
+    Names { names }
+}
+
+#[divan::bench(consts = CAPACITIES, min_time = Duration::from_secs(3))]
+fn ser<const N: usize>(bencher: divan::Bencher) {
+    let data = generate_names::<N>();
+    let mut bytes = rkyv::util::AlignedVec::<16>::new();
+
+    bencher.bench_local(|| {
+        let mut buffer = core::mem::take(&mut bytes);
+        buffer.clear();
+
+        bytes = divan::black_box(
+            rkyv::api::high::to_bytes_in::<_, Failure>(
+                divan::black_box(&data),
+                divan::black_box(buffer),
+            )
+            .unwrap(),
+        );
+    });
+}
+
+#[divan::bench(consts = CAPACITIES, min_time = Duration::from_secs(3))]
+fn read<const N: usize>(bencher: divan::Bencher) {
+    let bytes = rkyv::to_bytes::<Failure>(&generate_names::<N>()).unwrap();
+    let archived = rkyv::access::<Archived<Names<N>>, Failure>(&bytes).unwrap();
+
+    bencher.bench_local(|| {
+        divan::black_box(archived)
+            .names
+            .iter()
+            .map(|name| name.as_bytes()[name.len() - 1] as usize)
+            .sum::<usize>()
+    });
+}
+
+#[divan::bench(consts = CAPACITIES, min_time = Duration::from_secs(3))]
+fn check<const N: usize>(bencher: divan::Bencher) {
+    let bytes = rkyv::to_bytes::<Failure>(&generate_names::<N>()).unwrap();
+
+    bencher
+        .counter(divan::counter::BytesCount::new(bytes.len()))
+        .bench_local(|| {
+            rkyv::access::<Archived<Names<N>>, Failure>(divan::black_box(
+                &bytes,
+            ))
+            .unwrap();
+        });
+}
+
+fn main() {
+    divan::main();
+}
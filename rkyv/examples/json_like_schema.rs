@@ -2,6 +2,8 @@ use std::{collections::HashMap, fmt};
 
 use rkyv::{access, rancor::Error, Archive, Deserialize, Serialize};
 
+// rkyv provides a ready-made version of this type as `rkyv::dynamic::Value`.
+// This example shows how to write a recursive type like it yourself.
 #[derive(Archive, Debug, Deserialize, Serialize)]
 // We have a recursive type, which requires some special handling
 //
@@ -1,9 +1,12 @@
+use core::alloc::Layout;
+
 use bytecheck::CheckBytes;
 use ptr_meta::Pointee;
 use rancor::{Source, Strategy};
 
 use crate::{
     api::{access_pos_unchecked, root_position},
+    projection::FieldOffset,
     validation::{ArchiveContext, ArchiveContextExt},
     Portable,
 };
@@ -128,6 +131,32 @@ where
     unsafe { Ok(access_pos_unchecked::<T>(bytes, pos)) }
 }
 
+/// Access a single field of the root of a byte slice with a given context.
+///
+/// Only the bounds and alignment of the root are checked. The field and the
+/// values it points to are validated, but the rest of the root is not.
+///
+/// Most of the time, the context should be newly-created and not reused. Prefer
+/// `access_field` whenever possible.
+pub fn access_field_with_context<'a, P, F, C, E>(
+    bytes: &'a [u8],
+    field: FieldOffset<P, F>,
+    context: &mut C,
+) -> Result<&'a F, E>
+where
+    P: Portable,
+    F: Portable + CheckBytes<Strategy<C, E>> + Pointee<Metadata = ()>,
+    C: ArchiveContext<E> + ?Sized,
+    E: Source,
+{
+    let pos = root_position::<P>(bytes.len());
+    context.check_subtree_ptr(
+        bytes.as_ptr().wrapping_add(pos),
+        &Layout::new::<P>(),
+    )?;
+    access_pos_with_context::<F, C, E>(bytes, pos + field.offset(), context)
+}
+
 /// Access a byte slice with a given context.
 ///
 /// This is a safe alternative to [`access_unchecked`].
@@ -3,18 +3,34 @@
 //! These APIs have default writers, automatically manage allocators, and
 //! support shared pointers.
 
+use core::{fmt, marker::PhantomData, mem::MaybeUninit, ops::Deref};
+
 use bytecheck::CheckBytes;
 use rancor::{Source, Strategy};
 
 use crate::{
     api::{
+        access_field_with_context, access_pos_unchecked,
         access_pos_unchecked_mut, access_pos_with_context, access_with_context,
-        check_pos_with_context, deserialize_using, root_position,
+        check_pos_with_context, deserialize_into, deserialize_using,
+        high::DeserializeBorrowed, root_position,
     },
-    de::pooling::Pool,
+    de::{pooling::Pool, Borrower},
+    endian::{check_endianness, ArchivedEndianTaggedRoot},
+    header::check_header,
+    projection::FieldOffset,
     seal::Seal,
+    traits::Versioned,
+    type_hash::TypeHash,
+    util::{verify_checksum, AlignedBytes},
     validation::{
-        archive::ArchiveValidator, shared::SharedValidator, Validator,
+        archive::{ArchiveValidator, ValidationLimits},
+        shared::SharedValidator,
+        DeferredChecks, Validator,
+    },
+    version::{
+        access_compat_with_context, check_current_version,
+        ArchivedVersionedRoot, CheckCompat, Compat, DeserializeCompat,
     },
     Archive, Deserialize, Portable,
 };
@@ -22,11 +38,27 @@ use crate::{
 /// A high-level validator.
 ///
 /// This is part of the [high-level API](crate::api::high).
-pub type HighValidator<'a, E> =
-    Strategy<Validator<ArchiveValidator<'a>, SharedValidator>, E>;
+pub type HighValidator<'a, E> = Strategy<
+    Validator<
+        ArchiveValidator<'a>,
+        SharedValidator,
+        DeferredChecks<ArchiveValidator<'a>, SharedValidator, E>,
+    >,
+    E,
+>;
 
-fn validator(bytes: &[u8]) -> Validator<ArchiveValidator<'_>, SharedValidator> {
-    Validator::new(ArchiveValidator::new(bytes), SharedValidator::new())
+pub(super) fn validator<E>(
+    bytes: &[u8],
+) -> Validator<
+    ArchiveValidator<'_>,
+    SharedValidator,
+    DeferredChecks<ArchiveValidator<'_>, SharedValidator, E>,
+> {
+    Validator::with_deferred(
+        ArchiveValidator::new(bytes),
+        SharedValidator::new(),
+        DeferredChecks::new(),
+    )
 }
 
 /// Access a byte slice with a given root position.
@@ -115,6 +147,431 @@ where
     access_with_context::<_, _, E>(bytes, &mut validator(bytes))
 }
 
+/// Access a single field of the root of a byte slice.
+///
+/// Only the field and the values it points to are validated, so this is much
+/// faster than [`access`] when only a small part of a large archive is needed.
+/// Field offsets are generated for structs which derive `Archive` with
+/// `#[rkyv(field_offsets)]`, and can be chained with [`FieldOffset::then`] to
+/// access nested fields.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{api::high::access_field, rancor::Error, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(field_offsets)]
+/// struct Example {
+///     version: u16,
+///     values: Vec<i32>,
+/// }
+///
+/// let value = Example {
+///     version: 2,
+///     values: vec![3, 1, 4, 1, 5],
+/// };
+///
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let version =
+///     access_field::<_, _, Error>(&bytes, ArchivedExample::VERSION).unwrap();
+///
+/// assert_eq!(*version, 2);
+/// ```
+pub fn access_field<P, F, E>(
+    bytes: &[u8],
+    field: FieldOffset<P, F>,
+) -> Result<&F, E>
+where
+    P: Portable,
+    F: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    access_field_with_context::<_, _, _, E>(bytes, field, &mut validator(bytes))
+}
+
+/// Access a byte slice while enforcing validation limits.
+///
+/// This is like [`access`], but fails validation if the archive exceeds any of
+/// the given [`ValidationLimits`]. Use this when accessing untrusted archives
+/// to bound the stack depth and amount of work that validation may use.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use core::num::NonZeroUsize;
+///
+/// use rkyv::{
+///     api::high::access_with_limits, rancor::Error, to_bytes,
+///     validation::archive::ValidationLimits, Archived,
+/// };
+///
+/// let value = vec![vec![1u32, 2], vec![3]];
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+///
+/// // The root, the outer vec, and the inner vecs are each validated in a
+/// // nested subtree range.
+/// let limits = ValidationLimits {
+///     max_depth: NonZeroUsize::new(3),
+///     ..Default::default()
+/// };
+/// access_with_limits::<Archived<Vec<Vec<u32>>>, Error>(&bytes, limits)
+///     .unwrap();
+///
+/// let limits = ValidationLimits {
+///     max_depth: NonZeroUsize::new(2),
+///     ..Default::default()
+/// };
+/// access_with_limits::<Archived<Vec<Vec<u32>>>, Error>(&bytes, limits)
+///     .unwrap_err();
+/// ```
+pub fn access_with_limits<T, E>(
+    bytes: &[u8],
+    limits: ValidationLimits,
+) -> Result<&T, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    let mut context = Validator::with_deferred(
+        ArchiveValidator::with_limits(bytes, limits),
+        SharedValidator::new(),
+        DeferredChecks::new(),
+    );
+    access_with_context::<_, _, E>(bytes, &mut context)
+}
+
+/// The alignment that buffers are copied to by [`access_unaligned`].
+const BUFFER_ALIGNMENT: usize =
+    if cfg!(feature = "unaligned") { 1 } else { 16 };
+
+/// An archived value accessed from a buffer which may have been copied to
+/// align it.
+///
+/// This is returned by [`access_unaligned`] and dereferences to the archived
+/// value.
+pub struct AlignedAccess<'a, T> {
+    bytes: AlignedBytes<'a, BUFFER_ALIGNMENT>,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<T> AlignedAccess<'_, T> {
+    /// Returns the buffer which the archived value was accessed from.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns whether the archived value was accessed from the original
+    /// buffer instead of an aligned copy of it.
+    pub fn is_borrowed(&self) -> bool {
+        self.bytes.is_borrowed()
+    }
+}
+
+impl<T: Portable> Deref for AlignedAccess<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The buffer was validated by `access_unaligned` and can't be
+        // modified afterward.
+        unsafe {
+            access_pos_unchecked::<T>(
+                &self.bytes,
+                root_position::<T>(self.bytes.len()),
+            )
+        }
+    }
+}
+
+impl<T: Portable + fmt::Debug> fmt::Debug for AlignedAccess<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+/// Access a byte slice which may not be aligned.
+///
+/// If the byte slice is not sufficiently aligned, it is copied into an
+/// [`AlignedVec`](crate::util::AlignedVec) before it is validated. Otherwise,
+/// the byte slice is accessed in place. When the `unaligned` feature is
+/// enabled, the byte slice is never copied.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::access_unaligned, rancor::Error, to_bytes, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+///
+/// // Place the archive at an arbitrary offset, like in a network packet
+/// let mut packet = vec![0xff; 3];
+/// packet.extend_from_slice(&bytes);
+///
+/// let archived =
+///     access_unaligned::<ArchivedExample, Error>(&packet[3..]).unwrap();
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+/// ```
+pub fn access_unaligned<T, E>(bytes: &[u8]) -> Result<AlignedAccess<'_, T>, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    let bytes = AlignedBytes::new(bytes);
+    access::<T, E>(&bytes)?;
+    Ok(AlignedAccess {
+        bytes,
+        _phantom: PhantomData,
+    })
+}
+
+/// Access a byte slice with a version tag.
+///
+/// The version tag is checked before the buffer is validated. Buffers tagged
+/// with a newer version than `T` will fail to access. Buffers tagged with an
+/// older version are missing the `#[rkyv(default)]` fields of `T`, so they
+/// will also fail to access and must be accessed with [`access_compat`]
+/// instead. See the [`version`](crate::version) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_compat, access_versioned, to_bytes_versioned},
+///     rancor::Error,
+///     version::check_version,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 1)]
+/// struct ExampleV1 {
+///     value: i32,
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 2)]
+/// struct ExampleV2 {
+///     value: i32,
+///     #[rkyv(default)]
+///     name: Option<String>,
+/// }
+///
+/// let v1 = to_bytes_versioned::<_, Error>(&ExampleV1 { value: 42 }).unwrap();
+/// let v2 = to_bytes_versioned::<_, Error>(&ExampleV2 {
+///     value: 42,
+///     name: Some("answer".to_string()),
+/// })
+/// .unwrap();
+///
+/// let archived = access_versioned::<ArchivedExampleV2, Error>(&v2).unwrap();
+/// assert_eq!(archived.name.as_deref(), Some("answer"));
+///
+/// // Newer archives can't be read by older versions
+/// assert!(access_versioned::<ArchivedExampleV1, Error>(&v2).is_err());
+///
+/// // Older archives can be read by newer versions, but are missing fields
+/// assert_eq!(check_version::<ArchivedExampleV2, Error>(&v1).unwrap(), 1);
+/// assert!(access_versioned::<ArchivedExampleV2, Error>(&v1).is_err());
+/// let compat = access_compat::<ArchivedExampleV2, Error>(&v1).unwrap();
+/// assert_eq!(compat.version(), 1);
+/// ```
+pub fn access_versioned<T, E>(bytes: &[u8]) -> Result<&T, E>
+where
+    T: Portable + Versioned,
+    ArchivedVersionedRoot<T>: for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    check_current_version::<T, E>(bytes)?;
+    Ok(access::<ArchivedVersionedRoot<T>, E>(bytes)?.get())
+}
+
+/// Access a byte slice with an endianness tag.
+///
+/// The endianness tag is checked before the buffer is validated. Buffers
+/// tagged with a different byte order than rkyv was built to access will fail
+/// to access. See the [`endian`](crate::endian) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_endian_tagged, to_bytes_endian_tagged},
+///     rancor::Error,
+///     Archived,
+/// };
+///
+/// let bytes = to_bytes_endian_tagged::<_, Error>(&42u32).unwrap();
+/// let archived =
+///     access_endian_tagged::<Archived<u32>, Error>(&bytes).unwrap();
+/// assert_eq!(*archived, 42);
+/// ```
+pub fn access_endian_tagged<T, E>(bytes: &[u8]) -> Result<&T, E>
+where
+    T: Portable,
+    ArchivedEndianTaggedRoot<T>: for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    check_endianness::<E>(bytes)?;
+    Ok(access::<ArchivedEndianTaggedRoot<T>, E>(bytes)?.get())
+}
+
+/// Access a byte slice prefixed with an archive header.
+///
+/// The header written by [`to_bytes_with_header`] is checked before the rest of
+/// the buffer is validated. Archives written with different format control
+/// options or with a root type whose [`TypeHash`] differs from `T` fail to
+/// access with an error describing the mismatch. See the
+/// [`header`](crate::header) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// [`to_bytes_with_header`]: crate::api::high::to_bytes_with_header
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_with_header, to_bytes_with_header},
+///     rancor::Error,
+///     Archived,
+/// };
+///
+/// let bytes = to_bytes_with_header::<_, Error>(&42u32).unwrap();
+/// let archived = access_with_header::<Archived<u32>, Error>(&bytes).unwrap();
+/// assert_eq!(*archived, 42);
+///
+/// // The archive was not written with a `u64` root
+/// assert!(access_with_header::<Archived<u64>, Error>(&bytes).is_err());
+/// ```
+pub fn access_with_header<T, E>(bytes: &[u8]) -> Result<&T, E>
+where
+    T: Portable + TypeHash + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    access::<T, E>(check_header::<T, E>(bytes)?)
+}
+
+/// Access a byte slice with a version tag, allowing older versions which are
+/// missing `#[rkyv(default)]` fields.
+///
+/// Unlike [`access_versioned`], buffers tagged with an older version than `T`
+/// can be accessed as long as they are at least
+/// [`MIN_VERSION`](crate::traits::Versioned::MIN_VERSION). Only the
+/// fields present at the tagged version are validated. See the
+/// [`version`](crate::version) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_compat, to_bytes_versioned},
+///     rancor::Error,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 1)]
+/// struct ExampleV1 {
+///     value: i32,
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 2)]
+/// struct ExampleV2 {
+///     value: i32,
+///     #[rkyv(default)]
+///     name: Option<String>,
+/// }
+///
+/// let v1 = to_bytes_versioned::<_, Error>(&ExampleV1 { value: 42 }).unwrap();
+///
+/// let compat = access_compat::<ArchivedExampleV2, Error>(&v1).unwrap();
+/// assert_eq!(compat.version(), 1);
+/// // The archive is missing `name`, so it can't be accessed directly
+/// assert!(compat.get().is_none());
+/// ```
+pub fn access_compat<T, E>(bytes: &[u8]) -> Result<Compat<'_, T>, E>
+where
+    T: for<'a> CheckCompat<HighValidator<'a, E>>,
+    E: Source,
+{
+    access_compat_with_context::<T, _, E>(bytes, &mut validator(bytes))
+}
+
+/// Access a byte slice with a checksum footer.
+///
+/// The footer written by [`Checksummed`] is verified before the rest of the
+/// buffer is validated. This detects corruption of primitive data which
+/// structural validation alone would accept.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// [`Checksummed`]: crate::util::Checksummed
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_checked_crc, to_bytes_in},
+///     rancor::Error,
+///     util::{AlignedVec, Checksummed},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     value: i32,
+/// }
+///
+/// let writer = Checksummed::new(AlignedVec::<16>::new());
+/// let mut bytes = to_bytes_in::<_, Error>(&Example { value: 42 }, writer)
+///     .unwrap()
+///     .finish::<Error>()
+///     .unwrap();
+///
+/// let archived =
+///     access_checked_crc::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.value, 42);
+///
+/// // Flipping a bit in the value is caught by the checksum
+/// bytes[0] ^= 1;
+/// assert!(access_checked_crc::<ArchivedExample, Error>(&bytes).is_err());
+/// ```
+pub fn access_checked_crc<T, E>(bytes: &[u8]) -> Result<&T, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    access::<T, E>(verify_checksum::<E>(bytes)?)
+}
+
 /// Mutably access a byte slice with a given root position.
 ///
 /// This is a safe alternative to [`access_pos_unchecked_mut`] and is part of
@@ -161,8 +618,7 @@ where
     T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
     E: Source,
 {
-    let mut context = validator(bytes);
-    check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
+    check_pos_with_context::<T, _, E>(bytes, pos, &mut validator(bytes))?;
     unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
 }
 
@@ -210,9 +666,8 @@ where
     T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
     E: Source,
 {
-    let mut context = validator(bytes);
     let pos = root_position::<T>(bytes.len());
-    check_pos_with_context::<T, _, E>(bytes, pos, &mut context)?;
+    check_pos_with_context::<T, _, E>(bytes, pos, &mut validator(bytes))?;
     unsafe { Ok(access_pos_unchecked_mut::<T>(bytes, pos)) }
 }
 
@@ -256,3 +711,181 @@ where
     let mut deserializer = Pool::default();
     deserialize_using(access::<T::Archived, E>(bytes)?, &mut deserializer)
 }
+
+/// Deserialize a value from the given bytes into `out`.
+///
+/// This is like [`from_bytes`], but the value is constructed in `out` instead
+/// of being returned. Derived impls of `Deserialize` for structs and impls for
+/// arrays construct their fields in place, so large values are not moved
+/// after they are deserialized. See [`deserialize_into`] for more details.
+///
+/// Returns a reference to the initialized value. If an error is returned,
+/// `out` is left uninitialized.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// [`deserialize_into`]: crate::api::deserialize_into
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     api::high::from_bytes_into, rancor::Error, to_bytes, Archive,
+///     Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let mut out = MaybeUninit::uninit();
+/// let deserialized = from_bytes_into::<Example, Error>(&bytes, &mut out)
+///     .unwrap();
+///
+/// assert_eq!(*deserialized, value);
+/// # unsafe { out.assume_init_drop() };
+/// ```
+pub fn from_bytes_into<'o, T, E>(
+    bytes: &[u8],
+    out: &'o mut MaybeUninit<T>,
+) -> Result<&'o mut T, E>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>
+        + Deserialize<T, Strategy<Pool, E>>,
+    E: Source,
+{
+    let mut deserializer = Pool::default();
+    deserialize_into(access::<T::Archived, E>(bytes)?, out, &mut deserializer)
+}
+
+/// Deserialize a value from the given bytes, borrowing data from them where
+/// possible.
+///
+/// Fields which use the [`Borrowed`](crate::with::Borrowed) wrapper are
+/// deserialized as references into `bytes` instead of being copied, so the
+/// deserialized value may borrow from `bytes` for `'a`.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::from_bytes_borrowed, rancor::Error, to_bytes,
+///     with::Borrowed, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example<'a> {
+///     #[rkyv(with = Borrowed)]
+///     name: &'a str,
+///     #[rkyv(with = Borrowed)]
+///     data: &'a [u8],
+/// }
+///
+/// let value = Example {
+///     name: "pi",
+///     data: &[3, 1, 4, 1, 5],
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+///
+/// let deserialized = from_bytes_borrowed::<Example, Error>(&bytes).unwrap();
+/// assert_eq!(deserialized.name, "pi");
+/// assert_eq!(deserialized.data, &[3, 1, 4, 1, 5]);
+/// assert!(bytes.as_ptr_range().contains(&deserialized.name.as_ptr()));
+/// ```
+pub fn from_bytes_borrowed<'a, T, E>(bytes: &'a [u8]) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: for<'v> CheckBytes<HighValidator<'v, E>>
+        + DeserializeBorrowed<'a, T, E>,
+    E: Source,
+{
+    let mut deserializer = Borrower::new(bytes, Pool::new());
+    deserialize_using(access::<T::Archived, E>(bytes)?, &mut deserializer)
+}
+
+/// Deserialize a value from the given shared bytes, sharing ownership of them
+/// where possible.
+///
+/// Fields which use the [`AsOwnedBytes`](crate::with::AsOwnedBytes) wrapper
+/// are deserialized as slices of `bytes` instead of being copied. The bytes
+/// must be suitably aligned for the archived type.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// See [`AsOwnedBytes`](crate::with::AsOwnedBytes) for an example.
+#[cfg(feature = "bytes-1")]
+pub fn from_shared_bytes<T, E>(bytes: &bytes_1::Bytes) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>
+        + Deserialize<T, super::HighSlicingDeserializer<E>>,
+    E: Source,
+{
+    let mut deserializer =
+        crate::de::BytesSlicer::new(bytes.clone(), Pool::new());
+    deserialize_using(access::<T::Archived, E>(bytes)?, &mut deserializer)
+}
+
+/// Deserialize a value from a byte slice with a version tag, filling in fields
+/// which are absent from older versions with their default values.
+///
+/// See [`access_compat`] for which versions are accepted.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{from_bytes_compat, to_bytes_versioned},
+///     rancor::Error,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 1)]
+/// struct ExampleV1 {
+///     value: i32,
+/// }
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// #[rkyv(version = 2)]
+/// struct ExampleV2 {
+///     value: i32,
+///     #[rkyv(default)]
+///     name: Option<String>,
+/// }
+///
+/// let v1 = to_bytes_versioned::<_, Error>(&ExampleV1 { value: 42 }).unwrap();
+/// let value = from_bytes_compat::<ExampleV2, Error>(&v1).unwrap();
+/// assert_eq!(
+///     value,
+///     ExampleV2 {
+///         value: 42,
+///         name: None,
+///     }
+/// );
+/// ```
+pub fn from_bytes_compat<T, E>(bytes: &[u8]) -> Result<T, E>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckCompat<HighValidator<'a, E>>
+        + DeserializeCompat<T, Strategy<Pool, E>>,
+    E: Source,
+{
+    let compat = access_compat::<T::Archived, E>(bytes)?;
+    compat.deserialize(Strategy::wrap(&mut Pool::default()))
+}
@@ -0,0 +1,408 @@
+//! Compressed archives.
+
+use core::{error::Error, fmt};
+#[cfg(feature = "bytecheck")]
+use core::{marker::PhantomData, ops::Deref};
+
+use rancor::{fail, ResultExt as _, Source};
+
+use super::{to_bytes, HighSerializer};
+#[cfg(feature = "bytecheck")]
+use crate::Portable;
+use crate::{
+    alloc::vec::Vec, ser::allocator::ArenaHandle, util::AlignedVec, Serialize,
+};
+
+/// The bytes which start every compressed archive.
+const MAGIC: [u8; 4] = *b"rkyC";
+/// The size of the header which precedes the compressed archive.
+///
+/// The header contains the magic bytes, a one-byte compression ID followed by
+/// three bytes of padding, and the little-endian `u64` length of the
+/// uncompressed archive.
+const HEADER_SIZE: usize = 16;
+
+const LZ4_ID: u8 = 1;
+const ZSTD_ID: u8 = 2;
+
+/// A compression algorithm for [`to_bytes_compressed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Compression {
+    /// LZ4 block compression with `lz4_flex`.
+    #[cfg(feature = "lz4_flex")]
+    Lz4,
+    /// Zstandard compression with the given compression level. A level of `0`
+    /// uses zstd's default level.
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+impl Compression {
+    fn id(&self) -> u8 {
+        match self {
+            #[cfg(feature = "lz4_flex")]
+            Self::Lz4 => LZ4_ID,
+            #[cfg(feature = "zstd")]
+            Self::Zstd(_) => ZSTD_ID,
+        }
+    }
+
+    fn compress<E: Source>(&self, bytes: &[u8]) -> Result<Vec<u8>, E> {
+        match self {
+            #[cfg(feature = "lz4_flex")]
+            Self::Lz4 => Ok(lz4_flex::block::compress(bytes)),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => {
+                zstd::bulk::compress(bytes, *level).into_error()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct InvalidHeader;
+
+impl fmt::Display for InvalidHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "compressed archive header is missing or invalid")
+    }
+}
+
+impl Error for InvalidHeader {}
+
+#[derive(Debug)]
+struct UnsupportedCompression {
+    id: u8,
+}
+
+impl fmt::Display for UnsupportedCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.id {
+            LZ4_ID => write!(
+                f,
+                "archive is compressed with LZ4, but the `lz4_flex` feature \
+                 is not enabled",
+            ),
+            ZSTD_ID => write!(
+                f,
+                "archive is compressed with zstd, but the `zstd` feature is \
+                 not enabled",
+            ),
+            id => write!(f, "unknown compression ID {id}"),
+        }
+    }
+}
+
+impl Error for UnsupportedCompression {}
+
+#[derive(Debug)]
+struct LengthMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "decompressed archive length mismatch: expected {} bytes but got \
+             {}",
+            self.expected, self.actual,
+        )
+    }
+}
+
+impl Error for LengthMismatch {}
+
+/// Serialize a value to compressed bytes.
+///
+/// The archive is serialized with [`to_bytes`], then compressed and prefixed
+/// with a small header recording the compression algorithm and the length of
+/// the uncompressed archive. Use [`decompress`], [`access_compressed`], or
+/// [`from_bytes_compressed`] to read it back.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_compressed, to_bytes_compressed, Compression},
+///     rancor::Error,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     values: Vec<u32>,
+/// }
+///
+/// let value = Example {
+///     name: "zeros".to_string(),
+///     values: vec![0; 1024],
+/// };
+///
+/// # #[cfg(feature = "lz4_flex")]
+/// # let compression = Compression::Lz4;
+/// # #[cfg(all(not(feature = "lz4_flex"), feature = "zstd"))]
+/// # let compression = Compression::Zstd(0);
+/// let bytes = to_bytes_compressed::<_, Error>(&value, compression).unwrap();
+/// assert!(bytes.len() < 4 * 1024);
+///
+/// let archived = access_compressed::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.name, "zeros");
+/// assert_eq!(archived.values.len(), 1024);
+/// ```
+pub fn to_bytes_compressed<T, E>(
+    value: &T,
+    compression: Compression,
+) -> Result<Vec<u8>, E>
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: Source,
+{
+    let bytes = to_bytes::<E>(value)?;
+    let compressed = compression.compress::<E>(&bytes)?;
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + compressed.len());
+    result.extend_from_slice(&MAGIC);
+    result.extend_from_slice(&[compression.id(), 0, 0, 0]);
+    result.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    result.extend_from_slice(&compressed);
+    Ok(result)
+}
+
+/// Decompress bytes produced by [`to_bytes_compressed`].
+///
+/// The archive is decompressed into an [`AlignedVec`], so it is always
+/// sufficiently aligned to access.
+///
+/// The decompressed length is read from the header and allocated up front, so
+/// compressed bytes from untrusted sources may cause large allocations.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn decompress<E: Source>(bytes: &[u8]) -> Result<AlignedVec, E> {
+    if bytes.len() < HEADER_SIZE || bytes[0..4] != MAGIC {
+        fail!(InvalidHeader);
+    }
+
+    let id = bytes[4];
+    let mut len = [0; 8];
+    len.copy_from_slice(&bytes[8..HEADER_SIZE]);
+    let Ok(len) = usize::try_from(u64::from_le_bytes(len)) else {
+        fail!(InvalidHeader);
+    };
+    let compressed = &bytes[HEADER_SIZE..];
+
+    let mut result = AlignedVec::new();
+    result.resize(len, 0);
+    let actual = match id {
+        #[cfg(feature = "lz4_flex")]
+        LZ4_ID => {
+            lz4_flex::block::decompress_into(compressed, result.as_mut_slice())
+                .into_error()?
+        }
+        #[cfg(feature = "zstd")]
+        ZSTD_ID => {
+            zstd::bulk::decompress_to_buffer(compressed, result.as_mut_slice())
+                .into_error()?
+        }
+        id => fail!(UnsupportedCompression { id }),
+    };
+
+    if actual != len {
+        fail!(LengthMismatch {
+            expected: len,
+            actual,
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(feature = "bytecheck")]
+/// A decompressed archive containing a `T`.
+///
+/// This dereferences to the archived value. It is returned by
+/// [`access_compressed`].
+pub struct Decompressed<T> {
+    bytes: AlignedVec,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T> Decompressed<T> {
+    /// Returns the decompressed bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the decompressed archive and returns its bytes.
+    pub fn into_bytes(self) -> AlignedVec {
+        self.bytes
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T: Portable> Deref for Decompressed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The bytes were validated as a `T` when the decompressed
+        // archive was created, and they can't be modified afterward.
+        unsafe { crate::api::access_unchecked::<T>(&self.bytes) }
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T: Portable + fmt::Debug> fmt::Debug for Decompressed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt(self, f)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use bytecheck::CheckBytes;
+    use rancor::Source;
+
+    use super::{decompress, Decompressed};
+    use crate::{
+        api::high::{access, deserialize, HighDeserializer, HighValidator},
+        Archive, Deserialize, Portable,
+    };
+
+    /// Decompress and access bytes produced by
+    /// [`to_bytes_compressed`](super::to_bytes_compressed).
+    ///
+    /// The decompressed archive is validated before it is returned.
+    ///
+    /// This is part of the [high-level API](crate::api::high).
+    pub fn access_compressed<T, E>(bytes: &[u8]) -> Result<Decompressed<T>, E>
+    where
+        T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let bytes = decompress::<E>(bytes)?;
+        access::<T, E>(&bytes)?;
+        Ok(Decompressed {
+            bytes,
+            _phantom: core::marker::PhantomData,
+        })
+    }
+
+    /// Decompress and deserialize a value from bytes produced by
+    /// [`to_bytes_compressed`](super::to_bytes_compressed).
+    ///
+    /// This is part of the [high-level API](crate::api::high).
+    pub fn from_bytes_compressed<T, E>(bytes: &[u8]) -> Result<T, E>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>
+            + Deserialize<T, HighDeserializer<E>>,
+        E: Source,
+    {
+        let archived = access_compressed::<T::Archived, E>(bytes)?;
+        deserialize::<T, E>(&*archived)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::checked::*;
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::{Error, Failure};
+
+    use super::{
+        access_compressed, from_bytes_compressed, to_bytes_compressed,
+        Compression, HEADER_SIZE,
+    };
+    use crate::{
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate)]
+    struct Example {
+        name: String,
+        values: Vec<u64>,
+    }
+
+    fn compressions() -> Vec<Compression> {
+        vec![
+            #[cfg(feature = "lz4_flex")]
+            Compression::Lz4,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd(0),
+        ]
+    }
+
+    #[test]
+    fn roundtrip_compressed() {
+        let value = Example {
+            name: "a string which is long enough to be stored out-of-line"
+                .to_string(),
+            values: (0..1000).map(|i| i % 7).collect(),
+        };
+
+        for compression in compressions() {
+            let bytes =
+                to_bytes_compressed::<_, Error>(&value, compression).unwrap();
+            assert!(bytes.len() < 1000 * 8);
+
+            let archived =
+                access_compressed::<ArchivedExample, Failure>(&bytes).unwrap();
+            assert_eq!(archived.name, value.name);
+            assert_eq!(archived.values[999], 999 % 7);
+
+            let deserialized =
+                from_bytes_compressed::<Example, Failure>(&bytes).unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    fn invalid_compressed() {
+        let value = Example {
+            name: "example".to_string(),
+            values: vec![1, 2, 3],
+        };
+
+        for compression in compressions() {
+            let bytes =
+                to_bytes_compressed::<_, Error>(&value, compression).unwrap();
+
+            // Truncated header
+            assert!(access_compressed::<ArchivedExample, Failure>(
+                &bytes[..HEADER_SIZE - 1]
+            )
+            .is_err());
+
+            // Unknown compression ID
+            let mut unknown = bytes.clone();
+            unknown[4] = 0xff;
+            assert!(access_compressed::<ArchivedExample, Failure>(&unknown)
+                .is_err());
+
+            // Wrong uncompressed length
+            let mut wrong_len = bytes.clone();
+            wrong_len[8] = wrong_len[8].wrapping_add(1);
+            assert!(access_compressed::<ArchivedExample, Failure>(&wrong_len)
+                .is_err());
+
+            // Truncated body
+            assert!(access_compressed::<ArchivedExample, Failure>(
+                &bytes[..bytes.len() - 1]
+            )
+            .is_err());
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Delta archives.
+
+use core::{error::Error, fmt, hash::Hasher as _};
+
+use rancor::{fail, Source};
+
+use super::{to_bytes, HighSerializer};
+use crate::{
+    alloc::{collections::BTreeMap, vec::Vec},
+    hash::FxHasher64,
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Serialize,
+};
+
+/// The bytes which start every delta.
+const MAGIC: [u8; 4] = *b"rkyD";
+/// The size of the header which precedes the delta operations.
+///
+/// The header contains the magic bytes, four bytes of padding, the
+/// little-endian `u64` length and hash of the base archive, and the
+/// little-endian `u64` length of the new archive.
+const HEADER_SIZE: usize = 32;
+
+/// The size of the blocks of the base archive which the new archive is matched
+/// against.
+const BLOCK_SIZE: usize = 32;
+/// The multiplier of the rolling hash used to find matching blocks.
+const ROLLING_BASE: u64 = 0x100_0000_01b3;
+
+const COPY_OP: u8 = 0;
+const INSERT_OP: u8 = 1;
+
+#[derive(Debug)]
+struct InvalidDelta;
+
+impl fmt::Display for InvalidDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "delta is truncated or malformed")
+    }
+}
+
+impl Error for InvalidDelta {}
+
+#[derive(Debug)]
+struct BaseMismatch;
+
+impl fmt::Display for BaseMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "delta was not created against the given base archive")
+    }
+}
+
+impl Error for BaseMismatch {}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = FxHasher64::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+fn rolling_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |hash, &b| {
+        hash.wrapping_mul(ROLLING_BASE).wrapping_add(b as u64)
+    })
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint<E: Source>(bytes: &mut &[u8]) -> Result<usize, E> {
+    let mut value = 0usize;
+    let mut shift = 0;
+    while let Some((&byte, rest)) = bytes.split_first() {
+        *bytes = rest;
+        if shift >= usize::BITS {
+            break;
+        }
+        value |= ((byte & 0x7f) as usize) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+    fail!(InvalidDelta);
+}
+
+struct DeltaWriter<'a> {
+    new: &'a [u8],
+    out: Vec<u8>,
+}
+
+impl DeltaWriter<'_> {
+    fn insert(&mut self, start: usize, end: usize) {
+        if start < end {
+            self.out.push(INSERT_OP);
+            write_varint(&mut self.out, end - start);
+            self.out.extend_from_slice(&self.new[start..end]);
+        }
+    }
+
+    fn copy(&mut self, offset: usize, len: usize) {
+        self.out.push(COPY_OP);
+        write_varint(&mut self.out, offset);
+        write_varint(&mut self.out, len);
+    }
+}
+
+/// Computes the delta which turns the `base` archive into the `new` archive.
+///
+/// The delta copies every run of bytes that the new archive shares with the
+/// base archive, even if it has moved, and includes the rest of the new
+/// archive inline. Apply it with [`apply_delta`].
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn encode_delta(base: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut blocks = BTreeMap::new();
+    for (i, block) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+        blocks.entry(rolling_hash(block)).or_insert(i * BLOCK_SIZE);
+    }
+
+    let mut out = Vec::with_capacity(HEADER_SIZE);
+    out.extend_from_slice(&MAGIC);
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(base.len() as u64).to_le_bytes());
+    out.extend_from_slice(&hash_bytes(base).to_le_bytes());
+    out.extend_from_slice(&(new.len() as u64).to_le_bytes());
+    let mut writer = DeltaWriter { new, out };
+
+    // The factor which removes the outgoing byte from the rolling hash.
+    let outgoing = (1..BLOCK_SIZE)
+        .fold(1u64, |factor, _| factor.wrapping_mul(ROLLING_BASE));
+
+    let mut pending = 0;
+    let mut pos = 0;
+    let mut hash = new.get(..BLOCK_SIZE).map(rolling_hash);
+    while let Some(current) = hash {
+        let matched = blocks.get(&current).copied().filter(|&offset| {
+            base[offset..offset + BLOCK_SIZE] == new[pos..pos + BLOCK_SIZE]
+        });
+
+        if let Some(offset) = matched {
+            let forward = base[offset..]
+                .iter()
+                .zip(&new[pos..])
+                .take_while(|(a, b)| a == b)
+                .count();
+            let backward = base[..offset]
+                .iter()
+                .rev()
+                .zip(new[pending..pos].iter().rev())
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            writer.insert(pending, pos - backward);
+            writer.copy(offset - backward, backward + forward);
+            pos += forward;
+            pending = pos;
+            hash = new.get(pos..pos + BLOCK_SIZE).map(rolling_hash);
+        } else if pos + BLOCK_SIZE < new.len() {
+            let rolled = current
+                .wrapping_sub((new[pos] as u64).wrapping_mul(outgoing))
+                .wrapping_mul(ROLLING_BASE)
+                .wrapping_add(new[pos + BLOCK_SIZE] as u64);
+            pos += 1;
+            hash = Some(rolled);
+        } else {
+            hash = None;
+        }
+    }
+    writer.insert(pending, new.len());
+
+    writer.out
+}
+
+/// Serialize a value as a delta against a base archive.
+///
+/// The value is serialized with [`to_bytes`], and only the parts of the
+/// archive which differ from `base` are included in the delta. Applying the
+/// delta to the same base with [`apply_delta`] produces exactly the bytes that
+/// `to_bytes` would have.
+///
+/// The base must be the bytes of the whole base archive rather than a
+/// reference to its archived value, because the subtrees of an archived value
+/// may be located anywhere in its buffer.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{apply_delta, to_bytes_delta},
+///     rancor::Error,
+///     to_bytes, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Snapshot {
+///     tick: u64,
+///     positions: Vec<(f32, f32)>,
+/// }
+///
+/// let mut snapshot = Snapshot {
+///     tick: 0,
+///     positions: vec![(0.0, 0.0); 1000],
+/// };
+/// let base = to_bytes::<Error>(&snapshot).unwrap();
+///
+/// snapshot.tick += 1;
+/// snapshot.positions[500].1 = 1.0;
+/// let delta = to_bytes_delta::<_, Error>(&base, &snapshot).unwrap();
+/// assert!(delta.len() < base.len() / 10);
+///
+/// let bytes = apply_delta::<Error>(&base, &delta).unwrap();
+/// let expected = to_bytes::<Error>(&snapshot).unwrap();
+/// assert_eq!(bytes.as_slice(), expected.as_slice());
+/// ```
+pub fn to_bytes_delta<T, E>(base: &[u8], value: &T) -> Result<Vec<u8>, E>
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: Source,
+{
+    let new = to_bytes::<E>(value)?;
+    Ok(encode_delta(base, &new))
+}
+
+/// Apply a delta produced by [`to_bytes_delta`] or [`encode_delta`] to the base
+/// archive it was created against.
+///
+/// The new archive is written into an [`AlignedVec`], so it is always
+/// sufficiently aligned to access. An error is returned if the delta is
+/// malformed or was created against a different base archive.
+///
+/// The length of the new archive is read from the delta and allocated up
+/// front, so deltas from untrusted sources may cause large allocations.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn apply_delta<E: Source>(
+    base: &[u8],
+    delta: &[u8],
+) -> Result<AlignedVec, E> {
+    if delta.len() < HEADER_SIZE || delta[0..4] != MAGIC {
+        fail!(InvalidDelta);
+    }
+
+    let read_u64 = |start: usize| {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&delta[start..start + 8]);
+        u64::from_le_bytes(bytes)
+    };
+    if read_u64(8) != base.len() as u64 || read_u64(16) != hash_bytes(base) {
+        fail!(BaseMismatch);
+    }
+    let Ok(len) = usize::try_from(read_u64(24)) else {
+        fail!(InvalidDelta);
+    };
+
+    let mut result = AlignedVec::with_capacity(len);
+    let mut ops = &delta[HEADER_SIZE..];
+    while let Some((&op, rest)) = ops.split_first() {
+        ops = rest;
+        let bytes = match op {
+            COPY_OP => {
+                let offset = read_varint::<E>(&mut ops)?;
+                let len = read_varint::<E>(&mut ops)?;
+                offset
+                    .checked_add(len)
+                    .and_then(|end| base.get(offset..end))
+            }
+            INSERT_OP => {
+                let len = read_varint::<E>(&mut ops)?;
+                let inserted = ops.get(..len);
+                ops = ops.get(len..).unwrap_or_default();
+                inserted
+            }
+            _ => None,
+        };
+        let Some(bytes) = bytes else {
+            fail!(InvalidDelta);
+        };
+        if result.len() + bytes.len() > len {
+            fail!(InvalidDelta);
+        }
+        result.extend_from_slice(bytes);
+    }
+
+    if result.len() != len {
+        fail!(InvalidDelta);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::{Error, Failure};
+
+    use super::{apply_delta, encode_delta, to_bytes_delta};
+    use crate::{
+        alloc::{
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::high::to_bytes,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Clone)]
+    #[rkyv(crate)]
+    struct Entity {
+        id: u32,
+        name: String,
+        tags: Vec<u16>,
+    }
+
+    fn roundtrip_delta(base: &[u8], new: &[u8]) -> usize {
+        let delta = encode_delta(base, new);
+        let applied = apply_delta::<Error>(base, &delta).unwrap();
+        assert_eq!(applied.as_slice(), new);
+        delta.len()
+    }
+
+    #[test]
+    fn delta_roundtrip() {
+        let entities = (0..200)
+            .map(|id| Entity {
+                id,
+                name: "entity number ".to_string() + &id.to_string(),
+                tags: vec![id as u16; id as usize % 5],
+            })
+            .collect::<Vec<_>>();
+        let base = to_bytes::<Error>(&entities).unwrap();
+
+        // Unchanged
+        assert!(roundtrip_delta(&base, &base) < 64);
+
+        // Changed in place
+        let mut changed = entities.clone();
+        changed[100].id = 12345;
+        let new = to_bytes::<Error>(&changed).unwrap();
+        assert!(roundtrip_delta(&base, &new) < base.len() / 10);
+
+        // Changed length, shifting the rest of the archive
+        let mut resized = entities.clone();
+        resized[50].name.push_str(" with a longer name");
+        resized[150].tags.clear();
+        let new = to_bytes::<Error>(&resized).unwrap();
+        let delta = to_bytes_delta::<_, Error>(&base, &resized).unwrap();
+        assert!(delta.len() < base.len() / 2);
+        assert_eq!(roundtrip_delta(&base, &new), delta.len());
+
+        // Nothing in common
+        assert_eq!(roundtrip_delta(&[], &new), new.len() + 32 + 1 + 2);
+        roundtrip_delta(&base, &[]);
+    }
+
+    #[test]
+    fn apply_invalid_delta() {
+        let base = to_bytes::<Error>(&vec![1u32, 2, 3]).unwrap();
+        let new = to_bytes::<Error>(&vec![1u32, 2, 3, 4]).unwrap();
+        let delta = encode_delta(&base, &new);
+
+        assert!(apply_delta::<Failure>(&base, &delta[..10]).is_err());
+        assert!(
+            apply_delta::<Failure>(&base, &delta[..delta.len() - 1]).is_err()
+        );
+        assert!(apply_delta::<Failure>(&new, &delta).is_err());
+
+        let mut corrupted = delta.clone();
+        corrupted[32] = 7;
+        assert!(apply_delta::<Failure>(&base, &corrupted).is_err());
+    }
+}
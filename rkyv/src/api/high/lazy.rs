@@ -0,0 +1,487 @@
+//! Lazily-validated access.
+
+use core::{
+    alloc::Layout, any::TypeId, cell::RefCell, error::Error, fmt,
+    hash::BuildHasherDefault, marker::PhantomData, mem::size_of,
+};
+
+use bytecheck::CheckBytes;
+use hashbrown::HashMap;
+use rancor::{fail, Source};
+
+use super::{checked::validator, HighValidator};
+use crate::{
+    alloc::{rc::Rc, vec, vec::Vec},
+    api::{access_pos_unchecked, check_pos_with_context, root_position},
+    boxed::ArchivedBox,
+    hash::FxHasher64,
+    validation::{archive::ArchiveValidator, ArchiveContext},
+    vec::ArchivedVec,
+    Portable,
+};
+
+#[derive(Debug)]
+struct ProjectionOutOfBounds {
+    pos: usize,
+    size: usize,
+    parent_pos: usize,
+    parent_size: usize,
+}
+
+impl fmt::Display for ProjectionOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "projected value at {}..{} is not contained in its parent at \
+             {}..{}",
+            self.pos,
+            self.pos + self.size,
+            self.parent_pos,
+            self.parent_pos + self.parent_size,
+        )
+    }
+}
+
+impl Error for ProjectionOutOfBounds {}
+
+#[derive(Debug)]
+struct IndexOutOfBounds {
+    index: usize,
+    len: usize,
+}
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index {} is out of bounds for an archived vec of length {}",
+            self.index, self.len,
+        )
+    }
+}
+
+impl Error for IndexOutOfBounds {}
+
+/// The positions which have been validated for a single type, stored as one
+/// bit per aligned position in the buffer.
+struct Bitmap {
+    words: Vec<u64>,
+}
+
+impl Bitmap {
+    fn new(len: usize, align: usize) -> Self {
+        Self {
+            words: vec![0; (len / align).div_ceil(64) + 1],
+        }
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set(&mut self, index: usize) {
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+}
+
+struct LazyState<'a> {
+    bytes: &'a [u8],
+    validated: RefCell<HashMap<TypeId, Bitmap, BuildHasherDefault<FxHasher64>>>,
+}
+
+impl LazyState<'_> {
+    fn is_validated<T: 'static>(&self, pos: usize) -> bool {
+        self.validated
+            .borrow()
+            .get(&TypeId::of::<T>())
+            .is_some_and(|bitmap| bitmap.get(pos / align_of::<T>()))
+    }
+
+    fn set_validated<T: 'static>(&self, pos: usize) {
+        self.validated
+            .borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Bitmap::new(self.bytes.len(), align_of::<T>()))
+            .set(pos / align_of::<T>());
+    }
+}
+
+/// A lazily-validated archived value.
+///
+/// A `Lazy` only guarantees that the bytes of its value are in bounds and
+/// properly aligned. The value itself, along with everything it points to, is
+/// validated the first time it is accessed with [`get`](Lazy::get). Values may
+/// be narrowed to their fields with [`project`](Lazy::project), and boxes and
+/// vecs may be followed without validating their contents. This allows large
+/// archives to be accessed safely while only validating the parts which are
+/// actually used.
+///
+/// Successful validations are recorded in a per-type bitmap shared by all of
+/// the lazy values created from the same call to [`access_lazy`], so each
+/// value is validated at most once.
+///
+/// Each call to `get` validates its value independently, so data which is
+/// reachable from multiple validated values may be validated multiple times.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub struct Lazy<'a, T> {
+    state: Rc<LazyState<'a>>,
+    pos: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<T> Clone for Lazy<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            pos: self.pos,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Lazy<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Lazy").field("pos", &self.pos).finish()
+    }
+}
+
+impl<'a, T: Portable> Lazy<'a, T> {
+    /// Creates a new lazy value at the given position, checking that it is in
+    /// bounds and properly aligned.
+    fn new<E: Source>(state: Rc<LazyState<'a>>, pos: usize) -> Result<Self, E> {
+        let ptr = state.bytes.as_ptr().wrapping_add(pos);
+        ArchiveContext::<E>::check_subtree_ptr(
+            &mut ArchiveValidator::new(state.bytes),
+            ptr,
+            &Layout::new::<T>(),
+        )?;
+
+        Ok(Self {
+            state,
+            pos,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the position of the value in the archive.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    fn as_ptr(&self) -> *const T {
+        self.state.bytes.as_ptr().wrapping_add(self.pos).cast()
+    }
+
+    /// Validates the value if it has not been validated yet and returns a
+    /// reference to it.
+    pub fn get<E>(&self) -> Result<&'a T, E>
+    where
+        T: 'static + for<'b> CheckBytes<HighValidator<'b, E>>,
+        E: Source,
+    {
+        let bytes = self.state.bytes;
+        if !self.state.is_validated::<T>(self.pos) {
+            check_pos_with_context::<T, _, E>(
+                bytes,
+                self.pos,
+                &mut validator(bytes),
+            )?;
+            self.state.set_validated::<T>(self.pos);
+        }
+
+        // SAFETY: The value at `pos` has been validated as a `T`.
+        unsafe { Ok(access_pos_unchecked::<T>(bytes, self.pos)) }
+    }
+
+    /// Narrows the value to a part of it, usually one of its fields.
+    ///
+    /// The projection function receives a pointer to the value which has not
+    /// been validated yet. It must not read through the pointer, and should
+    /// only calculate the address of a field (e.g. with
+    /// [`addr_of!`](core::ptr::addr_of)). Returns an error if the projected
+    /// value is not located inside of this value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use core::ptr::addr_of;
+    ///
+    /// use rkyv::{api::high::access_lazy, rancor::Error, Archive, Serialize};
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// struct Example {
+    ///     name: String,
+    ///     values: Vec<i32>,
+    /// }
+    ///
+    /// let value = Example {
+    ///     name: "pi".to_string(),
+    ///     values: vec![3, 1, 4, 1, 5],
+    /// };
+    ///
+    /// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+    /// let lazy = access_lazy::<ArchivedExample, Error>(&bytes).unwrap();
+    ///
+    /// // Only the name is validated, `values` is never checked.
+    /// let name = lazy
+    ///     .project::<_, Error>(|p| unsafe { addr_of!((*p).name) })
+    ///     .unwrap();
+    /// assert_eq!(name.get::<Error>().unwrap(), "pi");
+    /// ```
+    pub fn project<U, E>(
+        &self,
+        f: impl FnOnce(*const T) -> *const U,
+    ) -> Result<Lazy<'a, U>, E>
+    where
+        U: Portable,
+        E: Source,
+    {
+        let pos = (f(self.as_ptr()) as usize)
+            .wrapping_sub(self.state.bytes.as_ptr() as usize);
+        if pos < self.pos
+            || pos.saturating_add(size_of::<U>()) > self.pos + size_of::<T>()
+        {
+            fail!(ProjectionOutOfBounds {
+                pos,
+                size: size_of::<U>(),
+                parent_pos: self.pos,
+                parent_size: size_of::<T>(),
+            });
+        }
+
+        Lazy::new(self.state.clone(), pos)
+    }
+
+    fn follow<U: Portable, E: Source>(
+        &self,
+        ptr: *const U,
+    ) -> Result<Lazy<'a, U>, E> {
+        let pos =
+            (ptr as usize).wrapping_sub(self.state.bytes.as_ptr() as usize);
+        Lazy::new(self.state.clone(), pos)
+    }
+}
+
+impl<'a, T: Portable> Lazy<'a, ArchivedBox<T>> {
+    /// Returns the value pointed to by the archived box without validating it.
+    pub fn inner<E: Source>(&self) -> Result<Lazy<'a, T>, E> {
+        // SAFETY: `Lazy` guarantees that its value is in bounds and properly
+        // aligned, and every bit pattern is a valid `ArchivedBox` of a sized
+        // type.
+        let boxed = unsafe { &*self.as_ptr() };
+        self.follow(boxed.as_ptr_wrapping())
+    }
+}
+
+impl<'a, T: Portable> Lazy<'a, ArchivedVec<T>> {
+    fn as_vec(&self) -> &'a ArchivedVec<T> {
+        // SAFETY: `Lazy` guarantees that its value is in bounds and properly
+        // aligned, and every bit pattern is a valid `ArchivedVec`.
+        unsafe { &*self.as_ptr() }
+    }
+
+    /// Returns the number of elements in the archived vec.
+    pub fn len(&self) -> usize {
+        self.as_vec().len()
+    }
+
+    /// Returns whether the archived vec is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at the given index without validating it.
+    pub fn index<E: Source>(&self, index: usize) -> Result<Lazy<'a, T>, E> {
+        let vec = self.as_vec();
+        if index >= vec.len() {
+            fail!(IndexOutOfBounds {
+                index,
+                len: vec.len(),
+            });
+        }
+
+        self.follow(vec.as_ptr_wrapping().wrapping_add(index))
+    }
+}
+
+/// Access a byte slice lazily.
+///
+/// Only the bounds and alignment of the root are checked. The root and the
+/// values it points to are validated when they are first accessed through the
+/// returned [`Lazy`]. This is a safe alternative to [`access_unchecked`] for
+/// large archives where only a small part of the data is used.
+///
+/// Lazy access is limited to fields, boxes, and vecs. Other values, including
+/// the contents of hash maps and B-tree maps, are validated in full when they
+/// are accessed.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// [`access_unchecked`]: crate::access_unchecked
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{api::high::access_lazy, rancor::Error, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Record {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// let records = (0..100)
+///     .map(|id| Record {
+///         id,
+///         name: format!("record {id}"),
+///     })
+///     .collect::<Vec<_>>();
+///
+/// let bytes = rkyv::to_bytes::<Error>(&records).unwrap();
+/// let lazy =
+///     access_lazy::<rkyv::Archived<Vec<Record>>, Error>(&bytes).unwrap();
+///
+/// assert_eq!(lazy.len(), 100);
+/// // Only the record at index 42 is validated.
+/// let record = lazy.index::<Error>(42).unwrap().get::<Error>().unwrap();
+/// assert_eq!(record.id, 42);
+/// assert_eq!(record.name, "record 42");
+/// ```
+pub fn access_lazy<T, E>(bytes: &[u8]) -> Result<Lazy<'_, T>, E>
+where
+    T: Portable,
+    E: Source,
+{
+    let state = Rc::new(LazyState {
+        bytes,
+        validated: RefCell::new(HashMap::default()),
+    });
+    Lazy::new(state, root_position::<T>(bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr::addr_of;
+
+    use rancor::{Error, Failure};
+
+    use crate::{
+        alloc::{
+            boxed::Box,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::high::{access_lazy, to_bytes},
+        Archive, Archived, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Example {
+        name: String,
+        boxed: Box<u32>,
+        values: Vec<String>,
+    }
+
+    fn example() -> Example {
+        Example {
+            name: "example".to_string(),
+            boxed: Box::new(42),
+            values: vec![
+                "a".to_string(),
+                "a string which is long enough to be stored out-of-line"
+                    .to_string(),
+                "c".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn lazy_access() {
+        let bytes = to_bytes::<Error>(&example()).unwrap();
+        let lazy = access_lazy::<ArchivedExample, Failure>(&bytes).unwrap();
+
+        let name = lazy
+            .project::<_, Failure>(|p| unsafe { addr_of!((*p).name) })
+            .unwrap();
+        assert_eq!(name.get::<Failure>().unwrap(), "example");
+
+        let boxed = lazy
+            .project::<_, Failure>(|p| unsafe { addr_of!((*p).boxed) })
+            .unwrap()
+            .inner::<Failure>()
+            .unwrap();
+        assert_eq!(boxed.get::<Failure>().unwrap().to_native(), 42);
+
+        let values = lazy
+            .project::<_, Failure>(|p| unsafe { addr_of!((*p).values) })
+            .unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(
+            values
+                .index::<Failure>(1)
+                .unwrap()
+                .get::<Failure>()
+                .unwrap(),
+            "a string which is long enough to be stored out-of-line",
+        );
+        assert!(values.index::<Failure>(3).is_err());
+
+        let root = lazy.get::<Failure>().unwrap();
+        assert_eq!(root.values[2], "c");
+    }
+
+    #[test]
+    fn lazy_access_skips_unused_values() {
+        let mut bytes = to_bytes::<Error>(&example()).unwrap();
+        // Corrupt the contents of the out-of-line string in the vec
+        let contents = b"a string which is long enough";
+        let pos = bytes
+            .windows(contents.len())
+            .position(|window| window == contents)
+            .unwrap();
+        bytes[pos] = 0xff;
+
+        let lazy = access_lazy::<ArchivedExample, Failure>(&bytes).unwrap();
+        let values = lazy
+            .project::<_, Failure>(|p| unsafe { addr_of!((*p).values) })
+            .unwrap();
+        assert!(values
+            .index::<Failure>(1)
+            .unwrap()
+            .get::<Failure>()
+            .is_err());
+        assert!(lazy.get::<Failure>().is_err());
+
+        // Values which don't depend on the corrupted bytes are still valid
+        let name = lazy
+            .project::<_, Failure>(|p| unsafe { addr_of!((*p).name) })
+            .unwrap();
+        assert_eq!(name.get::<Failure>().unwrap(), "example");
+        assert_eq!(
+            values
+                .index::<Failure>(2)
+                .unwrap()
+                .get::<Failure>()
+                .unwrap(),
+            "c",
+        );
+    }
+
+    #[test]
+    fn lazy_access_out_of_bounds() {
+        let bytes = to_bytes::<Error>(&vec![1u32, 2, 3]).unwrap();
+        assert!(
+            access_lazy::<Archived<Vec<u64>>, Failure>(&bytes[..4]).is_err()
+        );
+
+        let lazy = access_lazy::<Archived<Vec<u32>>, Failure>(&bytes).unwrap();
+        assert!(lazy
+            .project::<Archived<u64>, Failure>(|p| p
+                .cast::<Archived<u64>>()
+                .wrapping_add(1))
+            .is_err());
+    }
+}
@@ -5,20 +5,50 @@
 
 #[cfg(feature = "bytecheck")]
 mod checked;
+#[cfg(any(feature = "lz4_flex", feature = "zstd"))]
+mod compressed;
+mod delta;
+#[cfg(feature = "bytecheck")]
+mod lazy;
+mod reusable;
+mod roots;
+#[cfg(feature = "bytecheck")]
+mod tracked;
+
+use core::future::Future;
 
 use rancor::Strategy;
 
 #[cfg(feature = "bytecheck")]
 pub use self::checked::*;
+#[cfg(any(feature = "lz4_flex", feature = "zstd"))]
+pub use self::compressed::*;
+#[cfg(feature = "bytecheck")]
+pub use self::lazy::*;
+#[cfg(feature = "bytecheck")]
+pub use self::tracked::*;
+pub use self::{delta::*, reusable::*, roots::*};
+#[cfg(feature = "std")]
+use crate::ser::writer::ChunkedIoWriter;
 use crate::{
     access_unchecked,
+    alloc::vec::Vec,
     api::{deserialize_using, serialize_using},
-    de::Pool,
+    de::{BlobReader, Borrower, Pool},
+    endian::EndianTaggedRoot,
+    header::ArchiveHeader,
     ser::{
-        allocator::ArenaHandle, sharing::Share, Allocator, Serializer, Writer,
+        allocator::{AllocationTracker, ArenaHandle},
+        blobs::BlobWriter,
+        sharing::Share,
+        writer::{write_async, CountingWriter},
+        Allocator, AsyncWriter, Serializer, SerializerStats, Writer,
     },
+    traits::Versioned,
+    type_hash::TypeHash,
     util::{with_arena, AlignedVec},
-    Archive, Deserialize, Serialize,
+    version::VersionedRoot,
+    Archive, Deserialize, Serialize, SerializeUnsized,
 };
 
 /// A high-level serializer.
@@ -31,6 +61,41 @@ pub type HighSerializer<W, A, E> = Strategy<Serializer<W, A, Share>, E>;
 /// This is part of the [high-level API](crate::api::high).
 pub type HighDeserializer<E> = Strategy<Pool, E>;
 
+/// A high-level deserializer which can borrow from the archive buffer.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub type HighBorrowingDeserializer<'a, E> = Strategy<Borrower<'a, Pool>, E>;
+
+/// A high-level deserializer which can read bytes from a blob region.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub type HighBlobDeserializer<'a, E> = Strategy<BlobReader<'a, Pool>, E>;
+
+/// A high-level deserializer which can share ownership of the archive buffer.
+///
+/// This is part of the [high-level API](crate::api::high).
+#[cfg(feature = "bytes-1")]
+pub type HighSlicingDeserializer<E> = Strategy<crate::de::BytesSlicer<Pool>, E>;
+
+/// An archived value which can be deserialized into a `T` that borrows from
+/// the archive buffer for `'a`.
+///
+/// This is implemented for all types which can be deserialized with a
+/// [`HighBorrowingDeserializer`]. Native types containing `&'a str` or
+/// `&'a [u8]` fields can implement it by annotating those fields with the
+/// [`Borrowed`](crate::with::Borrowed) wrapper.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub trait DeserializeBorrowed<'a, T, E>:
+    Deserialize<T, HighBorrowingDeserializer<'a, E>>
+{
+}
+
+impl<'a, T, E, A> DeserializeBorrowed<'a, T, E> for A where
+    A: Deserialize<T, HighBorrowingDeserializer<'a, E>> + ?Sized
+{
+}
+
 /// Serialize a value to bytes.
 ///
 /// Returns the serialized bytes in an [`AlignedVec`].
@@ -113,6 +178,57 @@ where
     with_arena(|arena| to_bytes_in_with_alloc(value, writer, arena.acquire()))
 }
 
+/// Compute the size of a value's serialized bytes without writing them.
+///
+/// The value is serialized to a [`CountingWriter`], which counts the bytes
+/// that would be written instead of copying them. The returned size is exactly
+/// the length of the bytes returned by [`to_bytes`]. This can be used to
+/// allocate a buffer of the right size ahead of time or to reject values which
+/// are too large.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{api::high::serialized_size, rancor::Error, to_bytes};
+///
+/// let value = vec![(1u32, "one".to_string()), (2, "two".to_string())];
+/// let size = serialized_size::<Error>(&value).unwrap();
+/// assert_eq!(size, to_bytes::<Error>(&value).unwrap().len());
+/// ```
+pub fn serialized_size<E>(
+    value: &impl for<'a> Serialize<
+        HighSerializer<CountingWriter, ArenaHandle<'a>, E>,
+    >,
+) -> Result<usize, E>
+where
+    E: rancor::Source,
+{
+    to_bytes_in(value, CountingWriter::new()).map(|writer| writer.len())
+}
+
+/// Serialize a value to bytes, writing fields which use the
+/// [`ExternalBytes`](crate::with::ExternalBytes) wrapper to a separate blob
+/// region.
+///
+/// Returns the archive bytes and the blob region. Both must be kept to access
+/// the external bytes later. See [`ExternalBytes`](crate::with::ExternalBytes)
+/// for an example.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn to_bytes_with_blobs<E>(
+    value: &impl for<'a> Serialize<
+        HighSerializer<BlobWriter<AlignedVec, Vec<u8>>, ArenaHandle<'a>, E>,
+    >,
+) -> Result<(AlignedVec, Vec<u8>), E>
+where
+    E: rancor::Source,
+{
+    to_bytes_in(value, BlobWriter::new(AlignedVec::new(), Vec::new()))
+        .map(BlobWriter::into_inner)
+}
+
 /// Serialize a value using the given allocator.
 ///
 /// This is part of the [high-level API](crate::api::high).
@@ -155,6 +271,54 @@ where
     to_bytes_in_with_alloc(value, AlignedVec::new(), alloc)
 }
 
+/// Serialize a value to bytes and collect stats about the serialization.
+///
+/// The returned [`SerializerStats`] record the peak scratch space usage, the
+/// number of allocations, and the final size of the archive. They can be used
+/// to pre-size the arena and [`AlignedVec`] when serializing similar values.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{to_bytes_in, to_bytes_with_stats},
+///     rancor::Error,
+///     util::AlignedVec,
+/// };
+///
+/// let value = vec![vec![1, 2], vec![3, 4]];
+/// let (bytes, stats) = to_bytes_with_stats::<Error>(&value).unwrap();
+/// assert_eq!(stats.archive_size, bytes.len());
+///
+/// let bytes = to_bytes_in::<_, Error>(
+///     &value,
+///     AlignedVec::<16>::with_capacity(stats.archive_size),
+/// )
+/// .unwrap();
+/// assert_eq!(bytes.capacity(), stats.archive_size);
+/// ```
+pub fn to_bytes_with_stats<E>(
+    #[rustfmt::skip] value: &impl for<'a> Serialize<
+        HighSerializer<AlignedVec, AllocationTracker<ArenaHandle<'a>>, E>,
+    >,
+) -> Result<(AlignedVec, SerializerStats), E>
+where
+    E: rancor::Source,
+{
+    with_arena(|arena| {
+        let mut serializer = Serializer::new(
+            AlignedVec::new(),
+            AllocationTracker::new(arena.acquire()),
+            Share::new(),
+        );
+        serialize_using(value, &mut serializer)?;
+        let stats = serializer.stats();
+        Ok((serializer.into_writer(), stats))
+    })
+}
+
 /// Serialize a value using the given allocator and write the bytes to the given
 /// writer.
 ///
@@ -211,6 +375,244 @@ where
     Ok(serializer.into_writer())
 }
 
+/// Serialize a value and write the bytes to the given
+/// [`io::Write`](std::io::Write).
+///
+/// The bytes are written in chunks of
+/// [`DEFAULT_CHUNK_SIZE`](ChunkedIoWriter::DEFAULT_CHUNK_SIZE) bytes as
+/// serialization progresses, so the archive is never held in memory all at
+/// once. The writer does not need to support seeking. To use a different chunk
+/// size, pass a [`ChunkedIoWriter`] to [`to_bytes_in`] instead.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_writer, from_bytes, rancor::Error, util::AlignedVec,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_writer::<_, Error>(&value, Vec::new()).unwrap();
+///
+/// let mut aligned = AlignedVec::<16>::new();
+/// aligned.extend_from_slice(&bytes);
+/// let deserialized = from_bytes::<Example, Error>(&aligned).unwrap();
+///
+/// assert_eq!(deserialized, value);
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer<W, E>(
+    value: &impl for<'a> Serialize<
+        HighSerializer<ChunkedIoWriter<W>, ArenaHandle<'a>, E>,
+    >,
+    writer: W,
+) -> Result<W, E>
+where
+    W: std::io::Write,
+    E: rancor::Source,
+{
+    to_bytes_in(value, ChunkedIoWriter::new(writer))?.into_inner()
+}
+
+/// Serialize a value and write the bytes to the given [`AsyncWriter`].
+///
+/// The value is serialized to an [`AlignedVec`] as soon as this function is
+/// called, and the returned future only writes the serialized bytes. This keeps
+/// the future from borrowing `value`, and it yields whenever the writer is not
+/// ready so writing large archives doesn't block the executor thread.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_async, rancor::Error, ser::AsyncWriter, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// async fn send<W: AsyncWriter<Error> + Unpin>(
+///     socket: &mut W,
+///     value: &Example,
+/// ) -> Result<(), Error> {
+///     to_bytes_async(value, socket).await
+/// }
+/// ```
+pub fn to_bytes_async<'w, W, E>(
+    #[rustfmt::skip] value: &impl for<'a> Serialize<
+        HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+    >,
+    writer: &'w mut W,
+) -> impl Future<Output = Result<(), E>> + 'w
+where
+    W: AsyncWriter<E> + Unpin + ?Sized,
+    E: rancor::Source + 'w,
+{
+    let bytes = to_bytes(value);
+    async move { write_async(&bytes?, writer).await }
+}
+
+/// Serialize a value to bytes with a version tag.
+///
+/// The value is serialized as a [`VersionedRoot`], which allows readers to
+/// check the version of the archive before accessing it. See the
+/// [`version`](crate::version) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_versioned, to_bytes_versioned},
+///     rancor::Error,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 1)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_bytes_versioned::<_, Error>(&value).unwrap();
+/// let archived = access_versioned::<ArchivedExample, Error>(&bytes).unwrap();
+///
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+/// ```
+pub fn to_bytes_versioned<T, E>(value: &T) -> Result<AlignedVec, E>
+where
+    T: Versioned + ?Sized,
+    T: for<'a> SerializeUnsized<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: rancor::Source,
+{
+    to_bytes(&VersionedRoot::new(value))
+}
+
+/// Serialize a value to bytes with an endianness tag.
+///
+/// The value is serialized as an [`EndianTaggedRoot`], which allows readers to
+/// check the byte order of the archive before accessing it. See the
+/// [`endian`](crate::endian) module for more information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_endian_tagged, to_bytes_endian_tagged},
+///     rancor::Error,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_bytes_endian_tagged::<_, Error>(&value).unwrap();
+/// let archived =
+///     access_endian_tagged::<ArchivedExample, Error>(&bytes).unwrap();
+///
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+/// ```
+pub fn to_bytes_endian_tagged<T, E>(value: &T) -> Result<AlignedVec, E>
+where
+    T: for<'a> SerializeUnsized<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>
+        + ?Sized,
+    E: rancor::Source,
+{
+    to_bytes(&EndianTaggedRoot::new(value))
+}
+
+/// Serialize a value to bytes prefixed with an archive header.
+///
+/// The archive starts with an [`ArchiveHeader`] which records the format
+/// control options and the [`TypeHash`] of the root type that it was written
+/// with. Readers can check
+/// the header with [`access_with_header`] to reject incompatible archives with
+/// a descriptive error. See the [`header`](crate::header) module for more
+/// information.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// [`access_with_header`]: crate::api::high::access_with_header
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_with_header, to_bytes_with_header},
+///     rancor::Error,
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(type_hash)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let bytes = to_bytes_with_header::<_, Error>(&value).unwrap();
+/// let archived =
+///     access_with_header::<ArchivedExample, Error>(&bytes).unwrap();
+///
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+/// ```
+pub fn to_bytes_with_header<T, E>(value: &T) -> Result<AlignedVec, E>
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    T::Archived: TypeHash,
+    E: rancor::Source,
+{
+    let mut bytes = AlignedVec::new();
+    bytes.extend_from_slice(
+        &ArchiveHeader::for_root::<T::Archived>().to_bytes(),
+    );
+    to_bytes_in(value, bytes)
+}
+
 /// Deserialize a value from the given bytes.
 ///
 /// This function does not check that the data is valid. Use [`from_bytes`] to
@@ -295,6 +697,110 @@ pub fn deserialize<T, E>(
     deserialize_using(value, &mut Pool::new())
 }
 
+/// Deserialize a value from the given archived value using an existing
+/// [`Pool`].
+///
+/// Unlike [`deserialize`], this does not allocate a new pool for each call.
+/// The pool is not cleared afterward, so shared pointers deserialized by
+/// earlier calls are reused by later calls. The pool must be
+/// [cleared](Pool::clear) before it is used with values from a different
+/// archive.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// use rkyv::{
+///     access, api::high::deserialize_with_pool, de::Pool, rancor::Error,
+///     to_bytes,
+/// };
+///
+/// let shared = Rc::new("shared".to_string());
+/// let value = vec![shared.clone(), shared];
+///
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived =
+///     access::<rkyv::Archived<Vec<Rc<String>>>, Error>(&bytes).unwrap();
+///
+/// let mut pool = Pool::new();
+/// let first =
+///     deserialize_with_pool::<Rc<String>, Error>(&archived[0], &mut pool)
+///         .unwrap();
+/// let second =
+///     deserialize_with_pool::<Rc<String>, Error>(&archived[1], &mut pool)
+///         .unwrap();
+/// assert!(Rc::ptr_eq(&first, &second));
+///
+/// pool.clear();
+/// assert_eq!(Rc::strong_count(&first), 2);
+/// ```
+pub fn deserialize_with_pool<T, E>(
+    value: &impl Deserialize<T, HighDeserializer<E>>,
+    pool: &mut Pool,
+) -> Result<T, E> {
+    deserialize_using(value, pool)
+}
+
+/// Deserialize a value from the given archived value, borrowing data from the
+/// buffer it was accessed from where possible.
+///
+/// Fields which use the [`Borrowed`](crate::with::Borrowed) wrapper are
+/// deserialized as references into `bytes` instead of being copied. The
+/// archived value must be located in `bytes`.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use rkyv::{
+///     access, api::high::deserialize_borrowed, rancor::Error, to_bytes,
+///     with::Borrowed, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example<'a> {
+///     #[rkyv(with = Borrowed)]
+///     name: Cow<'a, str>,
+/// }
+///
+/// let value = Example {
+///     name: Cow::Borrowed("pi"),
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived = access::<ArchivedExample, Error>(&bytes).unwrap();
+///
+/// let deserialized =
+///     deserialize_borrowed::<Example, Error>(archived, &bytes).unwrap();
+/// assert_eq!(deserialized.name, "pi");
+/// assert!(matches!(deserialized.name, Cow::Borrowed(_)));
+/// ```
+pub fn deserialize_borrowed<'a, T, E>(
+    value: &impl Deserialize<T, HighBorrowingDeserializer<'a, E>>,
+    bytes: &'a [u8],
+) -> Result<T, E> {
+    deserialize_using(value, &mut Borrower::new(bytes, Pool::new()))
+}
+
+/// Deserialize a value from the given archived value, reading fields which
+/// use the [`ExternalBytes`](crate::with::ExternalBytes) wrapper from the
+/// given blob region.
+///
+/// See [`ExternalBytes`](crate::with::ExternalBytes) for an example.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn deserialize_with_blobs<T, E>(
+    value: &impl for<'a> Deserialize<T, HighBlobDeserializer<'a, E>>,
+    blobs: &[u8],
+) -> Result<T, E> {
+    deserialize_using(value, &mut BlobReader::new(blobs, Pool::new()))
+}
+
 #[cfg(test)]
 mod tests {
     use rancor::Panic;
@@ -310,4 +816,189 @@ mod tests {
         let bytes = to_bytes_in::<_, Panic>(&value, Vec::new()).unwrap();
         assert!(!bytes.is_empty());
     }
+
+    #[test]
+    fn serialized_size_matches() {
+        use crate::{
+            alloc::{collections::BTreeMap, rc::Rc, vec},
+            api::high::{serialized_size, to_bytes},
+        };
+
+        let shared = Rc::new("shared string value".to_string());
+        let value = (
+            vec![shared.clone(), shared],
+            [(1u32, "one".to_string()), (2, "two".to_string())]
+                .into_iter()
+                .collect::<BTreeMap<_, _>>(),
+            0u8,
+        );
+        let size = serialized_size::<Panic>(&value).unwrap();
+        assert_eq!(size, to_bytes::<Panic>(&value).unwrap().len());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn access_unaligned_copies_misaligned() {
+        use crate::{
+            alloc::vec,
+            api::high::{access_unaligned, to_bytes},
+            util::AlignedVec,
+            Archived,
+        };
+
+        let value = vec![1u64, 2, 3, 4];
+        let bytes = to_bytes::<Panic>(&value).unwrap();
+
+        let archived =
+            access_unaligned::<Archived<Vec<u64>>, Panic>(&bytes).unwrap();
+        assert!(archived.is_borrowed());
+        assert_eq!(*archived, value);
+
+        let mut shifted = AlignedVec::<16>::new();
+        shifted.push(0);
+        shifted.extend_from_slice(&bytes);
+        let archived =
+            access_unaligned::<Archived<Vec<u64>>, Panic>(&shifted[1..])
+                .unwrap();
+        assert_eq!(archived.is_borrowed(), cfg!(feature = "unaligned"));
+        assert_eq!(*archived, value);
+        assert_eq!(archived.bytes(), &*bytes);
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn from_bytes_borrowed_references_buffer() {
+        use crate::{
+            api::high::{from_bytes_borrowed, to_bytes},
+            with::Borrowed,
+            Archive, Deserialize, Serialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Example<'a> {
+            #[rkyv(with = Borrowed)]
+            name: &'a str,
+            #[rkyv(with = Borrowed)]
+            data: &'a [u8],
+            value: u32,
+        }
+
+        let value = Example {
+            name: "a string which is stored out of line",
+            data: &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            value: 42,
+        };
+        let bytes = to_bytes::<Panic>(&value).unwrap();
+
+        let deserialized =
+            from_bytes_borrowed::<Example<'_>, Panic>(&bytes).unwrap();
+        assert_eq!(deserialized.name, value.name);
+        assert_eq!(deserialized.data, value.data);
+        assert_eq!(deserialized.value, 42);
+
+        let range = bytes.as_ptr_range();
+        assert!(range.contains(&deserialized.name.as_ptr()));
+        assert!(range.contains(&deserialized.data.as_ptr()));
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn from_bytes_into_drops_on_error() {
+        use core::{
+            fmt,
+            mem::MaybeUninit,
+            sync::atomic::{AtomicUsize, Ordering},
+        };
+
+        use rancor::{fail, Error, Fallible, Source};
+
+        use crate::{
+            alloc::{boxed::Box, string::String},
+            api::high::{from_bytes_into, to_bytes},
+            Archive, Deserialize, Serialize,
+        };
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Counted(String);
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        #[derive(Debug)]
+        struct Invalid;
+
+        impl fmt::Display for Invalid {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "invalid value")
+            }
+        }
+
+        impl core::error::Error for Invalid {}
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Checked(bool);
+
+        impl<D> Deserialize<Checked, D> for ArchivedChecked
+        where
+            D: Fallible + ?Sized,
+            D::Error: Source,
+        {
+            fn deserialize(&self, _: &mut D) -> Result<Checked, D::Error> {
+                if self.0 {
+                    fail!(Invalid);
+                }
+                Ok(Checked(false))
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Item {
+            counted: Counted,
+            checked: Checked,
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Outer {
+            first: Counted,
+            items: [Item; 3],
+            values: [u32; 1024],
+        }
+
+        let item = |name: &str, invalid| Item {
+            counted: Counted(name.into()),
+            checked: Checked(invalid),
+        };
+        let mut value = Outer {
+            first: Counted("first".into()),
+            items: [item("a", false), item("b", false), item("c", false)],
+            values: [7; 1024],
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+
+        let mut out = Box::new(MaybeUninit::<Outer>::uninit());
+        let deserialized =
+            from_bytes_into::<Outer, Error>(&bytes, &mut out).unwrap();
+        assert_eq!(deserialized.first.0, "first");
+        assert_eq!(deserialized.items[2].counted.0, "c");
+        assert!(deserialized.values.iter().all(|&v| v == 7));
+        // SAFETY: `out` was initialized by `from_bytes_into`.
+        unsafe { out.assume_init_drop() };
+        assert_eq!(DROPS.swap(0, Ordering::Relaxed), 4);
+
+        value.items[2].checked = Checked(true);
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        assert!(from_bytes_into::<Outer, Error>(&bytes, &mut out).is_err());
+        // `first` and the counted values of all three items were dropped.
+        assert_eq!(DROPS.load(Ordering::Relaxed), 4);
+    }
 }
@@ -0,0 +1,188 @@
+//! Repeated serialization which reuses allocations between values.
+
+use core::{fmt, mem::take};
+
+use rancor::{Source, Strategy};
+
+use super::HighSerializer;
+use crate::{
+    ser::{
+        allocator::{Arena, ArenaHandle},
+        sharing::Share,
+        Serializer,
+    },
+    util::AlignedVec,
+    SerializeUnsized,
+};
+
+/// A serializer which keeps its allocations between serializations.
+///
+/// Each call to [`serialize`](ReusableSerializer::serialize) writes a complete
+/// archive for one value, like [`to_bytes`](super::to_bytes). Instead of
+/// allocating a new buffer, arena, and shared pointer map for every value, a
+/// `ReusableSerializer` clears and reuses the ones from the previous call. This
+/// makes it well-suited for loops which serialize many values in a row, since
+/// after the first few values it usually doesn't need to allocate at all.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access, api::high::ReusableSerializer, rancor::Error, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Sample {
+///     sensor: String,
+///     readings: Vec<f32>,
+/// }
+///
+/// let mut serializer = ReusableSerializer::<16>::new();
+/// for i in 0..100 {
+///     let sample = Sample {
+///         sensor: format!("sensor-{}", i % 4),
+///         readings: vec![i as f32; 8],
+///     };
+///
+///     let bytes = serializer.serialize::<_, Error>(&sample).unwrap();
+///     let archived = access::<ArchivedSample, Error>(bytes).unwrap();
+///     assert_eq!(archived.readings[0], i as f32);
+/// }
+/// ```
+pub struct ReusableSerializer<const A: usize = 16> {
+    bytes: AlignedVec<A>,
+    arena: Arena,
+    sharing: Share,
+}
+
+impl<const A: usize> ReusableSerializer<A> {
+    /// Creates a new `ReusableSerializer`.
+    pub fn new() -> Self {
+        Self {
+            bytes: AlignedVec::new(),
+            arena: Arena::new(),
+            sharing: Share::new(),
+        }
+    }
+
+    /// Creates a new `ReusableSerializer` with space for archives of at least
+    /// `bytes_capacity` bytes and at least `arena_capacity` bytes of scratch
+    /// space.
+    ///
+    /// The [`SerializerStats`](crate::ser::SerializerStats) returned by
+    /// [`to_bytes_with_stats`](super::to_bytes_with_stats) can be used to
+    /// choose these capacities.
+    pub fn with_capacity(bytes_capacity: usize, arena_capacity: usize) -> Self {
+        Self {
+            bytes: AlignedVec::with_capacity(bytes_capacity),
+            arena: Arena::with_capacity(arena_capacity),
+            sharing: Share::new(),
+        }
+    }
+
+    /// Serializes a value and returns its archived bytes.
+    ///
+    /// The bytes from the previous call are overwritten. If serialization
+    /// fails, the buffer is left in an unspecified state but keeps its
+    /// allocation.
+    pub fn serialize<T, E>(&mut self, value: &T) -> Result<&AlignedVec<A>, E>
+    where
+        T: for<'a> SerializeUnsized<
+                HighSerializer<&'a mut AlignedVec<A>, ArenaHandle<'a>, E>,
+            > + ?Sized,
+        E: Source,
+    {
+        self.bytes.clear();
+        // Shared pointers are deduplicated by address, so addresses from
+        // previous values must be forgotten before they can be reused.
+        self.sharing.clear();
+
+        let mut serializer = Serializer::new(
+            &mut self.bytes,
+            self.arena.acquire(),
+            take(&mut self.sharing),
+        );
+        let result = value.serialize_unsized(Strategy::wrap(&mut serializer));
+        let (_, _, sharing) = serializer.into_raw_parts();
+        self.sharing = sharing;
+        result?;
+
+        Ok(&self.bytes)
+    }
+
+    /// Returns the bytes written by the last call to
+    /// [`serialize`](ReusableSerializer::serialize).
+    pub fn bytes(&self) -> &AlignedVec<A> {
+        &self.bytes
+    }
+
+    /// Returns the capacity of the buffer that archives are written to.
+    pub fn bytes_capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Returns the capacity of the scratch space arena.
+    pub fn arena_capacity(&self) -> usize {
+        self.arena.capacity()
+    }
+}
+
+impl<const A: usize> Default for ReusableSerializer<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const A: usize> fmt::Debug for ReusableSerializer<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReusableSerializer")
+            .field("bytes", &self.bytes)
+            .field("sharing", &self.sharing)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::Failure;
+
+    use crate::{
+        access,
+        alloc::{rc::Rc, string::String, vec, vec::Vec},
+        api::high::{to_bytes, ReusableSerializer},
+        Archived,
+    };
+
+    #[test]
+    fn reuses_allocations() {
+        let mut serializer = ReusableSerializer::<16>::new();
+
+        let large =
+            vec![String::from("a string which is stored out of line"); 64];
+        serializer.serialize::<_, Failure>(&large).unwrap();
+        let bytes_capacity = serializer.bytes_capacity();
+        let arena_capacity = serializer.arena_capacity();
+
+        for i in 0..32 {
+            let shared = Rc::new(String::from("a shared string"));
+            let value = (vec![shared.clone(), shared], large[..i].to_vec());
+
+            let bytes = serializer.serialize::<_, Failure>(&value).unwrap();
+            assert_eq!(**bytes, *to_bytes::<Failure>(&value).unwrap());
+
+            type ArchivedValue = Archived<(Vec<Rc<String>>, Vec<String>)>;
+            let archived = access::<ArchivedValue, Failure>(bytes).unwrap();
+            assert_eq!(archived.1.len(), i);
+            assert_eq!(
+                archived.0[0].as_str().as_ptr(),
+                archived.0[1].as_str().as_ptr()
+            );
+
+            assert_eq!(serializer.bytes_capacity(), bytes_capacity);
+            assert_eq!(serializer.arena_capacity(), arena_capacity);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Serialization of multiple roots which share pointers.
+
+use core::{fmt, marker::PhantomData, mem::take};
+
+use rancor::{Source, Strategy};
+
+use super::HighSerializer;
+use crate::{
+    ser::{
+        allocator::{Arena, ArenaHandle},
+        sharing::Share,
+        Serializer, Writer,
+    },
+    SerializeUnsized,
+};
+
+/// A serializer which writes multiple root values into the same writer.
+///
+/// Unlike calling [`to_bytes_in`](super::to_bytes_in) repeatedly, shared
+/// pointers (like `Rc` and `Arc`) and interned strings are deduplicated across
+/// all of the values serialized with the same `RootSerializer`. Each call to
+/// [`serialize`](RootSerializer::serialize) returns the position of the
+/// serialized root, which can be accessed with
+/// [`access_pos`](crate::api::high::access_pos).
+///
+/// Shared pointers are deduplicated by address, so values are borrowed for
+/// `'a` to guarantee that they are not dropped while the serializer is in use.
+///
+/// This is part of the [high-level API](crate::api::high).
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use rkyv::{
+///     api::high::{access_pos, RootSerializer},
+///     rancor::Error,
+///     util::AlignedVec,
+///     Archive, Archived, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: Arc<str>,
+///     value: i32,
+/// }
+///
+/// let name = Arc::<str>::from("a name which is shared between roots");
+/// let a = Example {
+///     name: name.clone(),
+///     value: 1,
+/// };
+/// let b = Example { name, value: 2 };
+///
+/// let mut serializer = RootSerializer::new(AlignedVec::<16>::new());
+/// let pos_a = serializer.serialize::<_, Error>(&a).unwrap();
+/// let pos_b = serializer.serialize::<_, Error>(&b).unwrap();
+/// let bytes = serializer.into_writer();
+///
+/// let a = access_pos::<ArchivedExample, Error>(&bytes, pos_a).unwrap();
+/// let b = access_pos::<ArchivedExample, Error>(&bytes, pos_b).unwrap();
+/// assert_eq!(a.value, 1);
+/// assert_eq!(b.value, 2);
+/// assert_eq!(a.name.as_ptr(), b.name.as_ptr());
+/// ```
+pub struct RootSerializer<'a, W> {
+    writer: W,
+    arena: Arena,
+    sharing: Share,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a, W> RootSerializer<'a, W> {
+    /// Creates a new `RootSerializer` which writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            arena: Arena::new(),
+            sharing: Share::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Serializes a root value and returns its position in the writer.
+    ///
+    /// Shared pointers which were already serialized by a previous root are
+    /// not serialized again.
+    pub fn serialize<T, E>(&mut self, value: &'a T) -> Result<usize, E>
+    where
+        T: for<'s> SerializeUnsized<
+                HighSerializer<&'s mut W, ArenaHandle<'s>, E>,
+            > + ?Sized,
+        W: Writer<E>,
+        E: Source,
+    {
+        let mut serializer = Serializer::new(
+            &mut self.writer,
+            self.arena.acquire(),
+            take(&mut self.sharing),
+        );
+        let result = value.serialize_unsized(Strategy::wrap(&mut serializer));
+        let (_, _, sharing) = serializer.into_raw_parts();
+        self.sharing = sharing;
+        result
+    }
+
+    /// Returns a reference to the writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Consumes the serializer and returns the writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for RootSerializer<'_, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootSerializer")
+            .field("writer", &self.writer)
+            .field("sharing", &self.sharing)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::Failure;
+
+    use crate::{
+        alloc::{rc::Rc, string::String, vec, vec::Vec},
+        api::high::{access_pos, RootSerializer},
+        util::AlignedVec,
+        Archived,
+    };
+
+    #[test]
+    fn roots_share_pointers() {
+        let shared = Rc::new(String::from("a string which is shared"));
+        let a = vec![shared.clone(), shared.clone()];
+        let b = vec![shared];
+
+        let mut serializer = RootSerializer::new(AlignedVec::<16>::new());
+        let pos_a = serializer.serialize::<_, Failure>(&a).unwrap();
+        let len_a = serializer.writer().len();
+        let pos_b = serializer.serialize::<_, Failure>(&b).unwrap();
+        let len_b = serializer.writer().len() - len_a;
+        let bytes = serializer.into_writer();
+
+        // The second root only needs to write its vec and one pointer.
+        assert!(len_b < len_a);
+
+        type ArchivedRcs = Archived<Vec<Rc<String>>>;
+        let a = access_pos::<ArchivedRcs, Failure>(&bytes, pos_a).unwrap();
+        let b = access_pos::<ArchivedRcs, Failure>(&bytes, pos_b).unwrap();
+        assert_eq!(a[0].as_str(), "a string which is shared");
+        assert_eq!(b[0].as_str(), "a string which is shared");
+        assert_eq!(a[0].as_str().as_ptr(), b[0].as_str().as_ptr());
+    }
+}
@@ -0,0 +1,334 @@
+//! Mutable access with partial re-validation.
+
+use core::{error::Error, fmt, marker::PhantomData, mem::size_of, ops::Range};
+
+use bytecheck::CheckBytes;
+use rancor::{fail, Source};
+
+use super::{checked::validator, HighValidator};
+use crate::{
+    alloc::vec::Vec,
+    api::{
+        access_pos_unchecked, access_pos_unchecked_mut, check_pos_with_context,
+        root_position,
+    },
+    seal::Seal,
+    Portable,
+};
+
+#[derive(Debug)]
+struct MutationOutOfBounds {
+    pos: usize,
+    size: usize,
+    len: usize,
+}
+
+impl fmt::Display for MutationOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "mutated value at {}..{} is not contained in the buffer of length \
+             {}",
+            self.pos,
+            self.pos.wrapping_add(self.size),
+            self.len,
+        )
+    }
+}
+
+impl Error for MutationOutOfBounds {}
+
+/// A value which was mutated and must be validated again.
+struct Mutation<E> {
+    pos: usize,
+    size: usize,
+    check: fn(&[u8], usize) -> Result<(), E>,
+}
+
+fn check_mutation<U, E>(bytes: &[u8], pos: usize) -> Result<(), E>
+where
+    U: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    check_pos_with_context::<U, _, E>(bytes, pos, &mut validator(bytes))
+}
+
+/// A mutably-accessed archived value which tracks the parts that have been
+/// mutated.
+///
+/// Each call to [`mutate`](TrackedMut::mutate) records the position and type
+/// of the value it mutates. Before the archived value can be accessed again,
+/// only the recorded values (and everything they point to) are validated
+/// again instead of the whole buffer.
+///
+/// Only the value returned from the selection function is re-validated.
+/// Invariants which containing values place on it are not checked again, so
+/// the selection function should only navigate to the value and not mutate
+/// anything along the way.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub struct TrackedMut<'a, T, E> {
+    bytes: &'a mut [u8],
+    pos: usize,
+    mutations: Vec<Mutation<E>>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T, E> fmt::Debug for TrackedMut<'_, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedMut")
+            .field("pos", &self.pos)
+            .field("dirty", &self.dirty_ranges().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<'a, T, E> TrackedMut<'a, T, E> {
+    /// Returns whether any values have been mutated since the archived value
+    /// was last validated.
+    pub fn is_dirty(&self) -> bool {
+        !self.mutations.is_empty()
+    }
+
+    /// Returns the byte ranges of the values which have been mutated since the
+    /// archived value was last validated.
+    ///
+    /// Data which is pointed to by the mutated values may also have changed,
+    /// and is validated again along with them.
+    pub fn dirty_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.mutations
+            .iter()
+            .map(|mutation| mutation.pos..mutation.pos + mutation.size)
+    }
+}
+
+impl<'a, T: Portable, E: Source> TrackedMut<'a, T, E> {
+    /// Validates the values which have been mutated since the archived value
+    /// was last validated.
+    ///
+    /// Values which fail validation remain dirty, so the archived value can't
+    /// be accessed until they are fixed.
+    pub fn revalidate(&mut self) -> Result<(), E> {
+        while let Some(mutation) = self.mutations.last() {
+            (mutation.check)(self.bytes, mutation.pos)?;
+            self.mutations.pop();
+        }
+        Ok(())
+    }
+
+    /// Re-validates any mutated values and returns a reference to the
+    /// archived value.
+    pub fn get(&mut self) -> Result<&T, E> {
+        self.revalidate()?;
+        // SAFETY: The buffer was validated when it was accessed, and every
+        // value which has been mutated since has been validated again.
+        unsafe { Ok(access_pos_unchecked::<T>(self.bytes, self.pos)) }
+    }
+
+    /// Mutates part of the archived value.
+    ///
+    /// `select` navigates from the archived value to the part to mutate, which
+    /// is then passed to `f`. The selected value is recorded so that it is
+    /// validated again before the archived value is next accessed. Any values
+    /// which were mutated before are re-validated first.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     api::high::access_mut_tracked, munge::munge, rancor::Error,
+    ///     vec::ArchivedVec, Archive, Serialize,
+    /// };
+    ///
+    /// #[derive(Archive, Serialize)]
+    /// struct Example {
+    ///     name: String,
+    ///     values: Vec<i32>,
+    /// }
+    ///
+    /// let value = Example {
+    ///     name: "pi".to_string(),
+    ///     values: vec![3, 1, 4, 1, 5],
+    /// };
+    ///
+    /// let mut bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+    /// let mut tracked =
+    ///     access_mut_tracked::<ArchivedExample, Error>(&mut bytes).unwrap();
+    ///
+    /// tracked
+    ///     .mutate(
+    ///         |example| {
+    ///             munge!(let ArchivedExample { values, .. } = example);
+    ///             ArchivedVec::as_slice_seal(values).index(2)
+    ///         },
+    ///         |mut value| *value = 9.into(),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// // Only the mutated value is validated again.
+    /// assert!(tracked.is_dirty());
+    /// assert_eq!(tracked.get().unwrap().values[2], 9);
+    /// assert!(!tracked.is_dirty());
+    /// ```
+    pub fn mutate<U, R>(
+        &mut self,
+        select: impl FnOnce(Seal<'_, T>) -> Seal<'_, U>,
+        f: impl FnOnce(Seal<'_, U>) -> R,
+    ) -> Result<R, E>
+    where
+        U: Portable + for<'b> CheckBytes<HighValidator<'b, E>>,
+    {
+        self.revalidate()?;
+
+        let base = self.bytes.as_ptr() as usize;
+        let len = self.bytes.len();
+        // SAFETY: Every value which has been mutated has been validated again.
+        let root =
+            unsafe { access_pos_unchecked_mut::<T>(self.bytes, self.pos) };
+        let selected = select(root);
+
+        let pos = (&*selected as *const U as usize).wrapping_sub(base);
+        if pos > len || len - pos < size_of::<U>() {
+            fail!(MutationOutOfBounds {
+                pos,
+                size: size_of::<U>(),
+                len,
+            });
+        }
+
+        // Record the mutation before running `f` so that the value is still
+        // validated again if `f` panics.
+        self.mutations.push(Mutation {
+            pos,
+            size: size_of::<U>(),
+            check: check_mutation::<U, E>,
+        });
+
+        Ok(f(selected))
+    }
+
+    /// Re-validates any mutated values and returns the sealed archived value.
+    pub fn into_inner(mut self) -> Result<Seal<'a, T>, E> {
+        self.revalidate()?;
+        // SAFETY: Every value which has been mutated has been validated again.
+        unsafe { Ok(access_pos_unchecked_mut::<T>(self.bytes, self.pos)) }
+    }
+}
+
+/// Mutably access a byte slice and track which parts are mutated.
+///
+/// The buffer is validated in full once. Afterward, the returned
+/// [`TrackedMut`] only validates the values which were mutated through it
+/// before they are accessed again.
+///
+/// This is part of the [high-level API](crate::api::high).
+pub fn access_mut_tracked<T, E>(
+    bytes: &mut [u8],
+) -> Result<TrackedMut<'_, T, E>, E>
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source,
+{
+    let pos = root_position::<T>(bytes.len());
+    check_pos_with_context::<T, _, E>(bytes, pos, &mut validator(bytes))?;
+    Ok(TrackedMut {
+        bytes,
+        pos,
+        mutations: Vec::new(),
+        _phantom: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use munge::munge;
+    use rancor::{Error, Failure};
+
+    use crate::{
+        alloc::{string::ToString, vec},
+        api::high::{access_mut_tracked, to_bytes},
+        string::ArchivedString,
+        vec::ArchivedVec,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Example {
+        name: String,
+        flag: bool,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn tracked_mutation() {
+        let value = Example {
+            name: "example".to_string(),
+            flag: false,
+            values: vec![1, 2, 3],
+        };
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+        let mut tracked =
+            access_mut_tracked::<ArchivedExample, Failure>(&mut bytes).unwrap();
+        assert!(!tracked.is_dirty());
+
+        tracked
+            .mutate(
+                |example| {
+                    munge!(let ArchivedExample { values, .. } = example);
+                    ArchivedVec::as_slice_seal(values).index(1)
+                },
+                |mut value| *value = 20.into(),
+            )
+            .unwrap();
+        tracked
+            .mutate(
+                |example| {
+                    munge!(let ArchivedExample { name, .. } = example);
+                    name
+                },
+                |name| ArchivedString::as_str_seal(name).make_ascii_uppercase(),
+            )
+            .unwrap();
+        assert!(tracked.is_dirty());
+        assert_eq!(tracked.dirty_ranges().count(), 1);
+
+        let example = tracked.get().unwrap();
+        assert_eq!(example.name, "EXAMPLE");
+        assert_eq!(example.values[1], 20);
+        assert!(!tracked.is_dirty());
+
+        let example = tracked.into_inner().unwrap();
+        assert!(!example.flag);
+    }
+
+    #[test]
+    fn tracked_invalid_mutation() {
+        let value = Example {
+            name: "example".to_string(),
+            flag: false,
+            values: vec![1, 2, 3],
+        };
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+        let mut tracked =
+            access_mut_tracked::<ArchivedExample, Failure>(&mut bytes).unwrap();
+
+        tracked
+            .mutate(
+                |example| {
+                    munge!(let ArchivedExample { flag, .. } = example);
+                    flag
+                },
+                |flag| unsafe {
+                    let flag = flag.unseal_unchecked() as *mut bool;
+                    flag.cast::<u8>().write(2);
+                },
+            )
+            .unwrap();
+
+        assert!(tracked.revalidate().is_err());
+        assert!(tracked.is_dirty());
+        assert!(tracked.get().is_err());
+        assert!(tracked.into_inner().is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Low-level checked APIs.
 //!
-//! These APIs require user-provided writers and allocators, and do not support
-//! shared pointers.
+//! These APIs require user-provided writers and allocators. Only
+//! [`access_bounded`] supports shared pointers.
 
 use bytecheck::CheckBytes;
 use rancor::{Source, Strategy};
@@ -13,7 +13,11 @@ use crate::{
     },
     de::pooling::Unpool,
     seal::Seal,
-    validation::{archive::ArchiveValidator, Validator},
+    validation::{
+        archive::{ArchiveValidator, ValidationLimits},
+        shared::BoundedSharedValidator,
+        Validator,
+    },
     Archive, Deserialize, Portable,
 };
 
@@ -22,6 +26,12 @@ use crate::{
 /// This is part of the [low-level API](crate::api::low).
 pub type LowValidator<'a, E> = Strategy<Validator<ArchiveValidator<'a>, ()>, E>;
 
+/// A low-level validator which can validate up to `N` shared pointers.
+///
+/// This is part of the [low-level API](crate::api::low).
+pub type BoundedValidator<'a, E, const N: usize> =
+    Strategy<Validator<ArchiveValidator<'a>, BoundedSharedValidator<N>>, E>;
+
 fn validator(bytes: &[u8]) -> Validator<ArchiveValidator<'_>, ()> {
     Validator::new(ArchiveValidator::new(bytes), ())
 }
@@ -311,3 +321,127 @@ where
 {
     deserialize_using(access::<T::Archived, E>(bytes)?, &mut Unpool)
 }
+
+/// Access a byte slice while bounding the resources used to validate it.
+///
+/// Validation recurses into each nested out-of-line value, so accessing
+/// untrusted data without a depth limit may overflow the stack. This validates
+/// the archive with the given limits and supports up to `N` shared pointers,
+/// all without allocating. Set [`max_depth`](ValidationLimits::max_depth) to
+/// bound the stack space used by validation.
+///
+/// This is part of the [low-level API](crate::api::low).
+///
+/// # Example
+///
+/// ```
+/// use core::{mem::MaybeUninit, num::NonZeroUsize};
+///
+/// use rkyv::{
+///     api::low::{access_bounded, to_bytes_in_with_alloc},
+///     rancor::Failure,
+///     ser::{allocator::SubAllocator, writer::Buffer},
+///     util::Align,
+///     validation::archive::ValidationLimits,
+///     with::InlineAsBox,
+///     Archive, Serialize,
+/// };
+///
+/// let mut output = Align([MaybeUninit::<u8>::uninit(); 256]);
+/// let mut alloc = [MaybeUninit::<u8>::uninit(); 256];
+///
+/// #[derive(Archive, Serialize)]
+/// struct Packet<'a> {
+///     #[rkyv(with = InlineAsBox)]
+///     reading: &'a i32,
+/// }
+///
+/// let reading = 42;
+/// let value = Packet { reading: &reading };
+///
+/// let bytes = to_bytes_in_with_alloc::<_, _, Failure>(
+///     &value,
+///     Buffer::from(&mut *output),
+///     SubAllocator::new(&mut alloc),
+/// )
+/// .unwrap();
+///
+/// let limits = ValidationLimits {
+///     max_depth: NonZeroUsize::new(8),
+///     ..Default::default()
+/// };
+/// let archived =
+///     access_bounded::<ArchivedPacket<'_>, Failure, 4>(&*bytes, limits)
+///         .unwrap();
+/// assert_eq!(*archived.reading, 42);
+/// ```
+pub fn access_bounded<T, E, const N: usize>(
+    bytes: &[u8],
+    limits: ValidationLimits,
+) -> Result<&T, E>
+where
+    T: Portable + for<'a> CheckBytes<BoundedValidator<'a, E, N>>,
+    E: Source,
+{
+    let mut context = Validator::new(
+        ArchiveValidator::with_limits(bytes, limits),
+        BoundedSharedValidator::<N>::new(),
+    );
+    access_with_context::<_, _, E>(bytes, &mut context)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use core::num::NonZeroUsize;
+
+    use rancor::Failure;
+
+    use super::access_bounded;
+    use crate::{
+        alloc::{
+            rc::Rc,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::high::to_bytes,
+        validation::archive::ValidationLimits,
+        Archived,
+    };
+
+    type Value = Vec<Rc<u32>>;
+
+    #[test]
+    fn bounded_shared_pointers() {
+        let shared = (0..3).map(Rc::new).collect::<Vec<_>>();
+        let value = shared.iter().chain(&shared).cloned().collect::<Value>();
+        let bytes = to_bytes::<Failure>(&value).unwrap();
+
+        let limits = ValidationLimits::default();
+        let archived =
+            access_bounded::<Archived<Value>, Failure, 3>(&bytes, limits)
+                .unwrap();
+        assert_eq!(*archived[4], 1);
+        assert!(
+            access_bounded::<Archived<Value>, Failure, 2>(&bytes, limits)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn bounded_depth() {
+        let value =
+            vec![vec!["a string which is stored out of line".to_string()]];
+        let bytes = to_bytes::<Failure>(&value).unwrap();
+
+        let limits = |depth| ValidationLimits {
+            max_depth: NonZeroUsize::new(depth),
+            ..Default::default()
+        };
+        type Nested = Archived<Vec<Vec<String>>>;
+        let access =
+            |depth| access_bounded::<Nested, Failure, 0>(&bytes, limits(depth));
+        assert!(access(4).is_ok());
+        assert!(access(3).is_err());
+    }
+}
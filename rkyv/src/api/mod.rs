@@ -21,15 +21,19 @@ mod checked;
 #[cfg(feature = "alloc")]
 pub mod high;
 pub mod low;
+#[cfg(feature = "alloc")]
+pub mod multi;
 #[cfg(test)]
 pub mod test;
 
-use core::mem::size_of;
+use core::mem::{size_of, MaybeUninit};
 
 use rancor::Strategy;
 
 #[cfg(feature = "bytecheck")]
 pub use self::checked::*;
+#[cfg(feature = "alloc")]
+pub use self::high::serialized_size;
 use crate::{seal::Seal, ser::Writer, Deserialize, Portable, SerializeUnsized};
 
 #[cfg(debug_assertions)]
@@ -414,3 +418,63 @@ pub fn deserialize_using<T, D, E>(
 ) -> Result<T, E> {
     value.deserialize(Strategy::wrap(deserializer))
 }
+
+/// Deserialize a value into `out` using the given deserializer.
+///
+/// Derived impls of `Deserialize` for structs and impls for arrays construct
+/// their fields directly in `out`, so large values are not moved after they
+/// are deserialized. This also makes it possible to deserialize into storage
+/// which was allocated ahead of time, like slots in an arena or a pool.
+///
+/// Returns a reference to the initialized value. If an error is returned,
+/// `out` is left uninitialized.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     access, api::deserialize_into, de::Pool, rancor::Error, to_bytes,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Frame {
+///     id: u32,
+///     pixels: [u8; 4096],
+/// }
+///
+/// let value = Frame {
+///     id: 7,
+///     pixels: [255; 4096],
+/// };
+///
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived = access::<ArchivedFrame, Error>(&bytes).unwrap();
+///
+/// let mut slot = Box::new(MaybeUninit::<Frame>::uninit());
+/// let frame = deserialize_into::<Frame, _, Error>(
+///     archived,
+///     &mut slot,
+///     &mut Pool::new(),
+/// )
+/// .unwrap();
+/// assert_eq!(frame.id, 7);
+/// assert!(frame.pixels.iter().all(|&p| p == 255));
+/// # unsafe { slot.assume_init_drop() };
+/// ```
+pub fn deserialize_into<'o, T, D, E>(
+    value: &impl Deserialize<T, Strategy<D, E>>,
+    out: &'o mut MaybeUninit<T>,
+    deserializer: &mut D,
+) -> Result<&'o mut T, E> {
+    // SAFETY: `out` is a mutable reference, so it is non-null, properly
+    // aligned, and valid for writes.
+    unsafe {
+        value
+            .deserialize_into(Strategy::wrap(deserializer), out.as_mut_ptr())?;
+    }
+    // SAFETY: `deserialize_into` initialized `out` because it returned `Ok`.
+    Ok(unsafe { out.assume_init_mut() })
+}
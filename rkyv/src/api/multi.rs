@@ -0,0 +1,366 @@
+//! Archives which contain multiple unrelated root values.
+//!
+//! A [`MultiSerializer`] serializes several values into the same buffer and
+//! finishes it with an index that records the position, type tag, and optional
+//! name of each value. A [`MultiArchive`] reads the index back and provides
+//! validated access to each value by index or by name.
+//!
+//! Shared pointers are deduplicated across all of the values in a multi-root
+//! archive, just like with [`RootSerializer`].
+//!
+//! The type tag of each value is a hash of the name of its archived type. It
+//! guards against accidentally accessing a value as the wrong type, but type
+//! names are not guaranteed to be stable across compiler versions or crate
+//! refactorings.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     api::multi::{MultiArchive, MultiSerializer},
+//!     rancor::Error,
+//!     string::ArchivedString,
+//!     util::AlignedVec,
+//!     Archived,
+//! };
+//!
+//! let name = "config".to_string();
+//! let values = vec![1u32, 2, 3];
+//!
+//! let mut serializer = MultiSerializer::new(AlignedVec::<16>::new());
+//! serializer
+//!     .serialize_named::<_, Error>("name", &name)
+//!     .unwrap();
+//! serializer.serialize::<_, Error>(&values).unwrap();
+//! let bytes = serializer.finish::<Error>().unwrap();
+//!
+//! let archive = MultiArchive::new::<Error>(&bytes).unwrap();
+//! assert_eq!(archive.len(), 2);
+//!
+//! let name = archive.get_named::<ArchivedString, Error>("name").unwrap();
+//! assert_eq!(name, "config");
+//! let values = archive.get::<Archived<Vec<u32>>, Error>(1).unwrap();
+//! assert_eq!(values, &[1, 2, 3]);
+//!
+//! // Accessing a value as the wrong type fails.
+//! assert!(archive.get::<ArchivedString, Error>(1).is_err());
+//! ```
+
+use core::any::type_name;
+#[cfg(feature = "bytecheck")]
+use core::{error::Error, fmt};
+
+#[cfg(feature = "bytecheck")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "bytecheck")]
+use rancor::fail;
+use rancor::Source;
+
+#[cfg(feature = "bytecheck")]
+use crate::api::high::{access, access_pos, HighValidator};
+#[cfg(feature = "bytecheck")]
+use crate::Portable;
+use crate::{
+    alloc::{string::String, vec::Vec},
+    api::high::{to_bytes_in, HighSerializer, RootSerializer},
+    hash::{hash_value, FxHasher64},
+    ser::{allocator::ArenaHandle, Writer},
+    Archive, ArchiveUnsized, Serialize, SerializeUnsized,
+};
+
+fn type_tag<T: ?Sized>() -> u64 {
+    hash_value::<str, FxHasher64>(type_name::<T>())
+}
+
+#[derive(Archive, Serialize, Debug)]
+#[rkyv(crate)]
+struct MultiEntry {
+    name: Option<String>,
+    type_tag: u64,
+    pos: usize,
+}
+
+#[derive(Archive, Serialize)]
+#[rkyv(crate)]
+struct MultiIndex {
+    entries: Vec<MultiEntry>,
+}
+
+/// A serializer which writes multiple root values into a multi-root archive.
+///
+/// Values are appended to the writer as they are serialized. Calling
+/// [`finish`](MultiSerializer::finish) writes the index of the archive and
+/// returns the writer.
+#[derive(Debug)]
+pub struct MultiSerializer<'a, W> {
+    roots: RootSerializer<'a, W>,
+    entries: Vec<MultiEntry>,
+}
+
+impl<'a, W> MultiSerializer<'a, W> {
+    /// Creates a new `MultiSerializer` which writes to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            roots: RootSerializer::new(writer),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of values which have been serialized.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no values have been serialized.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Serializes a value and returns its index in the archive.
+    pub fn serialize<T, E>(&mut self, value: &'a T) -> Result<usize, E>
+    where
+        T: for<'s> SerializeUnsized<
+                HighSerializer<&'s mut W, ArenaHandle<'s>, E>,
+            > + ?Sized,
+        W: Writer<E>,
+        E: Source,
+    {
+        self.push(None, value)
+    }
+
+    /// Serializes a value with the given name and returns its index in the
+    /// archive.
+    ///
+    /// Names do not have to be unique, but only the first value with a given
+    /// name can be accessed with
+    /// [`get_named`](MultiArchive::get_named).
+    pub fn serialize_named<T, E>(
+        &mut self,
+        name: &str,
+        value: &'a T,
+    ) -> Result<usize, E>
+    where
+        T: for<'s> SerializeUnsized<
+                HighSerializer<&'s mut W, ArenaHandle<'s>, E>,
+            > + ?Sized,
+        W: Writer<E>,
+        E: Source,
+    {
+        self.push(Some(name.into()), value)
+    }
+
+    fn push<T, E>(
+        &mut self,
+        name: Option<String>,
+        value: &'a T,
+    ) -> Result<usize, E>
+    where
+        T: for<'s> SerializeUnsized<
+                HighSerializer<&'s mut W, ArenaHandle<'s>, E>,
+            > + ?Sized,
+        W: Writer<E>,
+        E: Source,
+    {
+        let pos = self.roots.serialize(value)?;
+        self.entries.push(MultiEntry {
+            name,
+            type_tag: type_tag::<<T as ArchiveUnsized>::Archived>(),
+            pos,
+        });
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Writes the index of the archive and returns the writer.
+    pub fn finish<E>(self) -> Result<W, E>
+    where
+        W: Writer<E>,
+        E: Source,
+    {
+        let index = MultiIndex {
+            entries: self.entries,
+        };
+        to_bytes_in(&index, self.roots.into_writer())
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+#[derive(Debug)]
+enum RootNotFound {
+    Index(usize),
+    Name(String),
+}
+
+#[cfg(feature = "bytecheck")]
+impl fmt::Display for RootNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => {
+                write!(f, "multi-root archive has no value at index {index}")
+            }
+            Self::Name(name) => {
+                write!(f, "multi-root archive has no value named `{name}`")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl Error for RootNotFound {}
+
+#[cfg(feature = "bytecheck")]
+#[derive(Debug)]
+struct RootTypeMismatch {
+    index: usize,
+    expected: &'static str,
+}
+
+#[cfg(feature = "bytecheck")]
+impl fmt::Display for RootTypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value at index {} of multi-root archive is not a `{}`",
+            self.index, self.expected,
+        )
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl Error for RootTypeMismatch {}
+
+/// A reader for archives written with a [`MultiSerializer`].
+#[cfg(feature = "bytecheck")]
+pub struct MultiArchive<'a> {
+    bytes: &'a [u8],
+    index: &'a ArchivedMultiIndex,
+}
+
+#[cfg(feature = "bytecheck")]
+impl<'a> MultiArchive<'a> {
+    /// Validates the index of the given multi-root archive.
+    ///
+    /// Values are validated individually when they are accessed.
+    pub fn new<E: Source>(bytes: &'a [u8]) -> Result<Self, E> {
+        Ok(Self {
+            bytes,
+            index: access::<ArchivedMultiIndex, E>(bytes)?,
+        })
+    }
+
+    /// Returns the number of values in the archive.
+    pub fn len(&self) -> usize {
+        self.index.entries.len()
+    }
+
+    /// Returns whether the archive contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.index.entries.is_empty()
+    }
+
+    /// Returns the name of the value at the given index, if it has one.
+    pub fn name(&self, index: usize) -> Option<&'a str> {
+        self.index
+            .entries
+            .get(index)?
+            .name
+            .as_ref()
+            .map(|n| n.as_str())
+    }
+
+    /// Returns the index of the first value with the given name.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.index.entries.iter().position(|entry| {
+            entry.name.as_ref().is_some_and(|n| n.as_str() == name)
+        })
+    }
+
+    /// Validates and returns the value at the given index.
+    ///
+    /// Returns an error if there is no value at the index or if it was not
+    /// archived as a `T`.
+    pub fn get<T, E>(&self, index: usize) -> Result<&'a T, E>
+    where
+        T: Portable + for<'v> CheckBytes<HighValidator<'v, E>>,
+        E: Source,
+    {
+        let Some(entry) = self.index.entries.get(index) else {
+            fail!(RootNotFound::Index(index));
+        };
+        if entry.type_tag != type_tag::<T>() {
+            fail!(RootTypeMismatch {
+                index,
+                expected: type_name::<T>(),
+            });
+        }
+        access_pos::<T, E>(self.bytes, entry.pos.to_native() as usize)
+    }
+
+    /// Validates and returns the first value with the given name.
+    ///
+    /// Returns an error if there is no value with the name or if it was not
+    /// archived as a `T`.
+    pub fn get_named<T, E>(&self, name: &str) -> Result<&'a T, E>
+    where
+        T: Portable + for<'v> CheckBytes<HighValidator<'v, E>>,
+        E: Source,
+    {
+        let Some(index) = self.position(name) else {
+            fail!(RootNotFound::Name(name.into()));
+        };
+        self.get(index)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl fmt::Debug for MultiArchive<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries((0..self.len()).map(|i| (i, self.name(i))))
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::Failure;
+
+    use crate::{
+        alloc::{rc::Rc, string::String, vec, vec::Vec},
+        api::multi::{MultiArchive, MultiSerializer},
+        string::ArchivedString,
+        util::AlignedVec,
+        Archived,
+    };
+
+    #[test]
+    fn multi_roundtrip() {
+        let shared = Rc::new(String::from("a string which is shared"));
+        let a = vec![shared.clone()];
+        let b = vec![shared];
+        let n = 42u32;
+
+        let mut serializer = MultiSerializer::new(AlignedVec::<16>::new());
+        assert!(serializer.is_empty());
+        serializer.serialize_named::<_, Failure>("a", &a).unwrap();
+        serializer.serialize::<_, Failure>(&n).unwrap();
+        serializer.serialize_named::<_, Failure>("b", &b).unwrap();
+        assert_eq!(serializer.len(), 3);
+        let bytes = serializer.finish::<Failure>().unwrap();
+
+        let archive = MultiArchive::new::<Failure>(&bytes).unwrap();
+        assert_eq!(archive.len(), 3);
+        assert_eq!(archive.name(0), Some("a"));
+        assert_eq!(archive.name(1), None);
+        assert_eq!(archive.position("b"), Some(2));
+
+        type ArchivedRcs = Archived<Vec<Rc<String>>>;
+        let a = archive.get_named::<ArchivedRcs, Failure>("a").unwrap();
+        let b = archive.get_named::<ArchivedRcs, Failure>("b").unwrap();
+        assert_eq!(a[0].as_str().as_ptr(), b[0].as_str().as_ptr());
+        assert_eq!(*archive.get::<Archived<u32>, Failure>(1).unwrap(), 42);
+
+        assert!(archive.get::<Archived<u32>, Failure>(3).is_err());
+        assert!(archive.get::<ArchivedString, Failure>(1).is_err());
+        assert!(archive.get_named::<ArchivedRcs, Failure>("c").is_err());
+    }
+}
@@ -0,0 +1,497 @@
+//! Archived arbitrary-precision integers.
+//!
+//! [`ArchivedBigUint`] stores the magnitude of an integer as a vec of archived
+//! `u64` limbs, starting with the least significant limb. The most significant
+//! limb is never zero, so each value has exactly one representation.
+//! [`ArchivedBigInt`] adds an [`ArchivedSign`] to the magnitude.
+//!
+//! These are the archived types for `BigUint` and `BigInt` from `num-bigint`.
+//! They support comparisons and conversions to primitive integers without
+//! deserializing.
+
+use core::{cmp::Ordering, fmt, hash};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::ArchivedU64,
+    ser::{Allocator, Writer},
+    traits::NoUndef,
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable,
+};
+
+/// Returns `limbs` without any most significant zero limbs.
+fn trim(limbs: &[u64]) -> &[u64] {
+    let len = limbs
+        .iter()
+        .rposition(|&limb| limb != 0)
+        .map_or(0, |i| i + 1);
+    &limbs[..len]
+}
+
+/// An archived arbitrary-precision unsigned integer.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(transparent)]
+pub struct ArchivedBigUint {
+    limbs: ArchivedVec<ArchivedU64>,
+}
+
+impl ArchivedBigUint {
+    /// Returns the limbs of the integer, starting with the least significant
+    /// limb.
+    pub fn limbs(&self) -> &[ArchivedU64] {
+        self.limbs.as_slice()
+    }
+
+    /// Returns whether the integer is zero.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Returns the number of bits needed to represent the integer.
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(last) => {
+                self.limbs.len() as u64 * 64
+                    - last.to_native().leading_zeros() as u64
+            }
+            None => 0,
+        }
+    }
+
+    /// Returns the integer as a `u128`, or `None` if it is too large.
+    fn to_u128(&self) -> Option<u128> {
+        match self.limbs() {
+            [] => Some(0),
+            [lo] => Some(lo.to_native() as u128),
+            [lo, hi] => {
+                Some(lo.to_native() as u128 | (hi.to_native() as u128) << 64)
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts the integer to a primitive integer type, or returns `None` if
+    /// it doesn't fit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rkyv::big_int::ArchivedBigUint;
+    /// fn low_bits(value: &ArchivedBigUint) -> Option<u32> {
+    ///     value.to_native_checked::<u32>()
+    /// }
+    /// ```
+    pub fn to_native_checked<T: TryFrom<u128>>(&self) -> Option<T> {
+        T::try_from(self.to_u128()?).ok()
+    }
+
+    /// Serializes the limbs of an archived integer.
+    ///
+    /// Most significant zero limbs are not serialized.
+    pub fn serialize_from_limbs<S>(
+        limbs: &[u64],
+        serializer: &mut S,
+    ) -> Result<BigUintResolver, S::Error>
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        let limbs = trim(limbs);
+        Ok(BigUintResolver {
+            limbs: ArchivedVec::serialize_from_slice(limbs, serializer)?,
+            len: limbs.len(),
+        })
+    }
+
+    /// Resolves an archived integer from a [`BigUintResolver`].
+    pub fn resolve_from_resolver(resolver: BigUintResolver, out: Place<Self>) {
+        munge!(let ArchivedBigUint { limbs } = out);
+        ArchivedVec::resolve_from_len(resolver.len, resolver.limbs, limbs);
+    }
+}
+
+impl fmt::Debug for ArchivedBigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+impl fmt::LowerHex for ArchivedBigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            Some(first) => write!(f, "{:x}", first.to_native())?,
+            None => f.write_str("0")?,
+        }
+        for limb in limbs {
+            write!(f, "{:016x}", limb.to_native())?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for ArchivedBigUint {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs() == other.limbs()
+    }
+}
+
+impl Eq for ArchivedBigUint {}
+
+impl PartialOrd for ArchivedBigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArchivedBigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.len().cmp(&other.limbs.len()).then_with(|| {
+            self.limbs
+                .iter()
+                .rev()
+                .map(|limb| limb.to_native())
+                .cmp(other.limbs.iter().rev().map(|limb| limb.to_native()))
+        })
+    }
+}
+
+impl hash::Hash for ArchivedBigUint {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.limbs.len());
+        for limb in self.limbs.iter() {
+            state.write_u64(limb.to_native());
+        }
+    }
+}
+
+/// The resolver for [`ArchivedBigUint`].
+pub struct BigUintResolver {
+    limbs: VecResolver,
+    len: usize,
+}
+
+/// The sign of an [`ArchivedBigInt`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(u8)]
+#[rkyv(crate)]
+pub enum ArchivedSign {
+    /// The integer is negative.
+    Minus,
+    /// The integer is zero.
+    NoSign,
+    /// The integer is positive.
+    Plus,
+}
+
+// SAFETY: `ArchivedSign` is `repr(u8)` and so always consists of a single
+// well-defined byte.
+unsafe impl NoUndef for ArchivedSign {}
+
+/// An archived arbitrary-precision signed integer.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedBigInt {
+    magnitude: ArchivedBigUint,
+    sign: ArchivedSign,
+}
+
+impl ArchivedBigInt {
+    /// Returns the sign of the integer.
+    pub fn sign(&self) -> ArchivedSign {
+        self.sign
+    }
+
+    /// Returns the magnitude of the integer.
+    pub fn magnitude(&self) -> &ArchivedBigUint {
+        &self.magnitude
+    }
+
+    /// Returns whether the integer is negative.
+    pub fn is_negative(&self) -> bool {
+        self.sign == ArchivedSign::Minus
+    }
+
+    /// Returns whether the integer is zero.
+    pub fn is_zero(&self) -> bool {
+        self.sign == ArchivedSign::NoSign
+    }
+
+    /// Converts the integer to a primitive integer type, or returns `None` if
+    /// it doesn't fit.
+    pub fn to_native_checked<T>(&self) -> Option<T>
+    where
+        T: TryFrom<u128> + TryFrom<i128>,
+    {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.is_negative() {
+            let value = 0i128.checked_sub_unsigned(magnitude)?;
+            T::try_from(value).ok()
+        } else {
+            T::try_from(magnitude).ok()
+        }
+    }
+
+    /// Serializes the magnitude of an archived integer from its limbs.
+    pub fn serialize_from_limbs<S>(
+        limbs: &[u64],
+        serializer: &mut S,
+    ) -> Result<BigIntResolver, S::Error>
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        Ok(BigIntResolver {
+            magnitude: ArchivedBigUint::serialize_from_limbs(
+                limbs, serializer,
+            )?,
+        })
+    }
+
+    /// Resolves an archived integer from whether it is negative and a
+    /// [`BigIntResolver`].
+    ///
+    /// The sign of zero is always [`ArchivedSign::NoSign`].
+    pub fn resolve_from_resolver(
+        negative: bool,
+        resolver: BigIntResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedBigInt { magnitude, sign } = out);
+        let out_sign = if resolver.magnitude.len == 0 {
+            ArchivedSign::NoSign
+        } else if negative {
+            ArchivedSign::Minus
+        } else {
+            ArchivedSign::Plus
+        };
+        sign.write(out_sign);
+        ArchivedBigUint::resolve_from_resolver(resolver.magnitude, magnitude);
+    }
+}
+
+impl fmt::Debug for ArchivedBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative() {
+            f.write_str("-")?;
+        }
+        fmt::LowerHex::fmt(&self.magnitude, f)
+    }
+}
+
+impl PartialEq for ArchivedBigInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.sign == other.sign && self.magnitude == other.magnitude
+    }
+}
+
+impl Eq for ArchivedBigInt {}
+
+impl PartialOrd for ArchivedBigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArchivedBigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let rank = |sign| match sign {
+            ArchivedSign::Minus => 0,
+            ArchivedSign::NoSign => 1,
+            ArchivedSign::Plus => 2,
+        };
+        rank(self.sign).cmp(&rank(other.sign)).then_with(|| {
+            let ordering = self.magnitude.cmp(&other.magnitude);
+            if self.is_negative() {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+    }
+}
+
+impl hash::Hash for ArchivedBigInt {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.sign.hash(state);
+        self.magnitude.hash(state);
+    }
+}
+
+/// The resolver for [`ArchivedBigInt`].
+pub struct BigIntResolver {
+    magnitude: BigUintResolver,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::{ArchivedBigInt, ArchivedBigUint, ArchivedSign};
+
+    #[derive(Debug)]
+    enum InvalidBigInt {
+        TrailingZeroLimb,
+        SignMismatch,
+    }
+
+    impl fmt::Display for InvalidBigInt {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::TrailingZeroLimb => write!(
+                    f,
+                    "archived big integer has a most significant limb of zero",
+                ),
+                Self::SignMismatch => write!(
+                    f,
+                    "archived big integer has a sign which doesn't match its \
+                     magnitude",
+                ),
+            }
+        }
+    }
+
+    impl Error for InvalidBigInt {}
+
+    unsafe impl<C> Verify<C> for ArchivedBigUint
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            if self.limbs.last().is_some_and(|limb| limb.to_native() == 0) {
+                fail!(InvalidBigInt::TrailingZeroLimb);
+            }
+            Ok(())
+        }
+    }
+
+    unsafe impl<C> Verify<C> for ArchivedBigInt
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            if self.magnitude.is_zero() != (self.sign == ArchivedSign::NoSign) {
+                fail!(InvalidBigInt::SignMismatch);
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use rancor::Fallible;
+
+    use super::{ArchivedBigInt, ArchivedSign, BigIntResolver};
+    use crate::{
+        alloc::{format, vec, vec::Vec},
+        api::test::to_archived,
+        ser::{Allocator, Writer},
+        Archive, Place, Serialize,
+    };
+
+    struct TestBigInt {
+        negative: bool,
+        limbs: Vec<u64>,
+    }
+
+    impl Archive for TestBigInt {
+        type Archived = ArchivedBigInt;
+        type Resolver = BigIntResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            ArchivedBigInt::resolve_from_resolver(self.negative, resolver, out);
+        }
+    }
+
+    impl<S> Serialize<S> for TestBigInt
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedBigInt::serialize_from_limbs(&self.limbs, serializer)
+        }
+    }
+
+    fn with_archived(
+        negative: bool,
+        limbs: Vec<u64>,
+        f: impl FnOnce(&ArchivedBigInt),
+    ) {
+        to_archived(&TestBigInt { negative, limbs }, |archived| f(&archived));
+    }
+
+    #[test]
+    fn big_int_conversions() {
+        with_archived(true, vec![0, 0], |value| {
+            assert_eq!(value.sign(), ArchivedSign::NoSign);
+            assert!(value.magnitude().limbs().is_empty());
+            assert_eq!(value.to_native_checked::<u8>(), Some(0));
+        });
+        with_archived(true, vec![5], |value| {
+            assert_eq!(value.to_native_checked::<i8>(), Some(-5));
+            assert_eq!(value.to_native_checked::<u8>(), None);
+        });
+        with_archived(false, vec![0, 1, 0], |value| {
+            assert_eq!(value.magnitude().bits(), 65);
+            assert_eq!(value.to_native_checked::<u128>(), Some(1 << 64));
+            assert_eq!(value.to_native_checked::<u64>(), None);
+            assert_eq!(format!("{value:?}"), "10000000000000000");
+        });
+        with_archived(false, vec![1, 2, 3], |value| {
+            assert_eq!(value.to_native_checked::<u128>(), None);
+        });
+    }
+
+    #[test]
+    fn big_int_ordering() {
+        let values = [
+            (true, vec![0, 1]),
+            (true, vec![2]),
+            (false, vec![]),
+            (false, vec![u64::MAX]),
+            (false, vec![0, 1]),
+        ];
+        for (i, (a_negative, a_limbs)) in values.iter().enumerate() {
+            with_archived(*a_negative, a_limbs.clone(), |a| {
+                for (j, (b_negative, b_limbs)) in values.iter().enumerate() {
+                    with_archived(*b_negative, b_limbs.clone(), |b| {
+                        assert_eq!(a.cmp(b), i.cmp(&j));
+                    });
+                }
+            });
+        }
+    }
+}
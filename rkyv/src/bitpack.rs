@@ -0,0 +1,226 @@
+//! Bit-packed struct fields.
+//!
+//! Fields of a struct marked with `#[rkyv(with = BitPack, bits = ..)]` are
+//! packed together into a single byte array in the archived struct instead of
+//! each taking up at least one byte. The derive generates an accessor method on
+//! the archived struct for each packed field which returns its value.
+//!
+//! Fields are packed in declaration order starting from the least significant
+//! bit of the first byte. Signed integers are stored in two's complement and
+//! sign-extended when they are read.
+//!
+//! See [`BitPack`](crate::with::BitPack) for an example.
+
+use core::{error::Error, fmt};
+
+use rancor::{fail, Source};
+
+use crate::with::BitPack;
+
+/// A type which can be packed into a fixed number of bits.
+pub trait BitPackable: Copy {
+    /// The maximum number of bits the type can be packed into.
+    const BITS: u32;
+
+    /// Returns whether the value can be packed into `width` bits.
+    fn fits(self, width: u32) -> bool;
+
+    /// Returns the bits of the value, sign-extended to 64 bits for signed
+    /// integers.
+    fn to_bits(self) -> u64;
+
+    /// Returns the value stored in the low `width` bits of `bits`.
+    fn from_bits(bits: u64, width: u32) -> Self;
+}
+
+impl BitPackable for bool {
+    const BITS: u32 = 1;
+
+    #[inline]
+    fn fits(self, width: u32) -> bool {
+        width >= 1
+    }
+
+    #[inline]
+    fn to_bits(self) -> u64 {
+        self as u64
+    }
+
+    #[inline]
+    fn from_bits(bits: u64, _: u32) -> Self {
+        bits & 1 != 0
+    }
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl BitPackable for $ty {
+                const BITS: u32 = <$ty>::BITS;
+
+                #[inline]
+                fn fits(self, width: u32) -> bool {
+                    width >= Self::BITS || self >> width == 0
+                }
+
+                #[inline]
+                fn to_bits(self) -> u64 {
+                    self as u64
+                }
+
+                #[inline]
+                fn from_bits(bits: u64, width: u32) -> Self {
+                    (bits & mask(width)) as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64);
+
+macro_rules! impl_signed {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl BitPackable for $ty {
+                const BITS: u32 = <$ty>::BITS;
+
+                #[inline]
+                fn fits(self, width: u32) -> bool {
+                    if width >= Self::BITS {
+                        return true;
+                    }
+                    let shift = Self::BITS - width;
+                    (self << shift) >> shift == self
+                }
+
+                #[inline]
+                fn to_bits(self) -> u64 {
+                    self as i64 as u64
+                }
+
+                #[inline]
+                fn from_bits(bits: u64, width: u32) -> Self {
+                    let shift = 64 - width;
+                    ((bits << shift) as i64 >> shift) as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i8, i16, i32, i64);
+
+#[inline]
+fn mask(width: u32) -> u64 {
+    u64::MAX >> (64 - width)
+}
+
+/// Reads `width` bits starting at bit `offset` of `bytes`.
+///
+/// # Panics
+///
+/// Panics if `width` is not between 1 and 64, or if the bits are out of bounds
+/// of `bytes`.
+#[inline]
+pub fn read_bits(bytes: &[u8], offset: u32, width: u32) -> u64 {
+    assert!((1..=64).contains(&width));
+
+    let mut result = 0u64;
+    let mut read = 0;
+    while read < width {
+        let bit = offset + read;
+        let shift = bit % 8;
+        let count = (8 - shift).min(width - read);
+        let byte = (bytes[(bit / 8) as usize] >> shift) as u64;
+        result |= (byte & mask(count)) << read;
+        read += count;
+    }
+    result
+}
+
+/// Writes the low `width` bits of `bits` starting at bit `offset` of `bytes`.
+///
+/// # Panics
+///
+/// Panics if `width` is not between 1 and 64, or if the bits are out of bounds
+/// of `bytes`.
+#[inline]
+pub fn write_bits(bytes: &mut [u8], offset: u32, width: u32, bits: u64) {
+    assert!((1..=64).contains(&width));
+
+    let mut written = 0;
+    while written < width {
+        let bit = offset + written;
+        let shift = bit % 8;
+        let count = (8 - shift).min(width - written);
+        let byte_mask = (mask(count) as u8) << shift;
+        let value = ((bits >> written) as u8) << shift;
+        let byte = &mut bytes[(bit / 8) as usize];
+        *byte = (*byte & !byte_mask) | (value & byte_mask);
+        written += count;
+    }
+}
+
+#[derive(Debug)]
+struct BitPackOverflow {
+    width: u32,
+}
+
+impl fmt::Display for BitPackOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value does not fit in {} bits", self.width)
+    }
+}
+
+impl Error for BitPackOverflow {}
+
+impl BitPack {
+    /// Returns an error if `value` can't be packed into `width` bits.
+    #[inline]
+    pub fn check_fits<T: BitPackable, E: Source>(
+        value: T,
+        width: u32,
+    ) -> Result<(), E> {
+        if !value.fits(width) {
+            fail!(BitPackOverflow { width });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_bits, write_bits, BitPackable};
+
+    #[test]
+    fn read_write_bits() {
+        let mut bytes = [0u8; 16];
+        let fields = [(0, 1, 1), (1, 3, 5), (4, 12, 0xabc), (16, 64, !0x1234)];
+        for &(offset, width, bits) in fields.iter() {
+            write_bits(&mut bytes, offset, width, bits);
+        }
+        for &(offset, width, bits) in fields.iter() {
+            assert_eq!(read_bits(&bytes, offset, width), bits);
+        }
+
+        write_bits(&mut bytes, 1, 3, 0b010);
+        assert_eq!(read_bits(&bytes, 0, 1), 1);
+        assert_eq!(read_bits(&bytes, 1, 3), 0b010);
+        assert_eq!(read_bits(&bytes, 4, 12), 0xabc);
+    }
+
+    #[test]
+    fn fits_and_sign_extension() {
+        assert!(7u8.fits(3));
+        assert!(!8u8.fits(3));
+        assert!(u64::MAX.fits(64));
+
+        assert!((-4i8).fits(3));
+        assert!(3i8.fits(3));
+        assert!(!4i8.fits(3));
+        assert!(!(-5i8).fits(3));
+        assert_eq!(i8::from_bits((-3i8).to_bits(), 3), -3);
+        assert_eq!(i64::from_bits(i64::MIN.to_bits(), 64), i64::MIN);
+    }
+}
@@ -0,0 +1,72 @@
+//! Archived references to bytes stored outside of the archive.
+//!
+//! Fields archived with the [`ExternalBytes`](crate::with::ExternalBytes)
+//! wrapper aren't written into the archive. Instead, their bytes are written
+//! to a separate blob region and the archive only records where they are
+//! located in it. The blob region is managed by the caller, who can store it
+//! right after the archive, in a separate file, or anywhere else.
+
+use munge::munge;
+
+use crate::{primitive::ArchivedU64, Archive, Place, Portable};
+
+/// The location of some bytes in a blob region.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedBlob {
+    offset: ArchivedU64,
+    len: ArchivedU64,
+}
+
+impl ArchivedBlob {
+    /// Returns the offset of the bytes in the blob region.
+    pub fn offset(&self) -> u64 {
+        self.offset.to_native()
+    }
+
+    /// Returns the number of bytes in the blob.
+    pub fn len(&self) -> u64 {
+        self.len.to_native()
+    }
+
+    /// Returns whether the blob is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes of the blob from the given blob region.
+    ///
+    /// Returns `None` if the blob is not located entirely within `blobs`.
+    pub fn get<'a>(&self, blobs: &'a [u8]) -> Option<&'a [u8]> {
+        let start = usize::try_from(self.offset()).ok()?;
+        let len = usize::try_from(self.len()).ok()?;
+        blobs.get(start..start.checked_add(len)?)
+    }
+
+    /// Resolves an archived blob from the given resolver.
+    pub fn resolve_from_resolver(resolver: BlobResolver, out: Place<Self>) {
+        munge!(let ArchivedBlob { offset, len } = out);
+        resolver.offset.resolve((), offset);
+        resolver.len.resolve((), len);
+    }
+}
+
+/// The resolver for an [`ArchivedBlob`].
+#[derive(Debug)]
+pub struct BlobResolver {
+    offset: u64,
+    len: u64,
+}
+
+impl BlobResolver {
+    /// Creates a new blob resolver from the offset and length of the bytes in
+    /// the blob region.
+    pub fn new(offset: usize, len: usize) -> Self {
+        Self {
+            offset: offset as u64,
+            len: len as u64,
+        }
+    }
+}
@@ -32,6 +32,13 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
         unsafe { &*self.ptr.as_ptr() }
     }
 
+    /// Returns a pointer to the value of this archived box, calculated using
+    /// wrapping methods.
+    #[cfg(feature = "bytecheck")]
+    pub(crate) fn as_ptr_wrapping(&self) -> *const T {
+        self.ptr.as_ptr_wrapping()
+    }
+
     /// Returns a sealed mutable reference to the value of this archived box.
     pub fn get_seal(this: Seal<'_, Self>) -> Seal<'_, T> {
         munge!(let Self { ptr } = this);
@@ -184,9 +191,9 @@ mod verify {
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr = self.ptr.as_ptr_wrapping();
-            context.in_subtree(ptr, |context| unsafe {
-                T::check_bytes(ptr, context)
-            })
+            // SAFETY: `check_bytes` is safe to call on a pointer which is
+            // properly aligned and points to enough bytes for a `T`.
+            unsafe { context.in_subtree_deferred(ptr, T::check_bytes) }
         }
     }
 }
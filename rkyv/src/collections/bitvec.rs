@@ -0,0 +1,318 @@
+//! An archived bit vector.
+
+use core::{
+    fmt, hash,
+    iter::FusedIterator,
+    ops::{Index, Range},
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::ArchivedUsize,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable,
+};
+
+/// An archived vector of bits.
+///
+/// Bits are packed eight to a byte, starting from the least significant bit of
+/// each byte. Any unused bits in the last byte are always zero.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedBitVec {
+    bytes: ArchivedVec<u8>,
+    len: ArchivedUsize,
+}
+
+impl ArchivedBitVec {
+    /// Returns the number of bits in the archived bit vector.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the archived bit vector is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the packed bytes of the archived bit vector.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns the bit at the given index, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len() {
+            Some(self.as_bytes()[index / 8] & (1 << (index % 8)) != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the bits of the archived bit vector.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            bytes: self.as_bytes(),
+            range: 0..self.len(),
+        }
+    }
+
+    /// Returns the number of bits which are set.
+    pub fn count_ones(&self) -> usize {
+        self.as_bytes()
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns the number of bits which are not set.
+    pub fn count_zeros(&self) -> usize {
+        self.len() - self.count_ones()
+    }
+
+    /// Returns the number of set bits before the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the length of the archived bit vector.
+    pub fn rank(&self, index: usize) -> usize {
+        assert!(index <= self.len(), "rank index out of bounds");
+
+        let bytes = self.as_bytes();
+        let full = bytes[..index / 8]
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum::<usize>();
+        let rest = index % 8;
+        if rest == 0 {
+            full
+        } else {
+            let mask = (1u8 << rest) - 1;
+            full + (bytes[index / 8] & mask).count_ones() as usize
+        }
+    }
+
+    /// Returns the index of the `n`th set bit, counting from zero, or `None`
+    /// if fewer than `n + 1` bits are set.
+    pub fn select(&self, n: usize) -> Option<usize> {
+        let mut remaining = n;
+        for (i, &byte) in self.as_bytes().iter().enumerate() {
+            let ones = byte.count_ones() as usize;
+            if remaining < ones {
+                let mut byte = byte;
+                for _ in 0..remaining {
+                    // Clear the lowest set bit
+                    byte &= byte - 1;
+                }
+                return Some(i * 8 + byte.trailing_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        None
+    }
+
+    /// Resolves an archived bit vector from a given length in bits.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: BitVecResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedBitVec { bytes, len: out_len } = out);
+        ArchivedVec::<u8>::resolve_from_len(len.div_ceil(8), resolver.0, bytes);
+        len.resolve((), out_len);
+    }
+
+    /// Serializes an archived bit vector from an iterator of bits.
+    ///
+    /// The length passed to [`resolve_from_len`](Self::resolve_from_len) must
+    /// be the number of bits yielded by the iterator.
+    pub fn serialize_from_iter<I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<BitVecResolver, S::Error>
+    where
+        I: IntoIterator<Item = bool>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        let mut bytes = PackBits {
+            bits: iter.into_iter(),
+        };
+        Ok(BitVecResolver(
+            ArchivedVec::serialize_from_unknown_length_iter(
+                &mut bytes, serializer,
+            )?,
+        ))
+    }
+}
+
+struct PackBits<I> {
+    bits: I,
+}
+
+impl<I: Iterator<Item = bool>> Iterator for PackBits<I> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        let mut byte = u8::from(self.bits.next()?);
+        for i in 1..8 {
+            match self.bits.next() {
+                Some(bit) => byte |= u8::from(bit) << i,
+                None => break,
+            }
+        }
+        Some(byte)
+    }
+}
+
+impl fmt::Debug for ArchivedBitVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter().map(u8::from)).finish()
+    }
+}
+
+impl Eq for ArchivedBitVec {}
+
+impl hash::Hash for ArchivedBitVec {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Index<usize> for ArchivedBitVec {
+    type Output = bool;
+
+    fn index(&self, index: usize) -> &bool {
+        if self.get(index).expect("index out of bounds") {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ArchivedBitVec {
+    type Item = bool;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl PartialEq for ArchivedBitVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl PartialEq<[bool]> for ArchivedBitVec {
+    fn eq(&self, other: &[bool]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+/// An iterator over the bits of an [`ArchivedBitVec`].
+#[derive(Clone)]
+pub struct Iter<'a> {
+    bytes: &'a [u8],
+    range: Range<usize>,
+}
+
+impl Iter<'_> {
+    fn bit(&self, index: usize) -> bool {
+        self.bytes[index / 8] & (1 << (index % 8)) != 0
+    }
+}
+
+impl Iterator for Iter<'_> {
+    type Item = bool;
+
+    fn next(&mut self) -> Option<bool> {
+        self.range.next().map(|index| self.bit(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<bool> {
+        self.range.next_back().map(|index| self.bit(index))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// The resolver for [`ArchivedBitVec`].
+pub struct BitVecResolver(VecResolver);
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::ArchivedBitVec;
+
+    #[derive(Debug)]
+    enum InvalidBitVec {
+        LengthMismatch { len: usize, bytes: usize },
+        UnusedBitsSet,
+    }
+
+    impl fmt::Display for InvalidBitVec {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::LengthMismatch { len, bytes } => write!(
+                    f,
+                    "archived bit vector of length {len} has {bytes} bytes",
+                ),
+                Self::UnusedBitsSet => write!(
+                    f,
+                    "archived bit vector has unused bits set in its last byte",
+                ),
+            }
+        }
+    }
+
+    impl Error for InvalidBitVec {}
+
+    unsafe impl<C> Verify<C> for ArchivedBitVec
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            let bytes = self.as_bytes();
+            if bytes.len() != len.div_ceil(8) {
+                fail!(InvalidBitVec::LengthMismatch {
+                    len,
+                    bytes: bytes.len(),
+                });
+            }
+            let rest = len % 8;
+            if rest != 0 && bytes[bytes.len() - 1] >> rest != 0 {
+                fail!(InvalidBitVec::UnusedBitsSet);
+            }
+            Ok(())
+        }
+    }
+}
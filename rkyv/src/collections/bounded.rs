@@ -0,0 +1,424 @@
+//! Archived vecs and strings with a bounded length which are stored inline.
+//!
+//! These are archived by the [`Bounded`](crate::with::Bounded) wrapper. Their
+//! elements are stored in a fixed-size array next to the length, so they don't
+//! use any relative pointers and always have the same size.
+
+use core::{
+    borrow::Borrow,
+    cmp,
+    error::Error,
+    fmt, hash,
+    mem::MaybeUninit,
+    ops::Deref,
+    ptr::{copy_nonoverlapping, write_bytes},
+    slice, str,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    primitive::ArchivedUsize, seal::Seal, Archive, Place, Portable, Serialize,
+};
+
+#[derive(Debug)]
+struct ExceededBound {
+    len: usize,
+    bound: usize,
+}
+
+impl fmt::Display for ExceededBound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "length {} exceeds the bound of {}", self.len, self.bound,)
+    }
+}
+
+impl Error for ExceededBound {}
+
+fn check_bound<E: Source>(len: usize, bound: usize) -> Result<(), E> {
+    if len > bound {
+        fail!(ExceededBound { len, bound });
+    }
+    Ok(())
+}
+
+/// An archived vec which holds at most `N` elements inline.
+///
+/// Unlike `ArchivedVec`, the elements are stored in the archived vec itself
+/// instead of being pointed to. It always takes up space for `N` elements.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedBoundedVec<T, const N: usize> {
+    len: ArchivedUsize,
+    items: [MaybeUninit<T>; N],
+}
+
+impl<T, const N: usize> ArchivedBoundedVec<T, N> {
+    /// Returns the number of elements in the archived vec.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the archived vec is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum number of elements the archived vec can hold.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Gets the elements of the archived vec as a slice.
+    pub fn as_slice(&self) -> &[T] {
+        // SAFETY: The first `len` elements are always initialized, and `len` is
+        // at most `N`.
+        unsafe { slice::from_raw_parts(self.items.as_ptr().cast(), self.len()) }
+    }
+
+    /// Gets the elements of the archived vec as a sealed mutable slice.
+    pub fn as_slice_seal(this: Seal<'_, Self>) -> Seal<'_, [T]> {
+        let len = this.len();
+        munge!(let Self { items, .. } = this);
+        // SAFETY: The first `len` elements are always initialized, and the
+        // slice remains sealed.
+        let slice = unsafe {
+            slice::from_raw_parts_mut(
+                Seal::unseal_unchecked(items).as_mut_ptr().cast(),
+                len,
+            )
+        };
+        Seal::new(slice)
+    }
+
+    /// Serializes the elements of an archived bounded vec from a given slice.
+    ///
+    /// Returns an error if the slice has more than `N` elements.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<BoundedVecResolver<U::Resolver, N>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + ?Sized,
+        S::Error: Source,
+    {
+        check_bound(slice.len(), N)?;
+
+        let mut resolvers = core::array::from_fn(|_| None);
+        for (value, resolver) in slice.iter().zip(resolvers.iter_mut()) {
+            *resolver = Some(value.serialize(serializer)?);
+        }
+        Ok(BoundedVecResolver { resolvers })
+    }
+
+    /// Resolves an archived bounded vec from a given slice.
+    pub fn resolve_from_slice<U: Archive<Archived = T>>(
+        slice: &[U],
+        resolver: BoundedVecResolver<U::Resolver, N>,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedBoundedVec { len: out_len, items } = out);
+        // SAFETY: `MaybeUninit<T>` has the same layout as `T`.
+        let items = unsafe { items.cast_unchecked::<[T; N]>() };
+
+        let mut len = 0usize;
+        let resolvers = resolver.resolvers.into_iter().map_while(|r| r);
+        for (i, (value, resolver)) in slice.iter().zip(resolvers).enumerate() {
+            // SAFETY: There are at most `N` resolvers, so `i` is less than `N`.
+            let out_item = unsafe { items.index(i) };
+            value.resolve(resolver, out_item);
+            len += 1;
+        }
+        len.resolve((), out_len);
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArchivedBoundedVec<T, N> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArchivedBoundedVec<T, N> {
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArchivedBoundedVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Deref for ArchivedBoundedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArchivedBoundedVec<T, N> {}
+
+impl<T: hash::Hash, const N: usize> hash::Hash for ArchivedBoundedVec<T, N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize>
+    PartialEq<ArchivedBoundedVec<U, M>> for ArchivedBoundedVec<T, N>
+{
+    fn eq(&self, other: &ArchivedBoundedVec<U, M>) -> bool {
+        self.as_slice().eq(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]>
+    for ArchivedBoundedVec<T, N>
+{
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice().eq(other)
+    }
+}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArchivedBoundedVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArchivedBoundedVec<T, N> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+/// The resolver for [`ArchivedBoundedVec`].
+pub struct BoundedVecResolver<R, const N: usize> {
+    resolvers: [Option<R>; N],
+}
+
+/// An archived string which holds at most `N` bytes inline.
+///
+/// Unlike `ArchivedString`, strings of every length up to `N` are stored in
+/// the archived string itself. It always takes up space for `N` bytes.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedBoundedString<const N: usize> {
+    len: ArchivedUsize,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> ArchivedBoundedString<N> {
+    /// Returns the length of the archived string in bytes.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the archived string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the maximum length of the archived string in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Extracts a string slice containing the entire archived string.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: The first `len` bytes are always valid UTF-8.
+        unsafe { str::from_utf8_unchecked(&self.bytes[..self.len()]) }
+    }
+
+    /// Extracts a sealed mutable string slice containing the entire archived
+    /// string.
+    pub fn as_str_seal(this: Seal<'_, Self>) -> Seal<'_, str> {
+        let len = this.len();
+        munge!(let Self { bytes, .. } = this);
+        // SAFETY: The first `len` bytes are always valid UTF-8, and the string
+        // slice remains sealed.
+        let str = unsafe {
+            str::from_utf8_unchecked_mut(
+                &mut Seal::unseal_unchecked(bytes)[..len],
+            )
+        };
+        Seal::new(str)
+    }
+
+    /// Checks that an archived bounded string can be resolved from the given
+    /// `str`.
+    ///
+    /// Returns an error if the string is longer than `N` bytes.
+    pub fn serialize_from_str<S>(
+        value: &str,
+        _: &mut S,
+    ) -> Result<BoundedStringResolver, S::Error>
+    where
+        S: Fallible + ?Sized,
+        S::Error: Source,
+    {
+        check_bound(value.len(), N)?;
+        Ok(BoundedStringResolver { _private: () })
+    }
+
+    /// Resolves an archived bounded string from a given `str`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is longer than `N` bytes.
+    pub fn resolve_from_str(
+        value: &str,
+        _: BoundedStringResolver,
+        out: Place<Self>,
+    ) {
+        assert!(
+            value.len() <= N,
+            "string of length {} exceeds the bound of {}",
+            value.len(),
+            N,
+        );
+
+        munge!(let ArchivedBoundedString { len, bytes } = out);
+        value.len().resolve((), len);
+        // SAFETY: `bytes` is valid for writes of `N` bytes, and we checked
+        // that the string is at most `N` bytes long.
+        unsafe {
+            let ptr = bytes.ptr().cast::<u8>();
+            copy_nonoverlapping(value.as_ptr(), ptr, value.len());
+            write_bytes(ptr.add(value.len()), 0, N - value.len());
+        }
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArchivedBoundedString<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Borrow<str> for ArchivedBoundedString<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArchivedBoundedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for ArchivedBoundedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> Deref for ArchivedBoundedString<N> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> Eq for ArchivedBoundedString<N> {}
+
+impl<const N: usize> hash::Hash for ArchivedBoundedString<N> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl<const N: usize, const M: usize> PartialEq<ArchivedBoundedString<M>>
+    for ArchivedBoundedString<N>
+{
+    fn eq(&self, other: &ArchivedBoundedString<M>) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq<str> for ArchivedBoundedString<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for ArchivedBoundedString<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> PartialOrd for ArchivedBoundedString<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize> Ord for ArchivedBoundedString<N> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+/// The resolver for [`ArchivedBoundedString`].
+pub struct BoundedStringResolver {
+    _private: (),
+}
+
+#[cfg(feature = "bytecheck")]
+const _: () = {
+    use core::ptr::{addr_of, slice_from_raw_parts};
+
+    use crate::bytecheck::CheckBytes;
+
+    unsafe impl<T, C, const N: usize> CheckBytes<C> for ArchivedBoundedVec<T, N>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // SAFETY: `ArchivedUsize` is valid for every bit pattern.
+            let len = unsafe { (*value).len() };
+            check_bound(len, N)?;
+
+            let items = unsafe { addr_of!((*value).items).cast::<T>() };
+            // SAFETY: We checked that the first `len` elements are in bounds.
+            unsafe {
+                <[T]>::check_bytes(slice_from_raw_parts(items, len), context)
+            }
+        }
+    }
+
+    unsafe impl<C, const N: usize> CheckBytes<C> for ArchivedBoundedString<N>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        unsafe fn check_bytes(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<(), C::Error> {
+            // SAFETY: `ArchivedUsize` is valid for every bit pattern.
+            let len = unsafe { (*value).len() };
+            check_bound(len, N)?;
+
+            let bytes = unsafe { addr_of!((*value).bytes).cast::<u8>() };
+            let ptr = ptr_meta::from_raw_parts::<str>(bytes.cast(), len);
+            // SAFETY: We checked that the first `len` bytes are in bounds.
+            unsafe { str::check_bytes(ptr, context) }
+        }
+    }
+};
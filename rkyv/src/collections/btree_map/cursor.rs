@@ -0,0 +1,446 @@
+use core::{
+    borrow::Borrow, cmp::Ordering, fmt, marker::PhantomData, ops::Bound,
+    ptr::addr_of_mut,
+};
+
+use crate::{
+    collections::btree_map::{
+        iter::MAX_HEIGHT, ArchivedBTreeMap, InnerNode, LeafNode, Node, NodeKind,
+    },
+    util::InlineVec,
+    RelPtr,
+};
+
+impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
+    /// Returns a [`Cursor`] pointing at the gap before the smallest key
+    /// greater than the given bound.
+    ///
+    /// Passing `Bound::Included(x)` will return a cursor pointing to the gap
+    /// before the smallest key greater than or equal to `x`. Passing
+    /// `Bound::Excluded(x)` will return a cursor pointing to the gap before the
+    /// smallest key greater than `x`. Passing `Bound::Unbounded` will return a
+    /// cursor pointing to the gap before the smallest key in the map.
+    pub fn lower_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V, E>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+    {
+        let this = (self as *const Self).cast_mut();
+        let mut path = Path::new();
+        if !self.is_empty() {
+            let root = unsafe { addr_of_mut!((*this).root) };
+            match bound {
+                Bound::Included(key) => unsafe { path.seek(root, key, true) },
+                Bound::Excluded(key) => unsafe { path.seek(root, key, false) },
+                Bound::Unbounded => unsafe {
+                    path.descend_first(RelPtr::as_ptr_raw(root))
+                },
+            }
+        }
+
+        Cursor {
+            map: this,
+            path,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a [`Cursor`] pointing at the gap after the greatest key smaller
+    /// than the given bound.
+    ///
+    /// Passing `Bound::Included(x)` will return a cursor pointing to the gap
+    /// after the greatest key smaller than or equal to `x`. Passing
+    /// `Bound::Excluded(x)` will return a cursor pointing to the gap after the
+    /// greatest key smaller than `x`. Passing `Bound::Unbounded` will return a
+    /// cursor pointing to the gap after the greatest key in the map.
+    pub fn upper_bound<Q>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V, E>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+    {
+        let this = (self as *const Self).cast_mut();
+        let mut path = Path::new();
+        if !self.is_empty() {
+            let root = unsafe { addr_of_mut!((*this).root) };
+            match bound {
+                Bound::Included(key) => unsafe { path.seek(root, key, false) },
+                Bound::Excluded(key) => unsafe { path.seek(root, key, true) },
+                Bound::Unbounded => (),
+            }
+        }
+
+        Cursor {
+            map: this,
+            path,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A cursor over an `ArchivedBTreeMap`.
+///
+/// A cursor always points to a gap between two entries of the map, and can
+/// step forward and backward over the entries on either side of it. Like the
+/// gaps of a slice, there are `len + 1` positions: one before the first entry,
+/// one after the last entry, and one between each pair of adjacent entries.
+///
+/// This struct is created by the [`lower_bound`](ArchivedBTreeMap::lower_bound)
+/// and [`upper_bound`](ArchivedBTreeMap::upper_bound) methods on
+/// [`ArchivedBTreeMap`]. See their documentation for more.
+pub struct Cursor<'a, K, V, const E: usize> {
+    map: *mut ArchivedBTreeMap<K, V, E>,
+    // The path to the entry after the gap, or empty if the gap is after the
+    // last entry.
+    path: Path<K, V, E>,
+    _phantom: PhantomData<&'a ArchivedBTreeMap<K, V, E>>,
+}
+
+impl<'a, K, V, const E: usize> Cursor<'a, K, V, E> {
+    /// Advances the cursor to the next gap, returning the entry that it moved
+    /// over.
+    ///
+    /// If the cursor is already at the end of the map then `None` is returned
+    /// and the cursor is not moved.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let (node, i) = self.path.entry()?;
+        unsafe {
+            self.path.advance();
+        }
+        Some(unsafe { entry(node, i) })
+    }
+
+    /// Advances the cursor to the previous gap, returning the entry that it
+    /// moved over.
+    ///
+    /// If the cursor is already at the start of the map then `None` is
+    /// returned and the cursor is not moved.
+    pub fn prev(&mut self) -> Option<(&'a K, &'a V)> {
+        let path = self.prev_path()?;
+        self.path = path;
+        let (node, i) = self.path.entry()?;
+        Some(unsafe { entry(node, i) })
+    }
+
+    /// Returns the entry after the cursor without moving it.
+    ///
+    /// If the cursor is at the end of the map then `None` is returned.
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        let (node, i) = self.path.entry()?;
+        Some(unsafe { entry(node, i) })
+    }
+
+    /// Returns the entry before the cursor without moving it.
+    ///
+    /// If the cursor is at the start of the map then `None` is returned.
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        let path = self.prev_path()?;
+        let (node, i) = path.entry()?;
+        Some(unsafe { entry(node, i) })
+    }
+
+    /// Returns the path to the entry before the cursor, or `None` if the
+    /// cursor is at the start of the map.
+    fn prev_path(&self) -> Option<Path<K, V, E>> {
+        let mut path = self.path.clone();
+        if path.is_empty() {
+            if unsafe { (*self.map).is_empty() } {
+                return None;
+            }
+            let root = unsafe { addr_of_mut!((*self.map).root) };
+            unsafe {
+                path.descend_last(RelPtr::as_ptr_raw(root));
+            }
+            Some(path)
+        } else {
+            unsafe { path.retreat() }.then_some(path)
+        }
+    }
+}
+
+impl<K, V, const E: usize> Clone for Cursor<'_, K, V, E> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            path: self.path.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, const E: usize> fmt::Debug
+    for Cursor<'_, K, V, E>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("prev", &self.peek_prev())
+            .field("next", &self.peek_next())
+            .finish()
+    }
+}
+
+/// # Safety
+///
+/// `node` must point to a valid node and `i` must be the index of one of its
+/// entries.
+unsafe fn entry<'a, K, V, const E: usize>(
+    node: *mut Node<K, V, E>,
+    i: usize,
+) -> (&'a K, &'a V) {
+    let k = unsafe { &*addr_of_mut!((*node).keys[i]).cast::<K>() };
+    let v = unsafe { &*addr_of_mut!((*node).values[i]).cast::<V>() };
+    (k, v)
+}
+
+/// The path from the root of a B-tree to one of its entries.
+///
+/// Every element except the last holds an inner node and the index of the
+/// child which the path descends into, where `E` is the index of the greater
+/// node. The last element holds the node and index of the entry. Conveniently,
+/// the entry which follows the child at index `i` is also at index `i`.
+struct Path<K, V, const E: usize> {
+    stack: InlineVec<(*mut Node<K, V, E>, usize), MAX_HEIGHT>,
+}
+
+impl<K, V, const E: usize> Clone for Path<K, V, E> {
+    fn clone(&self) -> Self {
+        let mut stack = InlineVec::new();
+        stack.extend_from_slice(self.stack.as_slice());
+        Self { stack }
+    }
+}
+
+impl<K, V, const E: usize> Path<K, V, E> {
+    fn new() -> Self {
+        Self {
+            stack: InlineVec::new(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    fn entry(&self) -> Option<(*mut Node<K, V, E>, usize)> {
+        self.stack.as_slice().last().copied()
+    }
+
+    /// # Safety
+    ///
+    /// `node` must point to a valid leaf node.
+    unsafe fn leaf_len(node: *mut Node<K, V, E>) -> usize {
+        let leaf = node.cast::<LeafNode<K, V, E>>();
+        unsafe { (*leaf).len.to_native() as usize }
+    }
+
+    /// Returns the child of an inner node at index `i`, or `None` if it is
+    /// invalid.
+    ///
+    /// # Safety
+    ///
+    /// `node` must point to a valid inner node.
+    unsafe fn child(
+        node: *mut Node<K, V, E>,
+        i: usize,
+    ) -> Option<*mut Node<K, V, E>> {
+        let inner = node.cast::<InnerNode<K, V, E>>();
+        let child = if i < E {
+            unsafe { addr_of_mut!((*inner).lesser_nodes[i]) }
+        } else {
+            unsafe { addr_of_mut!((*inner).greater_node) }
+        };
+        if unsafe { RelPtr::is_invalid_raw(child) } {
+            None
+        } else {
+            Some(unsafe { RelPtr::as_ptr_raw(child) })
+        }
+    }
+
+    /// Extends the path to the first entry of the subtree rooted at `node`.
+    ///
+    /// # Safety
+    ///
+    /// `node` must point to a valid node.
+    unsafe fn descend_first(&mut self, mut node: *mut Node<K, V, E>) {
+        loop {
+            self.stack.push((node, 0));
+            match unsafe { (*node).kind } {
+                NodeKind::Leaf => break,
+                NodeKind::Inner => match unsafe { Self::child(node, 0) } {
+                    Some(child) => node = child,
+                    None => break,
+                },
+            }
+        }
+    }
+
+    /// Extends the path to the last entry of the subtree rooted at `node`.
+    ///
+    /// # Safety
+    ///
+    /// `node` must point to a valid node.
+    unsafe fn descend_last(&mut self, mut node: *mut Node<K, V, E>) {
+        loop {
+            match unsafe { (*node).kind } {
+                NodeKind::Leaf => {
+                    let len = unsafe { Self::leaf_len(node) };
+                    self.stack.push((node, len - 1));
+                    break;
+                }
+                NodeKind::Inner => match unsafe { Self::child(node, E) } {
+                    Some(child) => {
+                        self.stack.push((node, E));
+                        node = child;
+                    }
+                    None => {
+                        self.stack.push((node, E - 1));
+                        break;
+                    }
+                },
+            }
+        }
+    }
+
+    /// Unwinds the path to the first entry after the subtree which was just
+    /// left. If there is none, the path is left empty.
+    fn ascend_next(&mut self) {
+        while let Some((node, i)) = self.stack.pop() {
+            if i < E {
+                self.stack.push((node, i));
+                break;
+            }
+        }
+    }
+
+    /// Unwinds the path to the last entry before the subtree which was just
+    /// left. Returns `false` if there is none.
+    fn ascend_prev(&mut self) -> bool {
+        while let Some((node, i)) = self.stack.pop() {
+            if i > 0 {
+                self.stack.push((node, i - 1));
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves the path to the next entry, or empties it if the path is at the
+    /// last entry.
+    ///
+    /// # Safety
+    ///
+    /// The path must not be empty.
+    unsafe fn advance(&mut self) {
+        let (node, i) = self.stack.pop().unwrap();
+        let next_i = i + 1;
+        match unsafe { (*node).kind } {
+            NodeKind::Leaf => {
+                if next_i < unsafe { Self::leaf_len(node) } {
+                    self.stack.push((node, next_i));
+                } else {
+                    self.ascend_next();
+                }
+            }
+            NodeKind::Inner => match unsafe { Self::child(node, next_i) } {
+                Some(child) => {
+                    self.stack.push((node, next_i));
+                    unsafe {
+                        self.descend_first(child);
+                    }
+                }
+                None if next_i < E => self.stack.push((node, next_i)),
+                None => self.ascend_next(),
+            },
+        }
+    }
+
+    /// Moves the path to the previous entry. Returns `false` if the path is at
+    /// the first entry, in which case the path is left in an unspecified state.
+    ///
+    /// # Safety
+    ///
+    /// The path must not be empty.
+    unsafe fn retreat(&mut self) -> bool {
+        let (node, i) = self.stack.pop().unwrap();
+        match unsafe { (*node).kind } {
+            NodeKind::Leaf => {
+                if i > 0 {
+                    self.stack.push((node, i - 1));
+                    true
+                } else {
+                    self.ascend_prev()
+                }
+            }
+            NodeKind::Inner => match unsafe { Self::child(node, i) } {
+                Some(child) => {
+                    self.stack.push((node, i));
+                    unsafe {
+                        self.descend_last(child);
+                    }
+                    true
+                }
+                None if i > 0 => {
+                    self.stack.push((node, i - 1));
+                    true
+                }
+                None => self.ascend_prev(),
+            },
+        }
+    }
+
+    /// Sets the path to the first entry which is not less than (or, if
+    /// `inclusive` is false, not less than or equal to) `key`. If there is
+    /// none, the path is left empty.
+    ///
+    /// # Safety
+    ///
+    /// `root` must point to a valid relative pointer to a node.
+    unsafe fn seek<Q>(
+        &mut self,
+        root: *mut RelPtr<Node<K, V, E>>,
+        key: &Q,
+        inclusive: bool,
+    ) where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        let is_after = |node: *mut Node<K, V, E>, i: usize| {
+            let k = unsafe { &*addr_of_mut!((*node).keys[i]).cast::<K>() };
+            let ordering = key.cmp(k.borrow());
+            ordering == Ordering::Less
+                || (inclusive && ordering == Ordering::Equal)
+        };
+
+        self.stack.clear();
+        let mut node = unsafe { RelPtr::as_ptr_raw(root) };
+        loop {
+            match unsafe { (*node).kind } {
+                NodeKind::Leaf => {
+                    let len = unsafe { Self::leaf_len(node) };
+                    match (0..len).find(|&i| is_after(node, i)) {
+                        Some(i) => self.stack.push((node, i)),
+                        None => self.ascend_next(),
+                    }
+                    break;
+                }
+                NodeKind::Inner => {
+                    let i = (0..E).find(|&i| is_after(node, i)).unwrap_or(E);
+                    match unsafe { Self::child(node, i) } {
+                        Some(child) => {
+                            self.stack.push((node, i));
+                            node = child;
+                        }
+                        None => {
+                            if i < E {
+                                self.stack.push((node, i));
+                            } else {
+                                self.ascend_next();
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,15 +1,27 @@
-use core::{marker::PhantomData, ptr::addr_of_mut};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+    ptr::addr_of_mut,
+};
 
 use crate::{
-    alloc::vec::Vec,
     collections::btree_map::{
-        entries_to_height, ArchivedBTreeMap, InnerNode, LeafNode, Node,
-        NodeKind,
+        ArchivedBTreeMap, InnerNode, LeafNode, Node, NodeKind,
     },
+    primitive::FixedUsize,
     seal::Seal,
+    util::InlineVec,
     RelPtr,
 };
 
+// Every level of a B-tree has at least twice as many entries as the level
+// above it, so a tree with at most `FixedUsize::MAX` entries can't be taller
+// than this.
+pub(super) const MAX_HEIGHT: usize = FixedUsize::BITS as usize;
+
 impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
     /// Gets an iterator over the entries of the map, sorted by key.
     pub fn iter(&self) -> Iter<'_, K, V, E> {
@@ -55,6 +67,48 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             _phantom: PhantomData,
         }
     }
+
+    /// Gets an iterator over a sub-range of entries in the map, sorted by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or if the start and end of
+    /// the range are equal and both excluded.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, V, E>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+    {
+        let this = (self as *const Self).cast_mut();
+        Range {
+            inner: unsafe { RawRange::new(this, range) },
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Gets a mutable iterator over a sub-range of entries in the map, sorted
+    /// by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or if the start and end of
+    /// the range are equal and both excluded.
+    pub fn range_seal<Q, R>(
+        this: Seal<'_, Self>,
+        range: R,
+    ) -> RangeSeal<'_, K, V, E>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+    {
+        let this = unsafe { Seal::unseal_unchecked(this) as *mut Self };
+        RangeSeal {
+            inner: unsafe { RawRange::new(this, range) },
+            _phantom: PhantomData,
+        }
+    }
 }
 
 /// An iterator over the entires of an `ArchivedBTreeMap`.
@@ -74,11 +128,18 @@ impl<'a, K, V, const E: usize> Iterator for Iter<'a, K, V, E> {
             .next()
             .map(|(k, v)| (unsafe { &*k }, unsafe { &*v }))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
+impl<K, V, const E: usize> ExactSizeIterator for Iter<'_, K, V, E> {}
+impl<K, V, const E: usize> FusedIterator for Iter<'_, K, V, E> {}
+
 /// An iterator over the entires of an `ArchivedBTreeMap`.
 ///
-/// This struct is created by the [`iter_pin`](ArchivedBTreeMap::iter_pin)
+/// This struct is created by the [`iter_seal`](ArchivedBTreeMap::iter_seal)
 /// method on [`ArchivedBTreeMap`]. See its documentation for more.
 pub struct IterSeal<'a, K, V, const E: usize> {
     inner: RawIter<K, V, E>,
@@ -93,8 +154,15 @@ impl<'a, K, V, const E: usize> Iterator for IterSeal<'a, K, V, E> {
             .next()
             .map(|(k, v)| (unsafe { &*k }, Seal::new(unsafe { &mut *v })))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
+impl<K, V, const E: usize> ExactSizeIterator for IterSeal<'_, K, V, E> {}
+impl<K, V, const E: usize> FusedIterator for IterSeal<'_, K, V, E> {}
+
 /// An iterator over the keys of an `ArchivedBTreeMap`.
 ///
 /// This struct is created by the [`keys`](ArchivedBTreeMap::keys) method on
@@ -110,12 +178,19 @@ impl<'a, K, V, const E: usize> Iterator for Keys<'a, K, V, E> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|(k, _)| unsafe { &*k })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
+impl<K, V, const E: usize> ExactSizeIterator for Keys<'_, K, V, E> {}
+impl<K, V, const E: usize> FusedIterator for Keys<'_, K, V, E> {}
+
 /// An iterator over the values of an `ArchivedBTreeMap`.
 ///
-/// This struct is created by the [`values`](ArchivedBTreeMap::keys) method on
-/// [`ArchivedBTreeMap`]. See its documentation for more.
+/// This struct is created by the [`values`](ArchivedBTreeMap::values) method
+/// on [`ArchivedBTreeMap`]. See its documentation for more.
 pub struct Values<'a, K, V, const E: usize> {
     inner: RawIter<K, V, E>,
     _phantom: PhantomData<&'a ArchivedBTreeMap<K, V, E>>,
@@ -127,12 +202,20 @@ impl<'a, K, V, const E: usize> Iterator for Values<'a, K, V, E> {
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next().map(|(_, v)| unsafe { &*v })
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
+impl<K, V, const E: usize> ExactSizeIterator for Values<'_, K, V, E> {}
+impl<K, V, const E: usize> FusedIterator for Values<'_, K, V, E> {}
+
 /// A mutable iterator over the values of an `ArchivedBTreeMap`.
 ///
-/// This struct is created by the [`values_pin`](ArchivedBTreeMap::keys) method
-/// on [`ArchivedBTreeMap`]. See its documentation for more.
+/// This struct is created by the
+/// [`values_seal`](ArchivedBTreeMap::values_seal) method on
+/// [`ArchivedBTreeMap`]. See its documentation for more.
 pub struct ValuesSeal<'a, K, V, const E: usize> {
     inner: RawIter<K, V, E>,
     _phantom: PhantomData<Seal<'a, ArchivedBTreeMap<K, V, E>>>,
@@ -146,49 +229,176 @@ impl<'a, K, V, const E: usize> Iterator for ValuesSeal<'a, K, V, E> {
             .next()
             .map(|(_, v)| Seal::new(unsafe { &mut *v }))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
-struct RawIter<K, V, const E: usize> {
-    remaining: usize,
-    stack: Vec<(*mut Node<K, V, E>, usize)>,
+impl<K, V, const E: usize> ExactSizeIterator for ValuesSeal<'_, K, V, E> {}
+impl<K, V, const E: usize> FusedIterator for ValuesSeal<'_, K, V, E> {}
+
+/// An iterator over a sub-range of entries in an `ArchivedBTreeMap`.
+///
+/// This struct is created by the [`range`](ArchivedBTreeMap::range) method on
+/// [`ArchivedBTreeMap`]. See its documentation for more.
+pub struct Range<'a, K, V, const E: usize> {
+    inner: RawRange<K, V, E>,
+    _phantom: PhantomData<&'a ArchivedBTreeMap<K, V, E>>,
 }
 
-impl<K, V, const E: usize> RawIter<K, V, E> {
-    unsafe fn new(map: *mut ArchivedBTreeMap<K, V, E>) -> Self {
-        let remaining = unsafe { (*map).len.to_native() as usize };
-        let mut stack = Vec::new();
-        if remaining != 0 {
-            stack.reserve(entries_to_height::<E>(remaining) as usize);
-            let mut current =
-                unsafe { RelPtr::as_ptr_raw(addr_of_mut!((*map).root)) };
-            loop {
-                stack.push((current, 0));
-                let kind = unsafe { (*current).kind };
-                match kind {
-                    NodeKind::Inner => {
-                        let inner = current.cast::<InnerNode<K, V, E>>();
-                        let lesser =
-                            unsafe { addr_of_mut!((*inner).lesser_nodes[0]) };
-                        current = unsafe { RelPtr::as_ptr_raw(lesser) };
+impl<'a, K, V, const E: usize> Iterator for Range<'a, K, V, E> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(k, v)| (unsafe { &*k }, unsafe { &*v }))
+    }
+}
+
+impl<K, V, const E: usize> FusedIterator for Range<'_, K, V, E> {}
+
+/// A mutable iterator over a sub-range of entries in an `ArchivedBTreeMap`.
+///
+/// This struct is created by the
+/// [`range_seal`](ArchivedBTreeMap::range_seal) method on
+/// [`ArchivedBTreeMap`]. See its documentation for more.
+pub struct RangeSeal<'a, K, V, const E: usize> {
+    inner: RawRange<K, V, E>,
+    _phantom: PhantomData<Seal<'a, ArchivedBTreeMap<K, V, E>>>,
+}
+
+impl<'a, K, V, const E: usize> Iterator for RangeSeal<'a, K, V, E> {
+    type Item = (&'a K, Seal<'a, V>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(k, v)| (unsafe { &*k }, Seal::new(unsafe { &mut *v })))
+    }
+}
+
+impl<K, V, const E: usize> FusedIterator for RangeSeal<'_, K, V, E> {}
+
+/// An in-order cursor over the nodes of a B-tree.
+///
+/// Each stack entry holds a node and the index of the next entry to yield from
+/// it. The stack never holds more than one entry per level of the tree, so it
+/// fits in a fixed-size inline buffer.
+struct Cursor<K, V, const E: usize> {
+    stack: InlineVec<(*mut Node<K, V, E>, usize), MAX_HEIGHT>,
+}
+
+impl<K, V, const E: usize> Cursor<K, V, E> {
+    fn new() -> Self {
+        Self {
+            stack: InlineVec::new(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `node` must point to a valid relative pointer to a node.
+    unsafe fn descend_first(&mut self, mut node: *mut RelPtr<Node<K, V, E>>) {
+        loop {
+            if unsafe { RelPtr::is_invalid_raw(node) } {
+                break;
+            }
+
+            let current = unsafe { RelPtr::as_ptr_raw(node) };
+            self.stack.push((current, 0));
+            let kind = unsafe { (*current).kind };
+            match kind {
+                NodeKind::Inner => {
+                    let inner = current.cast::<InnerNode<K, V, E>>();
+                    node = unsafe { addr_of_mut!((*inner).lesser_nodes[0]) };
+                }
+                NodeKind::Leaf => break,
+            }
+        }
+    }
+
+    /// Positions the cursor on the first entry which is not less than (or, if
+    /// `inclusive` is false, not less than or equal to) `key`.
+    ///
+    /// # Safety
+    ///
+    /// `node` must point to a valid relative pointer to a node.
+    unsafe fn descend_bound<Q>(
+        &mut self,
+        mut node: *mut RelPtr<Node<K, V, E>>,
+        key: &Q,
+        inclusive: bool,
+    ) where
+        Q: Ord + ?Sized,
+        K: Borrow<Q>,
+    {
+        let is_after = |k: *mut K| {
+            let ordering = key.cmp(unsafe { (*k).borrow() });
+            ordering == Ordering::Less
+                || (inclusive && ordering == Ordering::Equal)
+        };
+
+        loop {
+            if unsafe { RelPtr::is_invalid_raw(node) } {
+                break;
+            }
+
+            let current = unsafe { RelPtr::as_ptr_raw(node) };
+            let kind = unsafe { (*current).kind };
+            match kind {
+                NodeKind::Inner => {
+                    let inner = current.cast::<InnerNode<K, V, E>>();
+                    let i = (0..E)
+                        .find(|&i| is_after(Self::key_raw(current, i)))
+                        .unwrap_or(E);
+                    if i < E {
+                        self.stack.push((current, i));
+                        node =
+                            unsafe { addr_of_mut!((*inner).lesser_nodes[i]) };
+                    } else {
+                        node = unsafe { addr_of_mut!((*inner).greater_node) };
                     }
-                    NodeKind::Leaf => break,
+                }
+                NodeKind::Leaf => {
+                    let len = unsafe { Self::leaf_len(current) };
+                    if let Some(i) =
+                        (0..len).find(|&i| is_after(Self::key_raw(current, i)))
+                    {
+                        self.stack.push((current, i));
+                    }
+                    break;
                 }
             }
         }
+    }
 
-        Self { remaining, stack }
+    fn key_raw(node: *mut Node<K, V, E>, i: usize) -> *mut K {
+        unsafe { addr_of_mut!((*node).keys[i]).cast::<K>() }
     }
-}
 
-impl<K, V, const E: usize> Iterator for RawIter<K, V, E> {
-    type Item = (*mut K, *mut V);
+    fn value_raw(node: *mut Node<K, V, E>, i: usize) -> *mut V {
+        unsafe { addr_of_mut!((*node).values[i]).cast::<V>() }
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// # Safety
+    ///
+    /// `node` must point to a valid leaf node.
+    unsafe fn leaf_len(node: *mut Node<K, V, E>) -> usize {
+        let leaf = node.cast::<LeafNode<K, V, E>>();
+        unsafe { (*leaf).len.to_native() as usize }
+    }
+
+    fn peek(&self) -> Option<(*mut Node<K, V, E>, usize)> {
+        self.stack.as_slice().last().copied()
+    }
+
+    fn next(&mut self) -> Option<(*mut K, *mut V)> {
         let (current, i) = self.stack.pop()?;
-        self.remaining -= 1;
 
-        let k = unsafe { addr_of_mut!((*current).keys[i]).cast::<K>() };
-        let v = unsafe { addr_of_mut!((*current).values[i]).cast::<V>() };
+        let k = Self::key_raw(current, i);
+        let v = Self::value_raw(current, i);
         let next_i = i + 1;
 
         // Advance to the next item
@@ -200,35 +410,21 @@ impl<K, V, const E: usize> Iterator for RawIter<K, V, E> {
                     // More values in the current node
                     self.stack.push((current, next_i));
 
-                    // Recurse to a lesser if valid
-                    let next_lesser =
-                        unsafe { addr_of_mut!((*inner).lesser_nodes[next_i]) };
-                    let next_lesser_is_invalid =
-                        unsafe { RelPtr::is_invalid_raw(next_lesser) };
-                    if !next_lesser_is_invalid {
-                        self.stack.push((
-                            unsafe { RelPtr::as_ptr_raw(next_lesser).cast() },
-                            0,
+                    // Recurse to the first entry of the next lesser node
+                    unsafe {
+                        self.descend_first(addr_of_mut!(
+                            (*inner).lesser_nodes[next_i]
                         ));
                     }
                 } else {
-                    // Recurse to a greater if valid
-                    let next_greater =
-                        unsafe { addr_of_mut!((*inner).greater_node) };
-                    let next_greater_is_invalid =
-                        unsafe { RelPtr::is_invalid_raw(next_greater) };
-                    if !next_greater_is_invalid {
-                        self.stack.push((
-                            unsafe { RelPtr::as_ptr_raw(next_greater).cast() },
-                            0,
-                        ));
+                    // Recurse to the first entry of the greater node
+                    unsafe {
+                        self.descend_first(addr_of_mut!((*inner).greater_node));
                     }
                 }
             }
             NodeKind::Leaf => {
-                let leaf = current.cast::<LeafNode<K, V, E>>();
-                let len = unsafe { (*leaf).len.to_native() as usize };
-                if next_i < len {
+                if next_i < unsafe { Self::leaf_len(current) } {
                     self.stack.push((current, next_i));
                 }
             }
@@ -237,3 +433,112 @@ impl<K, V, const E: usize> Iterator for RawIter<K, V, E> {
         Some((k, v))
     }
 }
+
+struct RawIter<K, V, const E: usize> {
+    remaining: usize,
+    cursor: Cursor<K, V, E>,
+}
+
+impl<K, V, const E: usize> RawIter<K, V, E> {
+    unsafe fn new(map: *mut ArchivedBTreeMap<K, V, E>) -> Self {
+        let remaining = unsafe { (*map).len.to_native() as usize };
+        let mut cursor = Cursor::new();
+        if remaining != 0 {
+            unsafe {
+                cursor.descend_first(addr_of_mut!((*map).root));
+            }
+        }
+
+        Self { remaining, cursor }
+    }
+}
+
+impl<K, V, const E: usize> Iterator for RawIter<K, V, E> {
+    type Item = (*mut K, *mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.cursor.next()?;
+        self.remaining -= 1;
+        Some(result)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+struct RawRange<K, V, const E: usize> {
+    cursor: Cursor<K, V, E>,
+    // The position of the first entry which is past the end of the range, if
+    // any. Since the range bounds may not outlive the call to `range`, the end
+    // is located eagerly and compared by position.
+    end: Option<(*mut Node<K, V, E>, usize)>,
+}
+
+impl<K, V, const E: usize> RawRange<K, V, E> {
+    unsafe fn new<Q, R>(map: *mut ArchivedBTreeMap<K, V, E>, range: R) -> Self
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+    {
+        match (range.start_bound(), range.end_bound()) {
+            (Bound::Excluded(s), Bound::Excluded(e)) if s == e => {
+                panic!("range start and end are equal and excluded in BTreeMap")
+            }
+            (
+                Bound::Included(s) | Bound::Excluded(s),
+                Bound::Included(e) | Bound::Excluded(e),
+            ) if s > e => {
+                panic!("range start is greater than range end in BTreeMap")
+            }
+            _ => (),
+        }
+
+        let mut cursor = Cursor::new();
+        let mut end_cursor = Cursor::new();
+        let is_empty = unsafe { (*map).len.to_native() == 0 };
+        if !is_empty {
+            let root = unsafe { addr_of_mut!((*map).root) };
+            unsafe {
+                match range.start_bound() {
+                    Bound::Included(key) => {
+                        cursor.descend_bound(root, key, true)
+                    }
+                    Bound::Excluded(key) => {
+                        cursor.descend_bound(root, key, false)
+                    }
+                    Bound::Unbounded => cursor.descend_first(root),
+                }
+            }
+
+            unsafe {
+                match range.end_bound() {
+                    Bound::Included(key) => {
+                        end_cursor.descend_bound(root, key, false)
+                    }
+                    Bound::Excluded(key) => {
+                        end_cursor.descend_bound(root, key, true)
+                    }
+                    Bound::Unbounded => (),
+                }
+            }
+        }
+
+        Self {
+            cursor,
+            end: end_cursor.peek(),
+        }
+    }
+}
+
+impl<K, V, const E: usize> Iterator for RawRange<K, V, E> {
+    type Item = (*mut K, *mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if Some(self.cursor.peek()?) == self.end {
+            return None;
+        }
+        self.cursor.next()
+    }
+}
@@ -0,0 +1,202 @@
+use core::{cmp::Ordering, iter::FusedIterator, ops::Bound};
+
+use crate::collections::btree_map::{ArchivedBTreeMap, Cursor, Iter};
+
+impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
+    /// Gets an iterator which merges the entries of this map with the entries
+    /// of another map, sorted by key.
+    ///
+    /// Keys which are present in both maps are yielded once as
+    /// [`MergedEntry::Both`].
+    pub fn iter_merged<'a, const E2: usize>(
+        &'a self,
+        other: &'a ArchivedBTreeMap<K, V, E2>,
+    ) -> Merged<'a, K, V, E, E2>
+    where
+        K: Ord,
+    {
+        Merged {
+            left: self.iter(),
+            right: other.iter(),
+            peeked_left: None,
+            peeked_right: None,
+        }
+    }
+
+    /// Gets an iterator over the entries whose keys are present in both this
+    /// map and another map, sorted by key.
+    ///
+    /// Each item holds the key along with the values from this map and the
+    /// other map. When one map is much smaller than the other, the larger map
+    /// is searched for the next key instead of being stepped through entry by
+    /// entry.
+    pub fn intersection<'a, const E2: usize>(
+        &'a self,
+        other: &'a ArchivedBTreeMap<K, V, E2>,
+    ) -> Intersection<'a, K, V, E, E2>
+    where
+        K: Ord,
+    {
+        Intersection {
+            left_map: self,
+            right_map: other,
+            left: self.lower_bound(Bound::Unbounded),
+            right: other.lower_bound(Bound::Unbounded),
+        }
+    }
+}
+
+/// An entry yielded by a [`Merged`] iterator.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MergedEntry<'a, K, V> {
+    /// The key is only present in the left map.
+    Left(&'a K, &'a V),
+    /// The key is only present in the right map.
+    Right(&'a K, &'a V),
+    /// The key is present in both maps. The key is from the left map.
+    Both(&'a K, &'a V, &'a V),
+}
+
+impl<K, V> Clone for MergedEntry<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for MergedEntry<'_, K, V> {}
+
+impl<'a, K, V> MergedEntry<'a, K, V> {
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &'a K {
+        match self {
+            Self::Left(k, _) | Self::Right(k, _) | Self::Both(k, ..) => k,
+        }
+    }
+
+    /// Returns the value from the left map, if any.
+    pub fn left(&self) -> Option<&'a V> {
+        match self {
+            Self::Left(_, v) | Self::Both(_, v, _) => Some(v),
+            Self::Right(..) => None,
+        }
+    }
+
+    /// Returns the value from the right map, if any.
+    pub fn right(&self) -> Option<&'a V> {
+        match self {
+            Self::Right(_, v) | Self::Both(_, _, v) => Some(v),
+            Self::Left(..) => None,
+        }
+    }
+}
+
+/// An iterator over the merged entries of two `ArchivedBTreeMap`s.
+///
+/// This struct is created by the
+/// [`iter_merged`](ArchivedBTreeMap::iter_merged) method on
+/// [`ArchivedBTreeMap`]. See its documentation for more.
+pub struct Merged<'a, K, V, const E: usize, const E2: usize> {
+    left: Iter<'a, K, V, E>,
+    right: Iter<'a, K, V, E2>,
+    peeked_left: Option<(&'a K, &'a V)>,
+    peeked_right: Option<(&'a K, &'a V)>,
+}
+
+impl<'a, K: Ord, V, const E: usize, const E2: usize> Iterator
+    for Merged<'a, K, V, E, E2>
+{
+    type Item = MergedEntry<'a, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let left = self.peeked_left.take().or_else(|| self.left.next());
+        let right = self.peeked_right.take().or_else(|| self.right.next());
+
+        match (left, right) {
+            (Some((lk, lv)), Some((rk, rv))) => match lk.cmp(rk) {
+                Ordering::Less => {
+                    self.peeked_right = Some((rk, rv));
+                    Some(MergedEntry::Left(lk, lv))
+                }
+                Ordering::Greater => {
+                    self.peeked_left = Some((lk, lv));
+                    Some(MergedEntry::Right(rk, rv))
+                }
+                Ordering::Equal => Some(MergedEntry::Both(lk, lv, rv)),
+            },
+            (Some((lk, lv)), None) => Some(MergedEntry::Left(lk, lv)),
+            (None, Some((rk, rv))) => Some(MergedEntry::Right(rk, rv)),
+            (None, None) => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.left.len() + self.peeked_left.is_some() as usize;
+        let right = self.right.len() + self.peeked_right.is_some() as usize;
+        (left.max(right), Some(left + right))
+    }
+}
+
+impl<K: Ord, V, const E: usize, const E2: usize> FusedIterator
+    for Merged<'_, K, V, E, E2>
+{
+}
+
+/// An iterator over the entries whose keys are present in two
+/// `ArchivedBTreeMap`s.
+///
+/// This struct is created by the
+/// [`intersection`](ArchivedBTreeMap::intersection) method on
+/// [`ArchivedBTreeMap`]. See its documentation for more.
+pub struct Intersection<'a, K, V, const E: usize, const E2: usize> {
+    left_map: &'a ArchivedBTreeMap<K, V, E>,
+    right_map: &'a ArchivedBTreeMap<K, V, E2>,
+    left: Cursor<'a, K, V, E>,
+    right: Cursor<'a, K, V, E2>,
+}
+
+impl<'a, K: Ord, V, const E: usize, const E2: usize> Iterator
+    for Intersection<'a, K, V, E, E2>
+{
+    type Item = (&'a K, &'a V, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (lk, lv) = self.left.peek_next()?;
+            let (rk, rv) = self.right.peek_next()?;
+
+            match lk.cmp(rk) {
+                Ordering::Equal => {
+                    self.left.next();
+                    self.right.next();
+                    return Some((lk, lv, rv));
+                }
+                // Try stepping forward once before falling back to a search,
+                // so that maps with similar keys are walked in linear time.
+                Ordering::Less => {
+                    self.left.next();
+                    if self.left.peek_next().is_some_and(|(k, _)| k < rk) {
+                        self.left =
+                            self.left_map.lower_bound(Bound::Included(rk));
+                    }
+                }
+                Ordering::Greater => {
+                    self.right.next();
+                    if self.right.peek_next().is_some_and(|(k, _)| k < lk) {
+                        self.right =
+                            self.right_map.lower_bound(Bound::Included(lk));
+                    }
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.left_map.len().min(self.right_map.len());
+        (0, Some(len))
+    }
+}
+
+impl<K: Ord, V, const E: usize, const E2: usize> FusedIterator
+    for Intersection<'_, K, V, E, E2>
+{
+}
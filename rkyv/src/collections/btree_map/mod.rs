@@ -24,9 +24,11 @@ use crate::{
     Place, Portable, RelPtr, Serialize,
 };
 
-// TODO(#515): Get Iterator APIs working without the `alloc` feature enabled
-#[cfg(feature = "alloc")]
+mod cursor;
 mod iter;
+mod merge;
+
+pub use self::{cursor::*, iter::*, merge::*};
 
 // B-trees are typically characterized as having a branching factor of B.
 // However, in this implementation our B-trees are characterized as having a
@@ -235,6 +237,49 @@ impl<K, V, const E: usize> ArchivedBTreeMap<K, V, E> {
             .map(|(k, v)| (unsafe { &*k }, Seal::new(unsafe { &mut *v })))
     }
 
+    /// Returns the first key-value pair in the B-tree map, or `None` if the map
+    /// is empty.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// Returns the last key-value pair in the B-tree map, or `None` if the map
+    /// is empty.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let this = (self as *const Self).cast_mut();
+        let root_ptr = unsafe { addr_of_mut!((*this).root) };
+        let mut current = unsafe { RelPtr::as_ptr_raw(root_ptr) };
+        let i = loop {
+            let kind = unsafe { (*current).kind };
+
+            match kind {
+                NodeKind::Leaf => {
+                    let leaf = current.cast::<LeafNode<K, V, E>>();
+                    break unsafe { (*leaf).len.to_native() as usize } - 1;
+                }
+                NodeKind::Inner => {
+                    let inner = current.cast::<InnerNode<K, V, E>>();
+                    let greater =
+                        unsafe { addr_of_mut!((*inner).greater_node) };
+                    let greater_is_invalid =
+                        unsafe { RelPtr::is_invalid_raw(greater) };
+                    if greater_is_invalid {
+                        break E - 1;
+                    }
+                    current = unsafe { RelPtr::as_ptr_raw(greater) };
+                }
+            }
+        };
+
+        let k = unsafe { &*addr_of_mut!((*current).keys[i]).cast::<K>() };
+        let v = unsafe { &*addr_of_mut!((*current).values[i]).cast::<V>() };
+        Some((k, v))
+    }
+
     fn get_key_value_raw<Q>(
         this: *mut Self,
         key: &Q,
@@ -741,8 +786,6 @@ where
     }
 }
 
-// TODO(#515): ungate this impl
-#[cfg(feature = "alloc")]
 impl<K, V, const E: usize> Eq for ArchivedBTreeMap<K, V, E>
 where
     K: PartialEq,
@@ -762,8 +805,6 @@ where
     }
 }
 
-// TODO(#515): ungate this impl
-#[cfg(feature = "alloc")]
 impl<K, V, const E1: usize, const E2: usize>
     PartialEq<ArchivedBTreeMap<K, V, E2>> for ArchivedBTreeMap<K, V, E1>
 where
@@ -799,7 +840,7 @@ mod verify {
     use bytecheck::{CheckBytes, Verify};
     use rancor::{fail, Fallible, Source};
 
-    use super::{ArchivedBTreeMap, InnerNode, Node};
+    use super::{iter::MAX_HEIGHT, ArchivedBTreeMap, InnerNode, Node};
     use crate::{
         collections::btree_map::{LeafNode, NodeKind},
         validation::{ArchiveContext, ArchiveContextExt as _},
@@ -825,6 +866,23 @@ mod verify {
 
     impl Error for InvalidLength {}
 
+    #[derive(Debug)]
+    struct TreeTooTall {
+        maximum: usize,
+    }
+
+    impl fmt::Display for TreeTooTall {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "B-tree was taller than the maximum height of {}",
+                self.maximum,
+            )
+        }
+    }
+
+    impl Error for TreeTooTall {}
+
     unsafe impl<C, K, V, const E: usize> Verify<C> for ArchivedBTreeMap<K, V, E>
     where
         C: Fallible + ArchiveContext + ?Sized,
@@ -839,12 +897,18 @@ mod verify {
                 return Ok(());
             }
 
-            check_node_rel_ptr::<C, K, V, E>(&self.root, context)
+            check_node_rel_ptr::<C, K, V, E>(&self.root, 1, context)
         }
     }
 
+    /// Checks the node that `node_rel_ptr` points to, which is `height` levels
+    /// from the top of the tree.
+    ///
+    /// The iterators and cursors keep a fixed-size stack with an entry for each
+    /// level of the tree, so trees taller than `MAX_HEIGHT` are rejected.
     fn check_node_rel_ptr<C, K, V, const E: usize>(
         node_rel_ptr: &RelPtr<Node<K, V, E>>,
+        height: usize,
         context: &mut C,
     ) -> Result<(), C::Error>
     where
@@ -853,6 +917,12 @@ mod verify {
         K: CheckBytes<C>,
         V: CheckBytes<C>,
     {
+        if height > MAX_HEIGHT {
+            fail!(TreeTooTall {
+                maximum: MAX_HEIGHT
+            });
+        }
+
         let node_ptr = node_rel_ptr.as_ptr_wrapping().cast::<Node<K, V, E>>();
         context.check_subtree_ptr(
             node_ptr.cast::<u8>(),
@@ -886,7 +956,11 @@ mod verify {
                 // We checked to make sure that `node_ptr` is properly aligned
                 // and dereferenceable.
                 unsafe {
-                    check_inner_node::<C, K, V, E>(node_ptr.cast(), context)?
+                    check_inner_node::<C, K, V, E>(
+                        node_ptr.cast(),
+                        height,
+                        context,
+                    )?
                 }
             }
         }
@@ -908,38 +982,58 @@ mod verify {
         K: CheckBytes<C>,
         V: CheckBytes<C>,
     {
-        context.in_subtree(node_ptr, |context| {
-            // SAFETY: We checked to make sure that `node_ptr` is properly
-            // aligned and dereferenceable by calling
-            // `check_subtree_ptr`.
-            let len_ptr = unsafe { addr_of!((*node_ptr).len) };
-            // SAFETY: `len_ptr` is a pointer to a subfield of `node_ptr` and so
-            // is also properly aligned and dereferenceable.
-            unsafe {
-                CheckBytes::check_bytes(len_ptr, context)?;
-            }
-            // SAFETY: `len_ptr` was always properly aligned and
-            // dereferenceable, and we just checked to make sure it
-            // pointed to a valid `ArchivedUsize`.
-            let len = unsafe { &*len_ptr };
-            let len = len.to_native() as usize;
-            if len > E {
-                fail!(InvalidLength { len, maximum: E });
-            }
+        // SAFETY: `check_leaf_node_contents` is safe to call on a pointer
+        // which is properly aligned and dereferenceable.
+        unsafe {
+            context.in_subtree_deferred(
+                node_ptr,
+                check_leaf_node_contents::<C, K, V, E>,
+            )
+        }
+    }
 
-            // SAFETY: We checked that `node_ptr` is properly-aligned and
-            // dereferenceable.
-            let node_ptr = unsafe { addr_of!((*node_ptr).node) };
-            // SAFETY:
-            // - We checked that `node_ptr` is properly aligned and
-            //   dereferenceable.
-            // - We checked that `len` is less than or equal to `E`.
-            unsafe {
-                check_node_entries(node_ptr, len, context)?;
-            }
+    /// # Safety
+    ///
+    /// `node_ptr` must be properly aligned and dereferenceable.
+    unsafe fn check_leaf_node_contents<C, K, V, const E: usize>(
+        node_ptr: *const LeafNode<K, V, E>,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+        K: CheckBytes<C>,
+        V: CheckBytes<C>,
+    {
+        // SAFETY: The caller has guaranteed that `node_ptr` is properly
+        // aligned and dereferenceable.
+        let len_ptr = unsafe { addr_of!((*node_ptr).len) };
+        // SAFETY: `len_ptr` is a pointer to a subfield of `node_ptr` and so
+        // is also properly aligned and dereferenceable.
+        unsafe {
+            CheckBytes::check_bytes(len_ptr, context)?;
+        }
+        // SAFETY: `len_ptr` was always properly aligned and
+        // dereferenceable, and we just checked to make sure it
+        // pointed to a valid `ArchivedUsize`.
+        let len = unsafe { &*len_ptr };
+        let len = len.to_native() as usize;
+        if len > E {
+            fail!(InvalidLength { len, maximum: E });
+        }
 
-            Ok(())
-        })
+        // SAFETY: We checked that `node_ptr` is properly-aligned and
+        // dereferenceable.
+        let node_ptr = unsafe { addr_of!((*node_ptr).node) };
+        // SAFETY:
+        // - We checked that `node_ptr` is properly aligned and
+        //   dereferenceable.
+        // - We checked that `len` is less than or equal to `E`.
+        unsafe {
+            check_node_entries(node_ptr, len, context)?;
+        }
+
+        Ok(())
     }
 
     /// # Safety
@@ -980,10 +1074,10 @@ mod verify {
 
     /// # Safety
     ///
-    /// - `node_ptr` must be properly aligned and dereferenceable.
-    /// - `len` must be less than or equal to `E`.
+    /// `node_ptr` must be properly aligned and dereferenceable.
     unsafe fn check_inner_node<C, K, V, const E: usize>(
         node_ptr: *const InnerNode<K, V, E>,
+        height: usize,
         context: &mut C,
     ) -> Result<(), C::Error>
     where
@@ -992,55 +1086,165 @@ mod verify {
         K: CheckBytes<C>,
         V: CheckBytes<C>,
     {
+        // Inner nodes aren't deferred because the height of the tree is
+        // already bounded by `MAX_HEIGHT`.
         context.in_subtree(node_ptr, |context| {
-            for i in 0..E {
-                // SAFETY: `in_subtree` guarantees that `node_ptr` is properly
-                // aligned and dereferenceable.
-                let lesser_node_ptr =
-                    unsafe { addr_of!((*node_ptr).lesser_nodes[i]) };
-                // SAFETY: `lesser_node_ptr` is a subfield of an inner node, and
-                // so is guaranteed to be properly aligned and point to enough
-                // bytes for a `RelPtr`.
-                unsafe {
-                    RelPtr::check_bytes(lesser_node_ptr, context)?;
-                }
-                // SAFETY: We just checked the `lesser_node_ptr` and it
-                // succeeded, so it's safe to dereference.
-                let lesser_node = unsafe { &*lesser_node_ptr };
-                if !lesser_node.is_invalid() {
-                    check_node_rel_ptr::<C, K, V, E>(lesser_node, context)?;
-                }
+            // SAFETY: `in_subtree` checked that `node_ptr` is properly aligned
+            // and dereferenceable.
+            unsafe {
+                check_inner_node_contents::<C, K, V, E>(
+                    node_ptr, height, context,
+                )
             }
-            // SAFETY: We checked that `node_ptr` is properly aligned and
-            // dereferenceable.
-            let greater_node_ptr =
-                unsafe { addr_of!((*node_ptr).greater_node) };
-            // SAFETY: `greater_node_ptr` is a subfield of an inner node, and so
-            // is guaranteed to be properly aligned and point to enough bytes
-            // for a `RelPtr`.
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `node_ptr` must be properly aligned and dereferenceable.
+    unsafe fn check_inner_node_contents<C, K, V, const E: usize>(
+        node_ptr: *const InnerNode<K, V, E>,
+        height: usize,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+        K: CheckBytes<C>,
+        V: CheckBytes<C>,
+    {
+        for i in 0..E {
+            // SAFETY: The caller has guaranteed that `node_ptr` is properly
+            // aligned and dereferenceable.
+            let lesser_node_ptr =
+                unsafe { addr_of!((*node_ptr).lesser_nodes[i]) };
+            // SAFETY: `lesser_node_ptr` is a subfield of an inner node, and
+            // so is guaranteed to be properly aligned and point to enough
+            // bytes for a `RelPtr`.
             unsafe {
-                RelPtr::check_bytes(greater_node_ptr, context)?;
+                RelPtr::check_bytes(lesser_node_ptr, context)?;
             }
-            // SAFETY: We just checked the `greater_node_ptr` and it succeeded,
-            // so it's safe to dereference.
-            let greater_node = unsafe { &*greater_node_ptr };
-            if !greater_node.is_invalid() {
-                check_node_rel_ptr::<C, K, V, E>(greater_node, context)?;
+            // SAFETY: We just checked the `lesser_node_ptr` and it
+            // succeeded, so it's safe to dereference.
+            let lesser_node = unsafe { &*lesser_node_ptr };
+            if !lesser_node.is_invalid() {
+                check_node_rel_ptr::<C, K, V, E>(
+                    lesser_node,
+                    height + 1,
+                    context,
+                )?;
             }
+        }
+        // SAFETY: We checked that `node_ptr` is properly aligned and
+        // dereferenceable.
+        let greater_node_ptr = unsafe { addr_of!((*node_ptr).greater_node) };
+        // SAFETY: `greater_node_ptr` is a subfield of an inner node, and so
+        // is guaranteed to be properly aligned and point to enough bytes
+        // for a `RelPtr`.
+        unsafe {
+            RelPtr::check_bytes(greater_node_ptr, context)?;
+        }
+        // SAFETY: We just checked the `greater_node_ptr` and it succeeded,
+        // so it's safe to dereference.
+        let greater_node = unsafe { &*greater_node_ptr };
+        if !greater_node.is_invalid() {
+            check_node_rel_ptr::<C, K, V, E>(
+                greater_node,
+                height + 1,
+                context,
+            )?;
+        }
 
-            // SAFETY: We checked that `node_ptr` is properly aligned and
-            // dereferenceable.
-            let node_ptr = unsafe { addr_of!((*node_ptr).node) };
-            // SAFETY:
-            // - The caller has guaranteed that `node_ptr` points to a valid
-            //   `Node<K, V, E>`.
-            // - All inner nodes have `E` items, and `E` is less than or equal
-            //   to `E`.
-            unsafe {
-                check_node_entries::<C, K, V, E>(node_ptr, E, context)?;
+        // SAFETY: We checked that `node_ptr` is properly aligned and
+        // dereferenceable.
+        let node_ptr = unsafe { addr_of!((*node_ptr).node) };
+        // SAFETY:
+        // - The caller has guaranteed that `node_ptr` points to a valid
+        //   `Node<K, V, E>`.
+        // - All inner nodes have `E` items, and `E` is less than or equal
+        //   to `E`.
+        unsafe {
+            check_node_entries::<C, K, V, E>(node_ptr, E, context)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use core::mem::size_of;
+
+    use munge::munge;
+    use rancor::Error;
+
+    use super::{
+        iter::MAX_HEIGHT, ArchivedBTreeMap, InnerNode, Node, NodeKind,
+    };
+    use crate::{
+        access,
+        primitive::{ArchivedU32, ArchivedUsize, FixedUsize},
+        util::AlignedVec,
+        Place, RelPtr,
+    };
+
+    type Map = ArchivedBTreeMap<ArchivedU32, ArchivedU32>;
+    type Inner = InnerNode<ArchivedU32, ArchivedU32, 5>;
+
+    /// Writes a map whose root is a chain of `height` inner nodes.
+    fn inner_node_chain(height: usize) -> AlignedVec {
+        let node_size = size_of::<Inner>();
+        let root_pos = height * node_size;
+        let mut bytes = AlignedVec::new();
+        bytes.resize(root_pos + size_of::<Map>(), 0);
+
+        for i in 0..height {
+            let pos = i * node_size;
+            // SAFETY: `pos` is in bounds and aligned for an inner node.
+            let out = unsafe {
+                Place::new_unchecked(
+                    pos,
+                    bytes.as_mut_ptr().add(pos).cast::<Inner>(),
+                )
+            };
+            munge! {
+                let InnerNode {
+                    node: Node { kind, .. },
+                    lesser_nodes,
+                    greater_node,
+                } = out;
+            }
+            kind.write(NodeKind::Inner);
+            for j in 0..5 {
+                // SAFETY: `j` is in bounds for the lesser nodes.
+                RelPtr::emplace_invalid(unsafe { lesser_nodes.index(j) });
+            }
+            if i == 0 {
+                RelPtr::emplace_invalid(greater_node);
+            } else {
+                RelPtr::emplace(pos - node_size, greater_node);
             }
+        }
 
-            Ok(())
-        })
+        // SAFETY: `root_pos` is in bounds and aligned for the map.
+        let out = unsafe {
+            Place::new_unchecked(
+                root_pos,
+                bytes.as_mut_ptr().add(root_pos).cast::<Map>(),
+            )
+        };
+        munge!(let ArchivedBTreeMap { root, len, _phantom: _ } = out);
+        RelPtr::emplace(root_pos - node_size, root);
+        len.write(ArchivedUsize::from_native((5 * height) as FixedUsize));
+
+        bytes
+    }
+
+    #[test]
+    fn reject_too_tall() {
+        access::<Map, Error>(&inner_node_chain(MAX_HEIGHT)).unwrap();
+        let error = access::<Map, Error>(&inner_node_chain(MAX_HEIGHT + 1))
+            .expect_err("trees taller than the iterator stack must fail");
+        assert!(error.to_string().contains("taller than the maximum height"));
     }
 }
@@ -1,12 +1,18 @@
 //! [`Archive`](crate::Archive) implementation for B-tree sets.
 
-use core::{borrow::Borrow, fmt, ops::ControlFlow};
+use core::{
+    borrow::Borrow,
+    cmp::Ordering,
+    fmt,
+    iter::{FusedIterator, Peekable},
+    ops::{ControlFlow, RangeBounds},
+};
 
 use munge::munge;
 use rancor::{Fallible, Source};
 
 use crate::{
-    collections::btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+    collections::btree_map::{self, ArchivedBTreeMap, BTreeMapResolver},
     ser::{Allocator, Writer},
     Place, Portable, Serialize,
 };
@@ -44,6 +50,122 @@ impl<K, const E: usize> ArchivedBTreeSet<K, E> {
         self.0.get_key_value(value).map(|(key, _)| key)
     }
 
+    /// Returns `true` if the set contains a value for the specified key.
+    ///
+    /// This is an alias for [`contains_key`](Self::contains_key) which matches
+    /// the name used by the standard library.
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q> + Ord,
+    {
+        self.0.contains_key(key)
+    }
+
+    /// Returns the first key in the set, or `None` if the set is empty.
+    pub fn first(&self) -> Option<&K> {
+        self.0.first_key_value().map(|(key, _)| key)
+    }
+
+    /// Returns the last key in the set, or `None` if the set is empty.
+    pub fn last(&self) -> Option<&K> {
+        self.0.last_key_value().map(|(key, _)| key)
+    }
+
+    /// Gets an iterator over the keys of the set, in sorted order.
+    pub fn iter(&self) -> Iter<'_, K, E> {
+        Iter {
+            inner: self.0.keys(),
+        }
+    }
+
+    /// Gets an iterator over a sub-range of keys in the set, in sorted order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range starts after it ends, or if the start and end of
+    /// the range are equal and both excluded.
+    pub fn range<Q, R>(&self, range: R) -> Range<'_, K, E>
+    where
+        Q: Ord + ?Sized,
+        K: Borrow<Q> + Ord,
+        R: RangeBounds<Q>,
+    {
+        Range {
+            inner: self.0.range(range),
+        }
+    }
+
+    /// Returns an iterator over the keys which are in `self` but not in
+    /// `other`, in sorted order.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, K, E>
+    where
+        K: Ord,
+    {
+        Difference {
+            inner: MergeIter::new(self, other),
+        }
+    }
+
+    /// Returns an iterator over the keys which are in `self` or `other`, but
+    /// not in both, in sorted order.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> SymmetricDifference<'a, K, E>
+    where
+        K: Ord,
+    {
+        SymmetricDifference {
+            inner: MergeIter::new(self, other),
+        }
+    }
+
+    /// Returns an iterator over the keys which are in both `self` and `other`,
+    /// in sorted order.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, K, E>
+    where
+        K: Ord,
+    {
+        Intersection {
+            inner: MergeIter::new(self, other),
+        }
+    }
+
+    /// Returns an iterator over the keys which are in `self` or `other`,
+    /// without duplicates, in sorted order.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, K, E>
+    where
+        K: Ord,
+    {
+        Union {
+            inner: MergeIter::new(self, other),
+        }
+    }
+
+    /// Returns whether `self` and `other` have no keys in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool
+    where
+        K: Ord,
+    {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns whether every key in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool
+    where
+        K: Ord,
+    {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+
+    /// Returns whether every key in `other` is also in `self`.
+    pub fn is_superset(&self, other: &Self) -> bool
+    where
+        K: Ord,
+    {
+        other.is_subset(self)
+    }
+
     /// Returns `true` if the set contains no elements.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
@@ -114,5 +236,188 @@ where
     }
 }
 
+impl<K: PartialEq, const E1: usize, const E2: usize>
+    PartialEq<ArchivedBTreeSet<K, E2>> for ArchivedBTreeSet<K, E1>
+{
+    fn eq(&self, other: &ArchivedBTreeSet<K, E2>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Eq, const E: usize> Eq for ArchivedBTreeSet<K, E> {}
+
+/// An iterator over the keys of an `ArchivedBTreeSet`.
+///
+/// This struct is created by the [`iter`](ArchivedBTreeSet::iter) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct Iter<'a, K, const E: usize> {
+    inner: btree_map::Keys<'a, K, (), E>,
+}
+
+impl<'a, K, const E: usize> Iterator for Iter<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, const E: usize> ExactSizeIterator for Iter<'_, K, E> {}
+impl<K, const E: usize> FusedIterator for Iter<'_, K, E> {}
+
+/// An iterator over a sub-range of keys in an `ArchivedBTreeSet`.
+///
+/// This struct is created by the [`range`](ArchivedBTreeSet::range) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct Range<'a, K, const E: usize> {
+    inner: btree_map::Range<'a, K, (), E>,
+}
+
+impl<'a, K, const E: usize> Iterator for Range<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+}
+
+impl<K, const E: usize> FusedIterator for Range<'_, K, E> {}
+
+/// Merges the keys of two sorted sets, yielding the keys from each set which
+/// are least.
+struct MergeIter<'a, K, const E: usize> {
+    a: Peekable<Iter<'a, K, E>>,
+    b: Peekable<Iter<'a, K, E>>,
+}
+
+impl<'a, K: Ord, const E: usize> MergeIter<'a, K, E> {
+    fn new(
+        a: &'a ArchivedBTreeSet<K, E>,
+        b: &'a ArchivedBTreeSet<K, E>,
+    ) -> Self {
+        Self {
+            a: a.iter().peekable(),
+            b: b.iter().peekable(),
+        }
+    }
+
+    /// Returns the next key from either set. If both sets have the same next
+    /// key, then both keys are returned.
+    fn nexts(&mut self) -> (Option<&'a K>, Option<&'a K>) {
+        let ordering = match (self.a.peek(), self.b.peek()) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => return (None, None),
+        };
+        match ordering {
+            Ordering::Less => (self.a.next(), None),
+            Ordering::Equal => (self.a.next(), self.b.next()),
+            Ordering::Greater => (None, self.b.next()),
+        }
+    }
+}
+
+/// An iterator over the difference of two `ArchivedBTreeSet`s.
+///
+/// This struct is created by the
+/// [`difference`](ArchivedBTreeSet::difference) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct Difference<'a, K, const E: usize> {
+    inner: MergeIter<'a, K, E>,
+}
+
+impl<'a, K: Ord, const E: usize> Iterator for Difference<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.nexts() {
+                (Some(a), None) => return Some(a),
+                (None, None) => return None,
+                (None, Some(_)) if self.inner.a.peek().is_none() => {
+                    return None
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl<K: Ord, const E: usize> FusedIterator for Difference<'_, K, E> {}
+
+/// An iterator over the symmetric difference of two `ArchivedBTreeSet`s.
+///
+/// This struct is created by the
+/// [`symmetric_difference`](ArchivedBTreeSet::symmetric_difference) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct SymmetricDifference<'a, K, const E: usize> {
+    inner: MergeIter<'a, K, E>,
+}
+
+impl<'a, K: Ord, const E: usize> Iterator for SymmetricDifference<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.nexts() {
+                (Some(_), Some(_)) => (),
+                (a, b) => return a.or(b),
+            }
+        }
+    }
+}
+
+impl<K: Ord, const E: usize> FusedIterator for SymmetricDifference<'_, K, E> {}
+
+/// An iterator over the intersection of two `ArchivedBTreeSet`s.
+///
+/// This struct is created by the
+/// [`intersection`](ArchivedBTreeSet::intersection) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct Intersection<'a, K, const E: usize> {
+    inner: MergeIter<'a, K, E>,
+}
+
+impl<'a, K: Ord, const E: usize> Iterator for Intersection<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.inner.a.peek().is_none() || self.inner.b.peek().is_none() {
+                return None;
+            }
+            if let (Some(a), Some(_)) = self.inner.nexts() {
+                return Some(a);
+            }
+        }
+    }
+}
+
+impl<K: Ord, const E: usize> FusedIterator for Intersection<'_, K, E> {}
+
+/// An iterator over the union of two `ArchivedBTreeSet`s.
+///
+/// This struct is created by the [`union`](ArchivedBTreeSet::union) method on
+/// [`ArchivedBTreeSet`]. See its documentation for more.
+pub struct Union<'a, K, const E: usize> {
+    inner: MergeIter<'a, K, E>,
+}
+
+impl<'a, K: Ord, const E: usize> Iterator for Union<'a, K, E> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (a, b) = self.inner.nexts();
+        a.or(b)
+    }
+}
+
+impl<K: Ord, const E: usize> FusedIterator for Union<'_, K, E> {}
+
 /// The resolver for archived B-tree sets.
 pub struct BTreeSetResolver(BTreeMapResolver);
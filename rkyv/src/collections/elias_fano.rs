@@ -0,0 +1,481 @@
+//! An archived set of sorted integers compressed with Elias-Fano encoding.
+//!
+//! [`ArchivedEliasFano`] stores a non-decreasing sequence of `u64`s in a little
+//! over `2 + log2(max / len)` bits per value. Each value is split into its low
+//! bits, which are packed together, and its high bits, which are stored as a
+//! unary-coded bit vector. Every 256th set bit of the high bits is sampled so
+//! that [`select`](ArchivedEliasFano::select) only has to scan a few words.
+//!
+//! This is a good fit for posting lists, document ID sets, and other large
+//! monotone sequences which are built once and then queried.
+
+use core::{error::Error, fmt, iter::FusedIterator};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::IteratorLengthMismatch,
+    primitive::{ArchivedU64, ArchivedUsize},
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable,
+};
+
+/// The number of values between each sampled position in the high bits.
+const SAMPLE_RATE: usize = 256;
+
+/// An archived set of sorted integers compressed with Elias-Fano encoding.
+///
+/// The archived set may contain duplicate values.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedEliasFano {
+    low: ArchivedVec<ArchivedU64>,
+    high: ArchivedVec<ArchivedU64>,
+    samples: ArchivedVec<ArchivedU64>,
+    len: ArchivedUsize,
+    low_bits: u8,
+}
+
+impl ArchivedEliasFano {
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.len.to_native() as usize
+    }
+
+    /// Returns whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the `n`th smallest value in the set, counting from zero, or
+    /// `None` if the set has `n` or fewer values.
+    pub fn select(&self, n: usize) -> Option<u64> {
+        if n >= self.len() {
+            return None;
+        }
+
+        let start = self.samples[n / SAMPLE_RATE].to_native() as usize;
+        let mut remaining = n % SAMPLE_RATE;
+        let mut word_index = start / 64;
+        let mut word = self.high[word_index].to_native() & (!0 << (start % 64));
+        loop {
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                break;
+            }
+            remaining -= ones;
+            word_index += 1;
+            word = self.high[word_index].to_native();
+        }
+        for _ in 0..remaining {
+            // Clear the lowest set bit
+            word &= word - 1;
+        }
+
+        let pos = word_index * 64 + word.trailing_zeros() as usize;
+        Some(self.value(n, pos))
+    }
+
+    /// Returns the number of values in the set which are less than `value`.
+    pub fn rank(&self, value: u64) -> usize {
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.select(mid).unwrap() < value {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// Returns whether the set contains `value`.
+    pub fn contains(&self, value: u64) -> bool {
+        self.select(self.rank(value)) == Some(value)
+    }
+
+    /// Returns the smallest value in the set, or `None` if it is empty.
+    pub fn first(&self) -> Option<u64> {
+        self.select(0)
+    }
+
+    /// Returns the largest value in the set, or `None` if it is empty.
+    pub fn last(&self) -> Option<u64> {
+        self.select(self.len().checked_sub(1)?)
+    }
+
+    /// Returns an iterator over the values in the set in ascending order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            set: self,
+            index: 0,
+            word_index: 0,
+            word: self.high.first().map_or(0, |word| word.to_native()),
+        }
+    }
+
+    /// Returns the value at `index`, given the position of its set bit in the
+    /// high bits.
+    fn value(&self, index: usize, pos: usize) -> u64 {
+        let high = (pos - index) as u64;
+        (high << self.low_bits) | self.low_value(index)
+    }
+
+    fn low_value(&self, index: usize) -> u64 {
+        let width = self.low_bits as usize;
+        if width == 0 {
+            return 0;
+        }
+
+        let bit = index * width;
+        let (word, offset) = (bit / 64, bit % 64);
+        let mut bits = self.low[word].to_native() >> offset;
+        if offset + width > 64 {
+            bits |= self.low[word + 1].to_native() << (64 - offset);
+        }
+        bits & (!0 >> (64 - width))
+    }
+
+    /// Serializes an archived Elias-Fano set from an iterator of values.
+    ///
+    /// The values must be yielded in non-decreasing order. The length passed
+    /// to [`resolve_from_len`](Self::resolve_from_len) must be the number of
+    /// values yielded by the iterator.
+    pub fn serialize_from_iter<I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<EliasFanoResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = u64> + Clone,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+        let max = iter.clone().last().unwrap_or(0);
+        let low_bits = match max.checked_div(len as u64) {
+            Some(quotient) if quotient > 0 => quotient.ilog2(),
+            _ => 0,
+        };
+        let low_words = (len * low_bits as usize).div_ceil(64);
+        let high_words = (len + (max >> low_bits) as usize + 1).div_ceil(64);
+        let sample_count = len.div_ceil(SAMPLE_RATE);
+
+        SerVec::<u64>::with_capacity(
+            serializer,
+            low_words,
+            |low, serializer| {
+                SerVec::<u64>::with_capacity(
+                    serializer,
+                    high_words,
+                    |high, serializer| {
+                        SerVec::<u64>::with_capacity(
+                            serializer,
+                            sample_count,
+                            |samples, serializer| {
+                                for _ in 0..low_words {
+                                    low.push(0);
+                                }
+                                for _ in 0..high_words {
+                                    high.push(0);
+                                }
+
+                                let mut previous = 0;
+                                let mut count = 0;
+                                for (i, value) in iter.take(len).enumerate() {
+                                    if value < previous {
+                                        fail!(UnsortedValues);
+                                    }
+                                    previous = value;
+
+                                    let pos = (value >> low_bits) as usize + i;
+                                    high.as_mut_slice()[pos / 64] |=
+                                        1 << (pos % 64);
+                                    if i % SAMPLE_RATE == 0 {
+                                        samples.push(pos as u64);
+                                    }
+
+                                    write_low_value(
+                                        low.as_mut_slice(),
+                                        i,
+                                        low_bits,
+                                        value,
+                                    );
+                                    count += 1;
+                                }
+                                if count != len {
+                                    fail!(IteratorLengthMismatch {
+                                        expected: len,
+                                        actual: count,
+                                    });
+                                }
+
+                                Ok(EliasFanoResolver {
+                                    low: ArchivedVec::serialize_from_slice(
+                                        low.as_slice(),
+                                        serializer,
+                                    )?,
+                                    low_len: low_words,
+                                    high: ArchivedVec::serialize_from_slice(
+                                        high.as_slice(),
+                                        serializer,
+                                    )?,
+                                    high_len: high_words,
+                                    samples: ArchivedVec::serialize_from_slice(
+                                        samples.as_slice(),
+                                        serializer,
+                                    )?,
+                                    samples_len: sample_count,
+                                    low_bits: low_bits as u8,
+                                })
+                            },
+                        )?
+                    },
+                )?
+            },
+        )?
+    }
+
+    /// Resolves an archived Elias-Fano set from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: EliasFanoResolver,
+        out: Place<Self>,
+    ) {
+        munge! {
+            let ArchivedEliasFano {
+                low,
+                high,
+                samples,
+                len: out_len,
+                low_bits,
+            } = out;
+        }
+        ArchivedVec::resolve_from_len(resolver.low_len, resolver.low, low);
+        ArchivedVec::resolve_from_len(resolver.high_len, resolver.high, high);
+        ArchivedVec::resolve_from_len(
+            resolver.samples_len,
+            resolver.samples,
+            samples,
+        );
+        len.resolve((), out_len);
+        low_bits.write(resolver.low_bits);
+    }
+}
+
+fn write_low_value(words: &mut [u64], index: usize, low_bits: u32, value: u64) {
+    let width = low_bits as usize;
+    if width == 0 {
+        return;
+    }
+
+    let bits = value & (!0 >> (64 - width));
+    let bit = index * width;
+    let (word, offset) = (bit / 64, bit % 64);
+    words[word] |= bits << offset;
+    if offset + width > 64 {
+        words[word + 1] |= bits >> (64 - offset);
+    }
+}
+
+#[derive(Debug)]
+struct UnsortedValues;
+
+impl fmt::Display for UnsortedValues {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Elias-Fano values must be in non-decreasing order")
+    }
+}
+
+impl Error for UnsortedValues {}
+
+impl fmt::Debug for ArchivedEliasFano {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Eq for ArchivedEliasFano {}
+
+impl<'a> IntoIterator for &'a ArchivedEliasFano {
+    type Item = u64;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl PartialEq for ArchivedEliasFano {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialEq<[u64]> for ArchivedEliasFano {
+    fn eq(&self, other: &[u64]) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+/// An iterator over the values of an [`ArchivedEliasFano`].
+#[derive(Clone)]
+pub struct Iter<'a> {
+    set: &'a ArchivedEliasFano,
+    index: usize,
+    word_index: usize,
+    word: u64,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.index >= self.set.len() {
+            return None;
+        }
+
+        while self.word == 0 {
+            self.word_index += 1;
+            self.word = self.set.high[self.word_index].to_native();
+        }
+        let pos = self.word_index * 64 + self.word.trailing_zeros() as usize;
+        // Clear the lowest set bit
+        self.word &= self.word - 1;
+
+        let value = self.set.value(self.index, pos);
+        self.index += 1;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.set.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl FusedIterator for Iter<'_> {}
+
+/// The resolver for [`ArchivedEliasFano`].
+pub struct EliasFanoResolver {
+    low: VecResolver,
+    low_len: usize,
+    high: VecResolver,
+    high_len: usize,
+    samples: VecResolver,
+    samples_len: usize,
+    low_bits: u8,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::{ArchivedEliasFano, SAMPLE_RATE};
+
+    #[derive(Debug)]
+    enum InvalidEliasFano {
+        TooManyLowBits(u8),
+        LowLengthMismatch { expected: usize, actual: usize },
+        HighCountMismatch { expected: usize, actual: usize },
+        SampleMismatch,
+    }
+
+    impl fmt::Display for InvalidEliasFano {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::TooManyLowBits(bits) => write!(
+                    f,
+                    "archived Elias-Fano set has {bits} low bits, which is \
+                     more than the maximum of 63",
+                ),
+                Self::LowLengthMismatch { expected, actual } => write!(
+                    f,
+                    "archived Elias-Fano set has {actual} words of low bits \
+                     but expected {expected}",
+                ),
+                Self::HighCountMismatch { expected, actual } => write!(
+                    f,
+                    "archived Elias-Fano set has {actual} high bits set but \
+                     expected {expected}",
+                ),
+                Self::SampleMismatch => write!(
+                    f,
+                    "archived Elias-Fano set has incorrect select samples",
+                ),
+            }
+        }
+    }
+
+    impl Error for InvalidEliasFano {}
+
+    unsafe impl<C> Verify<C> for ArchivedEliasFano
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            if self.low_bits > 63 {
+                fail!(InvalidEliasFano::TooManyLowBits(self.low_bits));
+            }
+            let expected = len
+                .checked_mul(self.low_bits as usize)
+                .map_or(usize::MAX, |bits| bits.div_ceil(64));
+            if self.low.len() != expected {
+                fail!(InvalidEliasFano::LowLengthMismatch {
+                    expected,
+                    actual: self.low.len(),
+                });
+            }
+
+            // Check that the samples point to every `SAMPLE_RATE`th set bit,
+            // which also ensures that `select` never runs off the end of the
+            // high bits.
+            if self.samples.len() != len.div_ceil(SAMPLE_RATE) {
+                fail!(InvalidEliasFano::SampleMismatch);
+            }
+            let mut count = 0;
+            for (i, word) in self.high.iter().enumerate() {
+                let mut word = word.to_native();
+                while word != 0 {
+                    if count % SAMPLE_RATE == 0 {
+                        let pos = i * 64 + word.trailing_zeros() as usize;
+                        match self.samples.get(count / SAMPLE_RATE) {
+                            Some(sample)
+                                if sample.to_native() == pos as u64 => {}
+                            _ => fail!(InvalidEliasFano::SampleMismatch),
+                        }
+                    }
+                    word &= word - 1;
+                    count += 1;
+                }
+            }
+            if count != len {
+                fail!(InvalidEliasFano::HighCountMismatch {
+                    expected: len,
+                    actual: count,
+                });
+            }
+
+            Ok(())
+        }
+    }
+}
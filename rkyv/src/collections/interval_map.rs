@@ -0,0 +1,439 @@
+//! An archived map from half-open intervals to values.
+//!
+//! The intervals are sorted by their start and laid out as an implicit,
+//! perfectly balanced binary search tree: the root of any subrange of entries
+//! is the entry in the middle of it. Every entry also stores the greatest end
+//! of any interval in its subtree, which lets queries skip subtrees that can't
+//! contain any overlapping intervals. Finding the `k` intervals which overlap a
+//! point or range takes `O(log n + k)` time.
+
+use core::{
+    borrow::Borrow, error::Error, fmt, iter::FusedIterator, ops::Range, slice,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::IteratorLengthMismatch,
+    primitive::FixedUsize,
+    ser::{Allocator, Writer},
+    util::{InlineVec, SerVec},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+// The implicit tree over at most `FixedUsize::MAX` entries is perfectly
+// balanced, so no path from the root to a leaf can be longer than this.
+const MAX_HEIGHT: usize = FixedUsize::BITS as usize;
+
+/// An interval and its value in an [`ArchivedIntervalMap`].
+#[derive(Debug, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct IntervalEntry<K, V> {
+    start: K,
+    end: K,
+    max_end: K,
+    value: V,
+}
+
+impl<K, V> IntervalEntry<K, V> {
+    fn range(&self) -> Range<&K> {
+        &self.start..&self.end
+    }
+}
+
+/// An archived map from half-open intervals to values.
+///
+/// Intervals may overlap, and the same interval may appear more than once.
+/// Entries are sorted by the start of their interval, then by its end.
+#[derive(Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedIntervalMap<K, V> {
+    entries: ArchivedVec<IntervalEntry<K, V>>,
+}
+
+impl<K, V> ArchivedIntervalMap<K, V> {
+    /// Returns the number of intervals in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the intervals and values of the map, sorted by
+    /// interval.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the intervals which contain the given point,
+    /// sorted by interval.
+    ///
+    /// An interval `start..end` contains a point `p` if `start <= p < end`.
+    pub fn find_overlapping<'a, Q>(
+        &'a self,
+        point: &'a Q,
+    ) -> Overlapping<'a, K, V, Q>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Overlapping::new(self.entries.as_slice(), point, point, true)
+    }
+
+    /// Returns an iterator over the intervals which overlap the half-open
+    /// range `start..end`, sorted by interval.
+    ///
+    /// An interval overlaps the range if it starts before `end` and ends after
+    /// `start`. If `start` is not less than `end`, the range is empty and no
+    /// intervals overlap it.
+    pub fn range<'a, Q>(
+        &'a self,
+        start: &'a Q,
+        end: &'a Q,
+    ) -> Overlapping<'a, K, V, Q>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Overlapping::new(self.entries.as_slice(), start, end, false)
+    }
+
+    /// Serializes an iterator of intervals and values as an interval map.
+    ///
+    /// The intervals may be yielded in any order.
+    pub fn serialize_from_iter<I, BRU, BVU, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<IntervalMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (BRU, BVU)>,
+        BRU: Borrow<Range<KU>>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Ord,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |items, serializer| {
+            let mut iter = iter;
+            for item in iter.by_ref().take(len) {
+                if item.0.borrow().end < item.0.borrow().start {
+                    fail!(InvalidInterval);
+                }
+                items.push(item);
+            }
+            if items.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: items.len(),
+                });
+            }
+            let leftovers = iter.count();
+            if leftovers != 0 {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: len + leftovers,
+                });
+            }
+
+            items.sort_unstable_by(|(a, _), (b, _)| {
+                let (a, b) = (a.borrow(), b.borrow());
+                a.start.cmp(&b.start).then_with(|| a.end.cmp(&b.end))
+            });
+
+            SerVec::with_capacity(serializer, len, |max_ends, serializer| {
+                for _ in 0..len {
+                    max_ends.push(0);
+                }
+                let end = |index: usize| &items[index].0.borrow().end;
+                fill_max_ends(&end, max_ends, 0, len);
+
+                let entries = ArchivedVec::serialize_from_iter::<
+                    IntervalEntryAdapter<'_, KU, VU>,
+                    _,
+                    _,
+                >(
+                    items.iter().zip(max_ends.iter()).map(
+                        |((range, value), &max_end)| {
+                            let range = range.borrow();
+                            IntervalEntryAdapter {
+                                start: &range.start,
+                                end: &range.end,
+                                max_end: end(max_end),
+                                value: value.borrow(),
+                            }
+                        },
+                    ),
+                    serializer,
+                )?;
+
+                Ok(IntervalMapResolver { entries })
+            })?
+        })?
+    }
+
+    /// Resolves an archived interval map from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: IntervalMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedIntervalMap { entries } = out);
+        ArchivedVec::resolve_from_len(len, resolver.entries, entries);
+    }
+}
+
+/// Records the index of the interval with the greatest end in each subtree of
+/// the implicit tree over `lo..hi`, and returns the index for the whole range.
+fn fill_max_ends<'a, KU, F>(
+    end: &F,
+    max_ends: &mut [usize],
+    lo: usize,
+    hi: usize,
+) -> Option<usize>
+where
+    KU: Ord + 'a,
+    F: Fn(usize) -> &'a KU,
+{
+    if lo >= hi {
+        return None;
+    }
+
+    let mid = lo + (hi - lo) / 2;
+    let mut max = mid;
+    let left = fill_max_ends(end, max_ends, lo, mid);
+    let right = fill_max_ends(end, max_ends, mid + 1, hi);
+    for child in [left, right].into_iter().flatten() {
+        if end(child) > end(max) {
+            max = child;
+        }
+    }
+    max_ends[mid] = max;
+    Some(max)
+}
+
+struct IntervalEntryAdapter<'a, K, V> {
+    start: &'a K,
+    end: &'a K,
+    max_end: &'a K,
+    value: &'a V,
+}
+
+struct IntervalEntryResolver<K, V> {
+    start: K,
+    end: K,
+    max_end: K,
+    value: V,
+}
+
+impl<K: Archive, V: Archive> Archive for IntervalEntryAdapter<'_, K, V> {
+    type Archived = IntervalEntry<K::Archived, V::Archived>;
+    type Resolver = IntervalEntryResolver<K::Resolver, V::Resolver>;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let IntervalEntry { start, end, max_end, value } = out);
+        self.start.resolve(resolver.start, start);
+        self.end.resolve(resolver.end, end);
+        self.max_end.resolve(resolver.max_end, max_end);
+        self.value.resolve(resolver.value, value);
+    }
+}
+
+impl<S, K, V> Serialize<S> for IntervalEntryAdapter<'_, K, V>
+where
+    S: Fallible + ?Sized,
+    K: Serialize<S>,
+    V: Serialize<S>,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(IntervalEntryResolver {
+            start: self.start.serialize(serializer)?,
+            end: self.end.serialize(serializer)?,
+            max_end: self.max_end.serialize(serializer)?,
+            value: self.value.serialize(serializer)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct InvalidInterval;
+
+impl fmt::Display for InvalidInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interval ends before it starts")
+    }
+}
+
+impl Error for InvalidInterval {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedIntervalMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ArchivedIntervalMap<K, V> {
+    type Item = (Range<&'a K>, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedIntervalMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedIntervalMap<K, V> {}
+
+/// An iterator over the intervals and values of an [`ArchivedIntervalMap`].
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, IntervalEntry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (Range<&'a K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (entry.range(), &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|entry| (entry.range(), &entry.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// An iterator over the intervals of an [`ArchivedIntervalMap`] which overlap
+/// a point or range.
+///
+/// This `struct` is created by [`ArchivedIntervalMap::find_overlapping`] and
+/// [`ArchivedIntervalMap::range`].
+pub struct Overlapping<'a, K, V, Q: ?Sized> {
+    entries: &'a [IntervalEntry<K, V>],
+    lower: &'a Q,
+    upper: &'a Q,
+    inclusive: bool,
+    // Entries which start before the upper bound and whose subtrees may still
+    // hold overlapping intervals, along with the end of their subtree.
+    stack: InlineVec<(usize, usize), MAX_HEIGHT>,
+}
+
+impl<'a, K, V, Q> Overlapping<'a, K, V, Q>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    fn new(
+        entries: &'a [IntervalEntry<K, V>],
+        lower: &'a Q,
+        upper: &'a Q,
+        inclusive: bool,
+    ) -> Self {
+        let mut result = Self {
+            entries,
+            lower,
+            upper,
+            inclusive,
+            stack: InlineVec::new(),
+        };
+        if inclusive || lower < upper {
+            result.descend(0, entries.len());
+        }
+        result
+    }
+
+    fn starts_in_range(&self, start: &K) -> bool {
+        if self.inclusive {
+            start.borrow() <= self.upper
+        } else {
+            start.borrow() < self.upper
+        }
+    }
+
+    fn descend(&mut self, lo: usize, mut hi: usize) {
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let entry = &self.entries[mid];
+            // No interval in this subtree ends after the lower bound.
+            if entry.max_end.borrow() <= self.lower {
+                break;
+            }
+            // Entries to the right of `mid` start no earlier than it does, so
+            // only the left subtree can overlap if `mid` starts too late.
+            if self.starts_in_range(&entry.start) {
+                self.stack.push((mid, hi));
+            }
+            hi = mid;
+        }
+    }
+}
+
+impl<'a, K, V, Q> Iterator for Overlapping<'a, K, V, Q>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Item = (Range<&'a K>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((mid, hi)) = self.stack.pop() {
+            self.descend(mid + 1, hi);
+            let entry = &self.entries[mid];
+            if entry.end.borrow() > self.lower {
+                return Some((entry.range(), &entry.value));
+            }
+        }
+        None
+    }
+}
+
+impl<K, V, Q> FusedIterator for Overlapping<'_, K, V, Q>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+}
+
+/// The resolver for [`ArchivedIntervalMap`].
+pub struct IntervalMapResolver {
+    entries: VecResolver,
+}
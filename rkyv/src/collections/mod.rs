@@ -1,6 +1,17 @@
 //! Archived versions of standard library containers.
 
+pub mod bitvec;
+pub mod bounded;
 pub mod btree_map;
 pub mod btree_set;
+pub mod elias_fano;
+pub mod interval_map;
+pub mod multi_map;
+pub mod ndarray;
+pub mod phf;
+pub mod rle_vec;
+pub mod sorted_vec_map;
 pub mod swiss_table;
+pub mod trie;
 pub mod util;
+pub mod vec_deque;
@@ -0,0 +1,393 @@
+//! An archived hash multi-map.
+//!
+//! [`ArchivedHashMultiMap`] maps each key to a slice of values. The values for
+//! every key are stored contiguously in a single vec, so looking up a key
+//! yields its values without following a pointer per key like an archived
+//! `HashMap<K, Vec<V>>` would.
+
+use core::{
+    borrow::Borrow,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::Range,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::{
+        swiss_table::table::{self, ArchivedHashTable, HashTableResolver},
+        util::{Entry, EntryAdapter, IteratorLengthMismatch},
+    },
+    hash::{hash_value, FxHasher64},
+    ops::ArchivedRange,
+    primitive::ArchivedUsize,
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable, Serialize,
+};
+
+/// An archived hash map which maps each key to any number of values.
+///
+/// Each distinct key is stored once in a hash table alongside the range of its
+/// values. The values are grouped by key and stored in the order they were
+/// serialized.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedHashMultiMap<K, V, H = FxHasher64> {
+    table: ArchivedHashTable<Entry<K, ArchivedRange<ArchivedUsize>>>,
+    values: ArchivedVec<V>,
+    _phantom: PhantomData<H>,
+}
+
+impl<K, V, H> ArchivedHashMultiMap<K, V, H> {
+    /// Returns the number of key-value pairs in the multi-map.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the multi-map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of distinct keys in the multi-map.
+    pub fn keys_len(&self) -> usize {
+        self.table.len()
+    }
+
+    /// Returns all of the values in the multi-map, grouped by key.
+    pub fn values(&self) -> &[V] {
+        self.values.as_slice()
+    }
+
+    /// Returns an iterator over the distinct keys of the multi-map and their
+    /// values.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.table.iter(),
+            values: self.values.as_slice(),
+        }
+    }
+
+    /// Returns an iterator over the distinct keys of the multi-map.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    fn values_in(&self, range: &ArchivedRange<ArchivedUsize>) -> &[V] {
+        let start = range.start.to_native() as usize;
+        let end = range.end.to_native() as usize;
+        &self.values[start..end]
+    }
+
+    /// Returns the key and values whose key has the given hash and satisfies
+    /// the given equality function.
+    ///
+    /// The hash must be computed with [`hash_value`] and the multi-map's hasher
+    /// `H`.
+    pub fn get_key_values_with_hash<C>(
+        &self,
+        hash: u64,
+        eq: C,
+    ) -> Option<(&K, &[V])>
+    where
+        C: Fn(&K) -> bool,
+    {
+        let entry = self.table.get_with(hash, |e| eq(&e.key))?;
+        Some((&entry.key, self.values_in(&entry.value)))
+    }
+}
+
+impl<K, V, H: Hasher + Default> ArchivedHashMultiMap<K, V, H> {
+    /// Returns the key and values corresponding to the supplied key.
+    pub fn get_key_values<Q>(&self, key: &Q) -> Option<(&K, &[V])>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_values_with_hash(hash_value::<Q, H>(key), |k| {
+            k.borrow() == key
+        })
+    }
+
+    /// Returns the values corresponding to the supplied key.
+    ///
+    /// Returns an empty slice if the multi-map does not contain the key.
+    pub fn get<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_values(key).map_or(&[], |(_, values)| values)
+    }
+
+    /// Returns whether the multi-map contains the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_values(key).is_some()
+    }
+
+    /// Serializes an iterator of key-value pairs as a multi-map.
+    ///
+    /// The pairs are grouped by key. The values for each key are stored in the
+    /// order that the iterator yields them.
+    pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashMultiMapResolver, S::Error>
+    where
+        I: Clone + Iterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.clone().count();
+
+        SerVec::with_capacity(serializer, len, |items, serializer| {
+            for item in iter.take(len) {
+                items.push(item);
+            }
+            if items.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: items.len(),
+                });
+            }
+
+            SerVec::with_capacity(serializer, len, |order, serializer| {
+                for (index, (key, _)) in items.iter().enumerate() {
+                    order.push((hash_value::<KU, H>(key.borrow()), index));
+                }
+                order.sort_unstable();
+
+                SerVec::with_capacity(serializer, len, |groups, serializer| {
+                    group_by_key(order, groups, |a, b| {
+                        items[a].0.borrow() == items[b].0.borrow()
+                    });
+
+                    let table = ArchivedHashTable::serialize_from_iter::<
+                        _,
+                        EntryAdapter<&KU, Range<usize>, KU, Range<usize>>,
+                        _,
+                        _,
+                    >(
+                        groups.iter().map(|range| {
+                            let key = items[order[range.start].1].0.borrow();
+                            EntryAdapter::new(key, range.clone())
+                        }),
+                        groups.iter().map(|range| order[range.start].0),
+                        load_factor,
+                        serializer,
+                    )?;
+                    let values = ArchivedVec::serialize_from_iter::<VU, _, _>(
+                        order.iter().map(|&(_, i)| items[i].1.borrow()),
+                        serializer,
+                    )?;
+
+                    Ok(HashMultiMapResolver {
+                        table,
+                        values,
+                        keys_len: groups.len(),
+                    })
+                })?
+            })?
+        })?
+    }
+
+    /// Resolves an archived multi-map from the number of key-value pairs and
+    /// the load factor it was serialized with.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: HashMultiMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedHashMultiMap { table, values, _phantom: _ } = out);
+        ArchivedHashTable::resolve_from_len(
+            resolver.keys_len,
+            load_factor,
+            resolver.table,
+            table,
+        );
+        ArchivedVec::resolve_from_len(len, resolver.values, values);
+    }
+}
+
+/// Reorders `order`, which is sorted by hash and then by index, so that the
+/// indices of equal keys are adjacent and remain sorted. Pushes the range of
+/// each group of equal keys to `groups`.
+fn group_by_key(
+    order: &mut [(u64, usize)],
+    groups: &mut SerVec<Range<usize>>,
+    eq: impl Fn(usize, usize) -> bool,
+) {
+    let mut start = 0;
+    while start < order.len() {
+        let hash = order[start].0;
+        let end = start
+            + order[start..]
+                .iter()
+                .take_while(|(h, _)| *h == hash)
+                .count();
+
+        // Keys with the same hash are usually equal, so this is linear in the
+        // length of the run unless there are hash collisions.
+        let mut group_start = start;
+        while group_start < end {
+            let key = order[group_start].1;
+            let mut group_end = group_start + 1;
+            for i in group_start + 1..end {
+                if eq(key, order[i].1) {
+                    order.swap(group_end, i);
+                    group_end += 1;
+                }
+            }
+            order[group_end..end].sort_unstable();
+            groups.push(group_start..group_end);
+            group_start = group_end;
+        }
+
+        start = end;
+    }
+}
+
+impl<K, V, H> fmt::Debug for ArchivedHashMultiMap<K, V, H>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V, H> IntoIterator for &'a ArchivedHashMultiMap<K, V, H> {
+    type Item = (&'a K, &'a [V]);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the keys and values of an [`ArchivedHashMultiMap`].
+pub struct Iter<'a, K, V> {
+    inner: table::Iter<'a, Entry<K, ArchivedRange<ArchivedUsize>>>,
+    values: &'a [V],
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a [V]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| {
+            let start = entry.value.start.to_native() as usize;
+            let end = entry.value.end.to_native() as usize;
+            (&entry.key, &self.values[start..end])
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// An iterator over the keys of an [`ArchivedHashMultiMap`].
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// The resolver for [`ArchivedHashMultiMap`].
+pub struct HashMultiMapResolver {
+    table: HashTableResolver,
+    values: VecResolver,
+    keys_len: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::ArchivedHashMultiMap;
+
+    #[derive(Debug)]
+    struct InvalidValueRange {
+        start: usize,
+        end: usize,
+        len: usize,
+    }
+
+    impl fmt::Display for InvalidValueRange {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "multi-map value range {}..{} is invalid for {} values",
+                self.start, self.end, self.len,
+            )
+        }
+    }
+
+    impl Error for InvalidValueRange {}
+
+    unsafe impl<K, V, H, C> Verify<C> for ArchivedHashMultiMap<K, V, H>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            for entry in self.table.iter() {
+                let start = entry.value.start.to_native() as usize;
+                let end = entry.value.end.to_native() as usize;
+                if start > end || end > len {
+                    fail!(InvalidValueRange { start, end, len });
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! An archived N-dimensional array.
+//!
+//! [`ArchivedArrayD`] stores the shape and strides of an array alongside a
+//! contiguous slice of its elements. With the `ndarray-0_15` or `ndarray-0_16`
+//! features enabled, `ndarray` arrays archive as an `ArchivedArrayD` and can
+//! be viewed as an `ArrayView` without copying their elements.
+
+use core::{borrow::Borrow, error::Error, fmt};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    primitive::{ArchivedIsize, ArchivedUsize},
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable, Serialize,
+};
+
+/// An archived N-dimensional array with a dynamic number of dimensions.
+///
+/// The elements are stored in a single contiguous slice. The element at an
+/// index is found by multiplying each component of the index by the stride of
+/// its axis and summing the results. Strides are never negative, and no two
+/// indices refer to the same element.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedArrayD<T> {
+    shape: ArchivedVec<ArchivedUsize>,
+    strides: ArchivedVec<ArchivedIsize>,
+    data: ArchivedVec<T>,
+}
+
+impl<T> ArchivedArrayD<T> {
+    /// Returns the number of dimensions of the array.
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    /// Returns the length of each axis of the array.
+    pub fn shape(&self) -> &[ArchivedUsize] {
+        self.shape.as_slice()
+    }
+
+    /// Returns the stride of each axis of the array, in elements.
+    pub fn strides(&self) -> &[ArchivedIsize] {
+        self.strides.as_slice()
+    }
+
+    /// Returns the number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns whether the array has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the elements of the array in the order they are stored.
+    ///
+    /// This is the same as the logical order of the elements if the array is
+    /// in [standard layout](Self::is_standard_layout).
+    pub fn as_slice_memory_order(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Returns whether the elements of the array are stored in row-major
+    /// order.
+    pub fn is_standard_layout(&self) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        let mut expected = 1;
+        for (&len, &stride) in self.shape.iter().zip(self.strides.iter()).rev()
+        {
+            let len = len.to_native() as usize;
+            if len > 1 && stride.to_native() as usize != expected {
+                return false;
+            }
+            expected *= len;
+        }
+        true
+    }
+
+    /// Returns a reference to the element at the given index, or `None` if
+    /// the index is out of bounds.
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        if index.len() != self.ndim() {
+            return None;
+        }
+
+        let mut offset = 0usize;
+        for ((&i, &len), &stride) in
+            index.iter().zip(self.shape.iter()).zip(self.strides.iter())
+        {
+            if i >= len.to_native() as usize {
+                return None;
+            }
+            let stride = stride.to_native() as usize;
+            offset = offset.checked_add(i.checked_mul(stride)?)?;
+        }
+        self.data.get(offset)
+    }
+
+    /// Serializes an archived array from its shape, strides, and a slice of
+    /// its elements in memory order.
+    ///
+    /// Returns an error if the shape and strides don't describe a layout of
+    /// exactly `data.len()` distinct elements starting at the beginning of the
+    /// slice.
+    pub fn serialize_from_slice<U, S>(
+        shape: &[usize],
+        strides: &[isize],
+        data: &[U],
+        serializer: &mut S,
+    ) -> Result<ArrayDResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        if shape.len() != strides.len()
+            || !is_valid_layout(
+                shape.len(),
+                |axis| shape[axis],
+                |axis| strides[axis],
+                data.len(),
+            )
+        {
+            fail!(InvalidArrayLayout);
+        }
+
+        Ok(ArrayDResolver {
+            shape: ArchivedVec::serialize_from_slice(shape, serializer)?,
+            strides: ArchivedVec::serialize_from_slice(strides, serializer)?,
+            data: ArchivedVec::serialize_from_slice(data, serializer)?,
+        })
+    }
+
+    /// Serializes an archived array in standard layout from its shape and an
+    /// iterator of its elements in row-major order.
+    pub fn serialize_from_iter<U, I, S>(
+        shape: &[usize],
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<ArrayDResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        I: ExactSizeIterator + Clone,
+        I::Item: Borrow<U>,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        // Like `ndarray`, use zero strides for arrays without any elements.
+        let is_empty = shape.contains(&0);
+        let stride = move |axis: usize| {
+            if is_empty {
+                return 0;
+            }
+            shape[axis + 1..]
+                .iter()
+                .try_fold(1usize, |stride, &len| stride.checked_mul(len))
+                .map_or(-1, |stride| stride as isize)
+        };
+        if !is_valid_layout(shape.len(), |axis| shape[axis], stride, iter.len())
+        {
+            fail!(InvalidArrayLayout);
+        }
+
+        Ok(ArrayDResolver {
+            shape: ArchivedVec::serialize_from_slice(shape, serializer)?,
+            strides: ArchivedVec::serialize_from_iter::<isize, _, _>(
+                (0..shape.len()).map(stride),
+                serializer,
+            )?,
+            data: ArchivedVec::serialize_from_iter(iter, serializer)?,
+        })
+    }
+
+    /// Resolves an archived array from its number of dimensions and elements.
+    pub fn resolve_from_len(
+        ndim: usize,
+        len: usize,
+        resolver: ArrayDResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedArrayD { shape, strides, data } = out);
+        ArchivedVec::resolve_from_len(ndim, resolver.shape, shape);
+        ArchivedVec::resolve_from_len(ndim, resolver.strides, strides);
+        ArchivedVec::resolve_from_len(len, resolver.data, data);
+    }
+}
+
+/// Returns whether the given shape and strides index exactly `len` distinct
+/// elements.
+///
+/// These are the same conditions that `ndarray` checks when creating an array
+/// view from a slice, so an array with a valid layout can always be viewed.
+fn is_valid_layout(
+    ndim: usize,
+    shape: impl Fn(usize) -> usize,
+    strides: impl Fn(usize) -> isize,
+    len: usize,
+) -> bool {
+    let mut elements = 1usize;
+    let mut nonzero_elements = 1usize;
+    let mut max_offset = 0usize;
+    for axis in 0..ndim {
+        let (axis_len, stride) = (shape(axis), strides(axis));
+        if stride < 0 {
+            return false;
+        }
+
+        let offset = axis_len.saturating_sub(1).checked_mul(stride as usize);
+        match (
+            elements.checked_mul(axis_len),
+            nonzero_elements.checked_mul(axis_len.max(1)),
+            offset.and_then(|offset| max_offset.checked_add(offset)),
+        ) {
+            (Some(e), Some(n), Some(m)) => {
+                elements = e;
+                nonzero_elements = n;
+                max_offset = m;
+            }
+            _ => return false,
+        }
+    }
+
+    if elements != len
+        || nonzero_elements > isize::MAX as usize
+        || max_offset > isize::MAX as usize
+    {
+        return false;
+    }
+    if len == 0 {
+        return max_offset == 0;
+    }
+    if max_offset >= len {
+        return false;
+    }
+
+    // Every axis must step over all of the elements reachable through the
+    // axes with smaller strides, or two indices would share an element.
+    (0..ndim).all(|axis| {
+        let stride = strides(axis) as usize;
+        shape(axis) <= 1
+            || (0..ndim)
+                .filter(|&other| {
+                    other != axis
+                        && shape(other) > 1
+                        && (strides(other) as usize, other) < (stride, axis)
+                })
+                .map(|other| (shape(other) - 1) * strides(other) as usize)
+                .sum::<usize>()
+                < stride
+    })
+}
+
+#[derive(Debug)]
+pub(crate) struct InvalidArrayLayout;
+
+impl fmt::Display for InvalidArrayLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "array shape and strides do not match the number of elements",
+        )
+    }
+}
+
+impl Error for InvalidArrayLayout {}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedArrayD<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedArrayD")
+            .field("shape", &self.shape)
+            .field("strides", &self.strides)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+/// The resolver for [`ArchivedArrayD`].
+pub struct ArrayDResolver {
+    shape: VecResolver,
+    strides: VecResolver,
+    data: VecResolver,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::{is_valid_layout, ArchivedArrayD, InvalidArrayLayout};
+
+    unsafe impl<T, C> Verify<C> for ArchivedArrayD<T>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let shape = self.shape();
+            let strides = self.strides();
+            if shape.len() != strides.len()
+                || !is_valid_layout(
+                    shape.len(),
+                    |axis| shape[axis].to_native() as usize,
+                    |axis| strides[axis].to_native() as isize,
+                    self.len(),
+                )
+            {
+                fail!(InvalidArrayLayout);
+            }
+            Ok(())
+        }
+    }
+}
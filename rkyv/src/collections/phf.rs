@@ -0,0 +1,632 @@
+//! An archived hash map which uses a perfect hash function.
+//!
+//! The perfect hash function is computed with the CHD ("compress, hash and
+//! displace") algorithm when the map is serialized. Every key is assigned to a
+//! unique slot, so lookups hash the key once and then check exactly one entry.
+//! The map stores one pair of displacements for every five entries, so the
+//! space overhead over a plain slice of entries is small.
+
+use core::{
+    borrow::Borrow,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::Index,
+    slice,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::{Entry, EntryAdapter, IteratorLengthMismatch},
+    hash::FxHasher64,
+    primitive::{ArchivedU32, ArchivedU64},
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable, Serialize,
+};
+
+/// The average number of entries in each bucket.
+const LAMBDA: usize = 5;
+
+/// The maximum number of seeds to try before giving up on building a perfect
+/// hash function.
+const MAX_SEEDS: u64 = 64;
+
+fn bucket_count(len: usize) -> usize {
+    len.div_ceil(LAMBDA)
+}
+
+fn seed_for_attempt(attempt: u64) -> u64 {
+    attempt.wrapping_mul(0x9e37_79b9_7f4a_7c15)
+}
+
+#[derive(Clone, Copy)]
+struct Hashes {
+    g: u32,
+    f1: u32,
+    f2: u32,
+}
+
+impl Hashes {
+    fn new<Q, H>(seed: u64, key: &Q) -> Self
+    where
+        Q: Hash + ?Sized,
+        H: Hasher + Default,
+    {
+        let mut state = H::default();
+        state.write_u64(seed);
+        key.hash(&mut state);
+        let hash = state.finish();
+
+        // Mix the hash so that the bucket is independent of the displacement
+        // hashes even if the hasher has poor avalanche.
+        let mut mixed = hash;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        mixed ^= mixed >> 31;
+
+        Self {
+            g: mixed as u32,
+            f1: hash as u32,
+            f2: (hash >> 32) as u32,
+        }
+    }
+
+    fn bucket(&self, buckets: usize) -> usize {
+        (self.g as u64 % buckets as u64) as usize
+    }
+
+    fn index(&self, d1: u32, d2: u32, len: usize) -> usize {
+        let displaced = d2
+            .wrapping_add(self.f1.wrapping_mul(d1))
+            .wrapping_add(self.f2);
+        (displaced as u64 % len as u64) as usize
+    }
+}
+
+/// An archived hash map with guaranteed single-probe lookups.
+///
+/// Unlike [`ArchivedHashMap`](crate::collections::swiss_table::ArchivedHashMap)
+/// the perfect hash function is computed when the map is serialized, which
+/// takes longer but makes every lookup check exactly one entry.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedPhfMap<K, V, H = FxHasher64> {
+    seed: ArchivedU64,
+    displacements: ArchivedVec<[ArchivedU32; 2]>,
+    entries: ArchivedVec<Entry<K, V>>,
+    _phantom: PhantomData<H>,
+}
+
+impl<K, V, H> ArchivedPhfMap<K, V, H> {
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the key-value pairs of the map in slot order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map in slot order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values of the map in slot order.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+}
+
+impl<K, V, H: Hasher + Default> ArchivedPhfMap<K, V, H> {
+    fn find<Q>(&self, key: &Q) -> Option<&Entry<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        if self.is_empty() {
+            return None;
+        }
+
+        let hashes = Hashes::new::<Q, H>(self.seed.to_native(), key);
+        let displacements = self.displacements.as_slice();
+        let [d1, d2] = displacements[hashes.bucket(displacements.len())];
+        let index = hashes.index(d1.to_native(), d2.to_native(), self.len());
+        let entry = &self.entries[index];
+        (entry.key.borrow() == key).then_some(entry)
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map(|entry| &entry.value)
+    }
+
+    /// Returns whether the map contains the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Serializes an iterator of key-value pairs as a perfect hash map.
+    ///
+    /// The keys yielded by the iterator must be unique.
+    pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<PhfMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+        let buckets = bucket_count(len);
+
+        SerVec::with_capacity(serializer, len, |items, serializer| {
+            let mut iter = iter;
+            for item in iter.by_ref().take(len) {
+                items.push(item);
+            }
+            if items.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: items.len(),
+                });
+            }
+            let leftovers = iter.count();
+            if leftovers != 0 {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: len + leftovers,
+                });
+            }
+
+            SerVec::with_capacity(serializer, len, |keys, serializer| {
+                SerVec::with_capacity(
+                    serializer,
+                    buckets,
+                    |runs, serializer| {
+                        SerVec::with_capacity(
+                            serializer,
+                            len,
+                            |slots, serializer| {
+                                SerVec::with_capacity(
+                                    serializer,
+                                    buckets,
+                                    |displacements, serializer| {
+                                        let seed = find_seed::<_, H, _>(
+                                            items
+                                                .iter()
+                                                .map(|(key, _)| key.borrow()),
+                                            keys,
+                                            runs,
+                                            slots,
+                                            displacements,
+                                        )?;
+
+                                        let displacements =
+                                            ArchivedVec::serialize_from_slice(
+                                                displacements.as_slice(),
+                                                serializer,
+                                            )?;
+                                        let entries =
+                                            ArchivedVec::serialize_from_iter::<
+                                                EntryAdapter<&KU, &VU, KU, VU>,
+                                                _,
+                                                _,
+                                            >(
+                                                slots.iter().map(|slot| {
+                                                    let (key, value) =
+                                                        &items[slot.index];
+                                                    EntryAdapter::new(
+                                                        key.borrow(),
+                                                        value.borrow(),
+                                                    )
+                                                }),
+                                                serializer,
+                                            )?;
+
+                                        Ok(PhfMapResolver {
+                                            seed,
+                                            displacements,
+                                            entries,
+                                        })
+                                    },
+                                )?
+                            },
+                        )?
+                    },
+                )?
+            })?
+        })?
+    }
+
+    /// Resolves an archived perfect hash map from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: PhfMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedPhfMap {
+            seed,
+            displacements,
+            entries,
+            _phantom: _,
+        } = out);
+        resolver.seed.resolve((), seed);
+        ArchivedVec::resolve_from_len(
+            bucket_count(len),
+            resolver.displacements,
+            displacements,
+        );
+        ArchivedVec::resolve_from_len(len, resolver.entries, entries);
+    }
+}
+
+struct KeyHash {
+    index: usize,
+    bucket: usize,
+    hashes: Hashes,
+}
+
+struct Run {
+    bucket: usize,
+    start: usize,
+    len: usize,
+}
+
+struct Slot {
+    index: usize,
+    generation: u64,
+}
+
+/// Tries seeds until one yields a perfect hash function for the given keys.
+fn find_seed<'a, Q, H, E>(
+    keys: impl Iterator<Item = &'a Q> + Clone,
+    hashes: &mut SerVec<KeyHash>,
+    runs: &mut SerVec<Run>,
+    slots: &mut SerVec<Slot>,
+    displacements: &mut SerVec<[u32; 2]>,
+) -> Result<u64, E>
+where
+    Q: Hash + 'a + ?Sized,
+    H: Hasher + Default,
+    E: Source,
+{
+    for attempt in 0..MAX_SEEDS {
+        let seed = seed_for_attempt(attempt);
+
+        hashes.clear();
+        for (index, key) in keys.clone().enumerate() {
+            let key_hashes = Hashes::new::<Q, H>(seed, key);
+            hashes.push(KeyHash {
+                index,
+                bucket: key_hashes.bucket(displacements.capacity()),
+                hashes: key_hashes,
+            });
+        }
+
+        if solve(hashes, runs, slots, displacements) {
+            return Ok(seed);
+        }
+    }
+
+    fail!(PhfConstructionFailed { len: hashes.len() })
+}
+
+/// Tries to find displacements which place every key in a unique slot.
+///
+/// On success, `slots` holds the index of the key in each slot and
+/// `displacements` holds the displacements for each bucket.
+fn solve(
+    keys: &mut SerVec<KeyHash>,
+    runs: &mut SerVec<Run>,
+    slots: &mut SerVec<Slot>,
+    displacements: &mut SerVec<[u32; 2]>,
+) -> bool {
+    let len = keys.len();
+
+    // Group the keys by bucket. Keys with identical hashes can never be
+    // separated, so fail early if there are any.
+    keys.sort_unstable_by_key(|key| (key.bucket, key.hashes.f1, key.hashes.f2));
+    for pair in keys.windows(2) {
+        if pair[0].bucket == pair[1].bucket
+            && pair[0].hashes.f1 == pair[1].hashes.f1
+            && pair[0].hashes.f2 == pair[1].hashes.f2
+        {
+            return false;
+        }
+    }
+
+    // Place the largest buckets first while there are many free slots.
+    runs.clear();
+    let mut start = 0;
+    while start < len {
+        let bucket = keys[start].bucket;
+        let run_len = keys[start..]
+            .iter()
+            .take_while(|key| key.bucket == bucket)
+            .count();
+        runs.push(Run {
+            bucket,
+            start,
+            len: run_len,
+        });
+        start += run_len;
+    }
+    runs.sort_unstable_by(|a, b| {
+        b.len.cmp(&a.len).then(a.bucket.cmp(&b.bucket))
+    });
+
+    slots.clear();
+    for _ in 0..len {
+        slots.push(Slot {
+            index: usize::MAX,
+            generation: 0,
+        });
+    }
+    displacements.clear();
+    for _ in 0..displacements.capacity() {
+        displacements.push([0, 0]);
+    }
+
+    let max_displacement = u32::try_from(len).unwrap_or(u32::MAX);
+    let mut generation = 0;
+    'runs: for run in runs.iter() {
+        let bucket_keys = &keys[run.start..run.start + run.len];
+        for d1 in 0..max_displacement {
+            'displacements: for d2 in 0..max_displacement {
+                generation += 1;
+                for key in bucket_keys {
+                    let slot = &mut slots[key.hashes.index(d1, d2, len)];
+                    if slot.index != usize::MAX || slot.generation == generation
+                    {
+                        continue 'displacements;
+                    }
+                    slot.generation = generation;
+                }
+
+                for key in bucket_keys {
+                    slots[key.hashes.index(d1, d2, len)].index = key.index;
+                }
+                displacements[run.bucket] = [d1, d2];
+                continue 'runs;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+#[derive(Debug)]
+struct PhfConstructionFailed {
+    len: usize,
+}
+
+impl fmt::Display for PhfConstructionFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to find a perfect hash function for {} keys; the keys may \
+             not be unique",
+            self.len,
+        )
+    }
+}
+
+impl Error for PhfConstructionFailed {}
+
+impl<K: fmt::Debug, V: fmt::Debug, H> fmt::Debug for ArchivedPhfMap<K, V, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, Q, V, H> Index<&Q> for ArchivedPhfMap<K, V, H>
+where
+    K: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    H: Hasher + Default,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+impl<'a, K, V, H> IntoIterator for &'a ArchivedPhfMap<K, V, H> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V, H> Eq for ArchivedPhfMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: Eq,
+    H: Hasher + Default,
+{
+}
+
+impl<K, V, H> PartialEq for ArchivedPhfMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    H: Hasher + Default,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key).is_some_and(|v| value == v))
+    }
+}
+
+/// An iterator over the key-value pairs of an [`ArchivedPhfMap`].
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// An iterator over the keys of an [`ArchivedPhfMap`].
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of an [`ArchivedPhfMap`].
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// The resolver for [`ArchivedPhfMap`].
+pub struct PhfMapResolver {
+    seed: u64,
+    displacements: VecResolver,
+    entries: VecResolver,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::{bucket_count, ArchivedPhfMap};
+
+    #[derive(Debug)]
+    struct InvalidDisplacements {
+        len: usize,
+        displacements: usize,
+    }
+
+    impl fmt::Display for InvalidDisplacements {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "archived perfect hash map of length {} has {} displacements",
+                self.len, self.displacements,
+            )
+        }
+    }
+
+    impl Error for InvalidDisplacements {}
+
+    unsafe impl<K, V, H, C> Verify<C> for ArchivedPhfMap<K, V, H>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            let displacements = self.displacements.len();
+            if displacements != bucket_count(len) {
+                fail!(InvalidDisplacements { len, displacements });
+            }
+            Ok(())
+        }
+    }
+}
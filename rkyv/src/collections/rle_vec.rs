@@ -0,0 +1,317 @@
+//! An archived vec compressed with run-length encoding.
+//!
+//! [`ArchivedRleVec`] stores each run of equal consecutive values once, along
+//! with the index where the run ends. Elements are found with a binary search
+//! over the runs, so [`get`](ArchivedRleVec::get) takes `O(log runs)` time.
+//! This is a good fit for very long vecs which are mostly made of a few
+//! repeated values.
+
+use core::{borrow::Borrow, fmt, iter::FusedIterator, slice};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::{Entry, EntryAdapter, IteratorLengthMismatch},
+    primitive::ArchivedUsize,
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable, Serialize,
+};
+
+/// An archived vec compressed with run-length encoding.
+///
+/// Each run is stored as an entry whose key is the index one past the end of
+/// the run and whose value is the repeated value.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedRleVec<T> {
+    runs: ArchivedVec<Entry<ArchivedUsize, T>>,
+}
+
+impl<T> ArchivedRleVec<T> {
+    fn end(run: &Entry<ArchivedUsize, T>) -> usize {
+        run.key.to_native() as usize
+    }
+
+    /// Returns the number of elements in the vec.
+    pub fn len(&self) -> usize {
+        self.runs.last().map_or(0, Self::end)
+    }
+
+    /// Returns whether the vec is empty.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Returns the number of runs in the vec.
+    pub fn run_count(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Returns the element at the given index, or `None` if the index is out
+    /// of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let run = self.runs.partition_point(|run| Self::end(run) <= index);
+        self.runs.get(run).map(|run| &run.value)
+    }
+
+    /// Returns an iterator over the elements of the vec.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            runs: self.runs.as_slice(),
+            run: 0,
+            index: 0,
+            len: self.len(),
+        }
+    }
+
+    /// Returns an iterator over the runs of the vec, yielding the length and
+    /// value of each run.
+    pub fn runs(&self) -> Runs<'_, T> {
+        Runs {
+            inner: self.runs.iter(),
+            start: 0,
+        }
+    }
+
+    /// Serializes an archived RLE vec from an iterator of values.
+    ///
+    /// Consecutive values which are equal are combined into a single run.
+    pub fn serialize_from_iter<I, BU, U, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<RleVecResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = BU>,
+        BU: Borrow<U>,
+        U: Serialize<S, Archived = T> + PartialEq,
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::<(usize, BU)>::with_capacity(
+            serializer,
+            len,
+            |runs, serializer| {
+                let mut iter = iter;
+                let mut count = 0;
+                for value in iter.by_ref().take(len) {
+                    count += 1;
+                    match runs.as_mut_slice().last_mut() {
+                        Some((end, last))
+                            if Borrow::<U>::borrow(&*last)
+                                == value.borrow() =>
+                        {
+                            *end = count;
+                        }
+                        _ => runs.push((count, value)),
+                    }
+                }
+                let leftovers = iter.count();
+                if count != len || leftovers != 0 {
+                    fail!(IteratorLengthMismatch {
+                        expected: len,
+                        actual: count + leftovers,
+                    });
+                }
+
+                let runs_len = runs.len();
+                let resolver = ArchivedVec::serialize_from_iter::<
+                    EntryAdapter<usize, &U, usize, U>,
+                    _,
+                    _,
+                >(
+                    runs.iter().map(|(end, value)| {
+                        EntryAdapter::new(*end, value.borrow())
+                    }),
+                    serializer,
+                )?;
+
+                Ok(RleVecResolver {
+                    runs: resolver,
+                    runs_len,
+                })
+            },
+        )?
+    }
+
+    /// Resolves an archived RLE vec from a resolver.
+    pub fn resolve_from_resolver(resolver: RleVecResolver, out: Place<Self>) {
+        munge!(let ArchivedRleVec { runs } = out);
+        ArchivedVec::resolve_from_len(resolver.runs_len, resolver.runs, runs);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedRleVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedRleVec<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PartialEq> PartialEq for ArchivedRleVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for ArchivedRleVec<T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedRleVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len()
+            && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+/// An iterator over the elements of an [`ArchivedRleVec`].
+pub struct Iter<'a, T> {
+    runs: &'a [Entry<ArchivedUsize, T>],
+    run: usize,
+    index: usize,
+    len: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.len {
+            return None;
+        }
+
+        while ArchivedRleVec::end(&self.runs[self.run]) <= self.index {
+            self.run += 1;
+        }
+        self.index += 1;
+        Some(&self.runs[self.run].value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            runs: self.runs,
+            run: self.run,
+            index: self.index,
+            len: self.len,
+        }
+    }
+}
+
+/// An iterator over the runs of an [`ArchivedRleVec`].
+pub struct Runs<'a, T> {
+    inner: slice::Iter<'a, Entry<ArchivedUsize, T>>,
+    start: usize,
+}
+
+impl<'a, T> Iterator for Runs<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let run = self.inner.next()?;
+        let end = ArchivedRleVec::end(run);
+        let len = end - self.start;
+        self.start = end;
+        Some((len, &run.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Runs<'_, T> {}
+
+impl<T> FusedIterator for Runs<'_, T> {}
+
+impl<T> Clone for Runs<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            start: self.start,
+        }
+    }
+}
+
+/// The resolver for [`ArchivedRleVec`].
+pub struct RleVecResolver {
+    runs: VecResolver,
+    runs_len: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::ArchivedRleVec;
+
+    #[derive(Debug)]
+    struct InvalidRun {
+        index: usize,
+    }
+
+    impl fmt::Display for InvalidRun {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "run {} of archived RLE vec is empty or ends before the \
+                 previous run",
+                self.index,
+            )
+        }
+    }
+
+    impl Error for InvalidRun {}
+
+    unsafe impl<T, C> Verify<C> for ArchivedRleVec<T>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let mut previous = 0;
+            for (index, run) in self.runs.iter().enumerate() {
+                let end = Self::end(run);
+                if end <= previous {
+                    fail!(InvalidRun { index });
+                }
+                previous = end;
+            }
+
+            Ok(())
+        }
+    }
+}
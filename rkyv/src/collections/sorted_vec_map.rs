@@ -0,0 +1,278 @@
+//! An archived map backed by a sorted vec.
+//!
+//! [`ArchivedSortedVecMap`] stores its entries in a single vec sorted by key,
+//! and finds keys with a binary search. For maps with only a few entries, this
+//! is smaller than an archived hash map or B-tree map and about as fast to
+//! search.
+
+use core::{borrow::Borrow, fmt, iter::FusedIterator, slice};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::{Entry, EntryAdapter, IteratorLengthMismatch},
+    ser::{Allocator, Writer},
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Place, Portable, Serialize,
+};
+
+/// An archived map whose entries are stored in a vec sorted by key.
+#[derive(Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedSortedVecMap<K, V> {
+    entries: ArchivedVec<Entry<K, V>>,
+}
+
+impl<K, V> ArchivedSortedVecMap<K, V> {
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the entries of the map, sorted by key.
+    pub fn entries(&self) -> &[Entry<K, V>] {
+        self.entries.as_slice()
+    }
+
+    /// Returns an iterator over the keys and values of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map in sorted order.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values of the map, sorted by key.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Returns the key and value corresponding to the supplied key.
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let index = self
+            .entries
+            .binary_search_by(|entry| entry.key.borrow().cmp(key))
+            .ok()?;
+        let entry = &self.entries[index];
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns the value corresponding to the supplied key.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Some(self.get_key_value(key)?.1)
+    }
+
+    /// Returns whether the map contains the given key.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Serializes an iterator of key-value pairs as a sorted vec map.
+    ///
+    /// The pairs may be yielded in any order. If the iterator yields the same
+    /// key more than once, looking up that key may return any of its values.
+    pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<SortedVecMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Ord,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |items, serializer| {
+            let mut iter = iter;
+            for item in iter.by_ref().take(len) {
+                items.push(item);
+            }
+            if items.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: items.len(),
+                });
+            }
+            let leftovers = iter.count();
+            if leftovers != 0 {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: len + leftovers,
+                });
+            }
+
+            items.sort_unstable_by(|(a, _), (b, _)| a.borrow().cmp(b.borrow()));
+
+            let entries = ArchivedVec::serialize_from_iter::<
+                EntryAdapter<&KU, &VU, KU, VU>,
+                _,
+                _,
+            >(
+                items.iter().map(|(key, value)| {
+                    EntryAdapter::new(key.borrow(), value.borrow())
+                }),
+                serializer,
+            )?;
+
+            Ok(SortedVecMapResolver { entries })
+        })?
+    }
+
+    /// Resolves an archived sorted vec map from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: SortedVecMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedSortedVecMap { entries } = out);
+        ArchivedVec::resolve_from_len(len, resolver.entries, entries);
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedSortedVecMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ArchivedSortedVecMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedSortedVecMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries() == other.entries()
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedSortedVecMap<K, V> {}
+
+/// An iterator over the keys and values of an [`ArchivedSortedVecMap`].
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Entry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// An iterator over the keys of an [`ArchivedSortedVecMap`].
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(key, _)| key)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+/// An iterator over the values of an [`ArchivedSortedVecMap`].
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, value)| value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+/// The resolver for [`ArchivedSortedVecMap`].
+pub struct SortedVecMapResolver {
+    entries: VecResolver,
+}
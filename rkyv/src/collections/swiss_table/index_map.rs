@@ -518,11 +518,98 @@ mod verify {
                 self.table.len(),
             );
 
-            context.in_subtree(ptr, |context| {
-                // SAFETY: `in_subtree` has checked that `ptr` is aligned and
-                // points to enough bytes to represent its slice.
-                unsafe { <[Entry<K, V>]>::check_bytes(ptr, context) }
-            })
+            // SAFETY: `check_bytes` is safe to call on a pointer which is
+            // properly aligned and points to enough bytes for the slice.
+            unsafe {
+                context.in_subtree_deferred(ptr, <[Entry<K, V>]>::check_bytes)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use rancor::{Fallible, Source};
+
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        api::test::to_archived,
+        collections::swiss_table::{ArchivedIndexMap, IndexMapResolver},
+        primitive::ArchivedU32,
+        ser::{Allocator, Writer},
+        string::ArchivedString,
+        Archive, Place, Serialize,
+    };
+
+    struct OrderedPairs(Vec<(String, u32)>);
+
+    impl Archive for OrderedPairs {
+        type Archived = ArchivedIndexMap<ArchivedString, ArchivedU32>;
+        type Resolver = IndexMapResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            ArchivedIndexMap::resolve_from_len(
+                self.0.len(),
+                (7, 8),
+                resolver,
+                out,
+            );
         }
     }
+
+    impl<S> Serialize<S> for OrderedPairs
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            Self::Archived::serialize_from_iter::<_, _, _, String, u32, _>(
+                self.0.iter().map(|(k, v)| (k, v)),
+                (7, 8),
+                serializer,
+            )
+        }
+    }
+
+    fn pairs() -> OrderedPairs {
+        OrderedPairs(
+            ["zeta", "alpha", "mu", "beta", "omega"]
+                .iter()
+                .enumerate()
+                .map(|(i, k)| (String::from(*k), i as u32))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn preserves_order() {
+        let value = pairs();
+        to_archived(&value, |b| {
+            assert_eq!(value.0.len(), b.len());
+            for (i, ((ak, av), (bk, bv))) in
+                value.0.iter().zip(b.iter()).enumerate()
+            {
+                assert_eq!(ak, bk);
+                assert_eq!(*av, bv.to_native());
+                assert_eq!(b.get_index_of(ak.as_str()), Some(i));
+                assert_eq!(b.get(ak.as_str()), Some(bv));
+            }
+            assert!(b.get("missing").is_none());
+        });
+    }
+
+    #[test]
+    fn empty() {
+        to_archived(&OrderedPairs(Vec::new()), |archived| {
+            assert!(archived.is_empty());
+            assert_eq!(archived.iter().count(), 0);
+        });
+    }
 }
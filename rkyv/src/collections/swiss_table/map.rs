@@ -6,7 +6,9 @@ use core::{
     hash::{Hash, Hasher},
     iter::FusedIterator,
     marker::PhantomData,
+    mem::replace,
     ops::Index,
+    ptr::addr_of_mut,
 };
 
 use munge::munge;
@@ -14,12 +16,15 @@ use rancor::{Fallible, Source};
 
 use crate::{
     collections::{
-        swiss_table::table::{ArchivedHashTable, HashTableResolver, RawIter},
+        swiss_table::table::{
+            ArchivedHashTable, HashTableOptions, HashTableResolver, RawIter,
+        },
         util::{Entry, EntryAdapter},
     },
     hash::{hash_value, FxHasher64},
     seal::Seal,
     ser::{Allocator, Writer},
+    traits::NoUndef,
     Place, Portable, Serialize,
 };
 
@@ -90,6 +95,57 @@ impl<K, V, H> ArchivedHashMap<K, V, H> {
             _phantom: PhantomData,
         }
     }
+
+    /// Returns the key-value pair whose key has the given hash and satisfies
+    /// the given equality function.
+    ///
+    /// The hash must be computed the same way as the hashes of the keys the
+    /// map was serialized from: with [`hash_value`] and the map's hasher `H`.
+    /// This avoids re-hashing keys whose hashes are already known, and allows
+    /// looking up keys of types which can't be borrowed from `K`.
+    pub fn get_key_value_with_hash<C>(
+        &self,
+        hash: u64,
+        eq: C,
+    ) -> Option<(&K, &V)>
+    where
+        C: Fn(&K) -> bool,
+    {
+        let entry = self.table.get_with(hash, |e| eq(&e.key))?;
+        Some((&entry.key, &entry.value))
+    }
+
+    /// Returns a reference to the value whose key has the given hash and
+    /// satisfies the given equality function.
+    ///
+    /// See [`get_key_value_with_hash`](Self::get_key_value_with_hash) for
+    /// details about how the hash must be computed.
+    pub fn get_with_hash<C>(&self, hash: u64, eq: C) -> Option<&V>
+    where
+        C: Fn(&K) -> bool,
+    {
+        Some(self.get_key_value_with_hash(hash, eq)?.1)
+    }
+
+    /// Returns the mutable key-value pair whose key has the given hash and
+    /// satisfies the given equality function.
+    ///
+    /// See [`get_key_value_with_hash`](Self::get_key_value_with_hash) for
+    /// details about how the hash must be computed.
+    pub fn get_key_value_seal_with_hash<C>(
+        this: Seal<'_, Self>,
+        hash: u64,
+        eq: C,
+    ) -> Option<(&K, Seal<'_, V>)>
+    where
+        C: Fn(&K) -> bool,
+    {
+        munge!(let Self { table, .. } = this);
+        let entry =
+            ArchivedHashTable::get_seal_with(table, hash, |e| eq(&e.key))?;
+        munge!(let Entry { key, value } = entry);
+        Some((key.unseal_ref(), value))
+    }
 }
 
 impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
@@ -100,10 +156,7 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
-        let entry = self
-            .table
-            .get_with(hash_value::<Q, H>(key), |e| cmp(key, &e.key))?;
-        Some((&entry.key, &entry.value))
+        self.get_key_value_with_hash(hash_value::<Q, H>(key), |k| cmp(key, k))
     }
 
     /// Returns the key-value pair corresponding to the supplied key.
@@ -146,14 +199,9 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         Q: Hash + Eq + ?Sized,
         C: Fn(&Q, &K) -> bool,
     {
-        munge!(let Self { table, .. } = this);
-        let entry = ArchivedHashTable::get_seal_with(
-            table,
-            hash_value::<Q, H>(key),
-            |e| cmp(key, &e.key),
-        )?;
-        munge!(let Entry { key, value } = entry);
-        Some((key.unseal_ref(), value))
+        Self::get_key_value_seal_with_hash(this, hash_value::<Q, H>(key), |k| {
+            cmp(key, k)
+        })
     }
 
     /// Returns the mutable key-value pair corresponding to the supplied key.
@@ -202,6 +250,87 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         self.get(key).is_some()
     }
 
+    /// Removes the entry corresponding to the supplied key from the sealed
+    /// hash map using the given comparison function.
+    ///
+    /// Returns whether an entry was removed.
+    pub fn remove_seal_with<Q, C>(this: Seal<'_, Self>, key: &Q, cmp: C) -> bool
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        munge!(let Self { table, .. } = this);
+        ArchivedHashTable::remove_seal_with(
+            table,
+            hash_value::<Q, H>(key),
+            |e| cmp(key, &e.key),
+        )
+    }
+
+    /// Removes the entry corresponding to the supplied key from the sealed
+    /// hash map.
+    ///
+    /// The removed entry's bucket is marked as deleted rather than empty, so it
+    /// can only be reused by later calls to [`insert_seal`](Self::insert_seal)
+    /// for keys whose probe sequence passes through it. Re-inserting a removed
+    /// key always reuses capacity this way. Returns whether an entry was
+    /// removed.
+    pub fn remove_seal<Q>(this: Seal<'_, Self>, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Self::remove_seal_with(this, key, |q, k| q == k.borrow())
+    }
+
+    /// Inserts a key-value pair into the sealed hash map.
+    ///
+    /// If the map already contains the key, its value is replaced and the old
+    /// value is returned. Otherwise, the entry is written into a free bucket.
+    /// Sealed hash maps can't grow, so new entries must fit into the spare
+    /// capacity the map was serialized with. Maps serialized with a lower load
+    /// factor (see [`serialize_from_iter`](Self::serialize_from_iter)) have
+    /// more spare capacity. If no free bucket is available, an error is
+    /// returned.
+    ///
+    /// Only keys and values which can be moved (i.e. which do not contain
+    /// relative pointers) can be inserted.
+    pub fn insert_seal<E>(
+        this: Seal<'_, Self>,
+        key: K,
+        value: V,
+    ) -> Result<Option<V>, E>
+    where
+        K: Hash + Eq + NoUndef + Unpin,
+        V: NoUndef + Unpin,
+        E: Source,
+    {
+        let hash = hash_value::<K, H>(&key);
+        munge!(let Self { mut table, .. } = this);
+
+        if let Some(entry) =
+            ArchivedHashTable::get_seal_with(table.as_mut(), hash, |e| {
+                e.key == key
+            })
+        {
+            munge!(let Entry { value: old, .. } = entry);
+            return Ok(Some(replace(old.unseal(), value)));
+        }
+
+        // SAFETY: We checked that the table does not contain `key`, and both
+        // fields of the entry are initialized. Padding bytes are left as they
+        // were, so they remain initialized.
+        unsafe {
+            ArchivedHashTable::insert_seal_unchecked(table, hash, |bucket| {
+                let entry = bucket.as_ptr();
+                addr_of_mut!((*entry).key).write(key);
+                addr_of_mut!((*entry).value).write(value);
+            })?;
+        }
+
+        Ok(None)
+    }
+
     /// Serializes an iterator of key-value pairs as a hash map.
     pub fn serialize_from_iter<I, BKU, BVU, KU, VU, S>(
         iter: I,
@@ -227,6 +356,103 @@ impl<K, V, H: Hasher + Default> ArchivedHashMap<K, V, H> {
         .map(HashMapResolver)
     }
 
+    /// Serializes an iterator of key-value pairs as a hash map with the given
+    /// options.
+    ///
+    /// The load factor of `options` must also be passed to
+    /// [`resolve_from_len`](Self::resolve_from_len).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rkyv::{
+    ///     collections::swiss_table::{
+    ///         ArchivedHashMap, HashMapResolver, HashTableOptions,
+    ///     },
+    ///     rancor::{Error, Fallible, Source},
+    ///     ser::{Allocator, Writer},
+    ///     Archive, Archived, Place, Serialize,
+    /// };
+    ///
+    /// const OPTIONS: HashTableOptions = HashTableOptions {
+    ///     load_factor: (1, 2),
+    ///     sort_by_bucket: true,
+    /// };
+    ///
+    /// type ArchivedIndex = ArchivedHashMap<Archived<u32>, Archived<u32>>;
+    ///
+    /// struct Index(HashMap<u32, u32>);
+    ///
+    /// impl Archive for Index {
+    ///     type Archived = ArchivedIndex;
+    ///     type Resolver = HashMapResolver;
+    ///
+    ///     fn resolve(
+    ///         &self,
+    ///         resolver: HashMapResolver,
+    ///         out: Place<ArchivedIndex>,
+    ///     ) {
+    ///         ArchivedHashMap::resolve_from_len(
+    ///             self.0.len(),
+    ///             OPTIONS.load_factor,
+    ///             resolver,
+    ///             out,
+    ///         );
+    ///     }
+    /// }
+    ///
+    /// impl<S> Serialize<S> for Index
+    /// where
+    ///     S: Fallible + Writer + Allocator + ?Sized,
+    ///     S::Error: Source,
+    /// {
+    ///     fn serialize(
+    ///         &self,
+    ///         serializer: &mut S,
+    ///     ) -> Result<HashMapResolver, S::Error> {
+    ///         ArchivedIndex::serialize_from_iter_with_options::<
+    ///             _,
+    ///             _,
+    ///             _,
+    ///             u32,
+    ///             u32,
+    ///             _,
+    ///         >(self.0.iter(), OPTIONS, serializer)
+    ///     }
+    /// }
+    ///
+    /// let index = Index((0..100).map(|i| (i, i * i)).collect());
+    /// let bytes = rkyv::to_bytes::<Error>(&index).unwrap();
+    /// let archived = rkyv::access::<ArchivedIndex, Error>(&bytes).unwrap();
+    /// assert!(archived.capacity() >= 200);
+    /// assert_eq!(archived.get(&9.into()).unwrap(), &81);
+    /// ```
+    pub fn serialize_from_iter_with_options<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        options: HashTableOptions,
+        serializer: &mut S,
+    ) -> Result<HashMapResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = K> + Hash + Eq,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        ArchivedHashTable::<Entry<K, V>>::serialize_from_iter_with_options(
+            iter.clone()
+                .map(|(key, value)| EntryAdapter::new(key, value)),
+            iter.map(|(key, _)| hash_value::<KU, H>(key.borrow())),
+            options,
+            serializer,
+        )
+        .map(HashMapResolver)
+    }
+
     /// Resolves an archived hash map from a given length and parameters.
     pub fn resolve_from_len(
         len: usize,
@@ -10,4 +10,4 @@ pub use index_map::{ArchivedIndexMap, IndexMapResolver};
 pub use index_set::{ArchivedIndexSet, IndexSetResolver};
 pub use map::{ArchivedHashMap, HashMapResolver};
 pub use set::{ArchivedHashSet, HashSetResolver};
-pub use table::{ArchivedHashTable, HashTableResolver};
+pub use table::{ArchivedHashTable, HashTableOptions, HashTableResolver};
@@ -4,6 +4,7 @@ use core::{
     borrow::Borrow,
     fmt,
     hash::{Hash, Hasher},
+    iter::{Chain, FusedIterator},
 };
 
 use munge::munge;
@@ -38,12 +39,44 @@ impl<K, H> ArchivedHashSet<K, H> {
     }
 
     /// Gets an iterator over the keys of the underlying hash map.
-    pub fn iter(&self) -> Keys<K, (), H> {
+    pub fn iter(&self) -> Keys<'_, K, (), H> {
         self.inner.keys()
     }
+
+    /// Gets the key which has the given hash and satisfies the given equality
+    /// function.
+    ///
+    /// See [`ArchivedHashMap::get_key_value_with_hash`] for details about how
+    /// the hash must be computed.
+    pub fn get_with_hash<C>(&self, hash: u64, eq: C) -> Option<&K>
+    where
+        C: Fn(&K) -> bool,
+    {
+        self.inner.get_key_value_with_hash(hash, eq).map(|(k, _)| k)
+    }
 }
 
 impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
+    /// Gets the key corresponding to the given key in the hash set using the
+    /// given comparison function.
+    pub fn get_with<Q, C>(&self, k: &Q, cmp: C) -> Option<&K>
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        self.inner.get_key_value_with(k, cmp).map(|(k, _)| k)
+    }
+
+    /// Returns whether the given key is in the hash set using the given
+    /// comparison function.
+    pub fn contains_with<Q, C>(&self, k: &Q, cmp: C) -> bool
+    where
+        Q: Hash + Eq + ?Sized,
+        C: Fn(&Q, &K) -> bool,
+    {
+        self.get_with(k, cmp).is_some()
+    }
+
     /// Gets the key corresponding to the given key in the hash set.
     pub fn get<Q>(&self, k: &Q) -> Option<&K>
     where
@@ -62,6 +95,84 @@ impl<K, H: Hasher + Default> ArchivedHashSet<K, H> {
         self.inner.contains_key(k)
     }
 
+    /// Returns an iterator over the keys which are in `self` but not in
+    /// `other`.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, K, H>
+    where
+        K: Hash + Eq,
+    {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Returns an iterator over the keys which are in `self` or `other`, but
+    /// not in both.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> SymmetricDifference<'a, K, H>
+    where
+        K: Hash + Eq,
+    {
+        SymmetricDifference {
+            iter: self.difference(other).chain(other.difference(self)),
+        }
+    }
+
+    /// Returns an iterator over the keys which are in both `self` and `other`.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, K, H>
+    where
+        K: Hash + Eq,
+    {
+        // Iterate over the smaller set and check membership in the larger one
+        let (smaller, larger) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        Intersection {
+            iter: smaller.iter(),
+            other: larger,
+        }
+    }
+
+    /// Returns an iterator over the keys which are in `self` or `other`,
+    /// without duplicates.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, K, H>
+    where
+        K: Hash + Eq,
+    {
+        Union {
+            iter: self.iter().chain(other.difference(self)),
+        }
+    }
+
+    /// Returns whether `self` and `other` have no keys in common.
+    pub fn is_disjoint(&self, other: &Self) -> bool
+    where
+        K: Hash + Eq,
+    {
+        self.intersection(other).next().is_none()
+    }
+
+    /// Returns whether every key in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Self) -> bool
+    where
+        K: Hash + Eq,
+    {
+        self.len() <= other.len() && self.iter().all(|k| other.contains(k))
+    }
+
+    /// Returns whether every key in `other` is also in `self`.
+    pub fn is_superset(&self, other: &Self) -> bool
+    where
+        K: Hash + Eq,
+    {
+        other.is_subset(self)
+    }
+
     /// Resolves an archived hash set from the given length and parameters.
     pub fn resolve_from_len(
         len: usize,
@@ -113,5 +224,135 @@ impl<K: Hash + Eq, H: Hasher + Default> PartialEq for ArchivedHashSet<K, H> {
 
 impl<K: Hash + Eq, H: Hasher + Default> Eq for ArchivedHashSet<K, H> {}
 
+/// An iterator over the difference of two archived hash sets.
+///
+/// This struct is created by the
+/// [`difference`](ArchivedHashSet::difference) method on [`ArchivedHashSet`].
+pub struct Difference<'a, K, H> {
+    iter: Keys<'a, K, (), H>,
+    other: &'a ArchivedHashSet<K, H>,
+}
+
+impl<'a, K, H> Iterator for Difference<'a, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|k| !other.contains(*k))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, H> FusedIterator for Difference<'_, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+}
+
+/// An iterator over the symmetric difference of two archived hash sets.
+///
+/// This struct is created by the
+/// [`symmetric_difference`](ArchivedHashSet::symmetric_difference) method on
+/// [`ArchivedHashSet`].
+pub struct SymmetricDifference<'a, K, H> {
+    iter: Chain<Difference<'a, K, H>, Difference<'a, K, H>>,
+}
+
+impl<'a, K, H> Iterator for SymmetricDifference<'a, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, H> FusedIterator for SymmetricDifference<'_, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+}
+
+/// An iterator over the intersection of two archived hash sets.
+///
+/// This struct is created by the
+/// [`intersection`](ArchivedHashSet::intersection) method on
+/// [`ArchivedHashSet`].
+pub struct Intersection<'a, K, H> {
+    iter: Keys<'a, K, (), H>,
+    other: &'a ArchivedHashSet<K, H>,
+}
+
+impl<'a, K, H> Iterator for Intersection<'a, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|k| other.contains(*k))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, H> FusedIterator for Intersection<'_, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+}
+
+/// An iterator over the union of two archived hash sets.
+///
+/// This struct is created by the [`union`](ArchivedHashSet::union) method on
+/// [`ArchivedHashSet`].
+pub struct Union<'a, K, H> {
+    iter: Chain<Keys<'a, K, (), H>, Difference<'a, K, H>>,
+}
+
+impl<'a, K, H> Iterator for Union<'a, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, H> FusedIterator for Union<'_, K, H>
+where
+    K: Hash + Eq,
+    H: Hasher + Default,
+{
+}
+
 /// The resolver for archived hash sets.
 pub struct HashSetResolver(HashMapResolver);
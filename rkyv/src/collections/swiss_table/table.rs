@@ -5,7 +5,7 @@
 //!
 //! - The number of control bytes is rounded up to a maximum group width (16)
 //!   instead of the next power of two. This reduces the number of empty buckets
-//!   on the wire. Since this collection is immutable after writing, we'll never
+//!   on the wire. Since this collection can't grow after writing, we'll never
 //!   benefit from having more buckets than we need.
 //! - Because the bucket count is not a power of two, the triangular probing
 //!   sequence simply skips any indices larger than the actual size of the
@@ -16,12 +16,40 @@
 //! - Because the available SIMD group width may be less than the maximum group
 //!   width, each probe reads N groups before striding where N is the maximum
 //!   group width divided by the SIMD group width.
+//! - Removing an item from a sealed table marks its control byte DELETED
+//!   instead of EMPTY so that probe sequences passing through it are not cut
+//!   short. Sealed insertions can reuse DELETED buckets, but must always leave
+//!   at least one EMPTY bucket so that probing terminates.
+//!
+//! # Building custom collections
+//!
+//! [`ArchivedHashTable`] is the storage used by the archived hash maps and
+//! sets, and is a supported building block for other hashed collections. It
+//! stores entries of any archived type and leaves hashing and key comparison
+//! to the caller:
+//!
+//! - [`serialize_from_iter`](ArchivedHashTable::serialize_from_iter) takes an
+//!   iterator of items and a parallel iterator of their hashes.
+//!   [`serialize_from_iter_with_options`] does the same with a
+//!   [`HashTableOptions`] to tune the load factor and insertion order.
+//! - [`resolve_from_len`](ArchivedHashTable::resolve_from_len) resolves the
+//!   table, and must be given the same length and load factor.
+//! - [`get_with`](ArchivedHashTable::get_with) probes for an entry with a hash
+//!   and a comparison function.
+//!
+//! The only requirement is that lookups compute hashes the same way as they
+//! were computed during serialization, for example with
+//! [`hash_value`](crate::hash::hash_value) and the same hasher.
+//!
+//! [`serialize_from_iter_with_options`]:
+//!     ArchivedHashTable::serialize_from_iter_with_options
 
 use core::{
     alloc::Layout,
     borrow::Borrow,
     error::Error,
     fmt,
+    iter::FusedIterator,
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
     ptr::{self, null, NonNull},
@@ -29,19 +57,103 @@ use core::{
 };
 
 use munge::munge;
-use rancor::{fail, Fallible, ResultExt as _, Source};
+use rancor::{fail, Fallible, Panic, ResultExt as _, Source};
 
 use crate::{
     collections::util::IteratorLengthMismatch,
-    primitive::{ArchivedUsize, FixedUsize},
+    primitive::{ArchivedUsize, FixedIsize, FixedUsize},
     seal::Seal,
     ser::{Allocator, Writer, WriterExt},
     simd::{Bitmask, Group, MAX_GROUP_WIDTH},
+    traits::NoUndef,
     util::SerVec,
     Archive as _, Place, Portable, RawRelPtr, Serialize,
 };
 
+const EMPTY: u8 = 0xff;
+const DELETED: u8 = 0x80;
+
 /// A low-level archived SwissTable hash table with explicit hashing.
+///
+/// See the [module docs](crate::collections::swiss_table::table) for an
+/// overview of how to build collections on top of this table.
+///
+/// # Example
+///
+/// An index of records keyed by their ids:
+///
+/// ```
+/// use rkyv::{
+///     access_unchecked,
+///     collections::swiss_table::{ArchivedHashTable, HashTableResolver},
+///     hash::{hash_value, FxHasher64},
+///     rancor::{Error, Fallible, Source},
+///     ser::{Allocator, Writer},
+///     to_bytes, Archive, Place, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Record {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// struct RecordsById(Vec<Record>);
+///
+/// const LOAD_FACTOR: (usize, usize) = (7, 8);
+///
+/// impl Archive for RecordsById {
+///     type Archived = ArchivedHashTable<ArchivedRecord>;
+///     type Resolver = HashTableResolver;
+///
+///     fn resolve(
+///         &self,
+///         resolver: Self::Resolver,
+///         out: Place<Self::Archived>,
+///     ) {
+///         ArchivedHashTable::resolve_from_len(
+///             self.0.len(),
+///             LOAD_FACTOR,
+///             resolver,
+///             out,
+///         );
+///     }
+/// }
+///
+/// impl<S> Serialize<S> for RecordsById
+/// where
+///     S: Fallible + Allocator + Writer + ?Sized,
+///     S::Error: Source,
+/// {
+///     fn serialize(
+///         &self,
+///         serializer: &mut S,
+///     ) -> Result<Self::Resolver, S::Error> {
+///         ArchivedHashTable::serialize_from_iter::<_, Record, _, _>(
+///             self.0.iter(),
+///             self.0.iter().map(|r| hash_value::<u32, FxHasher64>(&r.id)),
+///             LOAD_FACTOR,
+///             serializer,
+///         )
+///     }
+/// }
+///
+/// let records = RecordsById(vec![
+///     Record { id: 7, name: "seven".to_string() },
+///     Record { id: 42, name: "forty-two".to_string() },
+/// ]);
+/// let bytes = to_bytes::<Error>(&records).unwrap();
+/// let table = unsafe {
+///     access_unchecked::<ArchivedHashTable<ArchivedRecord>>(&bytes)
+/// };
+///
+/// let hash = hash_value::<u32, FxHasher64>(&42);
+/// let record = table.get_with(hash, |r| r.id == 42).unwrap();
+/// assert_eq!(record.name, "forty-two");
+/// assert!(table.get_with(hash_value::<u32, FxHasher64>(&1), |r| r.id == 1)
+///     .is_none());
+/// assert_eq!(table.iter().count(), 2);
+/// ```
 #[derive(Portable)]
 #[cfg_attr(
     feature = "bytecheck",
@@ -92,26 +204,25 @@ impl<T> ArchivedHashTable<T> {
     /// # Safety
     ///
     /// - `this` must point to a valid `ArchivedHashTable`
-    /// - `index` must be less than `len()`
+    /// - `index` must be less than the number of control bytes
     unsafe fn control_raw(this: *mut Self, index: usize) -> *const u8 {
-        debug_assert!(unsafe { !(*this).is_empty() });
+        debug_assert!(unsafe { (*this).capacity() != 0 });
 
-        // SAFETY: As an invariant of `ArchivedHashTable`, if `self` is not
-        // empty then `self.ptr` is a valid relative pointer. Since `index` is
-        // at least 0 and strictly less than `len()`, this table must not be
-        // empty.
+        // SAFETY: As an invariant of `ArchivedHashTable`, if `self` has a
+        // nonzero capacity then `self.ptr` is a valid relative pointer. Since
+        // `index` is less than the number of control bytes, this table must
+        // have a nonzero capacity.
         let ptr =
             unsafe { RawRelPtr::as_ptr_raw(ptr::addr_of_mut!((*this).ptr)) };
-        // SAFETY: The caller has guaranteed that `index` is less than `len()`,
-        // and the first `len()` bytes following `ptr` are the control bytes of
-        // the hash table.
+        // SAFETY: The caller has guaranteed that `index` is less than the
+        // number of control bytes, which directly follow `ptr`.
         unsafe { ptr.cast::<u8>().add(index) }
     }
 
     /// # Safety
     ///
     /// - `this` must point to a valid `ArchivedHashTable`
-    /// - `index` must be less than `len()`
+    /// - `index` must be less than `capacity()`
     unsafe fn bucket_raw(this: *mut Self, index: usize) -> NonNull<T> {
         unsafe {
             NonNull::new_unchecked(
@@ -129,11 +240,7 @@ impl<T> ArchivedHashTable<T> {
     /// # Safety
     ///
     /// `this` must point to a valid `ArchivedHashTable`
-    unsafe fn get_entry_raw<C>(
-        this: *mut Self,
-        hash: u64,
-        cmp: C,
-    ) -> Option<NonNull<T>>
+    unsafe fn find_raw<C>(this: *mut Self, hash: u64, cmp: C) -> Option<usize>
     where
         C: Fn(&T) -> bool,
     {
@@ -162,12 +269,13 @@ impl<T> ArchivedHashTable<T> {
 
                 for bit in group.match_byte(h2_hash) {
                     let index = (pos + bit) % capacity;
-                    let bucket_ptr = unsafe { Self::bucket_raw(this, index) };
+                    let bucket_ptr =
+                        unsafe { ArchivedHashTable::bucket_raw(this, index) };
                     let bucket = unsafe { bucket_ptr.as_ref() };
 
                     // Opt: These can be marked as likely true on nightly.
                     if cmp(bucket) {
-                        return Some(bucket_ptr);
+                        return Some(index);
                     }
                 }
 
@@ -188,7 +296,26 @@ impl<T> ArchivedHashTable<T> {
         }
     }
 
-    /// Returns the key-value pair corresponding to the supplied key.
+    /// # Safety
+    ///
+    /// `this` must point to a valid `ArchivedHashTable`
+    unsafe fn get_entry_raw<C>(
+        this: *mut Self,
+        hash: u64,
+        cmp: C,
+    ) -> Option<NonNull<T>>
+    where
+        C: Fn(&T) -> bool,
+    {
+        let index = unsafe { Self::find_raw(this, hash, cmp)? };
+        Some(unsafe { Self::bucket_raw(this, index) })
+    }
+
+    /// Returns the entry with the given hash for which `cmp` returns `true`.
+    ///
+    /// `hash` must be computed the same way as the hash of the entry was
+    /// during serialization, or the entry may not be found. `cmp` is only
+    /// called on entries with a matching hash fingerprint.
     pub fn get_with<C>(&self, hash: u64, cmp: C) -> Option<&T>
     where
         C: Fn(&T) -> bool,
@@ -198,7 +325,10 @@ impl<T> ArchivedHashTable<T> {
         Some(unsafe { ptr.as_ref() })
     }
 
-    /// Returns the mutable key-value pair corresponding to the supplied key.
+    /// Returns the sealed entry with the given hash for which `cmp` returns
+    /// `true`.
+    ///
+    /// See [`get_with`](Self::get_with) for more information.
     pub fn get_seal_with<C>(
         this: Seal<'_, Self>,
         hash: u64,
@@ -213,6 +343,217 @@ impl<T> ArchivedHashTable<T> {
         Some(Seal::new(unsafe { ptr.as_mut() }))
     }
 
+    /// # Safety
+    ///
+    /// - `this` must point to a valid, non-empty `ArchivedHashTable`
+    /// - `index` must be less than `capacity()`
+    unsafe fn set_control_raw(this: *mut Self, index: usize, byte: u8) {
+        let capacity = unsafe { (*this).capacity() };
+        let control_count = Self::control_count(Self::probe_cap(capacity));
+
+        unsafe {
+            Self::control_raw(this, index).cast_mut().write(byte);
+        }
+        // If it's near the beginning of the control bytes, update the
+        // wraparound control byte
+        if index < control_count - capacity {
+            unsafe {
+                Self::control_raw(this, capacity + index)
+                    .cast_mut()
+                    .write(byte);
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `this` must point to a valid `ArchivedHashTable`
+    unsafe fn set_len_raw(this: *mut Self, len: usize) {
+        unsafe {
+            (*this).len = ArchivedUsize::from_native(len as FixedUsize);
+        }
+    }
+
+    /// Returns whether filling an EMPTY bucket would leave the table without
+    /// any EMPTY buckets.
+    ///
+    /// # Safety
+    ///
+    /// `this` must point to a valid, non-empty `ArchivedHashTable`
+    unsafe fn is_last_empty_raw(this: *mut Self) -> bool {
+        let capacity = unsafe { (*this).capacity() };
+        let mut found = 0;
+        for index in 0..capacity {
+            if unsafe { *Self::control_raw(this, index) } == EMPTY {
+                found += 1;
+                if found == 2 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Marks a new bucket for the given hash as full and returns a pointer to
+    /// it.
+    ///
+    /// # Safety
+    ///
+    /// - `this` must point to a valid `ArchivedHashTable`
+    /// - The table must not already contain an item equal to the one being
+    ///   inserted
+    /// - The caller must initialize the returned bucket before the table is
+    ///   accessed again
+    unsafe fn insert_raw<E: Source>(
+        this: *mut Self,
+        hash: u64,
+    ) -> Result<NonNull<T>, E> {
+        #[derive(Debug)]
+        struct TableFull {
+            len: usize,
+            capacity: usize,
+        }
+
+        impl fmt::Display for TableFull {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "archived hash table has no free capacity (length: {}, \
+                     capacity: {})",
+                    self.len, self.capacity,
+                )
+            }
+        }
+
+        impl Error for TableFull {}
+
+        let len = unsafe { (*this).len() };
+        let capacity = unsafe { (*this).capacity() };
+        if len + 1 >= capacity {
+            fail!(TableFull { len, capacity });
+        }
+
+        let probe_cap = Self::probe_cap(capacity);
+        let control_count = Self::control_count(probe_cap);
+        let bucket_mask = Self::bucket_mask(control_count);
+
+        let mut probe_seq = Self::probe_seq(hash, capacity);
+        let index = 'find: loop {
+            for i in 0..MAX_GROUP_WIDTH / Group::WIDTH {
+                let pos = probe_seq.pos + i * Group::WIDTH;
+                let group =
+                    unsafe { Group::read(Self::control_raw(this, pos)) };
+
+                if let Some(bit) =
+                    group.match_empty_or_deleted().lowest_set_bit()
+                {
+                    break 'find (pos + bit) % capacity;
+                }
+            }
+
+            loop {
+                probe_seq.move_next(bucket_mask);
+                if probe_seq.pos < probe_cap {
+                    break;
+                }
+            }
+        };
+
+        // Filling the last EMPTY bucket would leave probe sequences for
+        // missing items without a place to terminate.
+        let control = unsafe { *Self::control_raw(this, index) };
+        if control == EMPTY && unsafe { Self::is_last_empty_raw(this) } {
+            fail!(TableFull { len, capacity });
+        }
+
+        unsafe {
+            Self::set_control_raw(this, index, h2(hash));
+            Self::set_len_raw(this, len + 1);
+        }
+
+        Ok(unsafe { Self::bucket_raw(this, index) })
+    }
+
+    /// Removes the item matching the given comparison function from the sealed
+    /// hash table.
+    ///
+    /// The bucket is marked as deleted and may be reused by later calls to
+    /// [`insert_seal_with`](Self::insert_seal_with). Returns whether an item
+    /// was removed.
+    pub fn remove_seal_with<C>(this: Seal<'_, Self>, hash: u64, cmp: C) -> bool
+    where
+        C: Fn(&T) -> bool,
+    {
+        let this = unsafe { this.unseal_unchecked() as *mut Self };
+        let Some(index) = (unsafe { Self::find_raw(this, hash, cmp) }) else {
+            return false;
+        };
+
+        unsafe {
+            Self::set_control_raw(this, index, DELETED);
+            let len = (*this).len();
+            Self::set_len_raw(this, len - 1);
+        }
+
+        true
+    }
+
+    /// Inserts an item into the sealed hash table, replacing the item matching
+    /// the given comparison function if one exists.
+    ///
+    /// Sealed hash tables can't grow, so new items must fit into the spare
+    /// capacity the table was serialized with. Hash tables serialized with a
+    /// lower load factor have more spare capacity. If the table does not have
+    /// enough free capacity for the item, an error is returned.
+    ///
+    /// Returns the replaced item, if any.
+    pub fn insert_seal_with<C, E>(
+        mut this: Seal<'_, Self>,
+        hash: u64,
+        cmp: C,
+        item: T,
+    ) -> Result<Option<T>, E>
+    where
+        C: Fn(&T) -> bool,
+        T: NoUndef + Unpin,
+        E: Source,
+    {
+        if let Some(existing) = Self::get_seal_with(this.as_mut(), hash, cmp) {
+            return Ok(Some(core::mem::replace(existing.unseal(), item)));
+        }
+
+        unsafe {
+            Self::insert_raw(this.unseal_unchecked(), hash)?
+                .as_ptr()
+                .write(item);
+        }
+
+        Ok(None)
+    }
+
+    /// Inserts a new item into the sealed hash table without checking whether
+    /// it is already present.
+    ///
+    /// The bucket for the new item is initialized by calling `init` with a
+    /// pointer to it.
+    ///
+    /// # Safety
+    ///
+    /// - The table must not already contain an item equal to the one being
+    ///   inserted.
+    /// - `init` must fully initialize the bucket it is passed with a valid `T`.
+    ///   Any padding bytes in `T` will be left as they were.
+    pub unsafe fn insert_seal_unchecked<E: Source>(
+        this: Seal<'_, Self>,
+        hash: u64,
+        init: impl FnOnce(NonNull<T>),
+    ) -> Result<(), E> {
+        let bucket =
+            unsafe { Self::insert_raw(this.unseal_unchecked(), hash)? };
+        init(bucket);
+        Ok(())
+    }
+
     /// Returns whether the hash table is empty.
     pub const fn is_empty(&self) -> bool {
         self.len.to_native() == 0
@@ -240,6 +581,16 @@ impl<T> ArchivedHashTable<T> {
         }
     }
 
+    /// Returns an iterator over the entries in the hash table.
+    ///
+    /// The entries are yielded in an arbitrary order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            raw: self.raw_iter(),
+            _phantom: PhantomData,
+        }
+    }
+
     /// Returns an iterator over the entry pointers in the hash table.
     pub fn raw_iter(&self) -> RawIter<T> {
         if self.is_empty() {
@@ -293,7 +644,6 @@ impl<T> ArchivedHashTable<T> {
         probe_cap + MAX_GROUP_WIDTH - 1
     }
 
-    #[allow(dead_code)]
     fn memory_layout<E: Source>(
         capacity: usize,
         control_count: usize,
@@ -303,21 +653,10 @@ impl<T> ArchivedHashTable<T> {
         buckets_layout.extend(control_layout).into_error()
     }
 
-    /// Serializes an iterator of items as a hash table.
-    pub fn serialize_from_iter<I, U, H, S>(
-        items: I,
-        hashes: H,
+    /// Checks that a load factor is a fraction in the range (0, 1].
+    fn check_load_factor<E: Source>(
         load_factor: (usize, usize),
-        serializer: &mut S,
-    ) -> Result<HashTableResolver, S::Error>
-    where
-        I: Clone + ExactSizeIterator,
-        I::Item: Borrow<U>,
-        U: Serialize<S, Archived = T>,
-        H: ExactSizeIterator<Item = u64>,
-        S: Fallible + Writer + Allocator + ?Sized,
-        S::Error: Source,
-    {
+    ) -> Result<(), E> {
         #[derive(Debug)]
         struct InvalidLoadFactor {
             numerator: usize,
@@ -347,6 +686,87 @@ impl<T> ArchivedHashTable<T> {
             });
         }
 
+        Ok(())
+    }
+
+    /// Checks that a hash table with the given length and load factor can be
+    /// addressed with the relative pointers of the configured pointer width.
+    ///
+    /// The offset from the table to its data is checked separately when it is
+    /// resolved by [`try_resolve_from_len`](Self::try_resolve_from_len).
+    fn check_size<E: Source>(
+        len: usize,
+        load_factor: (usize, usize),
+    ) -> Result<(), E> {
+        #[derive(Debug)]
+        struct TableTooLarge {
+            len: usize,
+            size: Option<usize>,
+        }
+
+        impl fmt::Display for TableTooLarge {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "hash table with length {} ", self.len)?;
+                if let Some(size) = self.size {
+                    write!(f, "requires {} bytes, which ", size)?;
+                }
+                write!(
+                    f,
+                    "exceeds the maximum of {} bytes that can be addressed \
+                     with {}-bit relative pointers; consider enabling a \
+                     larger `pointer_width_*` feature",
+                    FixedIsize::MAX,
+                    FixedIsize::BITS,
+                )
+            }
+        }
+
+        impl Error for TableTooLarge {}
+
+        let size = len
+            .checked_mul(load_factor.1)
+            .map(|_| Self::capacity_from_len(len, load_factor))
+            .and_then(|capacity| {
+                let probe_cap =
+                    capacity.checked_next_multiple_of(MAX_GROUP_WIDTH)?;
+                let control_count =
+                    probe_cap.checked_add(MAX_GROUP_WIDTH - 1)?;
+                let (layout, _) =
+                    Self::memory_layout::<E>(capacity, control_count).ok()?;
+                Some(layout.size())
+            });
+
+        match size {
+            Some(size) if FixedIsize::try_from(size).is_ok() => Ok(()),
+            size => fail!(TableTooLarge { len, size }),
+        }
+    }
+
+    /// Serializes an iterator of items as a hash table.
+    ///
+    /// `hashes` must yield the hash of each item in the same order as `items`.
+    /// The same hashes must be used to look up the items with
+    /// [`get_with`](Self::get_with).
+    ///
+    /// The load factor is the maximum fraction of buckets which may be full,
+    /// and must be in the range (0, 1]. The same load factor must be passed to
+    /// [`resolve_from_len`](Self::resolve_from_len).
+    pub fn serialize_from_iter<I, U, H, S>(
+        items: I,
+        hashes: H,
+        load_factor: (usize, usize),
+        serializer: &mut S,
+    ) -> Result<HashTableResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator,
+        I::Item: Borrow<U>,
+        U: Serialize<S, Archived = T>,
+        H: ExactSizeIterator<Item = u64>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        Self::check_load_factor(load_factor)?;
+
         let len = items.len();
 
         if len == 0 {
@@ -361,6 +781,8 @@ impl<T> ArchivedHashTable<T> {
             return Ok(HashTableResolver { pos: 0 });
         }
 
+        Self::check_size(len, load_factor)?;
+
         let capacity = Self::capacity_from_len(len, load_factor);
         let probe_cap = Self::probe_cap(capacity);
         let control_count = Self::control_count(probe_cap);
@@ -471,12 +893,12 @@ impl<T> ArchivedHashTable<T> {
                                     }
                                 }
 
-                                let pos = serializer.pos();
+                                let pos =
+                                    FixedUsize::try_from(serializer.pos())
+                                        .into_error()?;
                                 serializer.write(control_bytes)?;
 
-                                Ok(HashTableResolver {
-                                    pos: pos as FixedUsize,
-                                })
+                                Ok(HashTableResolver { pos })
                             },
                         )?
                     },
@@ -485,19 +907,87 @@ impl<T> ArchivedHashTable<T> {
         )?
     }
 
-    /// Resolves an archived hash table from a given length and parameters.
-    pub fn resolve_from_len(
+    /// Serializes an iterator of items as a hash table with the given options.
+    ///
+    /// This is like [`serialize_from_iter`](Self::serialize_from_iter), but
+    /// takes its load factor from `options` and may insert the items in order
+    /// of their home buckets. The same load factor must be passed to
+    /// [`resolve_from_len`](Self::resolve_from_len).
+    pub fn serialize_from_iter_with_options<I, U, H, S>(
+        items: I,
+        hashes: H,
+        options: HashTableOptions,
+        serializer: &mut S,
+    ) -> Result<HashTableResolver, S::Error>
+    where
+        I: Clone + ExactSizeIterator,
+        I::Item: Borrow<U>,
+        U: Serialize<S, Archived = T>,
+        H: ExactSizeIterator<Item = u64>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let HashTableOptions {
+            load_factor,
+            sort_by_bucket,
+        } = options;
+        let len = items.len();
+        if !sort_by_bucket || len == 0 {
+            return Self::serialize_from_iter(
+                items,
+                hashes,
+                load_factor,
+                serializer,
+            );
+        }
+
+        Self::check_load_factor(load_factor)?;
+        Self::check_size(len, load_factor)?;
+        let capacity = Self::capacity_from_len(len, load_factor);
+
+        SerVec::with_capacity(serializer, len, |sorted, serializer| {
+            for (item, hash) in items.zip(hashes).take(len) {
+                sorted.push((hash, item));
+            }
+            if sorted.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: sorted.len(),
+                });
+            }
+
+            // Ties are broken by the full hash so that the order doesn't
+            // depend on the order of the input.
+            sorted.as_mut_slice().sort_unstable_by_key(|(hash, _)| {
+                (h1(*hash) % capacity, *hash)
+            });
+
+            Self::serialize_from_iter::<_, U, _, _>(
+                sorted.iter().map(|(_, item)| Borrow::<U>::borrow(item)),
+                sorted.iter().map(|(hash, _)| *hash),
+                load_factor,
+                serializer,
+            )
+        })?
+    }
+
+    /// Attempts to resolve an archived hash table from a given length and
+    /// parameters.
+    ///
+    /// Returns an error if the table data is too far away from `out` to be
+    /// addressed with the relative pointers of the configured pointer width.
+    pub fn try_resolve_from_len<E: Source>(
         len: usize,
         load_factor: (usize, usize),
         resolver: HashTableResolver,
         out: Place<Self>,
-    ) {
+    ) -> Result<(), E> {
         munge!(let Self { ptr, len: out_len, cap, _phantom: _ } = out);
 
         if len == 0 {
-            RawRelPtr::emplace_invalid(ptr);
+            RawRelPtr::try_emplace_invalid(ptr)?;
         } else {
-            RawRelPtr::emplace(resolver.pos as usize, ptr);
+            RawRelPtr::try_emplace(resolver.pos as usize, ptr)?;
         }
 
         len.resolve((), out_len);
@@ -506,6 +996,72 @@ impl<T> ArchivedHashTable<T> {
         capacity.resolve((), cap);
 
         // PhantomData doesn't need to be initialized
+
+        Ok(())
+    }
+
+    /// Resolves an archived hash table from a given length and parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table data is too far away from `out` to be addressed
+    /// with the relative pointers of the configured pointer width. Use
+    /// [`try_resolve_from_len`](Self::try_resolve_from_len) to handle this
+    /// case as an error.
+    pub fn resolve_from_len(
+        len: usize,
+        load_factor: (usize, usize),
+        resolver: HashTableResolver,
+        out: Place<Self>,
+    ) {
+        Self::try_resolve_from_len::<Panic>(len, load_factor, resolver, out)
+            .always_ok()
+    }
+}
+
+/// Options for serializing an [`ArchivedHashTable`].
+///
+/// # Example
+///
+/// ```
+/// use rkyv::collections::swiss_table::HashTableOptions;
+///
+/// // Trade space for shorter probe sequences in a read-heavy archive.
+/// let options = HashTableOptions {
+///     load_factor: (1, 2),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashTableOptions {
+    /// The maximum fraction of buckets which may be full, as a numerator and
+    /// denominator.
+    ///
+    /// The load factor must be in the range (0, 1]. Lower load factors make
+    /// the table larger, but make probe sequences shorter.
+    pub load_factor: (usize, usize),
+    /// Whether to insert items in order of their home buckets.
+    ///
+    /// This makes the layout of the table independent of the order that items
+    /// are yielded in, so the same items always serialize to the same bytes.
+    /// It also places items which probe from nearby buckets close together.
+    /// Sorting takes `O(n log n)` time and some scratch space from the
+    /// serializer.
+    pub sort_by_bucket: bool,
+}
+
+impl HashTableOptions {
+    /// The options used by the archived hash maps and sets of the standard
+    /// library collections.
+    pub const DEFAULT: Self = Self {
+        load_factor: (7, 8),
+        sort_by_bucket: false,
+    };
+}
+
+impl Default for HashTableOptions {
+    fn default() -> Self {
+        Self::DEFAULT
     }
 }
 
@@ -596,6 +1152,31 @@ impl<T> ExactSizeIterator for RawIter<T> {
     }
 }
 
+/// An iterator over the entries of an [`ArchivedHashTable`].
+pub struct Iter<'a, T> {
+    raw: RawIter<T>,
+    _phantom: PhantomData<&'a ArchivedHashTable<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // SAFETY: The raw iterator only yields pointers to the initialized
+        // entries of a table which is borrowed for `'a`.
+        self.raw.next().map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.raw.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
 #[cfg(feature = "bytecheck")]
 mod verify {
     use core::{error::Error, fmt};
@@ -603,11 +1184,8 @@ mod verify {
     use bytecheck::{CheckBytes, Verify};
     use rancor::{fail, Fallible, Source};
 
-    use super::ArchivedHashTable;
-    use crate::{
-        simd::Group,
-        validation::{ArchiveContext, ArchiveContextExt as _},
-    };
+    use super::{ArchivedHashTable, DELETED, EMPTY};
+    use crate::validation::{ArchiveContext, ArchiveContextExt as _};
 
     #[derive(Debug)]
     struct InvalidLength {
@@ -641,6 +1219,59 @@ mod verify {
 
     impl Error for UnwrappedControlByte {}
 
+    #[derive(Debug)]
+    struct InvalidControlByte {
+        index: usize,
+        byte: u8,
+    }
+
+    impl fmt::Display for InvalidControlByte {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "invalid control byte {:#04x} at index {}",
+                self.byte, self.index,
+            )
+        }
+    }
+
+    impl Error for InvalidControlByte {}
+
+    #[derive(Debug)]
+    struct FullBucketMismatch {
+        len: usize,
+        full: usize,
+    }
+
+    impl fmt::Display for FullBucketMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "hash table has {} full buckets but a length of {}",
+                self.full, self.len,
+            )
+        }
+    }
+
+    impl Error for FullBucketMismatch {}
+
+    #[derive(Debug)]
+    struct NoEmptyBuckets {
+        cap: usize,
+    }
+
+    impl fmt::Display for NoEmptyBuckets {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "hash table with capacity {} has no empty buckets",
+                self.cap,
+            )
+        }
+    }
+
+    impl Error for NoEmptyBuckets {}
+
     unsafe impl<C, T> Verify<C> for ArchivedHashTable<T>
     where
         C: Fallible + ArchiveContext + ?Sized,
@@ -670,43 +1301,297 @@ mod verify {
                 .cast::<u8>()
                 .wrapping_sub(control_offset);
 
-            context.in_subtree_raw(ptr, layout, |context| {
-                // Check each non-empty bucket
+            // SAFETY: `check_buckets` is safe to call on `self` once the memory
+            // allocation of the table is checked and pushed.
+            unsafe {
+                context.in_subtree_raw_deferred(
+                    ptr,
+                    layout,
+                    self as *const Self,
+                    check_buckets,
+                )
+            }
+        }
+    }
 
-                let this = (self as *const Self).cast_mut();
-                // SAFETY: We have checked that `self` is not empty.
-                let mut controls = unsafe { Self::control_iter(this) };
-                let mut base_index = 0;
-                'outer: while base_index < cap {
-                    while let Some(bit) = controls.next_full() {
-                        let index = base_index + bit;
-                        if index >= cap {
-                            break 'outer;
-                        }
+    /// # Safety
+    ///
+    /// `this` must point to a hash table which is not empty and whose memory
+    /// allocation is located within the current subtree range.
+    unsafe fn check_buckets<T, C>(
+        this: *const ArchivedHashTable<T>,
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+        T: CheckBytes<C>,
+    {
+        // SAFETY: The caller has guaranteed that `this` points to a hash table.
+        let (len, cap) = unsafe { ((*this).len(), (*this).capacity()) };
+        let control_count = ArchivedHashTable::<T>::control_count(
+            ArchivedHashTable::<T>::probe_cap(cap),
+        );
+
+        // Check each control byte and each full bucket
+
+        let this = this.cast_mut();
+        let mut full = 0;
+        let mut empty = 0;
+        for index in 0..cap {
+            let byte = unsafe { *ArchivedHashTable::control_raw(this, index) };
+            if byte & 0x80 == 0 {
+                full += 1;
+                unsafe {
+                    T::check_bytes(
+                        ArchivedHashTable::bucket_raw(this, index).as_ptr(),
+                        context,
+                    )?;
+                }
+            } else if byte == EMPTY {
+                empty += 1;
+            } else if byte != DELETED {
+                fail!(InvalidControlByte { index, byte });
+            }
+        }
 
-                        unsafe {
-                            T::check_bytes(
-                                Self::bucket_raw(this, index).as_ptr(),
-                                context,
-                            )?;
-                        }
-                    }
+        if full != len {
+            fail!(FullBucketMismatch { len, full });
+        }
+        // Probe sequences only terminate at EMPTY buckets, so there must always
+        // be at least one.
+        if empty == 0 {
+            fail!(NoEmptyBuckets { cap });
+        }
 
-                    controls.move_next();
-                    base_index += Group::WIDTH;
-                }
+        // Verify that wrapped bytes are set correctly
+        for i in cap..usize::min(2 * cap, control_count - cap) {
+            let byte = unsafe { *ArchivedHashTable::control_raw(this, i) };
+            let wrapped =
+                unsafe { *ArchivedHashTable::control_raw(this, i % cap) };
+            if wrapped != byte {
+                fail!(UnwrappedControlByte { index: i })
+            }
+        }
 
-                // Verify that wrapped bytes are set correctly
-                for i in cap..usize::min(2 * cap, control_count - cap) {
-                    let byte = unsafe { *Self::control_raw(this, i) };
-                    let wrapped = unsafe { *Self::control_raw(this, i % cap) };
-                    if wrapped != byte {
-                        fail!(UnwrappedControlByte { index: i })
-                    }
-                }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use rancor::{Error, Fallible, Source, Strategy};
+
+    use super::{ArchivedHashTable, HashTableResolver};
+    use crate::{
+        alloc::{string::ToString, vec::Vec},
+        api::test::to_archived,
+        primitive::{ArchivedU32, ArchivedUsize, FixedIsize},
+        ser::{sharing::Share, Allocator, Serializer, Writer},
+        util::{with_arena, AlignedVec},
+        Archive, Place, Serialize,
+    };
+
+    #[test]
+    fn serialize_too_large() {
+        let len = FixedIsize::MAX as usize;
+        with_arena(|arena| {
+            let mut serializer = Serializer::new(
+                AlignedVec::<16>::new(),
+                arena.acquire(),
+                Share::new(),
+            );
+            let result =
+                ArchivedHashTable::<ArchivedUsize>::serialize_from_iter::<
+                    _,
+                    usize,
+                    _,
+                    _,
+                >(
+                    0..len,
+                    (0..len).map(|i| i as u64),
+                    (7, 8),
+                    Strategy::<_, Error>::wrap(&mut serializer),
+                );
+            let error = result.err().unwrap().to_string();
+            assert!(error.contains("relative pointers"));
+        });
+    }
+
+    /// A table of `u32`s with deliberately colliding hashes.
+    struct Table(Vec<u32>);
+
+    impl Archive for Table {
+        type Archived = ArchivedHashTable<ArchivedU32>;
+        type Resolver = HashTableResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            ArchivedHashTable::resolve_from_len(
+                self.0.len(),
+                (1, 2),
+                resolver,
+                out,
+            );
+        }
+    }
+
+    impl<S> Serialize<S> for Table
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            // Deliberately collide every value into a few hashes.
+            ArchivedHashTable::serialize_from_iter::<_, u32, _, _>(
+                self.0.iter(),
+                self.0.iter().map(|&x| u64::from(x % 3)),
+                (1, 2),
+                serializer,
+            )
+        }
+    }
+
+    #[test]
+    fn explicit_hashes() {
+        let value = Table((0..50).collect());
+        to_archived(&value, |archived| {
+            assert_eq!(archived.len(), 50);
+            for x in 0..50u32 {
+                let found = archived.get_with(u64::from(x % 3), |e| *e == x);
+                assert_eq!(found.map(|e| e.to_native()), Some(x));
+            }
+            assert!(archived.get_with(1, |e| *e == 50).is_none());
 
-                Ok(())
-            })
+            let mut items =
+                archived.iter().map(|e| e.to_native()).collect::<Vec<_>>();
+            items.sort();
+            assert_eq!(items, value.0);
+        });
+    }
+
+    #[test]
+    fn layout_unchanged() {
+        // Sealed mutation must not add fields to the archived format.
+        assert_eq!(
+            core::mem::size_of::<ArchivedHashTable<ArchivedU32>>(),
+            3 * core::mem::size_of::<crate::primitive::ArchivedUsize>(),
+        );
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn sealed_inserts_stay_valid() {
+        use crate::api::high::{access, access_mut, to_bytes};
+
+        let mut bytes = to_bytes::<Error>(&Table((0..20).collect())).unwrap();
+        let mut table =
+            access_mut::<ArchivedHashTable<ArchivedU32>, Error>(&mut bytes)
+                .unwrap();
+
+        for x in (0..20).step_by(2) {
+            let hash = u64::from(x % 3);
+            assert!(ArchivedHashTable::remove_seal_with(
+                table.as_mut(),
+                hash,
+                |e| *e == x,
+            ));
         }
+
+        // Inserting fails before the last EMPTY bucket would be filled
+        let free = table.capacity() - table.len();
+        let mut inserted = 0;
+        for x in 100..200u32 {
+            let result = ArchivedHashTable::insert_seal_with::<_, Error>(
+                table.as_mut(),
+                u64::from(x % 3),
+                |e| *e == x,
+                ArchivedU32::from_native(x),
+            );
+            if result.is_err() {
+                break;
+            }
+            inserted += 1;
+        }
+        assert!(inserted > 0);
+        assert!(inserted < free);
+        assert!(table.len() < table.capacity());
+
+        access::<ArchivedHashTable<ArchivedU32>, Error>(&bytes).unwrap();
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn invalid_control_bytes() {
+        use crate::api::high::{access, to_bytes};
+
+        let bytes = to_bytes::<Error>(&Table((0..5).collect())).unwrap();
+        let table =
+            access::<ArchivedHashTable<ArchivedU32>, Error>(&bytes).unwrap();
+        let cap = table.capacity();
+        let control_count = ArchivedHashTable::<ArchivedU32>::control_count(
+            ArchivedHashTable::<ArchivedU32>::probe_cap(cap),
+        );
+        let start =
+            table.ptr.as_ptr_wrapping() as usize - bytes.as_ptr() as usize;
+        let controls = start..start + control_count;
+        let full = bytes[controls.clone()].iter().position(|&b| b < 0x80);
+        let full = full.unwrap();
+
+        let corrupt = |f: &dyn Fn(&mut [u8])| {
+            let mut bytes = bytes.clone();
+            f(&mut bytes[controls.clone()]);
+            let result =
+                access::<ArchivedHashTable<ArchivedU32>, Error>(&bytes);
+            assert!(
+                result.is_err(),
+                "corrupted control bytes must fail to validate"
+            );
+        };
+        let set = |controls: &mut [u8], index: usize, byte: u8| {
+            controls[index] = byte;
+            if index < control_count - cap {
+                controls[cap + index] = byte;
+            }
+        };
+
+        // Every table must have at least one EMPTY bucket
+        corrupt(&|controls| {
+            for byte in controls.iter_mut().filter(|b| **b == 0xff) {
+                *byte = 0x80;
+            }
+        });
+        // The number of full buckets must match the length
+        corrupt(&|controls| set(controls, full, 0x80));
+        // Control bytes must be full, EMPTY, or DELETED
+        corrupt(&|controls| set(controls, full, 0x90));
+    }
+
+    #[cfg(not(feature = "pointer_width_64"))]
+    #[test]
+    fn resolve_too_far() {
+        use core::mem::MaybeUninit;
+
+        let mut out = MaybeUninit::<ArchivedHashTable<ArchivedU32>>::zeroed();
+        // SAFETY: `out` is properly aligned, dereferenceable, and zeroed.
+        let place = unsafe {
+            Place::new_unchecked(
+                FixedIsize::MAX as usize + 16,
+                out.as_mut_ptr(),
+            )
+        };
+        let result = ArchivedHashTable::try_resolve_from_len::<Error>(
+            1,
+            (7, 8),
+            HashTableResolver { pos: 0 },
+            place,
+        );
+        assert!(result.is_err());
     }
 }
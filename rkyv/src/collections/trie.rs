@@ -0,0 +1,600 @@
+//! An archived map from strings to values which supports prefix queries.
+//!
+//! The map is laid out as a compressed radix tree over the bytes of its keys.
+//! Entries are stored in sorted order, so the keys under any node of the tree
+//! are a contiguous run of entries. Each node records that run and the length
+//! of the prefix it represents; the label on the edge into a node is read from
+//! the first key in its run instead of being stored separately. The nodes are
+//! stored in breadth-first order, so the children of every node are adjacent
+//! and sorted by their first byte.
+
+use core::{
+    borrow::Borrow, error::Error, fmt, iter::FusedIterator, ops::Index, slice,
+};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    collections::util::{Entry, EntryAdapter, IteratorLengthMismatch},
+    primitive::{ArchivedUsize, FixedUsize},
+    ser::{Allocator, Writer},
+    string::ArchivedString,
+    util::SerVec,
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable, Serialize,
+};
+
+/// A node in the radix tree of an [`ArchivedTrieMap`].
+#[derive(Debug, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct TrieNode {
+    depth: ArchivedUsize,
+    start: ArchivedUsize,
+    end: ArchivedUsize,
+    first_child: ArchivedUsize,
+    children: ArchivedUsize,
+}
+
+impl TrieNode {
+    fn depth(&self) -> usize {
+        self.depth.to_native() as usize
+    }
+
+    fn entries(&self) -> (usize, usize) {
+        (
+            self.start.to_native() as usize,
+            self.end.to_native() as usize,
+        )
+    }
+
+    fn children(&self) -> (usize, usize) {
+        let first = self.first_child.to_native() as usize;
+        let children = self.children.to_native() as usize;
+        (first, first.saturating_add(children))
+    }
+}
+
+/// An archived map from strings to values with prefix queries.
+///
+/// Entries are sorted by key, and every query walks down the radix tree one
+/// edge at a time. This makes lookups independent of the number of entries in
+/// the map, and lets all of the keys with a given prefix be found without
+/// searching.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedTrieMap<V> {
+    entries: ArchivedVec<Entry<ArchivedString, V>>,
+    nodes: ArchivedVec<TrieNode>,
+}
+
+impl<V> ArchivedTrieMap<V> {
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the key-value pairs of the map, sorted by key.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the map, sorted by key.
+    pub fn keys(&self) -> Keys<'_, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Returns an iterator over the values of the map, sorted by key.
+    pub fn values(&self) -> Values<'_, V> {
+        Values { inner: self.iter() }
+    }
+
+    fn key(&self, node: &TrieNode) -> &[u8] {
+        self.entries[node.entries().0].key.as_str().as_bytes()
+    }
+
+    fn value_entry(
+        &self,
+        node: &TrieNode,
+    ) -> Option<&Entry<ArchivedString, V>> {
+        let (start, end) = node.entries();
+        let entry = self.entries[start..end].first()?;
+        (entry.key.len() == node.depth()).then_some(entry)
+    }
+
+    fn child(&self, node: &TrieNode, byte: u8) -> Option<&TrieNode> {
+        let depth = node.depth();
+        let (first, last) = node.children();
+        let children = &self.nodes[first..last];
+        let index = children
+            .binary_search_by_key(&byte, |child| self.key(child)[depth])
+            .ok()?;
+        Some(&children[index])
+    }
+
+    /// Walks down the tree along `key` and returns the last node reached.
+    ///
+    /// Calls `visit` with each node on the path whose prefix `key` starts
+    /// with. The returned node either represents all of `key` or has an edge
+    /// label which `key` ends partway through.
+    fn descend(
+        &self,
+        key: &[u8],
+        mut visit: impl FnMut(&TrieNode),
+    ) -> Option<&TrieNode> {
+        let mut node = &self.nodes[0];
+        loop {
+            let depth = node.depth();
+            if key.len() <= depth {
+                return Some(node);
+            }
+            visit(node);
+
+            let child = self.child(node, key[depth])?;
+            let end = key.len().min(child.depth());
+            if key[depth..end] != self.key(child)[depth..end] {
+                return None;
+            }
+            node = child;
+        }
+    }
+
+    /// Returns the key-value pair corresponding to the supplied key.
+    pub fn get_key_value(&self, key: &str) -> Option<(&ArchivedString, &V)> {
+        let node = self.descend(key.as_bytes(), |_| ())?;
+        if node.depth() != key.len() {
+            return None;
+        }
+        self.value_entry(node)
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns a reference to the value corresponding to the supplied key.
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns whether the map contains the given key.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get_key_value(key).is_some()
+    }
+
+    /// Returns the entry with the longest key which is a prefix of the given
+    /// key.
+    ///
+    /// This is the lookup used by routing tables: with the keys `"/"` and
+    /// `"/api/"`, looking up `"/api/users"` returns the entry for `"/api/"`.
+    pub fn get_prefix(&self, key: &str) -> Option<(&ArchivedString, &V)> {
+        let mut longest = None;
+        let node = self.descend(key.as_bytes(), |node| {
+            if let Some(entry) = self.value_entry(node) {
+                longest = Some(entry);
+            }
+        });
+        if let Some(node) = node.filter(|node| node.depth() == key.len()) {
+            if let Some(entry) = self.value_entry(node) {
+                longest = Some(entry);
+            }
+        }
+        longest.map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns an iterator over the key-value pairs whose keys start with the
+    /// given prefix, sorted by key.
+    pub fn iter_prefix(&self, prefix: &str) -> Iter<'_, V> {
+        let entries = match self.descend(prefix.as_bytes(), |_| ()) {
+            Some(node) => {
+                let (start, end) = node.entries();
+                &self.entries[start..end]
+            }
+            None => &[],
+        };
+        Iter {
+            inner: entries.iter(),
+        }
+    }
+
+    /// Serializes an iterator of key-value pairs as a trie map.
+    ///
+    /// The iterator must yield its keys in strictly increasing order.
+    pub fn serialize_from_sorted_iter<I, BKU, BVU, KU, VU, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<TrieMapResolver, S::Error>
+    where
+        I: ExactSizeIterator<Item = (BKU, BVU)>,
+        BKU: Borrow<KU>,
+        BVU: Borrow<VU>,
+        KU: Serialize<S, Archived = ArchivedString> + Borrow<str>,
+        VU: Serialize<S, Archived = V>,
+        S: Fallible + Writer + Allocator + ?Sized,
+        S::Error: Source,
+    {
+        let len = iter.len();
+
+        SerVec::with_capacity(serializer, len, |items, serializer| {
+            let mut iter = iter;
+            for item in iter.by_ref().take(len) {
+                if let Some((last, _)) = items.as_slice().last() {
+                    if key_str::<_, KU>(last) >= key_str::<_, KU>(&item.0) {
+                        fail!(UnsortedKeys);
+                    }
+                }
+                items.push(item);
+            }
+            if items.len() != len {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: items.len(),
+                });
+            }
+            let leftovers = iter.count();
+            if leftovers != 0 {
+                fail!(IteratorLengthMismatch {
+                    expected: len,
+                    actual: len + leftovers,
+                });
+            }
+
+            // Every node except the root either holds a value or has at least
+            // two children, so there are at most `2 * len` of them.
+            SerVec::with_capacity(
+                serializer,
+                2 * len + 1,
+                |nodes, serializer| {
+                    build_nodes(
+                        len,
+                        |index| key_str::<_, KU>(&items[index].0).as_bytes(),
+                        nodes,
+                    );
+
+                    let entries = ArchivedVec::serialize_from_iter::<
+                        EntryAdapter<&KU, &VU, KU, VU>,
+                        _,
+                        _,
+                    >(
+                        items.iter().map(|(key, value)| {
+                            EntryAdapter::new(key.borrow(), value.borrow())
+                        }),
+                        serializer,
+                    )?;
+                    let nodes_len = nodes.len();
+                    let nodes = ArchivedVec::serialize_from_slice(
+                        nodes.as_slice(),
+                        serializer,
+                    )?;
+
+                    Ok(TrieMapResolver {
+                        entries,
+                        nodes,
+                        nodes_len,
+                    })
+                },
+            )?
+        })?
+    }
+
+    /// Resolves an archived trie map from a given length.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: TrieMapResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedTrieMap { entries, nodes } = out);
+        ArchivedVec::resolve_from_len(len, resolver.entries, entries);
+        ArchivedVec::resolve_from_len(
+            resolver.nodes_len,
+            resolver.nodes,
+            nodes,
+        );
+    }
+}
+
+fn key_str<'a, BKU, KU>(key: &'a BKU) -> &'a str
+where
+    BKU: Borrow<KU>,
+    KU: Borrow<str> + 'a,
+{
+    Borrow::<str>::borrow(Borrow::<KU>::borrow(key))
+}
+
+#[derive(Clone, Copy)]
+struct Node {
+    depth: usize,
+    start: usize,
+    end: usize,
+    first_child: usize,
+    children: usize,
+}
+
+impl Archive for Node {
+    type Archived = TrieNode;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let TrieNode { depth, start, end, first_child, children } = out);
+        (self.depth as FixedUsize).resolve((), depth);
+        (self.start as FixedUsize).resolve((), start);
+        (self.end as FixedUsize).resolve((), end);
+        (self.first_child as FixedUsize).resolve((), first_child);
+        (self.children as FixedUsize).resolve((), children);
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for Node {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+/// Builds the nodes of the radix tree over `len` sorted keys in breadth-first
+/// order.
+fn build_nodes<'a>(
+    len: usize,
+    key: impl Fn(usize) -> &'a [u8],
+    nodes: &mut SerVec<Node>,
+) {
+    nodes.push(Node {
+        depth: 0,
+        start: 0,
+        end: len,
+        first_child: 0,
+        children: 0,
+    });
+
+    let mut i = 0;
+    while i < nodes.len() {
+        let Node {
+            depth, start, end, ..
+        } = nodes[i];
+
+        // The key which ends at this node sorts before all of the others.
+        let mut lo = start;
+        if lo < end && key(lo).len() == depth {
+            lo += 1;
+        }
+
+        let first_child = nodes.len();
+        while lo < end {
+            let byte = key(lo)[depth];
+            let mut hi = lo + 1;
+            while hi < end && key(hi)[depth] == byte {
+                hi += 1;
+            }
+
+            let (first, last) = (key(lo), key(hi - 1));
+            let common = first
+                .iter()
+                .zip(last.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+            nodes.push(Node {
+                depth: common,
+                start: lo,
+                end: hi,
+                first_child: 0,
+                children: 0,
+            });
+            lo = hi;
+        }
+
+        nodes[i].first_child = first_child;
+        nodes[i].children = nodes.len() - first_child;
+        i += 1;
+    }
+}
+
+#[derive(Debug)]
+struct UnsortedKeys;
+
+impl fmt::Display for UnsortedKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "trie map keys were not unique and in sorted order")
+    }
+}
+
+impl Error for UnsortedKeys {}
+
+impl<V: fmt::Debug> fmt::Debug for ArchivedTrieMap<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V> Index<&str> for ArchivedTrieMap<V> {
+    type Output = V;
+
+    fn index(&self, key: &str) -> &V {
+        self.get(key).unwrap()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a ArchivedTrieMap<V> {
+    type Item = (&'a ArchivedString, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<V: PartialEq> PartialEq for ArchivedTrieMap<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries == other.entries
+    }
+}
+
+impl<V: Eq> Eq for ArchivedTrieMap<V> {}
+
+/// An iterator over the key-value pairs of an [`ArchivedTrieMap`].
+pub struct Iter<'a, V> {
+    inner: slice::Iter<'a, Entry<ArchivedString, V>>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (&'a ArchivedString, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<V> DoubleEndedIterator for Iter<'_, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+impl<V> ExactSizeIterator for Iter<'_, V> {}
+
+impl<V> FusedIterator for Iter<'_, V> {}
+
+impl<V> Clone for Iter<'_, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// An iterator over the keys of an [`ArchivedTrieMap`].
+pub struct Keys<'a, V> {
+    inner: Iter<'a, V>,
+}
+
+impl<'a, V> Iterator for Keys<'a, V> {
+    type Item = &'a ArchivedString;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for Keys<'_, V> {}
+
+impl<V> FusedIterator for Keys<'_, V> {}
+
+/// An iterator over the values of an [`ArchivedTrieMap`].
+pub struct Values<'a, V> {
+    inner: Iter<'a, V>,
+}
+
+impl<'a, V> Iterator for Values<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for Values<'_, V> {}
+
+impl<V> FusedIterator for Values<'_, V> {}
+
+/// The resolver for [`ArchivedTrieMap`].
+pub struct TrieMapResolver {
+    entries: VecResolver,
+    nodes: VecResolver,
+    nodes_len: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::ArchivedTrieMap;
+
+    #[derive(Debug)]
+    struct InvalidTrieNode {
+        index: usize,
+    }
+
+    impl fmt::Display for InvalidTrieNode {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "archived trie map node {} is invalid", self.index)
+        }
+    }
+
+    impl Error for InvalidTrieNode {}
+
+    unsafe impl<V, C> Verify<C> for ArchivedTrieMap<V>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let len = self.entries.len();
+            let nodes = self.nodes.as_slice();
+
+            let Some(root) = nodes.first() else {
+                fail!(InvalidTrieNode { index: 0 });
+            };
+            if root.depth() != 0 || root.entries() != (0, len) {
+                fail!(InvalidTrieNode { index: 0 });
+            }
+
+            for (index, node) in nodes.iter().enumerate() {
+                let (start, end) = node.entries();
+                let depth = node.depth();
+                // Every node but the root must have a key to read its label
+                // from, and that key must be at least as long as the label.
+                let valid_entries = start <= end
+                    && end <= len
+                    && (index == 0
+                        || start < end
+                            && self.entries[start].key.len() >= depth);
+
+                // Children must come after their parent so that walking the
+                // tree always terminates, and must be strictly deeper.
+                let (first, last) = node.children();
+                let valid_children = first <= last
+                    && last <= nodes.len()
+                    && (first == last || first > index)
+                    && nodes[first..last]
+                        .iter()
+                        .all(|child| child.depth() > depth);
+
+                if !valid_entries || !valid_children {
+                    fail!(InvalidTrieNode { index });
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! An archived version of `VecDeque`.
+
+use core::{
+    fmt, hash,
+    iter::{Chain, FusedIterator},
+    ops::Index,
+    slice,
+};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    primitive::{ArchivedUsize, FixedUsize},
+    seal::Seal,
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive as _, Place, Portable, Serialize,
+};
+
+/// An archived `VecDeque`.
+///
+/// The elements are stored in a single buffer which is treated as a ring. The
+/// front of the deque is located at a head index in the buffer, and the
+/// elements wrap around to the start of the buffer after reaching its end. The
+/// buffer has no spare capacity, so elements can't be pushed or popped, but a
+/// sealed deque can be rotated in constant time by moving its head.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedVecDeque<T> {
+    buffer: ArchivedVec<T>,
+    head: ArchivedUsize,
+}
+
+impl<T> ArchivedVecDeque<T> {
+    fn head(&self) -> usize {
+        self.head.to_native() as usize
+    }
+
+    /// Returns the number of elements in the archived deque.
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns whether the archived deque is empty.
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns a pair of slices which contain the contents of the archived
+    /// deque in order.
+    ///
+    /// The second slice is empty if the elements are contiguous in the buffer.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (back, front) = self.buffer.as_slice().split_at(self.head());
+        (front, back)
+    }
+
+    /// Returns a pair of sealed mutable slices which contain the contents of
+    /// the archived deque in order.
+    pub fn as_slices_seal(
+        this: Seal<'_, Self>,
+    ) -> (Seal<'_, [T]>, Seal<'_, [T]>) {
+        let head = this.head();
+        munge!(let Self { buffer, .. } = this);
+        let buffer = ArchivedVec::as_slice_seal(buffer);
+        // SAFETY: Splitting the slice does not move any of its elements, and
+        // both halves remain sealed.
+        let (back, front) =
+            unsafe { Seal::unseal_unchecked(buffer) }.split_at_mut(head);
+        (Seal::new(front), Seal::new(back))
+    }
+
+    /// Returns the element at the given index from the front, or `None` if it
+    /// is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let (front, back) = self.as_slices();
+        if index < front.len() {
+            Some(&front[index])
+        } else {
+            back.get(index - front.len())
+        }
+    }
+
+    /// Returns the first element of the archived deque, or `None` if it is
+    /// empty.
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    /// Returns the last element of the archived deque, or `None` if it is
+    /// empty.
+    pub fn back(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Returns an iterator over the elements of the archived deque from front
+    /// to back.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            inner: front.iter().chain(back.iter()),
+        }
+    }
+
+    /// Rotates a sealed archived deque `n` places to the left, so that the
+    /// element at index `n` becomes the first element.
+    ///
+    /// This only moves the head of the deque, so it takes constant time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the archived deque.
+    pub fn rotate_left_seal(this: Seal<'_, Self>, n: usize) {
+        let len = this.len();
+        assert!(n <= len, "rotated by more than the length of the deque");
+        if len != 0 {
+            let head = (this.head() + n) % len;
+            munge!(let Self { head: mut out_head, .. } = this);
+            *out_head = ArchivedUsize::from_native(head as FixedUsize);
+        }
+    }
+
+    /// Rotates a sealed archived deque `n` places to the right, so that the
+    /// first element moves to index `n`.
+    ///
+    /// This only moves the head of the deque, so it takes constant time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of the archived deque.
+    pub fn rotate_right_seal(this: Seal<'_, Self>, n: usize) {
+        let len = this.len();
+        assert!(n <= len, "rotated by more than the length of the deque");
+        Self::rotate_left_seal(this, len - n);
+    }
+
+    /// Resolves an archived `VecDeque` from a given length.
+    ///
+    /// The elements must have been serialized in order from front to back.
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: VecDequeResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedVecDeque { buffer, head } = out);
+        ArchivedVec::resolve_from_len(len, resolver.0, buffer);
+        0usize.resolve((), head);
+    }
+
+    /// Serializes an archived `VecDeque` from a pair of slices which contain
+    /// its elements in order.
+    pub fn serialize_from_slices<U, S>(
+        front: &[U],
+        back: &[U],
+        serializer: &mut S,
+    ) -> Result<VecDequeResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    {
+        let resolver = if back.is_empty() {
+            ArchivedVec::serialize_from_slice(front, serializer)?
+        } else if front.is_empty() {
+            ArchivedVec::serialize_from_slice(back, serializer)?
+        } else {
+            // `Chain` is not an `ExactSizeIterator`, so index into the slices
+            // instead.
+            let len = front.len() + back.len();
+            ArchivedVec::serialize_from_iter::<U, _, _>(
+                (0..len).map(|i| match front.get(i) {
+                    Some(value) => value,
+                    None => &back[i - front.len()],
+                }),
+                serializer,
+            )?
+        };
+        Ok(VecDequeResolver(resolver))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedVecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Eq> Eq for ArchivedVecDeque<T> {}
+
+impl<T: hash::Hash> hash::Hash for ArchivedVecDeque<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+impl<T> Index<usize> for ArchivedVecDeque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedVecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<ArchivedVecDeque<U>>
+    for ArchivedVecDeque<T>
+{
+    fn eq(&self, other: &ArchivedVecDeque<U>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+/// An iterator over the elements of an [`ArchivedVecDeque`].
+pub struct Iter<'a, T> {
+    inner: Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Iter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// The resolver for [`ArchivedVecDeque`].
+pub struct VecDequeResolver(VecResolver);
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{Fallible, Source},
+        Verify,
+    };
+    use rancor::fail;
+
+    use super::ArchivedVecDeque;
+
+    #[derive(Debug)]
+    struct InvalidHead {
+        head: usize,
+        len: usize,
+    }
+
+    impl fmt::Display for InvalidHead {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "archived deque head {} is out of bounds for length {}",
+                self.head, self.len,
+            )
+        }
+    }
+
+    impl Error for InvalidHead {}
+
+    unsafe impl<T, C> Verify<C> for ArchivedVecDeque<T>
+    where
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+            let head = self.head();
+            let len = self.len();
+            if head != 0 && head >= len {
+                fail!(InvalidHead { head, len });
+            }
+            Ok(())
+        }
+    }
+}
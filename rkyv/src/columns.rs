@@ -0,0 +1,142 @@
+//! Column-oriented archiving for sequences of structs.
+//!
+//! Structs which derive `Archive` with `#[rkyv(columns)]` can be archived in a
+//! struct-of-arrays layout with the [`AsColumns`](crate::with::AsColumns)
+//! wrapper. Instead of archiving each element of a `Vec<T>` one after another,
+//! each field is archived into its own `ArchivedVec`. Scans over a single field
+//! then only touch the bytes of that field.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     rancor::Error, with::AsColumns, Archive, Deserialize, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+//! #[rkyv(columns)]
+//! struct Point {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+//! struct Points {
+//!     #[rkyv(with = AsColumns)]
+//!     points: Vec<Point>,
+//! }
+//!
+//! let value = Points {
+//!     points: vec![Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }],
+//! };
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//! let archived = rkyv::access::<ArchivedPoints, Error>(&bytes).unwrap();
+//!
+//! // The archived points are an `ArchivedPointColumns`
+//! assert_eq!(archived.points.len(), 2);
+//! let sum_x = archived.points.x.iter().map(|x| x.to_native()).sum::<f32>();
+//! assert_eq!(sum_x, 4.0);
+//!
+//! let deserialized = rkyv::deserialize::<Points, Error>(archived).unwrap();
+//! assert_eq!(deserialized, value);
+//! ```
+
+use rancor::Fallible;
+
+use crate::{Place, Portable};
+
+/// A type which can be archived as columns.
+///
+/// This trait is implemented by deriving `Archive` with `#[rkyv(columns)]`.
+pub trait ArchiveColumns: Sized {
+    /// The archived columns for a sequence of this type.
+    type ArchivedColumns: Portable;
+    /// The resolver for the archived columns.
+    type ColumnsResolver;
+
+    /// Returns the number of rows in the given archived columns.
+    fn columns_len(columns: &Self::ArchivedColumns) -> usize;
+
+    /// Creates the archived columns for a sequence of `len` values at the
+    /// given output location.
+    fn resolve_columns(
+        len: usize,
+        resolver: Self::ColumnsResolver,
+        out: Place<Self::ArchivedColumns>,
+    );
+}
+
+/// A type which can be serialized as columns.
+///
+/// This trait is implemented by deriving `Serialize` for a type which derives
+/// `Archive` with `#[rkyv(columns)]`.
+pub trait SerializeColumns<S: Fallible + ?Sized>: ArchiveColumns {
+    /// Writes the columns of the given values to the serializer and returns a
+    /// resolver for them.
+    fn serialize_columns(
+        values: &[Self],
+        serializer: &mut S,
+    ) -> Result<Self::ColumnsResolver, S::Error>;
+}
+
+/// Archived columns which can be deserialized into rows of `T`.
+///
+/// This trait is implemented by deriving `Deserialize` for a type which derives
+/// `Archive` with `#[rkyv(columns)]`.
+pub trait DeserializeColumns<T, D: Fallible + ?Sized> {
+    /// Deserializes the row at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    fn deserialize_row(
+        &self,
+        index: usize,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error>;
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{error::Error, fmt};
+
+    use rancor::{fail, Source};
+
+    #[derive(Debug)]
+    struct ColumnLengthMismatch {
+        expected: usize,
+        found: usize,
+    }
+
+    impl fmt::Display for ColumnLengthMismatch {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "archived column length mismatch: expected {} rows but found \
+                 {}",
+                self.expected, self.found,
+            )
+        }
+    }
+
+    impl Error for ColumnLengthMismatch {}
+
+    /// Checks that all of the given column lengths are the same.
+    ///
+    /// This is used by the `CheckBytes` implementations of archived columns.
+    pub fn check_column_lengths<E: Source>(lengths: &[usize]) -> Result<(), E> {
+        if let Some((&expected, rest)) = lengths.split_first() {
+            for &found in rest {
+                if found != expected {
+                    fail!(ColumnLengthMismatch { expected, found });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::verify::check_column_lengths;
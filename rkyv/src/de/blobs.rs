@@ -0,0 +1,116 @@
+//! Deserializers which can read bytes stored outside of the archive.
+
+use core::{error::Error, fmt};
+
+use rancor::{fail, Fallible, Source, Strategy};
+
+use crate::{
+    blob::ArchivedBlob,
+    de::pooling::{ErasedPtr, Pooling, PoolingState},
+};
+
+/// A deserializer which can read bytes from the blob region of an archive.
+///
+/// This trait is required to deserialize fields with the
+/// [`ExternalBytes`](crate::with::ExternalBytes) wrapper.
+pub trait BlobSource<E = <Self as Fallible>::Error> {
+    /// Returns the blob region for the archive being deserialized.
+    fn blobs(&self) -> &[u8];
+}
+
+impl<T, E> BlobSource<E> for Strategy<T, E>
+where
+    T: BlobSource<E>,
+{
+    fn blobs(&self) -> &[u8] {
+        T::blobs(self)
+    }
+}
+
+#[derive(Debug)]
+struct BlobOutOfBounds {
+    offset: u64,
+    len: u64,
+    blobs_len: usize,
+}
+
+impl fmt::Display for BlobOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "blob of {} bytes at offset {} is out of bounds for a blob region \
+             of {} bytes",
+            self.len, self.offset, self.blobs_len,
+        )
+    }
+}
+
+impl Error for BlobOutOfBounds {}
+
+/// Helper methods for [`BlobSource`].
+pub trait BlobSourceExt<E>: BlobSource<E> {
+    /// Returns the bytes of the given archived blob.
+    ///
+    /// Returns an error if the blob is not located in the blob region.
+    fn get_blob(&self, blob: &ArchivedBlob) -> Result<&[u8], E>
+    where
+        E: Source,
+    {
+        let blobs = self.blobs();
+        match blob.get(blobs) {
+            Some(bytes) => Ok(bytes),
+            None => fail!(BlobOutOfBounds {
+                offset: blob.offset(),
+                len: blob.len(),
+                blobs_len: blobs.len(),
+            }),
+        }
+    }
+}
+
+impl<T, E> BlobSourceExt<E> for T where T: BlobSource<E> + ?Sized {}
+
+/// A deserializer which reads blobs from a blob region.
+///
+/// Shared pointers are deserialized with the inner deserializer `D`.
+#[derive(Debug)]
+pub struct BlobReader<'a, D> {
+    blobs: &'a [u8],
+    inner: D,
+}
+
+impl<'a, D> BlobReader<'a, D> {
+    /// Creates a new blob reader for the given blob region which wraps the
+    /// given deserializer.
+    pub fn new(blobs: &'a [u8], inner: D) -> Self {
+        Self { blobs, inner }
+    }
+
+    /// Returns the inner deserializer.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D, E> BlobSource<E> for BlobReader<'_, D> {
+    fn blobs(&self) -> &[u8] {
+        self.blobs
+    }
+}
+
+impl<D: Pooling<E>, E> Pooling<E> for BlobReader<'_, D> {
+    fn start_pooling(&mut self, address: usize) -> PoolingState {
+        self.inner.start_pooling(address)
+    }
+
+    unsafe fn finish_pooling(
+        &mut self,
+        address: usize,
+        ptr: ErasedPtr,
+        drop: unsafe fn(ErasedPtr),
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `finish_pooling` are the same as
+        // the requirements for calling this function.
+        unsafe { self.inner.finish_pooling(address, ptr, drop) }
+    }
+}
@@ -0,0 +1,130 @@
+//! Deserializers which can borrow data from the archive buffer.
+
+use core::{error::Error, fmt, mem::size_of_val};
+
+use rancor::{fail, Fallible, Source, Strategy};
+
+use crate::de::pooling::{ErasedPtr, Pooling, PoolingState};
+
+/// A deserializer which can borrow archived data for the lifetime of the
+/// buffer it is deserializing from.
+///
+/// This trait is required to deserialize with the
+/// [`Borrowed`](crate::with::Borrowed) wrapper.
+pub trait Borrowing<'a, E = <Self as Fallible>::Error> {
+    /// Returns the buffer which values are being deserialized from.
+    fn buffer(&self) -> &'a [u8];
+}
+
+impl<'a, T, E> Borrowing<'a, E> for Strategy<T, E>
+where
+    T: Borrowing<'a, E>,
+{
+    fn buffer(&self) -> &'a [u8] {
+        T::buffer(self)
+    }
+}
+
+#[derive(Debug)]
+struct NotInBuffer {
+    address: usize,
+    size: usize,
+}
+
+impl fmt::Display for NotInBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tried to borrow {} bytes at address {:#x} which are not located \
+             in the deserialization buffer",
+            self.size, self.address,
+        )
+    }
+}
+
+impl Error for NotInBuffer {}
+
+/// Checks that the `size` bytes at `address` are located entirely within
+/// `buffer`.
+pub(crate) fn check_in_buffer<E: Source>(
+    buffer: &[u8],
+    address: usize,
+    size: usize,
+) -> Result<(), E> {
+    let start = buffer.as_ptr() as usize;
+    let in_buffer = address >= start
+        && address
+            .checked_add(size)
+            .is_some_and(|end| end <= start + buffer.len());
+    if !in_buffer {
+        fail!(NotInBuffer { address, size });
+    }
+    Ok(())
+}
+
+/// Helper methods for [`Borrowing`].
+pub trait BorrowingExt<'a, E>: Borrowing<'a, E> {
+    /// Extends the lifetime of a reference to an archived value to the
+    /// lifetime of the buffer.
+    ///
+    /// Returns an error if the value is not located in the buffer.
+    fn borrow_archived<T: ?Sized>(&self, value: &T) -> Result<&'a T, E>
+    where
+        E: Source,
+    {
+        let address = value as *const T as *const () as usize;
+        check_in_buffer(self.buffer(), address, size_of_val(value))?;
+
+        // SAFETY: The value is located entirely within the buffer, which is
+        // immutably borrowed for `'a`.
+        Ok(unsafe { &*(value as *const T) })
+    }
+}
+
+impl<'a, T, E> BorrowingExt<'a, E> for T where T: Borrowing<'a, E> + ?Sized {}
+
+/// A deserializer which allows values to borrow from the buffer they are
+/// deserialized from.
+///
+/// Shared pointers are deserialized with the inner deserializer `D`.
+#[derive(Debug)]
+pub struct Borrower<'a, D> {
+    buffer: &'a [u8],
+    inner: D,
+}
+
+impl<'a, D> Borrower<'a, D> {
+    /// Creates a new borrower for the given buffer which wraps the given
+    /// deserializer.
+    pub fn new(buffer: &'a [u8], inner: D) -> Self {
+        Self { buffer, inner }
+    }
+
+    /// Returns the inner deserializer.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<'a, D, E> Borrowing<'a, E> for Borrower<'a, D> {
+    fn buffer(&self) -> &'a [u8] {
+        self.buffer
+    }
+}
+
+impl<D: Pooling<E>, E> Pooling<E> for Borrower<'_, D> {
+    fn start_pooling(&mut self, address: usize) -> PoolingState {
+        self.inner.start_pooling(address)
+    }
+
+    unsafe fn finish_pooling(
+        &mut self,
+        address: usize,
+        ptr: ErasedPtr,
+        drop: unsafe fn(ErasedPtr),
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `finish_pooling` are the same as
+        // the requirements for calling this function.
+        unsafe { self.inner.finish_pooling(address, ptr, drop) }
+    }
+}
@@ -1,6 +1,13 @@
 //! Deserialization traits, deserializers, and adapters.
 
+pub mod blobs;
+pub mod borrowing;
 pub mod pooling;
+#[cfg(feature = "bytes-1")]
+pub mod slicing;
 
+#[cfg(feature = "bytes-1")]
 #[doc(inline)]
-pub use self::pooling::*;
+pub use self::slicing::*;
+#[doc(inline)]
+pub use self::{blobs::*, borrowing::*, pooling::*};
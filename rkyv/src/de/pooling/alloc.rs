@@ -24,6 +24,37 @@ impl Drop for SharedPointer {
 
 /// A shared pointer strategy that pools together deserializations of the same
 /// shared pointer.
+///
+/// Shared pointers are pooled by the address of their archived value. A pool
+/// keeps a reference to every shared pointer it has deserialized until it is
+/// cleared or dropped, so reusing a pool across deserializations from the same
+/// archive also shares pointers between the deserialized values. A pool must be
+/// [cleared](Pool::clear) before it is used with a different archive, since a
+/// different archive may place a different type at the same address.
+///
+/// Clearing a pool retains its allocation, so a single pool can be reused in a
+/// hot deserialization loop without reallocating its internal map:
+///
+/// ```
+/// use rkyv::{
+///     access, api::high::deserialize_with_pool, de::Pool, rancor::Error,
+///     to_bytes,
+/// };
+///
+/// let mut pool = Pool::with_capacity(16);
+/// for i in 0..10u32 {
+///     let bytes = to_bytes::<Error>(&vec![i; 4]).unwrap();
+///     let archived =
+///         access::<rkyv::Archived<Vec<u32>>, Error>(&bytes).unwrap();
+///     let value =
+///         deserialize_with_pool::<Vec<u32>, Error>(archived, &mut pool)
+///             .unwrap();
+///     assert_eq!(value, vec![i; 4]);
+///
+///     pool.clear();
+/// }
+/// assert!(pool.capacity() >= 16);
+/// ```
 #[derive(Default)]
 pub struct Pool {
     shared_pointers:
@@ -47,6 +78,40 @@ impl Pool {
             ),
         }
     }
+
+    /// Returns the number of shared pointers in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.shared_pointers.len()
+    }
+
+    /// Returns whether the pool has no shared pointers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.shared_pointers.is_empty()
+    }
+
+    /// Returns the number of shared pointers the pool can hold without
+    /// reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.shared_pointers.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more shared pointers.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.shared_pointers.reserve(additional);
+    }
+
+    /// Releases all of the shared pointers in the pool while keeping its
+    /// allocated memory.
+    ///
+    /// The pool may be used with a different archive after it is cleared.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.shared_pointers.clear();
+    }
 }
 
 impl fmt::Debug for Pool {
@@ -0,0 +1,96 @@
+//! Deserializers which can share ownership of the archive buffer.
+
+use bytes_1::Bytes;
+use rancor::{Fallible, Source, Strategy};
+
+use crate::de::{
+    borrowing::check_in_buffer,
+    pooling::{ErasedPtr, Pooling, PoolingState},
+};
+
+/// A deserializer which holds a reference-counted owner of the buffer it is
+/// deserializing from.
+///
+/// This trait is required to deserialize with the
+/// [`AsOwnedBytes`](crate::with::AsOwnedBytes) wrapper.
+pub trait SlicingBytes<E = <Self as Fallible>::Error> {
+    /// Returns the owner of the buffer which values are being deserialized
+    /// from.
+    fn bytes_owner(&self) -> &Bytes;
+}
+
+impl<T, E> SlicingBytes<E> for Strategy<T, E>
+where
+    T: SlicingBytes<E>,
+{
+    fn bytes_owner(&self) -> &Bytes {
+        T::bytes_owner(self)
+    }
+}
+
+/// Helper methods for [`SlicingBytes`].
+pub trait SlicingBytesExt<E>: SlicingBytes<E> {
+    /// Returns a `Bytes` which shares ownership of the buffer and contains
+    /// exactly the given archived bytes.
+    ///
+    /// Returns an error if the bytes are not located in the buffer.
+    fn slice_bytes(&self, bytes: &[u8]) -> Result<Bytes, E>
+    where
+        E: Source,
+    {
+        let owner = self.bytes_owner();
+        if bytes.is_empty() {
+            return Ok(Bytes::new());
+        }
+        check_in_buffer(owner, bytes.as_ptr() as usize, bytes.len())?;
+        Ok(owner.slice_ref(bytes))
+    }
+}
+
+impl<T, E> SlicingBytesExt<E> for T where T: SlicingBytes<E> + ?Sized {}
+
+/// A deserializer which allows values to share ownership of the buffer they
+/// are deserialized from.
+///
+/// Shared pointers are deserialized with the inner deserializer `D`.
+#[derive(Debug)]
+pub struct BytesSlicer<D> {
+    owner: Bytes,
+    inner: D,
+}
+
+impl<D> BytesSlicer<D> {
+    /// Creates a new bytes slicer for the given buffer owner which wraps the
+    /// given deserializer.
+    pub fn new(owner: Bytes, inner: D) -> Self {
+        Self { owner, inner }
+    }
+
+    /// Returns the inner deserializer.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D, E> SlicingBytes<E> for BytesSlicer<D> {
+    fn bytes_owner(&self) -> &Bytes {
+        &self.owner
+    }
+}
+
+impl<D: Pooling<E>, E> Pooling<E> for BytesSlicer<D> {
+    fn start_pooling(&mut self, address: usize) -> PoolingState {
+        self.inner.start_pooling(address)
+    }
+
+    unsafe fn finish_pooling(
+        &mut self,
+        address: usize,
+        ptr: ErasedPtr,
+        drop: unsafe fn(ErasedPtr),
+    ) -> Result<(), E> {
+        // SAFETY: The safety requirements for `finish_pooling` are the same as
+        // the requirements for calling this function.
+        unsafe { self.inner.finish_pooling(address, ptr, drop) }
+    }
+}
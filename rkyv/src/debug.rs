@@ -0,0 +1,420 @@
+//! Layout inspection for archives.
+//!
+//! [`dump_layout`] validates an archive and records where every archived
+//! object is located in the buffer. Objects which are pointed to by other
+//! objects (like the elements of an `ArchivedVec` or the bytes of an
+//! `ArchivedString`) are reported as children of the object that points to
+//! them. Bytes which don't belong to any object are reported as padding.
+//!
+//! Values which are stored inline (like the fields of a struct) are part of
+//! the object which contains them and are not reported separately.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     debug::dump_layout, rancor::Error, to_bytes, Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Example {
+//!     name: String,
+//!     values: Vec<u32>,
+//! }
+//!
+//! let value = Example {
+//!     name: "a name longer than eight bytes".to_string(),
+//!     values: vec![1, 2, 3],
+//! };
+//!
+//! let bytes = to_bytes::<Error>(&value).unwrap();
+//! let layout = dump_layout::<ArchivedExample, Error>(&bytes);
+//! assert!(layout.error().is_none());
+//!
+//! let root = layout.root().unwrap();
+//! assert_eq!(root.size(), size_of::<ArchivedExample>());
+//! // The string bytes and the vec elements are stored out-of-line.
+//! assert_eq!(root.children().len(), 2);
+//! assert_eq!(root.children()[1].size(), 12);
+//!
+//! println!("{layout}");
+//! ```
+
+use core::{alloc::Layout, fmt, ops::Range};
+
+use bytecheck::CheckBytes;
+use rancor::{Source, Strategy};
+
+use crate::{
+    alloc::vec::Vec,
+    api::{check_pos_with_context, root_position},
+    validation::{
+        archive::ArchiveValidator, shared::SharedValidator, ArchiveContext,
+        Validator,
+    },
+    Portable,
+};
+
+/// An archived object located in a buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutNode {
+    offset: usize,
+    size: usize,
+    align: usize,
+    children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    /// Returns the offset of the object from the start of the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the size of the object in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the alignment the object was checked with.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the range of bytes which the object occupies.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.size
+    }
+
+    /// Returns the objects which are pointed to by this object, in the order
+    /// they were validated.
+    pub fn children(&self) -> &[LayoutNode] {
+        &self.children
+    }
+
+    fn collect_ranges(&self, ranges: &mut Vec<Range<usize>>) {
+        ranges.push(self.range());
+        for child in self.children.iter() {
+            child.collect_ranges(ranges);
+        }
+    }
+
+    fn fmt_indented(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+    ) -> fmt::Result {
+        writeln!(
+            f,
+            "{:indent$}{:#06x}..{:#06x} size {} align {}",
+            "",
+            self.offset,
+            self.offset + self.size,
+            self.size,
+            self.align,
+            indent = depth * 2,
+        )?;
+        for child in self.children.iter() {
+            child.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The layout of the objects in an archive.
+///
+/// If validation failed, the layout contains every object which was reached
+/// before the failure along with the error.
+pub struct LayoutTree<E> {
+    len: usize,
+    root: Option<LayoutNode>,
+    error: Option<E>,
+}
+
+impl<E> LayoutTree<E> {
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the buffer was empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the root object, if validation reached it.
+    pub fn root(&self) -> Option<&LayoutNode> {
+        self.root.as_ref()
+    }
+
+    /// Returns the error which validation failed with, if any.
+    pub fn error(&self) -> Option<&E> {
+        self.error.as_ref()
+    }
+
+    /// Returns the ranges of bytes which don't belong to any object.
+    ///
+    /// This is usually padding inserted to align objects. Large ranges of
+    /// padding can also indicate data which is no longer reachable from the
+    /// root.
+    pub fn padding(&self) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        if let Some(root) = &self.root {
+            root.collect_ranges(&mut ranges);
+        }
+        ranges.sort_unstable_by_key(|range| range.start);
+
+        let mut result = Vec::new();
+        let mut pos = 0;
+        for range in ranges {
+            if range.start > pos {
+                result.push(pos..range.start);
+            }
+            pos = pos.max(range.end);
+        }
+        if self.len > pos {
+            result.push(pos..self.len);
+        }
+        result
+    }
+}
+
+impl<E: fmt::Debug> fmt::Debug for LayoutTree<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LayoutTree")
+            .field("len", &self.len)
+            .field("root", &self.root)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for LayoutTree<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "archive of {} bytes", self.len)?;
+        if let Some(root) = &self.root {
+            root.fmt_indented(f, 1)?;
+        }
+        let padding = self.padding();
+        if !padding.is_empty() {
+            writeln!(f, "padding:")?;
+            for range in padding {
+                writeln!(
+                    f,
+                    "  {:#06x}..{:#06x} size {}",
+                    range.start,
+                    range.end,
+                    range.len(),
+                )?;
+            }
+        }
+        if let Some(error) = &self.error {
+            writeln!(f, "error: {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An [`ArchiveContext`] which records the objects that are validated.
+///
+/// Each subtree range pushed onto the context is recorded as an object, and
+/// the alignment it was checked with is taken from the preceding call to
+/// `check_subtree_ptr`.
+#[derive(Debug)]
+pub struct LayoutRecorder<C> {
+    inner: C,
+    base: usize,
+    checked: Option<(usize, usize)>,
+    stack: Vec<LayoutNode>,
+    root: Option<LayoutNode>,
+}
+
+impl<C> LayoutRecorder<C> {
+    /// Creates a new layout recorder which wraps the given context and
+    /// validates the given buffer.
+    pub fn new(inner: C, bytes: &[u8]) -> Self {
+        Self {
+            inner,
+            base: bytes.as_ptr() as usize,
+            checked: None,
+            stack: Vec::new(),
+            root: None,
+        }
+    }
+
+    /// Finishes recording and returns the root object, if one was recorded.
+    ///
+    /// Objects which were not finished because validation failed are still
+    /// included.
+    pub fn finish(mut self) -> Option<LayoutNode> {
+        while let Some(node) = self.stack.pop() {
+            self.attach(node);
+        }
+        self.root
+    }
+
+    fn attach(&mut self, node: LayoutNode) {
+        match self.stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => self.root = Some(node),
+        }
+    }
+}
+
+unsafe impl<C, E> ArchiveContext<E> for LayoutRecorder<C>
+where
+    C: ArchiveContext<E>,
+{
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), E> {
+        self.inner.check_subtree_ptr(ptr, layout)?;
+        self.checked = Some((ptr as usize, layout.align()));
+        Ok(())
+    }
+
+    unsafe fn push_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, E> {
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements.
+        let range = unsafe { self.inner.push_subtree_range(root, end)? };
+
+        let align = match self.checked.take() {
+            Some((ptr, align)) if ptr == root as usize => align,
+            _ => 1,
+        };
+        self.stack.push(LayoutNode {
+            offset: (root as usize).wrapping_sub(self.base),
+            size: (end as usize).wrapping_sub(root as usize),
+            align,
+            children: Vec::new(),
+        });
+
+        Ok(range)
+    }
+
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), E> {
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements.
+        unsafe { self.inner.pop_subtree_range(range)? };
+
+        if let Some(node) = self.stack.pop() {
+            self.attach(node);
+        }
+        Ok(())
+    }
+}
+
+/// A validator which records the layout of the objects it validates.
+pub type LayoutValidator<'a, E> = Strategy<
+    Validator<LayoutRecorder<ArchiveValidator<'a>>, SharedValidator>,
+    E,
+>;
+
+/// Validates an archive and records the layout of its objects.
+///
+/// Validation failures are reported through [`LayoutTree::error`], and the
+/// returned layout contains all of the objects which were reached before the
+/// failure. This makes it possible to inspect corrupted archives.
+pub fn dump_layout<T, E>(bytes: &[u8]) -> LayoutTree<E>
+where
+    T: Portable + for<'a> CheckBytes<LayoutValidator<'a, E>>,
+    E: Source,
+{
+    let mut validator = Validator::new(
+        LayoutRecorder::new(ArchiveValidator::new(bytes), bytes),
+        SharedValidator::new(),
+    );
+    let result = check_pos_with_context::<T, _, E>(
+        bytes,
+        root_position::<T>(bytes.len()),
+        &mut validator,
+    );
+    let (recorder, _) = validator.into_parts();
+
+    LayoutTree {
+        len: bytes.len(),
+        root: recorder.finish(),
+        error: result.err(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use rancor::{Error, Failure};
+
+    use crate::{
+        alloc::{string::ToString, vec, vec::Vec},
+        api::high::to_bytes,
+        debug::dump_layout,
+        string::ArchivedString,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Example {
+        flag: bool,
+        name: String,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn dump_example_layout() {
+        let value = Example {
+            flag: true,
+            name: "a string which is stored out of line".to_string(),
+            values: vec![1, 2, 3],
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let layout = dump_layout::<ArchivedExample, Failure>(&bytes);
+        assert!(layout.error().is_none());
+        assert_eq!(layout.len(), bytes.len());
+
+        let root = layout.root().unwrap();
+        assert_eq!(root.size(), size_of::<ArchivedExample>());
+        assert_eq!(root.range().end, bytes.len());
+        assert_eq!(root.children().len(), 2);
+
+        let name = &root.children()[0];
+        assert_eq!(name.size(), value.name.len());
+        assert_eq!(name.align(), 1);
+        assert_eq!(&bytes[name.range()], value.name.as_bytes(),);
+
+        let values = &root.children()[1];
+        assert_eq!(values.size(), 12);
+        assert_eq!(values.align(), 4);
+
+        let covered = layout.root().unwrap().size()
+            + name.size()
+            + values.size()
+            + layout.padding().iter().map(|r| r.len()).sum::<usize>();
+        assert_eq!(covered, bytes.len());
+    }
+
+    #[test]
+    fn dump_invalid_layout() {
+        let value = vec![
+            "a string which is stored out of line".to_string(),
+            "another string which is stored out of line".to_string(),
+        ];
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+        let pos = bytes.len() - 1;
+        bytes[pos] = 0xff;
+        let layout = dump_layout::<
+            crate::vec::ArchivedVec<ArchivedString>,
+            Failure,
+        >(&bytes);
+        assert!(layout.error().is_some());
+        assert!(layout.root().is_some());
+    }
+}
@@ -0,0 +1,390 @@
+//! Structural diffs between archived values.
+//!
+//! [`Diff`] compares two archived values of the same type and reports the path
+//! of every field that changed between them. Neither value needs to be
+//! deserialized. `Diff` can be derived for archived types with
+//! `#[rkyv(diff)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     access, diff::diff, rancor::Error, to_bytes, Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(diff)]
+//! struct Player {
+//!     name: String,
+//!     position: (f32, f32),
+//!     inventory: Vec<Item>,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(diff)]
+//! struct Item {
+//!     id: u32,
+//!     count: u32,
+//! }
+//!
+//! let before = Player {
+//!     name: "ferris".to_string(),
+//!     position: (0.0, 0.0),
+//!     inventory: vec![Item { id: 1, count: 3 }, Item { id: 2, count: 1 }],
+//! };
+//! let after = Player {
+//!     name: "ferris".to_string(),
+//!     position: (0.0, 0.0),
+//!     inventory: vec![Item { id: 1, count: 2 }, Item { id: 2, count: 1 }],
+//! };
+//!
+//! let before_bytes = to_bytes::<Error>(&before).unwrap();
+//! let after_bytes = to_bytes::<Error>(&after).unwrap();
+//! let before = access::<ArchivedPlayer, Error>(&before_bytes).unwrap();
+//! let after = access::<ArchivedPlayer, Error>(&after_bytes).unwrap();
+//!
+//! let changes = diff(before, after);
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].to_string(), "inventory[0].count");
+//! ```
+
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    alloc::vec::Vec,
+    boxed::ArchivedBox,
+    collections::{
+        bitvec::ArchivedBitVec, btree_map::ArchivedBTreeMap,
+        swiss_table::ArchivedHashMap,
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedU128, ArchivedU16, ArchivedU32,
+        ArchivedU64,
+    },
+    rel_ptr::Offset,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    vec::ArchivedVec,
+};
+
+/// A single step in the path to a changed value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A named or positional field of a struct or enum variant.
+    Field(&'static str),
+    /// An element of a sequence.
+    Index(usize),
+}
+
+/// The path from the root of an archived value to a value that changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FieldPath {
+    segments: Vec<PathSegment>,
+}
+
+impl FieldPath {
+    /// Returns the segments of the path, starting from the root.
+    ///
+    /// An empty path means that the root value itself changed.
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.segments.is_empty() {
+            return write!(f, ".");
+        }
+
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if i == 0 => write!(f, "{name}")?,
+                PathSegment::Field(name) => write!(f, ".{name}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tracks the current path while diffing and collects the paths of changed
+/// values.
+#[derive(Debug, Default)]
+pub struct Differ {
+    path: Vec<PathSegment>,
+    changes: Vec<FieldPath>,
+}
+
+impl Differ {
+    /// Returns a new `Differ` with no recorded changes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the value at the current path changed.
+    pub fn changed(&mut self) {
+        self.changes.push(FieldPath {
+            segments: self.path.clone(),
+        });
+    }
+
+    /// Diffs two values at the given path segment below the current path.
+    pub fn segment<T: Diff + ?Sized>(
+        &mut self,
+        segment: PathSegment,
+        this: &T,
+        other: &T,
+    ) {
+        self.path.push(segment);
+        this.diff(other, self);
+        self.path.pop();
+    }
+
+    /// Diffs two values of the field with the given name.
+    pub fn field<T: Diff + ?Sized>(
+        &mut self,
+        name: &'static str,
+        this: &T,
+        other: &T,
+    ) {
+        self.segment(PathSegment::Field(name), this, other);
+    }
+
+    /// Diffs two values at the given index.
+    pub fn index<T: Diff + ?Sized>(
+        &mut self,
+        index: usize,
+        this: &T,
+        other: &T,
+    ) {
+        self.segment(PathSegment::Index(index), this, other);
+    }
+
+    /// Returns the paths of all of the changes that were recorded.
+    pub fn into_changes(self) -> Vec<FieldPath> {
+        self.changes
+    }
+}
+
+/// A type which can report the structural differences between two values.
+///
+/// This is typically implemented for archived types, and can be derived with
+/// `#[rkyv(diff)]`. Types without any structure to diff into report a change
+/// at their own path whenever they are not equal.
+pub trait Diff {
+    /// Records the paths of all of the values that differ between `self` and
+    /// `other` in `differ`.
+    fn diff(&self, other: &Self, differ: &mut Differ);
+}
+
+/// Returns the paths of all of the values that differ between two values.
+pub fn diff<T: Diff + ?Sized>(this: &T, other: &T) -> Vec<FieldPath> {
+    let mut differ = Differ::new();
+    this.diff(other, &mut differ);
+    differ.into_changes()
+}
+
+macro_rules! impl_diff_eq {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Diff for $ty {
+                fn diff(&self, other: &Self, differ: &mut Differ) {
+                    if self != other {
+                        differ.changed();
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_diff_eq! {
+    (),
+    bool,
+    i8,
+    u8,
+    str,
+    ArchivedI16,
+    ArchivedI32,
+    ArchivedI64,
+    ArchivedI128,
+    ArchivedU16,
+    ArchivedU32,
+    ArchivedU64,
+    ArchivedU128,
+    ArchivedF32,
+    ArchivedF64,
+    ArchivedChar,
+    ArchivedString,
+    ArchivedBitVec,
+}
+
+impl<T: Diff> Diff for [T] {
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        if self.len() != other.len() {
+            differ.changed();
+        } else {
+            for (i, (this, other)) in self.iter().zip(other.iter()).enumerate()
+            {
+                differ.index(i, this, other);
+            }
+        }
+    }
+}
+
+impl<T: Diff, const N: usize> Diff for [T; N] {
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        self.as_slice().diff(other.as_slice(), differ);
+    }
+}
+
+impl<T: Diff, O: Offset, L: Offset> Diff for ArchivedVec<T, O, L> {
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        self.as_slice().diff(other.as_slice(), differ);
+    }
+}
+
+impl<T: ArchivePointee + Diff + ?Sized> Diff for ArchivedBox<T> {
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        self.get().diff(other.get(), differ);
+    }
+}
+
+impl<T: Diff> Diff for ArchivedOption<T> {
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        match (self.as_ref(), other.as_ref()) {
+            (Some(this), Some(other)) => this.diff(other, differ),
+            (None, None) => (),
+            _ => differ.changed(),
+        }
+    }
+}
+
+impl<K, V, const E: usize> Diff for ArchivedBTreeMap<K, V, E>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        if self.len() != other.len() || !self.iter().eq(other.iter()) {
+            differ.changed();
+        }
+    }
+}
+
+impl<K, V, H> Diff for ArchivedHashMap<K, V, H>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+{
+    fn diff(&self, other: &Self, differ: &mut Differ) {
+        if self != other {
+            differ.changed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::Panic;
+
+    use super::{diff, FieldPath};
+    use crate::{
+        access_unchecked,
+        alloc::{
+            boxed::Box,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        to_bytes, Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, diff)]
+    enum Shape {
+        Point,
+        Circle(f32),
+        Rect { width: f32, height: f32 },
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, diff)]
+    struct Labeled<T>(String, T);
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, diff)]
+    struct Scene {
+        shapes: Vec<Labeled<Shape>>,
+        parent: Option<Box<Shape>>,
+        r#type: u8,
+    }
+
+    fn diff_scenes(a: &Scene, b: &Scene) -> Vec<String> {
+        let a_bytes = to_bytes::<Panic>(a).unwrap();
+        let b_bytes = to_bytes::<Panic>(b).unwrap();
+        let a = unsafe { access_unchecked::<ArchivedScene>(&a_bytes) };
+        let b = unsafe { access_unchecked::<ArchivedScene>(&b_bytes) };
+        diff(a, b).iter().map(FieldPath::to_string).collect()
+    }
+
+    #[test]
+    fn diff_derived() {
+        let base = Scene {
+            shapes: vec![
+                Labeled("origin".to_string(), Shape::Point),
+                Labeled("wheel".to_string(), Shape::Circle(1.0)),
+                Labeled(
+                    "door".to_string(),
+                    Shape::Rect {
+                        width: 1.0,
+                        height: 2.0,
+                    },
+                ),
+            ],
+            parent: Some(Box::new(Shape::Circle(10.0))),
+            r#type: 1,
+        };
+        assert!(diff_scenes(&base, &base).is_empty());
+
+        let changed = Scene {
+            shapes: vec![
+                Labeled("origin".to_string(), Shape::Circle(0.0)),
+                Labeled("wheel".to_string(), Shape::Circle(1.0)),
+                Labeled(
+                    "gate".to_string(),
+                    Shape::Rect {
+                        width: 1.0,
+                        height: 3.0,
+                    },
+                ),
+            ],
+            parent: None,
+            r#type: 2,
+        };
+        assert_eq!(
+            diff_scenes(&base, &changed),
+            [
+                "shapes[0].1",
+                "shapes[2].0",
+                "shapes[2].1.height",
+                "parent",
+                "type",
+            ],
+        );
+
+        let shorter = Scene {
+            shapes: Vec::new(),
+            parent: Some(Box::new(Shape::Circle(11.0))),
+            r#type: 1,
+        };
+        assert_eq!(diff_scenes(&base, &shorter), ["shapes", "parent.0"]);
+    }
+}
@@ -0,0 +1,301 @@
+//! Dynamically-typed values.
+//!
+//! [`Value`] is a JSON-like value which can hold null, booleans, numbers,
+//! strings, arrays, and objects. It archives to an [`ArchivedValue`], which can
+//! be traversed without deserializing.
+//!
+//! With the `serde_json-1` feature enabled, `serde_json::Value` can also be
+//! serialized directly to an `ArchivedValue` and deserialized back out of one.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     dynamic::{ArchivedValue, Number, Value},
+//!     rancor::Error,
+//! };
+//!
+//! let value = Value::Object(
+//!     [
+//!         ("name".into(), Value::String("ferris".into())),
+//!         ("age".into(), Value::Number(Number::PosInt(10))),
+//!         ("is_crab".into(), Value::Bool(true)),
+//!     ]
+//!     .into_iter()
+//!     .collect(),
+//! );
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//! let archived = rkyv::access::<ArchivedValue, Error>(&bytes).unwrap();
+//!
+//! assert_eq!(archived.get("name").unwrap().as_str(), Some("ferris"));
+//! assert_eq!(archived.get("age").unwrap().as_u64(), Some(10));
+//! assert_eq!(archived.get("is_crab").unwrap().as_bool(), Some(true));
+//! assert!(archived.get("project").is_none());
+//! ```
+
+use core::borrow::Borrow;
+
+use rancor::Source;
+
+use crate::{
+    alloc::{collections::BTreeMap, string::String, vec::Vec},
+    collections::btree_map::ArchivedBTreeMap,
+    ser::{Allocator, Writer},
+    string::ArchivedString,
+    vec::ArchivedVec,
+    Archive, Deserialize, Serialize,
+};
+
+/// A dynamically-typed number.
+#[derive(Archive, Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[rkyv(crate, compare(PartialEq), derive(Debug, PartialEq))]
+pub enum Number {
+    /// A non-negative integer.
+    PosInt(u64),
+    /// A negative integer.
+    NegInt(i64),
+    /// A floating-point number.
+    Float(f64),
+}
+
+impl Number {
+    /// Returns the number as a `u64` if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::PosInt(n) => Some(n),
+            Self::NegInt(_) | Self::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as an `i64` if it is an integer which fits in an
+    /// `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::PosInt(n) => i64::try_from(n).ok(),
+            Self::NegInt(n) => Some(n),
+            Self::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as an `f64`, converting integers if necessary.
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            Self::PosInt(n) => n as f64,
+            Self::NegInt(n) => n as f64,
+            Self::Float(n) => n,
+        }
+    }
+}
+
+impl ArchivedNumber {
+    /// Returns the number as a `u64` if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::PosInt(n) => Some(n.to_native()),
+            Self::NegInt(_) | Self::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as an `i64` if it is an integer which fits in an
+    /// `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::PosInt(n) => i64::try_from(n.to_native()).ok(),
+            Self::NegInt(n) => Some(n.to_native()),
+            Self::Float(_) => None,
+        }
+    }
+
+    /// Returns the number as an `f64`, converting integers if necessary.
+    pub fn as_f64(&self) -> f64 {
+        match self {
+            Self::PosInt(n) => n.to_native() as f64,
+            Self::NegInt(n) => n.to_native() as f64,
+            Self::Float(n) => n.to_native(),
+        }
+    }
+}
+
+/// A dynamically-typed value.
+#[derive(Archive, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[rkyv(
+    crate,
+    serialize_bounds(__S: Writer + Allocator, __S::Error: Source),
+    deserialize_bounds(__D::Error: Source),
+    bytecheck(bounds(__C: crate::validation::ArchiveContext)),
+    compare(PartialEq),
+    derive(Debug, PartialEq),
+)]
+pub enum Value {
+    /// A null value.
+    Null,
+    /// A boolean.
+    Bool(bool),
+    /// A number.
+    Number(Number),
+    /// A string.
+    String(String),
+    /// An array of values.
+    Array(#[rkyv(omit_bounds)] Vec<Value>),
+    /// An object mapping strings to values.
+    Object(#[rkyv(omit_bounds)] BTreeMap<String, Value>),
+}
+
+impl ArchivedValue {
+    /// Returns whether the value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// Returns the value as a `bool` if it is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a number if it is a number.
+    pub fn as_number(&self) -> Option<&ArchivedNumber> {
+        match self {
+            Self::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `u64` if it is a non-negative integer.
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    /// Returns the value as an `i64` if it is an integer which fits in an
+    /// `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    /// Returns the value as an `f64` if it is a number.
+    pub fn as_f64(&self) -> Option<f64> {
+        Some(self.as_number()?.as_f64())
+    }
+
+    /// Returns the value as a string slice if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an array if it is an array.
+    pub fn as_array(&self) -> Option<&ArchivedVec<ArchivedValue>> {
+        match self {
+            Self::Array(a) => Some(a),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an object if it is an object.
+    pub fn as_object(
+        &self,
+    ) -> Option<&ArchivedBTreeMap<ArchivedString, ArchivedValue>> {
+        match self {
+            Self::Object(o) => Some(o),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the given key if this value is an object which
+    /// contains it.
+    pub fn get<Q>(&self, key: &Q) -> Option<&ArchivedValue>
+    where
+        Q: Ord + ?Sized,
+        ArchivedString: Borrow<Q>,
+    {
+        self.as_object()?.get(key)
+    }
+
+    /// Returns the element at the given index if this value is an array which
+    /// is long enough to contain it.
+    pub fn get_index(&self, index: usize) -> Option<&ArchivedValue> {
+        self.as_array()?.get(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Number, Value};
+    use crate::{
+        alloc::{string::ToString, vec},
+        api::test::{roundtrip, to_archived},
+    };
+
+    fn document() -> Value {
+        Value::Object(
+            [
+                ("null".to_string(), Value::Null),
+                ("bool".to_string(), Value::Bool(true)),
+                ("pos".to_string(), Value::Number(Number::PosInt(42))),
+                ("neg".to_string(), Value::Number(Number::NegInt(-42))),
+                ("float".to_string(), Value::Number(Number::Float(1.5))),
+                (
+                    "string".to_string(),
+                    Value::String(
+                        "a string which is stored out-of-line".into(),
+                    ),
+                ),
+                (
+                    "array".to_string(),
+                    Value::Array(vec![
+                        Value::Null,
+                        Value::Array(vec![Value::Bool(false)]),
+                        Value::Object(
+                            [("nested".to_string(), Value::Null)]
+                                .into_iter()
+                                .collect(),
+                        ),
+                    ]),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn roundtrip_value() {
+        roundtrip(&Value::Null);
+        roundtrip(&Value::Array(vec![]));
+        roundtrip(&document());
+    }
+
+    #[test]
+    fn traverse_archived_value() {
+        to_archived(&document(), |archived| {
+            assert!(archived.get("null").unwrap().is_null());
+            assert_eq!(archived.get("bool").unwrap().as_bool(), Some(true));
+            assert_eq!(archived.get("pos").unwrap().as_u64(), Some(42));
+            assert_eq!(archived.get("pos").unwrap().as_i64(), Some(42));
+            assert_eq!(archived.get("neg").unwrap().as_u64(), None);
+            assert_eq!(archived.get("neg").unwrap().as_i64(), Some(-42));
+            assert_eq!(archived.get("float").unwrap().as_f64(), Some(1.5));
+            assert_eq!(
+                archived.get("string").unwrap().as_str(),
+                Some("a string which is stored out-of-line"),
+            );
+            assert!(archived.get("missing").is_none());
+            assert!(archived.get_index(0).is_none());
+
+            let array = archived.get("array").unwrap();
+            assert_eq!(array.as_array().unwrap().len(), 3);
+            assert!(array.get_index(0).unwrap().is_null());
+            assert_eq!(
+                array.get_index(1).unwrap().get_index(0).unwrap().as_bool(),
+                Some(false),
+            );
+            assert!(array.get_index(2).unwrap().get("nested").is_some());
+            assert!(array.get_index(3).is_none());
+        });
+    }
+}
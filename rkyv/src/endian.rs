@@ -0,0 +1,262 @@
+//! Runtime detection of archive endianness.
+//!
+//! The byte order of archived data is chosen at compile time with the
+//! `little_endian` and `big_endian` features. Accessing an archive which was
+//! produced with a different byte order than the reader was built with
+//! silently reads the wrong values, or fails validation with an unrelated
+//! error.
+//!
+//! Archives which may cross between little-endian and big-endian builds can
+//! instead serialize an [`EndianTaggedRoot`], which stores the value
+//! out-of-line behind a one-byte endianness tag. The tag is located at a fixed
+//! position at the end of the buffer and is readable with either byte order,
+//! so readers can check it with [`archived_endianness`] before they access the
+//! data. This makes it possible to route each archive to a reader which was
+//! built with the matching byte order, or to report the mismatch clearly.
+//!
+//! Archived types always have a fixed byte order, so the tag does not allow a
+//! single build of rkyv to access archives of both byte orders.
+
+use core::{error::Error, fmt, mem::size_of};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    boxed::{ArchivedBox, BoxResolver},
+    traits::ArchivePointee,
+    Archive, ArchiveUnsized, Place, Portable, Serialize, SerializeUnsized,
+};
+
+/// The byte order of archived data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// Little-endian byte order.
+    Little,
+    /// Big-endian byte order.
+    Big,
+}
+
+impl Endianness {
+    /// The byte order that rkyv was built to archive data with.
+    pub const ARCHIVED: Self = if cfg!(feature = "big_endian") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+
+    /// The byte order of the target.
+    pub const NATIVE: Self = if cfg!(target_endian = "big") {
+        Self::Big
+    } else {
+        Self::Little
+    };
+
+    fn to_tag(self) -> u8 {
+        match self {
+            Self::Little => b'L',
+            Self::Big => b'B',
+        }
+    }
+
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            b'L' => Some(Self::Little),
+            b'B' => Some(Self::Big),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Little => write!(f, "little-endian"),
+            Self::Big => write!(f, "big-endian"),
+        }
+    }
+}
+
+/// A root value tagged with the byte order it is archived with.
+///
+/// This is the unarchived counterpart of [`ArchivedEndianTaggedRoot`].
+#[derive(Debug)]
+pub struct EndianTaggedRoot<'a, T: ?Sized> {
+    value: &'a T,
+}
+
+impl<'a, T: ?Sized> EndianTaggedRoot<'a, T> {
+    /// Creates a new `EndianTaggedRoot` which tags the given value with the
+    /// byte order it is archived with.
+    pub fn new(value: &'a T) -> Self {
+        Self { value }
+    }
+}
+
+/// An archived root value tagged with the byte order it was archived with.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedEndianTaggedRoot<T: ArchivePointee + ?Sized> {
+    endianness: u8,
+    value: ArchivedBox<T>,
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedEndianTaggedRoot<T> {
+    /// Returns the byte order that the archived value was tagged with, or
+    /// `None` if the tag is not recognized.
+    pub fn endianness(&self) -> Option<Endianness> {
+        Endianness::from_tag(self.endianness)
+    }
+
+    /// Returns a reference to the archived value.
+    pub fn get(&self) -> &T {
+        self.value.get()
+    }
+}
+
+impl<T> fmt::Debug for ArchivedEndianTaggedRoot<T>
+where
+    T: ArchivePointee + fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedEndianTaggedRoot")
+            .field("endianness", &self.endianness())
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// The resolver for [`EndianTaggedRoot`].
+pub struct EndianTaggedRootResolver {
+    value: BoxResolver,
+}
+
+impl<T: ArchiveUnsized + ?Sized> Archive for EndianTaggedRoot<'_, T> {
+    type Archived = ArchivedEndianTaggedRoot<T::Archived>;
+    type Resolver = EndianTaggedRootResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedEndianTaggedRoot { endianness, value } = out);
+        endianness.write(Endianness::ARCHIVED.to_tag());
+        ArchivedBox::resolve_from_ref(self.value, resolver.value, value);
+    }
+}
+
+impl<T, S> Serialize<S> for EndianTaggedRoot<'_, T>
+where
+    T: SerializeUnsized<S> + ?Sized,
+    S: Fallible + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(EndianTaggedRootResolver {
+            value: ArchivedBox::serialize_from_ref(self.value, serializer)?,
+        })
+    }
+}
+
+/// Returns the byte order tag of a buffer containing an
+/// [`ArchivedEndianTaggedRoot`].
+///
+/// Returns `None` if the buffer is too small to contain an endian-tagged root
+/// or the tag is not recognized. This function does not validate the buffer.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_endian_tagged,
+///     endian::{archived_endianness, Endianness},
+///     rancor::Error,
+/// };
+///
+/// let bytes = to_bytes_endian_tagged::<_, Error>(&42u32).unwrap();
+/// assert_eq!(archived_endianness(&bytes), Some(Endianness::ARCHIVED));
+/// ```
+pub fn archived_endianness(bytes: &[u8]) -> Option<Endianness> {
+    let size = size_of::<ArchivedEndianTaggedRoot<()>>();
+    let pos = bytes.len().checked_sub(size)?;
+    // The tag is the first field of the `#[repr(C)]` root.
+    Endianness::from_tag(bytes[pos])
+}
+
+#[derive(Debug)]
+struct EndianMismatch {
+    found: Option<Endianness>,
+}
+
+impl fmt::Display for EndianMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "archive is {} but rkyv was built to access {} archives",
+                found,
+                Endianness::ARCHIVED,
+            ),
+            None => write!(f, "buffer does not have a recognized endian tag"),
+        }
+    }
+}
+
+impl Error for EndianMismatch {}
+
+/// Checks that the byte order tag of the given buffer matches the byte order
+/// rkyv was built to access.
+pub fn check_endianness<E: Source>(bytes: &[u8]) -> Result<(), E> {
+    match archived_endianness(bytes) {
+        Some(Endianness::ARCHIVED) => Ok(()),
+        found => fail!(EndianMismatch { found }),
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use core::mem::size_of;
+
+    use rancor::Failure;
+
+    use crate::{
+        alloc::{string::ToString, vec::Vec},
+        api::high::{access_endian_tagged, to_bytes_endian_tagged},
+        endian::{archived_endianness, ArchivedEndianTaggedRoot, Endianness},
+        string::ArchivedString,
+        Archived,
+    };
+
+    #[test]
+    fn endian_tagged_roundtrip() {
+        let value = "a string which is stored out of line".to_string();
+        let bytes = to_bytes_endian_tagged::<_, Failure>(&value).unwrap();
+        assert_eq!(archived_endianness(&bytes), Some(Endianness::ARCHIVED));
+
+        let archived =
+            access_endian_tagged::<ArchivedString, Failure>(&bytes).unwrap();
+        assert_eq!(archived, value.as_str());
+    }
+
+    #[test]
+    fn endian_tag_mismatch() {
+        let mut bytes = to_bytes_endian_tagged::<_, Failure>(&42u32).unwrap();
+        let pos = bytes.len() - size_of::<ArchivedEndianTaggedRoot<()>>();
+
+        bytes[pos] = match Endianness::ARCHIVED {
+            Endianness::Little => b'B',
+            Endianness::Big => b'L',
+        };
+        assert_ne!(archived_endianness(&bytes), Some(Endianness::ARCHIVED));
+        let result = access_endian_tagged::<Archived<u32>, Failure>(&bytes);
+        assert!(result.is_err());
+
+        bytes[pos] = 0;
+        assert_eq!(archived_endianness(&bytes), None);
+        let result = access_endian_tagged::<Archived<u32>, Failure>(&bytes);
+        assert!(result.is_err());
+
+        assert_eq!(archived_endianness(&Vec::new()), None);
+    }
+}
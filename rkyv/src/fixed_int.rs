@@ -0,0 +1,333 @@
+//! Archived fixed-width integers wider than 128 bits.
+//!
+//! [`ArchivedFixedUint`] and [`ArchivedFixedInt`] store an integer as `N`
+//! archived `u64` words, starting with the least significant word. Each word
+//! uses the endianness and alignment of the archive, so they are portable like
+//! the other archived primitives. Signed integers are stored in two's
+//! complement.
+//!
+//! These are the archived types for 256-bit integers from crates like
+//! `ethnum` and `primitive-types`. They can also be serialized directly from
+//! their words with [`from_words`](ArchivedFixedUint::from_words).
+
+use core::{cmp::Ordering, fmt, hash};
+
+use crate::{primitive::ArchivedU64, Portable};
+
+/// An archived unsigned 256-bit integer.
+pub type ArchivedU256 = ArchivedFixedUint<4>;
+
+/// An archived signed 256-bit integer.
+pub type ArchivedI256 = ArchivedFixedInt<4>;
+
+/// An archived unsigned 512-bit integer.
+pub type ArchivedU512 = ArchivedFixedUint<8>;
+
+/// An archived unsigned integer made up of `N` 64-bit words.
+#[derive(Clone, Copy, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(transparent)]
+pub struct ArchivedFixedUint<const N: usize> {
+    words: [ArchivedU64; N],
+}
+
+/// An archived signed integer made up of `N` 64-bit words.
+#[derive(Clone, Copy, Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(transparent)]
+pub struct ArchivedFixedInt<const N: usize> {
+    words: [ArchivedU64; N],
+}
+
+macro_rules! impl_common {
+    ($ty:ident) => {
+        impl<const N: usize> $ty<N> {
+            /// Creates an archived integer from its words, starting with the
+            /// least significant word.
+            pub fn from_words(words: [u64; N]) -> Self {
+                Self {
+                    words: words.map(ArchivedU64::from_native),
+                }
+            }
+
+            /// Returns the words of the archived integer, starting with the
+            /// least significant word.
+            pub fn to_words(&self) -> [u64; N] {
+                self.words.map(|word| word.to_native())
+            }
+
+            /// Returns whether the archived integer is zero.
+            pub fn is_zero(&self) -> bool {
+                self.words.iter().all(|word| word.to_native() == 0)
+            }
+        }
+
+        impl<const N: usize> Default for $ty<N> {
+            fn default() -> Self {
+                Self::from_words([0; N])
+            }
+        }
+
+        impl<const N: usize> PartialEq for $ty<N> {
+            fn eq(&self, other: &Self) -> bool {
+                self.words == other.words
+            }
+        }
+
+        impl<const N: usize> Eq for $ty<N> {}
+
+        impl<const N: usize> PartialOrd for $ty<N> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<const N: usize> hash::Hash for $ty<N> {
+            fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                self.to_words().hash(state);
+            }
+        }
+
+        impl<const N: usize> fmt::LowerHex for $ty<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt_hex(&self.to_words(), f, false)
+            }
+        }
+
+        impl<const N: usize> fmt::UpperHex for $ty<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt_hex(&self.to_words(), f, true)
+            }
+        }
+
+        impl<const N: usize> fmt::Debug for $ty<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+    };
+}
+
+impl_common!(ArchivedFixedUint);
+impl_common!(ArchivedFixedInt);
+
+impl<const N: usize> ArchivedFixedInt<N> {
+    /// Returns whether the archived integer is negative.
+    pub fn is_negative(&self) -> bool {
+        N > 0 && (self.words[N - 1].to_native() as i64) < 0
+    }
+}
+
+impl<const N: usize> Ord for ArchivedFixedUint<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_words(&self.to_words(), &other.to_words())
+    }
+}
+
+impl<const N: usize> Ord for ArchivedFixedInt<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_negative(), other.is_negative()) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            // Two's complement values with the same sign compare the same way
+            // as their unsigned bits.
+            _ => cmp_words(&self.to_words(), &other.to_words()),
+        }
+    }
+}
+
+impl<const N: usize> fmt::Display for ArchivedFixedUint<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let words = self.to_words();
+        pad_integral(f, true, "", |out| write_decimal(words, out))
+    }
+}
+
+impl<const N: usize> fmt::Display for ArchivedFixedInt<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = self.to_words();
+        let negative = self.is_negative();
+        if negative {
+            negate(&mut words);
+        }
+        pad_integral(f, !negative, "", |out| write_decimal(words, out))
+    }
+}
+
+fn cmp_words(a: &[u64], b: &[u64]) -> Ordering {
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+fn negate(words: &mut [u64]) {
+    let mut carry = true;
+    for word in words.iter_mut() {
+        let (value, overflow) = (!*word).overflowing_add(carry as u64);
+        *word = value;
+        carry = overflow;
+    }
+}
+
+fn fmt_hex(
+    words: &[u64],
+    f: &mut fmt::Formatter<'_>,
+    upper: bool,
+) -> fmt::Result {
+    pad_integral(f, true, "0x", |out| {
+        let mut words = words.iter().rev().skip_while(|&&word| word == 0);
+        match (words.next(), upper) {
+            (None, _) => out.write_str("0")?,
+            (Some(first), false) => write!(out, "{first:x}")?,
+            (Some(first), true) => write!(out, "{first:X}")?,
+        }
+        for word in words {
+            if upper {
+                write!(out, "{word:016X}")?;
+            } else {
+                write!(out, "{word:016x}")?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Writes the decimal digits of the unsigned integer in `words` to `out`.
+fn write_decimal<const N: usize>(
+    mut words: [u64; N],
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    // The largest power of ten which fits in a `u64`.
+    const CHUNK: u64 = 10_000_000_000_000_000_000;
+
+    // Divide by `CHUNK` and write the quotient before the remainder.
+    let mut remainder = 0u128;
+    for word in words.iter_mut().rev() {
+        let value = (remainder << 64) | *word as u128;
+        *word = (value / CHUNK as u128) as u64;
+        remainder = value % CHUNK as u128;
+    }
+
+    if words.iter().all(|&word| word == 0) {
+        write!(out, "{remainder}")
+    } else {
+        write_decimal(words, out)?;
+        write!(out, "{remainder:019}")
+    }
+}
+
+/// Formats the digits written by `write` with the padding and sign options of
+/// `f`.
+///
+/// The digits are buffered so that they can be padded. Integers which are too
+/// large for the buffer are written without padding.
+fn pad_integral(
+    f: &mut fmt::Formatter<'_>,
+    is_nonnegative: bool,
+    prefix: &str,
+    write: impl Fn(&mut dyn fmt::Write) -> fmt::Result,
+) -> fmt::Result {
+    let mut buf = FmtBuf {
+        bytes: [0; 160],
+        len: 0,
+    };
+    if write(&mut buf).is_ok() {
+        return f.pad_integral(is_nonnegative, prefix, buf.as_str());
+    }
+
+    if !is_nonnegative {
+        f.write_str("-")?;
+    } else if f.sign_plus() {
+        f.write_str("+")?;
+    }
+    if f.alternate() {
+        f.write_str(prefix)?;
+    }
+    write(f)
+}
+
+/// A buffer which is large enough to format a 512-bit integer.
+struct FmtBuf {
+    bytes: [u8; 160],
+    len: usize,
+}
+
+impl FmtBuf {
+    fn as_str(&self) -> &str {
+        // SAFETY: Only `str`s are written to the buffer, and they are never
+        // split.
+        unsafe { core::str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+}
+
+impl fmt::Write for FmtBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArchivedI256, ArchivedU256, ArchivedU512};
+    use crate::alloc::format;
+
+    #[test]
+    fn format_u256() {
+        let value = ArchivedU256::from_words([0, 0, 0, 0]);
+        assert_eq!(format!("{value}"), "0");
+        assert_eq!(format!("{value:#x}"), "0x0");
+
+        let max = ArchivedU256::from_words([u64::MAX; 4]);
+        assert_eq!(
+            format!("{max}"),
+            "115792089237316195423570985008687907853269984665640564039457584\
+             007913129639935",
+        );
+        assert_eq!(format!("{max:x}"), "f".repeat(64));
+
+        let value = ArchivedU256::from_words([1, 2, 0, 0]);
+        assert_eq!(format!("{value:#X}"), "0x20000000000000001");
+        assert_eq!(format!("{value}"), "36893488147419103233");
+    }
+
+    #[test]
+    fn format_i256() {
+        let minus_one = ArchivedI256::from_words([u64::MAX; 4]);
+        assert!(minus_one.is_negative());
+        assert_eq!(format!("{minus_one}"), "-1");
+
+        let min = ArchivedI256::from_words([0, 0, 0, 1 << 63]);
+        assert_eq!(
+            format!("{min}"),
+            "-57896044618658097711785492504343953926634992332820282019728792\
+             003956564819968",
+        );
+
+        let big = ArchivedU512::from_words([u64::MAX; 8]);
+        assert_eq!(format!("{big}").len(), 155);
+        assert_eq!(
+            format!("{:>8}", ArchivedI256::from_words([1, 0, 0, 0])),
+            "       1"
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        let a = ArchivedU256::from_words([u64::MAX, 0, 0, 0]);
+        let b = ArchivedU256::from_words([0, 1, 0, 0]);
+        assert!(a < b);
+
+        let minus_one = ArchivedI256::from_words([u64::MAX; 4]);
+        let one = ArchivedI256::from_words([1, 0, 0, 0]);
+        let min = ArchivedI256::from_words([0, 0, 0, 1 << 63]);
+        assert!(min < minus_one);
+        assert!(minus_one < one);
+        assert!(ArchivedI256::default() < one);
+    }
+}
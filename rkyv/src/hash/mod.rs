@@ -0,0 +1,348 @@
+//! Hashing support for archived hash maps and sets.
+//!
+//! [`PortableHash`] hashes values the same way on every platform and in every
+//! version of rkyv, which makes it suitable for building custom hash-indexed
+//! archived structures.
+
+mod portable;
+
+use core::{
+    hash::{Hash, Hasher},
+    ops::BitXor as _,
+};
+
+pub use self::portable::*;
+use crate::primitive::{FixedIsize, FixedUsize};
+
+/// A cross-platform 64-bit implementation of fxhash.
+#[derive(Default)]
+pub struct FxHasher64 {
+    hash: u64,
+}
+
+#[inline]
+fn hash_word(hash: u64, word: u64) -> u64 {
+    const ROTATE: u32 = 5;
+    const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    hash.rotate_left(ROTATE).bitxor(word).wrapping_mul(SEED)
+}
+
+#[inline]
+fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let ptr = bytes.as_ptr();
+    let len = bytes.len();
+
+    for i in 0..len / 8 {
+        let bytes = unsafe { ptr.cast::<[u8; 8]>().add(i).read_unaligned() };
+        hash = hash_word(hash, u64::from_le_bytes(bytes));
+    }
+
+    if bytes.len() & 4 != 0 {
+        let bytes = unsafe {
+            ptr.add(bytes.len() & !7).cast::<[u8; 4]>().read_unaligned()
+        };
+        hash = hash_word(hash, u32::from_le_bytes(bytes).into());
+    }
+
+    if bytes.len() & 2 != 0 {
+        let bytes = unsafe {
+            ptr.add(bytes.len() & !3).cast::<[u8; 2]>().read_unaligned()
+        };
+        hash = hash_word(hash, u16::from_le_bytes(bytes).into());
+    }
+
+    if bytes.len() & 1 != 0 {
+        let byte = unsafe { ptr.add(len - 1).read() };
+        hash = hash_word(hash, byte.into());
+    }
+
+    hash
+}
+
+impl Hasher for FxHasher64 {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.hash = hash_bytes(self.hash, bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.hash = hash_word(self.hash, i as u64);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.hash = hash_word(self.hash, i as u64);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.hash = hash_word(self.hash, i as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.hash = hash_word(self.hash, i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        let bytes = i.to_ne_bytes();
+        let ptr = bytes.as_ptr().cast::<[u8; 8]>();
+        #[cfg(target_endian = "little")]
+        let (first, second) = (unsafe { ptr.read_unaligned() }, unsafe {
+            ptr.add(1).read_unaligned()
+        });
+        #[cfg(target_endian = "big")]
+        let (first, second) =
+            (unsafe { ptr.add(1).read_unaligned() }, unsafe {
+                ptr.read_unaligned()
+            });
+        self.hash = hash_word(
+            hash_word(self.hash, u64::from_ne_bytes(first)),
+            u64::from_ne_bytes(second),
+        );
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.hash = hash_word(self.hash, i as FixedUsize as u64);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write_i64(i as FixedIsize as i64)
+    }
+}
+
+/// A cross-platform implementation of SipHash-1-3 with a 128-bit key.
+///
+/// Unlike [`FxHasher64`], SipHash is resistant to hash flooding when its key is
+/// kept secret. Hash maps built from untrusted keys should be archived with a
+/// secret key using [`HashWith`](crate::with::HashWith), and then accessed with
+/// the same key.
+///
+/// The halves of the key are const generic parameters so that the hasher can
+/// be created with `Default` like other archived hashers. The default key is
+/// zero, which offers no protection.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{hash::SipHasher13, with::HashWith, Archive};
+///
+/// type SecretHasher =
+///     SipHasher13<0x0123_4567_89ab_cdef, 0xfedc_ba98_7654_3210>;
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = HashWith<SecretHasher>)]
+///     counts: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SipHasher13<const K0: u64 = 0, const K1: u64 = 0> {
+    state: SipState,
+}
+
+impl<const K0: u64, const K1: u64> Default for SipHasher13<K0, K1> {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            state: SipState::new(K0, K1),
+        }
+    }
+}
+
+impl<const K0: u64, const K1: u64> Hasher for SipHasher13<K0, K1> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.state.write::<1>(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.state.finish::<1, 3>()
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as FixedUsize).to_le_bytes());
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.write(&(i as FixedIsize).to_le_bytes());
+    }
+}
+
+#[derive(Clone, Debug)]
+struct SipState {
+    v: [u64; 4],
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+impl SipState {
+    #[inline]
+    fn new(k0: u64, k1: u64) -> Self {
+        Self {
+            v: [
+                k0 ^ 0x736f_6d65_7073_6575,
+                k1 ^ 0x646f_7261_6e64_6f6d,
+                k0 ^ 0x6c79_6765_6e65_7261,
+                k1 ^ 0x7465_6462_7974_6573,
+            ],
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn round(v: &mut [u64; 4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(13) ^ v[0];
+        v[0] = v[0].rotate_left(32);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(16) ^ v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = v[3].rotate_left(21) ^ v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = v[1].rotate_left(17) ^ v[2];
+        v[2] = v[2].rotate_left(32);
+    }
+
+    #[inline]
+    fn compress<const C: usize>(&mut self, word: u64) {
+        self.v[3] ^= word;
+        for _ in 0..C {
+            Self::round(&mut self.v);
+        }
+        self.v[0] ^= word;
+    }
+
+    #[inline]
+    fn write<const C: usize>(&mut self, mut bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+
+        while let Some((&byte, rest)) = bytes.split_first() {
+            if self.ntail == 0 && bytes.len() >= 8 {
+                let (word, rest) = bytes.split_at(8);
+                let word = u64::from_le_bytes(word.try_into().unwrap());
+                self.compress::<C>(word);
+                bytes = rest;
+            } else {
+                self.tail |= (byte as u64) << (8 * self.ntail);
+                self.ntail += 1;
+                if self.ntail == 8 {
+                    let word = self.tail;
+                    self.compress::<C>(word);
+                    self.tail = 0;
+                    self.ntail = 0;
+                }
+                bytes = rest;
+            }
+        }
+    }
+
+    #[inline]
+    fn finish<const C: usize, const D: usize>(&self) -> u64 {
+        let mut state = self.clone();
+        let last = ((self.length as u64 & 0xff) << 56) | self.tail;
+        state.compress::<C>(last);
+        state.v[2] ^= 0xff;
+        for _ in 0..D {
+            Self::round(&mut state.v);
+        }
+        state.v[0] ^ state.v[1] ^ state.v[2] ^ state.v[3]
+    }
+}
+
+/// Hashes the given value with the default value of the specified `Hasher`.
+pub fn hash_value<Q, H: Hasher + Default>(value: &Q) -> u64
+where
+    Q: Hash + ?Sized,
+{
+    let mut state = H::default();
+    value.hash(&mut state);
+    state.finish()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::hash::Hasher;
+
+    use super::{SipHasher13, SipState};
+
+    #[test]
+    #[allow(deprecated)]
+    fn sip_state_matches_std() {
+        // `std` only exposes SipHash-2-4, which shares everything but the
+        // number of rounds with SipHash-1-3.
+        const K0: u64 = 0x0706_0504_0302_0100;
+        const K1: u64 = 0x0f0e_0d0c_0b0a_0908;
+
+        let input = (0..64).collect::<std::vec::Vec<u8>>();
+        for len in 0..input.len() {
+            for split in [0, len / 3, len] {
+                let (a, b) = input[..len].split_at(split);
+
+                let mut expected = std::hash::SipHasher::new_with_keys(K0, K1);
+                expected.write(a);
+                expected.write(b);
+
+                let mut state = SipState::new(K0, K1);
+                state.write::<2>(a);
+                state.write::<2>(b);
+
+                assert_eq!(state.finish::<2, 4>(), expected.finish());
+            }
+        }
+    }
+
+    #[test]
+    fn sip_hasher_13_keys() {
+        fn hash<H: Hasher + Default>(value: &str) -> u64 {
+            super::hash_value::<str, H>(value)
+        }
+
+        assert_eq!(
+            hash::<SipHasher13>("hello"),
+            hash::<SipHasher13<0, 0>>("hello"),
+        );
+        assert_ne!(
+            hash::<SipHasher13>("hello"),
+            hash::<SipHasher13<1, 2>>("hello"),
+        );
+    }
+}
@@ -0,0 +1,632 @@
+use core::{
+    hash::Hasher,
+    marker::{PhantomData, PhantomPinned},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
+        NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8, NonZeroUsize,
+    },
+};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{btree_map::ArchivedBTreeMap, btree_set::ArchivedBTreeSet},
+    niche::{option_box::ArchivedOptionBox, option_nonzero::*},
+    option::ArchivedOption,
+    rc::ArchivedRc,
+    rel_ptr::Offset,
+    rend::{unaligned::*, *},
+    result::ArchivedResult,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    tuple::*,
+    vec::ArchivedVec,
+};
+
+/// A hash which is the same on every platform and in every version of rkyv.
+///
+/// The [`Hash`](core::hash::Hash) implementations in the standard library are
+/// not guaranteed to be stable: they can change between versions of Rust, and
+/// the hashes of `usize` and `isize` depend on the width of the target's
+/// pointers. `PortableHash` instead feeds values to the hasher in a fixed,
+/// documented way. As long as the hasher is also portable (like
+/// [`FxHasher64`](crate::hash::FxHasher64) or
+/// [`SipHasher13`](crate::hash::SipHasher13)), portable hashes can be stored
+/// in archives and recomputed on any other platform.
+///
+/// Archived types hash the same as the types they are archived from, so
+/// archived values can be looked up with native keys and vice versa.
+///
+/// # Stability
+///
+/// The way that each type is hashed is part of rkyv's format and will not
+/// change without a major version bump:
+///
+/// - Integers up to 64 bits wide, `bool`, and `char` are widened to 64 bits
+///   (sign-extending signed integers) and written with `write_u64`. 128-bit
+///   integers are written with `write_u128`. Non-zero integers hash the same as
+///   the integers they wrap. This means that `usize` and `isize` hash the same
+///   regardless of the `pointer_width_*` features.
+/// - Strings write their length in bytes as a `u64` with `write_u64`, followed
+///   by their bytes in a single call to `write`.
+/// - Slices and vectors write their length as a `u64` with `write_u64`,
+///   followed by the portable hash of each element in order. Arrays hash each
+///   element in order without a length.
+/// - Tuples hash each element in order, and `()` and `PhantomData` don't write
+///   anything.
+/// - `Option`s write `0` for `None` and `1` followed by the value for `Some`,
+///   each with `write_u64`. `Result`s write `0` followed by the value for `Ok`
+///   and `1` followed by the error for `Err`.
+/// - References, boxes, and reference-counted pointers hash the same as the
+///   value they point to.
+/// - B-tree maps and sets write their length as a `u64` with `write_u64`,
+///   followed by each of their entries in order. Maps hash each key followed by
+///   its value.
+///
+/// Floating-point numbers and unordered collections like hash maps do not
+/// implement `PortableHash`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access,
+///     hash::{portable_hash_value, FxHasher64, PortableHash},
+///     rancor::Error,
+///     to_bytes, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Key {
+///     name: String,
+///     index: u64,
+/// }
+///
+/// impl PortableHash for Key {
+///     fn portable_hash<H: core::hash::Hasher>(&self, state: &mut H) {
+///         self.name.portable_hash(state);
+///         self.index.portable_hash(state);
+///     }
+/// }
+///
+/// impl PortableHash for ArchivedKey {
+///     fn portable_hash<H: core::hash::Hasher>(&self, state: &mut H) {
+///         self.name.portable_hash(state);
+///         self.index.portable_hash(state);
+///     }
+/// }
+///
+/// let key = Key {
+///     name: "example".to_string(),
+///     index: 42,
+/// };
+/// let bytes = to_bytes::<Error>(&key).unwrap();
+/// let archived = access::<ArchivedKey, Error>(&bytes).unwrap();
+///
+/// assert_eq!(
+///     portable_hash_value::<_, FxHasher64>(&key),
+///     portable_hash_value::<_, FxHasher64>(archived),
+/// );
+/// ```
+pub trait PortableHash {
+    /// Feeds this value into the given hasher.
+    fn portable_hash<H: Hasher>(&self, state: &mut H);
+}
+
+/// Computes the portable hash of the given value with the default value of
+/// the specified `Hasher`.
+pub fn portable_hash_value<Q, H>(value: &Q) -> u64
+where
+    Q: PortableHash + ?Sized,
+    H: Hasher + Default,
+{
+    let mut state = H::default();
+    value.portable_hash(&mut state);
+    state.finish()
+}
+
+#[inline]
+fn write_len<H: Hasher>(len: usize, state: &mut H) {
+    state.write_u64(len as u64);
+}
+
+macro_rules! impl_unsigned {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                    state.write_u64(*self as u64);
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned!(u8, u16, u32, u64, usize, bool);
+
+macro_rules! impl_signed {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                    state.write_u64(*self as i64 as u64);
+                }
+            }
+        )*
+    };
+}
+
+impl_signed!(i8, i16, i32, i64, isize);
+
+impl PortableHash for char {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(*self as u64);
+    }
+}
+
+impl PortableHash for u128 {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(*self);
+    }
+}
+
+impl PortableHash for i128 {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(*self as u128);
+    }
+}
+
+macro_rules! impl_nonzero {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                    self.get().portable_hash(state);
+                }
+            }
+        )*
+    };
+}
+
+impl_nonzero!(
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+);
+
+macro_rules! impl_rend {
+    ($($le:ty, $be:ty, $ule:ty, $ube:ty);* $(;)?) => {
+        $(
+            impl_rend!(@impl $le, $be, $ule, $ube);
+        )*
+    };
+    (@impl $($ty:ty),*) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                    self.to_native().portable_hash(state);
+                }
+            }
+        )*
+    };
+}
+
+impl_rend! {
+    i16_le, i16_be, i16_ule, i16_ube;
+    i32_le, i32_be, i32_ule, i32_ube;
+    i64_le, i64_be, i64_ule, i64_ube;
+    i128_le, i128_be, i128_ule, i128_ube;
+    u16_le, u16_be, u16_ule, u16_ube;
+    u32_le, u32_be, u32_ule, u32_ube;
+    u64_le, u64_be, u64_ule, u64_ube;
+    u128_le, u128_be, u128_ule, u128_ube;
+    char_le, char_be, char_ule, char_ube;
+    NonZeroI16_le, NonZeroI16_be, NonZeroI16_ule, NonZeroI16_ube;
+    NonZeroI32_le, NonZeroI32_be, NonZeroI32_ule, NonZeroI32_ube;
+    NonZeroI64_le, NonZeroI64_be, NonZeroI64_ule, NonZeroI64_ube;
+    NonZeroI128_le, NonZeroI128_be, NonZeroI128_ule, NonZeroI128_ube;
+    NonZeroU16_le, NonZeroU16_be, NonZeroU16_ule, NonZeroU16_ube;
+    NonZeroU32_le, NonZeroU32_be, NonZeroU32_ule, NonZeroU32_ube;
+    NonZeroU64_le, NonZeroU64_be, NonZeroU64_ule, NonZeroU64_ube;
+    NonZeroU128_le, NonZeroU128_be, NonZeroU128_ule, NonZeroU128_ube;
+}
+
+impl PortableHash for str {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        write_len(self.len(), state);
+        state.write(self.as_bytes());
+    }
+}
+
+impl<T: PortableHash> PortableHash for [T] {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        write_len(self.len(), state);
+        for value in self {
+            value.portable_hash(state);
+        }
+    }
+}
+
+impl<T: PortableHash, const N: usize> PortableHash for [T; N] {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        for value in self {
+            value.portable_hash(state);
+        }
+    }
+}
+
+impl<T: PortableHash + ?Sized> PortableHash for &T {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        (**self).portable_hash(state);
+    }
+}
+
+impl<T: PortableHash + ?Sized> PortableHash for &mut T {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        (**self).portable_hash(state);
+    }
+}
+
+impl<T: ?Sized> PortableHash for PhantomData<T> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl PortableHash for PhantomPinned {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<T: PortableHash> PortableHash for Option<T> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            None => state.write_u64(0),
+            Some(value) => {
+                state.write_u64(1);
+                value.portable_hash(state);
+            }
+        }
+    }
+}
+
+impl<T: PortableHash, E: PortableHash> PortableHash for Result<T, E> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Ok(value) => {
+                state.write_u64(0);
+                value.portable_hash(state);
+            }
+            Err(error) => {
+                state.write_u64(1);
+                error.portable_hash(state);
+            }
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($archived:ident, $($t:ident $index:tt),*) => {
+        impl<$($t: PortableHash),*> PortableHash for ($($t,)*) {
+            #[inline]
+            fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$index.portable_hash(state);)*
+            }
+        }
+
+        impl<$($t: PortableHash),*> PortableHash for $archived<$($t),*> {
+            #[inline]
+            fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                $(self.$index.portable_hash(state);)*
+            }
+        }
+    };
+}
+
+impl PortableHash for () {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl_tuple!(ArchivedTuple1, T0 0);
+impl_tuple!(ArchivedTuple2, T0 0, T1 1);
+impl_tuple!(ArchivedTuple3, T0 0, T1 1, T2 2);
+impl_tuple!(ArchivedTuple4, T0 0, T1 1, T2 2, T3 3);
+impl_tuple!(ArchivedTuple5, T0 0, T1 1, T2 2, T3 3, T4 4);
+impl_tuple!(ArchivedTuple6, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+impl_tuple!(ArchivedTuple7, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+impl_tuple!(ArchivedTuple8, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+impl_tuple!(
+    ArchivedTuple9, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8
+);
+impl_tuple!(
+    ArchivedTuple10, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9
+);
+impl_tuple!(
+    ArchivedTuple11, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10
+);
+impl_tuple!(
+    ArchivedTuple12, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11
+);
+impl_tuple!(
+    ArchivedTuple13, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8,
+    T9 9, T10 10, T11 11, T12 12
+);
+
+// Archived types
+
+impl<const N: usize> PortableHash for ArchivedString<N> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().portable_hash(state);
+    }
+}
+
+impl<T: PortableHash, O: Offset, L: Offset> PortableHash
+    for ArchivedVec<T, O, L>
+{
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().portable_hash(state);
+    }
+}
+
+impl<T: PortableHash> PortableHash for ArchivedOption<T> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().portable_hash(state);
+    }
+}
+
+impl<T: PortableHash, E: PortableHash> PortableHash for ArchivedResult<T, E> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().portable_hash(state);
+    }
+}
+
+impl<T> PortableHash for ArchivedBox<T>
+where
+    T: ArchivePointee + PortableHash + ?Sized,
+{
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.get().portable_hash(state);
+    }
+}
+
+impl<T, F> PortableHash for ArchivedRc<T, F>
+where
+    T: ArchivePointee + PortableHash + ?Sized,
+{
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.get().portable_hash(state);
+    }
+}
+
+impl<T> PortableHash for ArchivedOptionBox<T>
+where
+    T: ArchivePointee + PortableHash + ?Sized,
+{
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        self.as_ref().portable_hash(state);
+    }
+}
+
+macro_rules! impl_option_nonzero {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl PortableHash for $ty {
+                #[inline]
+                fn portable_hash<H: Hasher>(&self, state: &mut H) {
+                    self.as_ref().portable_hash(state);
+                }
+            }
+        )*
+    };
+}
+
+impl_option_nonzero!(
+    ArchivedOptionNonZeroI8,
+    ArchivedOptionNonZeroI16,
+    ArchivedOptionNonZeroI32,
+    ArchivedOptionNonZeroI64,
+    ArchivedOptionNonZeroI128,
+    ArchivedOptionNonZeroU8,
+    ArchivedOptionNonZeroU16,
+    ArchivedOptionNonZeroU32,
+    ArchivedOptionNonZeroU64,
+    ArchivedOptionNonZeroU128,
+);
+
+impl<K, V, const E: usize> PortableHash for ArchivedBTreeMap<K, V, E>
+where
+    K: PortableHash,
+    V: PortableHash,
+{
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        write_len(self.len(), state);
+        for (key, value) in self.iter() {
+            key.portable_hash(state);
+            value.portable_hash(state);
+        }
+    }
+}
+
+impl<K: PortableHash, const E: usize> PortableHash for ArchivedBTreeSet<K, E> {
+    #[inline]
+    fn portable_hash<H: Hasher>(&self, state: &mut H) {
+        write_len(self.len(), state);
+        for key in self.iter() {
+            key.portable_hash(state);
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use core::hash::Hasher;
+
+    use super::{write_len, PortableHash};
+    use crate::alloc::{
+        boxed::Box,
+        collections::{BTreeMap, BTreeSet},
+        rc::Rc,
+        string::String,
+        vec::Vec,
+    };
+
+    impl PortableHash for String {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            self.as_str().portable_hash(state);
+        }
+    }
+
+    impl<T: PortableHash> PortableHash for Vec<T> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            self.as_slice().portable_hash(state);
+        }
+    }
+
+    impl<T: PortableHash + ?Sized> PortableHash for Box<T> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            (**self).portable_hash(state);
+        }
+    }
+
+    impl<T: PortableHash + ?Sized> PortableHash for Rc<T> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            (**self).portable_hash(state);
+        }
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    impl<T: PortableHash + ?Sized> PortableHash for crate::alloc::sync::Arc<T> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            (**self).portable_hash(state);
+        }
+    }
+
+    impl<K: PortableHash, V: PortableHash> PortableHash for BTreeMap<K, V> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            write_len(self.len(), state);
+            for (key, value) in self.iter() {
+                key.portable_hash(state);
+                value.portable_hash(state);
+            }
+        }
+    }
+
+    impl<K: PortableHash> PortableHash for BTreeSet<K> {
+        #[inline]
+        fn portable_hash<H: Hasher>(&self, state: &mut H) {
+            write_len(self.len(), state);
+            for key in self.iter() {
+                key.portable_hash(state);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{
+        alloc::{
+            boxed::Box,
+            collections::BTreeMap,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::to_archived,
+        hash::{portable_hash_value, FxHasher64, PortableHash},
+    };
+
+    fn hash<T: PortableHash + ?Sized>(value: &T) -> u64 {
+        portable_hash_value::<T, FxHasher64>(value)
+    }
+
+    #[test]
+    fn portable_hashes_are_stable() {
+        // These values are part of the format and must never change.
+        assert_eq!(hash(&0u8), 0);
+        assert_eq!(hash(&1u32), 0x517c_c1b7_2722_0a95);
+        assert_eq!(hash(&1usize), hash(&1u64));
+        assert_eq!(hash(&-1i8), hash(&u64::MAX));
+        assert_eq!(hash(&-1isize), hash(&-1i64));
+        assert_eq!(hash(&true), hash(&1u8));
+        assert_eq!(hash("hello"), hash(&"hello".to_string()));
+        assert_ne!(hash("hello"), hash(&b"hello"[..]));
+        assert_eq!(hash(&None::<u32>), hash(&0u8));
+        assert_eq!(hash(&Some(2u8)), hash(&(1u8, 2u8)));
+    }
+
+    #[test]
+    fn archived_hashes_match_native() {
+        type Value = (
+            u8,
+            i32,
+            u64,
+            usize,
+            String,
+            Vec<u16>,
+            Option<Box<str>>,
+            Result<i64, char>,
+            BTreeMap<String, Vec<u128>>,
+        );
+
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), vec![1, 2, 3]);
+        map.insert("b".to_string(), Vec::new());
+
+        let value: Value = (
+            1,
+            -2,
+            3,
+            4,
+            "five".to_string(),
+            vec![6, 7],
+            Some("eight".into()),
+            Err('9'),
+            map,
+        );
+
+        to_archived(&value, |archived| {
+            assert_eq!(hash(&*archived), hash(&value));
+        });
+    }
+}
@@ -0,0 +1,389 @@
+//! Self-describing archive headers.
+//!
+//! rkyv archives don't record how they were produced. Accessing an archive
+//! which was written with different format control features (see the
+//! [crate-level documentation](crate#format-control)) or as a different root
+//! type usually fails validation with an error that has nothing to do with the
+//! real problem, and accessing it without validation reads garbage.
+//!
+//! Archives can instead be prefixed with an [`ArchiveHeader`], which records
+//! the format control options and the [`TypeHash`] of the root type that the
+//! archive was written with. Readers check the header with [`check_header`]
+//! before they access the archive, so a mismatch is reported as such. The
+//! high-level [`to_bytes_with_header`] and [`access_with_header`] functions
+//! write and check headers automatically.
+//!
+//! The header is [`HEADER_SIZE`] bytes long and has the same layout regardless
+//! of the format control features that rkyv was built with:
+//!
+//! | Bytes   | Contents                                        |
+//! | ------- | ----------------------------------------------- |
+//! | `0..4`  | The magic bytes [`HEADER_MAGIC`]                |
+//! | `4`     | The version of the header layout                |
+//! | `5`     | The [`FormatFlags`] of the archive              |
+//! | `6..8`  | Reserved, always zero                           |
+//! | `8..16` | The type hash of the root type, little-endian   |
+//!
+//! The archive follows immediately after the header. Because the header is a
+//! multiple of 16 bytes long, the archive keeps the alignment of the buffer.
+//!
+//! [`to_bytes_with_header`]: crate::api::high::to_bytes_with_header
+//! [`access_with_header`]: crate::api::high::access_with_header
+
+use core::{any::type_name, error::Error, fmt, mem::size_of};
+
+use rancor::{fail, Source};
+
+use crate::{endian::Endianness, primitive::FixedUsize, type_hash::TypeHash};
+
+/// The magic bytes at the start of every archive header.
+pub const HEADER_MAGIC: [u8; 4] = *b"rkyv";
+
+/// The size of an [`ArchiveHeader`], in bytes.
+pub const HEADER_SIZE: usize = 16;
+
+const HEADER_VERSION: u8 = 1;
+
+const BIG_ENDIAN_FLAG: u8 = 1 << 0;
+const UNALIGNED_FLAG: u8 = 1 << 1;
+const POINTER_WIDTH_SHIFT: u32 = 2;
+const POINTER_WIDTH_MASK: u8 = 0b11 << POINTER_WIDTH_SHIFT;
+const KNOWN_FLAGS: u8 = BIG_ENDIAN_FLAG | UNALIGNED_FLAG | POINTER_WIDTH_MASK;
+
+/// The format control options that an archive was written with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FormatFlags {
+    /// The byte order of the archive.
+    pub endianness: Endianness,
+    /// Whether the archive uses unaligned primitives.
+    pub unaligned: bool,
+    /// The number of bits that `isize` and `usize` are archived with.
+    pub pointer_width: u32,
+}
+
+impl FormatFlags {
+    /// The format control options that rkyv was built with.
+    pub const CURRENT: Self = Self {
+        endianness: Endianness::ARCHIVED,
+        unaligned: cfg!(feature = "unaligned"),
+        pointer_width: 8 * size_of::<FixedUsize>() as u32,
+    };
+
+    fn to_byte(self) -> u8 {
+        let mut byte = match self.pointer_width {
+            16 => 0,
+            32 => 1,
+            _ => 2,
+        } << POINTER_WIDTH_SHIFT;
+        if self.endianness == Endianness::Big {
+            byte |= BIG_ENDIAN_FLAG;
+        }
+        if self.unaligned {
+            byte |= UNALIGNED_FLAG;
+        }
+        byte
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        if byte & !KNOWN_FLAGS != 0 {
+            return None;
+        }
+
+        let pointer_width =
+            match (byte & POINTER_WIDTH_MASK) >> POINTER_WIDTH_SHIFT {
+                0 => 16,
+                1 => 32,
+                2 => 64,
+                _ => return None,
+            };
+        Some(Self {
+            endianness: if byte & BIG_ENDIAN_FLAG != 0 {
+                Endianness::Big
+            } else {
+                Endianness::Little
+            },
+            unaligned: byte & UNALIGNED_FLAG != 0,
+            pointer_width,
+        })
+    }
+}
+
+impl fmt::Display for FormatFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}, {}-bit pointers",
+            self.endianness,
+            if self.unaligned {
+                "unaligned"
+            } else {
+                "aligned"
+            },
+            self.pointer_width,
+        )
+    }
+}
+
+/// A header which describes how an archive was written.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ArchiveHeader {
+    /// The format control options that the archive was written with.
+    pub flags: FormatFlags,
+    /// The [`TypeHash`] of the archived root type.
+    pub type_hash: u64,
+}
+
+impl ArchiveHeader {
+    /// Returns the header for an archive with `T` as its archived root type,
+    /// written with the format control options that rkyv was built with.
+    pub fn for_root<T: TypeHash + ?Sized>() -> Self {
+        Self {
+            flags: FormatFlags::CURRENT,
+            type_hash: T::TYPE_HASH,
+        }
+    }
+
+    /// Returns the bytes of the header.
+    pub fn to_bytes(&self) -> [u8; HEADER_SIZE] {
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..4].copy_from_slice(&HEADER_MAGIC);
+        bytes[4] = HEADER_VERSION;
+        bytes[5] = self.flags.to_byte();
+        bytes[8..16].copy_from_slice(&self.type_hash.to_le_bytes());
+        bytes
+    }
+
+    /// Reads the header at the start of the given buffer.
+    ///
+    /// This only checks that the buffer starts with a well-formed header. Use
+    /// [`check_header`] to also check that the archive is compatible.
+    pub fn read<E: Source>(bytes: &[u8]) -> Result<Self, E> {
+        if bytes.len() < HEADER_SIZE {
+            fail!(HeaderError::TooShort { len: bytes.len() });
+        }
+        if bytes[0..4] != HEADER_MAGIC {
+            let mut found = [0; 4];
+            found.copy_from_slice(&bytes[0..4]);
+            fail!(HeaderError::MagicMismatch { found });
+        }
+        if bytes[4] != HEADER_VERSION {
+            fail!(HeaderError::UnsupportedVersion { version: bytes[4] });
+        }
+        let Some(flags) = FormatFlags::from_byte(bytes[5]) else {
+            fail!(HeaderError::UnknownFlags { flags: bytes[5] });
+        };
+
+        let mut type_hash = [0; 8];
+        type_hash.copy_from_slice(&bytes[8..16]);
+        Ok(Self {
+            flags,
+            type_hash: u64::from_le_bytes(type_hash),
+        })
+    }
+}
+
+#[derive(Debug)]
+enum HeaderError {
+    TooShort {
+        len: usize,
+    },
+    MagicMismatch {
+        found: [u8; 4],
+    },
+    UnsupportedVersion {
+        version: u8,
+    },
+    UnknownFlags {
+        flags: u8,
+    },
+    FormatMismatch {
+        found: FormatFlags,
+    },
+    TypeMismatch {
+        found: u64,
+        expected: u64,
+        type_name: &'static str,
+    },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { len } => write!(
+                f,
+                "buffer of length {} is too short to contain a {}-byte \
+                 archive header",
+                len, HEADER_SIZE,
+            ),
+            Self::MagicMismatch { found } => write!(
+                f,
+                "buffer does not start with an archive header: expected \
+                 magic bytes {:02x?} but found {:02x?}",
+                HEADER_MAGIC, found,
+            ),
+            Self::UnsupportedVersion { version } => write!(
+                f,
+                "archive header has version {} but only version {} is \
+                 supported",
+                version, HEADER_VERSION,
+            ),
+            Self::UnknownFlags { flags } => write!(
+                f,
+                "archive header has unrecognized format flags {:#010b}",
+                flags,
+            ),
+            Self::FormatMismatch { found } => write!(
+                f,
+                "archive was written as {} but rkyv was built to access {} \
+                 archives",
+                found,
+                FormatFlags::CURRENT,
+            ),
+            Self::TypeMismatch {
+                found,
+                expected,
+                type_name,
+            } => write!(
+                f,
+                "archive root type hash {:#018x} does not match the type hash \
+                 of `{}` ({:#018x})",
+                found, type_name, expected,
+            ),
+        }
+    }
+}
+
+impl Error for HeaderError {}
+
+/// Checks the header of an archive with `T` as its archived root type and
+/// returns the archive after the header.
+///
+/// This fails if the buffer does not start with a well-formed header, if the
+/// archive was written with different format control options than rkyv was
+/// built with, or if the type hash of the archive's root type does not match
+/// `T`. The archive itself is not validated.
+///
+/// The returned slice starts [`HEADER_SIZE`] bytes after `bytes`, so it keeps
+/// the alignment of the original buffer.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_with_header, header::check_header,
+///     rancor::Error, Archived,
+/// };
+///
+/// let bytes = to_bytes_with_header::<_, Error>(&42u32).unwrap();
+/// assert!(check_header::<Archived<u32>, Error>(&bytes).is_ok());
+/// assert!(check_header::<Archived<u64>, Error>(&bytes).is_err());
+/// ```
+pub fn check_header<T, E>(bytes: &[u8]) -> Result<&[u8], E>
+where
+    T: TypeHash + ?Sized,
+    E: Source,
+{
+    let header = ArchiveHeader::read::<E>(bytes)?;
+    if header.flags != FormatFlags::CURRENT {
+        fail!(HeaderError::FormatMismatch {
+            found: header.flags,
+        });
+    }
+    let expected = T::TYPE_HASH;
+    if header.type_hash != expected {
+        fail!(HeaderError::TypeMismatch {
+            found: header.type_hash,
+            expected,
+            type_name: type_name::<T>(),
+        });
+    }
+
+    Ok(&bytes[HEADER_SIZE..])
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use rancor::Error;
+
+    use crate::{
+        alloc::string::{String, ToString},
+        api::high::{access_with_header, to_bytes_with_header},
+        endian::Endianness,
+        header::{ArchiveHeader, FormatFlags, HEADER_MAGIC, HEADER_SIZE},
+        string::ArchivedString,
+        Archived,
+    };
+
+    #[test]
+    fn header_roundtrip() {
+        let value = "a string which is stored out of line".to_string();
+        let bytes = to_bytes_with_header::<_, Error>(&value).unwrap();
+        assert_eq!(bytes[0..4], HEADER_MAGIC);
+
+        let header = ArchiveHeader::read::<Error>(&bytes).unwrap();
+        assert_eq!(header, ArchiveHeader::for_root::<ArchivedString>());
+
+        let archived =
+            access_with_header::<ArchivedString, Error>(&bytes).unwrap();
+        assert_eq!(archived, value.as_str());
+    }
+
+    #[test]
+    fn format_flags_roundtrip() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            for unaligned in [false, true] {
+                for pointer_width in [16, 32, 64] {
+                    let flags = FormatFlags {
+                        endianness,
+                        unaligned,
+                        pointer_width,
+                    };
+                    assert_eq!(
+                        FormatFlags::from_byte(flags.to_byte()),
+                        Some(flags),
+                    );
+                }
+            }
+        }
+        assert_eq!(FormatFlags::from_byte(0b1100), None);
+        assert_eq!(FormatFlags::from_byte(0b1_0000), None);
+    }
+
+    fn access_error(bytes: &[u8]) -> String {
+        access_with_header::<Archived<u32>, Error>(bytes)
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn header_mismatch() {
+        let mut bytes = to_bytes_with_header::<_, Error>(&42u32).unwrap();
+
+        assert!(access_error(&bytes[..HEADER_SIZE - 1]).contains("too short"));
+
+        let mismatched = |f: &dyn Fn(&mut [u8])| {
+            let mut bytes = bytes.clone();
+            f(&mut bytes);
+            access_error(&bytes)
+        };
+
+        assert!(mismatched(&|b| b[0] = b'R').contains("magic bytes"));
+        assert!(mismatched(&|b| b[4] = 2).contains("version 2"));
+        assert!(mismatched(&|b| b[5] = 0xff).contains("unrecognized"));
+        assert!(mismatched(&|b| b[5] ^= 1).contains("rkyv was built to"));
+        assert!(mismatched(&|b| b[8] ^= 1).contains("root type hash"));
+
+        let error = access_with_header::<Archived<u64>, Error>(&bytes)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("root type hash"));
+
+        bytes[HEADER_SIZE..].fill(0);
+        assert_eq!(
+            *access_with_header::<Archived<u32>, Error>(&bytes).unwrap(),
+            0,
+        );
+    }
+}
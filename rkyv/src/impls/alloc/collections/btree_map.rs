@@ -96,6 +96,16 @@ where
     }
 }
 
+impl<K, V, AK, AV> PartialEq<ArchivedBTreeMap<AK, AV>> for BTreeMap<K, V>
+where
+    AK: PartialEq<K>,
+    AV: PartialEq<V>,
+{
+    fn eq(&self, other: &ArchivedBTreeMap<AK, AV>) -> bool {
+        other.eq(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::ops::ControlFlow;
@@ -125,6 +135,23 @@ mod tests {
         roundtrip(&value);
     }
 
+    #[test]
+    fn compare_btree_map() {
+        let mut value = BTreeMap::new();
+        value.insert("foo".to_string(), vec![1, 2]);
+        value.insert("bar".to_string(), vec![3]);
+
+        to_archived(&value, |archived| {
+            assert!(*archived == value);
+            assert!(value == *archived);
+            assert!(value["foo"] == *archived.get("foo").unwrap());
+
+            let mut other = value.clone();
+            other.insert("baz".to_string(), Vec::new());
+            assert!(other != *archived);
+        });
+    }
+
     #[test]
     fn roundtrip_empty_btree_map() {
         roundtrip(&BTreeMap::<String, i32>::new());
@@ -272,4 +299,246 @@ mod tests {
             assert_eq!(i.next(), None);
         });
     }
+
+    #[test]
+    fn btree_map_iter_increasing_sizes() {
+        const SIZES: &[usize] = &[0, 1, 5, 6, 17, 35, 36, 112, 215, 1000];
+        for &size in SIZES {
+            let value: BTreeMap<i32, i32> =
+                (0..size as i32).map(|i| (i, i * 2)).collect();
+
+            to_archived(&value, |archived| {
+                assert_eq!(archived.iter().len(), size);
+                assert!(archived
+                    .iter()
+                    .map(|(k, v)| (k.to_native(), v.to_native()))
+                    .eq(value.iter().map(|(k, v)| (*k, *v))));
+                assert!(archived
+                    .keys()
+                    .map(|k| k.to_native())
+                    .eq(value.keys().copied()));
+                assert!(archived
+                    .values()
+                    .map(|v| v.to_native())
+                    .eq(value.values().copied()));
+            });
+        }
+    }
+
+    #[test]
+    fn btree_map_range() {
+        use core::ops::Bound::{Excluded, Included, Unbounded};
+
+        const SIZES: &[usize] = &[0, 1, 5, 6, 17, 36, 215, 1000];
+        for &size in SIZES {
+            // Only even keys are present so that bounds fall between entries
+            let value: BTreeMap<i32, i32> =
+                (0..size as i32).map(|i| (i * 2, i)).collect();
+            let max = size as i32 * 2 + 1;
+
+            to_archived(&value, |archived| {
+                for start in [-1, 0, 1, 7, 8, max / 2, max] {
+                    for end in [start, start + 1, start + 9, max / 2, max] {
+                        if end < start {
+                            continue;
+                        }
+                        let bounds = [
+                            (Included(start), Included(end)),
+                            (Included(start), Excluded(end)),
+                            (Excluded(start), Included(end)),
+                            (Unbounded, Excluded(end)),
+                            (Included(start), Unbounded),
+                            (Unbounded, Unbounded),
+                        ];
+                        for (lower, upper) in bounds {
+                            if lower == Excluded(start) && start == end {
+                                continue;
+                            }
+                            let archived_range = (
+                                lower.map(ArchivedI32::from_native),
+                                upper.map(ArchivedI32::from_native),
+                            );
+                            assert!(archived
+                                .range::<ArchivedI32, _>(archived_range)
+                                .map(|(k, v)| (k.to_native(), v.to_native()))
+                                .eq(value
+                                    .range((lower, upper))
+                                    .map(|(k, v)| (*k, *v))));
+                        }
+                    }
+                }
+            });
+        }
+    }
+
+    #[test]
+    fn btree_map_cursor() {
+        use core::ops::Bound::{self, Excluded, Included, Unbounded};
+
+        const SIZES: &[usize] = &[0, 1, 5, 6, 17, 36, 215, 1000];
+        for &size in SIZES {
+            // Only even keys are present so that bounds fall between entries
+            let value: BTreeMap<i32, i32> =
+                (0..size as i32).map(|i| (i * 2, i)).collect();
+            let max = size as i32 * 2 + 1;
+
+            to_archived(&value, |archived| {
+                let native = |entry: Option<(&ArchivedI32, &ArchivedI32)>| {
+                    entry.map(|(k, v)| (k.to_native(), v.to_native()))
+                };
+                let check = |bound: Bound<i32>, lower: bool| {
+                    let archived_bound = bound.map(ArchivedI32::from_native);
+                    let mut cursor = if lower {
+                        archived.lower_bound(archived_bound.as_ref())
+                    } else {
+                        archived.upper_bound(archived_bound.as_ref())
+                    };
+
+                    // The entries before the gap, nearest first
+                    let before = value
+                        .iter()
+                        .filter(|(k, _)| match (bound, lower) {
+                            (Included(b), true) | (Excluded(b), false) => {
+                                **k < b
+                            }
+                            (Excluded(b), true) | (Included(b), false) => {
+                                **k <= b
+                            }
+                            (Unbounded, lower) => !lower,
+                        })
+                        .map(|(k, v)| (*k, *v))
+                        .collect::<Vec<_>>();
+                    let after = value
+                        .iter()
+                        .skip(before.len())
+                        .map(|(k, v)| (*k, *v))
+                        .collect::<Vec<_>>();
+
+                    assert_eq!(
+                        native(cursor.peek_prev()),
+                        before.last().copied()
+                    );
+                    assert_eq!(
+                        native(cursor.peek_next()),
+                        after.first().copied()
+                    );
+
+                    let mut backward = cursor.clone();
+                    for &expected in after.iter() {
+                        assert_eq!(native(cursor.next()), Some(expected));
+                    }
+                    assert_eq!(native(cursor.next()), None);
+                    for &expected in before.iter().rev() {
+                        assert_eq!(native(backward.prev()), Some(expected));
+                    }
+                    assert_eq!(native(backward.prev()), None);
+                    assert_eq!(
+                        native(backward.peek_next()),
+                        value.iter().next().map(|(k, v)| (*k, *v))
+                    );
+
+                    // Walk all the way back from the end
+                    for expected in value.iter().rev() {
+                        assert_eq!(
+                            native(cursor.prev()),
+                            Some((*expected.0, *expected.1))
+                        );
+                    }
+                    assert_eq!(native(cursor.prev()), None);
+                };
+
+                for key in [-1, 0, 1, 7, 8, max / 2, max] {
+                    for lower in [true, false] {
+                        check(Included(key), lower);
+                        check(Excluded(key), lower);
+                    }
+                }
+                check(Unbounded, true);
+                check(Unbounded, false);
+            });
+        }
+    }
+
+    #[test]
+    fn btree_map_merge_and_intersection() {
+        use crate::collections::btree_map::MergedEntry;
+
+        const SIZES: &[(i32, i32)] =
+            &[(0, 0), (0, 10), (1, 1), (17, 36), (215, 6), (1000, 1000)];
+        for &(left_size, right_size) in SIZES {
+            // The left map has multiples of 2 and the right map has multiples
+            // of 3 so that they overlap on multiples of 6.
+            let left: BTreeMap<i32, i32> =
+                (0..left_size).map(|i| (i * 2, i)).collect();
+            let right: BTreeMap<i32, i32> =
+                (0..right_size).map(|i| (i * 3, -i)).collect();
+
+            to_archived(&(left.clone(), right.clone()), |archived| {
+                let (archived_left, archived_right) =
+                    (&archived.0, &archived.1);
+
+                let mut expected_merged = Vec::new();
+                for (k, v) in left.iter() {
+                    let r = right.get(k).copied();
+                    expected_merged.push((*k, Some(*v), r));
+                }
+                for (k, v) in right.iter() {
+                    if !left.contains_key(k) {
+                        expected_merged.push((*k, None, Some(*v)));
+                    }
+                }
+                expected_merged.sort();
+
+                let merged = archived_left
+                    .iter_merged(archived_right)
+                    .map(|entry| {
+                        if let MergedEntry::Both(k, l, r) = entry {
+                            assert_eq!(l.to_native(), k.to_native() / 2);
+                            assert_eq!(r.to_native(), -k.to_native() / 3);
+                        }
+                        (
+                            entry.key().to_native(),
+                            entry.left().map(|v| v.to_native()),
+                            entry.right().map(|v| v.to_native()),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(merged, expected_merged);
+
+                let expected_intersection = expected_merged
+                    .iter()
+                    .filter_map(|&(k, l, r)| Some((k, l?, r?)))
+                    .collect::<Vec<_>>();
+                let intersection = archived_left
+                    .intersection(archived_right)
+                    .map(|(k, l, r)| {
+                        (k.to_native(), l.to_native(), r.to_native())
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(intersection, expected_intersection);
+            });
+        }
+    }
+
+    #[test]
+    fn btree_map_range_seal() {
+        let value: BTreeMap<i32, i32> = (0..100).map(|i| (i, i)).collect();
+
+        to_archived(&value, |mut archived| {
+            let start = ArchivedI32::from_native(10);
+            let end = ArchivedI32::from_native(20);
+            for (_, mut v) in
+                ArchivedBTreeMap::range_seal(archived.as_mut(), start..end)
+            {
+                *v = ArchivedI32::from_native(-v.to_native());
+            }
+            for (k, v) in archived.iter() {
+                if (10..20).contains(&k.to_native()) {
+                    assert_eq!(v.to_native(), -k.to_native());
+                } else {
+                    assert_eq!(v.to_native(), k.to_native());
+                }
+            }
+        });
+    }
 }
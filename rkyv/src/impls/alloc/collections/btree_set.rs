@@ -88,11 +88,18 @@ impl<K, AK: PartialEq<K>> PartialEq<BTreeSet<K>> for ArchivedBTreeSet<AK> {
     }
 }
 
+impl<K, AK: PartialEq<K>> PartialEq<ArchivedBTreeSet<AK>> for BTreeSet<K> {
+    fn eq(&self, other: &ArchivedBTreeSet<AK>) -> bool {
+        other.eq(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        alloc::{collections::BTreeSet, string::ToString},
-        api::test::roundtrip,
+        alloc::{collections::BTreeSet, string::ToString, vec::Vec},
+        api::test::{roundtrip, to_archived},
+        primitive::ArchivedI32,
     };
 
     #[test]
@@ -113,4 +120,63 @@ mod tests {
 
         roundtrip(&value);
     }
+
+    #[test]
+    fn btree_set_iter_and_range() {
+        let value: BTreeSet<i32> = (0..100).map(|i| i * 3).collect();
+
+        to_archived(&value, |archived| {
+            assert_eq!(archived.first().map(|k| k.to_native()), Some(0));
+            assert_eq!(archived.last().map(|k| k.to_native()), Some(297));
+            assert!(archived
+                .iter()
+                .map(|k| k.to_native())
+                .eq(value.iter().copied()));
+
+            let start = ArchivedI32::from_native(10);
+            let end = ArchivedI32::from_native(50);
+            assert!(archived
+                .range(start..end)
+                .map(|k| k.to_native())
+                .eq(value.range(10..50).copied()));
+        });
+    }
+
+    #[test]
+    fn btree_set_operations() {
+        let a: BTreeSet<i32> = (0..60).filter(|i| i % 2 == 0).collect();
+        let b: BTreeSet<i32> = (0..60).filter(|i| i % 3 == 0).collect();
+        let c: BTreeSet<i32> = (0..60).filter(|i| i % 6 == 0).collect();
+
+        to_archived(&(a.clone(), b.clone(), c.clone()), |archived| {
+            let (aa, ab, ac) = (&archived.0, &archived.1, &archived.2);
+            let native = |iter: &mut dyn Iterator<Item = &ArchivedI32>| {
+                iter.map(|k| k.to_native()).collect::<Vec<_>>()
+            };
+
+            assert_eq!(
+                native(&mut aa.difference(ab)),
+                a.difference(&b).copied().collect::<Vec<_>>(),
+            );
+            assert_eq!(
+                native(&mut aa.symmetric_difference(ab)),
+                a.symmetric_difference(&b).copied().collect::<Vec<_>>(),
+            );
+            assert_eq!(
+                native(&mut aa.intersection(ab)),
+                a.intersection(&b).copied().collect::<Vec<_>>(),
+            );
+            assert_eq!(
+                native(&mut aa.union(ab)),
+                a.union(&b).copied().collect::<Vec<_>>(),
+            );
+
+            assert!(ac.is_subset(aa));
+            assert!(ac.is_subset(ab));
+            assert!(!aa.is_subset(ab));
+            assert!(aa.is_superset(ac));
+            assert!(!aa.is_disjoint(ab));
+            assert!(aa.difference(ab).all(|k| !ab.contains(k)));
+        });
+    }
 }
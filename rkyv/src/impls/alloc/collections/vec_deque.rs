@@ -4,18 +4,19 @@ use rancor::{Fallible, ResultExt, Source};
 
 use crate::{
     alloc::{alloc::alloc, boxed::Box, collections::VecDeque, vec::Vec},
+    collections::vec_deque::{ArchivedVecDeque, VecDequeResolver},
     ser::{Allocator, Writer},
     traits::LayoutRaw,
-    vec::{ArchivedVec, VecResolver},
+    vec::ArchivedVec,
     Archive, Deserialize, DeserializeUnsized, Place, Serialize,
 };
 
 impl<T: Archive> Archive for VecDeque<T> {
-    type Archived = ArchivedVec<T::Archived>;
-    type Resolver = VecResolver;
+    type Archived = ArchivedVecDeque<T::Archived>;
+    type Resolver = VecDequeResolver;
 
     fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
-        ArchivedVec::resolve_from_len(self.len(), resolver, out);
+        ArchivedVecDeque::resolve_from_len(self.len(), resolver, out);
     }
 }
 
@@ -28,17 +29,40 @@ where
         &self,
         serializer: &mut S,
     ) -> Result<Self::Resolver, S::Error> {
-        let (a, b) = self.as_slices();
-        if b.is_empty() {
-            ArchivedVec::<T::Archived>::serialize_from_slice(a, serializer)
-        } else if a.is_empty() {
-            ArchivedVec::<T::Archived>::serialize_from_slice(b, serializer)
-        } else {
-            ArchivedVec::<T::Archived>::serialize_from_iter::<T, _, _>(
-                self.iter(),
-                serializer,
-            )
+        let (front, back) = self.as_slices();
+        ArchivedVecDeque::<T::Archived>::serialize_from_slices(
+            front, back, serializer,
+        )
+    }
+}
+
+impl<T, D> Deserialize<VecDeque<T>, D> for ArchivedVecDeque<T::Archived>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<VecDeque<T>, D::Error> {
+        let mut result = VecDeque::with_capacity(self.len());
+        for value in self.iter() {
+            result.push_back(value.deserialize(deserializer)?);
         }
+        Ok(result)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<VecDeque<U>> for ArchivedVecDeque<T> {
+    fn eq(&self, other: &VecDeque<U>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: PartialOrd> PartialOrd<VecDeque<T>> for ArchivedVecDeque<T> {
+    fn partial_cmp(&self, other: &VecDeque<T>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
     }
 }
 
@@ -84,8 +108,14 @@ impl<T: PartialOrd> PartialOrd<VecDeque<T>> for ArchivedVec<T> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        access_unchecked, alloc::collections::VecDeque, api::test::deserialize,
-        rancor::Error, to_bytes, vec::ArchivedVec, Archived,
+        access_unchecked, access_unchecked_mut,
+        alloc::collections::VecDeque,
+        api::test::{deserialize, roundtrip},
+        collections::vec_deque::ArchivedVecDeque,
+        rancor::Error,
+        to_bytes,
+        vec::ArchivedVec,
+        Archived,
     };
 
     #[test]
@@ -105,7 +135,7 @@ mod tests {
                 // deserialized version contains `0..n`.
                 let bytes = to_bytes::<Error>(&deque).unwrap();
                 let archived = unsafe {
-                    access_unchecked::<ArchivedVec<Archived<i32>>>(&bytes)
+                    access_unchecked::<ArchivedVecDeque<Archived<i32>>>(&bytes)
                 };
                 assert!(archived.iter().copied().eq(0..n));
 
@@ -114,4 +144,67 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn vecdeque_from_vec() {
+        let bytes = to_bytes::<Error>(&vec![1, 2, 3]).unwrap();
+        let archived =
+            unsafe { access_unchecked::<ArchivedVec<Archived<i32>>>(&bytes) };
+        let deserialized = deserialize::<VecDeque<i32>>(archived);
+        assert_eq!(deserialized, [1, 2, 3]);
+    }
+
+    #[test]
+    fn vecdeque_rotate() {
+        let deque = (0..5).collect::<VecDeque<i32>>();
+        roundtrip(&deque);
+
+        let mut bytes = to_bytes::<Error>(&deque).unwrap();
+        let mut archived = unsafe {
+            access_unchecked_mut::<ArchivedVecDeque<Archived<i32>>>(&mut bytes)
+        };
+
+        ArchivedVecDeque::rotate_left_seal(archived.as_mut(), 2);
+        assert!(archived.iter().copied().eq([2, 3, 4, 0, 1]));
+        let (front, back) = archived.as_slices();
+        assert!(front.iter().copied().eq([2, 3, 4]));
+        assert!(back.iter().copied().eq([0, 1]));
+        assert_eq!(*archived.front().unwrap(), 2);
+        assert_eq!(*archived.back().unwrap(), 1);
+        assert_eq!(archived[4], 1);
+
+        ArchivedVecDeque::rotate_right_seal(archived.as_mut(), 3);
+        assert!(archived.iter().copied().eq([4, 0, 1, 2, 3]));
+        assert!(archived.iter().rev().copied().eq([3, 2, 1, 0, 4]));
+
+        let (front, _) = ArchivedVecDeque::as_slices_seal(archived.as_mut());
+        let mut first = front.index(0);
+        *first = 10.into();
+        assert_eq!(deserialize::<VecDeque<i32>>(&*archived), [10, 0, 1, 2, 3]);
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn vecdeque_invalid_head() {
+        use crate::{access, primitive::ArchivedUsize, util::AlignedVec};
+
+        type Archived = ArchivedVecDeque<crate::Archived<i32>>;
+
+        let deque = (0..5).collect::<VecDeque<i32>>();
+        let bytes = to_bytes::<Error>(&deque).unwrap();
+        access::<Archived, Error>(&bytes).unwrap();
+
+        // The head is the last field of the root object.
+        let mut corrupted = AlignedVec::<16>::new();
+        corrupted.extend_from_slice(&bytes);
+        let head_pos = corrupted.len() - size_of::<ArchivedUsize>();
+        unsafe {
+            corrupted
+                .as_mut_ptr()
+                .add(head_pos)
+                .cast::<ArchivedUsize>()
+                .write_unaligned(ArchivedUsize::from_native(5));
+        }
+        access::<Archived, Error>(&corrupted).unwrap_err();
+    }
 }
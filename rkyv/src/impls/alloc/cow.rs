@@ -0,0 +1,178 @@
+use core::cmp::Ordering;
+
+use rancor::{Fallible, Source};
+
+use crate::{
+    alloc::{borrow::Cow, string::String, vec::Vec},
+    rel_ptr::Offset,
+    ser::{Allocator, Writer},
+    string::{ArchivedString, StringResolver},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Serialize,
+};
+
+impl Archive for Cow<'_, str> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedString::resolve_from_str(self, resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for Cow<'_, str>
+where
+    S: Fallible + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(self, serializer)
+    }
+}
+
+impl<'a, D> Deserialize<Cow<'a, str>, D> for ArchivedString
+where
+    D: Fallible + ?Sized,
+    ArchivedString: Deserialize<String, D>,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Cow<'a, str>, D::Error> {
+        Ok(Cow::Owned(self.deserialize(deserializer)?))
+    }
+}
+
+impl<T: Archive + Clone> Archive for Cow<'_, [T]> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedVec::resolve_from_slice(self, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for Cow<'_, [T]>
+where
+    T: Serialize<S> + Clone,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(self, serializer)
+    }
+}
+
+impl<'a, T, O, L, D> Deserialize<Cow<'a, [T]>, D>
+    for ArchivedVec<T::Archived, O, L>
+where
+    T: Archive + Clone,
+    O: Offset,
+    L: Offset,
+    D: Fallible + ?Sized,
+    ArchivedVec<T::Archived, O, L>: Deserialize<Vec<T>, D>,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut D,
+    ) -> Result<Cow<'a, [T]>, D::Error> {
+        Ok(Cow::Owned(self.deserialize(deserializer)?))
+    }
+}
+
+impl<const N: usize> PartialEq<Cow<'_, str>> for ArchivedString<N> {
+    fn eq(&self, other: &Cow<'_, str>) -> bool {
+        self.as_str() == &**other
+    }
+}
+
+impl<const N: usize> PartialEq<ArchivedString<N>> for Cow<'_, str> {
+    fn eq(&self, other: &ArchivedString<N>) -> bool {
+        &**self == other.as_str()
+    }
+}
+
+impl<const N: usize> PartialOrd<Cow<'_, str>> for ArchivedString<N> {
+    fn partial_cmp(&self, other: &Cow<'_, str>) -> Option<Ordering> {
+        self.as_str().partial_cmp(&**other)
+    }
+}
+
+impl<const N: usize> PartialOrd<ArchivedString<N>> for Cow<'_, str> {
+    fn partial_cmp(&self, other: &ArchivedString<N>) -> Option<Ordering> {
+        (**self).partial_cmp(other.as_str())
+    }
+}
+
+impl<T, U, O, L> PartialEq<Cow<'_, [U]>> for ArchivedVec<T, O, L>
+where
+    T: PartialEq<U>,
+    U: Clone,
+    O: Offset,
+    L: Offset,
+{
+    fn eq(&self, other: &Cow<'_, [U]>) -> bool {
+        self.as_slice().eq(&**other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        alloc::{borrow::Cow, collections::BTreeMap, string::ToString, vec},
+        api::test::{roundtrip, to_archived},
+        Archive, Deserialize, Serialize,
+    };
+
+    #[test]
+    fn roundtrip_cow() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            a: Cow<'static, str>,
+            b: Cow<'static, [u32]>,
+        }
+
+        let value = Test {
+            a: Cow::Borrowed("a string which is stored out of line"),
+            b: Cow::Owned(vec![1, 2, 3]),
+        };
+        roundtrip(&value);
+
+        let value = Test {
+            a: Cow::Owned("hello".to_string()),
+            b: Cow::Borrowed(&[4, 5, 6]),
+        };
+        roundtrip(&value);
+    }
+
+    #[test]
+    fn compare_cow_str() {
+        let value = Cow::Borrowed("hello");
+        to_archived(&value, |archived| {
+            assert!(*archived == value);
+            assert!(value == *archived);
+            assert!(*archived < Cow::Borrowed("world"));
+            assert!(Cow::Borrowed("abc") < *archived);
+        });
+    }
+
+    #[test]
+    fn roundtrip_btree_map_cow_keys() {
+        let mut value = BTreeMap::new();
+        value.insert(Cow::Borrowed("foo"), 1i32);
+        value.insert(Cow::Owned("bar".to_string()), 2);
+        roundtrip(&value);
+
+        to_archived(&value, |archived| {
+            assert_eq!(archived.get("foo").map(|v| v.to_native()), Some(1));
+            assert_eq!(archived.get("bar").map(|v| v.to_native()), Some(2));
+            assert!(!archived.contains_key("baz"));
+        });
+    }
+}
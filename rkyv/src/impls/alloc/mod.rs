@@ -1,5 +1,6 @@
 mod boxed;
 mod collections;
+mod cow;
 mod ffi;
 mod rc;
 mod string;
@@ -163,6 +163,7 @@ mod tests {
             rc::{Rc, Weak},
             string::{String, ToString},
             vec,
+            vec::Vec,
         },
         api::{
             deserialize_using,
@@ -295,6 +296,40 @@ mod tests {
         roundtrip(&value);
     }
 
+    #[test]
+    fn reuse_pool() {
+        let shared = Rc::new(42u32);
+        let value = vec![shared.clone(), Rc::new(17), shared];
+
+        to_archived(&value, |archived| {
+            let mut pool = Pool::with_capacity(8);
+            let capacity = pool.capacity();
+
+            let first =
+                deserialize_using::<Rc<u32>, _, Panic>(&archived[0], &mut pool)
+                    .unwrap();
+            let all = deserialize_using::<Vec<Rc<u32>>, _, Panic>(
+                &*archived, &mut pool,
+            )
+            .unwrap();
+            assert_eq!(pool.len(), 2);
+            assert!(Rc::ptr_eq(&first, &all[0]));
+            assert!(Rc::ptr_eq(&first, &all[2]));
+            assert_eq!(Rc::strong_count(&first), 4);
+
+            pool.clear();
+            assert!(pool.is_empty());
+            assert_eq!(pool.capacity(), capacity);
+            assert_eq!(Rc::strong_count(&first), 3);
+            assert_eq!(Rc::strong_count(&all[1]), 1);
+
+            let again =
+                deserialize_using::<Rc<u32>, _, Panic>(&archived[0], &mut pool)
+                    .unwrap();
+            assert!(!Rc::ptr_eq(&first, &again));
+        });
+    }
+
     #[test]
     fn roundtrip_weak_ptr() {
         #[derive(Archive, Serialize, Deserialize)]
@@ -41,30 +41,30 @@ where
     }
 }
 
-impl PartialEq<String> for ArchivedString {
+impl<const N: usize> PartialEq<String> for ArchivedString<N> {
     #[inline]
     fn eq(&self, other: &String) -> bool {
         PartialEq::eq(self.as_str(), other.as_str())
     }
 }
 
-impl PartialEq<ArchivedString> for String {
+impl<const N: usize> PartialEq<ArchivedString<N>> for String {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<N>) -> bool {
         PartialEq::eq(other.as_str(), self.as_str())
     }
 }
 
-impl PartialOrd<String> for ArchivedString {
+impl<const N: usize> PartialOrd<String> for ArchivedString<N> {
     #[inline]
     fn partial_cmp(&self, other: &String) -> Option<Ordering> {
         self.as_str().partial_cmp(other.as_str())
     }
 }
 
-impl PartialOrd<ArchivedString> for String {
+impl<const N: usize> PartialOrd<ArchivedString<N>> for String {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<N>) -> Option<Ordering> {
         self.as_str().partial_cmp(other.as_str())
     }
 }
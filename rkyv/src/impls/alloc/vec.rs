@@ -2,6 +2,7 @@ use rancor::{Fallible, ResultExt as _, Source};
 
 use crate::{
     alloc::{alloc::alloc, boxed::Box, vec::Vec},
+    rel_ptr::Offset,
     ser::{Allocator, Writer},
     traits::LayoutRaw,
     vec::{ArchivedVec, VecResolver},
@@ -31,9 +32,11 @@ impl<T: Serialize<S>, S: Fallible + Allocator + Writer + ?Sized> Serialize<S>
     }
 }
 
-impl<T, D> Deserialize<Vec<T>, D> for ArchivedVec<T::Archived>
+impl<T, O, L, D> Deserialize<Vec<T>, D> for ArchivedVec<T::Archived, O, L>
 where
     T: Archive,
+    O: Offset,
+    L: Offset,
     [T::Archived]: DeserializeUnsized<[T], D>,
     D: Fallible + ?Sized,
     D::Error: Source,
@@ -54,13 +57,28 @@ where
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for ArchivedVec<T> {
+impl<T: PartialEq<U>, U, O: Offset, L: Offset> PartialEq<Vec<U>>
+    for ArchivedVec<T, O, L>
+{
     fn eq(&self, other: &Vec<U>) -> bool {
         self.as_slice().eq(other.as_slice())
     }
 }
 
-impl<T: PartialOrd<U>, U> PartialOrd<Vec<U>> for ArchivedVec<T> {
+impl<T: PartialEq<U>, U, O: Offset, L: Offset> PartialEq<ArchivedVec<T, O, L>>
+    for Vec<U>
+{
+    fn eq(&self, other: &ArchivedVec<T, O, L>) -> bool {
+        other.eq(self)
+    }
+}
+
+impl<T, U, O, L> PartialOrd<Vec<U>> for ArchivedVec<T, O, L>
+where
+    T: PartialOrd<U>,
+    O: Offset,
+    L: Offset,
+{
     fn partial_cmp(&self, other: &Vec<U>) -> Option<::core::cmp::Ordering> {
         crate::impls::lexicographical_partial_ord(
             self.as_slice(),
@@ -82,6 +100,24 @@ mod tests {
         roundtrip(&vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    fn roundtrip_vec_primitives() {
+        // Lengths which exercise both the bulk and per-element conversions.
+        for len in [0u32, 1, 3, 8, 13, 33] {
+            roundtrip(&(0..len).map(|i| i as u16 * 257).collect::<Vec<_>>());
+            roundtrip(&(0..len).map(|i| i * 0x01020304).collect::<Vec<_>>());
+            roundtrip(&(0..len).map(|i| i as f32 / 3.0).collect::<Vec<_>>());
+            roundtrip(&(0..len).map(|i| (i as i64) << 40).collect::<Vec<_>>());
+            roundtrip(&(0..len).map(|i| i as f64 * 1e300).collect::<Vec<_>>());
+            roundtrip(&(0..len).map(|i| !(i as i128)).collect::<Vec<_>>());
+            roundtrip(
+                &(0..len)
+                    .map(|i| char::from_u32(0x1f600 + i).unwrap())
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
     #[test]
     fn roundtrip_vec_zst() {
         roundtrip(&Vec::<()>::new());
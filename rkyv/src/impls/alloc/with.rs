@@ -1,4 +1,8 @@
-use core::{marker::PhantomData, ops::ControlFlow};
+use core::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ops::{ControlFlow, Range},
+};
 
 use ptr_meta::Pointee;
 use rancor::{Fallible, Source};
@@ -9,21 +13,44 @@ use crate::{
         boxed::Box,
         collections::{BTreeMap, BTreeSet},
         rc::Rc,
+        string::String,
         vec::Vec,
     },
+    api::high::{to_bytes, HighSerializer},
+    blob::{ArchivedBlob, BlobResolver},
     collections::{
+        bitvec::{ArchivedBitVec, BitVecResolver},
+        bounded::{
+            ArchivedBoundedString, ArchivedBoundedVec, BoundedStringResolver,
+            BoundedVecResolver,
+        },
         btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+        elias_fano::{ArchivedEliasFano, EliasFanoResolver},
+        interval_map::{ArchivedIntervalMap, IntervalMapResolver},
+        rle_vec::{ArchivedRleVec, RleVecResolver},
+        sorted_vec_map::{ArchivedSortedVecMap, SortedVecMapResolver},
+        trie::{ArchivedTrieMap, TrieMapResolver},
         util::{Entry, EntryAdapter},
     },
+    columns::{ArchiveColumns, DeserializeColumns, SerializeColumns},
+    de::{BlobSource, BlobSourceExt as _, Borrowing, BorrowingExt as _},
     impls::core::with::RefWrapper,
     niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{Allocator, Writer},
+    raw_archive::{ArchivedRawArchive, RawArchiveResolver},
+    rc::{ArchivedRc, InternFlavor, RcResolver},
+    ser::{
+        allocator::ArenaHandle, Allocator, Blobs, Interning, InterningExt as _,
+        Writer,
+    },
     string::{ArchivedString, StringResolver},
     traits::LayoutRaw,
-    vec::{ArchivedVec, VecResolver},
+    util::AlignedVec,
+    vec::{ArchivedVec, ArchivedWideVec, VecResolver, WideVecResolver},
     with::{
-        ArchiveWith, AsOwned, AsVec, DeserializeWith, Map, MapKV, Niche,
-        SerializeWith, Unshare,
+        ArchiveWith, AsBitVec, AsColumns, AsEliasFano, AsIntervalMap, AsOwned,
+        AsRawArchive, AsRle, AsSortedVec, AsTrieMap, AsVec, Borrowed, Bounded,
+        DeserializeWith, ExternalBytes, InlineCapacity, Intern, Map, MapKV,
+        Niche, SerializeWith, Unshare, Wide,
     },
     Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized,
     Place, Serialize, SerializeUnsized,
@@ -265,23 +292,35 @@ where
     }
 }
 
-impl<'a, T, D> DeserializeWith<ArchivedVec<T::Archived>, Cow<'a, [T]>, D>
-    for AsOwned
+impl<'a> ArchiveWith<Cow<'a, str>> for AsOwned {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &Cow<'a, str>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<Cow<'a, str>, S> for AsOwned
 where
-    T: Archive + Clone,
-    T::Archived: Deserialize<T, D>,
-    D: Fallible + ?Sized,
-    D::Error: Source,
+    S: Fallible + Writer + ?Sized,
+    S::Error: Source,
 {
-    fn deserialize_with(
-        field: &ArchivedVec<T::Archived>,
-        deserializer: &mut D,
-    ) -> Result<Cow<'a, [T]>, D::Error> {
-        Ok(Cow::Owned(field.deserialize(deserializer)?))
+    fn serialize_with(
+        field: &Cow<'a, str>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
     }
 }
 
-impl<'a> ArchiveWith<Cow<'a, str>> for AsOwned {
+// Borrowed
+
+impl<'a> ArchiveWith<Cow<'a, str>> for Borrowed {
     type Archived = ArchivedString;
     type Resolver = StringResolver;
 
@@ -294,7 +333,7 @@ impl<'a> ArchiveWith<Cow<'a, str>> for AsOwned {
     }
 }
 
-impl<'a, S> SerializeWith<Cow<'a, str>, S> for AsOwned
+impl<'a, S> SerializeWith<Cow<'a, str>, S> for Borrowed
 where
     S: Fallible + Writer + ?Sized,
     S::Error: Source,
@@ -307,15 +346,58 @@ where
     }
 }
 
-impl<'a, D> DeserializeWith<ArchivedString, Cow<'a, str>, D> for AsOwned
+impl<'a, D> DeserializeWith<ArchivedString, Cow<'a, str>, D> for Borrowed
 where
-    D: Fallible + ?Sized,
+    D: Fallible + Borrowing<'a, D::Error> + ?Sized,
+    D::Error: Source,
 {
     fn deserialize_with(
         field: &ArchivedString,
         deserializer: &mut D,
     ) -> Result<Cow<'a, str>, D::Error> {
-        Ok(Cow::Owned(field.deserialize(deserializer)?))
+        Ok(Cow::Borrowed(deserializer.borrow_archived(field.as_str())?))
+    }
+}
+
+impl<'a, T: Archive + Clone> ArchiveWith<Cow<'a, [T]>> for Borrowed {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &Cow<'a, [T]>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_slice(field, resolver, out);
+    }
+}
+
+impl<'a, T, S> SerializeWith<Cow<'a, [T]>, S> for Borrowed
+where
+    T: Serialize<S> + Clone,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Cow<'a, [T]>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<'a, T, D> DeserializeWith<ArchivedVec<T>, Cow<'a, [T]>, D> for Borrowed
+where
+    T: Archive<Archived = T> + Clone,
+    D: Fallible + Borrowing<'a, D::Error> + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<T>,
+        deserializer: &mut D,
+    ) -> Result<Cow<'a, [T]>, D::Error> {
+        Ok(Cow::Borrowed(
+            deserializer.borrow_archived(field.as_slice())?,
+        ))
     }
 }
 
@@ -428,6 +510,67 @@ where
     }
 }
 
+// AsSortedVec
+
+impl<K: Archive, V: Archive> ArchiveWith<BTreeMap<K, V>> for AsSortedVec {
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = SortedVecMapResolver;
+
+    fn resolve_with(
+        field: &BTreeMap<K, V>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for AsSortedVec
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedSortedVecMap<K::Archived, V::Archived>,
+        BTreeMap<K, V>,
+        D,
+    > for AsSortedVec
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
 // Niche
 
 impl<T> ArchiveWith<Option<Box<T>>> for Niche
@@ -481,6 +624,183 @@ where
     }
 }
 
+// AsColumns
+
+impl<T: ArchiveColumns> ArchiveWith<Vec<T>> for AsColumns {
+    type Archived = T::ArchivedColumns;
+    type Resolver = T::ColumnsResolver;
+
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        T::resolve_columns(field.len(), resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for AsColumns
+where
+    T: SerializeColumns<S>,
+    S: Fallible + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        T::serialize_columns(field, serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<T::ArchivedColumns, Vec<T>, D> for AsColumns
+where
+    T: ArchiveColumns,
+    T::ArchivedColumns: DeserializeColumns<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &T::ArchivedColumns,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let len = T::columns_len(field);
+        let mut result = Vec::with_capacity(len);
+        for i in 0..len {
+            result.push(field.deserialize_row(i, deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
+// Intern
+
+impl ArchiveWith<String> for Intern {
+    type Archived = ArchivedRc<ArchivedString, InternFlavor>;
+    type Resolver = RcResolver;
+
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRc::resolve_from_ref(field, resolver, out);
+    }
+}
+
+impl<S> SerializeWith<String, S> for Intern
+where
+    S: Fallible + Interning + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(RcResolver::from_pos(serializer.serialize_interned(field)?))
+    }
+}
+
+impl<D> DeserializeWith<ArchivedRc<ArchivedString, InternFlavor>, String, D>
+    for Intern
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRc<ArchivedString, InternFlavor>,
+        _: &mut D,
+    ) -> Result<String, D::Error> {
+        Ok(String::from(field.as_str()))
+    }
+}
+
+// ExternalBytes
+
+impl ArchiveWith<Vec<u8>> for ExternalBytes {
+    type Archived = ArchivedBlob;
+    type Resolver = BlobResolver;
+
+    fn resolve_with(
+        _: &Vec<u8>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBlob::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Vec<u8>, S> for ExternalBytes
+where
+    S: Fallible + Blobs + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<u8>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let offset = serializer.write_blob(field)?;
+        Ok(BlobResolver::new(offset, field.len()))
+    }
+}
+
+impl<D> DeserializeWith<ArchivedBlob, Vec<u8>, D> for ExternalBytes
+where
+    D: Fallible + BlobSource + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedBlob,
+        deserializer: &mut D,
+    ) -> Result<Vec<u8>, D::Error> {
+        Ok(deserializer.get_blob(field)?.to_vec())
+    }
+}
+
+// AsRawArchive
+
+impl<T> ArchiveWith<T> for AsRawArchive {
+    type Archived = ArchivedRawArchive<T>;
+    type Resolver = RawArchiveResolver;
+
+    fn resolve_with(
+        _: &T,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRawArchive::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<T, S> for AsRawArchive
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, S::Error>>,
+    S: Fallible + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &T,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let bytes = to_bytes::<S::Error>(field)?;
+        ArchivedRawArchive::<T>::serialize_from_bytes(&bytes, serializer)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T, D> DeserializeWith<ArchivedRawArchive<T>, T, D> for AsRawArchive
+where
+    T: Archive,
+    T::Archived: for<'a> bytecheck::CheckBytes<
+            crate::api::high::HighValidator<'a, D::Error>,
+        > + Deserialize<T, D>,
+    D: Fallible + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedRawArchive<T>,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error> {
+        field.access::<D::Error>()?.deserialize(deserializer)
+    }
+}
+
 // Unshare
 
 #[cfg(target_has_atomic = "ptr")]
@@ -559,84 +879,753 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use core::mem::size_of;
-
-    use crate::{
-        alloc::{
-            borrow::Cow,
-            boxed::Box,
-            collections::{BTreeMap, BTreeSet},
-            string::{String, ToString},
-        },
-        api::test::{roundtrip, roundtrip_with, to_archived},
-        niche::niching::Null,
-        with::{
-            AsOwned, AsVec, DefaultNiche, InlineAsBox, Map, MapKV, Niche,
-            NicheInto,
-        },
-        Archive, Deserialize, Serialize,
-    };
+// Wide
 
-    #[derive(Debug, Archive, Deserialize, Serialize, PartialEq)]
-    #[rkyv(crate, compare(PartialEq), derive(Debug))]
-    struct Test {
-        value: Option<Box<u128>>,
-    }
+impl<T: Archive> ArchiveWith<Vec<T>> for Wide {
+    type Archived = ArchivedWideVec<T::Archived>;
+    type Resolver = WideVecResolver;
 
-    #[test]
-    fn roundtrip_niche_none() {
-        roundtrip(&Test { value: None });
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedWideVec::resolve_wide_from_slice(
+            field.as_slice(),
+            resolver,
+            out,
+        );
     }
+}
 
-    #[test]
-    fn roundtrip_niche_some() {
-        roundtrip(&Test {
+impl<T, S> SerializeWith<Vec<T>, S> for Wide
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedWideVec::<T::Archived>::serialize_wide_from_slice(
+            field.as_slice(),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedWideVec<T::Archived>, Vec<T>, D> for Wide
+where
+    T: Archive,
+    ArchivedWideVec<T::Archived>: Deserialize<Vec<T>, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedWideVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// AsBitVec
+
+impl ArchiveWith<Vec<bool>> for AsBitVec {
+    type Archived = ArchivedBitVec;
+    type Resolver = BitVecResolver;
+
+    fn resolve_with(
+        field: &Vec<bool>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBitVec::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Vec<bool>, S> for AsBitVec
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Vec<bool>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBitVec::serialize_from_iter(field.iter().copied(), serializer)
+    }
+}
+
+impl<D> DeserializeWith<ArchivedBitVec, Vec<bool>, D> for AsBitVec
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBitVec,
+        _: &mut D,
+    ) -> Result<Vec<bool>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
+
+impl PartialEq<Vec<bool>> for ArchivedBitVec {
+    fn eq(&self, other: &Vec<bool>) -> bool {
+        *self == **other
+    }
+}
+
+// AsEliasFano
+
+impl ArchiveWith<Vec<u64>> for AsEliasFano {
+    type Archived = ArchivedEliasFano;
+    type Resolver = EliasFanoResolver;
+
+    fn resolve_with(
+        field: &Vec<u64>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedEliasFano::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Vec<u64>, S> for AsEliasFano
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Vec<u64>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedEliasFano::serialize_from_iter(
+            field.iter().copied(),
+            serializer,
+        )
+    }
+}
+
+impl<D> DeserializeWith<ArchivedEliasFano, Vec<u64>, D> for AsEliasFano
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedEliasFano,
+        _: &mut D,
+    ) -> Result<Vec<u64>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
+
+impl ArchiveWith<BTreeSet<u64>> for AsEliasFano {
+    type Archived = ArchivedEliasFano;
+    type Resolver = EliasFanoResolver;
+
+    fn resolve_with(
+        field: &BTreeSet<u64>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedEliasFano::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<BTreeSet<u64>, S> for AsEliasFano
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &BTreeSet<u64>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedEliasFano::serialize_from_iter(
+            field.iter().copied(),
+            serializer,
+        )
+    }
+}
+
+impl<D> DeserializeWith<ArchivedEliasFano, BTreeSet<u64>, D> for AsEliasFano
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedEliasFano,
+        _: &mut D,
+    ) -> Result<BTreeSet<u64>, D::Error> {
+        Ok(field.iter().collect())
+    }
+}
+
+impl PartialEq<Vec<u64>> for ArchivedEliasFano {
+    fn eq(&self, other: &Vec<u64>) -> bool {
+        *self == **other
+    }
+}
+
+// AsRle
+
+impl<T: Archive> ArchiveWith<Vec<T>> for AsRle {
+    type Archived = ArchivedRleVec<T::Archived>;
+    type Resolver = RleVecResolver;
+
+    fn resolve_with(
+        _: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedRleVec::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for AsRle
+where
+    T: Serialize<S> + PartialEq,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedRleVec::serialize_from_iter::<_, _, T, _>(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedRleVec<T::Archived>, Vec<T>, D> for AsRle
+where
+    T: Archive + Clone,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRleVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for (len, value) in field.runs() {
+            let value = value.deserialize(deserializer)?;
+            result.resize(result.len() + len, value);
+        }
+        Ok(result)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for ArchivedRleVec<T> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<BTreeSet<u64>> for ArchivedEliasFano {
+    fn eq(&self, other: &BTreeSet<u64>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+// InlineCapacity
+
+impl<const N: usize> ArchiveWith<String> for InlineCapacity<N> {
+    type Archived = ArchivedString<N>;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<S, const N: usize> SerializeWith<String, S> for InlineCapacity<N>
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+    str: SerializeUnsized<S>,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::<N>::serialize_with_inline_capacity(field, serializer)
+    }
+}
+
+impl<D, const N: usize> DeserializeWith<ArchivedString<N>, String, D>
+    for InlineCapacity<N>
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedString<N>,
+        _: &mut D,
+    ) -> Result<String, D::Error> {
+        Ok(String::from(field.as_str()))
+    }
+}
+
+// Bounded
+
+impl<T: Archive, const N: usize> ArchiveWith<Vec<T>> for Bounded<N> {
+    type Archived = ArchivedBoundedVec<T::Archived, N>;
+    type Resolver = BoundedVecResolver<T::Resolver, N>;
+
+    fn resolve_with(
+        field: &Vec<T>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBoundedVec::resolve_from_slice(field, resolver, out);
+    }
+}
+
+impl<T, S, const N: usize> SerializeWith<Vec<T>, S> for Bounded<N>
+where
+    T: Serialize<S>,
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Vec<T>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBoundedVec::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<T, D, const N: usize>
+    DeserializeWith<ArchivedBoundedVec<T::Archived, N>, Vec<T>, D>
+    for Bounded<N>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBoundedVec<T::Archived, N>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+impl<const N: usize> ArchiveWith<String> for Bounded<N> {
+    type Archived = ArchivedBoundedString<N>;
+    type Resolver = BoundedStringResolver;
+
+    fn resolve_with(
+        field: &String,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBoundedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<S, const N: usize> SerializeWith<String, S> for Bounded<N>
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &String,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBoundedString::<N>::serialize_from_str(field, serializer)
+    }
+}
+
+impl<D, const N: usize> DeserializeWith<ArchivedBoundedString<N>, String, D>
+    for Bounded<N>
+where
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBoundedString<N>,
+        _: &mut D,
+    ) -> Result<String, D::Error> {
+        Ok(String::from(field.as_str()))
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<Vec<U>>
+    for ArchivedBoundedVec<T, N>
+{
+    fn eq(&self, other: &Vec<U>) -> bool {
+        self.as_slice().eq(other.as_slice())
+    }
+}
+
+impl<const N: usize> PartialEq<String> for ArchivedBoundedString<N> {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+// AsTrieMap
+
+impl<K, V: Archive> ArchiveWith<BTreeMap<K, V>> for AsTrieMap {
+    type Archived = ArchivedTrieMap<V::Archived>;
+    type Resolver = TrieMapResolver;
+
+    fn resolve_with(
+        field: &BTreeMap<K, V>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedTrieMap::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for AsTrieMap
+where
+    K: Serialize<S, Archived = ArchivedString> + Borrow<str>,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedTrieMap::serialize_from_sorted_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedTrieMap<V::Archived>, BTreeMap<K, V>, D>
+    for AsTrieMap
+where
+    K: Ord,
+    ArchivedString: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedTrieMap<V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsIntervalMap
+
+impl<K: Archive, V: Archive> ArchiveWith<Vec<(Range<K>, V)>> for AsIntervalMap {
+    type Archived = ArchivedIntervalMap<K::Archived, V::Archived>;
+    type Resolver = IntervalMapResolver;
+
+    fn resolve_with(
+        field: &Vec<(Range<K>, V)>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedIntervalMap::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<Vec<(Range<K>, V)>, S> for AsIntervalMap
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Vec<(Range<K>, V)>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedIntervalMap::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter().map(|(range, value)| (range, value)),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedIntervalMap<K::Archived, V::Archived>,
+        Vec<(Range<K>, V)>,
+        D,
+    > for AsIntervalMap
+where
+    K: Archive,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedIntervalMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<(Range<K>, V)>, D::Error> {
+        field
+            .iter()
+            .map(|(range, value)| {
+                Ok((
+                    range.start.deserialize(deserializer)?
+                        ..range.end.deserialize(deserializer)?,
+                    value.deserialize(deserializer)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{mem::size_of, ops::Range};
+
+    use crate::{
+        alloc::{
+            borrow::Cow,
+            boxed::Box,
+            collections::{BTreeMap, BTreeSet},
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{roundtrip, roundtrip_with, to_archived},
+        niche::niching::Null,
+        string::ArchivedString,
+        vec::{ArchivedVec, ArchivedWideVec},
+        with::{
+            AsBitVec, AsColumns, AsEliasFano, AsIntervalMap, AsOwned, AsRle,
+            AsTrieMap, AsVec, Borrowed, Bounded, DefaultNiche, InlineAsBox,
+            InlineCapacity, Intern, Map, MapKV, Niche, NicheInto, Wide,
+        },
+        Archive, Archived, Deserialize, Serialize,
+    };
+
+    #[derive(Debug, Archive, Deserialize, Serialize, PartialEq)]
+    #[rkyv(crate, compare(PartialEq), derive(Debug))]
+    struct Test {
+        value: Option<Box<u128>>,
+    }
+
+    #[test]
+    fn roundtrip_niche_none() {
+        roundtrip(&Test { value: None });
+    }
+
+    #[test]
+    fn roundtrip_niche_some() {
+        roundtrip(&Test {
             value: Some(Box::new(128)),
         });
     }
 
     #[test]
-    fn ambiguous_niched_archived_box() {
-        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
-        #[rkyv(crate, compare(PartialEq), derive(Debug))]
-        struct HasNiche {
-            #[rkyv(with = Niche)]
-            inner: Option<Box<[u32]>>,
+    fn ambiguous_niched_archived_box() {
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct HasNiche {
+            #[rkyv(with = Niche)]
+            inner: Option<Box<[u32]>>,
+        }
+
+        roundtrip(&HasNiche {
+            inner: Some(Box::<[u32]>::from([])),
+        });
+        roundtrip(&HasNiche { inner: None });
+    }
+
+    #[test]
+    fn with_as_owned() {
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test<'a> {
+            #[rkyv(with = AsOwned)]
+            a: Cow<'a, u32>,
+            #[rkyv(with = AsOwned)]
+            b: Cow<'a, [u32]>,
+            #[rkyv(with = AsOwned)]
+            c: Cow<'a, str>,
+        }
+
+        let value = Test {
+            a: Cow::Borrowed(&100),
+            b: Cow::Borrowed(&[1, 2, 3, 4, 5, 6]),
+            c: Cow::Borrowed("hello world"),
+        };
+        to_archived(&value, |archived| {
+            assert_eq!(archived.a, 100);
+            assert_eq!(archived.b, [1, 2, 3, 4, 5, 6]);
+            assert_eq!(archived.c, "hello world");
+        });
+    }
+
+    #[test]
+    fn with_intern() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Intern)]
+            a: String,
+            #[rkyv(with = Intern)]
+            b: String,
+            #[rkyv(with = Map<Intern>)]
+            c: Vec<String>,
+            d: String,
+        }
+
+        let long = "a string which is too long to be stored inline";
+        let value = Test {
+            a: long.to_string(),
+            b: long.to_string(),
+            c: vec![long.to_string(), "short".to_string(), long.to_string()],
+            d: long.to_string(),
+        };
+
+        to_archived(&value, |archived| {
+            let ptr = archived.a.as_ptr();
+            assert_eq!(archived.a.as_str(), long);
+            assert_eq!(archived.b.as_ptr(), ptr);
+            assert_eq!(archived.c[0].as_ptr(), ptr);
+            assert_eq!(archived.c[1].as_str(), "short");
+            assert_eq!(archived.c[2].as_ptr(), ptr);
+            // Fields without `Intern` still get their own copy
+            assert_eq!(archived.d, long);
+            assert_ne!(archived.d.as_ptr(), ptr);
+        });
+        roundtrip_with(&value, |value, archived| {
+            assert_eq!(value.a, archived.a.as_str());
+        });
+    }
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, columns, derive(Debug))]
+    struct Row {
+        id: u32,
+        #[rkyv(with = Intern)]
+        name: String,
+    }
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, columns, derive(Debug))]
+    struct Pair(u8, u16);
+
+    #[test]
+    fn with_as_columns() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Table {
+            #[rkyv(with = AsColumns)]
+            rows: Vec<Row>,
+            #[rkyv(with = AsColumns)]
+            pairs: Vec<Pair>,
         }
 
-        roundtrip(&HasNiche {
-            inner: Some(Box::<[u32]>::from([])),
+        let long = "a string which is too long to be stored inline";
+        let value = Table {
+            rows: (0..10)
+                .map(|id| Row {
+                    id,
+                    name: long.to_string(),
+                })
+                .collect(),
+            pairs: vec![Pair(1, 2), Pair(3, 4)],
+        };
+
+        to_archived(&value, |archived| {
+            assert_eq!(archived.rows.len(), 10);
+            assert!(archived.rows.id.iter().map(|id| id.to_native()).eq(0..10));
+            assert_eq!(archived.rows.name[0].as_str(), long);
+            assert_eq!(
+                archived.rows.name[9].as_ptr(),
+                archived.rows.name[0].as_ptr(),
+            );
+            assert_eq!(archived.pairs.len(), 2);
+            assert_eq!(archived.pairs.0[1], 3);
+            assert_eq!(archived.pairs.1[1], 4);
         });
-        roundtrip(&HasNiche { inner: None });
+        roundtrip_with(&value, |value, archived| {
+            assert_eq!(value.rows.len(), archived.rows.len());
+        });
+        roundtrip_with(
+            &Table {
+                rows: Vec::new(),
+                pairs: Vec::new(),
+            },
+            |_, archived| {
+                assert!(archived.rows.is_empty());
+                assert!(archived.pairs.is_empty());
+            },
+        );
     }
 
     #[test]
-    fn with_as_owned() {
-        #[derive(Archive, Serialize, Deserialize)]
+    fn with_as_columns_generic() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, columns)]
+        struct Generic<'a, T> {
+            value: T,
+            #[rkyv(with = AsOwned)]
+            name: Cow<'a, str>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
         #[rkyv(crate)]
         struct Test<'a> {
-            #[rkyv(with = AsOwned)]
-            a: Cow<'a, u32>,
-            #[rkyv(with = AsOwned)]
-            b: Cow<'a, [u32]>,
-            #[rkyv(with = AsOwned)]
-            c: Cow<'a, str>,
+            #[rkyv(with = AsColumns)]
+            values: Vec<Generic<'a, u64>>,
         }
 
         let value = Test {
-            a: Cow::Borrowed(&100),
-            b: Cow::Borrowed(&[1, 2, 3, 4, 5, 6]),
-            c: Cow::Borrowed("hello world"),
+            values: vec![
+                Generic {
+                    value: 42,
+                    name: Cow::Borrowed("a"),
+                },
+                Generic {
+                    value: 43,
+                    name: Cow::Owned("b".to_string()),
+                },
+            ],
         };
         to_archived(&value, |archived| {
-            assert_eq!(archived.a, 100);
-            assert_eq!(archived.b, [1, 2, 3, 4, 5, 6]);
-            assert_eq!(archived.c, "hello world");
+            assert_eq!(archived.values.value[1], 43);
+            assert_eq!(archived.values.name[0], "a");
+            assert_eq!(archived.values.name[1], "b");
         });
     }
 
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_as_columns_mismatched_lengths() {
+        use rancor::{Error, Failure};
+
+        use crate::api::high::{access, to_bytes};
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Columns(Vec<u8>, Vec<u16>);
+
+        let bytes = to_bytes::<Error>(&Columns(vec![1, 3], vec![2])).unwrap();
+        assert!(access::<ArchivedPairColumns, Failure>(&bytes).is_err());
+
+        let bytes = to_bytes::<Error>(&Columns(vec![1], vec![2])).unwrap();
+        let archived = access::<ArchivedPairColumns, Failure>(&bytes).unwrap();
+        assert_eq!(archived.len(), 1);
+    }
+
     #[test]
     fn with_as_map() {
         #[derive(Archive, Serialize, Deserialize)]
@@ -831,4 +1820,571 @@ mod tests {
             assert_eq!(nichable.boxed.as_ref().to_native(), 727);
         });
     }
+
+    #[test]
+    fn with_niched_string_and_vec() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Niched {
+            #[rkyv(with = DefaultNiche)]
+            name: Option<String>,
+            #[rkyv(with = NicheInto<Null>)]
+            values: Option<Vec<u32>>,
+        }
+
+        assert_eq!(
+            size_of::<ArchivedNiched>(),
+            size_of::<ArchivedString>() + size_of::<ArchivedVec<u32>>(),
+        );
+
+        for name in [None, Some(""), Some("short"), Some("a long string")] {
+            for values in [None, Some(vec![]), Some(vec![1, 2, 3])] {
+                let value = Niched {
+                    name: name.map(String::from),
+                    values,
+                };
+                roundtrip(&value);
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Nichable {
+            #[rkyv(niche)]
+            name: String,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Outer {
+            #[rkyv(with = DefaultNiche)]
+            field: Option<Nichable>,
+        }
+
+        assert_eq!(size_of::<ArchivedNichable>(), size_of::<ArchivedOuter>());
+
+        roundtrip_with(&Outer { field: None }, |_, archived| {
+            assert!(archived.field.is_none());
+        });
+        let value = Outer {
+            field: Some(Nichable {
+                name: String::new(),
+            }),
+        };
+        roundtrip_with(&value, |_, archived| {
+            assert_eq!(archived.field.as_ref().unwrap().name, "");
+        });
+    }
+
+    #[test]
+    fn with_wide() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            narrow: Vec<u32>,
+            #[rkyv(with = Wide)]
+            wide: Vec<String>,
+        }
+
+        let value = Test {
+            narrow: vec![1, 2, 3],
+            wide: vec!["a".to_string(), "b".repeat(100)],
+        };
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            assert_eq!(archived.wide.len(), 2);
+            assert_eq!(archived.wide[1], "b".repeat(100));
+        });
+        assert_eq!(size_of::<ArchivedWideVec<u8>>(), 16);
+    }
+
+    #[test]
+    fn with_as_bit_vec() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsBitVec)]
+            flags: Vec<bool>,
+        }
+
+        let value = Test {
+            flags: (0..21).map(|i| i % 3 == 0).collect(),
+        };
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            let flags = &archived.flags;
+            assert_eq!(flags.len(), 21);
+            assert_eq!(flags.as_bytes().len(), 3);
+            assert_eq!(flags.get(3), Some(true));
+            assert_eq!(flags.get(4), Some(false));
+            assert_eq!(flags.get(21), None);
+            assert_eq!(flags.count_ones(), 7);
+            assert_eq!(flags.count_zeros(), 14);
+            assert_eq!(flags.rank(0), 0);
+            assert_eq!(flags.rank(1), 1);
+            assert_eq!(flags.rank(10), 4);
+            assert_eq!(flags.rank(21), 7);
+            assert_eq!(flags.select(0), Some(0));
+            assert_eq!(flags.select(3), Some(9));
+            assert_eq!(flags.select(6), Some(18));
+            assert_eq!(flags.select(7), None);
+        });
+    }
+
+    #[test]
+    fn with_as_elias_fano() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsEliasFano)]
+            values: Vec<u64>,
+            #[rkyv(with = AsEliasFano)]
+            set: BTreeSet<u64>,
+        }
+
+        let value = Test {
+            values: (0..1000).map(|i| i * i / 3).collect(),
+            set: [0, 1, 2, u64::MAX].into_iter().collect(),
+        };
+        roundtrip_with(&value, |a, b| {
+            assert!(b.values == a.values);
+            assert!(b.set.iter().eq(a.set.iter().copied()));
+        });
+        to_archived(&value, |archived| {
+            let values = &archived.values;
+            assert_eq!(values.len(), 1000);
+            for (i, value) in value.values.iter().copied().enumerate() {
+                assert_eq!(values.select(i), Some(value));
+                assert!(values.contains(value));
+            }
+            assert_eq!(values.select(1000), None);
+            assert_eq!(values.rank(0), 0);
+            assert_eq!(values.rank(4), 4);
+            assert_eq!(values.rank(u64::MAX), 1000);
+            assert!(!values.contains(2));
+
+            let set = &archived.set;
+            assert_eq!(set.first(), Some(0));
+            assert_eq!(set.last(), Some(u64::MAX));
+            assert_eq!(set.rank(u64::MAX), 3);
+            assert!(set.contains(u64::MAX));
+        });
+
+        let unsorted = Test {
+            values: vec![1, 0],
+            set: BTreeSet::new(),
+        };
+        assert!(crate::to_bytes::<rancor::Error>(&unsorted).is_err());
+    }
+
+    #[test]
+    fn with_as_rle() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsRle)]
+            levels: Vec<u16>,
+            #[rkyv(with = AsRle)]
+            labels: Vec<String>,
+        }
+
+        let mut levels = vec![0; 10_000];
+        levels[5000..5010].fill(7);
+        levels.push(1);
+        let value = Test {
+            levels,
+            labels: ["a", "a", "b", "c", "c", "c"]
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        };
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            let levels = &archived.levels;
+            let get = |index| levels.get(index).map(|level| level.to_native());
+            assert_eq!(levels.len(), 10_001);
+            assert_eq!(levels.run_count(), 4);
+            assert_eq!(get(0), Some(0));
+            assert_eq!(get(4999), Some(0));
+            assert_eq!(get(5000), Some(7));
+            assert_eq!(get(5009), Some(7));
+            assert_eq!(get(5010), Some(0));
+            assert_eq!(get(10_000), Some(1));
+            assert_eq!(get(10_001), None);
+            assert!(levels.iter().eq(value.levels.iter()));
+
+            let runs = archived
+                .labels
+                .runs()
+                .map(|(len, value)| (len, value.as_str()))
+                .collect::<Vec<_>>();
+            assert_eq!(runs, [(2, "a"), (1, "b"), (3, "c")]);
+        });
+
+        roundtrip(&Test {
+            levels: Vec::new(),
+            labels: Vec::new(),
+        });
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_as_rle_invalid_runs() {
+        use crate::collections::rle_vec::ArchivedRleVec;
+
+        // A vec of `(end, value)` pairs has the same layout as an archived RLE
+        // vec.
+        let check = |runs: Vec<(usize, u8)>| {
+            let bytes = crate::to_bytes::<rancor::Error>(&runs).unwrap();
+            crate::access::<ArchivedRleVec<u8>, rancor::Failure>(&bytes).is_ok()
+        };
+
+        assert!(check(vec![(2, 1), (5, 2)]));
+        assert!(!check(vec![(0, 1)]));
+        assert!(!check(vec![(3, 1), (3, 2)]));
+        assert!(!check(vec![(3, 1), (2, 2)]));
+    }
+
+    #[test]
+    fn with_inline_capacity() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            default: String,
+            #[rkyv(with = InlineCapacity<24>)]
+            inline: String,
+            #[rkyv(with = InlineCapacity<24>)]
+            out_of_line: String,
+        }
+
+        assert!(size_of::<ArchivedTest>() >= 2 * 24);
+
+        let value = Test {
+            default: "a medium length string".to_string(),
+            inline: "a medium length string".to_string(),
+            out_of_line: "a string which is too long to inline".to_string(),
+        };
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            assert!(!archived.default.is_inline());
+            assert!(archived.inline.is_inline());
+            assert!(!archived.out_of_line.is_inline());
+            assert_eq!(archived.inline, "a medium length string");
+        });
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_borrowed() {
+        use rancor::Failure;
+
+        use crate::{access, api::high::deserialize_borrowed, to_bytes};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test<'a> {
+            #[rkyv(with = Borrowed)]
+            name: Cow<'a, str>,
+            #[rkyv(with = Borrowed)]
+            bytes: Cow<'a, [u8]>,
+        }
+
+        let value = Test {
+            name: Cow::Owned(
+                "a string which is stored out of line".to_string(),
+            ),
+            bytes: Cow::Borrowed(&[1, 2, 3, 4]),
+        };
+        let bytes = to_bytes::<Failure>(&value).unwrap();
+        let archived = access::<ArchivedTest, Failure>(&bytes).unwrap();
+
+        let deserialized =
+            deserialize_borrowed::<Test, Failure>(archived, &bytes).unwrap();
+        let Cow::Borrowed(name) = deserialized.name else {
+            panic!("expected the name to be borrowed");
+        };
+        assert_eq!(name, value.name);
+        assert!(bytes.as_ptr_range().contains(&name.as_ptr()));
+        assert!(matches!(deserialized.bytes, Cow::Borrowed(&[1, 2, 3, 4])));
+
+        // Values can't be borrowed from outside of the given buffer
+        let other = bytes.clone();
+        assert!(
+            deserialize_borrowed::<Test, Failure>(archived, &other).is_err()
+        );
+    }
+
+    #[test]
+    fn with_bounded() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, compare(PartialEq), derive(Debug))]
+        struct Test {
+            #[rkyv(with = Bounded<8>)]
+            name: String,
+            #[rkyv(with = Bounded<4>)]
+            values: Vec<String>,
+        }
+
+        assert!(
+            size_of::<ArchivedTest>() >= 8 + 4 * size_of::<ArchivedString>()
+        );
+
+        let value = Test {
+            name: "abcdefgh".to_string(),
+            values: vec!["a string which is stored out of line".to_string()],
+        };
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            assert_eq!(archived.name.capacity(), 8);
+            assert_eq!(archived.values.len(), 1);
+            assert_eq!(archived.values.capacity(), 4);
+        });
+        roundtrip(&Test {
+            name: String::new(),
+            values: Vec::new(),
+        });
+
+        let too_long = Test {
+            name: "abcdefghi".to_string(),
+            values: Vec::new(),
+        };
+        assert!(crate::to_bytes::<rancor::Failure>(&too_long).is_err());
+        let too_many = Test {
+            name: String::new(),
+            values: vec![String::new(); 5],
+        };
+        assert!(crate::to_bytes::<rancor::Failure>(&too_many).is_err());
+    }
+
+    #[test]
+    fn with_as_interval_map() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsIntervalMap)]
+            intervals: Vec<(Range<u32>, u32)>,
+        }
+
+        let mut intervals = (0..100)
+            .map(|i| {
+                let start = i * 37 % 101;
+                (start..start + i * 13 % 17, i)
+            })
+            .collect::<Vec<_>>();
+        intervals.sort_by_key(|(range, _)| (range.start, range.end));
+        let value = Test { intervals };
+
+        roundtrip_with(&value, |a, b| {
+            let map = &b.intervals;
+            assert_eq!(map.len(), 100);
+
+            for point in 0..120 {
+                let expected = a
+                    .intervals
+                    .iter()
+                    .filter(|(range, _)| range.contains(&point))
+                    .map(|(_, value)| *value)
+                    .collect::<Vec<_>>();
+                let point = Archived::<u32>::from_native(point);
+                let actual = map
+                    .find_overlapping(&point)
+                    .map(|(_, value)| value.to_native())
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected);
+            }
+
+            for start in (0..120).step_by(7) {
+                for end in (0..120).step_by(11) {
+                    let expected = a
+                        .intervals
+                        .iter()
+                        .filter(|(range, _)| {
+                            start < end
+                                && range.start < end
+                                && range.end > start
+                        })
+                        .map(|(_, value)| *value)
+                        .collect::<Vec<_>>();
+                    let start = Archived::<u32>::from_native(start);
+                    let end = Archived::<u32>::from_native(end);
+                    let actual = map
+                        .range(&start, &end)
+                        .map(|(_, value)| value.to_native())
+                        .collect::<Vec<_>>();
+                    assert_eq!(actual, expected);
+                }
+            }
+        });
+
+        roundtrip_with(
+            &Test {
+                intervals: Vec::new(),
+            },
+            |_, b| assert!(b.intervals.is_empty()),
+        );
+
+        let invalid = Test {
+            intervals: vec![(Range { start: 2, end: 1 }, 0)],
+        };
+        assert!(crate::to_bytes::<rancor::Failure>(&invalid).is_err());
+    }
+
+    #[test]
+    fn with_as_trie_map() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsTrieMap)]
+            routes: BTreeMap<String, u32>,
+        }
+
+        let keys = [
+            "",
+            "/",
+            "/api",
+            "/api/",
+            "/api/users",
+            "/api/users/me",
+            "/apple",
+            "/b",
+            "/banana",
+            "/band",
+        ];
+        let value = Test {
+            routes: keys
+                .iter()
+                .enumerate()
+                .map(|(i, key)| (key.to_string(), i as u32))
+                .collect(),
+        };
+
+        roundtrip_with(&value, |a, b| {
+            let map = &b.routes;
+            assert_eq!(map.len(), keys.len());
+            assert!(map.iter().map(|(k, _)| k.as_str()).eq(a.routes.keys()));
+            for (k, v) in a.routes.iter() {
+                assert_eq!(map.get(k), Some(&Archived::<u32>::from_native(*v)));
+                assert_eq!(map[k.as_str()], *v);
+            }
+            for missing in ["/ap", "/api/user", "/bandana", "/c", "x"] {
+                assert!(!map.contains_key(missing));
+            }
+
+            let longest = |key| map.get_prefix(key).map(|(k, _)| k.as_str());
+            assert_eq!(longest("/api/users/42"), Some("/api/users"));
+            assert_eq!(longest("/api/"), Some("/api/"));
+            assert_eq!(longest("/apix"), Some("/api"));
+            assert_eq!(longest("/ap"), Some("/"));
+            assert_eq!(longest("x"), Some(""));
+
+            for prefix in ["", "/", "/a", "/api", "/api/u", "/b", "/ban", "/c"]
+            {
+                let expected = a
+                    .routes
+                    .keys()
+                    .filter(|k| k.starts_with(prefix))
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                let actual = map
+                    .iter_prefix(prefix)
+                    .map(|(k, _)| k.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected);
+            }
+        });
+
+        roundtrip_with(
+            &Test {
+                routes: BTreeMap::new(),
+            },
+            |_, b| {
+                assert!(b.routes.is_empty());
+                assert!(b.routes.get("").is_none());
+                assert!(b.routes.get_prefix("abc").is_none());
+                assert_eq!(b.routes.iter_prefix("").count(), 0);
+            },
+        );
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_bounded_invalid_len() {
+        use crate::{
+            collections::bounded::ArchivedBoundedString,
+            primitive::ArchivedUsize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = Bounded<8>)]
+            name: String,
+        }
+
+        let value = Test {
+            name: "abc".to_string(),
+        };
+        let mut bytes = crate::to_bytes::<rancor::Failure>(&value).unwrap();
+        let result = crate::access::<ArchivedTest, rancor::Failure>(&bytes);
+        assert!(result.is_ok());
+
+        // Overwrite the length with a value greater than the bound.
+        let root = bytes.len() - size_of::<ArchivedBoundedString<8>>();
+        bytes[root..root + size_of::<ArchivedUsize>()].fill(0xff);
+        let result = crate::access::<ArchivedTest, rancor::Failure>(&bytes);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_external_bytes() {
+        use rancor::Failure;
+
+        use crate::{
+            access,
+            api::high::{deserialize_with_blobs, to_bytes_with_blobs},
+            with::ExternalBytes,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = ExternalBytes)]
+            first: Vec<u8>,
+            name: String,
+            #[rkyv(with = ExternalBytes)]
+            empty: Vec<u8>,
+            #[rkyv(with = ExternalBytes)]
+            second: Vec<u8>,
+        }
+
+        let value = Test {
+            first: vec![1; 4096],
+            name: "a string which is stored out of line".to_string(),
+            empty: Vec::new(),
+            second: vec![2; 1024],
+        };
+        let (bytes, blobs) = to_bytes_with_blobs::<Failure>(&value).unwrap();
+        assert!(bytes.len() < 256);
+        assert_eq!(blobs.len(), 4096 + 1024);
+
+        let archived = access::<ArchivedTest, Failure>(&bytes).unwrap();
+        assert_eq!(archived.first.offset(), 0);
+        assert_eq!(archived.second.offset(), 4096);
+        assert_eq!(archived.first.get(&blobs), Some(&*value.first));
+        assert_eq!(archived.second.get(&blobs), Some(&*value.second));
+        assert!(archived.empty.is_empty());
+
+        let deserialized =
+            deserialize_with_blobs::<Test, Failure>(archived, &blobs).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Blobs outside of the blob region can't be read
+        assert_eq!(archived.second.get(&blobs[..4096]), None);
+        assert!(deserialize_with_blobs::<Test, Failure>(
+            archived,
+            &blobs[..4096]
+        )
+        .is_err());
+    }
 }
@@ -93,10 +93,7 @@ where
         // SAFETY: The caller has guaranteed that `out` is non-null, properly
         // aligned, valid for writes, and allocated according to the layout of
         // the deserialized metadata (the unit type for sized types).
-        unsafe {
-            out.write(self.deserialize(deserializer)?);
-        }
-        Ok(())
+        unsafe { self.deserialize_into(deserializer, out) }
     }
 
     fn deserialize_metadata(&self) -> <T as Pointee>::Metadata {}
@@ -246,13 +243,35 @@ where
 {
     fn deserialize(&self, deserializer: &mut D) -> Result<[T; N], D::Error> {
         let mut result = core::mem::MaybeUninit::<[T; N]>::uninit();
-        let result_ptr = result.as_mut_ptr().cast::<T>();
+        // SAFETY: `result` is non-null, properly aligned, and valid for writes,
+        // and `deserialize_into` initializes it when it returns `Ok`.
+        unsafe {
+            self.deserialize_into(deserializer, result.as_mut_ptr())?;
+            Ok(result.assume_init())
+        }
+    }
+
+    unsafe fn deserialize_into(
+        &self,
+        deserializer: &mut D,
+        out: *mut [T; N],
+    ) -> Result<(), D::Error> {
+        let out = out.cast::<T>();
         for (i, value) in self.iter().enumerate() {
-            unsafe {
-                result_ptr.add(i).write(value.deserialize(deserializer)?);
+            // SAFETY: The caller has guaranteed that `out` is valid for writes
+            // of a `[T; N]`, and `i` is less than `N`.
+            let result =
+                unsafe { value.deserialize_into(deserializer, out.add(i)) };
+            if let Err(error) = result {
+                // SAFETY: The first `i` elements of `out` were initialized by
+                // the previous iterations.
+                unsafe {
+                    ptr::drop_in_place(ptr::slice_from_raw_parts_mut(out, i));
+                }
+                return Err(error);
             }
         }
-        unsafe { Ok(result.assume_init()) }
+        Ok(())
     }
 }
 
@@ -337,20 +356,11 @@ where
         deserializer: &mut D,
         out: *mut [U],
     ) -> Result<(), D::Error> {
-        for (i, item) in self.iter().enumerate() {
-            // SAFETY: The caller has guaranteed that `out` points to a slice
-            // with a length guaranteed to match the length of `self`. Since `i`
-            // is less than the length of the slice, the result of the pointer
-            // add is always in-bounds.
-            let out_ptr = unsafe { out.cast::<U>().add(i) };
-            // SAFETY: `out_ptr` points to an element of `out` and so is
-            // guaranteed to be non-null, properly aligned, and valid for
-            // writes.
-            unsafe {
-                out_ptr.write(item.deserialize(deserializer)?);
-            }
-        }
-        Ok(())
+        // SAFETY: The caller has guaranteed that `out` points to a slice with
+        // a length guaranteed to match the length of `self`, so it is
+        // non-null, properly aligned, and valid for writes of `self.len()`
+        // elements.
+        unsafe { T::deserialize_slice(self, deserializer, out.cast::<U>()) }
     }
 
     fn deserialize_metadata(&self) -> <[U] as Pointee>::Metadata {
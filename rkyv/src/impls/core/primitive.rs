@@ -1,6 +1,11 @@
-use core::num::{
-    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize,
-    NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+use core::{
+    mem::size_of,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
+        NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8, NonZeroUsize,
+    },
+    ptr,
 };
 
 use rancor::Fallible;
@@ -14,6 +19,7 @@ use crate::{
         ArchivedNonZeroU32, ArchivedNonZeroU64, ArchivedNonZeroUsize,
         ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64, ArchivedUsize,
     },
+    simd,
     traits::{CopyOptimization, NoUndef},
     Archive, Deserialize, Place, Portable, Serialize,
 };
@@ -199,6 +205,37 @@ macro_rules! impl_multibyte_primitive {
             fn deserialize(&self, _: &mut D) -> Result<$type, D::Error> {
                 Ok(self.to_native())
             }
+
+            #[inline]
+            unsafe fn deserialize_slice(
+                values: &[Self],
+                _: &mut D,
+                out: *mut $type,
+            ) -> Result<(), D::Error> {
+                let src = values.as_ptr().cast::<u8>();
+                let dst = out.cast::<u8>();
+                // SAFETY: The caller has guaranteed that `out` is valid for
+                // writes of `values.len()` elements, and archived primitives
+                // have the same size as their native counterparts. A valid
+                // archived primitive holds the bytes of a valid native
+                // primitive, in reverse order if the endianness differs.
+                unsafe {
+                    if MULTIBYTE_PRIMITIVES_ARE_TRIVIALLY_COPYABLE {
+                        ptr::copy_nonoverlapping(
+                            src,
+                            dst,
+                            values.len() * size_of::<$type>(),
+                        );
+                    } else {
+                        simd::swap_bytes::<{ size_of::<$type>() }>(
+                            src,
+                            dst,
+                            values.len(),
+                        );
+                    }
+                }
+                Ok(())
+            }
         }
     };
 }
@@ -20,10 +20,11 @@ use core::{
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
+    de::{Borrowing, BorrowingExt as _},
     niche::{
         niched_option::NichedOption,
         niching::{DefaultNiche, Niching},
@@ -38,10 +39,15 @@ use crate::{
     },
     option::ArchivedOption,
     primitive::{FixedNonZeroIsize, FixedNonZeroUsize},
+    ser::{Allocator, Writer},
+    string::{ArchivedString, StringResolver},
     traits::NoUndef,
+    varint::{ArchivedVarint, VarintResolver, VarintValue},
+    vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsBox, DeserializeWith, Identity, Inline, InlineAsBox,
-        Map, MapNiche, Niche, NicheInto, SerializeWith, Skip, Unsafe,
+        ArchiveWith, AsBox, Borrowed, DeserializeWith, Identity, Inline,
+        InlineAsBox, Map, MapNiche, Niche, NicheInto, SerializeWith, Skip,
+        Unsafe, Varint,
     },
     Archive, ArchiveUnsized, Deserialize, Place, Serialize, SerializeUnsized,
 };
@@ -572,6 +578,88 @@ where
     }
 }
 
+// Borrowed
+
+impl<'a> ArchiveWith<&'a str> for Borrowed {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve_with(
+        field: &&'a str,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedString::resolve_from_str(field, resolver, out);
+    }
+}
+
+impl<'a, S> SerializeWith<&'a str, S> for Borrowed
+where
+    S: Fallible + ?Sized,
+    S::Error: Source,
+    str: SerializeUnsized<S>,
+{
+    fn serialize_with(
+        field: &&'a str,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(field, serializer)
+    }
+}
+
+impl<'a, D> DeserializeWith<ArchivedString, &'a str, D> for Borrowed
+where
+    D: Fallible + Borrowing<'a, D::Error> + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedString,
+        deserializer: &mut D,
+    ) -> Result<&'a str, D::Error> {
+        deserializer.borrow_archived(field.as_str())
+    }
+}
+
+impl<'a, T: Archive> ArchiveWith<&'a [T]> for Borrowed {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &&'a [T],
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVec::resolve_from_slice(field, resolver, out);
+    }
+}
+
+impl<'a, T, S> SerializeWith<&'a [T], S> for Borrowed
+where
+    T: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &&'a [T],
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_slice(field, serializer)
+    }
+}
+
+impl<'a, T, D> DeserializeWith<ArchivedVec<T>, &'a [T], D> for Borrowed
+where
+    T: Archive<Archived = T>,
+    D: Fallible + Borrowing<'a, D::Error> + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<T>,
+        deserializer: &mut D,
+    ) -> Result<&'a [T], D::Error> {
+        deserializer.borrow_archived(field.as_slice())
+    }
+}
+
 // Inline
 
 impl<F: Archive> ArchiveWith<&F> for Inline {
@@ -738,6 +826,47 @@ where
     }
 }
 
+// Varint
+
+impl<F: VarintValue> ArchiveWith<F> for Varint {
+    type Archived = ArchivedVarint<F>;
+    type Resolver = VarintResolver;
+
+    fn resolve_with(
+        _: &F,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedVarint::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<F, S> SerializeWith<F, S> for Varint
+where
+    F: VarintValue,
+    S: Fallible + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &F,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedVarint::serialize_from_value(*field, serializer)
+    }
+}
+
+impl<F, D> DeserializeWith<ArchivedVarint<F>, F, D> for Varint
+where
+    F: VarintValue,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedVarint<F>,
+        _: &mut D,
+    ) -> Result<F, D::Error> {
+        Ok(field.get())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::f32;
@@ -749,7 +878,7 @@ mod tests {
         ser::Writer,
         with::{
             ArchiveWith, AsBox, DeserializeWith, Identity, Inline, InlineAsBox,
-            Niche, NicheInto, SerializeWith, Unsafe, With,
+            Niche, NicheInto, SerializeWith, Unsafe, Varint, With,
         },
         Archive, Archived, Deserialize, Place, Serialize,
     };
@@ -1156,4 +1285,29 @@ mod tests {
             assert_eq!(archived.other, 10);
         });
     }
+
+    #[test]
+    fn with_varint() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = Varint)]
+            small: u64,
+            #[rkyv(with = Varint)]
+            large: u64,
+            #[rkyv(with = Varint)]
+            count: u32,
+        }
+
+        let value = Test {
+            small: 5,
+            large: u64::MAX,
+            count: 300,
+        };
+        roundtrip_with(&value, |a, b| {
+            assert_eq!(b.small, a.small);
+            assert_eq!(b.large, a.large);
+            assert_eq!(b.count.get(), a.count);
+        });
+    }
 }
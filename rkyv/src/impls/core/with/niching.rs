@@ -15,7 +15,10 @@ use crate::{
         ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
         ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
     },
+    rel_ptr::{Offset, RawRelPtr},
+    string::{repr::ArchivedStringRepr, ArchivedString},
     traits::ArchivePointee,
+    vec::ArchivedVec,
     Place, Portable, RelPtr,
 };
 
@@ -127,6 +130,48 @@ where
     }
 }
 
+impl<T, O: Offset, L: Offset> Niching<ArchivedVec<T, O, L>> for Null {
+    unsafe fn is_niched(niched: *const ArchivedVec<T, O, L>) -> bool {
+        unsafe { (*niched.cast::<RawRelPtr<O>>()).is_invalid() }
+    }
+
+    fn resolve_niched(out: Place<ArchivedVec<T, O, L>>) {
+        let out = unsafe { out.cast_unchecked::<RawRelPtr<O>>() };
+        RawRelPtr::emplace_invalid(out);
+    }
+}
+
+impl<T, O: Offset, L: Offset> Niching<ArchivedVec<T, O, L>> for DefaultNiche {
+    unsafe fn is_niched(niched: *const ArchivedVec<T, O, L>) -> bool {
+        unsafe { <Null as Niching<ArchivedVec<T, O, L>>>::is_niched(niched) }
+    }
+
+    fn resolve_niched(out: Place<ArchivedVec<T, O, L>>) {
+        <Null as Niching<ArchivedVec<T, O, L>>>::resolve_niched(out);
+    }
+}
+
+impl<const N: usize> Niching<ArchivedString<N>> for Null {
+    unsafe fn is_niched(niched: *const ArchivedString<N>) -> bool {
+        unsafe { (*niched.cast::<ArchivedStringRepr<N>>()).is_invalid() }
+    }
+
+    fn resolve_niched(out: Place<ArchivedString<N>>) {
+        let out = unsafe { out.cast_unchecked::<ArchivedStringRepr<N>>() };
+        ArchivedStringRepr::emplace_invalid(out);
+    }
+}
+
+impl<const N: usize> Niching<ArchivedString<N>> for DefaultNiche {
+    unsafe fn is_niched(niched: *const ArchivedString<N>) -> bool {
+        unsafe { <Null as Niching<ArchivedString<N>>>::is_niched(niched) }
+    }
+
+    fn resolve_niched(out: Place<ArchivedString<N>>) {
+        <Null as Niching<ArchivedString<N>>>::resolve_niched(out);
+    }
+}
+
 // SharedNiching
 
 impl<T, N1, N2> Niching<NichedOption<T, N1>> for N2
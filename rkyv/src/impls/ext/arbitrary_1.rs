@@ -0,0 +1,89 @@
+use core::{
+    mem::MaybeUninit,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6},
+    time::Duration,
+};
+
+use arbitrary_1::{Arbitrary, Result, Unstructured};
+
+use crate::{
+    fixed_int::{ArchivedFixedInt, ArchivedFixedUint},
+    net::{
+        ArchivedIpv4Addr, ArchivedIpv6Addr, ArchivedSocketAddrV4,
+        ArchivedSocketAddrV6,
+    },
+    time::ArchivedDuration,
+    Archive, Place,
+};
+
+/// Archives a value which doesn't have any out-of-line data.
+fn archive_inline<T: Archive<Resolver = ()>>(value: &T) -> T::Archived {
+    let mut out = MaybeUninit::<T::Archived>::zeroed();
+    // SAFETY: `out` is properly aligned, dereferenceable, and all of its bytes
+    // are initialized because it was zeroed.
+    let place = unsafe { Place::new_unchecked(0, out.as_mut_ptr()) };
+    value.resolve((), place);
+    // SAFETY: `resolve` initialized the archived value.
+    unsafe { out.assume_init() }
+}
+
+macro_rules! impl_arbitrary_inline {
+    ($($archived:ty: $native:ty),* $(,)?) => {
+        $(
+            impl<'a> Arbitrary<'a> for $archived {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Ok(archive_inline(&<$native>::arbitrary(u)?))
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <$native>::size_hint(depth)
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_inline! {
+    ArchivedDuration: Duration,
+    ArchivedIpv4Addr: Ipv4Addr,
+    ArchivedIpv6Addr: Ipv6Addr,
+    ArchivedSocketAddrV4: SocketAddrV4,
+    ArchivedSocketAddrV6: SocketAddrV6,
+}
+
+macro_rules! impl_arbitrary_fixed {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl<'a, const N: usize> Arbitrary<'a> for $ty<N> {
+                fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+                    Ok(Self::from_words(<[u64; N]>::arbitrary(u)?))
+                }
+
+                fn size_hint(depth: usize) -> (usize, Option<usize>) {
+                    <[u64; N]>::size_hint(depth)
+                }
+            }
+        )*
+    };
+}
+
+impl_arbitrary_fixed!(ArchivedFixedUint, ArchivedFixedInt);
+
+#[cfg(test)]
+mod tests {
+    use arbitrary_1::{Arbitrary, Unstructured};
+
+    use crate::{fixed_int::ArchivedU256, time::ArchivedDuration};
+
+    #[test]
+    fn arbitrary_archived() {
+        let data = (0..=255).collect::<crate::alloc::vec::Vec<u8>>();
+        let mut u = Unstructured::new(&data);
+
+        let duration = ArchivedDuration::arbitrary(&mut u).unwrap();
+        assert!(duration.subsec_nanos() < 1_000_000_000);
+
+        let int = ArchivedU256::arbitrary(&mut u).unwrap();
+        assert_eq!(ArchivedU256::from_words(int.to_words()), int);
+    }
+}
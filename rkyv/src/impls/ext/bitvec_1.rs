@@ -0,0 +1,72 @@
+use bitvec_1::{order::BitOrder, store::BitStore, vec::BitVec};
+use rancor::Fallible;
+
+use crate::{
+    collections::bitvec::{ArchivedBitVec, BitVecResolver},
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+impl<T: BitStore, O: BitOrder> Archive for BitVec<T, O> {
+    type Archived = ArchivedBitVec;
+    type Resolver = BitVecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedBitVec::resolve_from_len(self.len(), resolver, out);
+    }
+}
+
+impl<T, O, S> Serialize<S> for BitVec<T, O>
+where
+    T: BitStore,
+    O: BitOrder,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBitVec::serialize_from_iter(self.iter().by_vals(), serializer)
+    }
+}
+
+impl<T, O, D> Deserialize<BitVec<T, O>, D> for ArchivedBitVec
+where
+    T: BitStore,
+    O: BitOrder,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, _: &mut D) -> Result<BitVec<T, O>, D::Error> {
+        let mut result = BitVec::with_capacity(self.len());
+        result.extend(self.iter());
+        Ok(result)
+    }
+}
+
+impl<T: BitStore, O: BitOrder> PartialEq<BitVec<T, O>> for ArchivedBitVec {
+    fn eq(&self, other: &BitVec<T, O>) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter().by_vals())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec_1::{bitvec, order::Msb0, vec::BitVec};
+
+    use crate::api::test::{roundtrip, to_archived};
+
+    #[test]
+    fn roundtrip_bit_vec() {
+        let value: BitVec<u32, Msb0> = bitvec![u32, Msb0; 1, 0, 1, 1, 0, 0, 1];
+        roundtrip(&value);
+
+        let long = (0..100).map(|i| i % 7 == 0).collect::<BitVec>();
+        roundtrip(&long);
+        to_archived(&long, |archived| {
+            assert_eq!(archived.len(), 100);
+            assert_eq!(archived.count_ones(), long.count_ones());
+            assert_eq!(archived.select(10), Some(70));
+            assert_eq!(archived.rank(71), 11);
+        });
+    }
+}
@@ -1,9 +1,15 @@
 use bytes_1::{Bytes, BytesMut};
-use rancor::Fallible;
+use rancor::{Fallible, Source};
 
 use crate::{
-    ser::{Allocator, Writer},
+    blob::{ArchivedBlob, BlobResolver},
+    de::{BlobSource, BlobSourceExt as _, SlicingBytes, SlicingBytesExt as _},
+    ser::{Allocator, Blobs, Writer},
     vec::{ArchivedVec, VecResolver},
+    with::{
+        ArchiveWith, AsOwnedBytes, DeserializeWith, ExternalBytes,
+        SerializeWith,
+    },
     Archive, Archived, Deserialize, Place, Serialize,
 };
 
@@ -43,6 +49,87 @@ where
     }
 }
 
+// AsOwnedBytes
+
+impl ArchiveWith<Bytes> for AsOwnedBytes {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = VecResolver;
+
+    fn resolve_with(
+        field: &Bytes,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        field.resolve(resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Bytes, S> for AsOwnedBytes
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize_with(
+        field: &Bytes,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        field.serialize(serializer)
+    }
+}
+
+impl<D> DeserializeWith<ArchivedVec<u8>, Bytes, D> for AsOwnedBytes
+where
+    D: Fallible + SlicingBytes<D::Error> + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<u8>,
+        deserializer: &mut D,
+    ) -> Result<Bytes, D::Error> {
+        deserializer.slice_bytes(field.as_slice())
+    }
+}
+
+// ExternalBytes
+
+impl ArchiveWith<Bytes> for ExternalBytes {
+    type Archived = ArchivedBlob;
+    type Resolver = BlobResolver;
+
+    fn resolve_with(
+        _: &Bytes,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedBlob::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Bytes, S> for ExternalBytes
+where
+    S: Fallible + Blobs + ?Sized,
+{
+    fn serialize_with(
+        field: &Bytes,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let offset = serializer.write_blob(field)?;
+        Ok(BlobResolver::new(offset, field.len()))
+    }
+}
+
+impl<D> DeserializeWith<ArchivedBlob, Bytes, D> for ExternalBytes
+where
+    D: Fallible + BlobSource + ?Sized,
+    D::Error: Source,
+{
+    fn deserialize_with(
+        field: &ArchivedBlob,
+        deserializer: &mut D,
+    ) -> Result<Bytes, D::Error> {
+        Ok(Bytes::copy_from_slice(deserializer.get_blob(field)?))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Bytes;
@@ -52,4 +139,36 @@ mod tests {
     fn roundtrip_bytes() {
         roundtrip(&Bytes::from(vec![10, 20, 40, 80]));
     }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn with_as_owned_bytes() {
+        use rancor::Failure;
+
+        use crate::{
+            api::high::{from_shared_bytes, to_bytes},
+            with::AsOwnedBytes,
+            Archive, Deserialize, Serialize,
+        };
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[rkyv(crate)]
+        struct Test {
+            #[rkyv(with = AsOwnedBytes)]
+            body: Bytes,
+            #[rkyv(with = AsOwnedBytes)]
+            empty: Bytes,
+        }
+
+        let value = Test {
+            body: Bytes::from(vec![1; 64]),
+            empty: Bytes::new(),
+        };
+        let bytes = Bytes::from_owner(to_bytes::<Failure>(&value).unwrap());
+
+        let deserialized = from_shared_bytes::<Test, Failure>(&bytes).unwrap();
+        assert_eq!(deserialized.body, value.body);
+        assert!(deserialized.empty.is_empty());
+        assert!(bytes.as_ptr_range().contains(&deserialized.body.as_ptr()));
+    }
 }
@@ -0,0 +1,101 @@
+use ethnum_1::{I256, U256};
+use rancor::Fallible;
+
+use crate::{
+    fixed_int::{ArchivedI256, ArchivedU256},
+    Archive, Deserialize, Place, Serialize,
+};
+
+fn u256_to_words(value: U256) -> [u64; 4] {
+    let (hi, lo) = value.into_words();
+    [lo as u64, (lo >> 64) as u64, hi as u64, (hi >> 64) as u64]
+}
+
+fn u256_from_words(words: [u64; 4]) -> U256 {
+    let lo = words[0] as u128 | (words[1] as u128) << 64;
+    let hi = words[2] as u128 | (words[3] as u128) << 64;
+    U256::from_words(hi, lo)
+}
+
+impl Archive for U256 {
+    type Archived = ArchivedU256;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        out.write(ArchivedU256::from_words(u256_to_words(*self)));
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for U256 {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<U256, D> for ArchivedU256 {
+    fn deserialize(&self, _: &mut D) -> Result<U256, D::Error> {
+        Ok(u256_from_words(self.to_words()))
+    }
+}
+
+impl PartialEq<U256> for ArchivedU256 {
+    fn eq(&self, other: &U256) -> bool {
+        self.to_words() == u256_to_words(*other)
+    }
+}
+
+impl Archive for I256 {
+    type Archived = ArchivedI256;
+    type Resolver = ();
+
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        out.write(ArchivedI256::from_words(u256_to_words(self.as_u256())));
+    }
+}
+
+impl<S: Fallible + ?Sized> Serialize<S> for I256 {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<I256, D> for ArchivedI256 {
+    fn deserialize(&self, _: &mut D) -> Result<I256, D::Error> {
+        Ok(u256_from_words(self.to_words()).as_i256())
+    }
+}
+
+impl PartialEq<I256> for ArchivedI256 {
+    fn eq(&self, other: &I256) -> bool {
+        self.to_words() == u256_to_words(other.as_u256())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{I256, U256};
+    use crate::{
+        alloc::string::ToString,
+        api::test::{roundtrip, roundtrip_with},
+    };
+
+    #[test]
+    fn roundtrip_u256() {
+        roundtrip(&U256::ZERO);
+        roundtrip(&U256::MAX);
+        roundtrip_with(&U256::from_words(1, 2), |a, b| {
+            assert_eq!(b.to_words(), [2, 0, 1, 0]);
+            assert_eq!(b.to_string(), a.to_string());
+        });
+    }
+
+    #[test]
+    fn roundtrip_i256() {
+        roundtrip(&I256::MIN);
+        roundtrip(&I256::MAX);
+        roundtrip_with(&I256::new(-42), |a, b| {
+            assert!(b.is_negative());
+            assert_eq!(b.to_string(), a.to_string());
+        });
+    }
+}
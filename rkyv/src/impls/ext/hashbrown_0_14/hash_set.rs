@@ -1,7 +1,4 @@
-use core::{
-    borrow::Borrow,
-    hash::{BuildHasher, Hash},
-};
+use core::hash::{BuildHasher, Hash};
 
 use hashbrown_0_14::HashSet;
 use rancor::{Fallible, Source};
@@ -68,20 +65,28 @@ where
     }
 }
 
-impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
-    PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
+impl<K, AK, S> PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
+where
+    K: Hash + Eq,
+    AK: PartialEq<K>,
+    S: BuildHasher,
 {
     fn eq(&self, other: &HashSet<K, S>) -> bool {
         if self.len() != other.len() {
             false
         } else {
-            self.iter().all(|key| other.get(key).is_some())
+            other
+                .iter()
+                .all(|key| self.contains_with(key, |k, ak| ak == k))
         }
     }
 }
 
-impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
-    PartialEq<ArchivedHashSet<AK>> for HashSet<K, S>
+impl<K, AK, S> PartialEq<ArchivedHashSet<AK>> for HashSet<K, S>
+where
+    K: Hash + Eq,
+    AK: PartialEq<K>,
+    S: BuildHasher,
 {
     fn eq(&self, other: &ArchivedHashSet<AK>) -> bool {
         other.eq(self)
@@ -1,7 +1,4 @@
-use core::{
-    borrow::Borrow,
-    hash::{BuildHasher, Hash},
-};
+use core::hash::{BuildHasher, Hash};
 
 use hashbrown::HashMap;
 use rancor::{Fallible, Source};
@@ -76,8 +73,8 @@ where
 
 impl<K, V, AK, AV, S> PartialEq<HashMap<K, V, S>> for ArchivedHashMap<AK, AV>
 where
-    K: Hash + Eq + Borrow<AK>,
-    AK: Hash + Eq,
+    K: Hash + Eq,
+    AK: PartialEq<K>,
     AV: PartialEq<V>,
     S: BuildHasher,
 {
@@ -85,13 +82,28 @@ where
         if self.len() != other.len() {
             false
         } else {
-            self.iter().all(|(key, value)| {
-                other.get(key).map_or(false, |v| value.eq(v))
+            // Archived hash maps are indexed by the hashes of the original
+            // keys, so the original keys can be used to look up entries.
+            other.iter().all(|(key, value)| {
+                self.get_with(key, |k, ak| ak == k)
+                    .is_some_and(|v| v == value)
             })
         }
     }
 }
 
+impl<K, V, AK, AV, S> PartialEq<ArchivedHashMap<AK, AV>> for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    AK: PartialEq<K>,
+    AV: PartialEq<V>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &ArchivedHashMap<AK, AV>) -> bool {
+        other.eq(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::hash::BuildHasherDefault;
@@ -73,9 +73,22 @@ where
     S: BuildHasher,
 {
     fn eq(&self, other: &IndexMap<UK, UV, S>) -> bool {
-        self.iter()
-            .zip(other.iter())
-            .all(|((ak, av), (bk, bv))| ak == bk && av == bv)
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((ak, av), (bk, bv))| ak == bk && av == bv)
+    }
+}
+
+impl<UK, K, UV, V, S> PartialEq<ArchivedIndexMap<K, V>> for IndexMap<UK, UV, S>
+where
+    K: PartialEq<UK>,
+    V: PartialEq<UV>,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &ArchivedIndexMap<K, V>) -> bool {
+        other.eq(self)
     }
 }
 
@@ -64,6 +64,14 @@ impl<UK, K: PartialEq<UK>, S: BuildHasher> PartialEq<IndexSet<UK, S>>
     }
 }
 
+impl<UK, K: PartialEq<UK>, S: BuildHasher> PartialEq<ArchivedIndexSet<K>>
+    for IndexSet<UK, S>
+{
+    fn eq(&self, other: &ArchivedIndexSet<K>) -> bool {
+        other.eq(self)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::hash::BuildHasherDefault;
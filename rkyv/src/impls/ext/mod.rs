@@ -6,16 +6,32 @@
 // over time. Before adding support for another crate, please consider getting
 // rkyv support in the crate instead.
 
+#[cfg(feature = "arbitrary-1")]
+mod arbitrary_1;
 #[cfg(feature = "arrayvec-0_7")]
 mod arrayvec_0_7;
+#[cfg(feature = "bitvec-1")]
+mod bitvec_1;
 #[cfg(feature = "bytes-1")]
 mod bytes_1;
+#[cfg(feature = "ethnum-1")]
+mod ethnum_1;
 #[cfg(feature = "hashbrown-0_14")]
 mod hashbrown_0_14;
 #[cfg(feature = "hashbrown-0_15")]
 mod hashbrown_0_15;
 #[cfg(feature = "indexmap-2")]
 mod indexmap_2;
+#[cfg(feature = "ndarray-0_15")]
+mod ndarray_0_15;
+#[cfg(feature = "ndarray-0_16")]
+mod ndarray_0_16;
+#[cfg(feature = "num-bigint-0_4")]
+mod num_bigint_0_4;
+#[cfg(feature = "primitive-types-0_13")]
+mod primitive_types_0_13;
+#[cfg(feature = "serde_json-1")]
+mod serde_json_1;
 #[cfg(feature = "smallvec-1")]
 mod smallvec_1;
 #[cfg(feature = "smol_str-0_2")]
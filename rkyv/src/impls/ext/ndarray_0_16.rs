@@ -0,0 +1,235 @@
+use core::{error::Error, fmt};
+
+use ndarray_0_16::{
+    Array, ArrayBase, ArrayViewD, Data, Dimension, IxDyn, ShapeBuilder,
+};
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    alloc::vec::Vec,
+    collections::ndarray::{
+        ArchivedArrayD, ArrayDResolver, InvalidArrayLayout,
+    },
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+impl<T> ArchivedArrayD<T> {
+    /// Returns an `ndarray` view of the archived array.
+    ///
+    /// With `ndarray` 0.15, use `ArrayViewD::from` instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the shape and strides of the array are invalid. This can only
+    /// happen if the archive was accessed without validation.
+    pub fn as_array_view(&self) -> ArrayViewD<'_, T> {
+        ArrayViewD::from(self)
+    }
+}
+
+impl<'a, T> From<&'a ArchivedArrayD<T>> for ArrayViewD<'a, T> {
+    fn from(array: &'a ArchivedArrayD<T>) -> Self {
+        let shape = array
+            .shape()
+            .iter()
+            .map(|len| len.to_native() as usize)
+            .collect::<Vec<_>>();
+        let strides = array
+            .strides()
+            .iter()
+            .map(|stride| stride.to_native() as usize)
+            .collect::<Vec<_>>();
+        ArrayViewD::from_shape(
+            IxDyn(&shape).strides(IxDyn(&strides)),
+            array.as_slice_memory_order(),
+        )
+        .expect("archived array has an invalid shape and strides")
+    }
+}
+
+impl<A, S, D> Archive for ArrayBase<S, D>
+where
+    A: Archive,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    type Archived = ArchivedArrayD<A::Archived>;
+    type Resolver = ArrayDResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedArrayD::resolve_from_len(
+            self.ndim(),
+            self.len(),
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<A, S, D, Se> Serialize<Se> for ArrayBase<S, D>
+where
+    A: Serialize<Se>,
+    S: Data<Elem = A>,
+    D: Dimension,
+    Se: Fallible + Allocator + Writer + ?Sized,
+    Se::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut Se,
+    ) -> Result<Self::Resolver, Se::Error> {
+        // Contiguous arrays are archived in memory order so that their
+        // elements can be copied in bulk.
+        match self.as_slice_memory_order() {
+            Some(data)
+                if !data.is_empty()
+                    && self.strides().iter().all(|&stride| stride >= 0) =>
+            {
+                ArchivedArrayD::serialize_from_slice(
+                    self.shape(),
+                    self.strides(),
+                    data,
+                    serializer,
+                )
+            }
+            _ => ArchivedArrayD::serialize_from_iter::<A, _, _>(
+                self.shape(),
+                self.iter(),
+                serializer,
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DimensionMismatch {
+    expected: usize,
+    actual: usize,
+}
+
+impl fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected an array with {} dimensions but the archived array has \
+             {}",
+            self.expected, self.actual,
+        )
+    }
+}
+
+impl Error for DimensionMismatch {}
+
+impl<A, D, De> Deserialize<Array<A, D>, De> for ArchivedArrayD<A::Archived>
+where
+    A: Archive,
+    A::Archived: Deserialize<A, De>,
+    D: Dimension,
+    De: Fallible + ?Sized,
+    De::Error: Source,
+{
+    fn deserialize(
+        &self,
+        deserializer: &mut De,
+    ) -> Result<Array<A, D>, De::Error> {
+        if let Some(expected) = D::NDIM {
+            if expected != self.ndim() {
+                fail!(DimensionMismatch {
+                    expected,
+                    actual: self.ndim(),
+                });
+            }
+        }
+
+        let shape = self
+            .shape()
+            .iter()
+            .map(|len| len.to_native() as usize)
+            .collect::<Vec<_>>();
+        let strides = self
+            .strides()
+            .iter()
+            .map(|stride| stride.to_native() as usize)
+            .collect::<Vec<_>>();
+        let mut data = Vec::with_capacity(self.len());
+        for value in self.as_slice_memory_order() {
+            data.push(value.deserialize(deserializer)?);
+        }
+
+        let Ok(array) =
+            Array::from_shape_vec(IxDyn(&shape).strides(IxDyn(&strides)), data)
+        else {
+            fail!(InvalidArrayLayout);
+        };
+        match array.into_dimensionality::<D>() {
+            Ok(array) => Ok(array),
+            Err(_) => fail!(InvalidArrayLayout),
+        }
+    }
+}
+
+impl<T, A, S, D> PartialEq<ArrayBase<S, D>> for ArchivedArrayD<T>
+where
+    T: PartialEq<A>,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    fn eq(&self, other: &ArrayBase<S, D>) -> bool {
+        let view = self.as_array_view();
+        view.shape() == other.shape()
+            && view.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray_0_16::{s, Array, Array2, Array3, ShapeBuilder};
+
+    use crate::{
+        alloc::vec::Vec,
+        api::test::{deserialize, roundtrip, to_archived},
+    };
+
+    #[test]
+    fn roundtrip_standard_array() {
+        let value = Array::from_shape_vec((2, 3), (0..6).collect::<Vec<u32>>())
+            .unwrap();
+        roundtrip(&value);
+        to_archived(&value, |archived| {
+            assert_eq!(archived.ndim(), 2);
+            assert!(archived.is_standard_layout());
+            assert_eq!(archived.get(&[1, 2]).map(|x| x.to_native()), Some(5));
+            assert_eq!(archived.get(&[2, 0]), None);
+
+            let view = archived.as_array_view();
+            assert_eq!(view.shape(), &[2, 3]);
+            assert_eq!(view[[1, 0]], 3);
+        });
+    }
+
+    #[test]
+    fn roundtrip_non_standard_array() {
+        let fortran = Array::from_shape_vec(
+            (2, 3, 4).f(),
+            (0..24).map(|i| i as f32).collect::<Vec<_>>(),
+        )
+        .unwrap();
+        roundtrip(&fortran);
+        to_archived(&fortran, |archived| {
+            assert!(!archived.is_standard_layout());
+            assert_eq!(archived.get(&[1, 2, 3]).map(|x| x.to_native()), {
+                Some(fortran[[1, 2, 3]])
+            });
+        });
+
+        let strided = fortran.slice(s![.., ..;-1, 1..]);
+        to_archived(&strided, |archived| {
+            assert!(archived.is_standard_layout());
+            assert_eq!(*archived, strided);
+            assert_eq!(deserialize::<Array3<f32>>(&*archived), strided);
+        });
+
+        roundtrip(&Array2::<u8>::zeros((0, 3)));
+    }
+}
@@ -0,0 +1,136 @@
+use num_bigint_0_4::{BigInt, BigUint, Sign};
+use rancor::Fallible;
+
+use crate::{
+    alloc::vec::Vec,
+    big_int::{
+        ArchivedBigInt, ArchivedBigUint, ArchivedSign, BigIntResolver,
+        BigUintResolver,
+    },
+    ser::{Allocator, Writer},
+    Archive, Deserialize, Place, Serialize,
+};
+
+fn biguint_from_archived(archived: &ArchivedBigUint) -> BigUint {
+    let mut digits = Vec::with_capacity(archived.limbs().len() * 2);
+    for limb in archived.limbs() {
+        let limb = limb.to_native();
+        digits.push(limb as u32);
+        digits.push((limb >> 32) as u32);
+    }
+    BigUint::new(digits)
+}
+
+impl Archive for BigUint {
+    type Archived = ArchivedBigUint;
+    type Resolver = BigUintResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedBigUint::resolve_from_resolver(resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for BigUint
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBigUint::serialize_from_limbs(&self.to_u64_digits(), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<BigUint, D> for ArchivedBigUint {
+    fn deserialize(&self, _: &mut D) -> Result<BigUint, D::Error> {
+        Ok(biguint_from_archived(self))
+    }
+}
+
+impl PartialEq<BigUint> for ArchivedBigUint {
+    fn eq(&self, other: &BigUint) -> bool {
+        self.limbs().len() == other.iter_u64_digits().len()
+            && self
+                .limbs()
+                .iter()
+                .zip(other.iter_u64_digits())
+                .all(|(a, b)| a.to_native() == b)
+    }
+}
+
+impl Archive for BigInt {
+    type Archived = ArchivedBigInt;
+    type Resolver = BigIntResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedBigInt::resolve_from_resolver(
+            self.sign() == Sign::Minus,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<S> Serialize<S> for BigInt
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedBigInt::serialize_from_limbs(
+            &self.magnitude().to_u64_digits(),
+            serializer,
+        )
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<BigInt, D> for ArchivedBigInt {
+    fn deserialize(&self, _: &mut D) -> Result<BigInt, D::Error> {
+        let sign = match self.sign() {
+            ArchivedSign::Minus => Sign::Minus,
+            ArchivedSign::NoSign => Sign::NoSign,
+            ArchivedSign::Plus => Sign::Plus,
+        };
+        Ok(BigInt::from_biguint(
+            sign,
+            biguint_from_archived(self.magnitude()),
+        ))
+    }
+}
+
+impl PartialEq<BigInt> for ArchivedBigInt {
+    fn eq(&self, other: &BigInt) -> bool {
+        self.is_negative() == (other.sign() == Sign::Minus)
+            && *self.magnitude() == *other.magnitude()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BigInt, BigUint};
+    use crate::api::test::{roundtrip, roundtrip_with};
+
+    #[test]
+    fn roundtrip_big_uint() {
+        roundtrip(&BigUint::default());
+        roundtrip(&BigUint::from(u64::MAX));
+        roundtrip_with(&(BigUint::from(1u32) << 200), |_, b| {
+            assert_eq!(b.bits(), 201);
+            assert_eq!(b.to_native_checked::<u128>(), None);
+        });
+    }
+
+    #[test]
+    fn roundtrip_big_int() {
+        roundtrip(&BigInt::default());
+        roundtrip(&BigInt::from(i128::MIN));
+        roundtrip_with(&BigInt::from(-42), |_, b| {
+            assert!(b.is_negative());
+            assert_eq!(b.to_native_checked::<i32>(), Some(-42));
+        });
+        roundtrip(&-(BigInt::from(3) << 130));
+    }
+}
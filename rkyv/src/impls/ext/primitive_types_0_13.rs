@@ -0,0 +1,103 @@
+use primitive_types_0_13::{H128, H160, H256, H512, U128, U256, U512};
+use rancor::Fallible;
+
+use crate::{
+    fixed_int::ArchivedFixedUint, Archive, Deserialize, Place, Serialize,
+};
+
+macro_rules! impl_uint {
+    ($ty:ident, $words:literal) => {
+        impl Archive for $ty {
+            type Archived = ArchivedFixedUint<$words>;
+            type Resolver = ();
+
+            fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+                out.write(ArchivedFixedUint::from_words(self.0));
+            }
+        }
+
+        impl<S: Fallible + ?Sized> Serialize<S> for $ty {
+            fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$ty, D>
+            for ArchivedFixedUint<$words>
+        {
+            fn deserialize(&self, _: &mut D) -> Result<$ty, D::Error> {
+                Ok($ty(self.to_words()))
+            }
+        }
+
+        impl PartialEq<$ty> for ArchivedFixedUint<$words> {
+            fn eq(&self, other: &$ty) -> bool {
+                self.to_words() == other.0
+            }
+        }
+    };
+}
+
+impl_uint!(U128, 2);
+impl_uint!(U256, 4);
+impl_uint!(U512, 8);
+
+macro_rules! impl_hash {
+    ($ty:ident, $bytes:literal) => {
+        impl Archive for $ty {
+            type Archived = [u8; $bytes];
+            type Resolver = ();
+
+            fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+                out.write(self.0);
+            }
+        }
+
+        impl<S: Fallible + ?Sized> Serialize<S> for $ty {
+            fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+                Ok(())
+            }
+        }
+
+        impl<D: Fallible + ?Sized> Deserialize<$ty, D> for [u8; $bytes] {
+            fn deserialize(&self, _: &mut D) -> Result<$ty, D::Error> {
+                Ok($ty(*self))
+            }
+        }
+    };
+}
+
+impl_hash!(H128, 16);
+impl_hash!(H160, 20);
+impl_hash!(H256, 32);
+impl_hash!(H512, 64);
+
+#[cfg(test)]
+mod tests {
+    use super::{H160, H256, U256, U512};
+    use crate::{
+        alloc::string::ToString,
+        api::test::{roundtrip, roundtrip_with},
+    };
+
+    #[test]
+    fn roundtrip_uint() {
+        roundtrip(&U256::zero());
+        roundtrip(&U256::MAX);
+        roundtrip(&U512::MAX);
+        roundtrip_with(&U256::from(1_000_000_007u64), |a, b| {
+            assert_eq!(b.to_words(), a.0);
+            assert_eq!(b.to_string(), a.to_string());
+        });
+    }
+
+    #[test]
+    fn roundtrip_hash() {
+        roundtrip_with(&H160::repeat_byte(0xab), |a, b| {
+            assert_eq!(b, a.as_fixed_bytes());
+        });
+        roundtrip_with(&H256::repeat_byte(0x42), |a, b| {
+            assert_eq!(b, a.as_fixed_bytes());
+        });
+    }
+}
@@ -0,0 +1,287 @@
+use rancor::{Fallible, Source};
+use serde_json_1::{Map, Number as JsonNumber, Value as JsonValue};
+
+use crate::{
+    alloc::{string::String, vec::Vec},
+    collections::btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+    dynamic::{ArchivedNumber, ArchivedValue, Number, NumberResolver, Value},
+    ser::{Allocator, Writer},
+    string::ArchivedString,
+    util::SerVec,
+    with::{ArchiveWith, SerializeWith},
+    Archive, Deserialize, Place, Serialize,
+};
+
+impl From<&JsonNumber> for Number {
+    /// Converts a JSON number to a dynamic number.
+    ///
+    /// Numbers which can't be represented as a `u64`, `i64`, or `f64` are
+    /// converted to NaN.
+    fn from(value: &JsonNumber) -> Self {
+        if let Some(n) = value.as_u64() {
+            Number::PosInt(n)
+        } else if let Some(n) = value.as_i64() {
+            Number::NegInt(n)
+        } else {
+            Number::Float(value.as_f64().unwrap_or(f64::NAN))
+        }
+    }
+}
+
+impl From<Number> for JsonValue {
+    /// Converts a dynamic number to a JSON value.
+    ///
+    /// Infinite and NaN floats are converted to `null`.
+    fn from(value: Number) -> Self {
+        match value {
+            Number::PosInt(n) => JsonValue::Number(n.into()),
+            Number::NegInt(n) => JsonValue::Number(n.into()),
+            Number::Float(n) => JsonNumber::from_f64(n)
+                .map_or(JsonValue::Null, JsonValue::Number),
+        }
+    }
+}
+
+impl From<JsonValue> for Value {
+    fn from(value: JsonValue) -> Self {
+        match value {
+            JsonValue::Null => Value::Null,
+            JsonValue::Bool(b) => Value::Bool(b),
+            JsonValue::Number(n) => Value::Number(Number::from(&n)),
+            JsonValue::String(s) => Value::String(s),
+            JsonValue::Array(a) => {
+                Value::Array(a.into_iter().map(Value::from).collect())
+            }
+            JsonValue::Object(o) => Value::Object(
+                o.into_iter().map(|(k, v)| (k, Value::from(v))).collect(),
+            ),
+        }
+    }
+}
+
+impl From<Value> for JsonValue {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => JsonValue::Null,
+            Value::Bool(b) => JsonValue::Bool(b),
+            Value::Number(n) => JsonValue::from(n),
+            Value::String(s) => JsonValue::String(s),
+            Value::Array(a) => {
+                JsonValue::Array(a.into_iter().map(JsonValue::from).collect())
+            }
+            Value::Object(o) => JsonValue::Object(
+                o.into_iter()
+                    .map(|(k, v)| (k, JsonValue::from(v)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// `serde_json::Value` archives to an `ArchivedValue` by way of a remote derive
+// which uses `ArchivedValue` as its archived type. The variants and archived
+// field types must exactly match those of `Value`.
+
+struct AsNumber;
+
+impl ArchiveWith<JsonNumber> for AsNumber {
+    type Archived = ArchivedNumber;
+    type Resolver = NumberResolver;
+
+    fn resolve_with(
+        field: &JsonNumber,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        Number::from(field).resolve(resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<JsonNumber, S> for AsNumber {
+    fn serialize_with(
+        field: &JsonNumber,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Number::from(field).serialize(serializer)
+    }
+}
+
+type ArchivedObject = ArchivedBTreeMap<ArchivedString, ArchivedValue>;
+
+struct AsObject;
+
+impl ArchiveWith<Map<String, JsonValue>> for AsObject {
+    type Archived = ArchivedObject;
+    type Resolver = BTreeMapResolver;
+
+    fn resolve_with(
+        field: &Map<String, JsonValue>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedObject::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<S> SerializeWith<Map<String, JsonValue>, S> for AsObject
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &Map<String, JsonValue>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        // Maps are only sorted by key when the `preserve_order` feature of
+        // `serde_json` is disabled, so we have to sort the entries ourselves.
+        SerVec::with_capacity(
+            serializer,
+            field.len(),
+            |entries, serializer| {
+                for entry in field.iter() {
+                    entries.push(entry);
+                }
+                entries.as_mut_slice().sort_unstable_by_key(|&(key, _)| key);
+
+                ArchivedObject::serialize_from_ordered_iter::<
+                    _,
+                    _,
+                    _,
+                    String,
+                    JsonValue,
+                    _,
+                >(entries.as_slice().iter().copied(), serializer)
+            },
+        )?
+    }
+}
+
+#[derive(Archive, Serialize)]
+#[allow(dead_code)]
+#[rkyv(
+    crate,
+    remote = JsonValue,
+    as = ArchivedValue,
+    serialize_bounds(__S: Writer + Allocator, __S::Error: Source),
+)]
+pub enum JsonValueDef {
+    Null,
+    Bool(bool),
+    Number(#[rkyv(with = AsNumber)] JsonNumber),
+    String(String),
+    Array(#[rkyv(omit_bounds)] Vec<JsonValue>),
+    Object(#[rkyv(omit_bounds, with = AsObject)] Map<String, JsonValue>),
+}
+
+impl Archive for JsonValue {
+    type Archived = ArchivedValue;
+    type Resolver = JsonValueDefResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        JsonValueDef::resolve_with(self, resolver, out);
+    }
+}
+
+impl<S> Serialize<S> for JsonValue
+where
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        JsonValueDef::serialize_with(self, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<JsonValue, D> for ArchivedValue {
+    fn deserialize(&self, deserializer: &mut D) -> Result<JsonValue, D::Error> {
+        Ok(match self {
+            ArchivedValue::Null => JsonValue::Null,
+            ArchivedValue::Bool(b) => JsonValue::Bool(*b),
+            ArchivedValue::Number(n) => JsonValue::from(match n {
+                ArchivedNumber::PosInt(n) => Number::PosInt(n.to_native()),
+                ArchivedNumber::NegInt(n) => Number::NegInt(n.to_native()),
+                ArchivedNumber::Float(n) => Number::Float(n.to_native()),
+            }),
+            ArchivedValue::String(s) => {
+                JsonValue::String(s.deserialize(deserializer)?)
+            }
+            ArchivedValue::Array(a) => JsonValue::Array(
+                a.iter()
+                    .map(|v| {
+                        Deserialize::<JsonValue, D>::deserialize(
+                            v,
+                            deserializer,
+                        )
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            ArchivedValue::Object(o) => {
+                let mut map = Map::new();
+                for (k, v) in o.iter() {
+                    map.insert(
+                        k.deserialize(deserializer)?,
+                        Deserialize::<JsonValue, D>::deserialize(
+                            v,
+                            deserializer,
+                        )?,
+                    );
+                }
+                JsonValue::Object(map)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json_1::{json, Value as JsonValue};
+
+    use crate::{
+        api::test::{roundtrip_with, to_archived},
+        dynamic::Value,
+    };
+
+    fn document() -> JsonValue {
+        json!({
+            "name": "ferris",
+            "age": 10,
+            "offset": -3,
+            "height": 0.25,
+            "is_crab": true,
+            "project": null,
+            "tags": ["rust", "crab", { "nested": [1, 2, 3] }],
+            "a string which is long enough to be stored out-of-line": {},
+        })
+    }
+
+    #[test]
+    fn roundtrip_json_value() {
+        roundtrip_with(&document(), |a, b| {
+            assert_eq!(b.get("name").unwrap().as_str(), a["name"].as_str());
+        });
+    }
+
+    #[test]
+    fn traverse_archived_json_value() {
+        let value = document();
+        to_archived(&value, |archived| {
+            assert_eq!(archived.get("name").unwrap().as_str(), Some("ferris"));
+            assert_eq!(archived.get("age").unwrap().as_u64(), Some(10));
+            assert_eq!(archived.get("offset").unwrap().as_i64(), Some(-3));
+            assert_eq!(archived.get("height").unwrap().as_f64(), Some(0.25));
+            assert_eq!(archived.get("is_crab").unwrap().as_bool(), Some(true));
+            assert!(archived.get("project").unwrap().is_null());
+
+            let tags = archived.get("tags").unwrap();
+            assert_eq!(tags.get_index(1).unwrap().as_str(), Some("crab"));
+            let nested = tags.get_index(2).unwrap().get("nested").unwrap();
+            assert_eq!(nested.get_index(2).unwrap().as_u64(), Some(3));
+
+            // The archived value is the same as that of a `dynamic::Value`
+            assert_eq!(*archived, Value::from(value.clone()));
+        });
+    }
+}
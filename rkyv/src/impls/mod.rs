@@ -455,6 +455,111 @@ mod core_tests {
         }
     }
 
+    #[test]
+    fn compare_eq_ord_hash() {
+        use crate::hash::{hash_value, FxHasher64};
+
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+        )]
+        #[rkyv(crate, compare(Eq, Ord, Hash))]
+        pub struct StructFoo {
+            a: i32,
+            b: Option<u16>,
+            c: bool,
+        }
+
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+        )]
+        #[rkyv(crate, compare(Eq, Ord, Hash))]
+        pub enum EnumFoo {
+            A,
+            B(i32, u8),
+            C { x: u32 },
+        }
+
+        #[derive(
+            Archive,
+            Serialize,
+            Deserialize,
+            PartialEq,
+            Eq,
+            PartialOrd,
+            Ord,
+            Hash,
+        )]
+        #[rkyv(crate, compare(Eq, Ord, Hash))]
+        #[repr(u16)]
+        pub enum ReprFoo {
+            X = 3,
+            Y = 1,
+        }
+
+        macro_rules! check {
+            ($values:expr) => {
+                let values = $values;
+                to_archived(&values, |archived| {
+                    for (i, value) in values.iter().enumerate() {
+                        assert_eq!(
+                            hash_value::<_, FxHasher64>(value),
+                            hash_value::<_, FxHasher64>(&archived[i]),
+                        );
+                        for (j, other) in values.iter().enumerate() {
+                            assert_eq!(
+                                value.cmp(other),
+                                archived[i].cmp(&archived[j]),
+                            );
+                            assert_eq!(
+                                value == other,
+                                archived[i] == archived[j],
+                            );
+                        }
+                    }
+                });
+            };
+        }
+
+        check!([
+            StructFoo {
+                a: 1,
+                b: None,
+                c: true,
+            },
+            StructFoo {
+                a: 1,
+                b: Some(2),
+                c: false,
+            },
+            StructFoo {
+                a: -4,
+                b: Some(2),
+                c: false,
+            },
+        ]);
+        check!([
+            EnumFoo::A,
+            EnumFoo::B(-1, 2),
+            EnumFoo::B(-1, 3),
+            EnumFoo::C { x: 7 },
+        ]);
+        check!([ReprFoo::X, ReprFoo::Y]);
+    }
+
     #[test]
     fn default_type_parameters() {
         #[derive(Archive, Serialize, Deserialize)]
@@ -656,6 +761,147 @@ mod core_tests {
         }
     }
 
+    #[test]
+    fn archive_transparent() {
+        use core::any::TypeId;
+
+        use crate::{api::test::roundtrip_with, Archived};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, transparent)]
+        struct Meters {
+            value: u32,
+        }
+
+        assert_eq!(
+            TypeId::of::<Archived<Meters>>(),
+            TypeId::of::<ArchivedU32>(),
+        );
+        assert_eq!(
+            <Meters as Archive>::COPY_OPTIMIZATION.is_enabled(),
+            <u32 as Archive>::COPY_OPTIMIZATION.is_enabled(),
+        );
+        roundtrip_with(&Meters { value: 42 }, |a, b| assert_eq!(*b, a.value));
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, transparent)]
+        struct Offset(i32);
+
+        assert_eq!(
+            TypeId::of::<Archived<Offset>>(),
+            TypeId::of::<Archived<i32>>(),
+        );
+        roundtrip_with(&Offset(-7), |a, b| assert_eq!(*b, a.0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn archive_transparent_generic() {
+        use crate::{
+            alloc::{string::String, vec, vec::Vec},
+            api::test::roundtrip_with,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, transparent)]
+        struct Stack<T>(Vec<T>);
+
+        roundtrip_with(&Stack(vec![1u16, 2, 3]), |a, b| {
+            assert_eq!(b.as_slice(), a.0.as_slice())
+        });
+        roundtrip_with(&Stack(vec![String::from("a")]), |a, b| {
+            assert_eq!(b[0], a.0[0])
+        });
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, transparent)]
+        struct Boxed(#[rkyv(with = crate::with::AsBox)] i32);
+
+        roundtrip_with(&Boxed(42), |a, b| assert_eq!(**b, a.0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn archive_niche_discriminant() {
+        use core::{mem::size_of, num::NonZeroU32};
+
+        use crate::{
+            alloc::string::String, api::test::roundtrip_with,
+            niche::niching::Zero, string::ArchivedString, Archived,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, niche_discriminant)]
+        enum Name {
+            Anonymous,
+            Named(String),
+        }
+
+        assert_eq!(size_of::<Archived<Name>>(), size_of::<ArchivedString>());
+        roundtrip_with(&Name::Named(String::from("rkyv")), |_, b| {
+            assert_eq!(b.as_deref(), Some("rkyv"))
+        });
+        roundtrip_with(&Name::Anonymous, |_, b| assert!(b.is_none()));
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, niche_discriminant)]
+        enum Limit {
+            Limited {
+                #[rkyv(niche = Zero)]
+                max: NonZeroU32,
+            },
+            Unlimited,
+        }
+
+        assert_eq!(size_of::<Archived<Limit>>(), size_of::<Archived<u32>>());
+        roundtrip_with(
+            &Limit::Limited {
+                max: NonZeroU32::new(10).unwrap(),
+            },
+            |_, b| assert_eq!(b.as_ref().map(|max| max.get()), Some(10)),
+        );
+        roundtrip_with(&Limit::Unlimited, |_, b| assert!(b.is_none()));
+    }
+
+    #[test]
+    fn archive_bit_pack() {
+        use core::mem::size_of;
+
+        use crate::{api::test::roundtrip_with, with::BitPack, Archived};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Flags {
+            #[rkyv(with = BitPack)]
+            a: bool,
+            #[rkyv(with = BitPack, bits = 5)]
+            b: u8,
+            id: u32,
+            #[rkyv(with = BitPack, bits = 7)]
+            c: i16,
+            #[rkyv(with = BitPack)]
+            d: bool,
+        }
+
+        assert_eq!(size_of::<Archived<Flags>>(), 8);
+        roundtrip_with(
+            &Flags {
+                a: true,
+                b: 31,
+                id: 42,
+                c: -64,
+                d: false,
+            },
+            |a, b| {
+                assert_eq!(b.a(), a.a);
+                assert_eq!(b.b(), a.b);
+                assert_eq!(b.id, a.id);
+                assert_eq!(b.c(), a.c);
+                assert_eq!(b.d(), a.d);
+            },
+        );
+    }
+
     #[test]
     fn archive_crate_path() {
         use crate as alt_path;
@@ -804,4 +1050,58 @@ mod alloc_tests {
             }),
         });
     }
+
+    #[test]
+    fn resolver_builder() {
+        use rancor::Fallible;
+
+        use crate::ser::Allocator;
+
+        #[derive(Archive, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, resolver_builder, compare(PartialEq), derive(Debug))]
+        struct Config {
+            name: String,
+            values: Vec<u32>,
+            id: u32,
+        }
+
+        impl<S> Serialize<S> for Config
+        where
+            S: Fallible + Allocator + Writer + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                // Serialize the fields in a different order than they are
+                // declared in.
+                let mut builder = ConfigResolverBuilder::new();
+                builder
+                    .serialize_values(self, serializer)?
+                    .serialize_name(self, serializer)?
+                    .set_id(());
+                Ok(builder.build().unwrap())
+            }
+        }
+
+        roundtrip(&Config {
+            name: "config".to_string(),
+            values: vec![1, 2, 3],
+            id: 42,
+        });
+
+        let mut builder = ConfigResolverBuilder::default();
+        builder.set_id(());
+        assert!(builder.build().is_none());
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[rkyv(crate, resolver_builder)]
+        struct Pair(u32, u32);
+
+        let mut builder = PairResolverBuilder::new();
+        builder.set_field_0(()).set_field_1(());
+        assert!(builder.build().is_some());
+    }
 }
@@ -1,7 +1,4 @@
-use core::{
-    borrow::Borrow,
-    hash::{BuildHasher, Hash},
-};
+use core::hash::{BuildHasher, Hash};
 use std::collections::HashMap;
 
 use rancor::{Fallible, Source};
@@ -76,8 +73,8 @@ where
 
 impl<K, V, AK, AV, S> PartialEq<HashMap<K, V, S>> for ArchivedHashMap<AK, AV>
 where
-    K: Hash + Eq + Borrow<AK>,
-    AK: Hash + Eq,
+    K: Hash + Eq,
+    AK: PartialEq<K>,
     AV: PartialEq<V>,
     S: BuildHasher,
 {
@@ -85,17 +82,22 @@ where
         if self.len() != other.len() {
             false
         } else {
-            self.iter()
-                .all(|(key, value)| other.get(key).is_some_and(|v| value.eq(v)))
+            // Archived hash maps are indexed by the hashes of the original
+            // keys, so the original keys can be used to look up entries.
+            other.iter().all(|(key, value)| {
+                self.get_with(key, |k, ak| ak == k)
+                    .is_some_and(|v| v == value)
+            })
         }
     }
 }
 
-impl<K, V, AK, AV> PartialEq<ArchivedHashMap<AK, AV>> for HashMap<K, V>
+impl<K, V, AK, AV, S> PartialEq<ArchivedHashMap<AK, AV>> for HashMap<K, V, S>
 where
-    K: Hash + Eq + Borrow<AK>,
-    AK: Hash + Eq,
+    K: Hash + Eq,
+    AK: PartialEq<K>,
     AV: PartialEq<V>,
+    S: BuildHasher,
 {
     fn eq(&self, other: &ArchivedHashMap<AK, AV>) -> bool {
         other.eq(self)
@@ -152,6 +154,67 @@ mod tests {
         roundtrip_with(&map, assert_equal);
     }
 
+    #[test]
+    fn compare_hash_map_string_keys() {
+        let mut map = HashMap::new();
+        map.insert("hello".to_string(), 1);
+        map.insert("world".to_string(), 2);
+
+        to_archived(&map, |archived| {
+            assert!(*archived == map);
+            assert!(map == *archived);
+
+            let mut other = map.clone();
+            other.insert("world".to_string(), 3);
+            assert!(*archived != other);
+            other.remove("world");
+            assert!(other != *archived);
+        });
+    }
+
+    #[test]
+    fn roundtrip_hash_map_cow_keys() {
+        use std::borrow::Cow;
+
+        let mut map = HashMap::new();
+        map.insert(Cow::Borrowed("hello"), 1i32);
+        map.insert(Cow::Owned("world".to_string()), 2);
+        roundtrip(&map);
+
+        to_archived(&map, |archived| {
+            assert_eq!(archived.get("hello").map(|v| v.to_native()), Some(1));
+            assert_eq!(archived.get("world").map(|v| v.to_native()), Some(2));
+            assert!(!archived.contains_key("foo"));
+        });
+    }
+
+    #[test]
+    fn get_with_hash() {
+        use crate::hash::{hash_value, FxHasher64};
+
+        let mut map = HashMap::new();
+        map.insert("hello".to_string(), 1i32);
+        map.insert("world".to_string(), 2i32);
+
+        to_archived(&map, |mut archived| {
+            let hash = hash_value::<str, FxHasher64>("world");
+            let value = archived.get_with_hash(hash, |k| k == "world");
+            assert_eq!(value.map(|v| v.to_native()), Some(2));
+            assert!(archived.get_with_hash(hash, |k| k == "hello").is_none());
+
+            let (key, mut value) =
+                ArchivedHashMap::get_key_value_seal_with_hash(
+                    archived.as_mut(),
+                    hash,
+                    |k| k == "world",
+                )
+                .unwrap();
+            assert_eq!(key, "world");
+            *value = 3.into();
+            assert_eq!(archived.get("world").map(|v| v.to_native()), Some(3));
+        });
+    }
+
     #[test]
     fn roundtrip_hash_map_string_string() {
         let mut hash_map = HashMap::new();
@@ -261,6 +324,94 @@ mod tests {
         });
     }
 
+    #[test]
+    fn remove_seal() {
+        let map = (0..100u8)
+            .map(|i| (i, u32::from(i)))
+            .collect::<HashMap<_, _>>();
+
+        to_archived(&map, |mut archived| {
+            for i in (0..100).step_by(3) {
+                assert!(ArchivedHashMap::remove_seal(archived.as_mut(), &i));
+            }
+            assert!(!ArchivedHashMap::remove_seal(archived.as_mut(), &0));
+            assert!(!ArchivedHashMap::remove_seal(archived.as_mut(), &200));
+
+            assert_eq!(archived.len(), 66);
+            assert_eq!(archived.iter().count(), 66);
+            for i in 0..100 {
+                assert_eq!(archived.contains_key(&i), i % 3 != 0);
+            }
+        });
+    }
+
+    #[test]
+    fn insert_seal() {
+        use rancor::{Error, Panic};
+
+        use crate::primitive::ArchivedU32;
+
+        let map = (0..100u8)
+            .map(|i| (i, u32::from(i)))
+            .collect::<HashMap<_, _>>();
+
+        to_archived(&map, |mut archived| {
+            let spare = archived.capacity() - archived.len();
+
+            // Replacing an existing value does not use any capacity
+            let old = ArchivedHashMap::insert_seal::<Panic>(
+                archived.as_mut(),
+                10,
+                ArchivedU32::from_native(1000),
+            )
+            .unwrap();
+            assert_eq!(old.unwrap(), 10);
+            assert_eq!(archived.get(&10).unwrap(), &1000);
+
+            // Removed buckets can be reused
+            for i in 0..50 {
+                assert!(ArchivedHashMap::remove_seal(archived.as_mut(), &i));
+            }
+            for i in 0..50 {
+                let old = ArchivedHashMap::insert_seal::<Panic>(
+                    archived.as_mut(),
+                    i,
+                    ArchivedU32::from_native(u32::from(i) + 1000),
+                )
+                .unwrap();
+                assert!(old.is_none());
+            }
+            assert_eq!(archived.len(), 100);
+            assert_eq!(archived.capacity() - archived.len(), spare);
+
+            // New entries must fit into the spare capacity, and one bucket
+            // must always remain empty
+            let mut inserted = Vec::new();
+            for i in 200..=255 {
+                let result = ArchivedHashMap::insert_seal::<Error>(
+                    archived.as_mut(),
+                    i,
+                    ArchivedU32::from_native(u32::from(i)),
+                );
+                if result.is_err() {
+                    break;
+                }
+                inserted.push(i);
+            }
+            assert!(!inserted.is_empty());
+            assert!(inserted.len() < spare);
+            assert!(archived.len() < archived.capacity());
+
+            assert_eq!(archived.iter().count(), archived.len());
+            for i in 0..50 {
+                assert_eq!(archived.get(&i).unwrap(), &(u32::from(i) + 1000));
+            }
+            for i in inserted {
+                assert_eq!(archived.get(&i).unwrap(), &u32::from(i));
+            }
+        });
+    }
+
     #[test]
     fn large_hash_map() {
         let mut map = std::collections::HashMap::new();
@@ -298,4 +449,88 @@ mod tests {
         // This .unwrap() fails!
         let _decoded = access::<Archived<MyHashMap>, Panic>(&encoded).unwrap();
     }
+
+    #[test]
+    fn serialize_with_options() {
+        use rancor::{Error, Fallible, Source};
+
+        use crate::{
+            api::high::to_bytes,
+            collections::swiss_table::{HashMapResolver, HashTableOptions},
+            ser::{Allocator, Writer},
+            Place,
+        };
+
+        struct Tuned(Vec<(u32, u32)>, HashTableOptions);
+
+        impl Archive for Tuned {
+            type Archived = ArchivedHashMap<Archived<u32>, Archived<u32>>;
+            type Resolver = HashMapResolver;
+
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: Place<Self::Archived>,
+            ) {
+                ArchivedHashMap::resolve_from_len(
+                    self.0.len(),
+                    self.1.load_factor,
+                    resolver,
+                    out,
+                );
+            }
+        }
+
+        impl<S> Serialize<S> for Tuned
+        where
+            S: Fallible + Writer + Allocator + ?Sized,
+            S::Error: Source,
+        {
+            fn serialize(
+                &self,
+                serializer: &mut S,
+            ) -> Result<Self::Resolver, S::Error> {
+                Archived::<Self>::serialize_from_iter_with_options::<
+                    _,
+                    _,
+                    _,
+                    u32,
+                    u32,
+                    _,
+                >(
+                    self.0.iter().map(|(k, v)| (k, v)), self.1, serializer
+                )
+            }
+        }
+
+        let entries = (0..1000).map(|i| (i, i * 3)).collect::<Vec<_>>();
+        let options = HashTableOptions {
+            load_factor: (1, 2),
+            sort_by_bucket: true,
+        };
+
+        let forward = Tuned(entries.clone(), options);
+        let reversed = Tuned(entries.iter().rev().copied().collect(), options);
+        let bytes = to_bytes::<Error>(&forward).unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            to_bytes::<Error>(&reversed).unwrap().as_slice(),
+        );
+
+        let archived =
+            unsafe { crate::access_unchecked::<Archived<Tuned>>(&bytes) };
+        assert!(archived.capacity() >= 2000);
+        for (key, value) in entries.iter() {
+            assert_eq!(archived.get(&(*key).into()).unwrap(), value);
+        }
+
+        let invalid = Tuned(
+            entries,
+            HashTableOptions {
+                load_factor: (2, 1),
+                sort_by_bucket: true,
+            },
+        );
+        assert!(to_bytes::<Error>(&invalid).is_err());
+    }
 }
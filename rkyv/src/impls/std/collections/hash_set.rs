@@ -1,7 +1,4 @@
-use core::{
-    borrow::Borrow,
-    hash::{BuildHasher, Hash},
-};
+use core::hash::{BuildHasher, Hash};
 use std::collections::HashSet;
 
 use rancor::{Fallible, Source};
@@ -68,20 +65,28 @@ where
     }
 }
 
-impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
-    PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
+impl<K, AK, S> PartialEq<HashSet<K, S>> for ArchivedHashSet<AK>
+where
+    K: Hash + Eq,
+    AK: PartialEq<K>,
+    S: BuildHasher,
 {
     fn eq(&self, other: &HashSet<K, S>) -> bool {
         if self.len() != other.len() {
             false
         } else {
-            self.iter().all(|key| other.get(key).is_some())
+            other
+                .iter()
+                .all(|key| self.contains_with(key, |k, ak| ak == k))
         }
     }
 }
 
-impl<K: Hash + Eq + Borrow<AK>, AK: Hash + Eq, S: BuildHasher>
-    PartialEq<ArchivedHashSet<AK>> for HashSet<K, S>
+impl<K, AK, S> PartialEq<ArchivedHashSet<AK>> for HashSet<K, S>
+where
+    K: Hash + Eq,
+    AK: PartialEq<K>,
+    S: BuildHasher,
 {
     fn eq(&self, other: &ArchivedHashSet<AK>) -> bool {
         other.eq(self)
@@ -122,4 +127,46 @@ mod tests {
         value.insert(());
         roundtrip(&value);
     }
+
+    #[test]
+    fn hash_set_operations() {
+        use std::collections::BTreeSet;
+
+        use crate::api::test::to_archived;
+
+        let a: HashSet<u8> = (0..60).filter(|i| i % 2 == 0).collect();
+        let b: HashSet<u8> = (0..60).filter(|i| i % 3 == 0).collect();
+        let c: HashSet<u8> = (0..60).filter(|i| i % 6 == 0).collect();
+        let d: HashSet<u8> = (100..110).collect();
+
+        to_archived(&(a.clone(), b.clone(), c, d), |archived| {
+            let (aa, ab, ac, ad) =
+                (&archived.0, &archived.1, &archived.2, &archived.3);
+            let sorted = |iter: &mut dyn Iterator<Item = &u8>| -> BTreeSet<u8> {
+                iter.copied().collect()
+            };
+
+            assert_eq!(
+                sorted(&mut aa.difference(ab)),
+                sorted(&mut a.difference(&b)),
+            );
+            assert_eq!(
+                sorted(&mut aa.symmetric_difference(ab)),
+                sorted(&mut a.symmetric_difference(&b)),
+            );
+            assert_eq!(
+                sorted(&mut aa.intersection(ab)),
+                sorted(&mut a.intersection(&b)),
+            );
+            assert_eq!(sorted(&mut aa.union(ab)), sorted(&mut a.union(&b)));
+            assert_eq!(aa.union(ab).count(), a.union(&b).count());
+
+            assert!(ac.is_subset(aa));
+            assert!(ac.is_subset(ab));
+            assert!(!aa.is_subset(ab));
+            assert!(aa.is_superset(ac));
+            assert!(!aa.is_disjoint(ab));
+            assert!(aa.is_disjoint(ad));
+        });
+    }
 }
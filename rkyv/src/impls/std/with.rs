@@ -1,4 +1,8 @@
-use core::{error::Error, fmt, hash::BuildHasher};
+use core::{
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hasher},
+};
 use std::{
     borrow::Cow,
     collections::{HashMap, HashSet},
@@ -15,7 +19,12 @@ use rancor::{Fallible, OptionExt, ResultExt, Source};
 
 use crate::{
     collections::{
-        swiss_table::{ArchivedHashMap, HashMapResolver},
+        multi_map::{ArchivedHashMultiMap, HashMultiMapResolver},
+        phf::{ArchivedPhfMap, PhfMapResolver},
+        sorted_vec_map::{ArchivedSortedVecMap, SortedVecMapResolver},
+        swiss_table::{
+            ArchivedHashMap, ArchivedHashSet, HashMapResolver, HashSetResolver,
+        },
         util::{Entry, EntryAdapter},
     },
     ffi::{ArchivedCString, CStringResolver},
@@ -26,8 +35,9 @@ use crate::{
     time::ArchivedDuration,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsOwned, AsString, AsUnixTime, AsVec, DeserializeWith,
-        Lock, MapKV, SerializeWith,
+        ArchiveWith, AsMultiMap, AsOwned, AsPhfMap, AsSortedVec, AsString,
+        AsUnixTime, AsVec, DeserializeWith, HashWith, Lock, MapKV,
+        SerializeWith,
     },
     Archive, Deserialize, Place, Serialize, SerializeUnsized,
 };
@@ -117,6 +127,340 @@ where
     }
 }
 
+// HashWith
+
+impl<K, V, S, H> ArchiveWith<HashMap<K, V, S>> for HashWith<H>
+where
+    K: Archive,
+    V: Archive,
+    H: Hasher + Default,
+{
+    type Archived = ArchivedHashMap<K::Archived, V::Archived, H>;
+    type Resolver = HashMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedHashMap::resolve_from_len(field.len(), (7, 8), resolver, out)
+    }
+}
+
+impl<K, V, RS, H, S> SerializeWith<HashMap<K, V, RS>, S> for HashWith<H>
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    H: Hasher + Default,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashMap::<_, _, H>::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, S, H, D>
+    DeserializeWith<
+        ArchivedHashMap<K::Archived, V::Archived, H>,
+        HashMap<K, V, S>,
+        D,
+    > for HashWith<H>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    S: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashMap<K::Archived, V::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), S::default());
+        for (k, v) in field.iter() {
+            result.insert(
+                k.deserialize(deserializer)?,
+                v.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, S, H> ArchiveWith<HashSet<K, S>> for HashWith<H>
+where
+    K: Archive,
+    H: Hasher + Default,
+{
+    type Archived = ArchivedHashSet<K::Archived, H>;
+    type Resolver = HashSetResolver;
+
+    fn resolve_with(
+        field: &HashSet<K, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedHashSet::resolve_from_len(field.len(), (7, 8), resolver, out)
+    }
+}
+
+impl<K, RS, H, S> SerializeWith<HashSet<K, RS>, S> for HashWith<H>
+where
+    K: Serialize<S> + Hash + Eq,
+    H: Hasher + Default,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashSet<K, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashSet::<_, H>::serialize_from_iter::<_, K, _>(
+            field.iter(),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, S, H, D>
+    DeserializeWith<ArchivedHashSet<K::Archived, H>, HashSet<K, S>, D>
+    for HashWith<H>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    S: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashSet<K::Archived, H>,
+        deserializer: &mut D,
+    ) -> Result<HashSet<K, S>, D::Error> {
+        let mut result =
+            HashSet::with_capacity_and_hasher(field.len(), S::default());
+        for k in field.iter() {
+            result.insert(k.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
+// AsPhfMap
+
+impl<K, V, S> ArchiveWith<HashMap<K, V, S>> for AsPhfMap
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedPhfMap<K::Archived, V::Archived>;
+    type Resolver = PhfMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedPhfMap::resolve_from_len(field.len(), resolver, out)
+    }
+}
+
+impl<K, V, RS, S> SerializeWith<HashMap<K, V, RS>, S> for AsPhfMap
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedPhfMap::<_, _>::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, S, D>
+    DeserializeWith<
+        ArchivedPhfMap<K::Archived, V::Archived>,
+        HashMap<K, V, S>,
+        D,
+    > for AsPhfMap
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    S: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedPhfMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), S::default());
+        for (k, v) in field.iter() {
+            result.insert(
+                k.deserialize(deserializer)?,
+                v.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsSortedVec
+
+impl<K, V, S> ArchiveWith<HashMap<K, V, S>> for AsSortedVec
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedSortedVecMap<K::Archived, V::Archived>;
+    type Resolver = SortedVecMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, V, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        ArchivedSortedVecMap::resolve_from_len(field.len(), resolver, out);
+    }
+}
+
+impl<K, V, S, RS> SerializeWith<HashMap<K, V, RS>, S> for AsSortedVec
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedSortedVecMap::serialize_from_iter::<_, _, _, K, V, _>(
+            field.iter(),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, D, S>
+    DeserializeWith<
+        ArchivedSortedVecMap<K::Archived, V::Archived>,
+        HashMap<K, V, S>,
+        D,
+    > for AsSortedVec
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize_with(
+        field: &ArchivedSortedVecMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.len(), S::default());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// AsMultiMap
+
+impl<K, V, S> ArchiveWith<HashMap<K, Vec<V>, S>> for AsMultiMap
+where
+    K: Archive,
+    V: Archive,
+{
+    type Archived = ArchivedHashMultiMap<K::Archived, V::Archived>;
+    type Resolver = HashMultiMapResolver;
+
+    fn resolve_with(
+        field: &HashMap<K, Vec<V>, S>,
+        resolver: Self::Resolver,
+        out: Place<Self::Archived>,
+    ) {
+        let len = field.values().map(Vec::len).sum();
+        ArchivedHashMultiMap::resolve_from_len(len, (7, 8), resolver, out)
+    }
+}
+
+impl<K, V, RS, S> SerializeWith<HashMap<K, Vec<V>, RS>, S> for AsMultiMap
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: Fallible + Allocator + Writer + ?Sized,
+    S::Error: Source,
+{
+    fn serialize_with(
+        field: &HashMap<K, Vec<V>, RS>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        ArchivedHashMultiMap::<_, _>::serialize_from_iter::<_, _, _, K, V, _>(
+            field
+                .iter()
+                .flat_map(|(key, values)| values.iter().map(move |v| (key, v))),
+            (7, 8),
+            serializer,
+        )
+    }
+}
+
+impl<K, V, S, D>
+    DeserializeWith<
+        ArchivedHashMultiMap<K::Archived, V::Archived>,
+        HashMap<K, Vec<V>, S>,
+        D,
+    > for AsMultiMap
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    S: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedHashMultiMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, Vec<V>, S>, D::Error> {
+        let mut result =
+            HashMap::with_capacity_and_hasher(field.keys_len(), S::default());
+        for (k, values) in field.iter() {
+            let values = values
+                .iter()
+                .map(|v| v.deserialize(deserializer))
+                .collect::<Result<Vec<_>, _>>()?;
+            result.insert(k.deserialize(deserializer)?, values);
+        }
+        Ok(result)
+    }
+}
+
 // AsString
 
 #[derive(Debug)]
@@ -562,8 +906,19 @@ mod tests {
     use crate::{
         alloc::collections::HashMap,
         api::test::{roundtrip_with, to_archived},
-        with::{AsString, InlineAsBox, Lock, MapKV},
-        Archive, Deserialize, Serialize,
+        collections::{
+            multi_map::ArchivedHashMultiMap,
+            phf::ArchivedPhfMap,
+            sorted_vec_map::ArchivedSortedVecMap,
+            swiss_table::{ArchivedHashMap, ArchivedHashSet},
+        },
+        hash::SipHasher13,
+        string::ArchivedString,
+        with::{
+            AsMultiMap, AsPhfMap, AsSortedVec, AsString, HashWith, InlineAsBox,
+            Lock, MapKV,
+        },
+        Archive, Archived, Deserialize, Serialize,
     };
 
     #[test]
@@ -697,4 +1052,123 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn with_hash_with() {
+        use std::collections::HashSet;
+
+        type Keyed = SipHasher13<0x0123_4567, 0x89ab_cdef>;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = HashWith<Keyed>)]
+            map: HashMap<String, u32>,
+            #[rkyv(hasher = Keyed)]
+            set: HashSet<String>,
+        }
+
+        let value = Test {
+            map: (0..20).map(|i| (i.to_string(), i)).collect(),
+            set: (0..20).map(|i| (i * 2).to_string()).collect(),
+        };
+        roundtrip_with(&value, |a, b| {
+            let map: &ArchivedHashMap<ArchivedString, Archived<u32>, Keyed> =
+                &b.map;
+            let set: &ArchivedHashSet<ArchivedString, Keyed> = &b.set;
+            for (k, v) in a.map.iter() {
+                assert_eq!(map.get(k.as_str()).unwrap(), v);
+            }
+            for k in a.set.iter() {
+                assert!(set.contains(k.as_str()));
+            }
+            assert!(map.get("20").is_none());
+            assert!(!set.contains("1"));
+        });
+    }
+
+    #[test]
+    fn with_as_phf_map() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsPhfMap)]
+            map: HashMap<String, u32>,
+        }
+
+        for len in [0, 1, 7, 1000] {
+            let value = Test {
+                map: (0..len).map(|i| (i.to_string(), i)).collect(),
+            };
+            roundtrip_with(&value, |a, b| {
+                let map: &ArchivedPhfMap<ArchivedString, Archived<u32>> =
+                    &b.map;
+                assert_eq!(map.len(), a.map.len());
+                for (k, v) in a.map.iter() {
+                    assert_eq!(map.get(k.as_str()).unwrap(), v);
+                    assert_eq!(map[k.as_str()], *v);
+                }
+                assert!(!map.contains_key("missing"));
+                assert!(map.get(len.to_string().as_str()).is_none());
+            });
+        }
+    }
+
+    #[test]
+    fn with_as_multi_map() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsMultiMap)]
+            map: HashMap<String, Vec<u32>>,
+        }
+
+        for len in [0, 1, 7, 100] {
+            let value = Test {
+                map: (0..len)
+                    .map(|i| (i.to_string(), (0..i % 5 + 1).collect()))
+                    .collect(),
+            };
+            roundtrip_with(&value, |a, b| {
+                let map: &ArchivedHashMultiMap<ArchivedString, Archived<u32>> =
+                    &b.map;
+                assert_eq!(map.keys_len(), a.map.len());
+                assert_eq!(
+                    map.len(),
+                    a.map.values().map(Vec::len).sum::<usize>()
+                );
+                for (k, v) in a.map.iter() {
+                    assert_eq!(map.get(k.as_str()), v.as_slice());
+                }
+                assert!(!map.contains_key("missing"));
+                assert!(map.get("missing").is_empty());
+            });
+        }
+    }
+
+    #[test]
+    fn with_as_sorted_vec() {
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[rkyv(crate, derive(Debug))]
+        struct Test {
+            #[rkyv(with = AsSortedVec)]
+            map: HashMap<String, u32>,
+        }
+
+        for len in [0, 1, 7, 100] {
+            let value = Test {
+                map: (0..len).map(|i| (i.to_string(), i)).collect(),
+            };
+            roundtrip_with(&value, |a, b| {
+                let map: &ArchivedSortedVecMap<ArchivedString, Archived<u32>> =
+                    &b.map;
+                assert_eq!(map.len(), a.map.len());
+                assert!(map.keys().zip(map.keys().skip(1)).all(|(a, b)| a < b));
+                for (k, v) in a.map.iter() {
+                    assert_eq!(map.get(k.as_str()), Some(&(*v).into()));
+                }
+                assert!(!map.contains_key("missing"));
+            });
+        }
+    }
 }
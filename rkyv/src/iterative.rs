@@ -0,0 +1,393 @@
+//! Iterative serialization and deserialization for linked chains.
+//!
+//! The derived `Serialize` and `Deserialize` impls recurse into each field, so
+//! a chain of values linked through `Option<Box<Self>>` uses stack space
+//! proportional to its length. Marking the link field with
+//! `#[rkyv(iterative)]` makes the derived impls walk the chain with a loop
+//! instead:
+//!
+//! ```
+//! use rkyv::{
+//!     iterative::drop_chain,
+//!     rancor::{Error, Source},
+//!     validation::ArchiveContext,
+//!     Archive, Deserialize, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! #[rkyv(bytecheck(bounds(__C: ArchiveContext, __C::Error: Source)))]
+//! struct Node {
+//!     value: u32,
+//!     #[rkyv(iterative)]
+//!     next: Option<Box<Node>>,
+//! }
+//!
+//! // Dropping a `Box` chain recurses too, so unlink it first.
+//! impl Drop for Node {
+//!     fn drop(&mut self) {
+//!         drop_chain(self, |node| &mut node.next);
+//!     }
+//! }
+//!
+//! let mut head = None;
+//! for value in 0..100_000 {
+//!     head = Some(Box::new(Node { value, next: head }));
+//! }
+//! let head = head.unwrap();
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&*head).unwrap();
+//! let archived = rkyv::access::<ArchivedNode, Error>(&bytes).unwrap();
+//! assert_eq!(archived.value, 99_999);
+//!
+//! let deserialized = rkyv::deserialize::<Node, Error>(archived).unwrap();
+//! let mut node = Some(&deserialized);
+//! let mut expected = 100_000;
+//! while let Some(current) = node {
+//!     expected -= 1;
+//!     assert_eq!(current.value, expected);
+//!     node = current.next.as_deref();
+//! }
+//! assert_eq!(expected, 0);
+//! ```
+//!
+//! # Guarantees
+//!
+//! - Serializing, deserializing, and validating a chain uses a bounded amount
+//!   of stack space regardless of its length. The fields of each node are
+//!   still serialized and deserialized recursively, so only the marked link
+//!   is followed iteratively. Validation bounds its own recursion (see
+//!   [`ArchiveValidator`]), so it does not need any annotation.
+//! - The archived bytes are identical to those written by the recursive
+//!   derive, so the attribute can be added to or removed from a type without
+//!   changing its format.
+//! - If deserializing any node fails, the nodes which were already
+//!   deserialized are dropped iteratively before the error is returned.
+//! - Dropping a chain is not covered, since the compiler's drop glue for `Box`
+//!   always recurses. Types which may form long chains should implement `Drop`
+//!   with [`drop_chain`].
+//!
+//! [`ArchiveValidator`]: crate::validation::archive::ArchiveValidator
+
+use rancor::Fallible;
+
+use crate::{
+    alloc::{boxed::Box, vec::Vec},
+    boxed::BoxResolver,
+    ser::{Writer, WriterExt as _},
+    Archive,
+};
+
+/// Serializes a chain of values starting at `head` without recursing along the
+/// links of the chain.
+///
+/// `next` returns the value linked from a node, if any. For each node,
+/// `prefix` serializes the fields which come before the link and
+/// `finish` serializes the fields after the link and builds the resolver for
+/// the node, given the resolver for the link. Nodes are written in the same
+/// order that serializing them recursively would write them.
+///
+/// This is used by the `Serialize` derive for fields marked with
+/// `#[rkyv(iterative)]`.
+pub fn serialize_chain<T, P, S>(
+    head: &T,
+    next: impl Fn(&T) -> Option<&T>,
+    mut prefix: impl FnMut(&T, &mut S) -> Result<P, S::Error>,
+    mut finish: impl FnMut(
+        &T,
+        P,
+        Option<BoxResolver>,
+        &mut S,
+    ) -> Result<T::Resolver, S::Error>,
+    serializer: &mut S,
+) -> Result<T::Resolver, S::Error>
+where
+    T: Archive,
+    S: Fallible + Writer + ?Sized,
+{
+    let mut nodes = Vec::new();
+    let mut node = Some(head);
+    while let Some(current) = node {
+        nodes.push((current, prefix(current, serializer)?));
+        node = next(current);
+    }
+
+    let mut link = None;
+    loop {
+        let (current, prefix) = nodes.pop().unwrap();
+        let resolver = finish(current, prefix, link, serializer)?;
+        if nodes.is_empty() {
+            return Ok(resolver);
+        }
+
+        serializer.align_for::<T::Archived>()?;
+        // SAFETY: We just aligned the serializer for `T::Archived`.
+        let pos = unsafe { serializer.resolve_aligned(current, resolver)? };
+        link = Some(BoxResolver::from_pos(pos));
+    }
+}
+
+/// Deserializes a chain of archived values starting at `head` without
+/// recursing along the links of the chain.
+///
+/// `next` returns the archived value linked from an archived node, if any.
+/// `deserialize` deserializes a single node with its link set to `None`, and
+/// `link` returns the link of a deserialized node so that the next node can be
+/// attached to it. If any node fails to deserialize, the nodes deserialized so
+/// far are dropped with [`drop_chain`].
+///
+/// This is used by the `Deserialize` derive for fields marked with
+/// `#[rkyv(iterative)]`.
+pub fn deserialize_chain<A, T, D>(
+    head: &A,
+    next: impl Fn(&A) -> Option<&A>,
+    mut deserialize: impl FnMut(&A, &mut D) -> Result<T, D::Error>,
+    link: impl Fn(&mut T) -> &mut Option<Box<T>>,
+    deserializer: &mut D,
+) -> Result<T, D::Error>
+where
+    D: Fallible + ?Sized,
+{
+    let mut result = deserialize(head, deserializer)?;
+
+    let mut tail = link(&mut result);
+    let mut node = next(head);
+    let error = loop {
+        let Some(current) = node else {
+            return Ok(result);
+        };
+        match deserialize(current, deserializer) {
+            Ok(value) => {
+                let value = tail.insert(Box::new(value));
+                tail = link(value);
+            }
+            Err(error) => break error,
+        }
+        node = next(current);
+    };
+
+    drop_chain(&mut result, link);
+    Err(error)
+}
+
+/// Drops the values linked from `head` without recursing.
+///
+/// `link` returns the link from a node to the next node. Each node is unlinked
+/// from the rest of the chain before it is dropped, so dropping it does not
+/// recurse. Because of this, `drop_chain` can be called from the `Drop` impl of
+/// the node type:
+///
+/// ```
+/// use rkyv::iterative::drop_chain;
+///
+/// struct Node {
+///     next: Option<Box<Node>>,
+/// }
+///
+/// impl Drop for Node {
+///     fn drop(&mut self) {
+///         drop_chain(self, |node| &mut node.next);
+///     }
+/// }
+///
+/// let mut head = Node { next: None };
+/// for _ in 0..100_000 {
+///     head = Node {
+///         next: Some(Box::new(head)),
+///     };
+/// }
+/// drop(head);
+/// ```
+pub fn drop_chain<T>(
+    head: &mut T,
+    link: impl Fn(&mut T) -> &mut Option<Box<T>>,
+) {
+    let mut next = link(head).take();
+    while let Some(mut node) = next {
+        next = link(&mut node).take();
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use core::fmt;
+
+    use rancor::{fail, Error, Fallible, Source};
+
+    use super::drop_chain;
+    use crate::{
+        access,
+        alloc::{
+            boxed::Box,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::{deserialize, to_bytes},
+        ser::Writer,
+        with::{ArchiveWith, DeserializeWith, SerializeWith},
+        Archive, Archived, Deserialize, Place, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(
+        crate,
+        bytecheck(bounds(
+            __C: crate::validation::ArchiveContext,
+            __C::Error: Source,
+        )),
+        serialize_bounds(__S: Writer),
+    )]
+    struct Recursive {
+        name: String,
+        #[rkyv(omit_bounds)]
+        next: Option<Box<Recursive>>,
+        values: Vec<u32>,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(
+        crate,
+        bytecheck(bounds(
+            __C: crate::validation::ArchiveContext,
+            __C::Error: Source,
+        )),
+    )]
+    struct Iterative {
+        name: String,
+        #[rkyv(iterative)]
+        next: Option<Box<Iterative>>,
+        values: Vec<u32>,
+    }
+
+    #[test]
+    fn matches_recursive_layout() {
+        let mut recursive = None;
+        let mut iterative = None;
+        for i in 0..10 {
+            recursive = Some(Box::new(Recursive {
+                name: i.to_string(),
+                next: recursive,
+                values: vec![i; i as usize],
+            }));
+            iterative = Some(Box::new(Iterative {
+                name: i.to_string(),
+                next: iterative,
+                values: vec![i; i as usize],
+            }));
+        }
+
+        to_bytes(&*recursive.unwrap(), |recursive| {
+            to_bytes(&*iterative.unwrap(), |iterative| {
+                assert_eq!(recursive, iterative);
+            });
+        });
+    }
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(
+        crate,
+        bytecheck(bounds(
+            __C: crate::validation::ArchiveContext,
+            __C::Error: Source,
+        )),
+    )]
+    struct Link(
+        #[rkyv(with = FailOnZero)] u32,
+        #[rkyv(iterative)] Option<Box<Link>>,
+        String,
+    );
+
+    impl Drop for Link {
+        fn drop(&mut self) {
+            drop_chain(self, |link| &mut link.1);
+        }
+    }
+
+    fn chain(len: u32) -> Link {
+        let mut head = Link(len, None, len.to_string());
+        for i in (1..len).rev() {
+            head = Link(i, Some(Box::new(head)), i.to_string());
+        }
+        head
+    }
+
+    #[derive(Debug)]
+    struct ZeroError;
+
+    impl fmt::Display for ZeroError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "found a zero")
+        }
+    }
+
+    impl core::error::Error for ZeroError {}
+
+    struct FailOnZero;
+
+    impl ArchiveWith<u32> for FailOnZero {
+        type Archived = Archived<u32>;
+        type Resolver = ();
+
+        fn resolve_with(field: &u32, _: (), out: Place<Self::Archived>) {
+            field.resolve((), out);
+        }
+    }
+
+    impl<S: Fallible + ?Sized> SerializeWith<u32, S> for FailOnZero {
+        fn serialize_with(_: &u32, _: &mut S) -> Result<(), S::Error> {
+            Ok(())
+        }
+    }
+
+    impl<D> DeserializeWith<Archived<u32>, u32, D> for FailOnZero
+    where
+        D: Fallible + ?Sized,
+        D::Error: Source,
+    {
+        fn deserialize_with(
+            field: &Archived<u32>,
+            _: &mut D,
+        ) -> Result<u32, D::Error> {
+            if field.to_native() == 0 {
+                fail!(ZeroError);
+            }
+            Ok(field.to_native())
+        }
+    }
+
+    #[test]
+    fn deep_chain() {
+        const LEN: u32 = 100_000;
+
+        to_bytes(&chain(LEN), |bytes| {
+            let archived = access::<ArchivedLink, Error>(bytes).unwrap();
+            let deserialized = deserialize::<Link>(archived);
+
+            let mut node = Some(&deserialized);
+            let mut expected = 0;
+            while let Some(Link(value, next, name)) = node {
+                expected += 1;
+                assert_eq!(*value, expected);
+                assert_eq!(*name, expected.to_string());
+                node = next.as_deref();
+            }
+            assert_eq!(expected, LEN);
+        });
+    }
+
+    #[test]
+    fn failed_deserialize() {
+        let mut head = chain(100_000);
+        let mut node = &mut head;
+        for _ in 0..50_000 {
+            node = node.1.as_deref_mut().unwrap();
+        }
+        node.0 = 0;
+
+        to_bytes(&head, |bytes| {
+            let archived = access::<ArchivedLink, Error>(bytes).unwrap();
+            let result = crate::deserialize::<Link, Error>(archived);
+            assert!(result.is_err());
+        });
+    }
+}
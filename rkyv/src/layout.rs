@@ -0,0 +1,718 @@
+//! Descriptions of archived layouts.
+//!
+//! [`layout_of`] describes where each field of an archived type is located,
+//! along with its size, alignment, and byte order. The description has a
+//! canonical textual form, so it can be checked into a project as a golden file
+//! and compared in a test. If a refactor changes the layout of an archived
+//! type, the description changes with it, and the test will fail instead of
+//! silently changing the wire format.
+//!
+//! [`DescribeLayout`] is implemented for the archived versions of primitives,
+//! tuples, arrays, and rkyv's archived containers. It can be implemented for
+//! archived types generated by `#[derive(Archive)]` with `#[rkyv(layout)]`, as
+//! long as the archived types of all of their fields implement
+//! `DescribeLayout`. The layouts of rkyv's archived containers are described
+//! by their type parameters instead of their fields, since their fields are
+//! implementation details.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{layout::layout_of, Archive};
+//!
+//! #[derive(Archive)]
+//! #[rkyv(layout)]
+//! struct Reading {
+//!     sensor: u8,
+//!     value: f32,
+//! }
+//!
+//! let layout = layout_of::<ArchivedReading>();
+//! assert_eq!(layout.size(), Some(8));
+//!
+//! // Compare against a golden file, e.g. with `include_str!`
+//! println!("{layout}");
+//! ```
+
+use core::{
+    fmt,
+    marker::{PhantomData, PhantomPinned},
+    mem::{align_of, offset_of, size_of},
+    num::{NonZeroI8, NonZeroU8},
+};
+
+use crate::{
+    alloc::{
+        boxed::Box,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    },
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{ArchivedHashMap, ArchivedHashSet},
+    },
+    endian::Endianness,
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedNonZeroI128, ArchivedNonZeroI16,
+        ArchivedNonZeroI32, ArchivedNonZeroI64, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    rc::ArchivedRc,
+    result::ArchivedResult,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    tuple::*,
+    vec::ArchivedVec,
+};
+
+/// An archived type which can describe its layout.
+///
+/// See the [module-level documentation](self) for more information.
+pub trait DescribeLayout {
+    /// Returns a description of this type's layout.
+    fn describe_layout() -> LayoutDescriptor;
+}
+
+/// Returns a description of the layout of `T`.
+///
+/// See the [module-level documentation](self) for more information.
+pub fn layout_of<T: DescribeLayout + ?Sized>() -> LayoutDescriptor {
+    T::describe_layout()
+}
+
+/// A description of the layout of an archived type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutDescriptor {
+    name: String,
+    size: Option<usize>,
+    align: usize,
+    kind: LayoutKind,
+}
+
+impl LayoutDescriptor {
+    /// Returns a new description of the sized type `T` with the given name and
+    /// kind.
+    pub fn new<T>(name: impl Into<String>, kind: LayoutKind) -> Self {
+        Self {
+            name: name.into(),
+            size: Some(size_of::<T>()),
+            align: align_of::<T>(),
+            kind,
+        }
+    }
+
+    /// Returns a new description of an unsized type with the given name,
+    /// alignment, and kind.
+    pub fn new_unsized(
+        name: impl Into<String>,
+        align: usize,
+        kind: LayoutKind,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            size: None,
+            align,
+            kind,
+        }
+    }
+
+    /// Returns the name of the type.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the size of the type, or `None` if it is unsized.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+
+    /// Returns the alignment of the type.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the kind of the type.
+    pub fn kind(&self) -> &LayoutKind {
+        &self.kind
+    }
+
+    fn fmt_header(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (", self.name)?;
+        match self.size {
+            Some(size) => write!(f, "size {size}")?,
+            None => write!(f, "unsized")?,
+        }
+        write!(f, ", align {}", self.align)?;
+        if let LayoutKind::Primitive {
+            endianness: Some(endianness),
+        } = self.kind
+        {
+            match endianness {
+                Endianness::Little => write!(f, ", little-endian")?,
+                Endianness::Big => write!(f, ", big-endian")?,
+            }
+        }
+        writeln!(f, ")")
+    }
+
+    fn fmt_children(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        depth: usize,
+    ) -> fmt::Result {
+        match self.kind {
+            LayoutKind::Primitive { .. } => Ok(()),
+            LayoutKind::Struct { ref fields } => fmt_fields(fields, f, depth),
+            LayoutKind::Enum { ref variants } => {
+                for variant in variants {
+                    writeln!(
+                        f,
+                        "{:indent$}{}",
+                        "",
+                        variant.name,
+                        indent = depth * 2
+                    )?;
+                    fmt_fields(&variant.fields, f, depth + 1)?;
+                }
+                Ok(())
+            }
+            LayoutKind::Array { ref element, .. }
+            | LayoutKind::Slice { ref element } => {
+                write!(f, "{:indent$}element: ", "", indent = depth * 2)?;
+                element.fmt_header(f)?;
+                element.fmt_children(f, depth + 1)
+            }
+            LayoutKind::Opaque { ref params } => {
+                for (i, param) in params.iter().enumerate() {
+                    write!(f, "{:indent$}param {i}: ", "", indent = depth * 2)?;
+                    param.fmt_header(f)?;
+                    param.fmt_children(f, depth + 1)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn fmt_fields(
+    fields: &[FieldLayout],
+    f: &mut fmt::Formatter<'_>,
+    depth: usize,
+) -> fmt::Result {
+    for field in fields {
+        write!(
+            f,
+            "{:indent$}+{} {}: ",
+            "",
+            field.offset,
+            field.name,
+            indent = depth * 2,
+        )?;
+        field.layout.fmt_header(f)?;
+        field.layout.fmt_children(f, depth + 1)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for LayoutDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_header(f)?;
+        self.fmt_children(f, 1)
+    }
+}
+
+/// The kind of an archived type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutKind {
+    /// A primitive type.
+    Primitive {
+        /// The byte order of the primitive, or `None` if it is a single byte.
+        endianness: Option<Endianness>,
+    },
+    /// A struct with fields.
+    Struct {
+        /// The fields of the struct, in declaration order.
+        fields: Vec<FieldLayout>,
+    },
+    /// An enum with variants.
+    Enum {
+        /// The variants of the enum, in declaration order.
+        variants: Vec<VariantLayout>,
+    },
+    /// An array with a fixed length.
+    Array {
+        /// The layout of the array's elements.
+        element: Box<LayoutDescriptor>,
+        /// The length of the array.
+        len: usize,
+    },
+    /// A slice.
+    Slice {
+        /// The layout of the slice's elements.
+        element: Box<LayoutDescriptor>,
+    },
+    /// A type whose fields are implementation details, described by its type
+    /// parameters instead.
+    Opaque {
+        /// The layouts of the type's parameters.
+        params: Vec<LayoutDescriptor>,
+    },
+}
+
+/// A description of a field of an archived struct or enum variant.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldLayout {
+    name: String,
+    offset: usize,
+    layout: LayoutDescriptor,
+}
+
+impl FieldLayout {
+    /// Returns a new description of a field with the given name, offset, and
+    /// layout.
+    pub fn new(
+        name: impl Into<String>,
+        offset: usize,
+        layout: LayoutDescriptor,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            offset,
+            layout,
+        }
+    }
+
+    /// Returns the name of the field.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the offset of the field from the start of its struct or enum.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the layout of the field.
+    pub fn layout(&self) -> &LayoutDescriptor {
+        &self.layout
+    }
+}
+
+/// A description of a variant of an archived enum.
+///
+/// The fields of each variant are located after the enum's tag, and their
+/// offsets are relative to the start of the enum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantLayout {
+    name: String,
+    fields: Vec<FieldLayout>,
+}
+
+impl VariantLayout {
+    /// Returns a new description of a variant with the given name and fields.
+    pub fn new(name: impl Into<String>, fields: Vec<FieldLayout>) -> Self {
+        Self {
+            name: name.into(),
+            fields,
+        }
+    }
+
+    /// Returns the name of the variant.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the fields of the variant.
+    pub fn fields(&self) -> &[FieldLayout] {
+        &self.fields
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty: $name:literal),* $(,)?) => {
+        $(
+            impl DescribeLayout for $ty {
+                fn describe_layout() -> LayoutDescriptor {
+                    let endianness = if size_of::<Self>() > 1 {
+                        Some(Endianness::ARCHIVED)
+                    } else {
+                        None
+                    };
+                    LayoutDescriptor::new::<Self>(
+                        $name,
+                        LayoutKind::Primitive { endianness },
+                    )
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    (): "()",
+    bool: "bool",
+    i8: "i8",
+    u8: "u8",
+    NonZeroI8: "NonZeroI8",
+    NonZeroU8: "NonZeroU8",
+    ArchivedI16: "i16",
+    ArchivedI32: "i32",
+    ArchivedI64: "i64",
+    ArchivedI128: "i128",
+    ArchivedU16: "u16",
+    ArchivedU32: "u32",
+    ArchivedU64: "u64",
+    ArchivedU128: "u128",
+    ArchivedF32: "f32",
+    ArchivedF64: "f64",
+    ArchivedChar: "char",
+    ArchivedNonZeroI16: "NonZeroI16",
+    ArchivedNonZeroI32: "NonZeroI32",
+    ArchivedNonZeroI64: "NonZeroI64",
+    ArchivedNonZeroI128: "NonZeroI128",
+    ArchivedNonZeroU16: "NonZeroU16",
+    ArchivedNonZeroU32: "NonZeroU32",
+    ArchivedNonZeroU64: "NonZeroU64",
+    ArchivedNonZeroU128: "NonZeroU128",
+}
+
+impl DescribeLayout for PhantomPinned {
+    fn describe_layout() -> LayoutDescriptor {
+        LayoutDescriptor::new::<Self>(
+            "PhantomPinned",
+            LayoutKind::Struct { fields: Vec::new() },
+        )
+    }
+}
+
+impl<T: ?Sized> DescribeLayout for PhantomData<T> {
+    fn describe_layout() -> LayoutDescriptor {
+        LayoutDescriptor::new::<Self>(
+            "PhantomData",
+            LayoutKind::Struct { fields: Vec::new() },
+        )
+    }
+}
+
+impl DescribeLayout for str {
+    fn describe_layout() -> LayoutDescriptor {
+        LayoutDescriptor::new_unsized(
+            "str",
+            1,
+            LayoutKind::Slice {
+                element: Box::new(u8::describe_layout()),
+            },
+        )
+    }
+}
+
+impl<T: DescribeLayout, const N: usize> DescribeLayout for [T; N] {
+    fn describe_layout() -> LayoutDescriptor {
+        let element = T::describe_layout();
+        LayoutDescriptor::new::<Self>(
+            format!("[{}; {N}]", element.name),
+            LayoutKind::Array {
+                element: Box::new(element),
+                len: N,
+            },
+        )
+    }
+}
+
+impl<T: DescribeLayout> DescribeLayout for [T] {
+    fn describe_layout() -> LayoutDescriptor {
+        let element = T::describe_layout();
+        LayoutDescriptor::new_unsized(
+            format!("[{}]", element.name),
+            align_of::<T>(),
+            LayoutKind::Slice {
+                element: Box::new(element),
+            },
+        )
+    }
+}
+
+macro_rules! impl_tuple {
+    ($name:ident, $($ty:ident $index:tt),*) => {
+        impl<$($ty: DescribeLayout),*> DescribeLayout for $name<$($ty),*> {
+            fn describe_layout() -> LayoutDescriptor {
+                let fields = vec![$(
+                    FieldLayout::new(
+                        stringify!($index),
+                        offset_of!(Self, $index),
+                        $ty::describe_layout(),
+                    ),
+                )*];
+                let names = fields
+                    .iter()
+                    .map(|field| field.layout.name.as_str())
+                    .collect::<Vec<_>>();
+                let name = if names.len() == 1 {
+                    format!("({},)", names[0])
+                } else {
+                    format!("({})", names.join(", "))
+                };
+                LayoutDescriptor::new::<Self>(
+                    name,
+                    LayoutKind::Struct { fields },
+                )
+            }
+        }
+    };
+}
+
+impl_tuple!(ArchivedTuple1, T0 0);
+impl_tuple!(ArchivedTuple2, T0 0, T1 1);
+impl_tuple!(ArchivedTuple3, T0 0, T1 1, T2 2);
+impl_tuple!(ArchivedTuple4, T0 0, T1 1, T2 2, T3 3);
+impl_tuple!(ArchivedTuple5, T0 0, T1 1, T2 2, T3 3, T4 4);
+impl_tuple!(ArchivedTuple6, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5);
+impl_tuple!(ArchivedTuple7, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6);
+impl_tuple!(ArchivedTuple8, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7);
+impl_tuple!(
+    ArchivedTuple9, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8
+);
+impl_tuple!(
+    ArchivedTuple10, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9
+);
+impl_tuple!(
+    ArchivedTuple11, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9,
+    T10 10
+);
+impl_tuple!(
+    ArchivedTuple12, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9,
+    T10 10, T11 11
+);
+impl_tuple!(
+    ArchivedTuple13, T0 0, T1 1, T2 2, T3 3, T4 4, T5 5, T6 6, T7 7, T8 8, T9 9,
+    T10 10, T11 11, T12 12
+);
+
+/// Describes an archived container by its name and the layouts of its type
+/// parameters.
+fn opaque<T>(
+    name: &str,
+    params: Vec<LayoutDescriptor>,
+    extra: Option<usize>,
+) -> LayoutDescriptor {
+    let mut names = params
+        .iter()
+        .map(|param| param.name.to_string())
+        .collect::<Vec<_>>();
+    names.extend(extra.map(|extra| extra.to_string()));
+    LayoutDescriptor::new::<T>(
+        format!("{name}<{}>", names.join(", ")),
+        LayoutKind::Opaque { params },
+    )
+}
+
+impl<T: DescribeLayout> DescribeLayout for ArchivedOption<T> {
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>("Option", vec![T::describe_layout()], None)
+    }
+}
+
+impl<T: DescribeLayout, E: DescribeLayout> DescribeLayout
+    for ArchivedResult<T, E>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>(
+            "Result",
+            vec![T::describe_layout(), E::describe_layout()],
+            None,
+        )
+    }
+}
+
+impl<T: ArchivePointee + DescribeLayout + ?Sized> DescribeLayout
+    for ArchivedBox<T>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>("Box", vec![T::describe_layout()], None)
+    }
+}
+
+impl<T: ArchivePointee + DescribeLayout + ?Sized, F> DescribeLayout
+    for ArchivedRc<T, F>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>("Rc", vec![T::describe_layout()], None)
+    }
+}
+
+impl<T: DescribeLayout, O: DescribeLayout, L: DescribeLayout> DescribeLayout
+    for ArchivedVec<T, O, L>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>(
+            "Vec",
+            vec![
+                T::describe_layout(),
+                O::describe_layout(),
+                L::describe_layout(),
+            ],
+            None,
+        )
+    }
+}
+
+impl<const N: usize> DescribeLayout for ArchivedString<N> {
+    fn describe_layout() -> LayoutDescriptor {
+        LayoutDescriptor::new::<Self>(
+            "String",
+            LayoutKind::Opaque { params: Vec::new() },
+        )
+    }
+}
+
+impl<K: DescribeLayout, V: DescribeLayout, H> DescribeLayout
+    for ArchivedHashMap<K, V, H>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>(
+            "HashMap",
+            vec![K::describe_layout(), V::describe_layout()],
+            None,
+        )
+    }
+}
+
+impl<K: DescribeLayout, H> DescribeLayout for ArchivedHashSet<K, H> {
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>("HashSet", vec![K::describe_layout()], None)
+    }
+}
+
+impl<K: DescribeLayout, V: DescribeLayout, const E: usize> DescribeLayout
+    for ArchivedBTreeMap<K, V, E>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>(
+            "BTreeMap",
+            vec![K::describe_layout(), V::describe_layout()],
+            Some(E),
+        )
+    }
+}
+
+impl<K: DescribeLayout, const E: usize> DescribeLayout
+    for ArchivedBTreeSet<K, E>
+{
+    fn describe_layout() -> LayoutDescriptor {
+        opaque::<Self>("BTreeSet", vec![K::describe_layout()], Some(E))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::{offset_of, size_of};
+
+    use super::{layout_of, LayoutKind};
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        Archive,
+    };
+
+    #[derive(Archive)]
+    #[rkyv(crate, layout)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, layout)]
+    struct Example {
+        id: u8,
+        name: String,
+        points: Vec<Point>,
+        pair: (u16, Option<u8>),
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, layout)]
+    enum Shape {
+        Circle { radius: f32 },
+        Polygon(u8, Vec<Point>),
+        Empty,
+    }
+
+    #[test]
+    fn struct_layout() {
+        let layout = layout_of::<ArchivedExample>();
+        assert_eq!(layout.name(), "Example");
+        assert_eq!(layout.size(), Some(size_of::<ArchivedExample>()));
+
+        let LayoutKind::Struct { fields } = layout.kind() else {
+            panic!("expected a struct layout");
+        };
+        let offsets = fields.iter().map(|f| f.offset()).collect::<Vec<_>>();
+        assert_eq!(
+            offsets,
+            [
+                offset_of!(ArchivedExample, id),
+                offset_of!(ArchivedExample, name),
+                offset_of!(ArchivedExample, points),
+                offset_of!(ArchivedExample, pair),
+            ],
+        );
+        assert_eq!(fields[3].layout().name(), "(u16, Option<u8>)");
+    }
+
+    #[test]
+    fn enum_layout() {
+        let layout = layout_of::<ArchivedShape>();
+        let LayoutKind::Enum { variants } = layout.kind() else {
+            panic!("expected an enum layout");
+        };
+        let names = variants.iter().map(|v| v.name()).collect::<Vec<_>>();
+        assert_eq!(names, ["Circle", "Polygon", "Empty"]);
+
+        let polygon = variants[1].fields();
+        assert_eq!(polygon[0].name(), "0");
+        assert_eq!(polygon[0].offset(), 1);
+        assert!(polygon[1].offset() > polygon[0].offset());
+        assert!(variants[2].fields().is_empty());
+    }
+
+    #[cfg(not(any(
+        feature = "big_endian",
+        feature = "unaligned",
+        feature = "pointer_width_16",
+        feature = "pointer_width_64",
+    )))]
+    #[test]
+    fn display() {
+        let expected = "\
+Point (size 8, align 4)
+  +0 x: i32 (size 4, align 4, little-endian)
+  +4 y: i32 (size 4, align 4, little-endian)
+";
+        assert_eq!(layout_of::<ArchivedPoint>().to_string(), expected);
+
+        let expected = "\
+Shape (size 12, align 4)
+  Circle
+    +4 radius: f32 (size 4, align 4, little-endian)
+  Polygon
+    +1 0: u8 (size 1, align 1)
+    +4 1: Vec<Point, i32, u32> (size 8, align 4)
+      param 0: Point (size 8, align 4)
+        +0 x: i32 (size 4, align 4, little-endian)
+        +4 y: i32 (size 4, align 4, little-endian)
+      param 1: i32 (size 4, align 4, little-endian)
+      param 2: u32 (size 4, align 4, little-endian)
+  Empty
+";
+        assert_eq!(layout_of::<ArchivedShape>().to_string(), expected);
+    }
+}
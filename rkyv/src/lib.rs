@@ -88,6 +88,11 @@
 //! enabled. In general, libraries should be able to support all format control
 //! options if they use rkyv's exported types and aliases.
 //!
+//! Archives can be prefixed with a header which records the format control
+//! options they were written with, so that readers built with different
+//! options can reject them with a descriptive error. See the
+//! [`header`](crate::header) module for more information.
+//!
 //! #### Endianness
 //!
 //! If an endianness feature is not enabled, rkyv will use little-endian byte
@@ -98,6 +103,10 @@
 //! - `big_endian`: Forces data serialization to use big-endian byte ordering.
 //!   This optimizes serialized data for big-endian architectures.
 //!
+//! Archives can be tagged with their byte order so that readers can detect
+//! archives with a mismatched byte order at runtime. See the
+//! [`endian`](crate::endian) module for more information.
+//!
 //! #### Alignment
 //!
 //! If an alignment feature is not enabled, rkyv will use aligned primitives by
@@ -135,6 +144,13 @@
 //! - `std`: Enables standard library support. Enabled by default.
 //! - `bytecheck`: Enables data validation through `bytecheck`. Enabled by
 //!   default.
+//! - `mmap`: Enables `util::mmap` for accessing archives in memory-mapped
+//!   files and `util::shm` for sharing archives between processes through
+//!   shared memory. Implies `std`.
+//! - `lz4_flex`: Enables LZ4 compression for `api::high::to_bytes_compressed`
+//!   and friends. Implies `alloc`.
+//! - `zstd`: Enables zstd compression for `api::high::to_bytes_compressed` and
+//!   friends. Implies `std`.
 //!
 //! ### Crates
 //!
@@ -142,16 +158,45 @@
 //! crates should depend on rkyv and provide their own integration. Enabling and
 //! disabling these features does not change rkyv's serialized format.
 //!
+//! - [`arbitrary-1`](https://docs.rs/arbitrary/1): implements `Arbitrary` for
+//!   archived primitives like
+//!   [`ArchivedDuration`](time::ArchivedDuration) and enables round-trip
+//!   property tests in `testing`
 //! - [`arrayvec-0_7`](https://docs.rs/arrayvec/0.7)
+//! - [`bitvec-1`](https://docs.rs/bitvec/1): archives `BitVec` as an
+//!   [`ArchivedBitVec`](collections::bitvec::ArchivedBitVec)
 //! - [`bytes-1`](https://docs.rs/bytes/1)
+//! - [`ethnum-1`](https://docs.rs/ethnum/1): archives `U256` and `I256` as an
+//!   [`ArchivedU256`](fixed_int::ArchivedU256) and
+//!   [`ArchivedI256`](fixed_int::ArchivedI256)
+//! - [`futures-io-0_3`](https://docs.rs/futures-io/0.3): implements
+//!   [`AsyncWriter`](ser::AsyncWriter) for `AsyncWrite` types
 //! - [`hashbrown-0_14`](https://docs.rs/hashbrown/0.14)
 //! - [`hashbrown-0_15`](https://docs.rs/hashbrown/0.15)
 //! - [`indexmap-2`](https://docs.rs/indexmap/2)
+//! - [`ndarray-0_15`](https://docs.rs/ndarray/0.15): archives `ndarray` arrays
+//!   as an [`ArchivedArrayD`](collections::ndarray::ArchivedArrayD)
+//! - [`ndarray-0_16`](https://docs.rs/ndarray/0.16): archives `ndarray` arrays
+//!   as an [`ArchivedArrayD`](collections::ndarray::ArchivedArrayD)
+//! - [`num-bigint-0_4`](https://docs.rs/num-bigint/0.4): archives `BigUint`
+//!   and `BigInt` as an [`ArchivedBigUint`](big_int::ArchivedBigUint) and
+//!   [`ArchivedBigInt`](big_int::ArchivedBigInt)
+//! - [`primitive-types-0_13`](https://docs.rs/primitive-types/0.13): archives
+//!   `U128`, `U256`, and `U512` as an
+//!   [`ArchivedFixedUint`](fixed_int::ArchivedFixedUint) and fixed hashes as
+//!   byte arrays
+//! - [`serde-1`](https://docs.rs/serde/1): implements `serde::Serialize` for
+//!   archived types, see [`serde`](crate::serde)
+//! - [`serde_json-1`](https://docs.rs/serde_json/1): archives
+//!   `serde_json::Value` as a
+//!   [`dynamic::ArchivedValue`](dynamic::ArchivedValue)
 //! - [`smallvec-1`](https://docs.rs/smallvec/1)
 //! - [`smol_str-0_2`](https://docs.rs/smol_str/0.2)
 //! - [`smol_str-0_3`](https://docs.rs/smol_str/0.3)
 //! - [`thin-vec-0_2`](https://docs.rs/thin-vec/0.2)
 //! - [`tinyvec-1`](https://docs.rs/tinyvec/1)
+//! - [`tokio-util-0_7`](https://docs.rs/tokio-util/0.7): enables
+//!   [`util::codec`] for framing archives in `Framed` transports
 //! - [`triomphe-0_1`](https://docs.rs/triomphe/0.1)
 //! - [`uuid-1`](https://docs.rs/uuid/1)
 //!
@@ -216,13 +261,32 @@ mod alias;
 #[macro_use]
 mod _macros;
 pub mod api;
+pub mod big_int;
+pub mod bitpack;
+pub mod blob;
 pub mod boxed;
 pub mod collections;
+pub mod columns;
 pub mod de;
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+pub mod debug;
+#[cfg(feature = "alloc")]
+pub mod diff;
+#[cfg(feature = "alloc")]
+pub mod dynamic;
+pub mod endian;
 pub mod ffi;
+pub mod fixed_int;
 mod fmt;
 pub mod hash;
+pub mod header;
 mod impls;
+#[cfg(feature = "alloc")]
+pub mod iterative;
+#[cfg(feature = "alloc")]
+pub mod layout;
+pub mod max_size;
+pub mod native;
 pub mod net;
 pub mod niche;
 pub mod ops;
@@ -230,20 +294,30 @@ pub mod option;
 pub mod place;
 mod polyfill;
 pub mod primitive;
+pub mod projection;
+pub mod raw_archive;
 pub mod rc;
 pub mod rel_ptr;
 pub mod result;
 pub mod seal;
 pub mod ser;
+#[cfg(feature = "serde-1")]
+pub mod serde;
 mod simd;
+pub mod stable;
 pub mod string;
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+pub mod testing;
 pub mod time;
 pub mod traits;
 pub mod tuple;
+pub mod type_hash;
 pub mod util;
 #[cfg(feature = "bytecheck")]
 pub mod validation;
+pub mod varint;
 pub mod vec;
+pub mod version;
 pub mod with;
 
 // Exports
@@ -0,0 +1,356 @@
+//! Compile-time bounds on serialized sizes.
+//!
+//! [`MaxSize`] is implemented for types whose serialized representation never
+//! exceeds a fixed number of bytes. This includes primitives, arrays, tuples,
+//! options, and structs and enums of those types. It can be used to size
+//! static buffers at compile time, for example when serializing in `no_std`
+//! environments without an allocator.
+//!
+//! `MaxSize` can be implemented for archived types generated by
+//! `#[derive(Archive)]` with `#[rkyv(max_size)]`. Fields whose types are
+//! unbounded (like `String` and `Vec`) prevent the derive from compiling unless
+//! they use a wrapper which implements [`MaxSizeWith`], like
+//! [`Bounded`](crate::with::Bounded).
+//!
+//! # Example
+//!
+//! ```
+//! use core::mem::MaybeUninit;
+//!
+//! use rkyv::{
+//!     api::low::to_bytes_in_with_alloc,
+//!     max_size::MaxSize,
+//!     rancor::Failure,
+//!     ser::{allocator::SubAllocator, writer::Buffer},
+//!     util::Align,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(max_size)]
+//! struct Reading {
+//!     sensor: u16,
+//!     values: [f32; 4],
+//!     status: Option<Status>,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(max_size)]
+//! enum Status {
+//!     Ok,
+//!     Fault(u32),
+//! }
+//!
+//! let mut bytes = Align([MaybeUninit::<u8>::uninit(); Reading::MAX_SIZE]);
+//! let value = Reading {
+//!     sensor: 7,
+//!     values: [1.0, 2.0, 3.0, 4.0],
+//!     status: Some(Status::Fault(3)),
+//! };
+//! to_bytes_in_with_alloc::<_, _, Failure>(
+//!     &value,
+//!     Buffer::from(&mut *bytes),
+//!     SubAllocator::empty(),
+//! )
+//! .unwrap();
+//! ```
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::{align_of, size_of},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
+        NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8, NonZeroUsize,
+    },
+};
+
+use crate::{
+    varint::VarintValue,
+    with::{ArchiveWith, AsBox, Inline, InlineAsBox, Map, Skip, Varint},
+    Archive, Archived,
+};
+
+/// A type whose serialized representation has a bounded size.
+///
+/// Serializing a value first writes its dependencies (any data it points to)
+/// and then its archived representation. `MAX_DEPENDENCIES_SIZE` bounds the
+/// former, and `MAX_SIZE` bounds the total number of bytes written when the
+/// value is serialized as the root of an empty buffer.
+///
+/// This can be implemented for archived types generated by
+/// `#[derive(Archive)]` with `#[rkyv(max_size)]`.
+pub trait MaxSize: Archive {
+    /// The maximum number of bytes written while serializing the dependencies
+    /// of a value, including padding.
+    const MAX_DEPENDENCIES_SIZE: usize;
+
+    /// The maximum number of bytes written while serializing a value as the
+    /// root of an empty buffer, including padding.
+    const MAX_SIZE: usize =
+        round_up(Self::MAX_DEPENDENCIES_SIZE, align_of::<Self::Archived>())
+            + size_of::<Self::Archived>();
+}
+
+/// A wrapper which bounds the serialized size of the type it wraps.
+///
+/// This is the [`MaxSize`] counterpart for wrapper types.
+pub trait MaxSizeWith<F: ?Sized>: ArchiveWith<F> {
+    /// The maximum number of bytes written while serializing the dependencies
+    /// of a field with this wrapper, including padding.
+    const MAX_DEPENDENCIES_SIZE: usize;
+}
+
+/// Returns the maximum number of bytes written while serializing a slice of at
+/// most `len` values of `T` at any position.
+///
+/// This is useful for implementing [`MaxSize`] for bounded collections.
+pub const fn max_slice_size<T: MaxSize>(len: usize) -> usize {
+    len * T::MAX_DEPENDENCIES_SIZE + align_of::<Archived<T>>() - 1
+        + len * size_of::<Archived<T>>()
+}
+
+/// Returns the maximum number of bytes written while serializing a single
+/// value of `T` at any position.
+const fn max_boxed_size<T: MaxSize>() -> usize {
+    max_slice_size::<T>(1)
+}
+
+const fn round_up(size: usize, align: usize) -> usize {
+    (size + align - 1) & !(align - 1)
+}
+
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+macro_rules! impl_max_size {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl MaxSize for $ty {
+                const MAX_DEPENDENCIES_SIZE: usize = 0;
+            }
+        )*
+    };
+}
+
+impl_max_size!(
+    (),
+    bool,
+    char,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+    PhantomPinned,
+);
+
+impl<T: ?Sized> MaxSize for PhantomData<T> {
+    const MAX_DEPENDENCIES_SIZE: usize = 0;
+}
+
+impl<T: MaxSize, const N: usize> MaxSize for [T; N] {
+    const MAX_DEPENDENCIES_SIZE: usize = N * T::MAX_DEPENDENCIES_SIZE;
+}
+
+impl<T: MaxSize> MaxSize for Option<T> {
+    const MAX_DEPENDENCIES_SIZE: usize = T::MAX_DEPENDENCIES_SIZE;
+}
+
+impl<T: MaxSize, E: MaxSize> MaxSize for Result<T, E> {
+    const MAX_DEPENDENCIES_SIZE: usize =
+        max(T::MAX_DEPENDENCIES_SIZE, E::MAX_DEPENDENCIES_SIZE);
+}
+
+macro_rules! impl_tuple {
+    ($($ty:ident),*) => {
+        impl<$($ty: MaxSize),*> MaxSize for ($($ty,)*) {
+            const MAX_DEPENDENCIES_SIZE: usize =
+                0 $(+ $ty::MAX_DEPENDENCIES_SIZE)*;
+        }
+    };
+}
+
+impl_tuple!(T0);
+impl_tuple!(T0, T1);
+impl_tuple!(T0, T1, T2);
+impl_tuple!(T0, T1, T2, T3);
+impl_tuple!(T0, T1, T2, T3, T4);
+impl_tuple!(T0, T1, T2, T3, T4, T5);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
+
+#[cfg(feature = "alloc")]
+impl<T: MaxSize> MaxSize for crate::alloc::boxed::Box<T> {
+    const MAX_DEPENDENCIES_SIZE: usize = max_boxed_size::<T>();
+}
+
+#[cfg(feature = "arrayvec-0_7")]
+impl<T: MaxSize, const CAP: usize> MaxSize for arrayvec_0_7::ArrayVec<T, CAP> {
+    const MAX_DEPENDENCIES_SIZE: usize = max_slice_size::<T>(CAP);
+}
+
+#[cfg(feature = "alloc")]
+impl<T: MaxSize, const N: usize> MaxSizeWith<crate::alloc::vec::Vec<T>>
+    for crate::with::Bounded<N>
+{
+    const MAX_DEPENDENCIES_SIZE: usize = N * T::MAX_DEPENDENCIES_SIZE;
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize> MaxSizeWith<crate::alloc::string::String>
+    for crate::with::Bounded<N>
+{
+    const MAX_DEPENDENCIES_SIZE: usize = 0;
+}
+
+impl<F> MaxSizeWith<F> for Skip {
+    const MAX_DEPENDENCIES_SIZE: usize = 0;
+}
+
+impl<F: MaxSize> MaxSizeWith<&F> for Inline {
+    const MAX_DEPENDENCIES_SIZE: usize = F::MAX_DEPENDENCIES_SIZE;
+}
+
+impl<F: MaxSize> MaxSizeWith<&F> for InlineAsBox {
+    const MAX_DEPENDENCIES_SIZE: usize = max_boxed_size::<F>();
+}
+
+impl<F: MaxSize> MaxSizeWith<F> for AsBox {
+    const MAX_DEPENDENCIES_SIZE: usize = max_boxed_size::<F>();
+}
+
+impl<F: VarintValue> MaxSizeWith<F> for Varint {
+    const MAX_DEPENDENCIES_SIZE: usize = F::MAX_LEN;
+}
+
+impl<A, W: MaxSizeWith<A>> MaxSizeWith<Option<A>> for Map<W> {
+    const MAX_DEPENDENCIES_SIZE: usize = W::MAX_DEPENDENCIES_SIZE;
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use rancor::Failure;
+
+    use crate::{
+        alloc::{boxed::Box, vec, vec::Vec},
+        api::low::to_bytes_in_with_alloc,
+        max_size::MaxSize,
+        ser::{allocator::SubAllocator, writer::Buffer},
+        util::Align,
+        with::{AsBox, Skip},
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, max_size)]
+    struct Example {
+        a: u8,
+        b: Option<Box<(u64, [u16; 3])>>,
+        #[rkyv(with = AsBox)]
+        c: u32,
+        #[rkyv(with = Skip)]
+        _d: Vec<u8>,
+        e: Kind,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, max_size)]
+    enum Kind {
+        A,
+        B(u8),
+        C { values: Box<[u32; 8]> },
+    }
+
+    fn serialized_len<T>(value: &T) -> usize
+    where
+        T: for<'a> Serialize<
+            crate::api::low::LowSerializer<
+                Buffer<'a>,
+                SubAllocator<'static>,
+                Failure,
+            >,
+        >,
+    {
+        let mut bytes = Align([MaybeUninit::<u8>::uninit(); 1024]);
+        to_bytes_in_with_alloc::<_, _, Failure>(
+            value,
+            Buffer::from(&mut *bytes),
+            SubAllocator::empty(),
+        )
+        .unwrap()
+        .len()
+    }
+
+    #[test]
+    fn max_size_bounds() {
+        assert_eq!(u32::MAX_SIZE, 4);
+        assert_eq!(<[u16; 3]>::MAX_SIZE, 6);
+        assert_eq!(<(u8, Option<u32>)>::MAX_DEPENDENCIES_SIZE, 0);
+        assert_eq!(
+            Kind::MAX_DEPENDENCIES_SIZE,
+            Box::<[u32; 8]>::MAX_DEPENDENCIES_SIZE,
+        );
+
+        let values = [
+            Example {
+                a: 1,
+                b: None,
+                c: 2,
+                _d: Vec::new(),
+                e: Kind::A,
+            },
+            Example {
+                a: 1,
+                b: None,
+                c: 2,
+                _d: Vec::new(),
+                e: Kind::B(3),
+            },
+            Example {
+                a: 1,
+                b: Some(Box::new((2, [3, 4, 5]))),
+                c: 6,
+                _d: vec![7, 8, 9],
+                e: Kind::C {
+                    values: Box::new([10; 8]),
+                },
+            },
+        ];
+        for value in values.iter() {
+            assert!(serialized_len(value) <= Example::MAX_SIZE);
+        }
+    }
+}
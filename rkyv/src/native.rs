@@ -0,0 +1,530 @@
+//! Native-friendly views of archived values.
+//!
+//! Archived types are often awkward to consume directly: archived integers
+//! have to be converted with `to_native`, and archived strings, vecs, and
+//! options have their own wrapper types. [`AsNative`] converts archived values
+//! into the closest native equivalent which can be produced without copying:
+//!
+//! - Archived primitives (like `u32_le`) are converted to native primitives.
+//! - `ArchivedString` is viewed as a `&str`.
+//! - `ArchivedVec<T>` is viewed as a `&[T]`.
+//! - `ArchivedOption<T>` is viewed as an `Option<&T>`.
+//! - `ArchivedResult<T, E>` is viewed as a `Result<&T, &E>`.
+//! - `ArchivedBox<T>` and `ArchivedRc<T>` are viewed as a `&T`.
+//! - Other archived types, including those generated by `#[derive(Archive)]`,
+//!   are viewed as references to themselves.
+//!
+//! Archived `usize` and `isize` values are converted to the fixed-width
+//! integers they are archived as, which depend on the `pointer_width_*`
+//! features.
+//!
+//! `#[rkyv(getters)]` generates accessor methods on archived structs which
+//! return the native view of each field. Fields whose archived types don't
+//! implement `AsNative` can be excluded with `#[rkyv(skip_getter)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{access, rancor::Error, to_bytes, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(getters)]
+//! struct Example {
+//!     id: u32,
+//!     name: String,
+//!     tags: Vec<u16>,
+//!     origin: Option<Point>,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(getters)]
+//! struct Point {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! let value = Example {
+//!     id: 2,
+//!     name: "example".to_string(),
+//!     tags: vec![1, 2, 3],
+//!     origin: Some(Point { x: 1.0, y: 2.0 }),
+//! };
+//!
+//! let bytes = to_bytes::<Error>(&value).unwrap();
+//! let archived = access::<ArchivedExample, Error>(&bytes).unwrap();
+//!
+//! let id: u32 = archived.id();
+//! let name: &str = archived.name();
+//! assert_eq!(id, 2);
+//! assert_eq!(name, "example");
+//! assert_eq!(archived.tags(), &[1, 2, 3]);
+//! assert_eq!(archived.origin().unwrap().y(), 2.0);
+//! ```
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    num::{NonZeroI8, NonZeroU8},
+};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{
+            ArchivedHashMap, ArchivedHashSet, ArchivedIndexMap,
+            ArchivedIndexSet,
+        },
+    },
+    option::ArchivedOption,
+    rc::ArchivedRc,
+    rel_ptr::Offset,
+    rend::{unaligned::*, *},
+    result::ArchivedResult,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    vec::ArchivedVec,
+};
+
+/// An archived type which can be viewed as a native-friendly value.
+///
+/// See the [module docs](crate::native) for more information.
+pub trait AsNative {
+    /// The native-friendly view of the archived value.
+    type Native<'a>
+    where
+        Self: 'a;
+
+    /// Returns a native-friendly view of the archived value.
+    fn as_native(&self) -> Self::Native<'_>;
+}
+
+macro_rules! impl_copy {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl AsNative for $ty {
+                type Native<'a> = $ty;
+
+                #[inline]
+                fn as_native(&self) -> Self::Native<'_> {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+impl_copy!((), bool, i8, u8, NonZeroI8, NonZeroU8, PhantomPinned);
+
+impl<T: ?Sized> AsNative for PhantomData<T> {
+    type Native<'a>
+        = PhantomData<T>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        PhantomData
+    }
+}
+
+macro_rules! impl_rend {
+    ($native:ty: $($ty:ty),* $(,)?) => {
+        $(
+            impl AsNative for $ty {
+                type Native<'a> = $native;
+
+                #[inline]
+                fn as_native(&self) -> Self::Native<'_> {
+                    self.to_native()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_rends {
+    ($($native:ty: $le:ty, $be:ty, $ule:ty, $ube:ty);* $(;)?) => {
+        $(impl_rend!($native: $le, $be, $ule, $ube);)*
+    };
+}
+
+impl_rends! {
+    i16: i16_le, i16_be, i16_ule, i16_ube;
+    i32: i32_le, i32_be, i32_ule, i32_ube;
+    i64: i64_le, i64_be, i64_ule, i64_ube;
+    i128: i128_le, i128_be, i128_ule, i128_ube;
+    u16: u16_le, u16_be, u16_ule, u16_ube;
+    u32: u32_le, u32_be, u32_ule, u32_ube;
+    u64: u64_le, u64_be, u64_ule, u64_ube;
+    u128: u128_le, u128_be, u128_ule, u128_ube;
+    f32: f32_le, f32_be, f32_ule, f32_ube;
+    f64: f64_le, f64_be, f64_ule, f64_ube;
+    char: char_le, char_be, char_ule, char_ube;
+    core::num::NonZeroI16:
+        NonZeroI16_le, NonZeroI16_be, NonZeroI16_ule, NonZeroI16_ube;
+    core::num::NonZeroI32:
+        NonZeroI32_le, NonZeroI32_be, NonZeroI32_ule, NonZeroI32_ube;
+    core::num::NonZeroI64:
+        NonZeroI64_le, NonZeroI64_be, NonZeroI64_ule, NonZeroI64_ube;
+    core::num::NonZeroI128:
+        NonZeroI128_le, NonZeroI128_be, NonZeroI128_ule, NonZeroI128_ube;
+    core::num::NonZeroU16:
+        NonZeroU16_le, NonZeroU16_be, NonZeroU16_ule, NonZeroU16_ube;
+    core::num::NonZeroU32:
+        NonZeroU32_le, NonZeroU32_be, NonZeroU32_ule, NonZeroU32_ube;
+    core::num::NonZeroU64:
+        NonZeroU64_le, NonZeroU64_be, NonZeroU64_ule, NonZeroU64_ube;
+    core::num::NonZeroU128:
+        NonZeroU128_le, NonZeroU128_be, NonZeroU128_ule, NonZeroU128_ube;
+}
+
+/// An archived primitive which has the same representation as a native
+/// primitive when the archive's endianness matches the target's.
+///
+/// This is used by [`as_native_slice`] to view slices of archived primitives
+/// as slices of native primitives without copying.
+///
+/// # Safety
+///
+/// `Self` must have the same size as `Self::Native`. If `IS_NATIVE_ENDIAN` is
+/// `true`, then the bytes of every valid `Self` must also be a valid
+/// `Self::Native` representing the same value.
+pub unsafe trait NativeRepr: Sized {
+    /// The native primitive with the same representation.
+    type Native: Copy;
+
+    /// Whether the archived primitive has the same endianness as the target.
+    const IS_NATIVE_ENDIAN: bool;
+}
+
+macro_rules! impl_native_repr {
+    ($native:ty: $($ty:ty => $endian:literal),* $(,)?) => {
+        $(
+            // SAFETY: Archived primitives have the same size as their native
+            // counterparts and store the same bytes when their endianness
+            // matches the target.
+            unsafe impl NativeRepr for $ty {
+                type Native = $native;
+
+                const IS_NATIVE_ENDIAN: bool = cfg!(target_endian = $endian);
+            }
+        )*
+    };
+}
+
+macro_rules! impl_native_reprs {
+    ($($native:ty: $le:ty, $be:ty, $ule:ty, $ube:ty);* $(;)?) => {
+        $(
+            impl_native_repr!(
+                $native:
+                    $le => "little",
+                    $be => "big",
+                    $ule => "little",
+                    $ube => "big",
+            );
+        )*
+    };
+}
+
+impl_native_reprs! {
+    i16: i16_le, i16_be, i16_ule, i16_ube;
+    i32: i32_le, i32_be, i32_ule, i32_ube;
+    i64: i64_le, i64_be, i64_ule, i64_ube;
+    i128: i128_le, i128_be, i128_ule, i128_ube;
+    u16: u16_le, u16_be, u16_ule, u16_ube;
+    u32: u32_le, u32_be, u32_ule, u32_ube;
+    u64: u64_le, u64_be, u64_ule, u64_ube;
+    u128: u128_le, u128_be, u128_ule, u128_ube;
+    f32: f32_le, f32_be, f32_ule, f32_ube;
+    f64: f64_le, f64_be, f64_ule, f64_ube;
+    char: char_le, char_be, char_ule, char_ube;
+    core::num::NonZeroI16:
+        NonZeroI16_le, NonZeroI16_be, NonZeroI16_ule, NonZeroI16_ube;
+    core::num::NonZeroI32:
+        NonZeroI32_le, NonZeroI32_be, NonZeroI32_ule, NonZeroI32_ube;
+    core::num::NonZeroI64:
+        NonZeroI64_le, NonZeroI64_be, NonZeroI64_ule, NonZeroI64_ube;
+    core::num::NonZeroI128:
+        NonZeroI128_le, NonZeroI128_be, NonZeroI128_ule, NonZeroI128_ube;
+    core::num::NonZeroU16:
+        NonZeroU16_le, NonZeroU16_be, NonZeroU16_ule, NonZeroU16_ube;
+    core::num::NonZeroU32:
+        NonZeroU32_le, NonZeroU32_be, NonZeroU32_ule, NonZeroU32_ube;
+    core::num::NonZeroU64:
+        NonZeroU64_le, NonZeroU64_be, NonZeroU64_ule, NonZeroU64_ube;
+    core::num::NonZeroU128:
+        NonZeroU128_le, NonZeroU128_be, NonZeroU128_ule, NonZeroU128_ube;
+}
+
+/// Returns a slice of archived primitives as a slice of native primitives, or
+/// `None` if they don't share a representation.
+///
+/// This succeeds when the endianness of the archived primitives matches the
+/// target and the slice is suitably aligned for the native primitive. Aligned
+/// archived primitives always meet the alignment requirement, but unaligned
+/// ones (with the `unaligned` feature) may not.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{native::as_native_slice, rend::u32_le};
+///
+/// let values = [u32_le::from_native(1), u32_le::from_native(2)];
+/// if cfg!(target_endian = "little") {
+///     assert_eq!(as_native_slice(&values), Some([1, 2].as_slice()));
+/// } else {
+///     assert_eq!(as_native_slice(&values), None);
+/// }
+/// ```
+#[inline]
+pub fn as_native_slice<T: NativeRepr>(slice: &[T]) -> Option<&[T::Native]> {
+    if !T::IS_NATIVE_ENDIAN {
+        return None;
+    }
+
+    let ptr = slice.as_ptr().cast::<T::Native>();
+    if !ptr.is_aligned() {
+        return None;
+    }
+
+    // SAFETY: `T` has the same size as `T::Native` and shares its
+    // representation when the endianness matches the target, and we checked
+    // that the pointer is properly aligned.
+    Some(unsafe { core::slice::from_raw_parts(ptr, slice.len()) })
+}
+
+impl<const N: usize> AsNative for ArchivedString<N> {
+    type Native<'a> = &'a str;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.as_str()
+    }
+}
+
+impl<T, O: Offset, L: Offset> AsNative for ArchivedVec<T, O, L> {
+    type Native<'a>
+        = &'a [T]
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.as_slice()
+    }
+}
+
+impl<T> AsNative for ArchivedOption<T> {
+    type Native<'a>
+        = Option<&'a T>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.as_ref()
+    }
+}
+
+impl<T, E> AsNative for ArchivedResult<T, E> {
+    type Native<'a>
+        = Result<&'a T, &'a E>
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.as_ref()
+    }
+}
+
+impl<T: ArchivePointee + ?Sized> AsNative for ArchivedBox<T> {
+    type Native<'a>
+        = &'a T
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.get()
+    }
+}
+
+impl<T: ArchivePointee + ?Sized, F> AsNative for ArchivedRc<T, F> {
+    type Native<'a>
+        = &'a T
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self.get()
+    }
+}
+
+impl<T, const N: usize> AsNative for [T; N] {
+    type Native<'a>
+        = &'a [T; N]
+    where
+        Self: 'a;
+
+    #[inline]
+    fn as_native(&self) -> Self::Native<'_> {
+        self
+    }
+}
+
+macro_rules! impl_by_ref {
+    ($(impl<$($param:ident),* $(; $(const $c:ident: usize),*)?> for $ty:ty;)*) => {
+        $(
+            impl<$($param,)* $($(const $c: usize,)*)?> AsNative for $ty {
+                type Native<'a>
+                    = &'a Self
+                where
+                    Self: 'a;
+
+                #[inline]
+                fn as_native(&self) -> Self::Native<'_> {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_by_ref! {
+    impl<K, V; const E: usize> for ArchivedBTreeMap<K, V, E>;
+    impl<K; const E: usize> for ArchivedBTreeSet<K, E>;
+    impl<K, V, H> for ArchivedHashMap<K, V, H>;
+    impl<K, H> for ArchivedHashSet<K, H>;
+    impl<K, V, H> for ArchivedIndexMap<K, V, H>;
+    impl<K, H> for ArchivedIndexSet<K, H>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        api::test::to_archived, native::AsNative as _, Archive, Serialize,
+    };
+
+    #[test]
+    fn derived_getters() {
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate, getters)]
+        struct Inner {
+            a: i64,
+        }
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate, getters)]
+        struct Test {
+            a: u8,
+            b: u32,
+            c: char,
+            d: Option<u16>,
+            e: [u8; 2],
+            inner: Inner,
+            #[rkyv(skip_getter)]
+            skipped: core::time::Duration,
+        }
+
+        let value = Test {
+            a: 1,
+            b: 2,
+            c: 'x',
+            d: Some(4),
+            e: [5, 6],
+            inner: Inner { a: -7 },
+            skipped: core::time::Duration::from_secs(1),
+        };
+        to_archived(&value, |archived| {
+            assert_eq!(archived.a(), 1u8);
+            assert_eq!(archived.b(), 2u32);
+            assert_eq!(archived.c(), 'x');
+            assert_eq!(archived.d().map(|d| d.as_native()), Some(4u16));
+            assert_eq!(archived.e(), &[5, 6]);
+            assert_eq!(archived.inner().a(), -7i64);
+            assert_eq!(archived.skipped.as_secs(), 1);
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn alloc_getters() {
+        use crate::alloc::{boxed::Box, string::String, vec, vec::Vec};
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate, getters)]
+        struct Test(String, Vec<u32>, Box<str>, Result<u8, String>);
+
+        let value = Test(
+            String::from("a string which is stored out of line"),
+            vec![1, 2, 3],
+            "boxed".into(),
+            Err(String::from("error")),
+        );
+        to_archived(&value, |archived| {
+            assert_eq!(archived.field_0(), value.0);
+            assert_eq!(archived.field_1(), &[1, 2, 3]);
+            assert_eq!(archived.field_2(), "boxed");
+            assert_eq!(archived.field_3().unwrap_err(), "error");
+        });
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn vec_as_native_slice() {
+        use crate::alloc::{vec, vec::Vec};
+
+        let value: Vec<u32> = vec![1, 2, 3];
+        to_archived(&value, |archived| {
+            let native = archived.as_native_slice();
+            if cfg!(feature = "big_endian") == cfg!(target_endian = "big") {
+                assert_eq!(native, Some([1, 2, 3].as_slice()));
+            } else {
+                assert_eq!(native, None);
+            }
+        });
+    }
+
+    #[test]
+    fn as_native_slice_checks_endian_and_alignment() {
+        use crate::{
+            native::as_native_slice,
+            rend::{u32_be, u32_le, unaligned::u32_ule},
+        };
+
+        let le = [u32_le::from_native(1), u32_le::from_native(2)];
+        let be = [u32_be::from_native(1), u32_be::from_native(2)];
+        if cfg!(target_endian = "little") {
+            assert_eq!(as_native_slice(&le), Some([1, 2].as_slice()));
+            assert_eq!(as_native_slice(&be), None);
+        } else {
+            assert_eq!(as_native_slice(&le), None);
+            assert_eq!(as_native_slice(&be), Some([1, 2].as_slice()));
+        }
+
+        #[repr(C, align(4))]
+        struct Aligned([u32_ule; 3]);
+
+        let values = Aligned([
+            u32_ule::from_native(1),
+            u32_ule::from_native(2),
+            u32_ule::from_native(3),
+        ]);
+        let aligned = &values.0[..];
+        // SAFETY: `u32_ule` has an alignment of 1, so any byte offset into the
+        // array is a valid place for one.
+        let misaligned = unsafe {
+            core::slice::from_raw_parts(
+                aligned.as_ptr().cast::<u8>().add(1).cast::<u32_ule>(),
+                2,
+            )
+        };
+        if cfg!(target_endian = "little") {
+            assert_eq!(as_native_slice(aligned), Some([1, 2, 3].as_slice()));
+        }
+        assert_eq!(as_native_slice(misaligned), None);
+    }
+}
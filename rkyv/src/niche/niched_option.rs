@@ -132,7 +132,11 @@ impl<T, N: Niching<T> + ?Sized> NichedOption<T, N> {
         }
     }
 
-    pub(crate) fn munge_place(out: Place<Self>) -> Place<T> {
+    /// Returns a place for the value of a `NichedOption`.
+    ///
+    /// Resolving a `T` into the returned place makes the option `Some`, and
+    /// resolving a niched value with `N` makes it `None`.
+    pub fn munge_place(out: Place<Self>) -> Place<T> {
         munge!(let Self { repr, .. } = out);
 
         unsafe { repr.cast_unchecked::<T>() }
@@ -0,0 +1,219 @@
+//! Typed field offsets for accessing part of an archive.
+//!
+//! A [`FieldOffset`] records where a field is located inside of an archived
+//! type. Types which derive `Archive` with `#[rkyv(field_offsets)]` get an
+//! associated constant for each of their fields (e.g. `ArchivedFoo::BAR` for a
+//! field named `bar`, or `ArchivedFoo::FIELD_0` for the first field of a tuple
+//! struct). Offsets can be chained with [`then`](FieldOffset::then) to build a
+//! path to a nested field.
+//!
+//! `access_field` uses a field offset to validate and access a single field of
+//! an archive's root without validating the rest of the archive. This makes it
+//! cheap to read a small part of a large archive, like a version number in its
+//! header.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{api::high::access_field, rancor::Error, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(field_offsets)]
+//! struct Header {
+//!     version: u32,
+//!     name: String,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(field_offsets)]
+//! struct Document {
+//!     header: Header,
+//!     body: Vec<String>,
+//! }
+//!
+//! let value = Document {
+//!     header: Header {
+//!         version: 3,
+//!         name: "report".to_string(),
+//!     },
+//!     body: vec!["lots of text".to_string(); 1000],
+//! };
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//!
+//! // Only the version is validated, the name and body are never checked.
+//! let version = access_field::<_, _, Error>(
+//!     &bytes,
+//!     ArchivedDocument::HEADER.then(ArchivedHeader::VERSION),
+//! )
+//! .unwrap();
+//! assert_eq!(*version, 3);
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use crate::Portable;
+
+/// The offset of a field of type `F` inside of a `P`.
+///
+/// See the [module-level documentation](self) for more information.
+pub struct FieldOffset<P, F> {
+    offset: usize,
+    _phantom: PhantomData<fn(&P) -> &F>,
+}
+
+impl<P, F> Clone for FieldOffset<P, F> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P, F> Copy for FieldOffset<P, F> {}
+
+impl<P, F> fmt::Debug for FieldOffset<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldOffset")
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl<P: Portable, F: Portable> FieldOffset<P, F> {
+    /// Creates a new field offset from a number of bytes.
+    ///
+    /// # Safety
+    ///
+    /// Every `P` must contain a valid `F` located `offset` bytes from its
+    /// start.
+    pub const unsafe fn new_unchecked(offset: usize) -> Self {
+        Self {
+            offset,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the offset of the field in bytes.
+    pub const fn offset(self) -> usize {
+        self.offset
+    }
+
+    /// Chains this offset with the offset of a field inside of `F`, returning
+    /// the offset of that field inside of `P`.
+    pub const fn then<G: Portable>(
+        self,
+        next: FieldOffset<F, G>,
+    ) -> FieldOffset<P, G> {
+        FieldOffset {
+            offset: self.offset + next.offset,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the field of the given value.
+    pub fn get(self, parent: &P) -> &F {
+        let ptr = (parent as *const P).cast::<u8>().wrapping_add(self.offset);
+        // SAFETY: The caller of `new_unchecked` has guaranteed that every `P`
+        // contains a valid `F` at `offset`.
+        unsafe { &*ptr.cast::<F>() }
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use core::mem::{offset_of, size_of};
+
+    use rancor::{Error, Failure};
+
+    use crate::{
+        alloc::{string::String, vec, vec::Vec},
+        api::high::{access, access_field, to_bytes},
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, field_offsets)]
+    struct Header {
+        version: u32,
+        name: String,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, field_offsets)]
+    struct Document {
+        header: Header,
+        body: Vec<u64>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, field_offsets)]
+    struct Pair<T>(u8, T);
+
+    fn document() -> Document {
+        Document {
+            header: Header {
+                version: 3,
+                name: String::from("a name which is stored out of line"),
+            },
+            body: vec![1, 2, 3, 4],
+        }
+    }
+
+    #[test]
+    fn field_offsets() {
+        assert_eq!(
+            ArchivedDocument::HEADER.offset(),
+            offset_of!(ArchivedDocument, header),
+        );
+        assert_eq!(
+            ArchivedDocument::HEADER.then(ArchivedHeader::NAME).offset(),
+            offset_of!(ArchivedDocument, header)
+                + offset_of!(ArchivedHeader, name),
+        );
+        assert_eq!(
+            ArchivedPair::<u64>::FIELD_1.offset(),
+            offset_of!(ArchivedPair<u64>, 1),
+        );
+    }
+
+    #[test]
+    fn access_nested_field() {
+        let bytes = to_bytes::<Error>(&document()).unwrap();
+
+        let header =
+            access_field::<_, _, Error>(&bytes, ArchivedDocument::HEADER)
+                .unwrap();
+        assert_eq!(header.version, 3);
+        assert_eq!(ArchivedHeader::NAME.get(header), header.name.as_str());
+
+        let name = access_field::<_, _, Error>(
+            &bytes,
+            ArchivedDocument::HEADER.then(ArchivedHeader::NAME),
+        )
+        .unwrap();
+        assert_eq!(name, "a name which is stored out of line");
+    }
+
+    #[test]
+    fn only_field_is_validated() {
+        let mut bytes = to_bytes::<Error>(&document()).unwrap();
+
+        // Corrupt the relative pointer of the body.
+        let root = bytes.len() - size_of::<ArchivedDocument>();
+        let body = root + ArchivedDocument::BODY.offset();
+        bytes[body..body + 4].copy_from_slice(&[0xff; 4]);
+
+        assert!(access::<ArchivedDocument, Failure>(&bytes).is_err());
+        let version = access_field::<_, _, Failure>(
+            &bytes,
+            ArchivedDocument::HEADER.then(ArchivedHeader::VERSION),
+        )
+        .unwrap();
+        assert_eq!(*version, 3);
+        access_field::<_, _, Failure>(&bytes, ArchivedDocument::BODY)
+            .unwrap_err();
+
+        // The root must still fit in the buffer.
+        access_field::<_, _, Failure>(&bytes[..8], ArchivedHeader::VERSION)
+            .unwrap_err();
+    }
+}
@@ -0,0 +1,421 @@
+//! Archives embedded in other archives.
+//!
+//! An [`ArchivedRawArchive<T>`] stores a complete archive of a `T` as a run of
+//! bytes inside of another archive. Validating the outer archive only checks
+//! that the bytes are in bounds. The embedded archive is validated when it is
+//! accessed with [`access`](ArchivedRawArchive::access), so a message can be
+//! made of a header which is always read and a payload which is only validated
+//! when it is needed.
+//!
+//! Embedded archives can be serialized from a value with the
+//! [`AsRawArchive`](crate::with::AsRawArchive) wrapper, or from bytes which
+//! were already serialized with a [`RawArchive`].
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     access, rancor::Error, to_bytes, with::AsRawArchive, Archive,
+//!     Deserialize, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! struct Payload {
+//!     values: Vec<u32>,
+//! }
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! struct Envelope {
+//!     kind: u8,
+//!     #[rkyv(with = AsRawArchive)]
+//!     payload: Payload,
+//! }
+//!
+//! let value = Envelope {
+//!     kind: 1,
+//!     payload: Payload {
+//!         values: vec![1, 2, 3],
+//!     },
+//! };
+//! let bytes = to_bytes::<Error>(&value).unwrap();
+//!
+//! // Only the envelope is validated here.
+//! let envelope = access::<ArchivedEnvelope, Error>(&bytes).unwrap();
+//! assert_eq!(envelope.kind, 1);
+//!
+//! // The payload is validated when it is accessed.
+//! let payload = envelope.payload.access::<Error>().unwrap();
+//! assert_eq!(payload.values, [1, 2, 3]);
+//! ```
+
+use core::{fmt, marker::PhantomData};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{
+    ser::{Writer, WriterExt as _},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Place, Portable,
+};
+
+/// The alignment of the bytes of an embedded archive.
+///
+/// This is the alignment of [`AlignedVec`](crate::util::AlignedVec), which is
+/// the alignment that archives are serialized with by default.
+pub const RAW_ARCHIVE_ALIGN: usize = 16;
+
+/// An archive of a `T` embedded in another archive.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Portable)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedRawArchive<T> {
+    bytes: ArchivedVec<u8>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> ArchivedRawArchive<T> {
+    /// Returns the bytes of the embedded archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Writes the given bytes as an embedded archive and returns a resolver
+    /// for them.
+    ///
+    /// The bytes are aligned to [`RAW_ARCHIVE_ALIGN`], so `bytes` must be an
+    /// archive which can be accessed at that alignment.
+    pub fn serialize_from_bytes<S>(
+        bytes: &[u8],
+        serializer: &mut S,
+    ) -> Result<RawArchiveResolver, S::Error>
+    where
+        S: Fallible + Writer + ?Sized,
+    {
+        let pos = serializer.align(RAW_ARCHIVE_ALIGN)?;
+        serializer.write(bytes)?;
+        Ok(RawArchiveResolver {
+            pos,
+            len: bytes.len(),
+        })
+    }
+
+    /// Resolves an embedded archive from the given resolver.
+    pub fn resolve_from_resolver(
+        resolver: RawArchiveResolver,
+        out: Place<Self>,
+    ) {
+        munge!(let ArchivedRawArchive { bytes, _phantom: _ } = out);
+        ArchivedVec::resolve_from_len(
+            resolver.len,
+            VecResolver::from_pos(resolver.pos),
+            bytes,
+        );
+    }
+}
+
+impl<T: Archive> ArchivedRawArchive<T> {
+    /// Accesses the embedded archive without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The embedded bytes must represent a valid archived `T`.
+    pub unsafe fn access_unchecked(&self) -> &T::Archived {
+        // SAFETY: The caller has guaranteed that the embedded bytes represent
+        // a valid archived `T`.
+        unsafe { crate::api::access_unchecked::<T::Archived>(self.as_bytes()) }
+    }
+}
+
+#[cfg(all(feature = "bytecheck", feature = "alloc"))]
+impl<T: Archive> ArchivedRawArchive<T> {
+    /// Validates and accesses the embedded archive.
+    ///
+    /// The embedded archive is validated each time it is accessed.
+    pub fn access<E>(&self) -> Result<&T::Archived, E>
+    where
+        T::Archived: for<'a> bytecheck::CheckBytes<
+            crate::api::high::HighValidator<'a, E>,
+        >,
+        E: rancor::Source,
+    {
+        crate::api::high::access::<T::Archived, E>(self.as_bytes())
+    }
+}
+
+impl<T> fmt::Debug for ArchivedRawArchive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedRawArchive")
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+/// The resolver for an [`ArchivedRawArchive`].
+#[derive(Debug)]
+pub struct RawArchiveResolver {
+    pos: usize,
+    len: usize,
+}
+
+#[cfg(feature = "alloc")]
+mod owned {
+    use core::{fmt, marker::PhantomData};
+
+    use rancor::{Fallible, Source};
+
+    use super::{ArchivedRawArchive, RawArchiveResolver};
+    use crate::{
+        api::high::{to_bytes, HighSerializer},
+        ser::{allocator::ArenaHandle, Writer},
+        util::AlignedVec,
+        Archive, Deserialize, Place, Serialize,
+    };
+
+    /// An already-serialized archive of a `T`.
+    ///
+    /// A `RawArchive` is archived as an [`ArchivedRawArchive`] by copying its
+    /// bytes into the archive, so a value which was serialized once can be
+    /// embedded in many archives.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rkyv::{
+    ///     access,
+    ///     raw_archive::{ArchivedRawArchive, RawArchive},
+    ///     rancor::Error,
+    ///     to_bytes,
+    /// };
+    ///
+    /// let payload = RawArchive::new::<Error>(&"hello world".to_string())
+    ///     .unwrap();
+    ///
+    /// let bytes = to_bytes::<Error>(&vec![payload.clone(), payload]).unwrap();
+    /// let archived = access::<
+    ///     rkyv::vec::ArchivedVec<ArchivedRawArchive<String>>,
+    ///     Error,
+    /// >(&bytes)
+    /// .unwrap();
+    /// assert_eq!(archived[1].access::<Error>().unwrap(), "hello world");
+    /// ```
+    pub struct RawArchive<T> {
+        bytes: AlignedVec,
+        _phantom: PhantomData<fn() -> T>,
+    }
+
+    impl<T> RawArchive<T> {
+        /// Serializes the given value to a new `RawArchive`.
+        pub fn new<E>(value: &T) -> Result<Self, E>
+        where
+            T: for<'a> Serialize<
+                HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+            >,
+            E: Source,
+        {
+            Ok(Self {
+                bytes: to_bytes(value)?,
+                _phantom: PhantomData,
+            })
+        }
+
+        /// Creates a `RawArchive` from the bytes of an archive without
+        /// validating them.
+        ///
+        /// # Safety
+        ///
+        /// `bytes` must represent a valid archived `T`.
+        pub unsafe fn from_bytes_unchecked(bytes: AlignedVec) -> Self {
+            Self {
+                bytes,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Returns the bytes of the archive.
+        pub fn as_bytes(&self) -> &[u8] {
+            &self.bytes
+        }
+
+        /// Consumes the `RawArchive` and returns the bytes of the archive.
+        pub fn into_bytes(self) -> AlignedVec {
+            self.bytes
+        }
+    }
+
+    #[cfg(feature = "bytecheck")]
+    impl<T: Archive> RawArchive<T> {
+        /// Creates a `RawArchive` from the bytes of an archive after
+        /// validating them.
+        pub fn from_bytes<E>(bytes: AlignedVec) -> Result<Self, E>
+        where
+            T::Archived: for<'a> bytecheck::CheckBytes<
+                crate::api::high::HighValidator<'a, E>,
+            >,
+            E: Source,
+        {
+            crate::api::high::access::<T::Archived, E>(&bytes)?;
+            Ok(Self {
+                bytes,
+                _phantom: PhantomData,
+            })
+        }
+
+        /// Validates and accesses the archive.
+        pub fn access<E>(&self) -> Result<&T::Archived, E>
+        where
+            T::Archived: for<'a> bytecheck::CheckBytes<
+                crate::api::high::HighValidator<'a, E>,
+            >,
+            E: Source,
+        {
+            crate::api::high::access::<T::Archived, E>(&self.bytes)
+        }
+    }
+
+    impl<T> Clone for RawArchive<T> {
+        fn clone(&self) -> Self {
+            Self {
+                bytes: self.bytes.clone(),
+                _phantom: PhantomData,
+            }
+        }
+    }
+
+    impl<T> fmt::Debug for RawArchive<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("RawArchive")
+                .field("len", &self.bytes.len())
+                .finish()
+        }
+    }
+
+    impl<T> Archive for RawArchive<T> {
+        type Archived = ArchivedRawArchive<T>;
+        type Resolver = RawArchiveResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            ArchivedRawArchive::resolve_from_resolver(resolver, out);
+        }
+    }
+
+    impl<T, S> Serialize<S> for RawArchive<T>
+    where
+        S: Fallible + Writer + ?Sized,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedRawArchive::<T>::serialize_from_bytes(
+                &self.bytes,
+                serializer,
+            )
+        }
+    }
+
+    impl<T, D> Deserialize<RawArchive<T>, D> for ArchivedRawArchive<T>
+    where
+        D: Fallible + ?Sized,
+    {
+        fn deserialize(&self, _: &mut D) -> Result<RawArchive<T>, D::Error> {
+            let mut bytes = AlignedVec::with_capacity(self.as_bytes().len());
+            bytes.extend_from_slice(self.as_bytes());
+            Ok(RawArchive {
+                bytes,
+                _phantom: PhantomData,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub use self::owned::RawArchive;
+
+#[cfg(all(test, feature = "bytecheck", feature = "alloc"))]
+mod tests {
+    use rancor::{Error, Failure};
+
+    use super::{ArchivedRawArchive, RawArchive, RAW_ARCHIVE_ALIGN};
+    use crate::{
+        access,
+        alloc::{string::String, vec, vec::Vec},
+        deserialize, to_bytes,
+        with::AsRawArchive,
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate)]
+    struct Envelope {
+        kind: u8,
+        #[rkyv(with = AsRawArchive)]
+        payload: Vec<String>,
+    }
+
+    #[test]
+    fn embedded_archive() {
+        let value = Envelope {
+            kind: 3,
+            payload: vec!["a string which is stored out of line".into()],
+        };
+        let bytes = to_bytes::<Error>(&value).unwrap();
+        let archived = access::<ArchivedEnvelope, Error>(&bytes).unwrap();
+        let embedded = archived.payload.as_bytes();
+        assert_eq!(embedded.as_ptr() as usize % RAW_ARCHIVE_ALIGN, 0);
+        assert_eq!(
+            archived.payload.access::<Error>().unwrap()[0],
+            value.payload[0]
+        );
+
+        let deserialized = deserialize::<Envelope, Error>(archived).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    fn lazy_validation() {
+        let value = Envelope {
+            kind: 3,
+            payload: vec!["a string which is stored out of line".into()],
+        };
+        let mut bytes = to_bytes::<Error>(&value).unwrap();
+
+        // Corrupt the length of the embedded string.
+        let archived = access::<ArchivedEnvelope, Error>(&bytes).unwrap();
+        let start = archived.payload.as_bytes().as_ptr() as usize
+            - bytes.as_ptr() as usize;
+        let len = archived.payload.as_bytes().len();
+        for byte in &mut bytes[start..start + len] {
+            *byte = 0xff;
+        }
+
+        let archived = access::<ArchivedEnvelope, Failure>(&bytes).unwrap();
+        assert_eq!(archived.kind, 3);
+        assert!(archived.payload.access::<Failure>().is_err());
+        assert!(deserialize::<Envelope, Failure>(archived).is_err());
+    }
+
+    #[test]
+    fn raw_archive() {
+        let raw = RawArchive::<Vec<u32>>::new::<Error>(&vec![1, 2, 3]).unwrap();
+        let bytes = to_bytes::<Error>(&raw).unwrap();
+        let archived =
+            access::<ArchivedRawArchive<Vec<u32>>, Error>(&bytes).unwrap();
+        assert_eq!(archived.access::<Error>().unwrap(), &[1, 2, 3][..]);
+
+        let deserialized =
+            deserialize::<RawArchive<Vec<u32>>, Error>(archived).unwrap();
+        assert_eq!(deserialized.as_bytes(), raw.as_bytes());
+        assert!(RawArchive::<Vec<u32>>::from_bytes::<Failure>(
+            raw.into_bytes()
+        )
+        .is_ok());
+    }
+}
@@ -35,6 +35,13 @@ impl Flavor for ArcFlavor {
     const ALLOW_CYCLES: bool = false;
 }
 
+/// The flavor type for strings interned with [`Intern`](crate::with::Intern).
+pub struct InternFlavor;
+
+impl Flavor for InternFlavor {
+    const ALLOW_CYCLES: bool = false;
+}
+
 /// An archived `Rc`.
 ///
 /// This is a thin wrapper around a [`RelPtr`] to the archived type paired with
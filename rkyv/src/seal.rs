@@ -23,6 +23,10 @@ use crate::traits::NoUndef;
 /// that the sealed value is dropped before its backing memory is reused. This
 /// means that creating a `Seal` from a mutable reference is completely safe to
 /// do.
+///
+/// Types which derive `Archive` with `#[rkyv(seal)]` also get a projection
+/// method for each of their fields, so `ArchivedFoo::bar_seal(foo)` projects a
+/// `Seal<'_, ArchivedFoo>` to its `bar` field without using `munge`.
 pub struct Seal<'a, T: ?Sized> {
     inner: &'a mut T,
 }
@@ -117,3 +121,43 @@ impl<'a, T> Seal<'a, [T]> {
         Seal::new(&mut ptr[index])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{api::test::to_archived, Archive, Serialize};
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, seal)]
+    struct Example {
+        a: u32,
+        r#type: [u16; 2],
+        pair: Pair,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, seal)]
+    struct Pair(u8, i64);
+
+    #[test]
+    fn seal_projections() {
+        let value = Example {
+            a: 1,
+            r#type: [2, 3],
+            pair: Pair(4, 5),
+        };
+
+        to_archived(&value, |mut archived| {
+            *ArchivedExample::a_seal(archived.as_mut()) = 10.into();
+            ArchivedExample::type_seal(archived.as_mut())[1] = 30.into();
+            let mut pair = ArchivedExample::pair_seal(archived.as_mut());
+            *ArchivedPair::field_0_seal(pair.as_mut()) = 40;
+            *ArchivedPair::field_1_seal(pair) = 50.into();
+
+            assert_eq!(archived.a, 10);
+            assert_eq!(archived.r#type[0], 2);
+            assert_eq!(archived.r#type[1], 30);
+            assert_eq!(archived.pair.0, 40);
+            assert_eq!(archived.pair.1, 50);
+        });
+    }
+}
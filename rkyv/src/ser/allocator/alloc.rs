@@ -71,6 +71,7 @@ impl Block {
 /// global allocations, which can save a considerable amount of time.
 pub struct Arena {
     head_ptr: NonNull<Block>,
+    peak_retained: usize,
 }
 
 // SAFETY: Arena is safe to send to other threads
@@ -99,13 +100,18 @@ impl Arena {
     pub fn with_capacity(cap: usize) -> Self {
         let head_size = (cap + size_of::<Block>()).next_power_of_two();
         let head_ptr = Block::alloc(head_size);
-        Self { head_ptr }
+        Self {
+            head_ptr,
+            peak_retained: head_size,
+        }
     }
 
     /// Cleans up allocated blocks which are no longer in use.
     ///
     /// The arena is automatically shrunk by [`acquire`](Self::acquire).
     pub fn shrink(&mut self) -> usize {
+        self.peak_retained = usize::max(self.peak_retained, self.retained());
+
         let (mut current_ptr, mut current_size) = {
             let head = unsafe { self.head_ptr.as_ref() };
             (head.next_ptr, head.next_size)
@@ -147,6 +153,56 @@ impl Arena {
         current_size - size_of::<Block>()
     }
 
+    /// Cleans up allocated blocks which are no longer in use, then frees the
+    /// remaining block if it has more than `max_capacity` bytes of capacity.
+    ///
+    /// This bounds the memory that an arena retains after serializing an
+    /// unusually large value. Returns the capacity of the arena afterward,
+    /// which is at most `max_capacity`.
+    pub fn shrink_to(&mut self, max_capacity: usize) -> usize {
+        let capacity = self.shrink();
+        if capacity <= max_capacity {
+            return capacity;
+        }
+
+        let peak_retained = self.peak_retained;
+        *self = Self {
+            head_ptr: Block::alloc(size_of::<Block>() + max_capacity),
+            peak_retained,
+        };
+        max_capacity
+    }
+
+    /// Returns the total size of all of the blocks in the arena.
+    fn retained(&self) -> usize {
+        let mut retained = 0;
+        let mut current_ptr = self.head_ptr;
+        loop {
+            let current = unsafe { current_ptr.as_ref() };
+            retained += current.next_size;
+            current_ptr = current.next_ptr;
+            if current_ptr == self.head_ptr {
+                break retained;
+            }
+        }
+    }
+
+    /// Returns statistics about the memory used by the arena.
+    pub fn stats(&self) -> ArenaStats {
+        let retained = self.retained();
+        ArenaStats {
+            capacity: self.capacity(),
+            retained,
+            peak_retained: usize::max(self.peak_retained, retained),
+        }
+    }
+
+    /// Resets the peak memory usage of the arena to the memory it currently
+    /// retains.
+    pub fn reset_peak(&mut self) {
+        self.peak_retained = self.retained();
+    }
+
     /// Returns the available capacity of the arena.
     pub fn capacity(&self) -> usize {
         let mut current_ptr = self.head_ptr;
@@ -174,6 +230,8 @@ impl Arena {
     }
 
     /// Consumes the `Arena`, returning a raw pointer.
+    ///
+    /// The peak memory usage of the arena is not preserved.
     pub fn into_raw(self) -> NonNull<()> {
         let this = ManuallyDrop::new(self);
         this.head_ptr.cast()
@@ -187,9 +245,12 @@ impl Arena {
     /// ownership over the pointer, and so `from_raw` must not be called on the
     /// same pointer more than once.
     pub unsafe fn from_raw(raw: NonNull<()>) -> Self {
-        Self {
+        let mut result = Self {
             head_ptr: raw.cast(),
-        }
+            peak_retained: 0,
+        };
+        result.reset_peak();
+        result
     }
 }
 
@@ -199,6 +260,20 @@ impl Default for Arena {
     }
 }
 
+/// Statistics about the memory used by an [`Arena`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArenaStats {
+    /// The number of bytes the arena can allocate before it needs to allocate
+    /// another block.
+    pub capacity: usize,
+    /// The total number of bytes the arena currently holds, including
+    /// bookkeeping.
+    pub retained: usize,
+    /// The largest number of bytes the arena has held at once, including
+    /// bookkeeping.
+    pub peak_retained: usize,
+}
+
 /// A handle which can allocate within an arena.
 pub struct ArenaHandle<'a> {
     tail_ptr: NonNull<Block>,
@@ -275,7 +350,10 @@ mod tests {
     use crate::{
         alloc::{string::ToString, vec},
         api::high::to_bytes_in_with_alloc,
-        ser::{allocator::Arena, Allocator},
+        ser::{
+            allocator::{Arena, ArenaStats},
+            Allocator,
+        },
         util::AlignedVec,
     };
 
@@ -320,4 +398,48 @@ mod tests {
                 .always_ok();
         }
     }
+
+    #[test]
+    fn shrink_to() {
+        let mut arena = Arena::new();
+        let initial = arena.stats();
+        assert_eq!(initial.capacity, arena.capacity());
+        assert_eq!(initial.retained, initial.peak_retained);
+
+        let value =
+            vec!["a string which is stored out of line".to_string(); 1024];
+        to_bytes_in_with_alloc::<_, _, Panic>(
+            &value,
+            AlignedVec::<16>::new(),
+            arena.acquire(),
+        )
+        .unwrap();
+
+        let ArenaStats {
+            capacity,
+            retained,
+            peak_retained,
+        } = arena.stats();
+        assert!(capacity > Arena::DEFAULT_CAPACITY);
+        assert!(peak_retained >= retained);
+        assert!(peak_retained > initial.peak_retained);
+
+        assert_eq!(arena.shrink_to(capacity), capacity);
+        assert_eq!(arena.shrink_to(100), 100);
+        assert_eq!(arena.capacity(), 100);
+
+        let stats = arena.stats();
+        assert!(stats.retained < retained);
+        assert_eq!(stats.peak_retained, peak_retained);
+
+        arena.reset_peak();
+        assert_eq!(arena.stats().peak_retained, arena.stats().retained);
+
+        to_bytes_in_with_alloc::<_, _, Panic>(
+            &value,
+            AlignedVec::<16>::new(),
+            arena.acquire(),
+        )
+        .unwrap();
+    }
 }
@@ -3,13 +3,14 @@
 #[cfg(feature = "alloc")]
 mod alloc;
 mod core;
+mod tail;
 
 use ::core::{alloc::Layout, ptr::NonNull};
 use rancor::{Fallible, Strategy};
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
-pub use self::core::*;
+pub use self::{core::*, tail::*};
 
 /// A serializer that can allocate scratch space.
 ///
@@ -63,10 +64,13 @@ unsafe impl<T: Allocator<E>, E> Allocator<E> for Strategy<T, E> {
 }
 
 /// Statistics for the allocations which occurred during serialization.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct AllocationStats {
     bytes_allocated: usize,
     allocations: usize,
+    /// Returns the total number of allocations made, including allocations
+    /// which have since been freed.
+    pub total_allocations: usize,
     /// Returns the maximum number of bytes that were concurrently allocated.
     pub max_bytes_allocated: usize,
     /// Returns the maximum number of concurrent allocations.
@@ -100,6 +104,7 @@ impl AllocationStats {
     fn push(&mut self, layout: Layout) {
         self.bytes_allocated += layout.size();
         self.allocations += 1;
+        self.total_allocations += 1;
         self.max_bytes_allocated =
             usize::max(self.bytes_allocated, self.max_bytes_allocated);
         self.max_allocations =
@@ -128,6 +133,7 @@ impl<T> AllocationTracker<T> {
             stats: AllocationStats {
                 bytes_allocated: 0,
                 allocations: 0,
+                total_allocations: 0,
                 max_bytes_allocated: 0,
                 max_allocations: 0,
                 max_alignment: 1,
@@ -135,6 +141,11 @@ impl<T> AllocationTracker<T> {
         }
     }
 
+    /// Returns the allocation stats accumulated so far.
+    pub fn stats(&self) -> &AllocationStats {
+        &self.stats
+    }
+
     /// Returns the allocation stats accumulated during serialization.
     pub fn into_stats(self) -> AllocationStats {
         self.stats
@@ -240,6 +251,7 @@ mod tests {
         let stats = track_serialize(&vec![vec![1, 2], vec![3, 4]]);
         assert_ne!(stats.max_bytes_allocated, 0);
         assert_eq!(stats.max_allocations, 1);
+        assert_eq!(stats.total_allocations, 1);
         assert_ne!(stats.min_arena_capacity(), 0);
     }
 }
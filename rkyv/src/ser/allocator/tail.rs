@@ -0,0 +1,297 @@
+use core::{
+    alloc::Layout,
+    cell::Cell,
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::Deref,
+    ptr::{copy_nonoverlapping, slice_from_raw_parts_mut, NonNull},
+    slice,
+};
+
+use rancor::{fail, Source};
+
+use crate::ser::{Allocator, Positional, Writer};
+
+#[derive(Debug)]
+struct SplitBufferFull {
+    request: usize,
+    len: usize,
+    scratch: usize,
+    cap: usize,
+}
+
+impl fmt::Display for SplitBufferFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "not enough space for a request of {} bytes: {} bytes are written \
+             and {} bytes are used for scratch space in a buffer of {} bytes",
+            self.request,
+            self.len,
+            self.cap - self.scratch,
+            self.cap,
+        )
+    }
+}
+
+impl Error for SplitBufferFull {}
+
+/// A fixed-size buffer which serialized bytes are written to and scratch space
+/// is allocated from.
+///
+/// Bytes are written to the front of the buffer with a [`SplitWriter`], and
+/// scratch space is allocated from the back of the buffer with a
+/// [`TailAllocator`]. This allows serializing types which need scratch space
+/// (like `Vec` and `HashMap`) without any heap allocations or a separate
+/// scratch buffer. Serialization fails if the written bytes and the scratch
+/// space ever overlap.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     access_unchecked, api::low::to_bytes_in_with_alloc, rancor::Failure,
+///     ser::allocator::SplitBuffer, util::Align, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     values: Vec<u32>,
+///     names: Vec<String>,
+/// }
+///
+/// let value = Example {
+///     values: vec![1, 2, 3],
+///     names: vec!["a string which is stored out of line".to_string()],
+/// };
+///
+/// let mut bytes = Align([MaybeUninit::<u8>::uninit(); 256]);
+/// let buffer = SplitBuffer::new(&mut *bytes);
+/// let (writer, alloc) = buffer.split();
+///
+/// let writer =
+///     to_bytes_in_with_alloc::<_, _, Failure>(&value, writer, alloc).unwrap();
+/// let archived = unsafe { access_unchecked::<ArchivedExample>(&writer) };
+/// assert_eq!(archived.values, [1, 2, 3]);
+/// assert_eq!(archived.names[0], "a string which is stored out of line");
+/// ```
+#[derive(Debug)]
+pub struct SplitBuffer<'a> {
+    ptr: NonNull<u8>,
+    cap: usize,
+    len: Cell<usize>,
+    scratch: Cell<usize>,
+    allocs: Cell<usize>,
+    _phantom: PhantomData<&'a mut [MaybeUninit<u8>]>,
+}
+
+impl<'a> SplitBuffer<'a> {
+    /// Creates a new split buffer from the given byte slice.
+    pub fn new(bytes: &'a mut [MaybeUninit<u8>]) -> Self {
+        Self {
+            // SAFETY: Slice pointers are always non-null.
+            ptr: unsafe { NonNull::new_unchecked(bytes.as_mut_ptr().cast()) },
+            cap: bytes.len(),
+            len: Cell::new(0),
+            scratch: Cell::new(bytes.len()),
+            allocs: Cell::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns a writer for the front of the buffer and an allocator for the
+    /// back of the buffer.
+    pub fn split(&self) -> (SplitWriter<'_>, TailAllocator<'_>) {
+        (SplitWriter { buffer: self }, TailAllocator { buffer: self })
+    }
+
+    /// Returns the number of bytes written to the buffer.
+    pub fn len(&self) -> usize {
+        self.len.get()
+    }
+
+    /// Returns whether no bytes have been written to the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len.get() == 0
+    }
+
+    /// Returns the total capacity of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the bytes written to the buffer.
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: The first `len` bytes of the buffer have been initialized by
+        // the writer, and scratch space is never allocated from them.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len.get()) }
+    }
+}
+
+/// The writer half of a [`SplitBuffer`].
+#[derive(Debug)]
+pub struct SplitWriter<'a> {
+    buffer: &'a SplitBuffer<'a>,
+}
+
+impl Deref for SplitWriter<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.buffer.as_slice()
+    }
+}
+
+impl Positional for SplitWriter<'_> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.buffer.len.get()
+    }
+}
+
+impl<E: Source> Writer<E> for SplitWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        let buffer = self.buffer;
+        let len = buffer.len.get();
+        let scratch = buffer.scratch.get();
+        if bytes.len() > scratch - len {
+            fail!(SplitBufferFull {
+                request: bytes.len(),
+                len,
+                scratch,
+                cap: buffer.cap,
+            });
+        }
+
+        // SAFETY: We checked that the bytes fit between the end of the written
+        // bytes and the start of the scratch space.
+        unsafe {
+            copy_nonoverlapping(
+                bytes.as_ptr(),
+                buffer.ptr.as_ptr().add(len),
+                bytes.len(),
+            );
+        }
+        buffer.len.set(len + bytes.len());
+        Ok(())
+    }
+}
+
+/// The allocator half of a [`SplitBuffer`].
+///
+/// Scratch space is allocated downward from the end of the buffer. Once every
+/// allocation has been popped, the whole tail of the buffer is available
+/// again.
+#[derive(Debug)]
+pub struct TailAllocator<'a> {
+    buffer: &'a SplitBuffer<'a>,
+}
+
+unsafe impl<E: Source> Allocator<E> for TailAllocator<'_> {
+    unsafe fn push_alloc(
+        &mut self,
+        layout: Layout,
+    ) -> Result<NonNull<[u8]>, E> {
+        let buffer = self.buffer;
+        let len = buffer.len.get();
+        let scratch = buffer.scratch.get();
+
+        let base = buffer.ptr.as_ptr() as usize;
+        let start = (base + scratch)
+            .checked_sub(layout.size())
+            .map(|end| (end & !(layout.align() - 1)).wrapping_sub(base))
+            .filter(|&start| start >= len && start <= scratch);
+        let Some(start) = start else {
+            fail!(SplitBufferFull {
+                request: layout.size(),
+                len,
+                scratch,
+                cap: buffer.cap,
+            });
+        };
+
+        buffer.scratch.set(start);
+        buffer.allocs.set(buffer.allocs.get() + 1);
+
+        // SAFETY: `start` is located between the end of the written bytes and
+        // the end of the buffer.
+        let ptr = unsafe { buffer.ptr.as_ptr().add(start) };
+        let slice_ptr = slice_from_raw_parts_mut(ptr, layout.size());
+        // SAFETY: `slice_ptr` is offset from `buffer.ptr`, which is non-null.
+        Ok(unsafe { NonNull::new_unchecked(slice_ptr) })
+    }
+
+    unsafe fn pop_alloc(
+        &mut self,
+        ptr: NonNull<u8>,
+        layout: Layout,
+    ) -> Result<(), E> {
+        let buffer = self.buffer;
+        let allocs = buffer.allocs.get() - 1;
+        buffer.allocs.set(allocs);
+        if allocs == 0 {
+            buffer.scratch.set(buffer.cap);
+        } else {
+            let start = ptr.as_ptr() as usize - buffer.ptr.as_ptr() as usize;
+            buffer.scratch.set(start + layout.size());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::mem::MaybeUninit;
+    use std::collections::HashMap;
+
+    use rancor::Failure;
+
+    use crate::{
+        alloc::{string::ToString, vec},
+        api::low::to_bytes_in_with_alloc,
+        collections::swiss_table::ArchivedHashMap,
+        ser::allocator::SplitBuffer,
+        string::ArchivedString,
+        util::Align,
+        Archived,
+    };
+
+    #[test]
+    fn split_buffer_hash_map() {
+        let value = (0..16)
+            .map(|i| (i, i.to_string().repeat(4)))
+            .collect::<HashMap<u32, _>>();
+
+        let mut bytes = Align([MaybeUninit::<u8>::uninit(); 2048]);
+        let buffer = SplitBuffer::new(&mut *bytes);
+        let (writer, alloc) = buffer.split();
+        to_bytes_in_with_alloc::<_, _, Failure>(&value, writer, alloc).unwrap();
+
+        let archived = unsafe {
+            crate::access_unchecked::<
+                ArchivedHashMap<Archived<u32>, ArchivedString>,
+            >(buffer.as_slice())
+        };
+        assert_eq!(archived.len(), 16);
+        for (key, value) in value.iter() {
+            assert_eq!(archived.get(&(*key).into()).unwrap(), value.as_str());
+        }
+    }
+
+    #[test]
+    fn split_buffer_overflow() {
+        let value = vec![0u8; 64];
+
+        let mut bytes = Align([MaybeUninit::<u8>::uninit(); 32]);
+        let buffer = SplitBuffer::new(&mut *bytes);
+        let (writer, alloc) = buffer.split();
+        assert!(
+            to_bytes_in_with_alloc::<_, _, Failure>(&value, writer, alloc,)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Writing bytes outside of the archive.
+
+use rancor::{Fallible, Strategy};
+
+use crate::ser::{Positional, Writer};
+
+/// A serializer which can write bytes to a blob region outside of the
+/// archive.
+///
+/// This trait is required to serialize fields with the
+/// [`ExternalBytes`](crate::with::ExternalBytes) wrapper.
+pub trait Blobs<E = <Self as Fallible>::Error> {
+    /// Writes the given bytes to the blob region and returns their offset in
+    /// it.
+    fn write_blob(&mut self, bytes: &[u8]) -> Result<usize, E>;
+}
+
+impl<T, E> Blobs<E> for &mut T
+where
+    T: Blobs<E> + ?Sized,
+{
+    fn write_blob(&mut self, bytes: &[u8]) -> Result<usize, E> {
+        T::write_blob(*self, bytes)
+    }
+}
+
+impl<T, E> Blobs<E> for Strategy<T, E>
+where
+    T: Blobs<E> + ?Sized,
+{
+    fn write_blob(&mut self, bytes: &[u8]) -> Result<usize, E> {
+        T::write_blob(self, bytes)
+    }
+}
+
+/// A writer which writes blobs to a separate writer.
+///
+/// The archive is written to `W` and blobs are written directly to `B`, so
+/// large blobs are never buffered in the archive. The blob writer can be any
+/// [`Writer`], like a `Vec<u8>` or an
+/// [`IoWriter`](crate::ser::writer::IoWriter) for a file. Offsets are
+/// relative to the position of the blob writer when it was wrapped.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     rancor::{Error, Strategy},
+///     ser::{blobs::BlobWriter, Blobs, Positional, Writer},
+/// };
+///
+/// let mut writer = BlobWriter::new(Vec::new(), Vec::new());
+/// let mut serializer = Strategy::<_, Error>::wrap(&mut writer);
+/// serializer.write(&[1, 2, 3]).unwrap();
+/// assert_eq!(serializer.write_blob(&[4, 5]).unwrap(), 0);
+/// assert_eq!(serializer.write_blob(&[6, 7]).unwrap(), 2);
+/// assert_eq!(serializer.pos(), 3);
+///
+/// let (archive, blobs) = writer.into_inner();
+/// assert_eq!(archive, [1, 2, 3]);
+/// assert_eq!(blobs, [4, 5, 6, 7]);
+/// ```
+#[derive(Debug, Default)]
+pub struct BlobWriter<W, B> {
+    writer: W,
+    blobs: B,
+    start: usize,
+}
+
+impl<W, B: Positional> BlobWriter<W, B> {
+    /// Creates a new `BlobWriter` which writes the archive to `writer` and
+    /// blobs to `blobs`.
+    pub fn new(writer: W, blobs: B) -> Self {
+        let start = blobs.pos();
+        Self {
+            writer,
+            blobs,
+            start,
+        }
+    }
+}
+
+impl<W, B> BlobWriter<W, B> {
+    /// Returns a reference to the archive writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a reference to the blob writer.
+    pub fn blobs(&self) -> &B {
+        &self.blobs
+    }
+
+    /// Consumes the `BlobWriter` and returns the archive and blob writers.
+    pub fn into_inner(self) -> (W, B) {
+        (self.writer, self.blobs)
+    }
+}
+
+impl<W: Positional, B> Positional for BlobWriter<W, B> {
+    fn pos(&self) -> usize {
+        self.writer.pos()
+    }
+}
+
+impl<W: Writer<E>, B, E> Writer<E> for BlobWriter<W, B> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.writer.write(bytes)
+    }
+}
+
+impl<W, B: Writer<E>, E> Blobs<E> for BlobWriter<W, B> {
+    fn write_blob(&mut self, bytes: &[u8]) -> Result<usize, E> {
+        let offset = self.blobs.pos() - self.start;
+        self.blobs.write(bytes)?;
+        Ok(offset)
+    }
+}
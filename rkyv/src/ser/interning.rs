@@ -0,0 +1,92 @@
+//! String interning.
+
+use rancor::{Fallible, Source, Strategy};
+
+use crate::{
+    ser::{Writer, WriterExt as _},
+    string::{ArchivedString, StringResolver},
+    Archive, Place, SerializeUnsized,
+};
+
+/// A string interning strategy.
+///
+/// This trait is required to serialize strings with the
+/// [`Intern`](crate::with::Intern) wrapper. Interning serializers write each
+/// distinct string once and share it between all of the interned strings with
+/// the same contents.
+pub trait Interning<E = <Self as Fallible>::Error> {
+    /// Returns the position of a previously-interned archived string with the
+    /// same contents as the given string, if any.
+    fn get_interned(&mut self, value: &str) -> Option<usize>;
+
+    /// Records the position of the archived string for the given string so
+    /// that later strings with the same contents can reuse it.
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), E>;
+}
+
+impl<T, E> Interning<E> for &mut T
+where
+    T: Interning<E> + ?Sized,
+{
+    fn get_interned(&mut self, value: &str) -> Option<usize> {
+        T::get_interned(*self, value)
+    }
+
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        T::add_interned(*self, value, pos)
+    }
+}
+
+impl<T, E> Interning<E> for Strategy<T, E>
+where
+    T: Interning<E> + ?Sized,
+{
+    fn get_interned(&mut self, value: &str) -> Option<usize> {
+        T::get_interned(self, value)
+    }
+
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        T::add_interned(self, value, pos)
+    }
+}
+
+/// Helper methods for [`Interning`].
+pub trait InterningExt<E>: Interning<E> {
+    /// Serializes the given string as an [`ArchivedString`] and returns its
+    /// position. If a string with the same contents has already been interned
+    /// then it returns the position of the previously added archived string.
+    fn serialize_interned(
+        &mut self,
+        value: &str,
+    ) -> Result<usize, <Self as Fallible>::Error>
+    where
+        Self: Fallible<Error = E> + Writer<E>,
+        E: Source,
+        str: SerializeUnsized<Self>,
+    {
+        if let Some(pos) = self.get_interned(value) {
+            return Ok(pos);
+        }
+
+        let resolver = ArchivedString::serialize_from_str(value, self)?;
+        self.align_for::<ArchivedString>()?;
+        // SAFETY: `resolver` is the result of serializing `value`, and the
+        // serializer was aligned for an `ArchivedString`.
+        let pos = unsafe { self.resolve_aligned(&Interned(value), resolver)? };
+        self.add_interned(value, pos)?;
+        Ok(pos)
+    }
+}
+
+impl<S, E> InterningExt<E> for S where S: Interning<E> + ?Sized {}
+
+struct Interned<'a>(&'a str);
+
+impl Archive for Interned<'_> {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        ArchivedString::resolve_from_str(self.0, resolver, out);
+    }
+}
@@ -1,16 +1,21 @@
 //! Serialization traits and adapters.
 
 pub mod allocator;
+pub mod blobs;
+pub mod interning;
 pub mod sharing;
 pub mod writer;
 
 use ::core::{alloc::Layout, ptr::NonNull};
 
+use self::allocator::{AllocationStats, AllocationTracker};
 #[doc(inline)]
 pub use self::{
     allocator::Allocator,
+    blobs::Blobs,
+    interning::{Interning, InterningExt},
     sharing::{Sharing, SharingExt},
-    writer::{Positional, Writer, WriterExt},
+    writer::{AsyncWriter, Positional, Writer, WriterExt},
 };
 
 /// A serializer built from composeable pieces.
@@ -21,6 +26,8 @@ pub struct Serializer<W, A, S> {
     /// The allocator of the serializer.
     pub allocator: A,
     /// The pointer sharing of the serializer.
+    ///
+    /// This also provides the string interning of the serializer.
     pub sharing: S,
 }
 
@@ -47,6 +54,28 @@ impl<W, A, S> Serializer<W, A, S> {
     }
 }
 
+impl<W: Positional, A, S> Serializer<W, AllocationTracker<A>, S> {
+    /// Returns the stats for the serialization performed so far.
+    ///
+    /// These can be used to pre-size the writer and allocator for similar
+    /// values.
+    pub fn stats(&self) -> SerializerStats {
+        SerializerStats {
+            allocations: *self.allocator.stats(),
+            archive_size: self.writer.pos(),
+        }
+    }
+}
+
+/// Statistics collected while serializing with an [`AllocationTracker`].
+#[derive(Clone, Copy, Debug)]
+pub struct SerializerStats {
+    /// The stats for the scratch space allocations that were made.
+    pub allocations: AllocationStats,
+    /// The number of bytes written to the writer.
+    pub archive_size: usize,
+}
+
 impl<W: Positional, A, S> Positional for Serializer<W, A, S> {
     fn pos(&self) -> usize {
         self.writer.pos()
@@ -59,6 +88,12 @@ impl<W: Writer<E>, A, S, E> Writer<E> for Serializer<W, A, S> {
     }
 }
 
+impl<W: Blobs<E>, A, S, E> Blobs<E> for Serializer<W, A, S> {
+    fn write_blob(&mut self, bytes: &[u8]) -> Result<usize, E> {
+        self.writer.write_blob(bytes)
+    }
+}
+
 unsafe impl<W, A: Allocator<E>, S, E> Allocator<E> for Serializer<W, A, S> {
     unsafe fn push_alloc(
         &mut self,
@@ -89,3 +124,13 @@ impl<W, A, S: Sharing<E>, E> Sharing<E> for Serializer<W, A, S> {
         self.sharing.finish_sharing(address, pos)
     }
 }
+
+impl<W, A, S: Interning<E>, E> Interning<E> for Serializer<W, A, S> {
+    fn get_interned(&mut self, value: &str) -> Option<usize> {
+        self.sharing.get_interned(value)
+    }
+
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        self.sharing.add_interned(value, pos)
+    }
+}
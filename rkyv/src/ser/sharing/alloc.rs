@@ -4,16 +4,21 @@ use hashbrown::hash_map::{Entry, HashMap};
 use rancor::{fail, Source};
 
 use crate::{
+    alloc::boxed::Box,
     hash::FxHasher64,
-    ser::{sharing::SharingState, Sharing},
+    ser::{sharing::SharingState, Interning, Sharing},
 };
 
 /// A shared pointer strategy that shares serializations of the same shared
 /// pointer.
+///
+/// This strategy also shares interned strings with the same contents.
 #[derive(Debug, Default)]
 pub struct Share {
     shared_address_to_pos:
         HashMap<usize, Option<usize>, BuildHasherDefault<FxHasher64>>,
+    interned_str_to_pos:
+        HashMap<Box<str>, usize, BuildHasherDefault<FxHasher64>>,
 }
 
 impl Share {
@@ -31,12 +36,14 @@ impl Share {
                 capacity,
                 Default::default(),
             ),
+            interned_str_to_pos: HashMap::default(),
         }
     }
 
     /// Clears the shared pointer unifier for reuse.
     pub fn clear(&mut self) {
         self.shared_address_to_pos.clear();
+        self.interned_str_to_pos.clear();
     }
 }
 
@@ -94,3 +101,28 @@ impl<E: Source> Sharing<E> for Share {
         }
     }
 }
+
+#[derive(Debug)]
+struct AlreadyInterned;
+
+impl fmt::Display for AlreadyInterned {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "string was already interned")
+    }
+}
+
+impl Error for AlreadyInterned {}
+
+impl<E: Source> Interning<E> for Share {
+    fn get_interned(&mut self, value: &str) -> Option<usize> {
+        self.interned_str_to_pos.get(value).copied()
+    }
+
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), E> {
+        if self.interned_str_to_pos.contains_key(value) {
+            fail!(AlreadyInterned);
+        }
+        self.interned_str_to_pos.insert(value.into(), pos);
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
-use crate::ser::{sharing::SharingState, Sharing};
+use crate::ser::{sharing::SharingState, Interning, Sharing};
 
 /// A shared pointer strategy that duplicates serializations of the same shared
 /// pointer.
+///
+/// This strategy also duplicates interned strings with the same contents.
 #[derive(Debug, Default)]
 pub struct Unshare;
 
@@ -14,3 +16,13 @@ impl<E> Sharing<E> for Unshare {
         Ok(())
     }
 }
+
+impl<E> Interning<E> for Unshare {
+    fn get_interned(&mut self, _: &str) -> Option<usize> {
+        None
+    }
+
+    fn add_interned(&mut self, _: &str, _: usize) -> Result<(), E> {
+        Ok(())
+    }
+}
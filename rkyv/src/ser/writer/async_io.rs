@@ -0,0 +1,210 @@
+use core::{
+    error::Error,
+    fmt,
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rancor::{fail, Source};
+
+/// A type that writes bytes to some output asynchronously.
+///
+/// Serializers write many small slices of bytes and need to know their
+/// position at all times, so they always write synchronously. To stream an
+/// archive over an async output, serialize it to a buffer first and then write
+/// the buffer with [`write_async`]. The high-level
+/// [`to_bytes_async`](crate::api::high::to_bytes_async) does both.
+///
+/// With the `futures-io-0_3` feature, this is implemented for all types that
+/// implement [`AsyncWrite`](futures_io_0_3::AsyncWrite). Other async runtimes
+/// can be supported by implementing this trait for a wrapper type.
+pub trait AsyncWriter<E> {
+    /// Attempts to write some of the given bytes, returning how many bytes
+    /// were written.
+    ///
+    /// Returns `Poll::Pending` and arranges for the current task to be woken
+    /// if the output is not ready to be written to.
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bytes: &[u8],
+    ) -> Poll<Result<usize, E>>;
+
+    /// Attempts to flush any buffered bytes to the output.
+    ///
+    /// Returns `Poll::Pending` and arranges for the current task to be woken
+    /// if the output is not ready to be flushed.
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), E>>;
+}
+
+#[cfg(feature = "futures-io-0_3")]
+impl<W, E> AsyncWriter<E> for W
+where
+    W: futures_io_0_3::AsyncWrite + ?Sized,
+    E: Source,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bytes: &[u8],
+    ) -> Poll<Result<usize, E>> {
+        futures_io_0_3::AsyncWrite::poll_write(self, cx, bytes).map_err(E::new)
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), E>> {
+        futures_io_0_3::AsyncWrite::poll_flush(self, cx).map_err(E::new)
+    }
+}
+
+#[derive(Debug)]
+struct WriteZero {
+    remaining: usize,
+}
+
+impl fmt::Display for WriteZero {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "async writer stopped accepting bytes with {} bytes left to write",
+            self.remaining,
+        )
+    }
+}
+
+impl Error for WriteZero {}
+
+/// Writes all of the given bytes to an [`AsyncWriter`] and flushes it.
+///
+/// The current task yields whenever the writer is not ready, so large archives
+/// can be written without blocking an executor thread.
+pub async fn write_async<W, E>(bytes: &[u8], writer: &mut W) -> Result<(), E>
+where
+    W: AsyncWriter<E> + Unpin + ?Sized,
+    E: Source,
+{
+    let mut remaining = bytes;
+    while !remaining.is_empty() {
+        let written =
+            poll_fn(|cx| Pin::new(&mut *writer).poll_write(cx, remaining))
+                .await?;
+        if written == 0 {
+            fail!(WriteZero {
+                remaining: remaining.len(),
+            });
+        }
+        remaining = &remaining[written..];
+    }
+    poll_fn(|cx| Pin::new(&mut *writer).poll_flush(cx)).await
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::{
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll, Waker},
+    };
+    use std::{sync::Arc, task::Wake, vec::Vec};
+
+    use rancor::{Failure, Panic};
+
+    use super::{write_async, AsyncWriter};
+    use crate::api::high::{to_bytes, to_bytes_async};
+
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(result) = future.as_mut().poll(&mut cx) {
+                return result;
+            }
+        }
+    }
+
+    /// Accepts at most `max_write` bytes at a time and is only ready on every
+    /// other poll.
+    struct Trickle {
+        bytes: Vec<u8>,
+        max_write: usize,
+        ready: bool,
+        flushed: bool,
+    }
+
+    impl Trickle {
+        fn new(max_write: usize) -> Self {
+            Self {
+                bytes: Vec::new(),
+                max_write,
+                ready: false,
+                flushed: false,
+            }
+        }
+    }
+
+    impl<E> AsyncWriter<E> for Trickle {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            bytes: &[u8],
+        ) -> Poll<Result<usize, E>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            let len = bytes.len().min(self.max_write);
+            self.bytes.extend_from_slice(&bytes[..len]);
+            self.flushed = false;
+            Poll::Ready(Ok(len))
+        }
+
+        fn poll_flush(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Result<(), E>> {
+            self.flushed = true;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn write_async_trickle() {
+        let bytes = (0..100u8).collect::<Vec<_>>();
+        let mut writer = Trickle::new(7);
+        block_on(write_async::<_, Panic>(&bytes, &mut writer)).unwrap();
+        assert_eq!(writer.bytes, bytes);
+        assert!(writer.flushed);
+
+        let mut closed = Trickle::new(0);
+        assert!(
+            block_on(write_async::<_, Failure>(&bytes, &mut closed)).is_err()
+        );
+    }
+
+    #[test]
+    fn to_bytes_async_matches_to_bytes() {
+        let value = (0..100u32)
+            .map(|i| (i, i.to_string().repeat(i as usize % 7)))
+            .collect::<Vec<_>>();
+        let expected = to_bytes::<Panic>(&value).unwrap();
+
+        let mut writer = Trickle::new(64);
+        block_on(to_bytes_async::<_, Panic>(&value, &mut writer)).unwrap();
+        assert_eq!(writer.bytes, &*expected);
+    }
+}
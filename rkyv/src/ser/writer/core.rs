@@ -166,6 +166,64 @@ impl<E: Source> Writer<E> for Buffer<'_> {
     }
 }
 
+/// A [`Writer`] which counts the bytes written to it without storing them.
+///
+/// Serializing to a `CountingWriter` computes the exact size of the serialized
+/// bytes without copying them anywhere.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_in, rancor::Error, ser::writer::CountingWriter,
+///     to_bytes,
+/// };
+///
+/// let value = vec!["hello".to_string(), "world".to_string()];
+/// let writer =
+///     to_bytes_in::<_, Error>(&value, CountingWriter::new()).unwrap();
+/// assert_eq!(writer.len(), to_bytes::<Error>(&value).unwrap().len());
+/// ```
+#[derive(Debug, Default)]
+pub struct CountingWriter {
+    len: usize,
+}
+
+impl CountingWriter {
+    /// Returns a new `CountingWriter` which has not counted any bytes.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of bytes written to the writer.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bytes have been written to the writer.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Positional for CountingWriter {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.len
+    }
+}
+
+impl<E> Writer<E> for CountingWriter {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.len += bytes.len();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::mem::MaybeUninit;
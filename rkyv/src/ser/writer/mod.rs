@@ -2,6 +2,7 @@
 
 #[cfg(feature = "alloc")]
 mod alloc;
+mod async_io;
 mod core;
 #[cfg(feature = "std")]
 mod std;
@@ -9,9 +10,9 @@ mod std;
 use ::core::mem;
 use rancor::{Fallible, Strategy};
 
-pub use self::core::*;
 #[cfg(feature = "std")]
 pub use self::std::*;
+pub use self::{async_io::*, core::*};
 use crate::{Archive, ArchiveUnsized, Place, RelPtr};
 
 /// A writer that knows its current position.
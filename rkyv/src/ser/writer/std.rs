@@ -1,4 +1,7 @@
-use std::io;
+use std::{
+    io::{self, IoSlice},
+    vec::Vec,
+};
 
 use rancor::{ResultExt as _, Source};
 
@@ -61,6 +64,269 @@ impl<W: io::Write, E: Source> Writer<E> for IoWriter<W> {
     }
 }
 
+/// Wraps a type that implements [`io::Write`](std::io::Write) and writes to it
+/// in fixed-size chunks.
+///
+/// Serializers write many small slices of bytes, and passing each of those
+/// directly to the inner writer can be slow. `ChunkedIoWriter` collects writes
+/// into a chunk buffer and only writes to the inner writer once a full chunk is
+/// available. Because archives are always written front-to-back, the inner
+/// writer never needs to seek and only one chunk is held in memory at a time.
+///
+/// The final partial chunk is only written when [`flush`](Self::flush) or
+/// [`into_inner`](Self::into_inner) is called.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::{Error, Strategy},
+///     ser::{writer::ChunkedIoWriter, Positional, Writer},
+/// };
+///
+/// let mut chunked = ChunkedIoWriter::with_chunk_size(Vec::new(), 4);
+/// let mut writer = Strategy::<_, Error>::wrap(&mut chunked);
+/// writer.write(&[0, 1, 2, 3, 4, 5]).unwrap();
+/// assert_eq!(writer.pos(), 6);
+///
+/// // Only the first full chunk has been written so far
+/// assert_eq!(chunked.get_ref(), &[0, 1, 2, 3]);
+///
+/// let buf = chunked.into_inner::<Error>().unwrap();
+/// assert_eq!(buf, vec![0, 1, 2, 3, 4, 5]);
+/// ```
+#[derive(Debug)]
+pub struct ChunkedIoWriter<W> {
+    inner: W,
+    chunk: Vec<u8>,
+    chunk_size: usize,
+    pos: usize,
+}
+
+impl<W> ChunkedIoWriter<W> {
+    /// The chunk size used by [`new`](Self::new).
+    pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+    /// Creates a new chunked writer with the default chunk size.
+    pub fn new(inner: W) -> Self {
+        Self::with_chunk_size(inner, Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a new chunked writer which writes to the inner writer in chunks
+    /// of the given size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(inner: W, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be nonzero");
+
+        Self {
+            inner,
+            chunk: Vec::with_capacity(chunk_size),
+            chunk_size,
+            pos: 0,
+        }
+    }
+
+    /// Returns the chunk size of the writer.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: io::Write> ChunkedIoWriter<W> {
+    /// Writes any buffered bytes to the inner writer and flushes it.
+    pub fn flush<E: Source>(&mut self) -> Result<(), E> {
+        self.inner.write_all(&self.chunk).into_error()?;
+        self.chunk.clear();
+        self.inner.flush().into_error()
+    }
+
+    /// Flushes any buffered bytes and returns the inner writer.
+    pub fn into_inner<E: Source>(mut self) -> Result<W, E> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W> Positional for ChunkedIoWriter<W> {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<W: io::Write, E: Source> Writer<E> for ChunkedIoWriter<W> {
+    fn write(&mut self, mut bytes: &[u8]) -> Result<(), E> {
+        self.pos += bytes.len();
+
+        if !self.chunk.is_empty() {
+            let len = bytes.len().min(self.chunk_size - self.chunk.len());
+            self.chunk.extend_from_slice(&bytes[..len]);
+            bytes = &bytes[len..];
+
+            if self.chunk.len() < self.chunk_size {
+                return Ok(());
+            }
+            self.inner.write_all(&self.chunk).into_error()?;
+            self.chunk.clear();
+        }
+
+        // Write whole chunks directly to avoid copying them into the buffer.
+        let direct_len = bytes.len() - bytes.len() % self.chunk_size;
+        if direct_len > 0 {
+            self.inner.write_all(&bytes[..direct_len]).into_error()?;
+        }
+        self.chunk.extend_from_slice(&bytes[direct_len..]);
+
+        Ok(())
+    }
+}
+
+/// Wraps a type that implements [`io::Write`](std::io::Write) and writes large
+/// byte runs to it with vectored writes.
+///
+/// Archives which are mostly made of large byte blobs spend much of their
+/// serialization time copying those blobs. `VectoredIoWriter` buffers small
+/// writes like [`ChunkedIoWriter`], but writes of at least `threshold` bytes
+/// are passed to the inner writer directly. They're sent along with any
+/// buffered bytes in a single call to
+/// [`write_vectored`](io::Write::write_vectored), so large blobs are never
+/// copied and the number of calls to the inner writer stays low. This works
+/// best with writers that implement `write_vectored` natively, like files and
+/// sockets.
+///
+/// Any buffered bytes are only written when [`flush`](Self::flush) or
+/// [`into_inner`](Self::into_inner) is called.
+///
+/// # Examples
+/// ```
+/// use rkyv::{
+///     rancor::{Error, Strategy},
+///     ser::{writer::VectoredIoWriter, Positional, Writer},
+/// };
+///
+/// let mut vectored = VectoredIoWriter::with_threshold(Vec::new(), 4);
+/// let mut writer = Strategy::<_, Error>::wrap(&mut vectored);
+/// writer.write(&[0, 1]).unwrap();
+/// assert_eq!(writer.pos(), 2);
+///
+/// // Small writes are buffered
+/// assert!(vectored.get_ref().is_empty());
+///
+/// // Large writes are written along with the buffered bytes
+/// let mut writer = Strategy::<_, Error>::wrap(&mut vectored);
+/// writer.write(&[2, 3, 4, 5]).unwrap();
+/// writer.write(&[6]).unwrap();
+/// assert_eq!(vectored.get_ref(), &[0, 1, 2, 3, 4, 5]);
+///
+/// let buf = vectored.into_inner::<Error>().unwrap();
+/// assert_eq!(buf, vec![0, 1, 2, 3, 4, 5, 6]);
+/// ```
+#[derive(Debug)]
+pub struct VectoredIoWriter<W> {
+    inner: W,
+    buffer: Vec<u8>,
+    threshold: usize,
+    pos: usize,
+}
+
+impl<W> VectoredIoWriter<W> {
+    /// The threshold used by [`new`](Self::new).
+    pub const DEFAULT_THRESHOLD: usize = 8 * 1024;
+
+    /// Creates a new vectored writer with the default threshold.
+    pub fn new(inner: W) -> Self {
+        Self::with_threshold(inner, Self::DEFAULT_THRESHOLD)
+    }
+
+    /// Creates a new vectored writer which writes runs of at least `threshold`
+    /// bytes directly to the inner writer.
+    ///
+    /// Smaller writes are buffered until `threshold` bytes are available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero.
+    pub fn with_threshold(inner: W, threshold: usize) -> Self {
+        assert!(threshold > 0, "threshold must be nonzero");
+
+        Self {
+            inner,
+            buffer: Vec::with_capacity(threshold),
+            threshold,
+            pos: 0,
+        }
+    }
+
+    /// Returns the threshold of the writer.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+}
+
+impl<W: io::Write> VectoredIoWriter<W> {
+    /// Writes any buffered bytes to the inner writer and flushes it.
+    pub fn flush<E: Source>(&mut self) -> Result<(), E> {
+        self.inner.write_all(&self.buffer).into_error()?;
+        self.buffer.clear();
+        self.inner.flush().into_error()
+    }
+
+    /// Flushes any buffered bytes and returns the inner writer.
+    pub fn into_inner<E: Source>(mut self) -> Result<W, E> {
+        self.flush()?;
+        Ok(self.inner)
+    }
+
+    fn write_all_vectored(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let mut slices = [IoSlice::new(&self.buffer), IoSlice::new(bytes)];
+        let mut slices = &mut slices[..];
+        IoSlice::advance_slices(&mut slices, 0);
+        while !slices.is_empty() {
+            match self.inner.write_vectored(slices) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => IoSlice::advance_slices(&mut slices, n),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W> Positional for VectoredIoWriter<W> {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<W: io::Write, E: Source> Writer<E> for VectoredIoWriter<W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.len() >= self.threshold {
+            self.write_all_vectored(bytes).into_error()?;
+        } else {
+            self.buffer.extend_from_slice(bytes);
+            if self.buffer.len() >= self.threshold {
+                self.write_all_vectored(&[]).into_error()?;
+            }
+        }
+
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use rancor::Failure;
@@ -84,4 +350,101 @@ mod tests {
         serialize_using::<_, Failure>(&foo, &mut ser)
             .expect_err("serialized to an undersized buffer must fail");
     }
+
+    #[test]
+    fn chunked_writer() {
+        use std::{io, vec::Vec};
+
+        use rancor::Panic;
+
+        use crate::{
+            api::high::{to_bytes, to_bytes_in},
+            ser::writer::ChunkedIoWriter,
+        };
+
+        struct Chunks(Vec<Vec<u8>>);
+
+        impl io::Write for Chunks {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push(buf.to_vec());
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let value = (0..100u32)
+            .map(|i| (i, i.to_string().repeat(i as usize % 7)))
+            .collect::<Vec<_>>();
+        let expected = to_bytes::<Panic>(&value).unwrap();
+
+        let writer = ChunkedIoWriter::with_chunk_size(Chunks(Vec::new()), 16);
+        let chunks = to_bytes_in::<_, Panic>(&value, writer)
+            .unwrap()
+            .into_inner::<Panic>()
+            .unwrap()
+            .0;
+        let (_, full) = chunks.split_last().unwrap();
+        assert!(full.iter().all(|chunk| chunk.len() % 16 == 0));
+        assert_eq!(chunks.concat(), &*expected);
+    }
+
+    #[test]
+    fn vectored_writer() {
+        use std::{
+            io::{self, IoSlice},
+            vec::Vec,
+        };
+
+        use rancor::Panic;
+
+        use crate::{
+            api::high::{to_bytes, to_bytes_in},
+            ser::writer::VectoredIoWriter,
+        };
+
+        // Records each call and only accepts part of each vectored write.
+        struct Calls(Vec<Vec<u8>>);
+
+        impl io::Write for Calls {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.push(buf.to_vec());
+                Ok(buf.len())
+            }
+
+            fn write_vectored(
+                &mut self,
+                bufs: &[IoSlice<'_>],
+            ) -> io::Result<usize> {
+                let mut written = Vec::new();
+                for buf in bufs {
+                    written.extend_from_slice(buf);
+                }
+                written
+                    .truncate(usize::max(1, written.len().saturating_sub(3)));
+                let len = written.len();
+                self.0.push(written);
+                Ok(len)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let value = (0..20u8)
+            .map(|i| (i, vec![i; 10 * i as usize]))
+            .collect::<Vec<_>>();
+        let expected = to_bytes::<Panic>(&value).unwrap();
+
+        let writer = VectoredIoWriter::with_threshold(Calls(Vec::new()), 64);
+        let calls = to_bytes_in::<_, Panic>(&value, writer)
+            .unwrap()
+            .into_inner::<Panic>()
+            .unwrap()
+            .0;
+        assert_eq!(calls.concat(), &*expected);
+    }
 }
@@ -0,0 +1,360 @@
+//! Serde support for archived types.
+//!
+//! Archived values can be serialized with serde without deserializing them
+//! first. This is mostly useful for exporting archives as JSON or another
+//! human-readable format for debugging and tooling.
+//!
+//! Archived primitives are defined in `rend` and can't implement
+//! `serde::Serialize` directly, so archived types implement
+//! [`SerializeArchived`] instead. Any value which implements it can be passed
+//! to serde by wrapping it in [`Serde`]. rkyv's own archived containers (like
+//! [`ArchivedVec`] and [`ArchivedString`]) also implement `serde::Serialize`
+//! directly.
+//!
+//! Archived types generated by `#[derive(Archive)]` can implement both traits
+//! with `#[rkyv(derive_serde)]`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{rancor::Error, Archive, Serialize};
+//!
+//! #[derive(Archive, Serialize)]
+//! #[rkyv(derive_serde)]
+//! struct Example {
+//!     name: String,
+//!     values: Vec<u32>,
+//! }
+//!
+//! let value = Example {
+//!     name: "pi".to_string(),
+//!     values: vec![3, 1, 4],
+//! };
+//!
+//! let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+//! let archived = rkyv::access::<ArchivedExample, Error>(&bytes).unwrap();
+//!
+//! let json = serde_json_1::to_string(archived).unwrap();
+//! assert_eq!(json, r#"{"name":"pi","values":[3,1,4]}"#);
+//! ```
+
+#[doc(hidden)]
+pub use ::serde_1 as __serde;
+use serde_1::{Serialize, Serializer};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{ArchivedHashMap, ArchivedHashSet},
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedU128, ArchivedU16, ArchivedU32,
+        ArchivedU64,
+    },
+    rel_ptr::Offset,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    vec::ArchivedVec,
+};
+
+/// An archived type which can be serialized with serde.
+///
+/// This can be implemented for archived types generated by
+/// `#[derive(Archive)]` with `#[rkyv(derive_serde)]`.
+pub trait SerializeArchived {
+    /// Serializes this archived value with the given serializer.
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>;
+}
+
+/// A wrapper which implements `serde::Serialize` for types that implement
+/// [`SerializeArchived`].
+#[derive(Debug)]
+pub struct Serde<'a, T: ?Sized>(pub &'a T);
+
+impl<T: SerializeArchived + ?Sized> Serialize for Serde<'_, T> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_archived(serializer)
+    }
+}
+
+macro_rules! impl_native {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SerializeArchived for $ty {
+                fn serialize_archived<S: Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    self.serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_native!((), bool, i8, u8, str);
+
+macro_rules! impl_primitive {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SerializeArchived for $ty {
+                fn serialize_archived<S: Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    self.to_native().serialize(serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive!(
+    ArchivedI16,
+    ArchivedI32,
+    ArchivedI64,
+    ArchivedI128,
+    ArchivedU16,
+    ArchivedU32,
+    ArchivedU64,
+    ArchivedU128,
+    ArchivedF32,
+    ArchivedF64,
+    ArchivedChar,
+);
+
+impl<T: SerializeArchived> SerializeArchived for [T] {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Serde))
+    }
+}
+
+impl<T: SerializeArchived, const N: usize> SerializeArchived for [T; N] {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde_1::ser::SerializeTuple as _;
+
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for value in self.iter() {
+            tuple.serialize_element(&Serde(value))?;
+        }
+        tuple.end()
+    }
+}
+
+impl<const N: usize> SerializeArchived for ArchivedString<N> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<T, O, L> SerializeArchived for ArchivedVec<T, O, L>
+where
+    T: SerializeArchived,
+    O: Offset,
+    L: Offset,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize_archived(serializer)
+    }
+}
+
+impl<T> SerializeArchived for ArchivedBox<T>
+where
+    T: ArchivePointee + SerializeArchived + ?Sized,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        self.get().serialize_archived(serializer)
+    }
+}
+
+impl<T: SerializeArchived> SerializeArchived for ArchivedOption<T> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            ArchivedOption::None => serializer.serialize_none(),
+            ArchivedOption::Some(value) => {
+                serializer.serialize_some(&Serde(value))
+            }
+        }
+    }
+}
+
+impl<K, V, H> SerializeArchived for ArchivedHashMap<K, V, H>
+where
+    K: SerializeArchived,
+    V: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(k, v)| (Serde(k), Serde(v))))
+    }
+}
+
+impl<K: SerializeArchived, H> SerializeArchived for ArchivedHashSet<K, H> {
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Serde))
+    }
+}
+
+impl<K, V, const E: usize> SerializeArchived for ArchivedBTreeMap<K, V, E>
+where
+    K: SerializeArchived,
+    V: SerializeArchived,
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter().map(|(k, v)| (Serde(k), Serde(v))))
+    }
+}
+
+impl<K: SerializeArchived, const E: usize> SerializeArchived
+    for ArchivedBTreeSet<K, E>
+{
+    fn serialize_archived<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter().map(Serde))
+    }
+}
+
+macro_rules! impl_serialize {
+    ($($ty:ty => [$($params:tt)*]),* $(,)?) => {
+        $(
+            impl<$($params)*> Serialize for $ty
+            where
+                Self: SerializeArchived,
+            {
+                fn serialize<S: Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    self.serialize_archived(serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_serialize!(
+    ArchivedString<N> => [const N: usize],
+    ArchivedVec<T, O, L> => [T, O, L],
+    ArchivedBox<T> => [T: ArchivePointee + ?Sized],
+    ArchivedOption<T> => [T],
+    ArchivedHashMap<K, V, H> => [K, V, H],
+    ArchivedHashSet<K, H> => [K, H],
+    ArchivedBTreeMap<K, V, E> => [K, V, const E: usize],
+    ArchivedBTreeSet<K, E> => [K, const E: usize],
+);
+
+#[cfg(test)]
+mod tests {
+    use serde_json_1::json;
+
+    use crate::{
+        alloc::{
+            collections::BTreeMap,
+            string::{String, ToString},
+            vec,
+            vec::Vec,
+        },
+        api::test::to_archived,
+        serde::Serde,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, derive_serde)]
+    struct Example {
+        name: String,
+        r#type: Option<u16>,
+        values: Vec<f32>,
+        map: BTreeMap<String, (u8, char)>,
+        unit: Unit,
+        pair: Pair,
+        kinds: Vec<Kind>,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, derive_serde)]
+    struct Unit;
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, derive_serde)]
+    struct Pair(i64, bool);
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, derive_serde)]
+    enum Kind {
+        A,
+        B(u32),
+        C(u32, u32),
+        D { x: i32 },
+    }
+
+    #[test]
+    fn serialize_archived() {
+        let value = Example {
+            name: "example".to_string(),
+            r#type: Some(42),
+            values: vec![1.5, 2.5],
+            map: [("a".to_string(), (1, 'x'))].into_iter().collect(),
+            unit: Unit,
+            pair: Pair(-1, true),
+            kinds: vec![Kind::A, Kind::B(1), Kind::C(2, 3), Kind::D { x: -4 }],
+        };
+
+        to_archived(&value, |archived| {
+            assert_eq!(
+                serde_json_1::to_value(&*archived).unwrap(),
+                json!({
+                    "name": "example",
+                    "type": 42,
+                    "values": [1.5, 2.5],
+                    "map": { "a": [1, "x"] },
+                    "unit": null,
+                    "pair": [-1, true],
+                    "kinds": ["A", { "B": 1 }, { "C": [2, 3] }, { "D": { "x": -4 } }],
+                }),
+            );
+            assert_eq!(
+                serde_json_1::to_value(Serde(&archived.pair.0)).unwrap(),
+                json!(-1),
+            );
+        });
+    }
+}
@@ -0,0 +1,201 @@
+//! Bulk byte swapping for slices of multibyte primitives.
+
+#[cfg(all(
+    target_feature = "sse2",
+    any(target_arch = "x86", target_arch = "x86_64"),
+    not(miri),
+))]
+mod block {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64 as x86;
+
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of 16 bytes and `dst` must be valid for
+    /// writes of 16 bytes.
+    #[inline]
+    pub unsafe fn swap<const N: usize>(src: *const u8, dst: *mut u8) {
+        // SAFETY: The caller has guaranteed that `src` and `dst` are valid for
+        // reads and writes of 16 bytes respectively, and SSE2 is available.
+        unsafe {
+            let mut x = x86::_mm_loadu_si128(src.cast());
+            // Reverse the order of the 16-bit words in each element.
+            x = match N {
+                4 => x86::_mm_shufflehi_epi16::<0b10_11_00_01>(
+                    x86::_mm_shufflelo_epi16::<0b10_11_00_01>(x),
+                ),
+                8 => x86::_mm_shufflehi_epi16::<0b00_01_10_11>(
+                    x86::_mm_shufflelo_epi16::<0b00_01_10_11>(x),
+                ),
+                16 => x86::_mm_shuffle_epi32::<0b01_00_11_10>(
+                    x86::_mm_shufflehi_epi16::<0b00_01_10_11>(
+                        x86::_mm_shufflelo_epi16::<0b00_01_10_11>(x),
+                    ),
+                ),
+                _ => x,
+            };
+            // Then swap the bytes of each 16-bit word.
+            x = x86::_mm_or_si128(
+                x86::_mm_slli_epi16::<8>(x),
+                x86::_mm_srli_epi16::<8>(x),
+            );
+            x86::_mm_storeu_si128(dst.cast(), x);
+        }
+    }
+}
+
+#[cfg(all(
+    target_feature = "neon",
+    target_arch = "aarch64",
+    // NEON intrinsics are currently broken on big-endian targets.
+    // See https://github.com/rust-lang/stdarch/issues/1484.
+    target_endian = "little",
+    not(miri),
+))]
+mod block {
+    use core::arch::aarch64;
+
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of 16 bytes and `dst` must be valid for
+    /// writes of 16 bytes.
+    #[inline]
+    pub unsafe fn swap<const N: usize>(src: *const u8, dst: *mut u8) {
+        // SAFETY: The caller has guaranteed that `src` and `dst` are valid for
+        // reads and writes of 16 bytes respectively, and NEON is available.
+        unsafe {
+            let x = aarch64::vld1q_u8(src);
+            let x = match N {
+                2 => aarch64::vrev16q_u8(x),
+                4 => aarch64::vrev32q_u8(x),
+                8 => aarch64::vrev64q_u8(x),
+                16 => {
+                    let x = aarch64::vrev64q_u8(x);
+                    aarch64::vextq_u8::<8>(x, x)
+                }
+                _ => x,
+            };
+            aarch64::vst1q_u8(dst, x);
+        }
+    }
+}
+
+#[cfg(all(
+    not(all(
+        target_feature = "sse2",
+        any(target_arch = "x86", target_arch = "x86_64"),
+        not(miri),
+    )),
+    not(all(
+        target_feature = "neon",
+        target_arch = "aarch64",
+        // NEON intrinsics are currently broken on big-endian targets.
+        // See https://github.com/rust-lang/stdarch/issues/1484.
+        target_endian = "little",
+        not(miri),
+    )),
+))]
+mod block {
+    /// # Safety
+    ///
+    /// `src` must be valid for reads of 16 bytes and `dst` must be valid for
+    /// writes of 16 bytes.
+    #[inline]
+    pub unsafe fn swap<const N: usize>(src: *const u8, dst: *mut u8) {
+        // SAFETY: The caller has guaranteed that `src` is valid for reads of
+        // 16 bytes.
+        let mut block = unsafe { src.cast::<[u8; 16]>().read_unaligned() };
+        for element in block.chunks_exact_mut(N) {
+            element.reverse();
+        }
+        // SAFETY: The caller has guaranteed that `dst` is valid for writes of
+        // 16 bytes.
+        unsafe {
+            dst.cast::<[u8; 16]>().write_unaligned(block);
+        }
+    }
+}
+
+/// Copies `len` elements of `N` bytes each from `src` to `dst`, reversing the
+/// order of the bytes in each element. `N` must be 2, 4, 8, or 16.
+///
+/// Elements are swapped 32 bytes at a time, and any remaining elements are
+/// swapped individually.
+///
+/// # Safety
+///
+/// - `src` must be valid for reads of `len * N` bytes.
+/// - `dst` must be valid for writes of `len * N` bytes.
+/// - `src` and `dst` must not overlap.
+#[inline]
+pub unsafe fn swap_bytes<const N: usize>(
+    src: *const u8,
+    dst: *mut u8,
+    len: usize,
+) {
+    const BLOCK: usize = 16;
+    const { assert!(matches!(N, 2 | 4 | 8 | 16)) };
+
+    let size = len * N;
+    let mut i = 0;
+    // SAFETY: Every block starts at an element boundary because `N` divides
+    // the block size, and ends within the `size` bytes that the caller has
+    // guaranteed are valid for reads from `src` and writes to `dst`.
+    unsafe {
+        while i + 2 * BLOCK <= size {
+            block::swap::<N>(src.add(i), dst.add(i));
+            block::swap::<N>(src.add(i + BLOCK), dst.add(i + BLOCK));
+            i += 2 * BLOCK;
+        }
+        if i + BLOCK <= size {
+            block::swap::<N>(src.add(i), dst.add(i));
+            i += BLOCK;
+        }
+    }
+    while i < size {
+        for j in 0..N {
+            // SAFETY: `i` is the start of an element, so `i + j` and
+            // `i + N - 1 - j` are both within the element.
+            unsafe {
+                dst.add(i + j).write(src.add(i + N - 1 - j).read());
+            }
+        }
+        i += N;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::swap_bytes;
+
+    fn check<const N: usize>(len: usize) {
+        let mut src = [0u8; 33 * 16];
+        for (i, byte) in src.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        let mut dst = [0u8; 33 * 16];
+        unsafe {
+            swap_bytes::<N>(src.as_ptr(), dst.as_mut_ptr(), len);
+        }
+        for (i, byte) in dst.iter().enumerate() {
+            let expected = if i < len * N {
+                src[i - i % N + N - 1 - i % N]
+            } else {
+                0
+            };
+            assert_eq!(*byte, expected);
+        }
+    }
+
+    #[test]
+    fn swap_bytes_all_widths() {
+        for len in [0, 1, 3, 7, 8, 9, 16, 17, 33] {
+            check::<2>(len);
+            check::<4>(len);
+            check::<8>(len);
+            check::<16>(len);
+        }
+    }
+}
@@ -94,6 +94,13 @@ impl Group {
 
     #[inline]
     pub fn match_empty(self) -> Bitmask {
+        // EMPTY is the only control byte with its top two bits set.
+        let bits = self.0 & (self.0 << 1) & Self::repeat(0x80);
+        Bitmask(bits)
+    }
+
+    #[inline]
+    pub fn match_empty_or_deleted(self) -> Bitmask {
         let bits = self.0 & Self::repeat(0x80);
         Bitmask(bits)
     }
@@ -1,3 +1,7 @@
+mod byteswap;
+
+pub use self::byteswap::swap_bytes;
+
 #[cfg(all(
     target_feature = "sse2",
     any(target_arch = "x86", target_arch = "x86_64"),
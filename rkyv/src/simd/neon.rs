@@ -76,6 +76,11 @@ impl Group {
 
     #[inline]
     pub fn match_empty(self) -> Bitmask {
+        self.match_byte(0xff)
+    }
+
+    #[inline]
+    pub fn match_empty_or_deleted(self) -> Bitmask {
         unsafe {
             Self::unpack(aarch64::vcltzq_s8(aarch64::vreinterpretq_s8_u8(
                 self.0,
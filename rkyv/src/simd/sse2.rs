@@ -66,6 +66,11 @@ impl Group {
 
     #[inline]
     pub fn match_empty(self) -> Bitmask {
+        self.match_byte(0xff)
+    }
+
+    #[inline]
+    pub fn match_empty_or_deleted(self) -> Bitmask {
         unsafe { Bitmask(x86::_mm_movemask_epi8(self.0) as u16) }
     }
 
@@ -0,0 +1,259 @@
+//! Types with a stable archived format.
+//!
+//! [`StableFormat`] marks archived types whose format rkyv guarantees will not
+//! change in future releases. Archives made only of these types can be
+//! persisted long-term and read back by any later version of rkyv which was
+//! built with the same format features (`little_endian` or `big_endian`,
+//! `aligned` or `unaligned`, and the `pointer_width_*` features).
+//!
+//! The stable format ("format v1") covers:
+//!
+//! - The archived versions of `bool`, integers, floats, `char`, non-zero
+//!   integers, `()`, and the marker types `PhantomData` and `PhantomPinned`.
+//! - Arrays, slices, `str`, and tuples of stable types.
+//! - `ArchivedOption`, `ArchivedResult`, `ArchivedBox`, `ArchivedRc`,
+//!   `ArchivedVec`, `ArchivedString`, `ArchivedBTreeMap`, and
+//!   `ArchivedBTreeSet` of stable types.
+//! - `ArchivedDuration`, the archived network address types, and the archived
+//!   fixed-width integers.
+//! - Structs and enums generated by `#[derive(Archive)]` with
+//!   `#[rkyv(stable_format)]` whose fields are all stable types.
+//!
+//! Hash maps and hash sets are not covered because their layout depends on the
+//! hash function and the layout of their control bytes. Use a B-tree map or
+//! set for long-term storage instead.
+//!
+//! For structs and enums with `#[rkyv(stable_format)]`, the derive requires
+//! that the archived types of all fields implement `StableFormat`. If any of
+//! them does not, the derived impl fails to compile:
+//!
+//! ```compile_fail
+//! use std::collections::HashMap;
+//!
+//! use rkyv::Archive;
+//!
+//! #[derive(Archive)]
+//! #[rkyv(stable_format)]
+//! struct Index {
+//!     entries: HashMap<u32, String>,
+//! }
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{stable::assert_stable_format, Archive};
+//!
+//! #[derive(Archive)]
+//! #[rkyv(stable_format)]
+//! struct Record {
+//!     id: u64,
+//!     tags: Vec<String>,
+//!     parent: Option<Box<u64>>,
+//! }
+//!
+//! const _: () = assert_stable_format::<ArchivedRecord>();
+//! ```
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    num::{NonZeroI8, NonZeroU8},
+};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{btree_map::ArchivedBTreeMap, btree_set::ArchivedBTreeSet},
+    fixed_int::{ArchivedFixedInt, ArchivedFixedUint},
+    net::{
+        ArchivedIpv4Addr, ArchivedIpv6Addr, ArchivedSocketAddrV4,
+        ArchivedSocketAddrV6,
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedNonZeroI128, ArchivedNonZeroI16,
+        ArchivedNonZeroI32, ArchivedNonZeroI64, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    rc::ArchivedRc,
+    result::ArchivedResult,
+    string::ArchivedString,
+    time::ArchivedDuration,
+    traits::ArchivePointee,
+    tuple::*,
+    vec::ArchivedVec,
+    Portable,
+};
+
+/// An archived type with a stable format.
+///
+/// See the [module-level documentation](self) for more information.
+pub trait StableFormat: Portable {}
+
+/// Asserts at compile time that `T` has a stable format.
+///
+/// This can be used in a constant to check that a type stays stable:
+/// `const _: () = assert_stable_format::<ArchivedFoo>();`
+pub const fn assert_stable_format<T: StableFormat + ?Sized>() {}
+
+macro_rules! impl_stable {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl StableFormat for $ty {}
+        )*
+    };
+}
+
+impl_stable! {
+    (),
+    bool,
+    i8,
+    u8,
+    NonZeroI8,
+    NonZeroU8,
+    ArchivedI16,
+    ArchivedI32,
+    ArchivedI64,
+    ArchivedI128,
+    ArchivedU16,
+    ArchivedU32,
+    ArchivedU64,
+    ArchivedU128,
+    ArchivedF32,
+    ArchivedF64,
+    ArchivedChar,
+    ArchivedNonZeroI16,
+    ArchivedNonZeroI32,
+    ArchivedNonZeroI64,
+    ArchivedNonZeroI128,
+    ArchivedNonZeroU16,
+    ArchivedNonZeroU32,
+    ArchivedNonZeroU64,
+    ArchivedNonZeroU128,
+    PhantomPinned,
+    str,
+    ArchivedDuration,
+    ArchivedIpv4Addr,
+    ArchivedIpv6Addr,
+    ArchivedSocketAddrV4,
+    ArchivedSocketAddrV6,
+}
+
+impl<T: ?Sized> StableFormat for PhantomData<T> {}
+
+impl<T: StableFormat, const N: usize> StableFormat for [T; N] {}
+
+impl<T: StableFormat> StableFormat for [T] {}
+
+impl<const N: usize> StableFormat for ArchivedFixedUint<N> {}
+
+impl<const N: usize> StableFormat for ArchivedFixedInt<N> {}
+
+macro_rules! impl_tuple {
+    ($name:ident, $($ty:ident),*) => {
+        impl<$($ty: StableFormat),*> StableFormat for $name<$($ty),*> {}
+    };
+}
+
+impl_tuple!(ArchivedTuple1, T0);
+impl_tuple!(ArchivedTuple2, T0, T1);
+impl_tuple!(ArchivedTuple3, T0, T1, T2);
+impl_tuple!(ArchivedTuple4, T0, T1, T2, T3);
+impl_tuple!(ArchivedTuple5, T0, T1, T2, T3, T4);
+impl_tuple!(ArchivedTuple6, T0, T1, T2, T3, T4, T5);
+impl_tuple!(ArchivedTuple7, T0, T1, T2, T3, T4, T5, T6);
+impl_tuple!(ArchivedTuple8, T0, T1, T2, T3, T4, T5, T6, T7);
+impl_tuple!(ArchivedTuple9, T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple!(ArchivedTuple10, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(ArchivedTuple11, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(
+    ArchivedTuple12,
+    T0,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11
+);
+impl_tuple!(
+    ArchivedTuple13,
+    T0,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11,
+    T12
+);
+
+impl<T: StableFormat> StableFormat for ArchivedOption<T> {}
+
+impl<T: StableFormat, E: StableFormat> StableFormat for ArchivedResult<T, E> {}
+
+impl<T: ArchivePointee + StableFormat + ?Sized> StableFormat
+    for ArchivedBox<T>
+{
+}
+
+impl<T: ArchivePointee + StableFormat + ?Sized, F> StableFormat
+    for ArchivedRc<T, F>
+{
+}
+
+impl<T: StableFormat, O: StableFormat, L: StableFormat> StableFormat
+    for ArchivedVec<T, O, L>
+{
+}
+
+impl<const N: usize> StableFormat for ArchivedString<N> {}
+
+impl<K: StableFormat, V: StableFormat, const E: usize> StableFormat
+    for ArchivedBTreeMap<K, V, E>
+{
+}
+
+impl<K: StableFormat, const E: usize> StableFormat for ArchivedBTreeSet<K, E> {}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::assert_stable_format;
+    use crate::{
+        alloc::{boxed::Box, collections::BTreeMap, string::String, vec::Vec},
+        Archive,
+    };
+
+    #[derive(Archive)]
+    #[rkyv(crate, stable_format)]
+    struct Record {
+        id: u64,
+        name: String,
+        children: Vec<Box<[u32]>>,
+        attributes: BTreeMap<String, (u8, Option<i32>)>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, stable_format)]
+    enum Node<T> {
+        Leaf(T),
+        Branch { left: Box<T>, right: Box<T> },
+        Empty,
+    }
+
+    const _: () = assert_stable_format::<ArchivedRecord>();
+    const _: () = assert_stable_format::<ArchivedNode<u32>>();
+    const _: () = assert_stable_format::<[ArchivedNode<Record>]>();
+}
@@ -27,6 +27,12 @@ use crate::{
 /// This has inline and out-of-line representations. Short strings will use the
 /// available space inside the structure to store the string, and long strings
 /// will store a [`RelPtr`](crate::RelPtr) to a `str` instead.
+///
+/// Strings of up to `N` bytes are stored inline. `String` always archives with
+/// the default inline capacity, which is the size of the out-of-line
+/// representation. A larger inline capacity avoids out-of-line allocations for
+/// longer strings at the cost of making every archived string larger. It can
+/// be chosen for a field with [`InlineCapacity`](crate::with::InlineCapacity).
 #[repr(transparent)]
 #[cfg_attr(
     feature = "bytecheck",
@@ -35,11 +41,20 @@ use crate::{
 )]
 #[derive(Portable)]
 #[rkyv(crate)]
-pub struct ArchivedString {
-    repr: ArchivedStringRepr,
+pub struct ArchivedString<const N: usize = INLINE_CAPACITY> {
+    repr: ArchivedStringRepr<N>,
 }
 
-impl ArchivedString {
+impl<const N: usize> ArchivedString<N> {
+    /// The maximum number of bytes that are stored inline.
+    pub const INLINE_CAPACITY: usize = N;
+
+    /// Returns whether the string is stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        self.repr.is_inline()
+    }
+
     /// Extracts a string slice containing the entire `ArchivedString`.
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -62,7 +77,7 @@ impl ArchivedString {
         out: Place<Self>,
     ) {
         munge!(let ArchivedString { repr } = out);
-        if value.len() <= repr::INLINE_CAPACITY {
+        if value.len() <= N {
             unsafe {
                 ArchivedStringRepr::emplace_inline(value, repr.ptr());
             }
@@ -77,8 +92,12 @@ impl ArchivedString {
         }
     }
 
-    /// Serializes an archived string from a given `str`.
-    pub fn serialize_from_str<S: Fallible + ?Sized>(
+    /// Serializes an archived string with an inline capacity of `N` from a
+    /// given `str`.
+    ///
+    /// [`serialize_from_str`](ArchivedString::serialize_from_str) should be
+    /// used instead when using the default inline capacity.
+    pub fn serialize_with_inline_capacity<S: Fallible + ?Sized>(
         value: &str,
         serializer: &mut S,
     ) -> Result<StringResolver, S::Error>
@@ -86,7 +105,7 @@ impl ArchivedString {
         S::Error: Source,
         str: SerializeUnsized<S>,
     {
-        if value.len() <= INLINE_CAPACITY {
+        if value.len() <= N {
             Ok(StringResolver { pos: 0 })
         } else if value.len() > repr::OUT_OF_LINE_CAPACITY {
             #[derive(Debug)]
@@ -112,28 +131,43 @@ impl ArchivedString {
     }
 }
 
-impl AsRef<str> for ArchivedString {
+impl ArchivedString {
+    /// Serializes an archived string from a given `str`.
+    #[inline]
+    pub fn serialize_from_str<S: Fallible + ?Sized>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<StringResolver, S::Error>
+    where
+        S::Error: Source,
+        str: SerializeUnsized<S>,
+    {
+        Self::serialize_with_inline_capacity(value, serializer)
+    }
+}
+
+impl<const N: usize> AsRef<str> for ArchivedString<N> {
     #[inline]
     fn as_ref(&self) -> &str {
         self.as_str()
     }
 }
 
-impl Borrow<str> for ArchivedString {
+impl<const N: usize> Borrow<str> for ArchivedString<N> {
     #[inline]
     fn borrow(&self) -> &str {
         self.as_str()
     }
 }
 
-impl fmt::Debug for ArchivedString {
+impl<const N: usize> fmt::Debug for ArchivedString<N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(self.as_str(), f)
     }
 }
 
-impl Deref for ArchivedString {
+impl<const N: usize> Deref for ArchivedString<N> {
     type Target = str;
 
     #[inline]
@@ -142,16 +176,16 @@ impl Deref for ArchivedString {
     }
 }
 
-impl fmt::Display for ArchivedString {
+impl<const N: usize> fmt::Display for ArchivedString<N> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(self.as_str(), f)
     }
 }
 
-impl Eq for ArchivedString {}
+impl<const N: usize> Eq for ArchivedString<N> {}
 
-impl hash::Hash for ArchivedString {
+impl<const N: usize> hash::Hash for ArchivedString<N> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_str().hash(state)
     }
@@ -159,7 +193,7 @@ impl hash::Hash for ArchivedString {
 
 macro_rules! impl_index {
     ($index:ty) => {
-        impl Index<$index> for ArchivedString {
+        impl<const N: usize> Index<$index> for ArchivedString<N> {
             type Output = str;
 
             #[inline]
@@ -177,79 +211,79 @@ impl_index!(RangeInclusive<usize>);
 impl_index!(RangeTo<usize>);
 impl_index!(RangeToInclusive<usize>);
 
-impl Ord for ArchivedString {
+impl<const N: usize> Ord for ArchivedString<N> {
     #[inline]
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_str().cmp(other.as_str())
     }
 }
 
-impl PartialEq for ArchivedString {
+impl<const N: usize> PartialEq for ArchivedString<N> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.as_str() == other.as_str()
     }
 }
 
-impl PartialOrd for ArchivedString {
+impl<const N: usize> PartialOrd for ArchivedString<N> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl PartialEq<&str> for ArchivedString {
+impl<const N: usize> PartialEq<&str> for ArchivedString<N> {
     #[inline]
     fn eq(&self, other: &&str) -> bool {
         PartialEq::eq(self.as_str(), *other)
     }
 }
 
-impl PartialEq<str> for ArchivedString {
+impl<const N: usize> PartialEq<str> for ArchivedString<N> {
     #[inline]
     fn eq(&self, other: &str) -> bool {
         PartialEq::eq(self.as_str(), other)
     }
 }
 
-impl PartialEq<ArchivedString> for &str {
+impl<const N: usize> PartialEq<ArchivedString<N>> for &str {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<N>) -> bool {
         PartialEq::eq(other.as_str(), *self)
     }
 }
 
-impl PartialEq<ArchivedString> for str {
+impl<const N: usize> PartialEq<ArchivedString<N>> for str {
     #[inline]
-    fn eq(&self, other: &ArchivedString) -> bool {
+    fn eq(&self, other: &ArchivedString<N>) -> bool {
         PartialEq::eq(other.as_str(), self)
     }
 }
 
-impl PartialOrd<&str> for ArchivedString {
+impl<const N: usize> PartialOrd<&str> for ArchivedString<N> {
     #[inline]
     fn partial_cmp(&self, other: &&str) -> Option<cmp::Ordering> {
         self.as_str().partial_cmp(*other)
     }
 }
 
-impl PartialOrd<str> for ArchivedString {
+impl<const N: usize> PartialOrd<str> for ArchivedString<N> {
     #[inline]
     fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
         self.as_str().partial_cmp(other)
     }
 }
 
-impl PartialOrd<ArchivedString> for &str {
+impl<const N: usize> PartialOrd<ArchivedString<N>> for &str {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<N>) -> Option<cmp::Ordering> {
         self.partial_cmp(&other.as_str())
     }
 }
 
-impl PartialOrd<ArchivedString> for str {
+impl<const N: usize> PartialOrd<ArchivedString<N>> for str {
     #[inline]
-    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+    fn partial_cmp(&self, other: &ArchivedString<N>) -> Option<cmp::Ordering> {
         self.partial_cmp(other.as_str())
     }
 }
@@ -271,7 +305,7 @@ mod verify {
         validation::{ArchiveContext, ArchiveContextExt},
     };
 
-    unsafe impl<C> Verify<C> for ArchivedString
+    unsafe impl<C, const N: usize> Verify<C> for ArchivedString<N>
     where
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
@@ -283,7 +317,7 @@ mod verify {
                 }
             } else {
                 let base =
-                    (&self.repr as *const ArchivedStringRepr).cast::<u8>();
+                    (&self.repr as *const ArchivedStringRepr<N>).cast::<u8>();
                 let offset = unsafe { self.repr.out_of_line_offset() };
                 let metadata = self.repr.len();
 
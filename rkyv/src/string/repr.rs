@@ -25,7 +25,7 @@ struct OutOfLineRepr {
     _phantom: PhantomPinned,
 }
 
-/// The maximum number of bytes that can be inlined.
+/// The default maximum number of bytes that can be inlined.
 pub const INLINE_CAPACITY: usize = mem::size_of::<OutOfLineRepr>();
 /// The maximum number of bytes that can be out-of-line.
 pub const OUT_OF_LINE_CAPACITY: usize = !(0b11 << (FixedUsize::BITS - 2));
@@ -33,24 +33,31 @@ pub const OUT_OF_LINE_CAPACITY: usize = !(0b11 << (FixedUsize::BITS - 2));
 #[derive(Clone, Copy, Portable)]
 #[rkyv(crate)]
 #[repr(C)]
-struct InlineRepr {
-    bytes: [u8; INLINE_CAPACITY],
+struct InlineRepr<const N: usize> {
+    bytes: [u8; N],
 }
 
 /// An archived string representation that can inline short strings.
+///
+/// Strings of up to `N` bytes are stored inline. The representation is always
+/// at least as large as an out-of-line string, so values of `N` smaller than
+/// [`INLINE_CAPACITY`] don't save any space.
 #[derive(Portable)]
 #[rkyv(crate)]
 #[repr(C)]
-pub union ArchivedStringRepr {
+pub union ArchivedStringRepr<const N: usize = INLINE_CAPACITY> {
     out_of_line: OutOfLineRepr,
-    inline: InlineRepr,
+    inline: InlineRepr<N>,
 }
 
-impl ArchivedStringRepr {
+impl<const N: usize> ArchivedStringRepr<N> {
     /// Returns whether the representation is inline.
     #[inline]
     pub fn is_inline(&self) -> bool {
-        unsafe { self.inline.bytes[0] & 0xc0 != 0x80 }
+        // The first byte is shared by both representations, and `N` may be
+        // zero.
+        let tag = unsafe { *(self as *const Self).cast::<u8>() };
+        tag & 0xc0 != 0x80
     }
 
     /// Returns the offset of the representation.
@@ -103,7 +110,7 @@ impl ArchivedStringRepr {
                     .bytes
                     .iter()
                     .position(|b| *b == 0xff)
-                    .unwrap_or(INLINE_CAPACITY)
+                    .unwrap_or(N)
             }
         } else {
             let len = unsafe { self.out_of_line.len.to_native() };
@@ -163,20 +170,19 @@ impl ArchivedStringRepr {
     ///
     /// # Safety
     ///
-    /// - The length of `value` must be less than or equal to
-    ///   [`INLINE_CAPACITY`].
+    /// - The length of `value` must be less than or equal to `N`.
     /// - `out` must point to a valid location to write the inline
     ///   representation.
     #[inline]
     pub unsafe fn emplace_inline(value: &str, out: *mut Self) {
-        debug_assert!(value.len() <= INLINE_CAPACITY);
+        debug_assert!(value.len() <= N);
 
         // SAFETY: The caller has guaranteed that `out` points to a
         // dereferenceable location.
         let out_bytes = unsafe { ptr::addr_of_mut!((*out).inline.bytes) };
 
         // SAFETY: The caller has guaranteed that the length of `value` is less
-        // than or equal to `INLINE_CAPACITY`. We know that `out_bytes` is a
+        // than or equal to `N`. We know that `out_bytes` is a
         // valid pointer to bytes because it is a subfield of `out` which the
         // caller has guaranteed points to a valid location.
         unsafe {
@@ -189,12 +195,44 @@ impl ArchivedStringRepr {
         }
     }
 
+    /// Returns whether the representation is invalid.
+    ///
+    /// Invalid representations are out-of-line with a length of zero. They are
+    /// never produced for a string, so they can be used as a niche.
+    #[inline]
+    pub fn is_invalid(&self) -> bool {
+        !self.is_inline() && self.is_empty()
+    }
+
+    /// Emplaces an invalid representation.
+    ///
+    /// The invalid representation is out-of-line, has a length of zero, and an
+    /// invalid relative offset.
+    pub fn emplace_invalid(out: Place<Self>) {
+        #[cfg(not(feature = "big_endian"))]
+        let l: FixedUsize = 0b1000_0000;
+        #[cfg(feature = "big_endian")]
+        let l: FixedUsize = 1 << (FixedUsize::BITS - 1);
+
+        // SAFETY: Every bit pattern is valid for the out-of-line
+        // representation, so its fields can be written through the union.
+        unsafe {
+            munge! {
+                let ArchivedStringRepr {
+                    out_of_line: OutOfLineRepr { len, offset, _phantom: _ }
+                } = out;
+            }
+            len.write(ArchivedUsize::from_native(l));
+            offset.write(ArchivedIsize::from_native(1));
+        }
+    }
+
     /// Emplaces a new out-of-line representation for the given `str`.
     ///
     /// # Safety
     ///
-    /// The length of `str` must be greater than [`INLINE_CAPACITY`] and less
-    /// than or equal to [`OUT_OF_LINE_CAPACITY`].
+    /// The length of `str` must be greater than `N` and less than or equal to
+    /// [`OUT_OF_LINE_CAPACITY`].
     pub unsafe fn try_emplace_out_of_line<E: Source>(
         value: &str,
         target: usize,
@@ -228,8 +266,8 @@ impl ArchivedStringRepr {
     ///
     /// # Safety
     ///
-    /// The length of `str` must be greater than [`INLINE_CAPACITY`] and less
-    /// than or equal to [`OUT_OF_LINE_CAPACITY`].
+    /// The length of `str` must be greater than `N` and less than or equal to
+    /// [`OUT_OF_LINE_CAPACITY`].
     #[inline]
     pub unsafe fn emplace_out_of_line(
         value: &str,
@@ -254,8 +292,8 @@ const _: () = {
 
     /// An error resulting from an invalid string representation.
     ///
-    /// Strings that are inline must have a length of at most
-    /// [`INLINE_CAPACITY`].
+    /// Strings that are out-of-line must have a length greater than the inline
+    /// capacity.
     #[derive(Debug)]
     pub struct CheckStringReprError;
 
@@ -271,7 +309,7 @@ const _: () = {
 
     impl Error for CheckStringReprError {}
 
-    unsafe impl<C> CheckBytes<C> for ArchivedStringRepr
+    unsafe impl<C, const N: usize> CheckBytes<C> for ArchivedStringRepr<N>
     where
         C: Fallible + ?Sized,
         C::Error: Source,
@@ -284,7 +322,7 @@ const _: () = {
             // every bit pattern.
             let repr = unsafe { &*value };
 
-            if !repr.is_inline() && repr.len() <= INLINE_CAPACITY {
+            if !repr.is_inline() && repr.len() <= N {
                 fail!(CheckStringReprError);
             } else {
                 Ok(())
@@ -0,0 +1,261 @@
+//! Utilities for fuzzing archived types.
+//!
+//! Validation is the only thing standing between untrusted bytes and
+//! undefined behavior, so it's worth fuzzing the validation of your own
+//! archived types. [`fuzz_access`] takes arbitrary bytes, validates them as an
+//! archived type, and then walks every field of the archived value by
+//! deserializing it. The derived `Deserialize` impl visits every field of a
+//! type, so any bytes which pass validation but point outside of the buffer or
+//! contain invalid values will be caught by tools like Miri or the address
+//! sanitizer.
+//!
+//! [`check_round_trip`] checks that a value survives serialization, validation,
+//! and deserialization unchanged. It's useful for fuzzing with structured
+//! inputs generated by crates like `arbitrary`.
+//!
+//! With the `arbitrary-1` feature enabled, `check_arbitrary_round_trips`
+//! checks the round trip for many arbitrary values of a type, and the
+//! `round_trip_tests!` macro generates a test for each type it's given.
+//!
+//! # Example
+//!
+//! A fuzz target for `cargo fuzz` might look like this:
+//!
+//! ```
+//! use rkyv::{testing::fuzz_access, Archive, Deserialize, Serialize};
+//!
+//! #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+//! struct Packet {
+//!     id: u32,
+//!     payload: Vec<u8>,
+//!     tags: Vec<String>,
+//! }
+//!
+//! // libfuzzer_sys::fuzz_target!(|data: &[u8]| { ... });
+//! fn fuzz_target(data: &[u8]) {
+//!     let _ = fuzz_access::<Packet>(data);
+//! }
+//!
+//! fuzz_target(&[]);
+//! fuzz_target(&[0xff; 64]);
+//! ```
+
+use core::fmt::Debug;
+
+#[cfg(feature = "arbitrary-1")]
+use arbitrary_1::{Arbitrary, Unstructured};
+use bytecheck::CheckBytes;
+use rancor::{Failure, Strategy};
+
+#[cfg(feature = "arbitrary-1")]
+use crate::alloc::vec::Vec;
+use crate::{
+    api::high::{access, deserialize, to_bytes, HighSerializer, HighValidator},
+    de::Pool,
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Archive, Deserialize, Serialize,
+};
+
+type RoundTripSerializer<'a> =
+    HighSerializer<AlignedVec, ArenaHandle<'a>, Failure>;
+
+/// Validates arbitrary bytes as an archived `T` and walks every field of the
+/// archived value.
+///
+/// The bytes are copied into an aligned buffer first, so the input does not
+/// need to be aligned. If validation succeeds, the archived value is
+/// deserialized to visit all of its fields and the deserialized value is
+/// returned. Returns `None` if the bytes are rejected.
+///
+/// This never panics or triggers undefined behavior unless there is a bug in
+/// the validation or deserialization of `T`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, testing::fuzz_access, to_bytes};
+///
+/// let bytes = to_bytes::<Error>(&vec![1u16, 2, 3]).unwrap();
+/// assert_eq!(fuzz_access::<Vec<u16>>(&bytes), Some(vec![1, 2, 3]));
+/// assert_eq!(fuzz_access::<Vec<u16>>(&bytes[1..]), None);
+/// ```
+pub fn fuzz_access<T>(data: &[u8]) -> Option<T>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, Failure>>
+        + Deserialize<T, Strategy<Pool, Failure>>,
+{
+    let mut bytes = AlignedVec::<16>::with_capacity(data.len());
+    bytes.extend_from_slice(data);
+    let archived = access::<T::Archived, Failure>(&bytes).ok()?;
+    deserialize::<T, Failure>(archived).ok()
+}
+
+/// Checks that a value is unchanged after being serialized, validated, and
+/// deserialized.
+///
+/// # Panics
+///
+/// Panics if the value fails to serialize, validate, or deserialize, or if the
+/// deserialized value is not equal to the original.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{testing::check_round_trip, Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// struct Example {
+///     name: String,
+///     values: Vec<i32>,
+/// }
+///
+/// check_round_trip(&Example {
+///     name: "pi".to_string(),
+///     values: vec![3, 1, 4, 1, 5],
+/// });
+/// ```
+pub fn check_round_trip<T>(value: &T)
+where
+    T: Archive + for<'a> Serialize<RoundTripSerializer<'a>> + PartialEq + Debug,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, Failure>>
+        + Deserialize<T, Strategy<Pool, Failure>>,
+{
+    let bytes = to_bytes::<Failure>(value).expect("failed to serialize value");
+    let archived = access::<T::Archived, Failure>(&bytes)
+        .expect("failed to validate serialized value");
+    let deserialized = deserialize::<T, Failure>(archived)
+        .expect("failed to deserialize archived value");
+    assert_eq!(
+        &deserialized, value,
+        "value changed after a round trip through rkyv",
+    );
+}
+
+/// The number of cases checked by each test generated by
+/// [`round_trip_tests!`](crate::round_trip_tests).
+#[cfg(feature = "arbitrary-1")]
+pub const DEFAULT_CASES: usize = 256;
+
+/// Checks that arbitrary values of `T` are unchanged after being serialized,
+/// validated, and deserialized.
+///
+/// The values are generated from a fixed sequence of pseudo-random bytes, so
+/// the same values are checked each time. Later cases are generated from more
+/// bytes than earlier cases, so they tend to be larger.
+///
+/// # Panics
+///
+/// Panics if any of the values fails to round trip. See [`check_round_trip`]
+/// for details.
+#[cfg(feature = "arbitrary-1")]
+pub fn check_arbitrary_round_trips<T>(cases: usize)
+where
+    T: for<'a> Arbitrary<'a>
+        + Archive
+        + for<'a> Serialize<RoundTripSerializer<'a>>
+        + PartialEq
+        + Debug,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, Failure>>
+        + Deserialize<T, Strategy<Pool, Failure>>,
+{
+    const MAX_LEN: usize = 4096;
+
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut data = Vec::with_capacity(MAX_LEN);
+    for case in 0..cases {
+        data.clear();
+        let len = usize::min(16 * (case + 1), MAX_LEN);
+        for _ in 0..len {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            data.push(state as u8);
+        }
+
+        let mut u = Unstructured::new(&data);
+        if let Ok(value) = T::arbitrary(&mut u) {
+            check_round_trip(&value);
+        }
+    }
+}
+
+/// Generates a test which checks that arbitrary values of a type round trip
+/// through rkyv.
+///
+/// Each generated test calls [`check_arbitrary_round_trips`] with
+/// [`DEFAULT_CASES`]. This requires the `arbitrary-1` feature.
+///
+/// [`check_arbitrary_round_trips`]: crate::testing::check_arbitrary_round_trips
+/// [`DEFAULT_CASES`]: crate::testing::DEFAULT_CASES
+///
+/// # Example
+///
+/// ```ignore
+/// rkyv::round_trip_tests! {
+///     strings_round_trip: Vec<String>,
+///     durations_round_trip: Option<core::time::Duration>,
+/// }
+/// ```
+#[cfg(feature = "arbitrary-1")]
+#[macro_export]
+macro_rules! round_trip_tests {
+    ($($name:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::testing::check_arbitrary_round_trips::<$ty>(
+                    $crate::testing::DEFAULT_CASES,
+                );
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use rancor::Failure;
+
+    use super::{check_round_trip, fuzz_access};
+    use crate::{
+        alloc::{string::String, vec, vec::Vec},
+        api::high::to_bytes,
+    };
+
+    type Value = Vec<(u32, String)>;
+
+    #[test]
+    fn fuzz_every_mutation() {
+        let value: Value = vec![
+            (1, "a string which is stored out of line".into()),
+            (2, "short".into()),
+        ];
+        check_round_trip(&value);
+
+        let bytes = to_bytes::<Failure>(&value).unwrap();
+        assert_eq!(fuzz_access::<Value>(&bytes), Some(value));
+
+        let mut mutated = bytes.to_vec();
+        for i in 0..mutated.len() {
+            for byte in [0x00, 0x7f, 0x80, 0xff] {
+                let original = core::mem::replace(&mut mutated[i], byte);
+                let _ = fuzz_access::<Value>(&mutated);
+                mutated[i] = original;
+            }
+        }
+        for len in 0..bytes.len() {
+            let _ = fuzz_access::<Value>(&bytes[..len]);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "arbitrary-1"))]
+mod arbitrary_tests {
+    use crate::alloc::{string::String, vec::Vec};
+
+    crate::round_trip_tests! {
+        strings_round_trip: Vec<String>,
+        options_round_trip: Vec<Option<(u8, i64, char)>>,
+    }
+}
@@ -273,6 +273,85 @@ pub trait Serialize<S: Fallible + ?Sized>: Archive {
 pub trait Deserialize<T, D: Fallible + ?Sized> {
     /// Deserializes using the given deserializer
     fn deserialize(&self, deserializer: &mut D) -> Result<T, D::Error>;
+
+    /// Deserializes directly into `out` instead of returning the value.
+    ///
+    /// The default implementation deserializes the value and then writes it to
+    /// `out`. Types which may be large, like arrays and derived structs,
+    /// override this to construct their fields in place so that the value is
+    /// never moved.
+    ///
+    /// If an error is returned, `out` is left uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// `out` must be non-null, properly aligned, and valid for writes.
+    #[inline]
+    unsafe fn deserialize_into(
+        &self,
+        deserializer: &mut D,
+        out: *mut T,
+    ) -> Result<(), D::Error> {
+        // SAFETY: The caller has guaranteed that `out` is non-null, properly
+        // aligned, and valid for writes.
+        unsafe {
+            out.write(self.deserialize(deserializer)?);
+        }
+        Ok(())
+    }
+
+    /// Deserializes a slice of values into `out`.
+    ///
+    /// The default implementation deserializes each value in order. Types
+    /// which can be converted in bulk, like archived primitives with a
+    /// different endianness than the target, override this to deserialize
+    /// slices faster.
+    ///
+    /// # Safety
+    ///
+    /// `out` must be non-null, properly aligned, and valid for writes of
+    /// `values.len()` elements.
+    #[inline]
+    unsafe fn deserialize_slice(
+        values: &[Self],
+        deserializer: &mut D,
+        out: *mut T,
+    ) -> Result<(), D::Error>
+    where
+        Self: Sized,
+    {
+        for (i, value) in values.iter().enumerate() {
+            // SAFETY: The caller has guaranteed that `out` is valid for writes
+            // of `values.len()` elements. Since `i` is less than the length of
+            // `values`, the result of the pointer add is always in-bounds and
+            // valid for writes.
+            unsafe {
+                out.add(i).write(value.deserialize(deserializer)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A type with a schema version.
+///
+/// Values of versioned types can be serialized with a version tag using
+/// [`VersionedRoot`](crate::version::VersionedRoot). Readers can then check the
+/// version of an archive before choosing which type to access it as. See the
+/// [`version`](crate::version) module for more details.
+///
+/// This can be derived with `#[rkyv(version = ..)]`, which implements
+/// `Versioned` for both the type and its archived type.
+pub trait Versioned {
+    /// The schema version of this type.
+    const VERSION: u32;
+
+    /// The oldest version whose archives can be read as this type.
+    ///
+    /// This is the same as [`VERSION`](Versioned::VERSION) unless the type has
+    /// `#[rkyv(default)]` fields, in which case it is the version before the
+    /// first `default` field was added.
+    const MIN_VERSION: u32 = Self::VERSION;
 }
 
 /// A counterpart of [`Archive`] that's suitable for unsized types.
@@ -1,5 +1,12 @@
 //! Archived versions of tuple types.
 
+#[cfg(feature = "serde-1")]
+use serde_1::Serializer;
+
+#[cfg(feature = "alloc")]
+use crate::diff::{Diff, Differ};
+#[cfg(feature = "serde-1")]
+use crate::serde::{Serde, SerializeArchived};
 use crate::Portable;
 
 macro_rules! impl_tuple {
@@ -28,6 +35,47 @@ macro_rules! impl_tuple {
                 $(self.$index == other.$index)&&*
             }
         }
+
+        #[cfg(feature = "serde-1")]
+        impl<$($t: SerializeArchived),*> SerializeArchived
+            for $name<$($t),*>
+        {
+            fn serialize_archived<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                use serde_1::ser::SerializeTuple as _;
+
+                let mut tuple = serializer.serialize_tuple($n)?;
+                $(tuple.serialize_element(&Serde(&self.$index))?;)*
+                tuple.end()
+            }
+        }
+
+        #[cfg(feature = "serde-1")]
+        impl<$($t: SerializeArchived),*> serde_1::Serialize
+            for $name<$($t),*>
+        {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                self.serialize_archived(serializer)
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<$($t: Diff),*> Diff for $name<$($t),*> {
+            fn diff(&self, other: &Self, differ: &mut Differ) {
+                $(
+                    differ.field(
+                        stringify!($index),
+                        &self.$index,
+                        &other.$index,
+                    );
+                )*
+            }
+        }
     };
 }
 
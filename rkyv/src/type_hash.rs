@@ -0,0 +1,446 @@
+//! Stable hashes of archived type definitions.
+//!
+//! [`TypeHash`] gives archived types a deterministic 64-bit hash of their
+//! structure. The hash of a struct or enum covers the names of its fields and
+//! variants, the hashes of their types, and its size and alignment. Changing
+//! any of these changes the hash, while renaming or moving the type does not.
+//! Type hashes are computed at compile time and don't depend on the compiler
+//! version, so they can be stored alongside archives and compared before any
+//! of the archived bytes are accessed.
+//!
+//! `TypeHash` is implemented for the archived versions of primitives, tuples,
+//! arrays, and rkyv's archived containers. It can be implemented for archived
+//! types generated by `#[derive(Archive)]` with `#[rkyv(type_hash)]`, as long
+//! as the archived types of all of their fields implement `TypeHash`.
+//!
+//! [Archive headers](crate::header) record the type hash of their root type.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::type_hash::TypeHash;
+//!
+//! mod v1 {
+//!     #[derive(rkyv::Archive)]
+//!     #[rkyv(type_hash)]
+//!     pub struct User {
+//!         pub id: u32,
+//!         pub name: String,
+//!     }
+//! }
+//!
+//! mod v2 {
+//!     #[derive(rkyv::Archive)]
+//!     #[rkyv(type_hash)]
+//!     pub struct User {
+//!         pub id: u64,
+//!         pub name: String,
+//!     }
+//! }
+//!
+//! assert_ne!(v1::ArchivedUser::TYPE_HASH, v2::ArchivedUser::TYPE_HASH);
+//! ```
+
+use core::{
+    marker::{PhantomData, PhantomPinned},
+    mem::{align_of, size_of},
+    num::{NonZeroI8, NonZeroU8},
+};
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_map::ArchivedBTreeMap,
+        btree_set::ArchivedBTreeSet,
+        swiss_table::{ArchivedHashMap, ArchivedHashSet},
+    },
+    option::ArchivedOption,
+    primitive::{
+        ArchivedChar, ArchivedF32, ArchivedF64, ArchivedI128, ArchivedI16,
+        ArchivedI32, ArchivedI64, ArchivedNonZeroI128, ArchivedNonZeroI16,
+        ArchivedNonZeroI32, ArchivedNonZeroI64, ArchivedNonZeroU128,
+        ArchivedNonZeroU16, ArchivedNonZeroU32, ArchivedNonZeroU64,
+        ArchivedU128, ArchivedU16, ArchivedU32, ArchivedU64,
+    },
+    rc::ArchivedRc,
+    result::ArchivedResult,
+    string::ArchivedString,
+    traits::ArchivePointee,
+    tuple::*,
+    vec::ArchivedVec,
+};
+
+/// An archived type with a stable hash of its definition.
+///
+/// See the [module-level documentation](self) for more information.
+pub trait TypeHash {
+    /// The hash of this type's definition.
+    const TYPE_HASH: u64;
+}
+
+/// A builder for type hashes which can be used in constant expressions.
+///
+/// This computes the 64-bit FNV-1a hash of everything written to it.
+#[derive(Clone, Copy, Debug)]
+pub struct TypeHasher {
+    state: u64,
+}
+
+impl TypeHasher {
+    /// Returns a new `TypeHasher`.
+    pub const fn new() -> Self {
+        Self {
+            state: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+
+    /// Writes the given bytes to the hasher.
+    pub const fn write_bytes(mut self, bytes: &[u8]) -> Self {
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut i = 0;
+        while i < bytes.len() {
+            self.state ^= bytes[i] as u64;
+            self.state = self.state.wrapping_mul(PRIME);
+            i += 1;
+        }
+        self
+    }
+
+    /// Writes the given integer to the hasher.
+    pub const fn write_u64(self, value: u64) -> Self {
+        self.write_bytes(&value.to_le_bytes())
+    }
+
+    /// Writes the given string to the hasher, prefixed with its length.
+    pub const fn write_str(self, value: &str) -> Self {
+        self.write_u64(value.len() as u64)
+            .write_bytes(value.as_bytes())
+    }
+
+    /// Writes the type hash of `T` to the hasher.
+    pub const fn write_type<T: TypeHash + ?Sized>(self) -> Self {
+        self.write_u64(T::TYPE_HASH)
+    }
+
+    /// Writes the size and alignment of `T` to the hasher.
+    pub const fn write_layout<T>(self) -> Self {
+        self.write_u64(size_of::<T>() as u64)
+            .write_u64(align_of::<T>() as u64)
+    }
+
+    /// Returns the hash of everything written to the hasher.
+    pub const fn finish(self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for TypeHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty: $name:literal),* $(,)?) => {
+        $(
+            impl TypeHash for $ty {
+                const TYPE_HASH: u64 = TypeHasher::new()
+                    .write_str($name)
+                    .write_layout::<Self>()
+                    .finish();
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    (): "()",
+    bool: "bool",
+    i8: "i8",
+    u8: "u8",
+    NonZeroI8: "NonZeroI8",
+    NonZeroU8: "NonZeroU8",
+    ArchivedI16: "i16",
+    ArchivedI32: "i32",
+    ArchivedI64: "i64",
+    ArchivedI128: "i128",
+    ArchivedU16: "u16",
+    ArchivedU32: "u32",
+    ArchivedU64: "u64",
+    ArchivedU128: "u128",
+    ArchivedF32: "f32",
+    ArchivedF64: "f64",
+    ArchivedChar: "char",
+    ArchivedNonZeroI16: "NonZeroI16",
+    ArchivedNonZeroI32: "NonZeroI32",
+    ArchivedNonZeroI64: "NonZeroI64",
+    ArchivedNonZeroI128: "NonZeroI128",
+    ArchivedNonZeroU16: "NonZeroU16",
+    ArchivedNonZeroU32: "NonZeroU32",
+    ArchivedNonZeroU64: "NonZeroU64",
+    ArchivedNonZeroU128: "NonZeroU128",
+    PhantomPinned: "PhantomPinned",
+}
+
+impl TypeHash for str {
+    const TYPE_HASH: u64 = TypeHasher::new().write_str("str").finish();
+}
+
+impl<T: ?Sized> TypeHash for PhantomData<T> {
+    const TYPE_HASH: u64 = TypeHasher::new().write_str("PhantomData").finish();
+}
+
+impl<T: TypeHash, const N: usize> TypeHash for [T; N] {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("array")
+        .write_type::<T>()
+        .write_u64(N as u64)
+        .finish();
+}
+
+impl<T: TypeHash> TypeHash for [T] {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("slice")
+        .write_type::<T>()
+        .finish();
+}
+
+macro_rules! impl_tuple {
+    ($name:ident, $($ty:ident),*) => {
+        impl<$($ty: TypeHash),*> TypeHash for $name<$($ty),*> {
+            const TYPE_HASH: u64 = TypeHasher::new()
+                .write_str("tuple")
+                $(.write_type::<$ty>())*
+                .write_layout::<Self>()
+                .finish();
+        }
+    };
+}
+
+impl_tuple!(ArchivedTuple1, T0);
+impl_tuple!(ArchivedTuple2, T0, T1);
+impl_tuple!(ArchivedTuple3, T0, T1, T2);
+impl_tuple!(ArchivedTuple4, T0, T1, T2, T3);
+impl_tuple!(ArchivedTuple5, T0, T1, T2, T3, T4);
+impl_tuple!(ArchivedTuple6, T0, T1, T2, T3, T4, T5);
+impl_tuple!(ArchivedTuple7, T0, T1, T2, T3, T4, T5, T6);
+impl_tuple!(ArchivedTuple8, T0, T1, T2, T3, T4, T5, T6, T7);
+impl_tuple!(ArchivedTuple9, T0, T1, T2, T3, T4, T5, T6, T7, T8);
+impl_tuple!(ArchivedTuple10, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+impl_tuple!(ArchivedTuple11, T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+impl_tuple!(
+    ArchivedTuple12,
+    T0,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11
+);
+impl_tuple!(
+    ArchivedTuple13,
+    T0,
+    T1,
+    T2,
+    T3,
+    T4,
+    T5,
+    T6,
+    T7,
+    T8,
+    T9,
+    T10,
+    T11,
+    T12
+);
+
+impl<T: TypeHash> TypeHash for ArchivedOption<T> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("Option")
+        .write_type::<T>()
+        .finish();
+}
+
+impl<T: TypeHash, E: TypeHash> TypeHash for ArchivedResult<T, E> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("Result")
+        .write_type::<T>()
+        .write_type::<E>()
+        .finish();
+}
+
+impl<T: ArchivePointee + TypeHash + ?Sized> TypeHash for ArchivedBox<T> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("Box")
+        .write_type::<T>()
+        .finish();
+}
+
+impl<T: ArchivePointee + TypeHash + ?Sized, F> TypeHash for ArchivedRc<T, F> {
+    const TYPE_HASH: u64 =
+        TypeHasher::new().write_str("Rc").write_type::<T>().finish();
+}
+
+impl<T: TypeHash, O: TypeHash, L: TypeHash> TypeHash for ArchivedVec<T, O, L> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("Vec")
+        .write_type::<T>()
+        .write_type::<O>()
+        .write_type::<L>()
+        .finish();
+}
+
+impl<const N: usize> TypeHash for ArchivedString<N> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("String")
+        .write_u64(N as u64)
+        .write_layout::<Self>()
+        .finish();
+}
+
+impl<K: TypeHash, V: TypeHash, H> TypeHash for ArchivedHashMap<K, V, H> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("HashMap")
+        .write_type::<K>()
+        .write_type::<V>()
+        .finish();
+}
+
+impl<K: TypeHash, H> TypeHash for ArchivedHashSet<K, H> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("HashSet")
+        .write_type::<K>()
+        .finish();
+}
+
+impl<K: TypeHash, V: TypeHash, const E: usize> TypeHash
+    for ArchivedBTreeMap<K, V, E>
+{
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("BTreeMap")
+        .write_type::<K>()
+        .write_type::<V>()
+        .write_u64(E as u64)
+        .finish();
+}
+
+impl<K: TypeHash, const E: usize> TypeHash for ArchivedBTreeSet<K, E> {
+    const TYPE_HASH: u64 = TypeHasher::new()
+        .write_str("BTreeSet")
+        .write_type::<K>()
+        .write_u64(E as u64)
+        .finish();
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        type_hash::TypeHash,
+        Archive, Archived,
+    };
+
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    mod renamed {
+        use crate::Archive;
+
+        #[allow(dead_code)]
+        #[derive(Archive)]
+        #[rkyv(crate, type_hash)]
+        pub struct Position {
+            x: i32,
+            y: i32,
+        }
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    struct SwappedPoint {
+        y: i32,
+        x: i32,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    struct WidePoint {
+        x: i64,
+        y: i64,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    struct Generic<T> {
+        value: T,
+        values: Vec<T>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    enum Shape {
+        Circle { radius: f32 },
+        Polygon(Vec<Point>),
+        Empty,
+    }
+
+    #[allow(dead_code)]
+    #[derive(Archive)]
+    #[rkyv(crate, type_hash)]
+    enum ShapeWithoutEmpty {
+        Circle { radius: f32 },
+        Polygon(Vec<Point>),
+    }
+
+    #[test]
+    fn derived_type_hashes() {
+        let point = ArchivedPoint::TYPE_HASH;
+        assert_eq!(point, renamed::ArchivedPosition::TYPE_HASH);
+        assert_ne!(point, ArchivedSwappedPoint::TYPE_HASH);
+        assert_ne!(point, ArchivedWidePoint::TYPE_HASH);
+
+        assert_ne!(
+            ArchivedGeneric::<u32>::TYPE_HASH,
+            ArchivedGeneric::<String>::TYPE_HASH,
+        );
+        assert_ne!(
+            ArchivedShape::TYPE_HASH,
+            ArchivedShapeWithoutEmpty::TYPE_HASH,
+        );
+    }
+
+    #[test]
+    fn container_type_hashes() {
+        assert_ne!(
+            Archived::<Vec<u32>>::TYPE_HASH,
+            Archived::<Vec<u64>>::TYPE_HASH,
+        );
+        assert_ne!(
+            Archived::<Option<u32>>::TYPE_HASH,
+            Archived::<Vec<u32>>::TYPE_HASH,
+        );
+        assert_ne!(
+            Archived::<(u32, String)>::TYPE_HASH,
+            Archived::<(String, u32)>::TYPE_HASH,
+        );
+        assert_ne!(
+            Archived::<[u8; 4]>::TYPE_HASH,
+            Archived::<[u8; 8]>::TYPE_HASH,
+        );
+    }
+}
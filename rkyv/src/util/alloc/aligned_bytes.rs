@@ -0,0 +1,100 @@
+use core::{borrow::Borrow, fmt, ops::Deref};
+
+use crate::util::AlignedVec;
+
+enum Inner<'a, const A: usize> {
+    Borrowed(&'a [u8]),
+    Owned(AlignedVec<A>),
+}
+
+/// A byte slice which is aligned to `A` bytes.
+///
+/// Like a `Cow<[u8]>`, this borrows the original bytes if they are already
+/// aligned and only copies them into an [`AlignedVec`] if they are not.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::util::{AlignedBytes, AlignedVec};
+///
+/// let mut vec = AlignedVec::<16>::new();
+/// vec.extend_from_slice(&[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+///
+/// let aligned = AlignedBytes::<16>::new(&vec);
+/// assert!(aligned.is_borrowed());
+///
+/// let misaligned = AlignedBytes::<16>::new(&vec[1..]);
+/// assert!(!misaligned.is_borrowed());
+/// assert_eq!(misaligned.as_ptr() as usize % 16, 0);
+/// assert_eq!(*misaligned, vec[1..]);
+/// ```
+pub struct AlignedBytes<'a, const A: usize = 16> {
+    inner: Inner<'a, A>,
+}
+
+impl<'a, const A: usize> AlignedBytes<'a, A> {
+    /// Borrows the given bytes if they are aligned to `A` bytes, and copies
+    /// them into a new [`AlignedVec`] otherwise.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let inner = if bytes.as_ptr() as usize % A == 0 {
+            Inner::Borrowed(bytes)
+        } else {
+            let mut vec = AlignedVec::with_capacity(bytes.len());
+            vec.extend_from_slice(bytes);
+            Inner::Owned(vec)
+        };
+        Self { inner }
+    }
+
+    /// Returns whether the bytes were borrowed instead of copied.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.inner, Inner::Borrowed(_))
+    }
+
+    /// Returns the aligned bytes as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        match &self.inner {
+            Inner::Borrowed(bytes) => bytes,
+            Inner::Owned(vec) => vec.as_slice(),
+        }
+    }
+
+    /// Converts the aligned bytes into an [`AlignedVec`], copying them if they
+    /// were borrowed.
+    pub fn into_owned(self) -> AlignedVec<A> {
+        match self.inner {
+            Inner::Borrowed(bytes) => {
+                let mut vec = AlignedVec::with_capacity(bytes.len());
+                vec.extend_from_slice(bytes);
+                vec
+            }
+            Inner::Owned(vec) => vec,
+        }
+    }
+}
+
+impl<const A: usize> AsRef<[u8]> for AlignedBytes<'_, A> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const A: usize> Borrow<[u8]> for AlignedBytes<'_, A> {
+    fn borrow(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<const A: usize> fmt::Debug for AlignedBytes<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<const A: usize> Deref for AlignedBytes<'_, A> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
@@ -1,7 +1,9 @@
 use core::{
     alloc::Layout,
     borrow::{Borrow, BorrowMut},
+    error::Error,
     fmt,
+    mem::ManuallyDrop,
     ops::{Deref, DerefMut, Index, IndexMut},
     ptr::NonNull,
     slice,
@@ -21,6 +23,61 @@ use crate::{
     Place,
 };
 
+/// How an [`AlignedVec`] grows its capacity when it runs out of space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// Grow the capacity to the next power of two which fits the new length.
+    ///
+    /// This makes repeated pushes take amortized constant time, but may leave
+    /// up to half of the capacity unused.
+    #[default]
+    Amortized,
+    /// Grow the capacity to exactly fit the new length.
+    ///
+    /// This never allocates more memory than is needed, but reallocates each
+    /// time the vector runs out of capacity. It's best suited for vectors
+    /// which are filled by a few large writes.
+    Exact,
+}
+
+/// The error returned when an [`AlignedVec`] fails to reserve capacity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryReserveError {
+    kind: TryReserveErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TryReserveErrorKind {
+    CapacityOverflow,
+    AllocError { layout: Layout },
+}
+
+impl TryReserveError {
+    const CAPACITY_OVERFLOW: Self = Self {
+        kind: TryReserveErrorKind::CapacityOverflow,
+    };
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            TryReserveErrorKind::CapacityOverflow => write!(
+                f,
+                "requested capacity exceeds the maximum capacity of an \
+                 AlignedVec"
+            ),
+            TryReserveErrorKind::AllocError { layout } => write!(
+                f,
+                "failed to allocate {} bytes with alignment {}",
+                layout.size(),
+                layout.align(),
+            ),
+        }
+    }
+}
+
+impl Error for TryReserveError {}
+
 /// A vector of bytes that aligns its memory to the specified alignment.
 ///
 /// ```
@@ -32,6 +89,7 @@ pub struct AlignedVec<const ALIGNMENT: usize = 16> {
     ptr: NonNull<u8>,
     cap: usize,
     len: usize,
+    growth: GrowthPolicy,
 }
 
 impl<const A: usize> Drop for AlignedVec<A> {
@@ -112,6 +170,7 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
                 ptr: NonNull::dangling(),
                 cap: 0,
                 len: 0,
+                growth: GrowthPolicy::Amortized,
             }
         } else {
             assert!(
@@ -135,10 +194,84 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
                 ptr,
                 cap: capacity,
                 len: 0,
+                growth: GrowthPolicy::Amortized,
             }
         }
     }
 
+    /// Converts a `Vec<u8>` into an `AlignedVec`.
+    ///
+    /// When `ALIGNMENT` is 1, this reuses the allocation of the `Vec`.
+    /// Otherwise, the bytes are copied into a new allocation with exactly
+    /// enough capacity to hold them, since the allocation of a `Vec<u8>` is
+    /// not guaranteed to be aligned.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let vec = AlignedVec::<16>::from_vec(vec![1, 2, 3]);
+    /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+    /// assert_eq!(vec.as_ptr() as usize % 16, 0);
+    ///
+    /// let bytes = vec![1, 2, 3];
+    /// let ptr = bytes.as_ptr();
+    /// let vec = AlignedVec::<1>::from_vec(bytes);
+    /// assert_eq!(vec.as_ptr(), ptr);
+    /// ```
+    pub fn from_vec(vec: Vec<u8>) -> Self {
+        if ALIGNMENT == 1 {
+            let mut vec = ManuallyDrop::new(vec);
+            let mut result = Self::new();
+            if vec.capacity() != 0 {
+                // SAFETY: A `Vec<u8>` with nonzero capacity always has a
+                // non-null pointer to memory allocated by the global allocator
+                // with a layout of `vec.capacity()` bytes with alignment 1,
+                // which is the same layout that `AlignedVec<1>` uses.
+                result.ptr =
+                    unsafe { NonNull::new_unchecked(vec.as_mut_ptr()) };
+                result.cap = vec.capacity();
+                result.len = vec.len();
+            }
+            result
+        } else {
+            let mut result = Self::with_capacity(vec.len());
+            result.extend_from_slice(&vec);
+            result
+        }
+    }
+
+    /// Returns the growth policy of the vector.
+    ///
+    /// New vectors use [`GrowthPolicy::Amortized`].
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth
+    }
+
+    /// Sets the growth policy of the vector.
+    ///
+    /// The growth policy determines how much capacity is reserved when the
+    /// vector runs out of space in [`reserve`](AlignedVec::reserve),
+    /// [`push`](AlignedVec::push), and other methods which may grow the
+    /// vector.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::{AlignedVec, GrowthPolicy};
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.set_growth_policy(GrowthPolicy::Exact);
+    /// vec.extend_from_slice(&[1, 2, 3]);
+    /// assert_eq!(vec.capacity(), 3);
+    ///
+    /// vec.set_growth_policy(GrowthPolicy::Amortized);
+    /// vec.push(4);
+    /// assert_eq!(vec.capacity(), 4);
+    /// vec.push(5);
+    /// assert_eq!(vec.capacity(), 8);
+    /// ```
+    pub fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.growth = policy;
+    }
+
     fn layout(&self) -> Layout {
         unsafe { Layout::from_size_align_unchecked(self.cap, Self::ALIGNMENT) }
     }
@@ -180,10 +313,34 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     ///   [`MAX_CAPACITY`](AlignedVec::MAX_CAPACITY)
     /// - `new_cap` must be greater than or equal to [`len()`](AlignedVec::len)
     pub unsafe fn change_capacity(&mut self, new_cap: usize) {
+        // SAFETY: The caller has guaranteed that `new_cap` is less than or
+        // equal to `MAX_CAPACITY` and greater than or equal to `len`.
+        if let Err(layout) = unsafe { self.try_change_capacity(new_cap) } {
+            handle_alloc_error(layout);
+        }
+    }
+
+    /// Changes the capacity of the vector to exactly `new_cap`, returning the
+    /// layout which failed to allocate if allocation fails.
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`change_capacity`](Self::change_capacity).
+    unsafe fn try_change_capacity(
+        &mut self,
+        new_cap: usize,
+    ) -> Result<(), Layout> {
         debug_assert!(new_cap <= Self::MAX_CAPACITY);
         debug_assert!(new_cap >= self.len);
 
         if new_cap > 0 {
+            // SAFETY:
+            // - `ALIGNMENT` is always guaranteed to be a nonzero power of two.
+            // - The caller has guaranteed that `new_cap` doesn't overflow
+            //   `isize` when rounded up to the nearest multiple of `ALIGNMENT`.
+            let layout = unsafe {
+                Layout::from_size_align_unchecked(new_cap, Self::ALIGNMENT)
+            };
             let new_ptr = if self.cap > 0 {
                 // SAFETY:
                 // - `self.ptr` is currently allocated because `self.cap` is
@@ -191,43 +348,13 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
                 // - `self.layout()` always matches the layout used to allocate
                 //   the current block of memory.
                 // - We checked that `new_cap` is greater than zero.
-                let new_ptr = unsafe {
-                    realloc(self.ptr.as_ptr(), self.layout(), new_cap)
-                };
-                if new_ptr.is_null() {
-                    // SAFETY:
-                    // - `ALIGNMENT` is always guaranteed to be a nonzero power
-                    //   of two.
-                    // - We checked that `new_cap` doesn't overflow `isize` when
-                    //   rounded up to the nearest power of two.
-                    let layout = unsafe {
-                        Layout::from_size_align_unchecked(
-                            new_cap,
-                            Self::ALIGNMENT,
-                        )
-                    };
-                    handle_alloc_error(layout);
-                }
-                new_ptr
+                unsafe { realloc(self.ptr.as_ptr(), self.layout(), new_cap) }
             } else {
-                // SAFETY:
-                // - `ALIGNMENT` is always guaranteed to be a nonzero power of
-                //   two.
-                // - We checked that `new_cap` doesn't overflow `isize` when
-                //   rounded up to the nearest power of two.
-                let layout = unsafe {
-                    Layout::from_size_align_unchecked(new_cap, Self::ALIGNMENT)
-                };
                 // SAFETY: We checked that `new_cap` has non-zero size.
-                let new_ptr = unsafe { alloc(layout) };
-                if new_ptr.is_null() {
-                    handle_alloc_error(layout);
-                }
-                new_ptr
+                unsafe { alloc(layout) }
             };
-            // SAFETY: We checked that `new_ptr` is non-null in each of the
-            // branches.
-            self.ptr = unsafe { NonNull::new_unchecked(new_ptr) };
+            // On failure, the original allocation is left untouched.
+            self.ptr = NonNull::new(new_ptr).ok_or(layout)?;
             self.cap = new_cap;
         } else if self.cap > 0 {
             // SAFETY: Because the capacity is nonzero, `self.ptr` points to a
@@ -239,6 +366,8 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
             self.ptr = NonNull::dangling();
             self.cap = 0;
         }
+
+        Ok(())
     }
 
     /// Shrinks the capacity of the vector as much as possible.
@@ -421,9 +550,11 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
 
     /// Grows total capacity of vector to `new_cap` or more.
     ///
-    /// Capacity after this call will be `new_cap` rounded up to next power of
-    /// 2, unless that would exceed maximum capacity, in which case capacity
-    /// is capped at the maximum.
+    /// With the default [`GrowthPolicy::Amortized`], capacity after this call
+    /// will be `new_cap` rounded up to next power of 2, unless that would
+    /// exceed maximum capacity, in which case capacity is capped at the
+    /// maximum. With [`GrowthPolicy::Exact`], capacity will be exactly
+    /// `new_cap`.
     ///
     /// This is same growth strategy used by `reserve`, `push` and
     /// `extend_from_slice`.
@@ -458,26 +589,115 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     pub unsafe fn grow_capacity_to(&mut self, new_cap: usize) {
         debug_assert!(new_cap > self.cap);
 
-        let new_cap = if new_cap > (isize::MAX as usize + 1) >> 1 {
+        let new_cap = self
+            .grown_capacity(new_cap)
+            .expect("cannot reserve a larger AlignedVec");
+        // SAFETY: `grown_capacity` always returns a capacity which is greater
+        // than or equal to `new_cap` and less than or equal to `MAX_CAPACITY`.
+        unsafe {
+            self.change_capacity(new_cap);
+        }
+    }
+
+    /// Returns the capacity that the vector should grow to in order to hold
+    /// at least `new_cap` bytes according to its growth policy, or `None` if
+    /// `new_cap` exceeds `MAX_CAPACITY`.
+    fn grown_capacity(&self, new_cap: usize) -> Option<usize> {
+        if new_cap > Self::MAX_CAPACITY {
+            None
+        } else if self.growth == GrowthPolicy::Exact {
+            Some(new_cap)
+        } else if new_cap > (isize::MAX as usize + 1) >> 1 {
             // Rounding up to next power of 2 would result in `isize::MAX + 1`
             // or higher, which exceeds max capacity. So cap at max
             // instead.
-            assert!(
-                new_cap <= Self::MAX_CAPACITY,
-                "cannot reserve a larger AlignedVec"
-            );
-            Self::MAX_CAPACITY
+            Some(Self::MAX_CAPACITY)
         } else {
             // Cannot overflow due to check above
-            new_cap.next_power_of_two()
-        };
-        // SAFETY: We just checked that `new_cap` is greater than or equal to
-        // `len` and less than or equal to `MAX_CAPACITY`.
-        unsafe {
-            self.change_capacity(new_cap);
+            Some(new_cap.next_power_of_two())
         }
     }
 
+    /// Tries to reserve capacity for at least `additional` more bytes to be
+    /// inserted into the vector, growing according to its growth policy.
+    ///
+    /// Unlike [`reserve`](AlignedVec::reserve), this returns an error instead
+    /// of panicking or aborting if the capacity overflows or the allocator
+    /// reports a failure. If an error is returned, the vector is unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.try_reserve(10).unwrap();
+    /// assert!(vec.capacity() >= 10);
+    ///
+    /// assert!(vec.try_reserve(usize::MAX).is_err());
+    /// assert!(vec.capacity() >= 10);
+    /// ```
+    pub fn try_reserve(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .ok_or(TryReserveError::CAPACITY_OVERFLOW)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        let new_cap = self
+            .grown_capacity(required)
+            .ok_or(TryReserveError::CAPACITY_OVERFLOW)?;
+        // SAFETY: `grown_capacity` always returns a capacity which is greater
+        // than or equal to `required` and less than or equal to
+        // `MAX_CAPACITY`.
+        unsafe { self.try_change_capacity(new_cap) }.map_err(|layout| {
+            TryReserveError {
+                kind: TryReserveErrorKind::AllocError { layout },
+            }
+        })
+    }
+
+    /// Tries to reserve the minimum capacity for exactly `additional` more
+    /// bytes to be inserted into the vector.
+    ///
+    /// Unlike [`reserve_exact`](AlignedVec::reserve_exact), this returns an
+    /// error instead of panicking or aborting if the capacity overflows or the
+    /// allocator reports a failure. If an error is returned, the vector is
+    /// unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rkyv::util::AlignedVec;
+    /// let mut vec = AlignedVec::<16>::new();
+    /// vec.push(1);
+    /// vec.try_reserve_exact(10).unwrap();
+    /// assert_eq!(vec.capacity(), 11);
+    /// ```
+    pub fn try_reserve_exact(
+        &mut self,
+        additional: usize,
+    ) -> Result<(), TryReserveError> {
+        let required = self
+            .len
+            .checked_add(additional)
+            .filter(|required| *required <= Self::MAX_CAPACITY)
+            .ok_or(TryReserveError::CAPACITY_OVERFLOW)?;
+        if required <= self.cap {
+            return Ok(());
+        }
+
+        // SAFETY: We checked that `required` is greater than `len` and less
+        // than or equal to `MAX_CAPACITY`.
+        unsafe { self.try_change_capacity(required) }.map_err(|layout| {
+            TryReserveError {
+                kind: TryReserveErrorKind::AllocError { layout },
+            }
+        })
+    }
+
     /// Resizes the Vec in-place so that len is equal to new_len.
     ///
     /// If new_len is greater than len, the Vec is extended by the difference,
@@ -712,8 +932,12 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
 
     /// Converts the vector into `Box<[u8]>`. The returned slice is 1-aligned.
     ///
-    /// This method reallocates and copies the underlying bytes. Any excess
-    /// capacity is dropped.
+    /// When `ALIGNMENT` is 1, this reuses the allocation of the vector.
+    /// Otherwise, this method reallocates and copies the underlying bytes
+    /// because a `Box<[u8]>` always frees its memory with an alignment of one.
+    /// Any excess capacity is dropped. To keep the bytes aligned without any
+    /// excess capacity, use [`shrink_to_fit`](AlignedVec::shrink_to_fit)
+    /// instead.
     ///
     /// # Examples
     /// ```
@@ -741,8 +965,9 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
 
     /// Converts the vector into `Vec<u8>`.
     ///
-    /// This method reallocates and copies the underlying bytes. Any excess
-    /// capacity is dropped.
+    /// When `ALIGNMENT` is 1, this reuses the allocation of the vector and
+    /// keeps its capacity. Otherwise, this method reallocates and copies the
+    /// underlying bytes, and any excess capacity is dropped.
     ///
     /// # Examples
     /// ```
@@ -755,7 +980,18 @@ impl<const ALIGNMENT: usize> AlignedVec<ALIGNMENT> {
     /// assert_eq!(vec.as_slice(), &[1, 2, 3]);
     /// ```
     pub fn into_vec(self) -> Vec<u8> {
-        Vec::from(self.as_ref())
+        if ALIGNMENT == 1 && self.cap != 0 {
+            let this = ManuallyDrop::new(self);
+            // SAFETY: `this.ptr` was allocated by the global allocator with a
+            // layout of `this.cap` bytes with alignment 1, which is the same
+            // layout that `Vec<u8>` uses. The first `this.len` bytes are
+            // initialized.
+            unsafe {
+                Vec::from_raw_parts(this.ptr.as_ptr(), this.len, this.cap)
+            }
+        } else {
+            Vec::from(self.as_ref())
+        }
     }
 }
 
@@ -899,9 +1135,15 @@ const _: () = {
     }
 };
 
+impl<const A: usize> From<Vec<u8>> for AlignedVec<A> {
+    fn from(vec: Vec<u8>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
 impl<const A: usize> From<AlignedVec<A>> for Vec<u8> {
     fn from(aligned: AlignedVec<A>) -> Self {
-        aligned.to_vec()
+        aligned.into_vec()
     }
 }
 
@@ -934,6 +1176,7 @@ impl<const A: usize> Clone for AlignedVec<A> {
         unsafe {
             let mut result = Self::with_capacity(self.len);
             result.len = self.len;
+            result.growth = self.growth;
             core::ptr::copy_nonoverlapping(
                 self.as_ptr(),
                 result.as_mut_ptr(),
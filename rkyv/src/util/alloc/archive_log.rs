@@ -0,0 +1,368 @@
+use core::mem::take;
+#[cfg(feature = "bytecheck")]
+use core::{error::Error, fmt, marker::PhantomData};
+
+#[cfg(feature = "bytecheck")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "bytecheck")]
+use rancor::fail;
+use rancor::Source;
+
+#[cfg(feature = "bytecheck")]
+use crate::{
+    api::high::{access_unaligned, AlignedAccess, HighValidator},
+    Portable,
+};
+use crate::{
+    api::high::{to_bytes_in, HighSerializer},
+    ser::{allocator::ArenaHandle, Writer},
+    util::{crc32, AlignedVec},
+    Serialize,
+};
+
+/// The alignment of each record in an [`ArchiveLog`].
+const RECORD_ALIGNMENT: usize = 16;
+/// The size of the header preceding each record in an [`ArchiveLog`].
+const HEADER_SIZE: usize = 16;
+
+fn padding_for(pos: usize) -> usize {
+    (RECORD_ALIGNMENT - pos % RECORD_ALIGNMENT) % RECORD_ALIGNMENT
+}
+
+/// A writer which appends archives to a log as individual records.
+///
+/// Each record consists of a 16-byte header followed by the archive. The header
+/// contains the little-endian length of the archive as a `u64` and a CRC32 of
+/// the archive as a `u32`, followed by four reserved zero bytes. Records start
+/// at 16-byte aligned offsets from the start of the log, so logs which are
+/// read from an aligned buffer (like a memory-mapped file) can be accessed in
+/// place.
+///
+/// Logs can be read back with [`read_log`].
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     rancor::Error,
+///     util::{read_log, AlignedVec, ArchiveLog},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Event {
+///     id: u32,
+///     message: String,
+/// }
+///
+/// let mut log = ArchiveLog::new(AlignedVec::<16>::new());
+/// for id in 0..3 {
+///     let event = Event {
+///         id,
+///         message: format!("event number {id}"),
+///     };
+///     log.append::<Error>(&event).unwrap();
+/// }
+/// let bytes = log.into_inner();
+///
+/// let mut records = read_log::<ArchivedEvent, Error>(&bytes);
+/// for id in 0..3 {
+///     let event = records.next().unwrap().unwrap();
+///     assert_eq!(event.id, id);
+///     assert_eq!(event.message, format!("event number {id}"));
+/// }
+/// assert!(records.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct ArchiveLog<W> {
+    inner: W,
+    records: usize,
+    scratch: AlignedVec,
+}
+
+impl<W> ArchiveLog<W> {
+    /// Creates a new `ArchiveLog` which appends records to the given writer.
+    ///
+    /// Record offsets are computed from the first byte written to `inner`, so
+    /// readers must start reading from the same position.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            records: 0,
+            scratch: AlignedVec::new(),
+        }
+    }
+
+    /// Returns the number of records that have been appended.
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    /// Returns whether no records have been appended.
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Serializes a value and appends it to the log as a new record.
+    pub fn append<E>(
+        &mut self,
+        // rustfmt insists on inlining this parameter even though it exceeds
+        // the max line length
+        #[rustfmt::skip] value: &impl for<'a> Serialize<
+            HighSerializer<AlignedVec, ArenaHandle<'a>, E>,
+        >,
+    ) -> Result<(), E>
+    where
+        W: Writer<E>,
+        E: Source,
+    {
+        let mut scratch = take(&mut self.scratch);
+        scratch.clear();
+        let scratch = to_bytes_in(value, scratch)?;
+        let result = self.append_bytes(&scratch);
+        self.scratch = scratch;
+        result
+    }
+
+    /// Appends an already-serialized archive to the log as a new record.
+    pub fn append_bytes<E>(&mut self, bytes: &[u8]) -> Result<(), E>
+    where
+        W: Writer<E>,
+    {
+        let mut header = [0; HEADER_SIZE];
+        header[0..8].copy_from_slice(&(bytes.len() as u64).to_le_bytes());
+        header[8..12].copy_from_slice(&crc32(bytes).to_le_bytes());
+
+        self.inner.write(&header)?;
+        self.inner.write(bytes)?;
+        let padding = padding_for(bytes.len());
+        self.inner.write(&[0; RECORD_ALIGNMENT][..padding])?;
+
+        self.records += 1;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+#[derive(Debug)]
+struct TruncatedRecord {
+    pos: usize,
+}
+
+#[cfg(feature = "bytecheck")]
+impl fmt::Display for TruncatedRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "log record at offset {} is truncated", self.pos)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl Error for TruncatedRecord {}
+
+#[cfg(feature = "bytecheck")]
+#[derive(Debug)]
+struct RecordChecksumMismatch {
+    pos: usize,
+    expected: u32,
+    found: u32,
+}
+
+#[cfg(feature = "bytecheck")]
+impl fmt::Display for RecordChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "log record at offset {} has checksum {:#010x} but hashes to \
+             {:#010x}",
+            self.pos, self.expected, self.found,
+        )
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl Error for RecordChecksumMismatch {}
+
+/// Returns the body of the record at `pos` and the position of the next record.
+#[cfg(feature = "bytecheck")]
+fn read_record<E: Source>(
+    bytes: &[u8],
+    pos: usize,
+) -> Result<(&[u8], usize), E> {
+    let Some(header) = bytes.get(pos..pos + HEADER_SIZE) else {
+        fail!(TruncatedRecord { pos });
+    };
+    let mut len = [0; 8];
+    len.copy_from_slice(&header[0..8]);
+    let mut expected = [0; 4];
+    expected.copy_from_slice(&header[8..12]);
+    let expected = u32::from_le_bytes(expected);
+
+    let start = pos + HEADER_SIZE;
+    let body = usize::try_from(u64::from_le_bytes(len))
+        .ok()
+        .and_then(|len| start.checked_add(len))
+        .and_then(|end| bytes.get(start..end));
+    let Some(body) = body else {
+        fail!(TruncatedRecord { pos });
+    };
+
+    let found = crc32(body);
+    if expected != found {
+        fail!(RecordChecksumMismatch {
+            pos,
+            expected,
+            found,
+        });
+    }
+
+    let next = start + body.len() + padding_for(body.len());
+    Ok((body, next.min(bytes.len())))
+}
+
+/// An iterator over the records of an [`ArchiveLog`].
+///
+/// Each record's checksum is verified and its archive is validated before it
+/// is yielded. Records which are not sufficiently aligned in the log buffer are
+/// copied into aligned memory. Iteration stops after the first error.
+#[cfg(feature = "bytecheck")]
+pub struct LogRecords<'a, T, E> {
+    bytes: &'a [u8],
+    pos: usize,
+    _phantom: PhantomData<fn() -> (&'a T, E)>,
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T, E> LogRecords<'_, T, E> {
+    /// Returns the offset of the next record in the log.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<'a, T, E> Iterator for LogRecords<'a, T, E>
+where
+    T: Portable + for<'v> CheckBytes<HighValidator<'v, E>>,
+    E: Source,
+{
+    type Item = Result<AlignedAccess<'a, T>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.bytes.len() {
+            return None;
+        }
+
+        let result =
+            read_record(self.bytes, self.pos).and_then(|(body, next)| {
+                self.pos = next;
+                access_unaligned(body)
+            });
+        if result.is_err() {
+            self.pos = self.bytes.len();
+        }
+        Some(result)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T, E> fmt::Debug for LogRecords<'_, T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogRecords")
+            .field("len", &self.bytes.len())
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+/// Returns an iterator over the validated records of a log written by an
+/// [`ArchiveLog`].
+///
+/// See [`ArchiveLog`] for an example.
+#[cfg(feature = "bytecheck")]
+pub fn read_log<T, E>(bytes: &[u8]) -> LogRecords<'_, T, E>
+where
+    T: Portable + for<'v> CheckBytes<HighValidator<'v, E>>,
+    E: Source,
+{
+    LogRecords {
+        bytes,
+        pos: 0,
+        _phantom: PhantomData,
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use rancor::Failure;
+
+    use super::{read_log, ArchiveLog};
+    use crate::{
+        alloc::{string::String, vec::Vec},
+        string::ArchivedString,
+        util::AlignedVec,
+    };
+
+    #[test]
+    fn log_roundtrip() {
+        let mut log = ArchiveLog::new(AlignedVec::<16>::new());
+        assert!(log.is_empty());
+        for i in 0..10 {
+            log.append::<Failure>(&"x".repeat(i * 7)).unwrap();
+        }
+        assert_eq!(log.len(), 10);
+        let bytes = log.into_inner();
+
+        let records = read_log::<ArchivedString, Failure>(&bytes)
+            .map(|r| r.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 10);
+        for (i, record) in records.iter().enumerate() {
+            assert!(record.is_borrowed());
+            assert_eq!(record.as_str(), "x".repeat(i * 7));
+        }
+
+        // Reading from a misaligned buffer copies records
+        let mut shifted = AlignedVec::<16>::new();
+        shifted.push(0);
+        shifted.extend_from_slice(&bytes);
+        let mut records = read_log::<ArchivedString, Failure>(&shifted[1..]);
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(record.is_borrowed(), cfg!(feature = "unaligned"));
+        assert_eq!(records.count(), 9);
+    }
+
+    #[test]
+    fn log_corruption() {
+        let mut log = ArchiveLog::new(Vec::new());
+        log.append::<Failure>(&String::from("first record"))
+            .unwrap();
+        let first_len = log.inner().len();
+        log.append::<Failure>(&String::from("second record"))
+            .unwrap();
+        let mut bytes = AlignedVec::<16>::new();
+        bytes.extend_from_slice(log.inner());
+
+        // Truncated final record
+        let truncated = &bytes[..bytes.len() - 20];
+        let mut records = read_log::<ArchivedString, Failure>(truncated);
+        assert!(records.next().unwrap().is_ok());
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().is_none());
+
+        // Flipped bit in the first record
+        bytes[first_len - 20] ^= 1;
+        let mut records = read_log::<ArchivedString, Failure>(&bytes);
+        assert!(records.next().unwrap().is_err());
+        assert!(records.next().is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ser::allocator::Arena;
+use crate::ser::allocator::{Arena, ArenaStats};
 
 #[cfg(feature = "std")]
 mod detail {
@@ -120,3 +120,21 @@ pub fn with_arena<T>(f: impl FnOnce(&mut Arena) -> T) -> T {
 pub fn clear_arena() {
     detail::clear_arena()
 }
+
+/// Returns statistics about the memory used by the builtin arena allocator.
+///
+/// The peak memory usage is only tracked across calls when the `std` feature
+/// is enabled. Otherwise, it is the same as the memory currently retained.
+pub fn arena_stats() -> ArenaStats {
+    with_arena(|arena| arena.stats())
+}
+
+/// Shrinks the builtin arena allocator so that it has at most `max_capacity`
+/// bytes of capacity, returning its capacity afterward.
+///
+/// Serializing a large value grows the builtin arena, and that memory is kept
+/// for future serializations. Long-running processes can call this
+/// periodically to bound the amount of memory retained by the arena.
+pub fn shrink_arena(max_capacity: usize) -> usize {
+    with_arena(|arena| arena.shrink_to(max_capacity))
+}
@@ -1,4 +1,6 @@
+mod aligned_bytes;
 mod aligned_vec;
+mod archive_log;
 mod arena;
 
-pub use self::{aligned_vec::*, arena::*};
+pub use self::{aligned_bytes::*, aligned_vec::*, archive_log::*, arena::*};
@@ -0,0 +1,264 @@
+use core::{error::Error, fmt};
+
+use rancor::{fail, Source};
+
+use crate::ser::{Positional, Writer};
+
+/// The size of the checksum footer written by [`Checksummed`], in bytes.
+pub const CHECKSUM_SIZE: usize = 4;
+
+const CRC32_TABLE: [u32; 256] = {
+    const POLYNOMIAL: u32 = 0xedb8_8320;
+
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            if crc & 1 != 0 {
+                crc = (crc >> 1) ^ POLYNOMIAL;
+            } else {
+                crc >>= 1;
+            }
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+#[inline]
+fn update_crc32(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for &byte in bytes {
+        crc = CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Computes the CRC32 (IEEE) checksum of the given bytes.
+///
+/// This is the checksum that [`Checksummed`] appends to archives.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    update_crc32(0, bytes)
+}
+
+/// A writer adapter which computes a checksum of the bytes written to it.
+///
+/// Calling [`finish`](Checksummed::finish) appends a CRC32 of all of the
+/// written bytes as a little-endian footer. Checksummed archives can be
+/// verified with [`verify_checksum`] before they are validated or accessed.
+///
+/// Structural validation alone can't detect bit flips inside primitive data,
+/// so this is useful for archives stored on unreliable media.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::{access_checked_crc, to_bytes_in},
+///     rancor::Error,
+///     util::{AlignedVec, Checksummed},
+///     Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let writer = Checksummed::new(AlignedVec::<16>::new());
+/// let bytes = to_bytes_in::<_, Error>(&value, writer)
+///     .unwrap()
+///     .finish::<Error>()
+///     .unwrap();
+///
+/// let archived =
+///     access_checked_crc::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.name, "pi");
+/// assert_eq!(archived.value, 31415926);
+/// ```
+#[derive(Debug, Default)]
+pub struct Checksummed<W> {
+    inner: W,
+    crc: u32,
+}
+
+impl<W> Checksummed<W> {
+    /// Wraps the given writer.
+    ///
+    /// Only bytes written through the adapter are included in the checksum.
+    pub fn new(inner: W) -> Self {
+        Self { inner, crc: 0 }
+    }
+
+    /// Returns the checksum of the bytes written so far.
+    pub fn checksum(&self) -> u32 {
+        self.crc
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the inner writer without writing the checksum footer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes the checksum footer to the inner writer and returns it.
+    pub fn finish<E>(mut self) -> Result<W, E>
+    where
+        W: Writer<E>,
+    {
+        self.inner.write(&self.crc.to_le_bytes())?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Positional> Positional for Checksummed<W> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+}
+
+impl<W: Writer<E>, E> Writer<E> for Checksummed<W> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        self.inner.write(bytes)?;
+        self.crc = update_crc32(self.crc, bytes);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct MissingChecksum {
+    len: usize,
+}
+
+impl fmt::Display for MissingChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer of length {} is too short to contain a {}-byte checksum \
+             footer",
+            self.len, CHECKSUM_SIZE,
+        )
+    }
+}
+
+impl Error for MissingChecksum {}
+
+#[derive(Debug)]
+struct ChecksumMismatch {
+    expected: u32,
+    found: u32,
+}
+
+impl fmt::Display for ChecksumMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "checksum mismatch: footer contains {:#010x} but archive body \
+             hashes to {:#010x}",
+            self.expected, self.found,
+        )
+    }
+}
+
+impl Error for ChecksumMismatch {}
+
+/// Verifies the checksum footer written by [`Checksummed`] and returns the
+/// archive body without the footer.
+///
+/// The returned slice starts at the same address as `bytes`, so it keeps the
+/// alignment of the original buffer.
+pub fn verify_checksum<E: Source>(bytes: &[u8]) -> Result<&[u8], E> {
+    let Some(body_len) = bytes.len().checked_sub(CHECKSUM_SIZE) else {
+        fail!(MissingChecksum { len: bytes.len() });
+    };
+    let (body, footer) = bytes.split_at(body_len);
+
+    let mut expected = [0; CHECKSUM_SIZE];
+    expected.copy_from_slice(footer);
+    let expected = u32::from_le_bytes(expected);
+    let found = crc32(body);
+    if expected != found {
+        fail!(ChecksumMismatch { expected, found });
+    }
+
+    Ok(body)
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use rancor::{Failure, Panic};
+
+    use super::{crc32, verify_checksum, Checksummed};
+    use crate::{
+        alloc::vec::Vec,
+        ser::{Positional as _, Writer},
+    };
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn checksummed_writer() {
+        let mut writer = Checksummed::new(Vec::new());
+        Writer::<Panic>::write(&mut writer, b"1234").unwrap();
+        Writer::<Panic>::write(&mut writer, b"56789").unwrap();
+        assert_eq!(writer.pos(), 9);
+        assert_eq!(writer.checksum(), 0xcbf4_3926);
+
+        let mut bytes = writer.finish::<Panic>().unwrap();
+        assert_eq!(bytes.len(), 13);
+        assert_eq!(verify_checksum::<Panic>(&bytes).unwrap(), b"123456789");
+
+        bytes[3] ^= 0x10;
+        assert!(verify_checksum::<Failure>(&bytes).is_err());
+        assert!(verify_checksum::<Failure>(&bytes[..3]).is_err());
+    }
+
+    #[cfg(feature = "bytecheck")]
+    #[test]
+    fn access_checked_crc() {
+        use crate::{
+            api::high::{access, access_checked_crc, to_bytes_in},
+            util::AlignedVec,
+            Archived,
+        };
+
+        let value = (42u32, 1.5f32);
+        let writer = Checksummed::new(AlignedVec::<16>::new());
+        let mut bytes = to_bytes_in::<_, Panic>(&value, writer)
+            .unwrap()
+            .finish::<Panic>()
+            .unwrap();
+
+        let archived =
+            access_checked_crc::<Archived<(u32, f32)>, Panic>(&bytes).unwrap();
+        assert_eq!(archived.0, 42);
+        assert_eq!(archived.1, 1.5);
+
+        // Structural validation accepts the flipped bit but the checksum
+        // doesn't
+        bytes[0] ^= 1;
+        let body = &bytes[..bytes.len() - super::CHECKSUM_SIZE];
+        assert_eq!(access::<Archived<(u32, f32)>, Panic>(body).unwrap().0, 43);
+        assert!(access_checked_crc::<Archived<(u32, f32)>, Failure>(&bytes)
+            .is_err());
+    }
+}
@@ -0,0 +1,362 @@
+//! Length-delimited framing for `tokio-util` transports.
+//!
+//! [`RkyvCodec`] implements `tokio-util`'s `Encoder` and `Decoder` traits, so
+//! it can be used with `Framed`, `FramedRead`, and `FramedWrite` to send and
+//! receive archives over a byte stream. Each frame consists of a 4-byte
+//! little-endian length followed by the archive bytes.
+
+use core::{fmt, marker::PhantomData};
+use std::io;
+
+use bytes_1::{Buf as _, BufMut as _, BytesMut};
+use rancor::Source;
+use tokio_util_0_7::codec::{Decoder, Encoder};
+
+#[cfg(feature = "bytecheck")]
+use crate::{
+    api::high::{access, HighValidator},
+    bytecheck::CheckBytes,
+};
+use crate::{
+    api::{
+        access_unchecked,
+        high::{to_bytes_in, HighSerializer},
+    },
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+    Archive, Serialize,
+};
+
+/// The size of the length prefix of each frame, in bytes.
+const LENGTH_SIZE: usize = 4;
+
+/// A `tokio-util` codec which frames archived `T`s.
+///
+/// When the `bytecheck` feature is enabled, frames are validated as they are
+/// decoded by default. Decoding fails with an [`io::ErrorKind::InvalidData`]
+/// error if a frame is invalid or longer than the maximum frame length.
+///
+/// # Example
+///
+/// ```
+/// # extern crate bytes_1 as bytes;
+/// # extern crate tokio_util_0_7 as tokio_util;
+/// use bytes::BytesMut;
+/// use rkyv::{util::codec::RkyvCodec, Archive, Serialize};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Message {
+///     id: u32,
+///     body: String,
+/// }
+///
+/// let mut codec = RkyvCodec::<Message>::new();
+/// let mut buffer = BytesMut::new();
+///
+/// let message = Message {
+///     id: 1,
+///     body: "hello world".to_string(),
+/// };
+/// codec.encode(&message, &mut buffer).unwrap();
+///
+/// let frame = codec.decode(&mut buffer).unwrap().unwrap();
+/// let archived = frame.access::<rkyv::rancor::Error>().unwrap();
+/// assert_eq!(archived.id, 1);
+/// assert_eq!(archived.body, "hello world");
+/// ```
+pub struct RkyvCodec<T, E = rancor::Error> {
+    max_frame_length: usize,
+    #[cfg(feature = "bytecheck")]
+    validate: bool,
+    scratch: AlignedVec,
+    _phantom: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E> RkyvCodec<T, E> {
+    /// The default maximum frame length, in bytes.
+    pub const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+    /// Creates a new codec with the default maximum frame length.
+    pub fn new() -> Self {
+        Self {
+            max_frame_length: Self::DEFAULT_MAX_FRAME_LENGTH,
+            #[cfg(feature = "bytecheck")]
+            validate: true,
+            scratch: AlignedVec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets the maximum length of frames which may be encoded or decoded.
+    pub fn max_frame_length(mut self, max_frame_length: usize) -> Self {
+        self.max_frame_length = max_frame_length;
+        self
+    }
+
+    /// Sets whether frames are validated as they are decoded.
+    ///
+    /// Frames which are not validated on decode are validated when they are
+    /// first accessed with [`Frame::access`] instead.
+    #[cfg(feature = "bytecheck")]
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    fn frame_too_long(&self, len: usize) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "frame of length {} exceeds the maximum frame length of {}",
+                len, self.max_frame_length,
+            ),
+        )
+    }
+
+    fn decode_bytes(
+        &mut self,
+        src: &mut BytesMut,
+    ) -> Result<Option<AlignedVec>, io::Error> {
+        if src.len() < LENGTH_SIZE {
+            src.reserve(LENGTH_SIZE - src.len());
+            return Ok(None);
+        }
+
+        let mut len = [0; LENGTH_SIZE];
+        len.copy_from_slice(&src[..LENGTH_SIZE]);
+        let len = u32::from_le_bytes(len) as usize;
+        if len > self.max_frame_length {
+            return Err(self.frame_too_long(len));
+        }
+
+        if src.len() < LENGTH_SIZE + len {
+            src.reserve(LENGTH_SIZE + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(LENGTH_SIZE);
+        let mut bytes = AlignedVec::with_capacity(len);
+        bytes.extend_from_slice(&src[..len]);
+        src.advance(len);
+        Ok(Some(bytes))
+    }
+}
+
+impl<T, E> Default for RkyvCodec<T, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, E> fmt::Debug for RkyvCodec<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("RkyvCodec");
+        debug.field("max_frame_length", &self.max_frame_length);
+        #[cfg(feature = "bytecheck")]
+        debug.field("validate", &self.validate);
+        debug.finish_non_exhaustive()
+    }
+}
+
+impl<T, E> Encoder<&T> for RkyvCodec<T, E>
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: Source + Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: &T, dst: &mut BytesMut) -> io::Result<()> {
+        let mut scratch = core::mem::take(&mut self.scratch);
+        scratch.clear();
+        let scratch = to_bytes_in::<_, E>(item, scratch)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let len = scratch.len();
+        let result = if len > self.max_frame_length || len > u32::MAX as usize {
+            Err(self.frame_too_long(len))
+        } else {
+            dst.reserve(LENGTH_SIZE + len);
+            dst.put_u32_le(len as u32);
+            dst.extend_from_slice(&scratch);
+            Ok(())
+        };
+        self.scratch = scratch;
+        result
+    }
+}
+
+impl<T, E> Encoder<T> for RkyvCodec<T, E>
+where
+    T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, E>>,
+    E: Source + Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        <Self as Encoder<&T>>::encode(self, &item, dst)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+impl<T, E> Decoder for RkyvCodec<T, E>
+where
+    T: Archive,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+    E: Source + Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Item = Frame<T>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Frame<T>>> {
+        let Some(bytes) = self.decode_bytes(src)? else {
+            return Ok(None);
+        };
+        if self.validate {
+            access::<T::Archived, E>(&bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(Some(Frame {
+            bytes,
+            validated: self.validate,
+            _phantom: PhantomData,
+        }))
+    }
+}
+
+#[cfg(not(feature = "bytecheck"))]
+impl<T: Archive, E> Decoder for RkyvCodec<T, E> {
+    type Item = Frame<T>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Frame<T>>> {
+        Ok(self.decode_bytes(src)?.map(|bytes| Frame {
+            bytes,
+            validated: false,
+            _phantom: PhantomData,
+        }))
+    }
+}
+
+/// A frame decoded by an [`RkyvCodec`].
+///
+/// The frame owns an aligned copy of the archive bytes.
+pub struct Frame<T> {
+    bytes: AlignedVec,
+    validated: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive> Frame<T> {
+    /// Returns whether the frame was validated when it was decoded.
+    pub fn is_validated(&self) -> bool {
+        self.validated
+    }
+
+    /// Returns the archive bytes of the frame.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the archive bytes of the frame.
+    pub fn into_bytes(self) -> AlignedVec {
+        self.bytes
+    }
+
+    /// Accesses the archived value in the frame, validating it first if it was
+    /// not validated when it was decoded.
+    #[cfg(feature = "bytecheck")]
+    pub fn access<E>(&self) -> Result<&T::Archived, E>
+    where
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        if self.validated {
+            // SAFETY: The frame was validated when it was decoded.
+            Ok(unsafe { self.access_unchecked() })
+        } else {
+            access::<T::Archived, E>(&self.bytes)
+        }
+    }
+
+    /// Accesses the archived value in the frame without validating it.
+    ///
+    /// # Safety
+    ///
+    /// The frame must contain a valid archived `T`. This is always the case if
+    /// [`is_validated`](Frame::is_validated) returns `true`.
+    pub unsafe fn access_unchecked(&self) -> &T::Archived {
+        // SAFETY: The caller has guaranteed that the frame contains a valid
+        // archived `T`.
+        unsafe { access_unchecked::<T::Archived>(&self.bytes) }
+    }
+}
+
+impl<T> fmt::Debug for Frame<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Frame")
+            .field("len", &self.bytes.len())
+            .field("validated", &self.validated)
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use bytes_1::BytesMut;
+    use rancor::Failure;
+    use tokio_util_0_7::codec::{Decoder as _, Encoder as _};
+
+    use super::RkyvCodec;
+    use crate::alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn codec_roundtrip() {
+        let mut codec = RkyvCodec::<Vec<String>, Failure>::new();
+        let mut buffer = BytesMut::new();
+
+        let first = vec![String::from("a string which is stored out of line")];
+        let second = Vec::new();
+        codec.encode(&first, &mut buffer).unwrap();
+        codec.encode(second, &mut buffer).unwrap();
+
+        // Partial frames are not decoded
+        let mut partial = buffer.split_to(10);
+        assert!(codec.decode(&mut partial).unwrap().is_none());
+        partial.unsplit(buffer);
+        let mut buffer = partial;
+
+        let frame = codec.decode(&mut buffer).unwrap().unwrap();
+        assert!(frame.is_validated());
+        assert_eq!(frame.access::<Failure>().unwrap(), &first);
+        let frame = codec.decode(&mut buffer).unwrap().unwrap();
+        assert!(frame.access::<Failure>().unwrap().is_empty());
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn codec_rejects_invalid_frames() {
+        let mut codec = RkyvCodec::<String, Failure>::new();
+        let mut buffer = BytesMut::new();
+        codec
+            .encode(&String::from("hello world"), &mut buffer)
+            .unwrap();
+        let mut invalid = buffer.clone();
+
+        let mut limited =
+            RkyvCodec::<String, Failure>::new().max_frame_length(4);
+        assert!(limited.decode(&mut buffer).is_err());
+        assert!(limited.encode(&String::new(), &mut buffer).is_err());
+
+        // Overwrite the string with invalid UTF-8
+        let len = invalid.len();
+        invalid[len - 8..].fill(0xfe);
+        let mut unvalidated =
+            RkyvCodec::<String, Failure>::new().validate(false);
+        let frame = unvalidated.decode(&mut invalid.clone()).unwrap().unwrap();
+        assert!(!frame.is_validated());
+        assert!(frame.access::<Failure>().is_err());
+        assert!(codec.decode(&mut invalid).is_err());
+    }
+}
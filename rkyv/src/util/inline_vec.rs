@@ -1,3 +1,5 @@
+//! A vector which uses inline-allocated memory.
+
 use core::{
     borrow::{Borrow, BorrowMut},
     fmt,
@@ -9,6 +11,28 @@ use core::{
 };
 
 /// A vector that uses inline-allocated memory.
+///
+/// `InlineVec` stores up to `N` elements inline, without allocating. It's
+/// useful for small, bounded amounts of temporary storage like the stack of a
+/// tree traversal.
+///
+/// Pushing more than `N` elements panics.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::util::InlineVec;
+///
+/// let mut vec = InlineVec::<u32, 4>::new();
+/// vec.push(1);
+/// vec.extend_from_slice(&[2, 3]);
+/// assert_eq!(vec.as_slice(), &[1, 2, 3]);
+/// assert_eq!(vec.pop(), Some(3));
+///
+/// let drained = vec.drain().collect::<Vec<_>>();
+/// assert_eq!(drained, [1, 2]);
+/// assert!(vec.is_empty());
+/// ```
 pub struct InlineVec<T, const N: usize> {
     elements: [MaybeUninit<T>; N],
     len: usize,
@@ -85,13 +109,13 @@ impl<T, const N: usize> InlineVec<T, N> {
         unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
     }
 
-    /// Returns the number of elements the vector can hole without reallocating.
+    /// Returns the number of elements the vector can hold without reallocating.
     pub const fn capacity(&self) -> usize {
         N
     }
 
     /// Ensures that there is capacity for at least `additional` more elements
-    /// to be inserted into the `ScratchVec`.
+    /// to be inserted into the vector.
     ///
     /// # Panics
     ///
@@ -120,10 +144,17 @@ impl<T, const N: usize> InlineVec<T, N> {
         self.len
     }
 
-    /// Copies and appends all elements in a slice to the `ScratchVec`.
+    /// Copies and appends all elements in a slice to the vector.
     ///
     /// The elements of the slice are appended in-order.
-    pub fn extend_from_slice(&mut self, other: &[T]) {
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector does not have enough capacity for the elements.
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Copy,
+    {
         if !other.is_empty() {
             self.reserve(other.len());
             unsafe {
@@ -164,6 +195,10 @@ impl<T, const N: usize> InlineVec<T, N> {
     }
 
     /// Appends an element to the back of a collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is already full.
     pub fn push(&mut self, value: T) {
         if self.len == N {
             Self::out_of_space()
@@ -175,7 +210,7 @@ impl<T, const N: usize> InlineVec<T, N> {
     }
 
     /// Reserves the minimum capacity for exactly `additional` more elements to
-    /// be inserted in the given `AlignedVec`. After calling
+    /// be inserted in the vector. After calling
     /// `reserve_exact`, capacity will be greater than or equal
     /// to `self.len() + additional`. Does nothing if the capacity is already
     /// sufficient.
@@ -202,8 +237,22 @@ impl<T, const N: usize> InlineVec<T, N> {
         self.len = new_len;
     }
 
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than or equal to the vector's current length, this
+    /// has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop());
+        }
+    }
+
     /// Creates a draining iterator that removes all of the elements from the
     /// vector.
+    ///
+    /// Any elements which are not yielded by the iterator are dropped when it
+    /// is dropped.
     pub fn drain(&mut self) -> Drain<'_, T, N> {
         let remaining = self.len();
         unsafe {
@@ -227,7 +276,7 @@ impl<T, const N: usize> InlineVec<MaybeUninit<T>, N> {
     /// It is up to the caller to guarantee that the `MaybeUninit<T>` elements
     /// really are in an initialized state. Calling this when the content is
     /// not yet fully initialized causes undefined behavior.
-    pub fn assume_init(self) -> InlineVec<T, N> {
+    pub unsafe fn assume_init(self) -> InlineVec<T, N> {
         let mut elements = unsafe {
             MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init()
         };
@@ -389,8 +438,62 @@ impl<T, const N: usize> core::iter::FusedIterator for Drain<'_, T, N> {}
 
 #[cfg(test)]
 mod tests {
+    use core::cell::Cell;
+
     use crate::util::InlineVec;
 
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        let mut vec = InlineVec::<u32, 4>::new();
+        assert_eq!(vec.capacity(), 4);
+        assert!(vec.is_empty());
+        vec.push(1);
+        vec.extend_from_slice(&[2, 3, 4]);
+        assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+        assert_eq!(vec.pop(), Some(4));
+        vec.truncate(1);
+        assert_eq!(vec.as_slice(), &[1]);
+        assert_eq!(vec.pop(), Some(1));
+        assert_eq!(vec.pop(), None);
+    }
+
+    #[test]
+    #[should_panic = "reserve requested more capacity"]
+    fn push_out_of_space() {
+        let mut vec = InlineVec::<u32, 2>::new();
+        vec.push(1);
+        vec.push(2);
+        vec.push(3);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let drops = Cell::new(0);
+        let mut vec = InlineVec::<_, 4>::new();
+        for _ in 0..4 {
+            vec.push(DropCounter(&drops));
+        }
+        let mut drain = vec.drain();
+        drop(drain.next());
+        assert_eq!(drops.get(), 1);
+        drop(drain);
+        assert_eq!(drops.get(), 4);
+
+        for _ in 0..3 {
+            vec.push(DropCounter(&drops));
+        }
+        drop(vec);
+        assert_eq!(drops.get(), 7);
+    }
+
     #[test]
     fn drain() {
         let mut vec = InlineVec::<_, 8>::new();
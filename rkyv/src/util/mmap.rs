@@ -0,0 +1,357 @@
+//! Memory-mapped archive access.
+
+use core::{fmt, marker::PhantomData, ops::Deref};
+use std::{fs::File, path::Path};
+
+use memmap2::{Mmap, MmapOptions};
+use rancor::{ResultExt as _, Source};
+
+use crate::{api::access_unchecked, util::AlignedVec, Archive};
+
+/// The alignment that mapped bytes must have to be accessed in place.
+pub const MMAP_ALIGNMENT: usize = AlignedVec::<16>::ALIGNMENT;
+
+enum Storage {
+    Mapped(Mmap),
+    Copied(AlignedVec),
+}
+
+impl Storage {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Storage::Mapped(mmap) => mmap,
+            Storage::Copied(vec) => vec,
+        }
+    }
+}
+
+/// A memory-mapped file containing an archived `T`.
+///
+/// Archives may contain values which require a higher alignment than the root,
+/// so the mapped bytes must be aligned to [`MMAP_ALIGNMENT`] to be accessed in
+/// place. This matches the alignment of [`AlignedVec`], which archives are
+/// serialized into by default. Memory maps always start on a page boundary, so
+/// archives located at the start of a file are always sufficiently aligned.
+/// When an archive starts at an offset into the file (for example, after a
+/// custom header) which is not a multiple of [`MMAP_ALIGNMENT`], the bytes are
+/// copied into an [`AlignedVec`] instead of failing to access.
+///
+/// # Example
+///
+/// ```
+/// use std::fs;
+///
+/// use rkyv::{
+///     rancor::Error, to_bytes, util::mmap::MmapArchive, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     name: String,
+///     value: i32,
+/// }
+///
+/// let value = Example {
+///     name: "pi".to_string(),
+///     value: 31415926,
+/// };
+///
+/// let path = std::env::temp_dir().join("rkyv_mmap_doctest.bin");
+/// fs::write(&path, to_bytes::<Error>(&value).unwrap()).unwrap();
+///
+/// // SAFETY: The file is not modified while it is mapped.
+/// let archive =
+///     unsafe { MmapArchive::<Example>::open::<Error>(&path) }.unwrap();
+/// assert_eq!(archive.name, "pi");
+/// assert_eq!(archive.value, 31415926);
+/// # drop(archive);
+/// # fs::remove_file(&path).unwrap();
+/// ```
+pub struct MmapArchive<T> {
+    storage: Storage,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive> MmapArchive<T> {
+    fn map<E: Source>(file: &File, offset: u64) -> Result<Storage, E> {
+        // SAFETY: The caller has guaranteed that the file will not be modified
+        // while it is mapped.
+        let mmap = unsafe { MmapOptions::new().offset(offset).map(file) }
+            .into_error()?;
+
+        if (mmap.as_ptr() as usize) & (MMAP_ALIGNMENT - 1) == 0 {
+            Ok(Storage::Mapped(mmap))
+        } else {
+            let mut vec = AlignedVec::with_capacity(mmap.len());
+            vec.extend_from_slice(&mmap);
+            Ok(Storage::Copied(vec))
+        }
+    }
+
+    /// Memory-maps the archive located in the file at the given path without
+    /// validating it.
+    ///
+    /// # Safety
+    ///
+    /// - The file must not be modified while it is mapped.
+    /// - The file must contain a valid archived `T` at the default root
+    ///   position.
+    /// - The archive must not contain any values which require an alignment
+    ///   greater than [`MMAP_ALIGNMENT`].
+    pub unsafe fn open_unchecked<E: Source>(
+        path: impl AsRef<Path>,
+    ) -> Result<Self, E> {
+        let file = File::open(path).into_error()?;
+        // SAFETY: The caller has upheld the safety requirements of
+        // `from_file_at_unchecked`.
+        unsafe { Self::from_file_at_unchecked(&file, 0) }
+    }
+
+    /// Memory-maps the archive located in the given file starting at `offset`
+    /// bytes without validating it.
+    ///
+    /// The archive is assumed to extend to the end of the file. If `offset` is
+    /// not a multiple of [`MMAP_ALIGNMENT`], the archive is copied into an
+    /// [`AlignedVec`] instead of being accessed in place.
+    ///
+    /// # Safety
+    ///
+    /// - The file must not be modified while it is mapped.
+    /// - The file must contain a valid archived `T` at the default root
+    ///   position of the bytes starting at `offset`.
+    /// - The archive must not contain any values which require an alignment
+    ///   greater than [`MMAP_ALIGNMENT`]. Archives serialized into an
+    ///   [`AlignedVec`] with the default alignment uphold this.
+    pub unsafe fn from_file_at_unchecked<E: Source>(
+        file: &File,
+        offset: u64,
+    ) -> Result<Self, E> {
+        Ok(Self {
+            storage: Self::map(file, offset)?,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns whether the archive was mapped in place.
+    ///
+    /// This returns `false` if the mapped bytes were insufficiently aligned and
+    /// had to be copied.
+    pub fn is_mapped(&self) -> bool {
+        matches!(self.storage, Storage::Mapped(_))
+    }
+
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.storage.as_slice()
+    }
+
+    /// Returns a reference to the archived value.
+    pub fn get(&self) -> &T::Archived {
+        // SAFETY: The bytes were either validated on creation or the caller
+        // has guaranteed that they contain a valid archived `T`.
+        unsafe { access_unchecked::<T::Archived>(self.as_bytes()) }
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use std::{fs::File, path::Path};
+
+    use bytecheck::CheckBytes;
+    use rancor::{ResultExt as _, Source};
+
+    use super::{MmapArchive, PhantomData};
+    use crate::{
+        api::high::{access, HighValidator},
+        Archive,
+    };
+
+    impl<T: Archive> MmapArchive<T> {
+        /// Memory-maps and validates the archive located in the file at the
+        /// given path.
+        ///
+        /// # Safety
+        ///
+        /// The file must not be modified while it is mapped.
+        pub unsafe fn open<E>(path: impl AsRef<Path>) -> Result<Self, E>
+        where
+            T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+            E: Source,
+        {
+            let file = File::open(path).into_error()?;
+            // SAFETY: The caller has guaranteed that the file will not be
+            // modified while it is mapped.
+            unsafe { Self::from_file_at(&file, 0) }
+        }
+
+        /// Memory-maps and validates the archive located in the given file
+        /// starting at `offset` bytes.
+        ///
+        /// The archive is assumed to extend to the end of the file. If
+        /// `offset` is not a multiple of [`MMAP_ALIGNMENT`], the archive is
+        /// copied into an [`AlignedVec`] instead of being accessed in place.
+        ///
+        /// [`MMAP_ALIGNMENT`]: super::MMAP_ALIGNMENT
+        /// [`AlignedVec`]: crate::util::AlignedVec
+        ///
+        /// # Safety
+        ///
+        /// The file must not be modified while it is mapped.
+        pub unsafe fn from_file_at<E>(
+            file: &File,
+            offset: u64,
+        ) -> Result<Self, E>
+        where
+            T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+            E: Source,
+        {
+            let storage = Self::map(file, offset)?;
+            access::<T::Archived, E>(storage.as_slice())?;
+            Ok(Self {
+                storage,
+                _phantom: PhantomData,
+            })
+        }
+    }
+}
+
+impl<T: Archive> Deref for MmapArchive<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Archive> fmt::Debug for MmapArchive<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapArchive")
+            .field("mapped", &self.is_mapped())
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use std::{
+        fs::{self, File},
+        io::Write as _,
+        path::PathBuf,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use rancor::{Error, Panic};
+
+    use crate::{to_bytes, util::mmap::MmapArchive, Archive, Serialize};
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Example {
+        name: String,
+        values: Vec<u64>,
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "rkyv_mmap_{}_{}",
+            std::process::id(),
+            name
+        ));
+        File::create(&path).unwrap().write_all(contents).unwrap();
+        path
+    }
+
+    fn example() -> Example {
+        Example {
+            name: "hello world".to_string(),
+            values: (0..100).collect(),
+        }
+    }
+
+    #[test]
+    fn open() {
+        let bytes = to_bytes::<Panic>(&example()).unwrap();
+        let path = temp_file("open", &bytes);
+
+        let archive =
+            unsafe { MmapArchive::<Example>::open::<Panic>(&path) }.unwrap();
+        assert!(archive.is_mapped());
+        assert_eq!(archive.name, "hello world");
+        assert_eq!(archive.values.len(), 100);
+        assert_eq!(archive.as_bytes(), &*bytes);
+
+        drop(archive);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn unaligned_offset() {
+        let bytes = to_bytes::<Panic>(&example()).unwrap();
+        let mut contents = vec![0xff; 3];
+        contents.extend_from_slice(&bytes);
+        let path = temp_file("unaligned_offset", &contents);
+
+        let file = File::open(&path).unwrap();
+        let archive =
+            unsafe { MmapArchive::<Example>::from_file_at::<Panic>(&file, 3) }
+                .unwrap();
+        assert!(!archive.is_mapped());
+        assert_eq!(archive.values[99], 99);
+
+        drop(archive);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn underaligned_offset() {
+        let bytes = to_bytes::<Panic>(&example()).unwrap();
+
+        // The root only requires 4-byte alignment, but the values require 8.
+        let mut contents = vec![0xff; 4];
+        contents.extend_from_slice(&bytes);
+        let path = temp_file("underaligned_offset", &contents);
+
+        let file = File::open(&path).unwrap();
+        let archive =
+            unsafe { MmapArchive::<Example>::from_file_at::<Panic>(&file, 4) }
+                .unwrap();
+        assert!(!archive.is_mapped());
+        assert_eq!(archive.values[99], 99);
+
+        drop(archive);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn aligned_offset() {
+        let bytes = to_bytes::<Panic>(&example()).unwrap();
+        let mut contents = vec![0xff; 16];
+        contents.extend_from_slice(&bytes);
+        let path = temp_file("aligned_offset", &contents);
+
+        let file = File::open(&path).unwrap();
+        let archive =
+            unsafe { MmapArchive::<Example>::from_file_at::<Panic>(&file, 16) }
+                .unwrap();
+        assert!(archive.is_mapped());
+        assert_eq!(archive.values[99], 99);
+
+        drop(archive);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid() {
+        let path = temp_file("invalid", &[0xff; 7]);
+
+        let result = unsafe { MmapArchive::<Example>::open::<Error>(&path) };
+        assert!(result.is_err(), "invalid archives must fail validation");
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -2,16 +2,33 @@
 
 #[cfg(feature = "alloc")]
 mod alloc;
-mod inline_vec;
-mod ser_vec;
+mod checksum;
+#[cfg(feature = "tokio-util-0_7")]
+pub mod codec;
+pub mod inline_vec;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod ser_vec;
+#[cfg(feature = "mmap")]
+pub mod shm;
 
 use core::ops::{Deref, DerefMut};
 
 #[doc(inline)]
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
+#[cfg(all(feature = "mmap", feature = "bytecheck"))]
 #[doc(inline)]
-pub use self::{inline_vec::InlineVec, ser_vec::SerVec};
+pub use self::shm::access_shm;
+#[cfg(feature = "mmap")]
+#[doc(inline)]
+pub use self::shm::ShmWriter;
+#[doc(inline)]
+pub use self::{
+    checksum::{crc32, verify_checksum, Checksummed, CHECKSUM_SIZE},
+    inline_vec::InlineVec,
+    ser_vec::SerVec,
+};
 
 /// A wrapper which aligns its inner value to 16 bytes.
 #[derive(Clone, Copy, Debug)]
@@ -1,3 +1,5 @@
+//! A vector which uses serializer-allocated memory.
+
 use core::{
     alloc::Layout,
     borrow::{Borrow, BorrowMut},
@@ -14,6 +16,40 @@ use rancor::Fallible;
 use crate::ser::Allocator;
 
 /// A vector that uses serializer-allocated memory.
+///
+/// `SerVec` provides temporary storage during serialization without touching
+/// the global allocator. Its memory is allocated from the serializer's
+/// [`Allocator`] with a fixed capacity, and is only available inside of the
+/// closure passed to [`with_capacity`](SerVec::with_capacity). Any elements
+/// still in the vector are dropped when the closure returns.
+///
+/// Pushing more elements than the vector has capacity for panics.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+///
+/// use rkyv::{
+///     rancor::{Error, Strategy},
+///     ser::allocator::SubAllocator,
+///     util::SerVec,
+/// };
+///
+/// let mut scratch = [MaybeUninit::<u8>::uninit(); 64];
+/// let mut alloc = SubAllocator::new(&mut scratch);
+/// let serializer = Strategy::<_, Error>::wrap(&mut alloc);
+///
+/// let sorted = SerVec::<u32>::with_capacity(serializer, 4, |vec, _| {
+///     vec.extend_from_slice(&[3, 1, 4]);
+///     vec.push(2);
+///     vec.sort();
+///     assert_eq!(vec.pop(), Some(4));
+///     vec.drain().collect::<Vec<_>>()
+/// })
+/// .unwrap();
+/// assert_eq!(sorted, [1, 2, 3]);
+/// ```
 pub struct SerVec<T> {
     ptr: NonNull<T>,
     cap: usize,
@@ -32,7 +68,16 @@ impl<T> SerVec<T> {
     /// Constructs a new, empty `SerVec` with the specified capacity.
     ///
     /// The vector will be able to hold exactly `capacity` elements. If
-    /// `capacity` is 0, the vector will not allocate.
+    /// `capacity` is 0, the vector will not allocate. The vector is passed to
+    /// `f` along with the serializer, and is cleared and deallocated after `f`
+    /// returns.
+    ///
+    /// Returns an error if the serializer fails to allocate or deallocate the
+    /// vector's memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `capacity` elements overflows `isize`.
     pub fn with_capacity<S, R>(
         serializer: &mut S,
         cap: usize,
@@ -115,13 +160,13 @@ impl<T> SerVec<T> {
         unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
     }
 
-    /// Returns the number of elements the vector can hole without reallocating.
+    /// Returns the number of elements the vector can hold without reallocating.
     pub fn capacity(&self) -> usize {
         self.cap
     }
 
     /// Ensures that there is capacity for at least `additional` more elements
-    /// to be inserted into the `ScratchVec`.
+    /// to be inserted into the vector.
     ///
     /// # Panics
     ///
@@ -148,9 +193,13 @@ impl<T> SerVec<T> {
         self.len
     }
 
-    /// Copies and appends all elements in a slice to the `ScratchVec`.
+    /// Copies and appends all elements in a slice to the vector.
     ///
     /// The elements of the slice are appended in-order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector does not have enough capacity for the elements.
     pub fn extend_from_slice(&mut self, other: &[T])
     where
         T: Copy,
@@ -195,6 +244,10 @@ impl<T> SerVec<T> {
     }
 
     /// Appends an element to the back of a collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector is already full.
     pub fn push(&mut self, value: T) {
         if self.len == self.cap {
             Self::out_of_space()
@@ -206,7 +259,7 @@ impl<T> SerVec<T> {
     }
 
     /// Reserves the minimum capacity for exactly `additional` more elements to
-    /// be inserted in the given `AlignedVec`. After calling
+    /// be inserted in the vector. After calling
     /// `reserve_exact`, capacity will be greater than or equal
     /// to `self.len() + additional`. Does nothing if the capacity is already
     /// sufficient.
@@ -233,8 +286,22 @@ impl<T> SerVec<T> {
         self.len = new_len;
     }
 
+    /// Shortens the vector, keeping the first `len` elements and dropping the
+    /// rest.
+    ///
+    /// If `len` is greater than or equal to the vector's current length, this
+    /// has no effect.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            drop(self.pop());
+        }
+    }
+
     /// Creates a draining iterator that removes all of the elements from the
     /// vector.
+    ///
+    /// Any elements which are not yielded by the iterator are dropped when it
+    /// is dropped.
     pub fn drain(&mut self) -> Drain<'_, T> {
         let remaining = self.len();
         unsafe {
@@ -258,7 +325,7 @@ impl<T> SerVec<MaybeUninit<T>> {
     /// It is up to the caller to guarantee that the `MaybeUninit<T>` elements
     /// really are in an initialized state. Calling this when the content is
     /// not yet fully initialized causes undefined behavior.
-    pub fn assume_init(self) -> SerVec<T> {
+    pub unsafe fn assume_init(self) -> SerVec<T> {
         SerVec {
             ptr: self.ptr.cast(),
             cap: self.cap,
@@ -398,3 +465,88 @@ impl<T> Drop for Drain<'_, T> {
 impl<T> ExactSizeIterator for Drain<'_, T> {}
 
 impl<T> core::iter::FusedIterator for Drain<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::Cell, mem::MaybeUninit};
+
+    use rancor::{Failure, Strategy};
+
+    use crate::{ser::allocator::SubAllocator, util::SerVec};
+
+    fn with_ser_vec<T, R>(
+        cap: usize,
+        f: impl FnOnce(&mut SerVec<T>) -> R,
+    ) -> R {
+        let mut scratch = [MaybeUninit::<u8>::uninit(); 256];
+        let mut alloc = SubAllocator::new(&mut scratch);
+        let serializer = Strategy::<_, Failure>::wrap(&mut alloc);
+        SerVec::with_capacity(serializer, cap, |vec, _| f(vec)).unwrap()
+    }
+
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn push_pop() {
+        with_ser_vec::<u32, _>(4, |vec| {
+            assert_eq!(vec.capacity(), 4);
+            assert!(vec.is_empty());
+            vec.push(1);
+            vec.extend_from_slice(&[2, 3, 4]);
+            assert_eq!(vec.as_slice(), &[1, 2, 3, 4]);
+            assert_eq!(vec.pop(), Some(4));
+            vec.truncate(1);
+            assert_eq!(vec.as_slice(), &[1]);
+            assert_eq!(vec.pop(), Some(1));
+            assert_eq!(vec.pop(), None);
+        });
+    }
+
+    #[test]
+    fn zero_capacity() {
+        with_ser_vec::<u32, _>(0, |vec| {
+            assert_eq!(vec.capacity(), 0);
+            assert!(vec.is_empty());
+        });
+    }
+
+    #[test]
+    #[should_panic = "reserve requested more capacity"]
+    fn push_out_of_space() {
+        with_ser_vec::<u32, _>(2, |vec| {
+            vec.push(1);
+            vec.push(2);
+            vec.push(3);
+        });
+    }
+
+    #[test]
+    fn drain_drops_remaining() {
+        let drops = Cell::new(0);
+        with_ser_vec(4, |vec| {
+            for _ in 0..4 {
+                vec.push(DropCounter(&drops));
+            }
+            let mut drain = vec.drain();
+            drop(drain.next());
+            drop(drain.next_back());
+            assert_eq!(drops.get(), 2);
+            assert_eq!(drain.len(), 2);
+        });
+        assert_eq!(drops.get(), 4);
+
+        drops.set(0);
+        with_ser_vec(3, |vec| {
+            for _ in 0..3 {
+                vec.push(DropCounter(&drops));
+            }
+        });
+        assert_eq!(drops.get(), 3);
+    }
+}
@@ -0,0 +1,460 @@
+//! Archives in shared memory for zero-copy IPC.
+//!
+//! A shared memory region is a file which is mapped into memory by multiple
+//! processes. On Linux, files in `/dev/shm` are kept entirely in memory, so
+//! they make good regions to share archives between processes.
+//!
+//! One process serializes a value into the region with a [`ShmWriter`] and
+//! [publishes](ShmWriter::publish) it. Other processes can then open the same
+//! region with [`access_shm`] and access the archived value in place. The
+//! region starts with a small header that records the length of the archive
+//! once it has been published, so readers never observe a partially-written
+//! archive.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     api::high::to_bytes_in,
+//!     rancor::Error,
+//!     util::shm::{access_shm, ShmWriter},
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Message {
+//!     id: u64,
+//!     body: String,
+//! }
+//!
+//! let path = std::env::temp_dir().join("rkyv_shm_doctest");
+//!
+//! // In the writing process:
+//! let message = Message {
+//!     id: 42,
+//!     body: "hello from another process".to_string(),
+//! };
+//! // SAFETY: No other process is accessing the region yet.
+//! let writer =
+//!     unsafe { ShmWriter::create::<Error>(&path, 4096) }.unwrap();
+//! to_bytes_in::<_, Error>(&message, writer).unwrap().publish();
+//!
+//! // In the reading process:
+//! // SAFETY: The region is not modified while it is accessed.
+//! let archive = unsafe { access_shm::<Message, Error>(&path) }.unwrap();
+//! assert_eq!(archive.id, 42);
+//! assert_eq!(archive.body, "hello from another process");
+//! # drop(archive);
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+
+use core::{
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    mem::size_of,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::{
+    fs::{File, OpenOptions},
+    path::Path,
+};
+
+use memmap2::{Mmap, MmapMut};
+use rancor::{fail, ResultExt as _, Source};
+
+use crate::{
+    api::access_unchecked,
+    ser::{Positional, Writer},
+    Archive,
+};
+
+const MAGIC: [u8; 8] = *b"rkyvshm\0";
+
+/// The size of the header at the start of each shared memory region.
+///
+/// The archive starts immediately after the header, so it is aligned to this
+/// many bytes.
+pub const SHM_HEADER_SIZE: usize = 16;
+
+const LEN_OFFSET: usize = 8;
+
+const _: () = assert!(LEN_OFFSET + size_of::<AtomicUsize>() <= SHM_HEADER_SIZE);
+
+/// Returns the published archive length stored in the header of a mapped
+/// region.
+///
+/// # Safety
+///
+/// `region` must point to a mapped region which is at least `SHM_HEADER_SIZE`
+/// bytes long and remains mapped for `'a`.
+unsafe fn published_len<'a>(region: *const u8) -> &'a AtomicUsize {
+    // SAFETY: The caller has guaranteed that the region is large enough and
+    // remains mapped for `'a`. Memory maps are page-aligned, so the length is
+    // sufficiently aligned for an `AtomicUsize`.
+    unsafe { &*region.add(LEN_OFFSET).cast::<AtomicUsize>() }
+}
+
+#[derive(Debug)]
+struct ShmOverflow {
+    write_len: usize,
+    pos: usize,
+    capacity: usize,
+}
+
+impl fmt::Display for ShmOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "overflowed shared memory region while writing {} bytes at \
+             position {} (capacity is {})",
+            self.write_len, self.pos, self.capacity,
+        )
+    }
+}
+
+impl Error for ShmOverflow {}
+
+#[derive(Debug)]
+enum ShmAccessError {
+    InvalidMagic,
+    Unpublished,
+    LengthOutOfBounds { len: usize, capacity: usize },
+}
+
+impl fmt::Display for ShmAccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMagic => {
+                write!(f, "file is not an rkyv shared memory region")
+            }
+            Self::Unpublished => write!(
+                f,
+                "shared memory region does not contain a published archive"
+            ),
+            Self::LengthOutOfBounds { len, capacity } => write!(
+                f,
+                "published archive length {} exceeds the capacity of the \
+                 shared memory region ({})",
+                len, capacity,
+            ),
+        }
+    }
+}
+
+impl Error for ShmAccessError {}
+
+/// A [`Writer`] which serializes directly into a shared memory region.
+///
+/// Creating a `ShmWriter` sizes the file at the given path to hold an archive
+/// of up to `capacity` bytes and maps it into memory. Writing past the end of
+/// the region fails instead of growing it. After the archive has been written,
+/// [`publish`](ShmWriter::publish) makes it visible to [`access_shm`].
+///
+/// See the [module docs](crate::util::shm) for an example.
+pub struct ShmWriter {
+    mmap: MmapMut,
+    pos: usize,
+}
+
+impl ShmWriter {
+    /// Creates a shared memory region at the given path with space for an
+    /// archive of `capacity` bytes.
+    ///
+    /// If the file already exists, it is truncated and any previously
+    /// published archive is removed.
+    ///
+    /// # Safety
+    ///
+    /// No other process may access the region until it is published, and the
+    /// region must not be modified by other processes while it is mapped.
+    pub unsafe fn create<E: Source>(
+        path: impl AsRef<Path>,
+        capacity: usize,
+    ) -> Result<Self, E> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .into_error()?;
+        file.set_len((SHM_HEADER_SIZE + capacity) as u64)
+            .into_error()?;
+
+        // SAFETY: The caller has guaranteed that the region will not be
+        // modified by other processes while it is mapped.
+        let mut mmap = unsafe { MmapMut::map_mut(&file) }.into_error()?;
+        mmap[..MAGIC.len()].copy_from_slice(&MAGIC);
+        // SAFETY: The region is `SHM_HEADER_SIZE + capacity` bytes long and
+        // stays mapped while `mmap` is alive.
+        unsafe { published_len(mmap.as_mut_ptr()) }.store(0, Ordering::Release);
+
+        Ok(Self { mmap, pos: 0 })
+    }
+
+    /// Returns the maximum size of an archive which can be written to the
+    /// region.
+    pub fn capacity(&self) -> usize {
+        self.mmap.len() - SHM_HEADER_SIZE
+    }
+
+    /// Returns the bytes written to the region so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap[SHM_HEADER_SIZE..SHM_HEADER_SIZE + self.pos]
+    }
+
+    /// Publishes the archive written to the region so that it can be accessed
+    /// with [`access_shm`].
+    ///
+    /// The archive must be written completely before it is published. This
+    /// does not flush the region to disk, since other processes can observe
+    /// it as soon as it is published.
+    pub fn publish(mut self) {
+        // SAFETY: The region is at least `SHM_HEADER_SIZE` bytes long and
+        // stays mapped while `self.mmap` is alive.
+        unsafe { published_len(self.mmap.as_mut_ptr()) }
+            .store(self.pos, Ordering::Release);
+    }
+}
+
+impl fmt::Debug for ShmWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmWriter")
+            .field("pos", &self.pos)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl Positional for ShmWriter {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<E: Source> Writer<E> for ShmWriter {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), E> {
+        if bytes.len() > self.capacity() - self.pos {
+            fail!(ShmOverflow {
+                write_len: bytes.len(),
+                pos: self.pos,
+                capacity: self.capacity(),
+            });
+        }
+
+        let start = SHM_HEADER_SIZE + self.pos;
+        self.mmap[start..start + bytes.len()].copy_from_slice(bytes);
+        self.pos += bytes.len();
+        Ok(())
+    }
+}
+
+/// An archived `T` in a shared memory region.
+///
+/// This is returned by [`access_shm`] and [`access_shm_unchecked`].
+pub struct ShmArchive<T> {
+    mmap: Mmap,
+    len: usize,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T: Archive> ShmArchive<T> {
+    fn map<E: Source>(file: &File) -> Result<Self, E> {
+        // SAFETY: The caller has guaranteed that the region will not be
+        // modified while it is mapped.
+        let mmap = unsafe { Mmap::map(file) }.into_error()?;
+        if mmap.len() < SHM_HEADER_SIZE || mmap[..MAGIC.len()] != MAGIC {
+            fail!(ShmAccessError::InvalidMagic);
+        }
+
+        // SAFETY: The region is at least `SHM_HEADER_SIZE` bytes long and
+        // stays mapped while `mmap` is alive.
+        let len =
+            unsafe { published_len(mmap.as_ptr()) }.load(Ordering::Acquire);
+        let capacity = mmap.len() - SHM_HEADER_SIZE;
+        if len == 0 {
+            fail!(ShmAccessError::Unpublished);
+        } else if len > capacity {
+            fail!(ShmAccessError::LengthOutOfBounds { len, capacity });
+        }
+
+        Ok(Self {
+            mmap,
+            len,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the bytes of the archive.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap[SHM_HEADER_SIZE..SHM_HEADER_SIZE + self.len]
+    }
+
+    /// Returns a reference to the archived value.
+    pub fn get(&self) -> &T::Archived {
+        // SAFETY: The bytes were either validated on creation or the caller
+        // has guaranteed that they contain a valid archived `T`.
+        unsafe { access_unchecked::<T::Archived>(self.as_bytes()) }
+    }
+}
+
+impl<T: Archive> Deref for ShmArchive<T> {
+    type Target = T::Archived;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Archive> fmt::Debug for ShmArchive<T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShmArchive")
+            .field("len", &self.len)
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// Accesses the archive published in the shared memory region at the given
+/// path without validating it.
+///
+/// Returns an error if the region does not contain a published archive.
+///
+/// # Safety
+///
+/// - The region must not be modified while it is mapped.
+/// - The region must contain a valid archived `T`.
+pub unsafe fn access_shm_unchecked<T: Archive, E: Source>(
+    path: impl AsRef<Path>,
+) -> Result<ShmArchive<T>, E> {
+    let file = File::open(path).into_error()?;
+    ShmArchive::map(&file)
+}
+
+#[cfg(feature = "bytecheck")]
+mod checked {
+    use std::{fs::File, path::Path};
+
+    use bytecheck::CheckBytes;
+    use rancor::{ResultExt as _, Source};
+
+    use super::ShmArchive;
+    use crate::{
+        api::high::{access, HighValidator},
+        Archive,
+    };
+
+    /// Accesses and validates the archive published in the shared memory
+    /// region at the given path.
+    ///
+    /// Returns an error if the region does not contain a published archive or
+    /// if the archive is invalid.
+    ///
+    /// # Safety
+    ///
+    /// The region must not be modified while it is mapped.
+    pub unsafe fn access_shm<T, E>(
+        path: impl AsRef<Path>,
+    ) -> Result<ShmArchive<T>, E>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<HighValidator<'a, E>>,
+        E: Source,
+    {
+        let file = File::open(path).into_error()?;
+        let archive = ShmArchive::<T>::map(&file)?;
+        access::<T::Archived, E>(archive.as_bytes())?;
+        Ok(archive)
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::checked::access_shm;
+
+#[cfg(all(test, feature = "bytecheck"))]
+mod tests {
+    use std::{
+        fs,
+        path::PathBuf,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use rancor::{Error, Panic};
+
+    use crate::{
+        api::high::to_bytes_in,
+        to_bytes,
+        util::shm::{access_shm, ShmWriter},
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Example {
+        name: String,
+        values: Vec<u64>,
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "rkyv_shm_{}_{}",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn example() -> Example {
+        Example {
+            name: "hello world".to_string(),
+            values: (0..100).collect(),
+        }
+    }
+
+    #[test]
+    fn publish_and_access() {
+        let path = temp_path("publish_and_access");
+
+        let writer =
+            unsafe { ShmWriter::create::<Panic>(&path, 4096) }.unwrap();
+        assert_eq!(writer.capacity(), 4096);
+        let writer = to_bytes_in::<_, Panic>(&example(), writer).unwrap();
+        assert_eq!(writer.as_bytes(), &*to_bytes::<Panic>(&example()).unwrap());
+
+        // Unpublished regions can't be accessed
+        assert!(unsafe { access_shm::<Example, Error>(&path) }.is_err());
+
+        writer.publish();
+        let archive = unsafe { access_shm::<Example, Panic>(&path) }.unwrap();
+        assert_eq!(archive.name, "hello world");
+        assert_eq!(archive.values[99], 99);
+
+        drop(archive);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn overflow() {
+        let path = temp_path("overflow");
+
+        let writer = unsafe { ShmWriter::create::<Panic>(&path, 64) }.unwrap();
+        assert!(to_bytes_in::<_, Error>(&example(), writer).is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn invalid_region() {
+        let path = temp_path("invalid_region");
+        fs::write(&path, [0xff; 64]).unwrap();
+
+        assert!(unsafe { access_shm::<Example, Error>(&path) }.is_err());
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -10,12 +10,21 @@ use rancor::ResultExt as _;
 pub use self::validator::*;
 use crate::traits::LayoutRaw;
 
+/// The subtree range and depth of an [`ArchiveContext`], saved while a subtree
+/// check is deferred.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubtreeState {
+    range: Range<usize>,
+    depth: usize,
+}
+
 /// A context that can validate nonlocal archive memory.
 ///
 /// # Safety
 ///
 /// `check_subtree_ptr` must only return true if `ptr` is located entirely
-/// within the subtree range and is safe to dereference.
+/// within the subtree range and is safe to dereference. Every check deferred by
+/// `defer_subtree` must be counted by `deferred_len` and run by `run_deferred`.
 pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
     /// Checks that the given data address and layout is located completely
     /// within the subtree range.
@@ -53,6 +62,81 @@ pub unsafe trait ArchiveContext<E = <Self as Fallible>::Error> {
         &mut self,
         range: Range<usize>,
     ) -> Result<(), E>;
+
+    /// Checks the subtree from `root` to `end` by calling `check` with the
+    /// subtree range pushed.
+    ///
+    /// Contexts which bound the depth of recursion may instead defer `check`
+    /// until the innermost subtree which contains it is finished. In that
+    /// case, the subtree range is claimed as if it had been pushed and popped.
+    /// By default, checks are never deferred.
+    ///
+    /// # Safety
+    ///
+    /// - `root` and `end` must be located inside the current subtree range.
+    /// - `check` must be safe to run with this context once the subtree range
+    ///   from `root` to `end` is pushed.
+    /// - `check` must not capture any borrows, since it may be stored until
+    ///   the context is dropped.
+    unsafe fn defer_subtree<F>(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+        check: F,
+    ) -> Result<(), E>
+    where
+        F: FnOnce(&mut Self) -> Result<(), E>,
+    {
+        // SAFETY: The caller has guaranteed that `root` and `end` are located
+        // inside the current subtree range.
+        unsafe { enter_subtree(self, root, end, check) }
+    }
+
+    /// Returns the number of deferred checks which have not been run yet.
+    fn deferred_len(&self) -> usize {
+        0
+    }
+
+    /// Runs the most recently deferred checks until there are only `len`
+    /// deferred checks left.
+    fn run_deferred(&mut self, len: usize) -> Result<(), E> {
+        let _ = len;
+        Ok(())
+    }
+
+    /// Claims the subtree range from `root` to `end` as if it had been pushed
+    /// and popped, so that it can be checked later.
+    ///
+    /// Returns the state to check the subtree with, or `None` if the subtree
+    /// should be checked immediately instead. By default, subtrees are always
+    /// checked immediately.
+    ///
+    /// # Safety
+    ///
+    /// `root` and `end` must be located inside the current subtree range.
+    unsafe fn defer_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Option<SubtreeState>, E> {
+        let _ = (root, end);
+        Ok(None)
+    }
+
+    /// Replaces the subtree range and depth of the context with `state`, and
+    /// returns the previous state.
+    ///
+    /// # Safety
+    ///
+    /// `state` must have been returned from `defer_subtree_range` or
+    /// `replace_subtree_state`, and any subtree ranges pushed since entering
+    /// the current state must have been popped.
+    unsafe fn replace_subtree_state(
+        &mut self,
+        state: SubtreeState,
+    ) -> SubtreeState {
+        state
+    }
 }
 
 unsafe impl<T, E> ArchiveContext<E> for Strategy<T, E>
@@ -85,6 +169,49 @@ where
         // has the same safety requirements.
         unsafe { T::pop_subtree_range(self, range) }
     }
+
+    unsafe fn defer_subtree<F>(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+        check: F,
+    ) -> Result<(), E>
+    where
+        F: FnOnce(&mut Self) -> Result<(), E>,
+    {
+        let check = move |inner: &mut T| check(Strategy::wrap(inner));
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements. `check` only rewraps the context
+        // it is called with.
+        unsafe { T::defer_subtree(self, root, end, check) }
+    }
+
+    fn deferred_len(&self) -> usize {
+        T::deferred_len(self)
+    }
+
+    fn run_deferred(&mut self, len: usize) -> Result<(), E> {
+        T::run_deferred(self, len)
+    }
+
+    unsafe fn defer_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Option<SubtreeState>, E> {
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements.
+        unsafe { T::defer_subtree_range(self, root, end) }
+    }
+
+    unsafe fn replace_subtree_state(
+        &mut self,
+        state: SubtreeState,
+    ) -> SubtreeState {
+        // SAFETY: This just forwards the call to the underlying context, which
+        // has the same safety requirements.
+        unsafe { T::replace_subtree_state(self, state) }
+    }
 }
 
 /// Helper methods for [`ArchiveContext`].
@@ -107,6 +234,78 @@ pub trait ArchiveContextExt<E>: ArchiveContext<E> {
         ptr: *const T,
         f: impl FnOnce(&mut Self) -> Result<R, E>,
     ) -> Result<R, E>;
+
+    /// Checks that the given pointer and layout are within the current subtree
+    /// range of the context, then calls `check` on `value` in a new subtree
+    /// range.
+    ///
+    /// If the context supports it, the check may be deferred until the
+    /// innermost subtree which contains it is finished. This bounds the depth
+    /// of recursion when validating deeply-nested archives.
+    ///
+    /// # Safety
+    ///
+    /// `check` must be safe to call with `value` and this context once the
+    /// range from `ptr` to `ptr + layout.size()` is checked and pushed.
+    unsafe fn in_subtree_raw_deferred<T: ?Sized>(
+        &mut self,
+        ptr: *const u8,
+        layout: Layout,
+        value: *const T,
+        check: unsafe fn(*const T, &mut Self) -> Result<(), E>,
+    ) -> Result<(), E>;
+
+    /// Checks that the value the given pointer points to is within the current
+    /// subtree range of the context, then calls `check` on it in a new subtree
+    /// range.
+    ///
+    /// If the context supports it, the check may be deferred until the
+    /// innermost subtree which contains it is finished. This bounds the depth
+    /// of recursion when validating deeply-nested archives.
+    ///
+    /// # Safety
+    ///
+    /// `check` must be safe to call with `ptr` and this context when `ptr` is
+    /// properly aligned and points to enough bytes to represent a `T`.
+    unsafe fn in_subtree_deferred<T: LayoutRaw + ?Sized>(
+        &mut self,
+        ptr: *const T,
+        check: unsafe fn(*const T, &mut Self) -> Result<(), E>,
+    ) -> Result<(), E>;
+}
+
+/// Pushes a new subtree range from `root` to `end`, calls the given function,
+/// and then runs any checks deferred inside of it.
+///
+/// # Safety
+///
+/// `root` and `end` must be located inside the current subtree range of
+/// `context`.
+pub(super) unsafe fn enter_subtree<C, E, R>(
+    context: &mut C,
+    root: *const u8,
+    end: *const u8,
+    f: impl FnOnce(&mut C) -> Result<R, E>,
+) -> Result<R, E>
+where
+    C: ArchiveContext<E> + ?Sized,
+{
+    let deferred_len = context.deferred_len();
+
+    // SAFETY: The caller has guaranteed that `root` and `end` are located
+    // inside the current subtree range.
+    let range = unsafe { context.push_subtree_range(root, end)? };
+
+    let result = f(context)?;
+
+    // SAFETY: `range` was returned from `push_subtree_range`.
+    unsafe {
+        context.pop_subtree_range(range)?;
+    }
+
+    context.run_deferred(deferred_len)?;
+
+    Ok(result)
 }
 
 impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
@@ -121,17 +320,7 @@ impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
 
         // SAFETY: We checked that the entire range from `ptr` to
         // `ptr + layout.size()` is located within the buffer.
-        let range =
-            unsafe { self.push_subtree_range(ptr, ptr.add(layout.size()))? };
-
-        let result = f(self)?;
-
-        // SAFETY: `range` was returned from `push_subtree_range`.
-        unsafe {
-            self.pop_subtree_range(range)?;
-        }
-
-        Ok(result)
+        unsafe { enter_subtree(self, ptr, ptr.add(layout.size()), f) }
     }
 
     #[allow(clippy::not_unsafe_ptr_arg_deref)]
@@ -145,4 +334,39 @@ impl<C: ArchiveContext<E> + ?Sized, E: Source> ArchiveContextExt<E> for C {
 
         self.in_subtree_raw(root, layout, f)
     }
+
+    unsafe fn in_subtree_raw_deferred<T: ?Sized>(
+        &mut self,
+        ptr: *const u8,
+        layout: Layout,
+        value: *const T,
+        check: unsafe fn(*const T, &mut Self) -> Result<(), E>,
+    ) -> Result<(), E> {
+        self.check_subtree_ptr(ptr, &layout)?;
+
+        let check = move |context: &mut Self| {
+            // SAFETY: The caller has guaranteed that `check` is safe to call
+            // with `value` once the subtree range is pushed.
+            unsafe { check(value, context) }
+        };
+        // SAFETY:
+        // - We checked that the entire range from `ptr` to
+        //   `ptr + layout.size()` is located within the current subtree range.
+        // - `check` only captures a pointer and a function pointer, so it
+        //   doesn't capture any borrows.
+        unsafe { self.defer_subtree(ptr, ptr.add(layout.size()), check) }
+    }
+
+    unsafe fn in_subtree_deferred<T: LayoutRaw + ?Sized>(
+        &mut self,
+        ptr: *const T,
+        check: unsafe fn(*const T, &mut Self) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let layout = T::layout_raw(ptr_meta::metadata(ptr)).into_error()?;
+
+        // SAFETY: `in_subtree_raw_deferred` checks that `ptr` is properly
+        // aligned and points to enough bytes for a `T` before calling
+        // `check`.
+        unsafe { self.in_subtree_raw_deferred(ptr.cast(), layout, ptr, check) }
+    }
 }
@@ -5,7 +5,14 @@ use core::{
 
 use rancor::{fail, OptionExt, Source};
 
-use crate::{fmt::Pointer, validation::ArchiveContext};
+use crate::{
+    fmt::Pointer,
+    validation::{archive::SubtreeState, ArchiveContext},
+};
+
+/// The depth past which subtree checks are deferred instead of run
+/// recursively.
+const MAX_RECURSION_DEPTH: usize = 32;
 
 #[derive(Debug)]
 struct UnalignedPointer {
@@ -62,6 +69,40 @@ impl fmt::Display for ExceededMaximumSubtreeDepth {
 
 impl Error for ExceededMaximumSubtreeDepth {}
 
+#[derive(Debug)]
+struct ExceededMaximumSubtrees {
+    max_subtrees: usize,
+}
+
+impl fmt::Display for ExceededMaximumSubtrees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "pushed more than the maximum of {} subtree ranges",
+            self.max_subtrees,
+        )
+    }
+}
+
+impl Error for ExceededMaximumSubtrees {}
+
+#[derive(Debug)]
+struct ExceededMaximumBytes {
+    max_bytes: usize,
+}
+
+impl fmt::Display for ExceededMaximumBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "subtree ranges claimed more than the maximum of {} bytes",
+            self.max_bytes,
+        )
+    }
+}
+
+impl Error for ExceededMaximumBytes {}
+
 #[derive(Debug)]
 struct RangePoppedTooManyTimes;
 
@@ -84,11 +125,60 @@ impl fmt::Display for RangePoppedOutOfOrder {
 
 impl Error for RangePoppedOutOfOrder {}
 
+/// Resource limits for validating untrusted archives.
+///
+/// Each limit is disabled when it is `None`. The default limits are all
+/// disabled.
+///
+/// # Example
+///
+/// ```
+/// use core::num::NonZeroUsize;
+///
+/// use rkyv::validation::archive::ValidationLimits;
+///
+/// let limits = ValidationLimits {
+///     max_depth: NonZeroUsize::new(64),
+///     max_subtrees: Some(1 << 16),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ValidationLimits {
+    /// The maximum number of subtree ranges that may be pushed at the same
+    /// time.
+    ///
+    /// The root of the archive and every box, vec, string, and B-tree node is
+    /// validated in a nested subtree range, so this bounds how deeply
+    /// validation can recurse.
+    pub max_depth: Option<NonZeroUsize>,
+    /// The maximum total number of subtree ranges that may be pushed.
+    ///
+    /// This bounds the number of out-of-line values that are validated,
+    /// including the first visit to each shared pointer.
+    pub max_subtrees: Option<usize>,
+    /// The maximum total number of bytes that may be claimed by subtree
+    /// ranges.
+    pub max_bytes: Option<usize>,
+}
+
+impl ValidationLimits {
+    /// Validation limits with every limit disabled.
+    pub const UNLIMITED: Self = Self {
+        max_depth: None,
+        max_subtrees: None,
+        max_bytes: None,
+    };
+}
+
 /// A validator that can verify archives with nonlocal memory.
 #[derive(Debug)]
 pub struct ArchiveValidator<'a> {
     subtree_range: Range<usize>,
-    max_subtree_depth: Option<NonZeroUsize>,
+    limits: ValidationLimits,
+    depth: usize,
+    subtrees: usize,
+    bytes: usize,
     _phantom: PhantomData<&'a [u8]>,
 }
 
@@ -96,7 +186,7 @@ impl<'a> ArchiveValidator<'a> {
     /// Creates a new bounds validator for the given bytes.
     #[inline]
     pub fn new(bytes: &'a [u8]) -> Self {
-        Self::with_max_depth(bytes, None)
+        Self::with_limits(bytes, ValidationLimits::UNLIMITED)
     }
 
     /// Crates a new bounds validator for the given bytes with a maximum
@@ -106,16 +196,69 @@ impl<'a> ArchiveValidator<'a> {
         bytes: &'a [u8],
         max_subtree_depth: Option<NonZeroUsize>,
     ) -> Self {
+        Self::with_limits(
+            bytes,
+            ValidationLimits {
+                max_depth: max_subtree_depth,
+                ..ValidationLimits::UNLIMITED
+            },
+        )
+    }
+
+    /// Creates a new bounds validator for the given bytes which enforces the
+    /// given validation limits.
+    #[inline]
+    pub fn with_limits(bytes: &'a [u8], limits: ValidationLimits) -> Self {
         let Range { start, end } = bytes.as_ptr_range();
         Self {
             subtree_range: Range {
                 start: start as usize,
                 end: end as usize,
             },
-            max_subtree_depth,
+            limits,
+            depth: 0,
+            subtrees: 0,
+            bytes: 0,
             _phantom: PhantomData,
         }
     }
+
+    /// Returns the validation limits enforced by this validator.
+    #[inline]
+    pub fn limits(&self) -> &ValidationLimits {
+        &self.limits
+    }
+
+    fn claim_subtree<E: Source>(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+        depth: usize,
+    ) -> Result<(), E> {
+        if let Some(max_depth) = self.limits.max_depth {
+            if depth > max_depth.get() {
+                fail!(ExceededMaximumSubtreeDepth);
+            }
+        }
+
+        self.subtrees += 1;
+        if let Some(max_subtrees) = self.limits.max_subtrees {
+            if self.subtrees > max_subtrees {
+                fail!(ExceededMaximumSubtrees { max_subtrees });
+            }
+        }
+
+        self.bytes = self
+            .bytes
+            .saturating_add((end as usize).saturating_sub(root as usize));
+        if let Some(max_bytes) = self.limits.max_bytes {
+            if self.bytes > max_bytes {
+                fail!(ExceededMaximumBytes { max_bytes });
+            }
+        }
+
+        Ok(())
+    }
 }
 
 unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
@@ -147,10 +290,8 @@ unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
         root: *const u8,
         end: *const u8,
     ) -> Result<Range<usize>, E> {
-        if let Some(max_subtree_depth) = &mut self.max_subtree_depth {
-            *max_subtree_depth = NonZeroUsize::new(max_subtree_depth.get() - 1)
-                .into_trace(ExceededMaximumSubtreeDepth)?;
-        }
+        self.claim_subtree(root, end, self.depth + 1)?;
+        self.depth += 1;
 
         let result = Range {
             start: end as usize,
@@ -168,11 +309,44 @@ unsafe impl<E: Source> ArchiveContext<E> for ArchiveValidator<'_> {
             fail!(RangePoppedOutOfOrder);
         }
         self.subtree_range = range;
-        if let Some(max_subtree_depth) = &mut self.max_subtree_depth {
-            *max_subtree_depth = max_subtree_depth
-                .checked_add(1)
-                .into_trace(RangePoppedTooManyTimes)?;
-        }
+        self.depth = self
+            .depth
+            .checked_sub(1)
+            .into_trace(RangePoppedTooManyTimes)?;
         Ok(())
     }
+
+    unsafe fn defer_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Option<SubtreeState>, E> {
+        if self.depth < MAX_RECURSION_DEPTH {
+            return Ok(None);
+        }
+
+        self.claim_subtree(root, end, self.depth + 1)?;
+
+        // Claim the subtree as if it had been pushed and popped.
+        let state = SubtreeState {
+            range: Range {
+                start: self.subtree_range.start,
+                end: root as usize,
+            },
+            depth: self.depth + 1,
+        };
+        self.subtree_range.start = end as usize;
+
+        Ok(Some(state))
+    }
+
+    unsafe fn replace_subtree_state(
+        &mut self,
+        state: SubtreeState,
+    ) -> SubtreeState {
+        SubtreeState {
+            range: core::mem::replace(&mut self.subtree_range, state.range),
+            depth: core::mem::replace(&mut self.depth, state.depth),
+        }
+    }
 }
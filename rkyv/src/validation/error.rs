@@ -0,0 +1,316 @@
+//! Structured validation errors.
+//!
+//! Validation errors are built up as they return through the `CheckBytes`
+//! impls of the archived types containing the invalid value. The impls derived
+//! by `bytecheck` trace the struct fields and enum variants that they check,
+//! and rkyv's collections trace the elements that they check with an
+//! [`ElementCheckContext`]. Most error types flatten these traces into a
+//! message. [`ValidationError`] instead records them as a [path](PathSegment)
+//! to the invalid value, which can be inspected or displayed like
+//! `root.users[3].name`.
+//!
+//! # Example
+//!
+//! ```
+//! use rkyv::{
+//!     access, rancor::Error, to_bytes, validation::error::ValidationError,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! struct User {
+//!     name: String,
+//!     active: bool,
+//! }
+//!
+//! #[derive(Archive, Serialize)]
+//! struct Users {
+//!     users: Vec<User>,
+//! }
+//!
+//! let value = Users {
+//!     users: vec![
+//!         User {
+//!             name: "alice".to_string(),
+//!             active: true,
+//!         },
+//!         User {
+//!             name: "bob".to_string(),
+//!             active: false,
+//!         },
+//!     ],
+//! };
+//! let mut bytes = to_bytes::<Error>(&value).unwrap();
+//!
+//! // Corrupt the `active` field of the second user
+//! let archived = access::<ArchivedUsers, Error>(&bytes).unwrap();
+//! let active = &archived.users[1].active as *const bool;
+//! let pos = active as usize - bytes.as_ptr() as usize;
+//! bytes[pos] = 2;
+//!
+//! let Err(error) = access::<ArchivedUsers, ValidationError>(&bytes) else {
+//!     panic!("expected validation to fail");
+//! };
+//! assert_eq!(error.path_string(), "root.users[1].active");
+//! assert!(error.offset_in(&bytes).is_some());
+//! ```
+
+use core::fmt;
+
+/// A trace recording the element of a collection that was being checked.
+///
+/// rkyv's collections add this trace to errors that occur while checking their
+/// elements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementCheckContext {
+    /// The index of the element in the collection.
+    pub index: usize,
+    /// The address of the element.
+    pub address: usize,
+}
+
+impl fmt::Display for ElementCheckContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "while checking element {} at address {:#x}",
+            self.index, self.address,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub use self::detailed::*;
+
+#[cfg(feature = "alloc")]
+mod detailed {
+    use core::{any::Any, error::Error, fmt};
+
+    use bytecheck::{
+        NamedEnumVariantCheckContext, StructCheckContext,
+        TupleStructCheckContext, UnnamedEnumVariantCheckContext,
+    };
+    use rancor::{BoxedError, Source, Trace};
+
+    use super::ElementCheckContext;
+    use crate::alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    /// A step in the path from the root of an archive to an invalid value.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum PathSegment {
+        /// A named field of a struct or enum variant.
+        Field(&'static str),
+        /// An unnamed field of a tuple struct or enum variant.
+        TupleField(usize),
+        /// A variant of an enum.
+        Variant(&'static str),
+        /// An element of a collection.
+        Index(usize),
+    }
+
+    impl fmt::Display for PathSegment {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Field(name) => write!(f, ".{}", name),
+                Self::TupleField(index) => write!(f, ".{}", index),
+                Self::Variant(name) => write!(f, "::{}", name),
+                Self::Index(index) => write!(f, "[{}]", index),
+            }
+        }
+    }
+
+    /// A validation error which records the path to the invalid value.
+    ///
+    /// See the [module-level documentation](super) for more information.
+    #[derive(Debug)]
+    pub struct ValidationError {
+        inner: BoxedError,
+        path: Vec<PathSegment>,
+        address: Option<usize>,
+        traces: Vec<String>,
+    }
+
+    impl ValidationError {
+        /// Returns the path from the root of the archive to the invalid value,
+        /// starting from the root.
+        pub fn path(&self) -> &[PathSegment] {
+            &self.path
+        }
+
+        /// Returns the path to the invalid value formatted like
+        /// `root.users[3].name`.
+        pub fn path_string(&self) -> String {
+            let mut result = "root".to_string();
+            for segment in self.path.iter() {
+                result.push_str(&segment.to_string());
+            }
+            result
+        }
+
+        /// Returns the address of the innermost collection element that
+        /// contains the invalid value, if any.
+        pub fn address(&self) -> Option<usize> {
+            self.address
+        }
+
+        /// Returns the offset in `bytes` of the innermost collection element
+        /// that contains the invalid value.
+        ///
+        /// `bytes` must be the buffer that was validated. Returns `None` if
+        /// the invalid value is not inside of a collection element, or if the
+        /// element is not inside of `bytes`.
+        pub fn offset_in(&self, bytes: &[u8]) -> Option<usize> {
+            let offset = self.address?.checked_sub(bytes.as_ptr() as usize)?;
+            (offset < bytes.len()).then_some(offset)
+        }
+
+        /// Returns the error that caused validation to fail.
+        pub fn cause(&self) -> &BoxedError {
+            &self.inner
+        }
+
+        /// Returns the messages of any traces which are not part of the path.
+        pub fn traces(&self) -> impl Iterator<Item = &str> {
+            self.traces.iter().map(String::as_str)
+        }
+
+        fn push_front(&mut self, segments: &[PathSegment]) {
+            self.path.splice(0..0, segments.iter().copied());
+        }
+    }
+
+    impl fmt::Display for ValidationError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "invalid value at {}", self.path_string())?;
+            if let Some(address) = self.address {
+                write!(f, " (element at address {:#x})", address)?;
+            }
+            write!(f, ": {}", self.inner)?;
+            for trace in self.traces.iter() {
+                write!(f, "\n{}", trace)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Error for ValidationError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.inner)
+        }
+    }
+
+    impl Trace for ValidationError {
+        fn trace<R>(mut self, trace: R) -> Self
+        where
+            R: fmt::Debug + fmt::Display + Send + Sync + 'static,
+        {
+            let any = &trace as &dyn Any;
+            if let Some(context) = any.downcast_ref::<StructCheckContext>() {
+                self.push_front(&[PathSegment::Field(context.field_name)]);
+            } else if let Some(context) =
+                any.downcast_ref::<TupleStructCheckContext>()
+            {
+                self.push_front(&[PathSegment::TupleField(
+                    context.field_index,
+                )]);
+            } else if let Some(context) =
+                any.downcast_ref::<NamedEnumVariantCheckContext>()
+            {
+                self.push_front(&[
+                    PathSegment::Variant(context.variant_name),
+                    PathSegment::Field(context.field_name),
+                ]);
+            } else if let Some(context) =
+                any.downcast_ref::<UnnamedEnumVariantCheckContext>()
+            {
+                self.push_front(&[
+                    PathSegment::Variant(context.variant_name),
+                    PathSegment::TupleField(context.field_index),
+                ]);
+            } else if let Some(context) =
+                any.downcast_ref::<ElementCheckContext>()
+            {
+                self.push_front(&[PathSegment::Index(context.index)]);
+                self.address.get_or_insert(context.address);
+            } else {
+                self.traces.push(trace.to_string());
+            }
+            self
+        }
+    }
+
+    impl Source for ValidationError {
+        fn new<T: Error + Send + Sync + 'static>(source: T) -> Self {
+            Self {
+                inner: BoxedError::new(source),
+                path: Vec::new(),
+                address: None,
+                traces: Vec::new(),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use super::{PathSegment, ValidationError};
+    use crate::{
+        access,
+        alloc::{vec, vec::Vec},
+        api::high::to_bytes_in,
+        util::AlignedVec,
+        Archive, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    struct Pair(u32, bool);
+
+    #[allow(dead_code)]
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate)]
+    enum Shape {
+        Empty,
+        Pairs { pairs: Vec<Pair> },
+    }
+
+    #[test]
+    fn enum_and_tuple_path() {
+        let value = Shape::Pairs {
+            pairs: vec![Pair(1, true), Pair(2, false), Pair(3, true)],
+        };
+        let mut bytes =
+            to_bytes_in::<_, rancor::Error>(&value, AlignedVec::<16>::new())
+                .unwrap();
+
+        let pos = match access::<ArchivedShape, rancor::Error>(&bytes).unwrap()
+        {
+            ArchivedShape::Pairs { pairs } => {
+                &pairs[2].1 as *const bool as usize - bytes.as_ptr() as usize
+            }
+            ArchivedShape::Empty => unreachable!(),
+        };
+        bytes[pos] = 3;
+
+        let Err(error) = access::<ArchivedShape, ValidationError>(&bytes)
+        else {
+            panic!("expected validation to fail");
+        };
+        assert_eq!(
+            error.path(),
+            &[
+                PathSegment::Variant("Pairs"),
+                PathSegment::Field("pairs"),
+                PathSegment::Index(2),
+                PathSegment::TupleField(1),
+            ],
+        );
+        assert_eq!(error.path_string(), "root::Pairs.pairs[2].1");
+
+        let element = error.offset_in(&bytes).unwrap();
+        assert!(element <= pos && pos < element + size_of::<ArchivedPair>());
+    }
+}
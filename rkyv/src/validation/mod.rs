@@ -1,33 +1,209 @@
 //! Validation implementations and helper types.
 
 pub mod archive;
+pub mod error;
 pub mod shared;
 
 use core::{any::TypeId, ops::Range};
 
+use self::archive::SubtreeState;
 pub use self::{
     archive::{ArchiveContext, ArchiveContextExt},
     shared::SharedContext,
 };
+#[cfg(feature = "alloc")]
+use crate::alloc::{boxed::Box, vec::Vec};
 
 /// The default validator.
+///
+/// Subtree checks deferred by the archive context are stored in `D`. By
+/// default, subtree checks are never deferred. See [`DeferredChecks`].
 #[derive(Debug)]
-pub struct Validator<A, S> {
+pub struct Validator<A, S, D = ()> {
     archive: A,
     shared: S,
+    deferred: D,
 }
 
 impl<A, S> Validator<A, S> {
     /// Creates a new validator from a byte range.
     #[inline]
     pub fn new(archive: A, shared: S) -> Self {
-        Self { archive, shared }
+        Self::with_deferred(archive, shared, ())
     }
 }
 
-unsafe impl<A, S, E> ArchiveContext<E> for Validator<A, S>
+impl<A, S, D> Validator<A, S, D> {
+    /// Creates a new validator which stores deferred subtree checks in
+    /// `deferred`.
+    #[inline]
+    pub fn with_deferred(archive: A, shared: S, deferred: D) -> Self {
+        Self {
+            archive,
+            shared,
+            deferred,
+        }
+    }
+
+    /// Consumes the validator and returns its archive and shared contexts.
+    #[inline]
+    pub fn into_parts(self) -> (A, S) {
+        (self.archive, self.shared)
+    }
+}
+
+/// A stack of subtree checks deferred by a [`Validator`].
+///
+/// # Safety
+///
+/// Every check pushed onto the queue must be counted by `deferred_len` and
+/// returned from `pop_deferred` exactly once, in the reverse order that they
+/// were pushed.
+pub unsafe trait DeferQueue<C: ?Sized, E> {
+    /// A check popped from the queue.
+    type Check: FnOnce(&mut C) -> Result<(), E>;
+
+    /// Returns whether checks can be pushed onto the queue.
+    fn can_defer(&self) -> bool;
+
+    /// Returns the number of checks in the queue.
+    fn deferred_len(&self) -> usize;
+
+    /// Pushes a check onto the queue along with the state to run it with.
+    ///
+    /// # Safety
+    ///
+    /// - `can_defer` must have returned `true`.
+    /// - `check` must not capture any borrows, since it may be stored until
+    ///   the queue is dropped.
+    unsafe fn push_deferred<F>(&mut self, check: F, state: SubtreeState)
+    where
+        F: FnOnce(&mut C) -> Result<(), E>;
+
+    /// Pops the most recently pushed check if there are more than `len` checks
+    /// in the queue.
+    fn pop_deferred(
+        &mut self,
+        len: usize,
+    ) -> Option<(Self::Check, SubtreeState)>;
+}
+
+// SAFETY: No checks can be pushed onto the queue.
+unsafe impl<C: ?Sized, E> DeferQueue<C, E> for () {
+    type Check = fn(&mut C) -> Result<(), E>;
+
+    fn can_defer(&self) -> bool {
+        false
+    }
+
+    fn deferred_len(&self) -> usize {
+        0
+    }
+
+    unsafe fn push_deferred<F>(&mut self, _: F, _: SubtreeState)
+    where
+        F: FnOnce(&mut C) -> Result<(), E>,
+    {
+        unreachable!("checks can't be deferred without a queue");
+    }
+
+    fn pop_deferred(
+        &mut self,
+        _: usize,
+    ) -> Option<(Self::Check, SubtreeState)> {
+        None
+    }
+}
+
+/// A check deferred by a [`Validator`] with [`DeferredChecks`].
+#[cfg(feature = "alloc")]
+type DeferredCheck<'a, A, S, E> = Box<
+    dyn FnOnce(&mut Validator<A, S, DeferredChecks<A, S, E>>) -> Result<(), E>
+        + 'a,
+>;
+
+/// A heap-allocated stack of the subtree checks deferred by a [`Validator`].
+///
+/// Subtree checks nested too deeply are pushed onto this stack instead of run
+/// recursively, which bounds the stack space needed to validate deeply-nested
+/// archives.
+#[cfg(feature = "alloc")]
+pub struct DeferredChecks<A, S, E> {
+    checks: Vec<(DeferredCheck<'static, A, S, E>, SubtreeState)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<A, S, E> DeferredChecks<A, S, E> {
+    /// Creates a new empty stack of deferred checks.
+    #[inline]
+    pub fn new() -> Self {
+        Self { checks: Vec::new() }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, S, E> Default for DeferredChecks<A, S, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<A, S, E> core::fmt::Debug for DeferredChecks<A, S, E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DeferredChecks")
+            .field("len", &self.checks.len())
+            .finish_non_exhaustive()
+    }
+}
+
+// SAFETY: Checks are stored on a stack, which pops them in the reverse order
+// that they were pushed.
+#[cfg(feature = "alloc")]
+unsafe impl<A, S, E> DeferQueue<Validator<A, S, Self>, E>
+    for DeferredChecks<A, S, E>
+{
+    type Check = DeferredCheck<'static, A, S, E>;
+
+    fn can_defer(&self) -> bool {
+        true
+    }
+
+    fn deferred_len(&self) -> usize {
+        self.checks.len()
+    }
+
+    unsafe fn push_deferred<F>(&mut self, check: F, state: SubtreeState)
+    where
+        F: FnOnce(&mut Validator<A, S, Self>) -> Result<(), E>,
+    {
+        let check: DeferredCheck<'_, A, S, E> = Box::new(check);
+        // SAFETY: The caller has guaranteed that `check` doesn't capture any
+        // borrows, so it can be stored until this queue is dropped.
+        let check = unsafe {
+            core::mem::transmute::<
+                DeferredCheck<'_, A, S, E>,
+                DeferredCheck<'static, A, S, E>,
+            >(check)
+        };
+        self.checks.push((check, state));
+    }
+
+    fn pop_deferred(
+        &mut self,
+        len: usize,
+    ) -> Option<(Self::Check, SubtreeState)> {
+        if self.checks.len() <= len {
+            return None;
+        }
+        self.checks.pop()
+    }
+}
+
+unsafe impl<A, S, D, E> ArchiveContext<E> for Validator<A, S, D>
 where
     A: ArchiveContext<E>,
+    D: DeferQueue<Self, E>,
 {
     fn check_subtree_ptr(
         &mut self,
@@ -55,9 +231,77 @@ where
         // which has the same safety requirements.
         unsafe { self.archive.pop_subtree_range(range) }
     }
+
+    unsafe fn defer_subtree<F>(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+        check: F,
+    ) -> Result<(), E>
+    where
+        F: FnOnce(&mut Self) -> Result<(), E>,
+    {
+        if self.deferred.can_defer() {
+            // SAFETY: The caller has guaranteed that `root` and `end` are
+            // located inside the current subtree range.
+            let state = unsafe { self.archive.defer_subtree_range(root, end)? };
+            if let Some(state) = state {
+                // SAFETY: We checked that the queue can defer checks, and the
+                // caller has guaranteed that `check` doesn't capture any
+                // borrows.
+                unsafe {
+                    self.deferred.push_deferred(check, state);
+                }
+                return Ok(());
+            }
+        }
+
+        // SAFETY: The caller has guaranteed that `root` and `end` are located
+        // inside the current subtree range.
+        unsafe { archive::enter_subtree(self, root, end, check) }
+    }
+
+    fn deferred_len(&self) -> usize {
+        self.deferred.deferred_len()
+    }
+
+    fn run_deferred(&mut self, len: usize) -> Result<(), E> {
+        while let Some((check, state)) = self.deferred.pop_deferred(len) {
+            // SAFETY: `state` was returned from `defer_subtree_range`, and
+            // deferred checks are only run once every subtree range pushed
+            // inside of the current one has been popped.
+            let previous = unsafe { self.archive.replace_subtree_state(state) };
+            check(self)?;
+            // SAFETY: `previous` was returned from `replace_subtree_state`, and
+            // `check` succeeded so it popped every subtree range it pushed.
+            unsafe {
+                self.archive.replace_subtree_state(previous);
+            }
+        }
+        Ok(())
+    }
+
+    unsafe fn defer_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Option<SubtreeState>, E> {
+        // SAFETY: This just forwards the call to the underlying `CoreValidator`
+        // which has the same safety requirements.
+        unsafe { self.archive.defer_subtree_range(root, end) }
+    }
+
+    unsafe fn replace_subtree_state(
+        &mut self,
+        state: SubtreeState,
+    ) -> SubtreeState {
+        // SAFETY: This just forwards the call to the underlying `CoreValidator`
+        // which has the same safety requirements.
+        unsafe { self.archive.replace_subtree_state(state) }
+    }
 }
 
-impl<A, S, E> SharedContext<E> for Validator<A, S>
+impl<A, S, D, E> SharedContext<E> for Validator<A, S, D>
 where
     S: SharedContext<E>,
 {
@@ -303,4 +547,192 @@ mod tests {
 
         access_pos::<ArchivedNode, Failure>(&*synthetic_buf, 0).unwrap_err();
     }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn validation_limits() {
+        use core::num::NonZeroUsize;
+
+        use crate::{
+            alloc::{boxed::Box, vec, vec::Vec},
+            api::high::{access_with_limits, to_bytes},
+            validation::archive::ValidationLimits,
+        };
+
+        let value = vec![Box::new(1u32), Box::new(2), Box::new(3)];
+        let bytes = to_bytes::<Failure>(&value).unwrap();
+        type Archived = crate::Archived<Vec<Box<u32>>>;
+
+        // One subtree each for the root, the vec, and every box
+        let limits = ValidationLimits {
+            max_depth: NonZeroUsize::new(3),
+            max_subtrees: Some(5),
+            max_bytes: Some(bytes.len()),
+        };
+        access_with_limits::<Archived, Failure>(&bytes, limits).unwrap();
+
+        let limits = ValidationLimits {
+            max_depth: NonZeroUsize::new(2),
+            ..ValidationLimits::UNLIMITED
+        };
+        access_with_limits::<Archived, Failure>(&bytes, limits)
+            .expect_err("expected exceeded depth error");
+
+        let limits = ValidationLimits {
+            max_subtrees: Some(4),
+            ..ValidationLimits::UNLIMITED
+        };
+        access_with_limits::<Archived, Failure>(&bytes, limits)
+            .expect_err("expected exceeded subtrees error");
+
+        let limits = ValidationLimits {
+            max_bytes: Some(8),
+            ..ValidationLimits::UNLIMITED
+        };
+        access_with_limits::<Archived, Failure>(&bytes, limits)
+            .expect_err("expected exceeded bytes error");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn deeply_nested_values() {
+        use std::thread;
+
+        use crate::{
+            alloc::{string::ToString, vec},
+            api::high::{access, to_bytes},
+            dynamic::{ArchivedValue, Value},
+        };
+
+        const DEPTH: usize = 10_000;
+
+        // Building, serializing, and dropping the value all recurse, so they
+        // need a large stack.
+        let mut bytes = thread::Builder::new()
+            .stack_size(64 << 20)
+            .spawn(|| {
+                let mut value = Value::Bool(true);
+                for i in 0..DEPTH {
+                    value = if i % 2 == 0 {
+                        Value::Array(vec![value])
+                    } else {
+                        Value::Object(
+                            [("a".to_string(), value)].into_iter().collect(),
+                        )
+                    };
+                }
+                to_bytes::<Failure>(&value).unwrap()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        // Validation should not need a large stack.
+        let pos = thread::Builder::new()
+            .stack_size(256 << 10)
+            .spawn({
+                let bytes = bytes.clone();
+                move || {
+                    let mut value =
+                        access::<ArchivedValue, Failure>(&bytes).unwrap();
+                    loop {
+                        value = match value {
+                            ArchivedValue::Array(array) => &array[0],
+                            ArchivedValue::Object(object) => {
+                                object.get("a").unwrap()
+                            }
+                            _ => break,
+                        };
+                    }
+                    let ArchivedValue::Bool(b) = value else {
+                        panic!("expected the innermost value to be a bool");
+                    };
+                    b as *const bool as usize - bytes.as_ptr() as usize
+                }
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        // Errors in deferred checks are still reported.
+        bytes[pos] = 2;
+        thread::Builder::new()
+            .stack_size(256 << 10)
+            .spawn(move || {
+                access::<ArchivedValue, Failure>(&bytes)
+                    .expect_err("expected invalid bool error");
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn deeply_nested_boxes() {
+        use std::thread;
+
+        use crate::{
+            alloc::boxed::Box,
+            api::high::{access, to_bytes},
+            rancor::Source,
+            ser::{Allocator, Writer},
+            Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(
+            crate,
+            serialize_bounds(__S: Writer + Allocator, __S::Error: Source),
+            bytecheck(bounds(
+                __C: crate::validation::ArchiveContext,
+                __C::Error: Source,
+            )),
+        )]
+        struct Node {
+            value: u32,
+            #[rkyv(omit_bounds)]
+            next: Option<Box<Node>>,
+        }
+
+        const DEPTH: usize = 100_000;
+
+        // Building, serializing, and dropping the list all recurse, so they
+        // need a large stack.
+        let bytes = thread::Builder::new()
+            .stack_size(256 << 20)
+            .spawn(|| {
+                let mut node = Node {
+                    value: 0,
+                    next: None,
+                };
+                for i in 1..DEPTH {
+                    node = Node {
+                        value: i as u32,
+                        next: Some(Box::new(node)),
+                    };
+                }
+                to_bytes::<Failure>(&node).unwrap()
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+
+        // Validation should not need a large stack.
+        thread::Builder::new()
+            .stack_size(256 << 10)
+            .spawn(move || {
+                let mut node = access::<ArchivedNode, Failure>(&bytes).unwrap();
+                let mut len = 1;
+                while let Some(next) = node.next.as_ref() {
+                    node = next;
+                    len += 1;
+                }
+                assert_eq!(len, DEPTH);
+                assert_eq!(node.value, 0);
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
 }
@@ -0,0 +1,136 @@
+use core::{any::TypeId, error::Error, fmt};
+
+use rancor::{fail, Source};
+
+use crate::{
+    util::InlineVec,
+    validation::{shared::ValidationState, SharedContext},
+};
+
+/// A validator that can verify a bounded number of shared pointers without
+/// allocating.
+///
+/// Up to `N` distinct shared pointers can be validated. Validating more than
+/// that fails with an error, so this is suitable for validating untrusted
+/// data in environments without an allocator. Looking up a shared pointer
+/// takes time proportional to `N`, so `N` should be kept small.
+#[derive(Debug, Default)]
+pub struct BoundedSharedValidator<const N: usize> {
+    shared: InlineVec<(usize, TypeId, bool), N>,
+}
+
+impl<const N: usize> BoundedSharedValidator<N> {
+    /// Creates a new bounded shared pointer validator.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn find(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<Option<&mut bool>, TypeMismatch> {
+        let Some((_, previous, finished)) =
+            self.shared.iter_mut().find(|(a, ..)| *a == address)
+        else {
+            return Ok(None);
+        };
+        if *previous != type_id {
+            Err(TypeMismatch)
+        } else {
+            Ok(Some(finished))
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TypeMismatch;
+
+impl fmt::Display for TypeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the same memory region has been claimed as two different types"
+        )
+    }
+}
+
+impl Error for TypeMismatch {}
+
+#[derive(Debug)]
+struct ExceededMaximumSharedPointers {
+    max: usize,
+}
+
+impl fmt::Display for ExceededMaximumSharedPointers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "exceeded the maximum number of shared pointers ({}) while \
+             validating",
+            self.max,
+        )
+    }
+}
+
+impl Error for ExceededMaximumSharedPointers {}
+
+#[derive(Debug)]
+struct NotStarted;
+
+impl fmt::Display for NotStarted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shared pointer was not started validation")
+    }
+}
+
+impl Error for NotStarted {}
+
+#[derive(Debug)]
+struct AlreadyFinished;
+
+impl fmt::Display for AlreadyFinished {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "shared pointer was already finished validation")
+    }
+}
+
+impl Error for AlreadyFinished {}
+
+impl<E: Source, const N: usize> SharedContext<E> for BoundedSharedValidator<N> {
+    fn start_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<ValidationState, E> {
+        match self.find(address, type_id) {
+            Err(error) => fail!(error),
+            Ok(Some(false)) => Ok(ValidationState::Pending),
+            Ok(Some(true)) => Ok(ValidationState::Finished),
+            Ok(None) => {
+                if self.shared.len() == N {
+                    fail!(ExceededMaximumSharedPointers { max: N });
+                }
+                self.shared.push((address, type_id, false));
+                Ok(ValidationState::Started)
+            }
+        }
+    }
+
+    fn finish_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<(), E> {
+        match self.find(address, type_id) {
+            Err(error) => fail!(error),
+            Ok(None) => fail!(NotStarted),
+            Ok(Some(true)) => fail!(AlreadyFinished),
+            Ok(Some(finished)) => {
+                *finished = true;
+                Ok(())
+            }
+        }
+    }
+}
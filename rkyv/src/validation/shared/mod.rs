@@ -1,5 +1,6 @@
 //! Shared pointer validation.
 
+mod bounded;
 #[cfg(feature = "alloc")]
 mod validator;
 
@@ -7,6 +8,7 @@ use core::any::TypeId;
 
 use rancor::{Fallible, Strategy};
 
+pub use self::bounded::*;
 #[cfg(feature = "alloc")]
 pub use self::validator::*;
 
@@ -49,7 +51,7 @@ pub trait SharedContext<E = <Self as Fallible>::Error> {
 
 impl<T, E> SharedContext<E> for Strategy<T, E>
 where
-    T: SharedContext<E>,
+    T: SharedContext<E> + ?Sized,
 {
     fn start_shared(
         &mut self,
@@ -0,0 +1,277 @@
+//! Variable-length integer encoding.
+//!
+//! Integers archived with [`Varint`](crate::with::Varint) are encoded as
+//! LEB128: seven bits of the value are stored in each byte starting from the
+//! least significant bits, and the high bit of each byte is set if more bytes
+//! follow. Values below 128 take up a single byte.
+//!
+//! The encoded bytes are written out-of-line, and the archived field is an
+//! [`ArchivedVarint`] which holds a relative pointer to them. This shrinks
+//! fields whose values are usually small but must be able to hold large values.
+
+use core::{cmp, fmt, marker::PhantomData};
+
+use munge::munge;
+use rancor::Fallible;
+
+use crate::{primitive::FixedUsize, ser::Writer, Place, Portable, RelPtr};
+
+/// The maximum number of bytes in an encoded `u64`.
+pub const MAX_LEN: usize = 10;
+
+/// An integer type which can be encoded as a varint.
+pub trait VarintValue: Copy {
+    /// The maximum number of bytes in an encoded value of this type.
+    const MAX_LEN: usize;
+
+    /// Returns the value as a `u64`.
+    fn to_u64(self) -> u64;
+
+    /// Returns the value for the given `u64`, or `None` if it is out of range.
+    fn from_u64(value: u64) -> Option<Self>;
+}
+
+macro_rules! impl_varint_value {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl VarintValue for $ty {
+                const MAX_LEN: usize = (<$ty>::BITS as usize).div_ceil(7);
+
+                #[inline]
+                fn to_u64(self) -> u64 {
+                    self as u64
+                }
+
+                #[inline]
+                fn from_u64(value: u64) -> Option<Self> {
+                    <$ty>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_varint_value!(u32, u64, usize);
+
+/// Encodes `value` into `out` and returns the number of bytes written.
+#[inline]
+pub fn encode(mut value: u64, out: &mut [u8; MAX_LEN]) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Decodes a varint from the start of `bytes` and returns the value along with
+/// the number of bytes read.
+///
+/// Returns `None` if `bytes` ends before the varint does, or if the varint
+/// encodes a value larger than `u64::MAX`.
+#[inline]
+pub fn decode(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        let bits = (byte & 0x7f) as u64;
+        let shift = 7 * i as u32;
+        if i == MAX_LEN - 1 && bits > 1 {
+            return None;
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// An archived integer encoded as a varint.
+///
+/// The encoded bytes are stored out-of-line and decoded on each call to
+/// [`get`](ArchivedVarint::get).
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[repr(C)]
+pub struct ArchivedVarint<T> {
+    ptr: RelPtr<u8>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: VarintValue> ArchivedVarint<T> {
+    /// Returns the value of the archived varint.
+    #[inline]
+    pub fn get(&self) -> T {
+        let ptr = unsafe { self.ptr.as_ptr() };
+        let mut bytes = [0u8; MAX_LEN];
+        let mut len = 0;
+        while len < T::MAX_LEN {
+            // SAFETY: Validation (or the caller of an unchecked access) has
+            // ensured that the encoded bytes are located within the archive.
+            bytes[len] = unsafe { ptr.add(len).read() };
+            len += 1;
+            if bytes[len - 1] & 0x80 == 0 {
+                break;
+            }
+        }
+        decode(&bytes[..len])
+            .and_then(|(value, _)| T::from_u64(value))
+            .expect("archived varint was malformed")
+    }
+
+    /// Serializes the encoded bytes of a varint.
+    pub fn serialize_from_value<S>(
+        value: T,
+        serializer: &mut S,
+    ) -> Result<VarintResolver, S::Error>
+    where
+        S: Fallible + Writer + ?Sized,
+    {
+        let mut bytes = [0u8; MAX_LEN];
+        let len = encode(value.to_u64(), &mut bytes);
+        let pos = serializer.pos();
+        serializer.write(&bytes[..len])?;
+        Ok(VarintResolver {
+            pos: pos as FixedUsize,
+        })
+    }
+
+    /// Resolves an archived varint from a [`VarintResolver`].
+    pub fn resolve_from_resolver(resolver: VarintResolver, out: Place<Self>) {
+        munge!(let ArchivedVarint { ptr, _phantom: _ } = out);
+        RelPtr::emplace(resolver.pos as usize, ptr);
+    }
+}
+
+impl<T: VarintValue + fmt::Debug> fmt::Debug for ArchivedVarint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: VarintValue + fmt::Display> fmt::Display for ArchivedVarint<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
+
+impl<T: VarintValue + PartialEq> PartialEq for ArchivedVarint<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: VarintValue + Eq> Eq for ArchivedVarint<T> {}
+
+impl<T: VarintValue + PartialOrd> PartialOrd for ArchivedVarint<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.get().partial_cmp(&other.get())
+    }
+}
+
+impl<T: VarintValue + Ord> Ord for ArchivedVarint<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.get().cmp(&other.get())
+    }
+}
+
+impl<T: VarintValue + PartialEq> PartialEq<T> for ArchivedVarint<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.get() == *other
+    }
+}
+
+/// The resolver for [`ArchivedVarint`].
+pub struct VarintResolver {
+    pos: FixedUsize,
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, error::Error, fmt};
+
+    use bytecheck::{
+        rancor::{fail, Fallible, Source},
+        Verify,
+    };
+
+    use super::{decode, ArchivedVarint, VarintValue, MAX_LEN};
+    use crate::validation::{ArchiveContext, ArchiveContextExt};
+
+    #[derive(Debug)]
+    struct InvalidVarint;
+
+    impl fmt::Display for InvalidVarint {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "varint was malformed or out of range for its type")
+        }
+    }
+
+    impl Error for InvalidVarint {}
+
+    unsafe impl<T, C> Verify<C> for ArchivedVarint<T>
+    where
+        T: VarintValue,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let ptr = self.ptr.as_ptr_wrapping();
+            let mut bytes = [0u8; MAX_LEN];
+            let mut len = 0;
+            while len < T::MAX_LEN {
+                let byte_ptr = ptr.wrapping_add(len);
+                context.check_subtree_ptr(byte_ptr, &Layout::new::<u8>())?;
+                // SAFETY: We just checked that `byte_ptr` is located within
+                // the subtree range.
+                bytes[len] = unsafe { byte_ptr.read() };
+                len += 1;
+                if bytes[len - 1] & 0x80 == 0 {
+                    break;
+                }
+            }
+
+            match decode(&bytes[..len]) {
+                Some((value, _)) if T::from_u64(value).is_some() => (),
+                _ => fail!(InvalidVarint),
+            }
+
+            let layout = Layout::array::<u8>(len).unwrap();
+            context.in_subtree_raw(ptr, layout, |_| Ok(()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, encode, MAX_LEN};
+
+    #[test]
+    fn encode_decode() {
+        let mut bytes = [0u8; MAX_LEN];
+        for (value, len) in [
+            (0, 1),
+            (127, 1),
+            (128, 2),
+            (300, 2),
+            (u32::MAX as u64, 5),
+            (u64::MAX, 10),
+        ] {
+            assert_eq!(encode(value, &mut bytes), len);
+            assert_eq!(decode(&bytes), Some((value, len)));
+        }
+
+        assert_eq!(decode(&[0x80, 0x80]), None);
+        assert_eq!(decode(&[0xff; MAX_LEN]), None);
+    }
+}
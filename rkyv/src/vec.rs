@@ -2,19 +2,27 @@
 
 use core::{
     borrow::Borrow,
-    cmp, fmt, hash,
+    cmp,
+    error::Error,
+    fmt, hash,
+    mem::size_of,
     ops::{Deref, Index},
     slice::SliceIndex,
 };
 
 use munge::munge;
-use rancor::Fallible;
+use rancor::{Fallible, Panic, ResultExt as _, Source};
 
 use crate::{
-    primitive::{ArchivedUsize, FixedUsize},
+    native::{self, NativeRepr},
+    primitive::{
+        ArchivedI64, ArchivedIsize, ArchivedU64, ArchivedUsize, FixedUsize,
+    },
+    rel_ptr::{Offset, RelPtr},
     seal::Seal,
     ser::{Allocator, Writer, WriterExt as _},
-    Archive, Place, Portable, RelPtr, Serialize, SerializeUnsized,
+    traits::NoUndef,
+    Archive, Place, Portable, Serialize, SerializeUnsized,
 };
 
 /// An archived [`Vec`].
@@ -22,6 +30,11 @@ use crate::{
 /// This uses a [`RelPtr`] to a `[T]` under the hood. Unlike
 /// [`ArchivedString`](crate::string::ArchivedString), it does not have an
 /// inline representation.
+///
+/// The offset type `O` of the relative pointer and the length type `L` default
+/// to the ones selected by the `pointer_width_*` features. A vec that is too
+/// large for that width can use a wider offset and length instead, see
+/// [`ArchivedWideVec`].
 #[derive(Portable)]
 #[cfg_attr(
     feature = "bytecheck",
@@ -30,20 +43,34 @@ use crate::{
 )]
 #[rkyv(crate)]
 #[repr(C)]
-pub struct ArchivedVec<T> {
-    ptr: RelPtr<T>,
-    len: ArchivedUsize,
+pub struct ArchivedVec<T, O = ArchivedIsize, L = ArchivedUsize> {
+    ptr: RelPtr<T, O>,
+    len: L,
 }
 
-impl<T> ArchivedVec<T> {
+/// An archived [`Vec`] which uses a 64-bit relative pointer and length
+/// regardless of the configured pointer width.
+///
+/// This lets a single large collection be archived without making the pointers
+/// of the rest of the archive wider. See [`Wide`](crate::with::Wide).
+pub type ArchivedWideVec<T> = ArchivedVec<T, ArchivedI64, ArchivedU64>;
+
+impl<T, O: Offset, L: Offset> ArchivedVec<T, O, L> {
     /// Returns a pointer to the first element of the archived vec.
     pub fn as_ptr(&self) -> *const T {
         unsafe { self.ptr.as_ptr() }
     }
 
+    /// Returns a pointer to the first element of the archived vec, calculated
+    /// using wrapping methods.
+    #[cfg(all(feature = "alloc", feature = "bytecheck"))]
+    pub(crate) fn as_ptr_wrapping(&self) -> *const T {
+        self.ptr.as_ptr_wrapping()
+    }
+
     /// Returns the number of elements in the archived vec.
     pub fn len(&self) -> usize {
-        self.len.to_native() as usize
+        self.len.to_isize() as usize
     }
 
     /// Returns whether the archived vec is empty.
@@ -76,16 +103,91 @@ impl<T> ArchivedVec<T> {
     }
 
     /// Resolves an archived `Vec` from a given length.
+    ///
+    /// # Panics
+    ///
+    /// - If the offset to the elements exceeds the offset storage
+    /// - If `len` exceeds the length storage
     pub fn resolve_from_len(
         len: usize,
         resolver: VecResolver,
         out: Place<Self>,
     ) {
+        let pos = usize::try_from(resolver.pos)
+            .into_error::<Panic>()
+            .always_ok();
+        Self::resolve_from_pos(len, pos, out);
+    }
+
+    /// Resolves an archived `Vec` from a given length and a position in the
+    /// output buffer where its elements are stored.
+    ///
+    /// # Panics
+    ///
+    /// - If the offset to the elements exceeds the offset storage
+    /// - If `len` exceeds the length storage
+    fn resolve_from_pos(len: usize, pos: usize, out: Place<Self>) {
         munge!(let ArchivedVec { ptr, len: out_len } = out);
-        RelPtr::emplace(resolver.pos as usize, ptr);
-        usize::resolve(&len, (), out_len);
+        RelPtr::emplace(pos, ptr);
+        let len = isize::try_from(len).into_error::<Panic>().always_ok();
+        out_len.write(L::from_isize::<Panic>(len).always_ok());
+    }
+}
+
+impl<T> ArchivedWideVec<T> {
+    /// Resolves an archived wide `Vec` from a given slice.
+    pub fn resolve_wide_from_slice<U: Archive<Archived = T>>(
+        slice: &[U],
+        resolver: WideVecResolver,
+        out: Place<Self>,
+    ) {
+        Self::resolve_wide_from_len(slice.len(), resolver, out);
+    }
+
+    /// Resolves an archived wide `Vec` from a given length.
+    ///
+    /// # Panics
+    ///
+    /// - If the position of the elements does not fit in a `usize`
+    /// - If the offset to the elements does not fit in an `isize`
+    pub fn resolve_wide_from_len(
+        len: usize,
+        resolver: WideVecResolver,
+        out: Place<Self>,
+    ) {
+        let pos = usize::try_from(resolver.pos)
+            .into_error::<Panic>()
+            .always_ok();
+        Self::resolve_from_pos(len, pos, out);
+    }
+
+    /// Serializes an archived wide `Vec` from a given slice.
+    pub fn serialize_wide_from_slice<
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    >(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<WideVecResolver, S::Error> {
+        Ok(WideVecResolver::from_pos(
+            slice.serialize_unsized(serializer)?,
+        ))
+    }
+}
+
+impl<T: NativeRepr, O: Offset, L: Offset> ArchivedVec<T, O, L> {
+    /// Returns the archived vec as a slice of native primitives, or `None` if
+    /// the archived primitives don't share a representation with them.
+    ///
+    /// See [`as_native_slice`](crate::native::as_native_slice) for more
+    /// information.
+    #[inline]
+    pub fn as_native_slice(&self) -> Option<&[T::Native]> {
+        native::as_native_slice(self.as_slice())
     }
+}
 
+impl<T> ArchivedVec<T> {
     /// Serializes an archived `Vec` from a given slice.
     pub fn serialize_from_slice<
         U: Serialize<S, Archived = T>,
@@ -94,9 +196,7 @@ impl<T> ArchivedVec<T> {
         slice: &[U],
         serializer: &mut S,
     ) -> Result<VecResolver, S::Error> {
-        Ok(VecResolver {
-            pos: slice.serialize_unsized(serializer)? as FixedUsize,
-        })
+        Ok(VecResolver::from_pos(slice.serialize_unsized(serializer)?))
     }
 
     /// Serializes an archived `Vec` from a given iterator.
@@ -132,9 +232,7 @@ impl<T> ArchivedVec<T> {
                     }
                 }
 
-                Ok(VecResolver {
-                    pos: pos as FixedUsize,
-                })
+                Ok(VecResolver::from_pos(pos))
             },
         )?
     }
@@ -165,32 +263,30 @@ impl<T> ArchivedVec<T> {
                 serializer.resolve_aligned(value.borrow(), resolver)?;
             }
 
-            Ok(VecResolver {
-                pos: pos as FixedUsize,
-            })
+            Ok(VecResolver::from_pos(pos))
         }
     }
 }
 
-impl<T> AsRef<[T]> for ArchivedVec<T> {
+impl<T, O: Offset, L: Offset> AsRef<[T]> for ArchivedVec<T, O, L> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T> Borrow<[T]> for ArchivedVec<T> {
+impl<T, O: Offset, L: Offset> Borrow<[T]> for ArchivedVec<T, O, L> {
     fn borrow(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T: fmt::Debug> fmt::Debug for ArchivedVec<T> {
+impl<T: fmt::Debug, O: Offset, L: Offset> fmt::Debug for ArchivedVec<T, O, L> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.as_slice()).finish()
     }
 }
 
-impl<T> Deref for ArchivedVec<T> {
+impl<T, O: Offset, L: Offset> Deref for ArchivedVec<T, O, L> {
     type Target = [T];
 
     fn deref(&self) -> &Self::Target {
@@ -198,15 +294,17 @@ impl<T> Deref for ArchivedVec<T> {
     }
 }
 
-impl<T: Eq> Eq for ArchivedVec<T> {}
+impl<T: Eq, O: Offset, L: Offset> Eq for ArchivedVec<T, O, L> {}
 
-impl<T: hash::Hash> hash::Hash for ArchivedVec<T> {
+impl<T: hash::Hash, O: Offset, L: Offset> hash::Hash for ArchivedVec<T, O, L> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.as_slice().hash(state)
     }
 }
 
-impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
+impl<T, O: Offset, L: Offset, I: SliceIndex<[T]>> Index<I>
+    for ArchivedVec<T, O, L>
+{
     type Output = <[T] as Index<I>>::Output;
 
     fn index(&self, index: I) -> &Self::Output {
@@ -214,56 +312,94 @@ impl<T, I: SliceIndex<[T]>> Index<I> for ArchivedVec<T> {
     }
 }
 
-impl<T: Ord> Ord for ArchivedVec<T> {
+impl<T: Ord, O: Offset, L: Offset> Ord for ArchivedVec<T, O, L> {
     fn cmp(&self, other: &Self) -> cmp::Ordering {
         self.as_slice().cmp(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for ArchivedVec<T> {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T, U, O, L, P, M> PartialEq<ArchivedVec<U, P, M>> for ArchivedVec<T, O, L>
+where
+    T: PartialEq<U>,
+    O: Offset,
+    L: Offset,
+    P: Offset,
+    M: Offset,
+{
+    fn eq(&self, other: &ArchivedVec<U, P, M>) -> bool {
         self.as_slice().eq(other.as_slice())
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for ArchivedVec<T> {
+impl<T, U, O, L, const N: usize> PartialEq<[U; N]> for ArchivedVec<T, O, L>
+where
+    T: PartialEq<U>,
+    O: Offset,
+    L: Offset,
+{
     fn eq(&self, other: &[U; N]) -> bool {
         self.as_slice().eq(&other[..])
     }
 }
 
-impl<T: PartialEq<U>, U, const N: usize> PartialEq<ArchivedVec<T>> for [U; N] {
-    fn eq(&self, other: &ArchivedVec<T>) -> bool {
+impl<T, U, O, L, const N: usize> PartialEq<ArchivedVec<T, O, L>> for [U; N]
+where
+    T: PartialEq<U>,
+    O: Offset,
+    L: Offset,
+{
+    fn eq(&self, other: &ArchivedVec<T, O, L>) -> bool {
         other.eq(self)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedVec<T> {
+impl<T: PartialEq<U>, U, O: Offset, L: Offset> PartialEq<[U]>
+    for ArchivedVec<T, O, L>
+{
     fn eq(&self, other: &[U]) -> bool {
         self.as_slice().eq(other)
     }
 }
 
-impl<T: PartialEq<U>, U> PartialEq<ArchivedVec<U>> for [T] {
-    fn eq(&self, other: &ArchivedVec<U>) -> bool {
+impl<T: PartialEq<U>, U, O: Offset, L: Offset> PartialEq<ArchivedVec<U, O, L>>
+    for [T]
+{
+    fn eq(&self, other: &ArchivedVec<U, O, L>) -> bool {
         self.eq(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for ArchivedVec<T> {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T, O, L, P, M> PartialOrd<ArchivedVec<T, P, M>> for ArchivedVec<T, O, L>
+where
+    T: PartialOrd,
+    O: Offset,
+    L: Offset,
+    P: Offset,
+    M: Offset,
+{
+    fn partial_cmp(
+        &self,
+        other: &ArchivedVec<T, P, M>,
+    ) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other.as_slice())
     }
 }
 
-impl<T: PartialOrd> PartialOrd<[T]> for ArchivedVec<T> {
+impl<T: PartialOrd, O: Offset, L: Offset> PartialOrd<[T]>
+    for ArchivedVec<T, O, L>
+{
     fn partial_cmp(&self, other: &[T]) -> Option<cmp::Ordering> {
         self.as_slice().partial_cmp(other)
     }
 }
 
-impl<T: PartialOrd> PartialOrd<ArchivedVec<T>> for [T] {
-    fn partial_cmp(&self, other: &ArchivedVec<T>) -> Option<cmp::Ordering> {
+impl<T: PartialOrd, O: Offset, L: Offset> PartialOrd<ArchivedVec<T, O, L>>
+    for [T]
+{
+    fn partial_cmp(
+        &self,
+        other: &ArchivedVec<T, O, L>,
+    ) -> Option<cmp::Ordering> {
         self.partial_cmp(other.as_slice())
     }
 }
@@ -276,40 +412,475 @@ pub struct VecResolver {
 impl VecResolver {
     /// Creates a new `VecResolver` from a position in the output buffer where
     /// the elements of the archived vector are stored.
+    ///
+    /// # Panics
+    ///
+    /// If `pos` does not fit in the configured pointer width. Use a
+    /// [`WideVecResolver`] to archive vecs located past that point.
     pub fn from_pos(pos: usize) -> Self {
         Self {
-            pos: pos as FixedUsize,
+            pos: FixedUsize::try_from(pos).into_error::<Panic>().always_ok(),
+        }
+    }
+}
+
+/// The resolver for [`ArchivedWideVec`].
+///
+/// Unlike [`VecResolver`], this always stores a 64-bit position so that the
+/// elements may be located anywhere in the output buffer.
+pub struct WideVecResolver {
+    pos: u64,
+}
+
+impl WideVecResolver {
+    /// Creates a new `WideVecResolver` from a position in the output buffer
+    /// where the elements of the archived vector are stored.
+    pub fn from_pos(pos: usize) -> Self {
+        Self {
+            pos: u64::try_from(pos).into_error::<Panic>().always_ok(),
+        }
+    }
+}
+
+/// An archived [`Vec`] with reserved space for more elements.
+///
+/// The capacity is recorded alongside the length, so elements can be appended
+/// to a sealed archived vec in place with
+/// [`push_within_capacity_seal`](Self::push_within_capacity_seal). This is
+/// useful for append-mostly archives, which would otherwise have to be
+/// reserialized for each new element.
+///
+/// It has the same layout as an [`ArchivedVec`] followed by the capacity, and
+/// dereferences to one.
+#[derive(Portable)]
+#[cfg_attr(
+    feature = "bytecheck",
+    derive(bytecheck::CheckBytes),
+    bytecheck(verify)
+)]
+#[rkyv(crate)]
+#[repr(C)]
+pub struct ArchivedVecWithCapacity<T> {
+    ptr: RelPtr<T, ArchivedIsize>,
+    len: ArchivedUsize,
+    cap: ArchivedUsize,
+}
+
+impl<T> ArchivedVecWithCapacity<T> {
+    /// Returns the number of elements the archived vec can hold.
+    pub fn capacity(&self) -> usize {
+        self.cap.to_native() as usize
+    }
+
+    /// Returns the archived vec as an [`ArchivedVec`].
+    pub fn as_vec(&self) -> &ArchivedVec<T> {
+        // SAFETY: `ArchivedVecWithCapacity` is `repr(C)` and starts with the
+        // same fields as `ArchivedVec<T>`. The relative pointer is at the same
+        // address, so it points to the same elements.
+        unsafe { &*(self as *const Self).cast::<ArchivedVec<T>>() }
+    }
+
+    /// Appends an element to the back of a sealed archived vec if it has
+    /// fewer elements than its capacity.
+    ///
+    /// Returns the element back if the archived vec is already full.
+    pub fn push_within_capacity_seal(
+        this: Seal<'_, Self>,
+        value: T,
+    ) -> Result<(), T>
+    where
+        T: NoUndef,
+    {
+        let len = this.len();
+        if len >= this.capacity() {
+            return Err(value);
+        }
+
+        munge!(let Self { ptr, len: mut out_len, .. } = this);
+        // SAFETY: Space for `capacity` elements was reserved after the start
+        // of the vec when it was serialized, and `len` is less than
+        // `capacity`.
+        unsafe {
+            RelPtr::as_mut_ptr(ptr).add(len).write(value);
+        }
+        *out_len = ArchivedUsize::from_native((len + 1) as FixedUsize);
+
+        Ok(())
+    }
+
+    /// Resolves an archived `Vec` with capacity from a given slice.
+    pub fn resolve_from_slice<U: Archive<Archived = T>>(
+        slice: &[U],
+        resolver: VecWithCapacityResolver,
+        out: Place<Self>,
+    ) {
+        Self::resolve_from_len(slice.len(), resolver, out);
+    }
+
+    /// Resolves an archived `Vec` with capacity from a given length.
+    ///
+    /// # Panics
+    ///
+    /// - If the offset to the elements exceeds the offset storage
+    /// - If `len` is greater than the capacity of the resolver
+    pub fn resolve_from_len(
+        len: usize,
+        resolver: VecWithCapacityResolver,
+        out: Place<Self>,
+    ) {
+        let capacity = resolver.cap as usize;
+        assert!(
+            len <= capacity,
+            "length {len} exceeds reserved capacity {capacity}"
+        );
+
+        // SAFETY: `ArchivedVecWithCapacity` is `repr(C)` and starts with the
+        // same fields as `ArchivedVec<T>`.
+        let vec = unsafe { out.cast_unchecked::<ArchivedVec<T>>() };
+        ArchivedVec::resolve_from_len(len, resolver.vec, vec);
+        munge!(let ArchivedVecWithCapacity { cap, .. } = out);
+        usize::resolve(&capacity, (), cap);
+    }
+
+    /// Serializes an archived `Vec` from a given slice and reserves space for
+    /// `extra` more elements after it.
+    ///
+    /// The reserved elements are zeroed and are not part of the archived vec
+    /// until they're added with
+    /// [`push_within_capacity_seal`](Self::push_within_capacity_seal).
+    pub fn serialize_from_slice<
+        U: Serialize<S, Archived = T>,
+        S: Fallible + Allocator + Writer + ?Sized,
+    >(
+        slice: &[U],
+        extra: usize,
+        serializer: &mut S,
+    ) -> Result<VecWithCapacityResolver, S::Error>
+    where
+        S::Error: Source,
+    {
+        #[derive(Debug)]
+        struct CapacityOverflow {
+            len: usize,
+            extra: usize,
+        }
+
+        impl fmt::Display for CapacityOverflow {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "reserving {} more elements after {} elements exceeds the \
+                     maximum capacity of an archived vec",
+                    self.extra, self.len,
+                )
+            }
+        }
+
+        impl Error for CapacityOverflow {}
+
+        const ZEROS: [u8; 64] = [0; 64];
+
+        let len = slice.len();
+        let overflow = || CapacityOverflow { len, extra };
+        let capacity =
+            len.checked_add(extra).ok_or_else(overflow).into_error()?;
+        let cap = FixedUsize::try_from(capacity)
+            .map_err(|_| overflow())
+            .into_error()?;
+        let mut remaining = extra
+            .checked_mul(size_of::<T>())
+            .ok_or_else(overflow)
+            .into_error()?;
+
+        let vec = ArchivedVec::<T>::serialize_from_slice(slice, serializer)?;
+        while remaining > 0 {
+            let len = remaining.min(ZEROS.len());
+            serializer.write(&ZEROS[..len])?;
+            remaining -= len;
         }
+
+        Ok(VecWithCapacityResolver { vec, cap })
+    }
+}
+
+impl<T> Deref for ArchivedVecWithCapacity<T> {
+    type Target = ArchivedVec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_vec()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedVecWithCapacity<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
     }
 }
 
+/// The resolver for [`ArchivedVecWithCapacity`].
+pub struct VecWithCapacityResolver {
+    vec: VecResolver,
+    cap: FixedUsize,
+}
+
 #[cfg(feature = "bytecheck")]
 mod verify {
+    use core::{alloc::Layout, error::Error, fmt};
+
     use bytecheck::{
-        rancor::{Fallible, Source},
+        rancor::{fail, Fallible, ResultExt as _, Source},
         CheckBytes, Verify,
     };
 
     use crate::{
-        validation::{ArchiveContext, ArchiveContextExt},
-        vec::ArchivedVec,
+        rel_ptr::Offset,
+        validation::{
+            error::ElementCheckContext, ArchiveContext, ArchiveContextExt,
+        },
+        vec::{ArchivedVec, ArchivedVecWithCapacity},
     };
 
-    unsafe impl<T, C> Verify<C> for ArchivedVec<T>
+    unsafe impl<T, O, L, C> Verify<C> for ArchivedVec<T, O, L>
     where
         T: CheckBytes<C>,
+        O: Offset,
+        L: Offset,
         C: Fallible + ArchiveContext + ?Sized,
         C::Error: Source,
     {
         fn verify(&self, context: &mut C) -> Result<(), C::Error> {
             let ptr = core::ptr::slice_from_raw_parts(
                 self.ptr.as_ptr_wrapping(),
-                self.len.to_native() as usize,
+                self.len(),
             );
 
-            context.in_subtree(ptr, |context| unsafe {
-                <[T]>::check_bytes(ptr, context)
-            })
+            // SAFETY: `check_elements` is safe to call on a pointer which is
+            // properly aligned and points to enough bytes for the slice.
+            unsafe { context.in_subtree_deferred(ptr, check_elements) }
+        }
+    }
+
+    #[derive(Debug)]
+    struct LengthExceedsCapacity {
+        len: usize,
+        capacity: usize,
+    }
+
+    impl fmt::Display for LengthExceedsCapacity {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(
+                f,
+                "archived vec length {} exceeds its capacity {}",
+                self.len, self.capacity,
+            )
         }
     }
+
+    impl Error for LengthExceedsCapacity {}
+
+    unsafe impl<T, C> Verify<C> for ArchivedVecWithCapacity<T>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ArchiveContext + ?Sized,
+        C::Error: Source,
+    {
+        fn verify(&self, context: &mut C) -> Result<(), C::Error> {
+            let len = self.len();
+            let capacity = self.capacity();
+            if len > capacity {
+                fail!(LengthExceedsCapacity { len, capacity });
+            }
+
+            // The reserved elements are claimed along with the initialized
+            // ones so that pushing can't write outside of the subtree.
+            let reserved = Layout::array::<T>(capacity).into_error()?;
+            let start = self.ptr.as_ptr_wrapping();
+            let ptr = core::ptr::slice_from_raw_parts(start, len);
+
+            // SAFETY: The range checked for `reserved` covers `ptr` since `len`
+            // is no more than `capacity`, so `check_elements` is safe to call
+            // once it is checked.
+            unsafe {
+                context.in_subtree_raw_deferred(
+                    start.cast(),
+                    reserved,
+                    ptr,
+                    check_elements,
+                )
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be properly aligned and point to enough bytes for a `[T]`.
+    unsafe fn check_elements<T, C>(
+        ptr: *const [T],
+        context: &mut C,
+    ) -> Result<(), C::Error>
+    where
+        T: CheckBytes<C>,
+        C: Fallible + ?Sized,
+        C::Error: Source,
+    {
+        let items = ptr.cast::<T>();
+        for index in 0..ptr.len() {
+            let item = items.wrapping_add(index);
+            // SAFETY: The caller has guaranteed that `ptr` is properly aligned
+            // and points to enough bytes for all of its elements.
+            unsafe { T::check_bytes(item, context) }.trace(
+                ElementCheckContext {
+                    index,
+                    address: item as usize,
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc", feature = "bytecheck"))]
+mod tests {
+    use core::mem::size_of;
+
+    use rancor::{Error, Fallible, Panic, Source};
+
+    use crate::{
+        access, access_mut,
+        alloc::vec::Vec,
+        primitive::{ArchivedU32, ArchivedUsize, FixedUsize},
+        ser::{Allocator, Writer},
+        to_bytes,
+        vec::{ArchivedVecWithCapacity, VecWithCapacityResolver},
+        Archive, Place, Serialize,
+    };
+
+    struct Log(Vec<u32>, usize);
+
+    impl Archive for Log {
+        type Archived = ArchivedVecWithCapacity<ArchivedU32>;
+        type Resolver = VecWithCapacityResolver;
+
+        fn resolve(
+            &self,
+            resolver: Self::Resolver,
+            out: Place<Self::Archived>,
+        ) {
+            ArchivedVecWithCapacity::resolve_from_slice(&self.0, resolver, out);
+        }
+    }
+
+    impl<S> Serialize<S> for Log
+    where
+        S: Fallible + Allocator + Writer + ?Sized,
+        S::Error: Source,
+    {
+        fn serialize(
+            &self,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            ArchivedVecWithCapacity::serialize_from_slice(
+                &self.0, self.1, serializer,
+            )
+        }
+    }
+
+    #[test]
+    fn push_within_capacity() {
+        let mut bytes = to_bytes::<Panic>(&Log(Vec::from([1, 2]), 3)).unwrap();
+
+        {
+            let mut archived = access_mut::<
+                ArchivedVecWithCapacity<ArchivedU32>,
+                Panic,
+            >(&mut bytes)
+            .unwrap();
+            assert_eq!(archived.capacity(), 5);
+            for value in 3..=5 {
+                ArchivedVecWithCapacity::push_within_capacity_seal(
+                    archived.as_mut(),
+                    ArchivedU32::from_native(value),
+                )
+                .unwrap();
+            }
+            let full = ArchivedVecWithCapacity::push_within_capacity_seal(
+                archived,
+                ArchivedU32::from_native(6),
+            );
+            assert_eq!(full, Err(ArchivedU32::from_native(6)));
+        }
+
+        let archived =
+            access::<ArchivedVecWithCapacity<ArchivedU32>, Panic>(&bytes)
+                .unwrap();
+        assert_eq!(archived.as_slice(), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn capacity_overflow() {
+        let result = to_bytes::<Error>(&Log(Vec::from([1, 2]), usize::MAX));
+        assert!(result.is_err());
+    }
+
+    fn set_capacity(bytes: &mut [u8], capacity: FixedUsize) {
+        let pos = bytes.len() - size_of::<ArchivedUsize>();
+        let cap = bytes[pos..].as_mut_ptr().cast::<ArchivedUsize>();
+        // SAFETY: The capacity is the last field of the root, which is located
+        // at the end of the bytes.
+        unsafe {
+            cap.write_unaligned(ArchivedUsize::from_native(capacity));
+        }
+    }
+
+    #[test]
+    fn invalid_capacity() {
+        let mut bytes = to_bytes::<Panic>(&Log(Vec::from([1, 2]), 0)).unwrap();
+
+        // Claim more capacity than was reserved after the elements.
+        set_capacity(&mut bytes, 3);
+        let result =
+            access::<ArchivedVecWithCapacity<ArchivedU32>, Error>(&bytes);
+        assert!(result.is_err());
+
+        // Claim less capacity than the length of the vec.
+        set_capacity(&mut bytes, 1);
+        let result =
+            access::<ArchivedVecWithCapacity<ArchivedU32>, Error>(&bytes);
+        assert!(result.is_err());
+
+        set_capacity(&mut bytes, 2);
+        let result =
+            access::<ArchivedVecWithCapacity<ArchivedU32>, Error>(&bytes);
+        assert!(result.is_ok());
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn wide_position_past_u32_max() {
+        use core::mem::MaybeUninit;
+
+        use crate::vec::{ArchivedWideVec, WideVecResolver};
+
+        // Pretend the elements were written just past 4 GiB and that the vec
+        // itself is being resolved at the start of the buffer.
+        let elements = u32::MAX as usize + 0x100;
+        let resolver = WideVecResolver::from_pos(elements);
+        let mut out = MaybeUninit::<ArchivedWideVec<u8>>::uninit();
+        let place = unsafe { Place::new_unchecked(0x10, out.as_mut_ptr()) };
+        ArchivedWideVec::resolve_wide_from_len(3, resolver, place);
+        let archived = unsafe { out.assume_init() };
+
+        assert_eq!(archived.len(), 3);
+        assert_eq!(archived.ptr.offset(), (elements - 0x10) as isize);
+    }
+
+    #[cfg(all(
+        target_pointer_width = "64",
+        not(any(feature = "pointer_width_16", feature = "pointer_width_64"))
+    ))]
+    #[test]
+    #[should_panic]
+    fn narrow_position_past_u32_max() {
+        crate::vec::VecResolver::from_pos(u32::MAX as usize + 0x100);
+    }
 }
@@ -0,0 +1,648 @@
+//! Schema versioning for archived data.
+//!
+//! Archived data is only valid to access as the exact type it was serialized
+//! from. Long-lived formats which need to evolve over time can instead
+//! serialize a [`VersionedRoot`], which stores the value out-of-line behind a
+//! version tag. The version tag is located at a fixed position at the end of
+//! the buffer, so readers can check it with [`archived_version`] before they
+//! choose which type to access the data as.
+//!
+//! Versioned types may only change by appending fields to the end of a struct.
+//! A type can read archives tagged with any version from
+//! [`Versioned::MIN_VERSION`] through [`Versioned::VERSION`]. Archives tagged
+//! with newer versions are rejected, since the reader doesn't know about the
+//! fields which were added since. By default `MIN_VERSION` is the same as
+//! `VERSION`, but appended fields which are marked with `#[rkyv(default)]`
+//! lower it to the version before they were added. Archives which are missing
+//! `default` fields can be accessed with
+//! [`access_compat`](crate::api::high::access_compat) and deserialized with
+//! [`from_bytes_compat`](crate::api::high::from_bytes_compat), which fills in
+//! the missing fields with their default values.
+//!
+//! Appending `default` fields must not increase the alignment of the archived
+//! struct, or archives of older versions may fail to validate.
+
+use core::{error::Error, fmt, marker::PhantomData, mem::size_of};
+
+use munge::munge;
+use rancor::{fail, Fallible, Source};
+
+use crate::{
+    boxed::{ArchivedBox, BoxResolver},
+    primitive::ArchivedU32,
+    traits::{ArchivePointee, Versioned},
+    Archive, ArchiveUnsized, Place, Portable, Serialize, SerializeUnsized,
+};
+
+/// A root value tagged with the schema version of its type.
+///
+/// This is the unarchived counterpart of [`ArchivedVersionedRoot`].
+#[derive(Debug)]
+pub struct VersionedRoot<'a, T: ?Sized> {
+    version: u32,
+    value: &'a T,
+}
+
+impl<'a, T: Versioned + ?Sized> VersionedRoot<'a, T> {
+    /// Creates a new `VersionedRoot` which tags the given value with the
+    /// current version of its type.
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            version: T::VERSION,
+            value,
+        }
+    }
+}
+
+impl<T: ?Sized> VersionedRoot<'_, T> {
+    /// Returns the version that the value will be tagged with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+/// An archived root value tagged with the schema version of its type.
+#[derive(Portable)]
+#[rkyv(crate)]
+#[cfg_attr(feature = "bytecheck", derive(bytecheck::CheckBytes))]
+#[repr(C)]
+pub struct ArchivedVersionedRoot<T: ArchivePointee + ?Sized> {
+    version: ArchivedU32,
+    value: ArchivedBox<T>,
+}
+
+impl<T: ArchivePointee + ?Sized> ArchivedVersionedRoot<T> {
+    /// Returns the version that the archived value was tagged with.
+    pub fn version(&self) -> u32 {
+        self.version.to_native()
+    }
+
+    /// Returns a reference to the archived value.
+    pub fn get(&self) -> &T {
+        self.value.get()
+    }
+}
+
+impl<T> fmt::Debug for ArchivedVersionedRoot<T>
+where
+    T: ArchivePointee + fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedVersionedRoot")
+            .field("version", &self.version())
+            .field("value", &self.get())
+            .finish()
+    }
+}
+
+/// The resolver for [`VersionedRoot`].
+pub struct VersionedRootResolver {
+    value: BoxResolver,
+}
+
+impl<T: ArchiveUnsized + ?Sized> Archive for VersionedRoot<'_, T> {
+    type Archived = ArchivedVersionedRoot<T::Archived>;
+    type Resolver = VersionedRootResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedVersionedRoot { version, value } = out);
+        version.write(ArchivedU32::from_native(self.version));
+        ArchivedBox::resolve_from_ref(self.value, resolver.value, value);
+    }
+}
+
+impl<T, S> Serialize<S> for VersionedRoot<'_, T>
+where
+    T: SerializeUnsized<S> + ?Sized,
+    S: Fallible + ?Sized,
+{
+    fn serialize(
+        &self,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        Ok(VersionedRootResolver {
+            value: ArchivedBox::serialize_from_ref(self.value, serializer)?,
+        })
+    }
+}
+
+/// Returns the version tag of a buffer containing an
+/// [`ArchivedVersionedRoot`].
+///
+/// Returns `None` if the buffer is too small to contain a versioned root. This
+/// function does not validate the buffer, and the returned version is only
+/// meaningful if the buffer was produced from a [`VersionedRoot`].
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     api::high::to_bytes_versioned, rancor::Error,
+///     version::archived_version, Archive, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(version = 3)]
+/// struct Example {
+///     value: i32,
+/// }
+///
+/// let bytes = to_bytes_versioned::<_, Error>(&Example { value: 42 }).unwrap();
+/// assert_eq!(archived_version(&bytes), Some(3));
+/// ```
+pub fn archived_version(bytes: &[u8]) -> Option<u32> {
+    let size = size_of::<ArchivedVersionedRoot<()>>();
+    let pos = bytes.len().checked_sub(size)?;
+    // SAFETY: `pos` is in-bounds of `bytes`, and there are at least
+    // `size_of::<ArchivedU32>()` bytes after it. The version is the first field
+    // of the `#[repr(C)]` root, and `ArchivedU32` has no invalid bit patterns.
+    // The read is unaligned, so no alignment is required.
+    let version = unsafe {
+        bytes
+            .as_ptr()
+            .add(pos)
+            .cast::<ArchivedU32>()
+            .read_unaligned()
+    };
+    Some(version.to_native())
+}
+
+#[derive(Debug)]
+struct VersionMismatch {
+    min: u32,
+    max: u32,
+    found: Option<u32>,
+}
+
+impl fmt::Display for VersionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.found {
+            Some(found) => write!(
+                f,
+                "archive has version {} but only versions {} through {} are \
+                 supported",
+                found, self.min, self.max,
+            ),
+            None => write!(
+                f,
+                "buffer is too small to contain a versioned root (expected \
+                 versions {} through {})",
+                self.min, self.max,
+            ),
+        }
+    }
+}
+
+impl Error for VersionMismatch {}
+
+/// Checks that the version tag of the given buffer is compatible with `T`.
+///
+/// Archives tagged with any version from [`Versioned::MIN_VERSION`] through
+/// [`Versioned::VERSION`] are compatible. Returns the version of the archive.
+pub fn check_version<T, E>(bytes: &[u8]) -> Result<u32, E>
+where
+    T: Versioned + ?Sized,
+    E: Source,
+{
+    match archived_version(bytes) {
+        Some(found) if (T::MIN_VERSION..=T::VERSION).contains(&found) => {
+            Ok(found)
+        }
+        found => fail!(VersionMismatch {
+            min: T::MIN_VERSION,
+            max: T::VERSION,
+            found,
+        }),
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+#[derive(Debug)]
+struct MissingDefaultFields {
+    found: u32,
+    current: u32,
+}
+
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+impl fmt::Display for MissingDefaultFields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archive has version {} which is missing `default` fields added \
+             by version {}, access it with `access_compat` instead",
+            self.found, self.current,
+        )
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+impl Error for MissingDefaultFields {}
+
+/// Checks that the version tag of the given buffer is the current version of
+/// `T`, so the archived value contains all of the fields of `T`.
+#[cfg(all(feature = "alloc", feature = "bytecheck"))]
+pub(crate) fn check_current_version<T, E>(bytes: &[u8]) -> Result<u32, E>
+where
+    T: Versioned + ?Sized,
+    E: Source,
+{
+    let found = check_version::<T, E>(bytes)?;
+    if found != T::VERSION {
+        fail!(MissingDefaultFields {
+            found,
+            current: T::VERSION,
+        });
+    }
+    Ok(found)
+}
+
+/// An archived versioned struct with fields that may be absent from archives
+/// produced by older versions of its type.
+///
+/// This is implemented by deriving `Archive` for a versioned struct with
+/// `#[rkyv(default)]` fields. Archives tagged with any version from
+/// [`MIN_VERSION`](Versioned::MIN_VERSION) through
+/// [`VERSION`](Versioned::VERSION) can be accessed with
+/// [`access_compat`](crate::api::high::access_compat) and deserialized with
+/// [`DeserializeCompat`]. Fields which are absent from older archives are
+/// filled with their default values during deserialization.
+///
+/// # Safety
+///
+/// - `MIN_VERSION` must be less than or equal to `VERSION`.
+/// - For every version from `MIN_VERSION` through `VERSION`, `size_at_version`
+///   must return the number of leading bytes of `Self` occupied by the fields
+///   present in archives tagged with that version. It must return
+///   `size_of::<Self>()` for `VERSION`.
+pub unsafe trait ArchivedCompat: Versioned + Portable + Sized {
+    /// Returns the number of leading bytes of this type present in archives
+    /// tagged with the given version.
+    fn size_at_version(version: u32) -> usize;
+}
+
+/// An [`ArchivedCompat`] type which can check its fields for the given version.
+///
+/// # Safety
+///
+/// `check_compat` must only return `Ok` if all of the fields present at the
+/// given version are valid.
+#[cfg(feature = "bytecheck")]
+pub unsafe trait CheckCompat<C: Fallible + ?Sized>:
+    ArchivedCompat
+{
+    /// Checks the fields of the archived value which are present at the given
+    /// version.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be properly aligned and point to at least
+    /// [`size_at_version(version)`](ArchivedCompat::size_at_version) bytes.
+    unsafe fn check_compat(
+        value: *const Self,
+        version: u32,
+        context: &mut C,
+    ) -> Result<(), C::Error>;
+}
+
+/// An [`ArchivedCompat`] type which can be deserialized from any compatible
+/// version.
+pub trait DeserializeCompat<T, D: Fallible + ?Sized>: ArchivedCompat {
+    /// Deserializes the archived value, filling in the fields that are absent
+    /// at the given version with their default values.
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to a valid archived value which was tagged with the
+    /// given version, and `version` must be between
+    /// [`MIN_VERSION`](Versioned::MIN_VERSION) and
+    /// [`VERSION`](Versioned::VERSION).
+    unsafe fn deserialize_compat(
+        value: *const Self,
+        version: u32,
+        deserializer: &mut D,
+    ) -> Result<T, D::Error>;
+}
+
+/// An archived versioned value which may be missing fields.
+///
+/// This is returned by [`access_compat`](crate::api::high::access_compat).
+/// Values tagged with the current version of `T` can be accessed as `T`
+/// directly. Values of any compatible version can be deserialized.
+pub struct Compat<'a, T> {
+    ptr: *const T,
+    version: u32,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T: ArchivedCompat> Compat<'a, T> {
+    /// Creates a `Compat` from a pointer to a value and its version.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid archived value tagged with `version`, which
+    /// must be between [`MIN_VERSION`](Versioned::MIN_VERSION) and
+    /// [`VERSION`](Versioned::VERSION). The value must be valid for `'a`.
+    pub unsafe fn new_unchecked(ptr: *const T, version: u32) -> Self {
+        Self {
+            ptr,
+            version,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the version that the archived value was tagged with.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns a reference to the archived value if it has all of the fields
+    /// of `T`.
+    pub fn get(&self) -> Option<&'a T> {
+        if self.version == T::VERSION {
+            // SAFETY: Values tagged with the current version contain all of the
+            // fields of `T`.
+            Some(unsafe { &*self.ptr })
+        } else {
+            None
+        }
+    }
+
+    /// Deserializes the archived value, filling in any absent fields with
+    /// their default values.
+    pub fn deserialize<U, D>(&self, deserializer: &mut D) -> Result<U, D::Error>
+    where
+        T: DeserializeCompat<U, D>,
+        D: Fallible + ?Sized,
+    {
+        // SAFETY: The caller of `new_unchecked` guaranteed that `ptr` points to
+        // a valid archived value tagged with `version`.
+        unsafe { T::deserialize_compat(self.ptr, self.version, deserializer) }
+    }
+}
+
+impl<T> Clone for Compat<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Compat<'_, T> {}
+
+impl<T> fmt::Debug for Compat<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compat")
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+mod verify {
+    use core::{alloc::Layout, mem::align_of};
+
+    use rancor::{ResultExt as _, Source, Strategy};
+
+    use super::{check_version, ArchivedVersionedRoot, CheckCompat, Compat};
+    use crate::{
+        api::root_position,
+        validation::{ArchiveContext, ArchiveContextExt as _},
+    };
+
+    /// Accesses a versioned buffer as a compatible version of `T` with the
+    /// given context.
+    ///
+    /// Most of the time, the context should be newly-created and not reused.
+    /// Prefer [`access_compat`](crate::api::high::access_compat) whenever
+    /// possible.
+    pub fn access_compat_with_context<'a, T, C, E>(
+        bytes: &'a [u8],
+        context: &mut C,
+    ) -> Result<Compat<'a, T>, E>
+    where
+        T: CheckCompat<Strategy<C, E>>,
+        C: ArchiveContext<E> + ?Sized,
+        E: Source,
+    {
+        let version = check_version::<T, E>(bytes)?;
+        let layout = Layout::from_size_align(
+            T::size_at_version(version),
+            align_of::<T>(),
+        )
+        .into_error()?;
+
+        let pos = root_position::<ArchivedVersionedRoot<T>>(bytes.len());
+        let root = bytes
+            .as_ptr()
+            .wrapping_add(pos)
+            .cast::<ArchivedVersionedRoot<T>>();
+
+        let context = Strategy::<C, E>::wrap(context);
+        let ptr = context.in_subtree(root, |context| {
+            // SAFETY: `in_subtree` has guaranteed that `root` is properly
+            // aligned and points to enough bytes for an
+            // `ArchivedVersionedRoot<T>`. Its fields are an `ArchivedU32` and a
+            // relative pointer, which are valid for all bit patterns.
+            let ptr = unsafe { (*root).value.as_ptr_wrapping() };
+            context.in_subtree_raw(ptr.cast(), layout, |context| {
+                // SAFETY: `in_subtree_raw` has guaranteed that `ptr` is
+                // properly aligned and points to enough bytes for the fields
+                // present at `version`.
+                unsafe { T::check_compat(ptr, version, context) }
+            })?;
+            Ok(ptr)
+        })?;
+
+        // SAFETY: We checked that `ptr` points to a valid archived value
+        // tagged with `version`, which is between `T::MIN_VERSION` and
+        // `T::VERSION`.
+        Ok(unsafe { Compat::new_unchecked(ptr, version) })
+    }
+}
+
+#[cfg(feature = "bytecheck")]
+pub use self::verify::access_compat_with_context;
+
+#[cfg(all(test, feature = "bytecheck", feature = "alloc"))]
+mod tests {
+    use rancor::{Error, Panic};
+
+    use crate::{
+        alloc::string::{String, ToString},
+        api::high::{
+            access_compat, access_versioned, from_bytes_compat,
+            to_bytes_versioned,
+        },
+        traits::Versioned,
+        version::{archived_version, check_version},
+        Archive, Deserialize, Serialize,
+    };
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, version = 1, derive(Debug))]
+    struct ExampleV1 {
+        a: i32,
+        b: u32,
+    }
+
+    #[derive(Archive, Serialize)]
+    #[rkyv(crate, version = 2, derive(Debug))]
+    struct ExampleV2 {
+        a: i32,
+        b: u32,
+        c: String,
+    }
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate, version = 3, derive(Debug))]
+    struct ExampleV3 {
+        a: i32,
+        b: u32,
+        #[rkyv(default, since = 2)]
+        c: String,
+        #[rkyv(default)]
+        d: Option<u32>,
+    }
+
+    #[test]
+    fn newer_as_older() {
+        let bytes = to_bytes_versioned::<_, Panic>(&ExampleV2 {
+            a: 1,
+            b: 2,
+            c: "hello world".to_string(),
+        })
+        .unwrap();
+        assert_eq!(archived_version(&bytes), Some(2));
+
+        check_version::<ArchivedExampleV1, Error>(&bytes)
+            .expect_err("v2 archives must not be readable by v1");
+        access_versioned::<ArchivedExampleV1, Error>(&bytes)
+            .expect_err("v2 archives must not be accessible as v1");
+
+        let v2 = access_versioned::<ArchivedExampleV2, Panic>(&bytes).unwrap();
+        assert_eq!(v2.c, "hello world");
+    }
+
+    #[test]
+    fn older_as_newer() {
+        let bytes =
+            to_bytes_versioned::<_, Panic>(&ExampleV1 { a: 1, b: 2 }).unwrap();
+        assert_eq!(archived_version(&bytes), Some(1));
+
+        // v2 has no `default` fields, so it can't read v1 archives
+        check_version::<ArchivedExampleV2, Error>(&bytes)
+            .expect_err("v1 archives must not be readable by v2");
+        let v1 = access_versioned::<ArchivedExampleV1, Panic>(&bytes).unwrap();
+        assert_eq!(v1.b, 2);
+
+        // v3 has `default` fields, so it can read v1 archives with compat
+        assert_eq!(
+            check_version::<ArchivedExampleV3, Panic>(&bytes).unwrap(),
+            1
+        );
+        access_versioned::<ArchivedExampleV3, Error>(&bytes)
+            .expect_err("v1 archives are missing the fields of v3");
+        let v3 = access_compat::<ArchivedExampleV3, Panic>(&bytes).unwrap();
+        assert_eq!(v3.version(), 1);
+    }
+
+    #[test]
+    fn too_small() {
+        assert_eq!(archived_version(&[1, 2]), None);
+        access_versioned::<ArchivedExampleV1, Error>(&[1, 2])
+            .expect_err("undersized buffers must fail");
+    }
+
+    #[test]
+    fn compat_defaults() {
+        assert_eq!(ArchivedExampleV3::MIN_VERSION, 1);
+        assert_eq!(ArchivedExampleV2::MIN_VERSION, 2);
+
+        let v1 =
+            to_bytes_versioned::<_, Panic>(&ExampleV1 { a: 1, b: 2 }).unwrap();
+        let compat = access_compat::<ArchivedExampleV3, Panic>(&v1).unwrap();
+        assert_eq!(compat.version(), 1);
+        assert!(compat.get().is_none());
+        assert_eq!(
+            from_bytes_compat::<ExampleV3, Panic>(&v1).unwrap(),
+            ExampleV3 {
+                a: 1,
+                b: 2,
+                c: String::new(),
+                d: None,
+            },
+        );
+
+        let v2 = to_bytes_versioned::<_, Panic>(&ExampleV2 {
+            a: 1,
+            b: 2,
+            c: "hello world".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            from_bytes_compat::<ExampleV3, Panic>(&v2).unwrap(),
+            ExampleV3 {
+                a: 1,
+                b: 2,
+                c: "hello world".to_string(),
+                d: None,
+            },
+        );
+
+        let value = ExampleV3 {
+            a: 1,
+            b: 2,
+            c: "hello world".to_string(),
+            d: Some(3),
+        };
+        let v3 = to_bytes_versioned::<_, Panic>(&value).unwrap();
+        let compat = access_compat::<ArchivedExampleV3, Panic>(&v3).unwrap();
+        assert_eq!(compat.get().unwrap().d, Some(3));
+        assert_eq!(from_bytes_compat::<ExampleV3, Panic>(&v3).unwrap(), value);
+    }
+
+    #[test]
+    fn compat_invalid() {
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate, version = 0)]
+        struct ExampleV0 {
+            a: i32,
+        }
+
+        let v0 = to_bytes_versioned::<_, Panic>(&ExampleV0 { a: 1 }).unwrap();
+        access_compat::<ArchivedExampleV3, Error>(&v0)
+            .expect_err("archives older than MIN_VERSION must fail");
+
+        #[derive(Archive, Serialize)]
+        #[rkyv(crate, version = 4)]
+        struct ExampleV4 {
+            a: i32,
+            b: u32,
+            c: String,
+            d: Option<u32>,
+            e: u8,
+        }
+
+        let v4 = to_bytes_versioned::<_, Panic>(&ExampleV4 {
+            a: 1,
+            b: 2,
+            c: "hello world".to_string(),
+            d: None,
+            e: 3,
+        })
+        .unwrap();
+        access_compat::<ArchivedExampleV3, Error>(&v4)
+            .expect_err("archives newer than VERSION must fail");
+
+        // The string in a v2 archive must still be validated
+        let mut v2 = to_bytes_versioned::<_, Panic>(&ExampleV2 {
+            a: 1,
+            b: 2,
+            c: "hello world".to_string(),
+        })
+        .unwrap();
+        let start = v2.windows(11).position(|w| w == b"hello world").unwrap();
+        v2[start] = 0xff;
+        access_compat::<ArchivedExampleV3, Error>(&v2)
+            .expect_err("invalid fields must fail to validate");
+    }
+}
@@ -140,6 +140,37 @@ pub trait DeserializeWith<F: ?Sized, T, D: Fallible + ?Sized> {
         -> Result<T, D::Error>;
 }
 
+/// An error which occurs when serializing a variant of a remote enum that is
+/// not part of the remote derive.
+///
+/// This is returned by remote derives of enums with `#[rkyv(non_exhaustive)]`,
+/// which lets them cover enums which are `#[non_exhaustive]` upstream.
+#[derive(Debug)]
+pub struct UnknownRemoteVariant {
+    type_name: &'static str,
+}
+
+impl UnknownRemoteVariant {
+    /// Returns a new `UnknownRemoteVariant` for the remote type `T`.
+    pub fn new<T: ?Sized>() -> Self {
+        Self {
+            type_name: core::any::type_name::<T>(),
+        }
+    }
+}
+
+impl fmt::Display for UnknownRemoteVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "variant of remote enum `{}` is not covered by its remote derive",
+            self.type_name,
+        )
+    }
+}
+
+impl core::error::Error for UnknownRemoteVariant {}
+
 /// A transparent wrapper which applies a "with" type.
 ///
 /// `With` wraps a reference to a type and applies the specified wrapper type
@@ -374,6 +405,146 @@ pub struct InlineAsBox;
 #[derive(Debug)]
 pub struct AsString;
 
+/// A wrapper that archives a `Vec` of structs in a column-oriented layout.
+///
+/// The element type must derive `Archive` with `#[rkyv(columns)]`. Each field
+/// of the elements is archived into its own `ArchivedVec`, and the archived
+/// type is the generated `Archived{Name}Columns` struct. See the
+/// [`columns`](crate::columns) module for more details.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::AsColumns, Archive};
+///
+/// #[derive(Archive)]
+/// #[rkyv(columns)]
+/// struct Point {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsColumns)]
+///     points: Vec<Point>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsColumns;
+
+/// A wrapper that deduplicates identical strings in the archive.
+///
+/// Interned strings are archived as shared pointers to an
+/// [`ArchivedString`](crate::string::ArchivedString), and all interned strings
+/// with the same contents point to the same archived string. This can greatly
+/// reduce the size of archives with many repeated strings. Serializing requires
+/// a serializer which implements [`Interning`](crate::ser::Interning), and
+/// validating requires a `SharedContext`.
+/// The high-level serializer interns strings, but the low-level serializer
+/// writes a separate copy of each string.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::Intern, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = Intern)]
+///     name: String,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Intern;
+
+/// A wrapper that writes bytes to a blob region outside of the archive.
+///
+/// Large byte buffers are usually copied into the archive buffer and then
+/// copied again when the archive is written out. `ExternalBytes` instead writes
+/// the bytes directly to a separate blob region and archives them as an
+/// [`ArchivedBlob`](crate::blob::ArchivedBlob) which records their offset and
+/// length. The blob region can then be stored alongside the archive, and the
+/// bytes are retrieved from it at access time.
+///
+/// Serializing requires a serializer which implements
+/// [`Blobs`](crate::ser::Blobs), like the one used by
+/// [`to_bytes_with_blobs`](crate::api::high::to_bytes_with_blobs).
+/// Deserializing requires a deserializer which implements
+/// [`BlobSource`](crate::de::BlobSource), like the one used by
+/// [`deserialize_with_blobs`](crate::api::high::deserialize_with_blobs).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access,
+///     api::high::{deserialize_with_blobs, to_bytes_with_blobs},
+///     rancor::Error,
+///     with::ExternalBytes,
+///     Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example {
+///     name: String,
+///     #[rkyv(with = ExternalBytes)]
+///     payload: Vec<u8>,
+/// }
+///
+/// let value = Example {
+///     name: "upload".to_string(),
+///     payload: vec![0xab; 1 << 20],
+/// };
+/// let (bytes, blobs) = to_bytes_with_blobs::<Error>(&value).unwrap();
+/// assert!(bytes.len() < 1024);
+/// assert_eq!(blobs.len(), 1 << 20);
+///
+/// let archived = access::<ArchivedExample, Error>(&bytes).unwrap();
+/// assert_eq!(archived.payload.get(&blobs), Some(&*value.payload));
+///
+/// let deserialized =
+///     deserialize_with_blobs::<Example, Error>(archived, &blobs).unwrap();
+/// assert_eq!(deserialized.payload, value.payload);
+/// ```
+#[derive(Debug)]
+pub struct ExternalBytes;
+
+/// A wrapper that serializes a field as an archive embedded in the outer
+/// archive.
+///
+/// The field is serialized to its own archive, which is then stored as an
+/// [`ArchivedRawArchive`](crate::raw_archive::ArchivedRawArchive). Validating
+/// the outer archive does not validate the embedded archive, which is
+/// validated each time it is accessed instead. Deserializing the field
+/// validates the embedded archive first.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     access, rancor::Error, to_bytes, with::AsRawArchive, Archive,
+///     Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Message {
+///     id: u32,
+///     #[rkyv(with = AsRawArchive)]
+///     body: String,
+/// }
+///
+/// let value = Message {
+///     id: 7,
+///     body: "hello world".to_string(),
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+/// let archived = access::<ArchivedMessage, Error>(&bytes).unwrap();
+/// assert_eq!(archived.body.access::<Error>().unwrap(), "hello world");
+/// ```
+#[derive(Debug)]
+pub struct AsRawArchive;
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
 /// This wrapper can panic under very specific circumstances when:
@@ -421,6 +592,88 @@ pub struct Lock;
 #[derive(Debug)]
 pub struct AsOwned;
 
+/// A wrapper that deserializes references and `Cow`s by borrowing from the
+/// archive buffer.
+///
+/// This archives `&str` and `Cow<str>` as strings, and `&[T]` and `Cow<[T]>` as
+/// vecs. Instead of copying them out during deserialization, they are
+/// deserialized as references (or `Cow::Borrowed`) pointing into the archive
+/// buffer. Slices can only be borrowed if their elements are archived as
+/// themselves (like `u8`). This requires a deserializer which implements
+/// [`Borrowing`](crate::de::Borrowing), like the one used by
+/// [`deserialize_borrowed`](crate::api::high::deserialize_borrowed).
+///
+/// # Example
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use rkyv::{
+///     access, api::high::deserialize_borrowed, rancor::Error, to_bytes,
+///     with::Borrowed, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example<'a> {
+///     #[rkyv(with = Borrowed)]
+///     name: Cow<'a, str>,
+///     #[rkyv(with = Borrowed)]
+///     blob: Cow<'a, [u8]>,
+/// }
+///
+/// let value = Example {
+///     name: Cow::Borrowed("a name which is stored out of line"),
+///     blob: Cow::Owned(vec![1, 2, 3, 4]),
+/// };
+/// let bytes = to_bytes::<Error>(&value).unwrap();
+///
+/// let archived = access::<ArchivedExample, Error>(&bytes).unwrap();
+/// let deserialized =
+///     deserialize_borrowed::<Example, Error>(archived, &bytes).unwrap();
+/// assert!(matches!(deserialized.name, Cow::Borrowed(_)));
+/// assert!(matches!(deserialized.blob, Cow::Borrowed(&[1, 2, 3, 4])));
+/// ```
+#[derive(Debug)]
+pub struct Borrowed;
+
+/// A wrapper that deserializes `Bytes` by sharing ownership of the archive
+/// buffer.
+///
+/// This archives `Bytes` as a vec of bytes, but deserializes them by slicing a
+/// reference-counted owner of the archive buffer instead of copying them. This
+/// requires a deserializer which implements
+/// [`SlicingBytes`](crate::de::SlicingBytes), like the one used by
+/// [`from_shared_bytes`](crate::api::high::from_shared_bytes).
+///
+/// # Example
+///
+/// ```
+/// # extern crate bytes_1 as bytes;
+/// use bytes::Bytes;
+/// use rkyv::{
+///     api::high::from_shared_bytes, rancor::Error, to_bytes,
+///     with::AsOwnedBytes, Archive, Deserialize, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Example {
+///     #[rkyv(with = AsOwnedBytes)]
+///     body: Bytes,
+/// }
+///
+/// let value = Example {
+///     body: Bytes::from_static(b"a large payload body"),
+/// };
+/// let bytes = Bytes::from_owner(to_bytes::<Error>(&value).unwrap());
+///
+/// let deserialized = from_shared_bytes::<Example, Error>(&bytes).unwrap();
+/// assert_eq!(deserialized.body, value.body);
+/// assert!(bytes.as_ptr_range().contains(&deserialized.body.as_ptr()));
+/// ```
+#[cfg(feature = "bytes-1")]
+#[derive(Debug)]
+pub struct AsOwnedBytes;
+
 /// A wrapper that serializes associative containers as a `Vec` of key-value
 /// pairs.
 ///
@@ -662,6 +915,416 @@ pub struct Skip;
 #[derive(Debug)]
 pub struct Unshare;
 
+/// A wrapper that archives a `Vec` with a 64-bit relative pointer and length.
+///
+/// The default pointer width is shared by every relative pointer in an
+/// archive, so one collection that's too large to address would otherwise force
+/// the entire archive to use `pointer_width_64`. Fields archived with `Wide`
+/// use [`ArchivedWideVec`](crate::vec::ArchivedWideVec) instead, and other
+/// fields keep the configured pointer width.
+///
+/// Only `Vec` is supported. Hash maps, B-trees, and the elements of the vec
+/// still use the configured pointer width, so a large collection of strings or
+/// nested vecs may need `pointer_width_64` regardless.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::Wide, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     name: String,
+///     #[rkyv(with = Wide)]
+///     blob: Vec<u8>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Wide;
+
+/// A wrapper that archives a `Vec<bool>` as a packed
+/// [`ArchivedBitVec`](crate::collections::bitvec::ArchivedBitVec).
+///
+/// An `ArchivedVec<bool>` uses a full byte for each element, while an
+/// `ArchivedBitVec` packs eight elements into each byte.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::AsBitVec, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsBitVec)]
+///     flags: Vec<bool>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsBitVec;
+
+/// A wrapper that archives a sorted `Vec<u64>` or a `BTreeSet<u64>` as an
+/// [`ArchivedEliasFano`](crate::collections::elias_fano::ArchivedEliasFano).
+///
+/// Elias-Fano encoding stores sorted integers in about `2 + log2(max / len)`
+/// bits each while still supporting fast `select`, `rank`, and `contains`.
+/// Serializing a `Vec<u64>` which is not sorted in non-decreasing order fails
+/// with an error.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::AsEliasFano, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct PostingList {
+///     #[rkyv(with = AsEliasFano)]
+///     doc_ids: Vec<u64>,
+/// }
+///
+/// let value = PostingList {
+///     doc_ids: vec![3, 17, 17, 250, 1024],
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedPostingList, Error>(&bytes).unwrap();
+/// assert_eq!(archived.doc_ids.select(3), Some(250));
+/// assert_eq!(archived.doc_ids.rank(100), 3);
+/// assert!(archived.doc_ids.contains(1024));
+/// assert!(!archived.doc_ids.contains(1000));
+/// ```
+#[derive(Debug)]
+pub struct AsEliasFano;
+
+/// A wrapper that archives a `Vec<T>` as an
+/// [`ArchivedRleVec`](crate::collections::rle_vec::ArchivedRleVec).
+///
+/// Runs of equal consecutive elements are stored once, so vecs which are
+/// mostly made of a few repeated values take up much less space. Elements can
+/// still be accessed by index with a binary search over the runs.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::AsRle, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Image {
+///     #[rkyv(with = AsRle)]
+///     pixels: Vec<u8>,
+/// }
+///
+/// let mut pixels = vec![0; 1_000_000];
+/// pixels[1000..2000].fill(255);
+///
+/// let bytes = rkyv::to_bytes::<Error>(&Image { pixels }).unwrap();
+/// assert!(bytes.len() < 100);
+///
+/// let archived = rkyv::access::<ArchivedImage, Error>(&bytes).unwrap();
+/// assert_eq!(archived.pixels.len(), 1_000_000);
+/// assert_eq!(archived.pixels.run_count(), 3);
+/// assert_eq!(archived.pixels.get(1500), Some(&255));
+/// ```
+#[derive(Debug)]
+pub struct AsRle;
+
+/// A wrapper that packs `bool` and integer fields of a struct into as few bits
+/// as possible.
+///
+/// Every field of a struct marked with `#[rkyv(with = BitPack, bits = N)]` is
+/// packed into `N` bits of a single byte array in the archived struct. `bits`
+/// may be omitted for `bool` fields, which take up one bit. Packed fields must
+/// be `bool`s or 8, 16, 32, or 64-bit integers, and serializing a value which
+/// doesn't fit in its bits fails with an error.
+///
+/// Packed fields are not stored as fields of the archived struct. Instead, the
+/// derive generates a method with the same name as each field which returns
+/// its value. `BitPack` may only be used on structs with named fields. See the
+/// [`bitpack`](crate::bitpack) module for details of the layout.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::BitPack, Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+/// struct Record {
+///     id: u32,
+///     #[rkyv(with = BitPack)]
+///     visible: bool,
+///     #[rkyv(with = BitPack, bits = 3)]
+///     priority: u8,
+///     #[rkyv(with = BitPack, bits = 4)]
+///     offset: i8,
+/// }
+///
+/// let value = Record {
+///     id: 42,
+///     visible: true,
+///     priority: 5,
+///     offset: -3,
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedRecord, Error>(&bytes).unwrap();
+/// assert!(archived.visible());
+/// assert_eq!(archived.priority(), 5);
+/// assert_eq!(archived.offset(), -3);
+///
+/// let too_large = Record {
+///     priority: 8,
+///     ..value
+/// };
+/// assert!(rkyv::to_bytes::<Error>(&too_large).is_err());
+/// ```
+#[derive(Debug)]
+pub struct BitPack;
+
+/// A wrapper that archives a `u32`, `u64`, or `usize` as a variable-length
+/// integer.
+///
+/// The value is encoded as LEB128 and written out-of-line, and the archived
+/// field is an [`ArchivedVarint`](crate::varint::ArchivedVarint) which decodes
+/// it on access. Values below 128 take up a single byte in addition to the
+/// relative pointer to them, so this is most useful for 64-bit fields which
+/// usually hold small values. See the [`varint`](crate::varint) module for
+/// details of the encoding.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{rancor::Error, with::Varint, Archive, Deserialize, Serialize};
+///
+/// #[derive(Archive, Serialize, Deserialize)]
+/// struct Counters {
+///     #[rkyv(with = Varint)]
+///     hits: u64,
+///     #[rkyv(with = Varint)]
+///     misses: usize,
+/// }
+///
+/// let value = Counters {
+///     hits: 3,
+///     misses: 1 << 40,
+/// };
+/// let bytes = rkyv::to_bytes::<Error>(&value).unwrap();
+/// let archived = rkyv::access::<ArchivedCounters, Error>(&bytes).unwrap();
+/// assert_eq!(archived.hits.get(), 3);
+/// assert_eq!(archived.misses, 1 << 40);
+/// ```
+#[derive(Debug)]
+pub struct Varint;
+
+/// A wrapper that archives a `String` with an inline capacity of `N` bytes.
+///
+/// By default, archived strings can only inline as many bytes as it takes to
+/// store a relative pointer and length. Strings of up to `N` bytes archived
+/// with `InlineCapacity<N>` are stored inline, which makes accessing them
+/// faster and avoids a separate allocation for each string. The tradeoff is
+/// that every archived string takes up at least `N` bytes, so this works best
+/// when most strings are close to `N` bytes long.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{with::InlineCapacity, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = InlineCapacity<24>)]
+///     name: String,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InlineCapacity<const N: usize>;
+
+/// A wrapper that archives a `Vec` or `String` inline with a maximum length of
+/// `N`.
+///
+/// Vecs and strings are archived as the types in
+/// [`collections::bounded`](crate::collections::bounded), which store their
+/// contents in a fixed-size array next to the length. They never use relative
+/// pointers. Serializing a field which is longer than `N` fails with an error.
+///
+/// Combined with [`MaxSize`](crate::max_size::MaxSize), this can be used to
+/// build message formats with a statically known size.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{max_size::MaxSize, with::Bounded, Archive, Serialize};
+///
+/// #[derive(Archive, Serialize)]
+/// #[rkyv(max_size)]
+/// struct Message {
+///     #[rkyv(with = Bounded<16>)]
+///     name: String,
+///     #[rkyv(with = Bounded<8>)]
+///     values: Vec<u32>,
+/// }
+///
+/// assert_eq!(Message::MAX_DEPENDENCIES_SIZE, 0);
+/// ```
+#[derive(Debug)]
+pub struct Bounded<const N: usize>;
+
+/// A wrapper that archives a hash map or set with the hasher `H`.
+///
+/// Archived hash maps and sets use [`FxHasher64`](crate::hash::FxHasher64) by
+/// default, which is fast but not resistant to hash flooding. Maps built from
+/// untrusted keys can use a keyed hasher like
+/// [`SipHasher13`](crate::hash::SipHasher13) instead. The archived map must
+/// then be accessed with the same hasher.
+///
+/// This can also be written as `#[rkyv(hasher = H)]` on a field.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{hash::SipHasher13, with::HashWith, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = HashWith<SipHasher13<1, 2>>)]
+///     a: HashMap<String, u32>,
+///     #[rkyv(hasher = SipHasher13<3, 4>)]
+///     b: HashMap<String, u32>,
+/// }
+/// ```
+pub struct HashWith<H: ?Sized> {
+    _phantom: PhantomData<H>,
+}
+
+impl<H: ?Sized> Default for HashWith<H> {
+    fn default() -> Self {
+        Self {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<H: ?Sized> fmt::Debug for HashWith<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HashWith")
+    }
+}
+
+/// A wrapper that archives a map as an
+/// [`ArchivedPhfMap`](crate::collections::phf::ArchivedPhfMap).
+///
+/// A perfect hash function is computed for the keys when the map is
+/// serialized. This makes serialization slower, but every lookup in the
+/// archived map checks exactly one entry. This works best for maps which are
+/// serialized once and then read many times.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{with::AsPhfMap, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsPhfMap)]
+///     values: HashMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsPhfMap;
+
+/// A wrapper that archives a `HashMap` or `BTreeMap` as an
+/// [`ArchivedSortedVecMap`](crate::collections::sorted_vec_map::ArchivedSortedVecMap).
+///
+/// The entries are sorted by key and stored in a single archived vec, which
+/// is searched with a binary search. This is more compact than an archived
+/// hash map or B-tree map, and is a good fit for maps with only a few entries.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{with::AsSortedVec, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsSortedVec)]
+///     headers: HashMap<String, String>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsSortedVec;
+
+/// A wrapper that archives a `HashMap` of `Vec`s as an
+/// [`ArchivedHashMultiMap`](crate::collections::multi_map::ArchivedHashMultiMap).
+///
+/// The values of every key are stored together in a single archived vec, so
+/// getting the values for a key doesn't follow a separate pointer for each
+/// key. Keys with no values are not archived, and so are missing from the
+/// deserialized map.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use rkyv::{with::AsMultiMap, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsMultiMap)]
+///     tags: HashMap<String, Vec<u32>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsMultiMap;
+
+/// A wrapper that archives a `Vec` of intervals and values as an
+/// [`ArchivedIntervalMap`](crate::collections::interval_map).
+///
+/// The intervals are sorted when they are serialized, and the archived map
+/// can find all of the intervals which overlap a point or range in
+/// logarithmic time. Deserializing the map yields the intervals in sorted
+/// order.
+///
+/// # Example
+///
+/// ```
+/// use core::ops::Range;
+///
+/// use rkyv::{with::AsIntervalMap, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsIntervalMap)]
+///     genes: Vec<(Range<u64>, String)>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsIntervalMap;
+
+/// A wrapper that archives a `BTreeMap` with string keys as an
+/// [`ArchivedTrieMap`](crate::collections::trie::ArchivedTrieMap).
+///
+/// The archived map supports looking up the longest key which is a prefix of
+/// a string, and iterating over all of the keys which start with a prefix.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// use rkyv::{with::AsTrieMap, Archive};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[rkyv(with = AsTrieMap)]
+///     routes: BTreeMap<String, u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsTrieMap;
+
 /// A no-op wrapper which uses the default impls for the type.
 ///
 /// This is most useful for wrappers like [`MapKV`] when you only want to apply
@@ -215,6 +215,138 @@ fn full_enum() {
     }
 }
 
+#[test]
+fn enum_unknown_variant() {
+    #[derive(Debug, PartialEq)]
+    enum Remote {
+        A,
+        B,
+        C,
+    }
+
+    // A newer version of the enum which knows about every variant.
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = Remote)]
+    enum New {
+        A,
+        B,
+        C,
+        #[rkyv(other)]
+        Other,
+    }
+
+    impl From<New> for Remote {
+        fn from(value: New) -> Self {
+            match value {
+                New::A => Remote::A,
+                New::B => Remote::B,
+                New::C | New::Other => Remote::C,
+            }
+        }
+    }
+
+    // An older version of the enum which only knows about the first variant.
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = Remote, compare(PartialEq))]
+    enum Old {
+        A,
+        #[rkyv(other)]
+        Other,
+    }
+
+    impl From<Old> for Remote {
+        fn from(value: Old) -> Self {
+            match value {
+                Old::A => Remote::A,
+                Old::Other => Remote::B,
+            }
+        }
+    }
+
+    let mut bytes = [0_u8; 128];
+    let buf = serialize::<New, Remote>(&Remote::C, &mut bytes);
+    let archived = access::<Old, Remote>(&buf);
+    assert!(*archived == Old::Other);
+    assert!(*archived != Old::A);
+
+    let deserialized: Remote =
+        <Old as DeserializeWith<_, _, Strategy<(), Panic>>>::deserialize_with(
+            archived,
+            Strategy::wrap(&mut ()),
+        )
+        .always_ok();
+    assert_eq!(deserialized, Remote::B);
+
+    roundtrip::<Old, _>(&Remote::A);
+}
+
+#[test]
+fn enum_non_exhaustive() {
+    use std::io::ErrorKind;
+
+    use rancor::Failure;
+    use rkyv::with::With;
+
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = ErrorKind, non_exhaustive)]
+    enum ErrorKindDef {
+        NotFound,
+        PermissionDenied,
+    }
+
+    impl From<ErrorKindDef> for ErrorKind {
+        fn from(value: ErrorKindDef) -> Self {
+            match value {
+                ErrorKindDef::NotFound => ErrorKind::NotFound,
+                ErrorKindDef::PermissionDenied => ErrorKind::PermissionDenied,
+            }
+        }
+    }
+
+    roundtrip::<ErrorKindDef, _>(&ErrorKind::NotFound);
+    roundtrip::<ErrorKindDef, _>(&ErrorKind::PermissionDenied);
+
+    let mut bytes = [0_u8; 128];
+    let mut scratch = [MaybeUninit::uninit(); 128];
+    let result = rkyv::api::low::to_bytes_in_with_alloc::<_, _, Failure>(
+        With::<_, ErrorKindDef>::cast(&ErrorKind::TimedOut),
+        Buffer::from(&mut bytes),
+        SubAllocator::new(&mut scratch),
+    );
+    assert!(result.is_err());
+
+    // An `other` variant archives unknown variants instead.
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = ErrorKind)]
+    enum ErrorKindOther {
+        NotFound,
+        #[rkyv(other)]
+        Other,
+    }
+
+    impl From<ErrorKindOther> for ErrorKind {
+        fn from(value: ErrorKindOther) -> Self {
+            match value {
+                ErrorKindOther::NotFound => ErrorKind::NotFound,
+                ErrorKindOther::Other => ErrorKind::Other,
+            }
+        }
+    }
+
+    roundtrip::<ErrorKindOther, _>(&ErrorKind::NotFound);
+    let buf = serialize::<ErrorKindOther, _>(&ErrorKind::TimedOut, &mut bytes);
+    let archived = access::<ErrorKindOther, ErrorKind>(&buf);
+    let deserialized: ErrorKind = <ErrorKindOther as DeserializeWith<
+        _,
+        _,
+        Strategy<(), Panic>,
+    >>::deserialize_with(
+        archived, Strategy::wrap(&mut ())
+    )
+    .always_ok();
+    assert_eq!(deserialized, ErrorKind::Other);
+}
+
 #[test]
 fn named_struct_private() {
     mod remote {
@@ -317,6 +449,51 @@ fn unnamed_struct_private() {
     roundtrip::<ExampleThroughRef, _>(&remote);
 }
 
+#[test]
+fn struct_from_constructor() {
+    use std::time::Duration;
+
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = Duration, from = Duration::new)]
+    struct DurationDef {
+        #[rkyv(getter = Duration::as_secs)]
+        secs: u64,
+        #[rkyv(getter = Duration::subsec_nanos)]
+        nanos: u32,
+    }
+
+    roundtrip::<DurationDef, _>(&Duration::new(0, 0));
+    roundtrip::<DurationDef, _>(&Duration::new(12, 345_678));
+
+    mod remote {
+        #[derive(Copy, Clone, Debug, Default, PartialEq)]
+        pub struct Remote([u8; 4], bool);
+
+        impl Remote {
+            pub fn new(inner: [u8; 4], flag: bool) -> Self {
+                Self(inner, flag)
+            }
+
+            pub fn inner(&self) -> &[u8; 4] {
+                &self.0
+            }
+
+            pub fn flag(&self) -> bool {
+                self.1
+            }
+        }
+    }
+
+    #[derive(Archive, Serialize, Deserialize)]
+    #[rkyv(remote = remote::Remote, from = remote::Remote::new)]
+    struct Example(
+        #[rkyv(getter = remote::Remote::inner)] [u8; 4],
+        #[rkyv(getter = remote::Remote::flag)] bool,
+    );
+
+    roundtrip::<Example, _>(&remote::Remote::new([1, 2, 3, 4], true));
+}
+
 #[cfg(feature = "bytecheck")]
 pub trait CheckedArchived:
     for<'a> rkyv::bytecheck::CheckBytes<rkyv::api::low::LowValidator<'a, Panic>>
@@ -0,0 +1,154 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Error, Field, Fields, Generics, Ident, Member};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+};
+
+/// A field which is packed into the bits of its archived struct.
+pub struct PackedField<'a> {
+    pub field: &'a Field,
+    pub member: Member,
+    pub offset: u32,
+    pub width: u32,
+}
+
+/// Returns the fields which are packed with `BitPack`, along with the bits
+/// which each is packed into.
+pub fn packed_fields<'a>(
+    attributes: &Attributes,
+    fields: &'a Fields,
+) -> Result<Vec<PackedField<'a>>, Error> {
+    let mut result = Vec::new();
+    let mut offset = 0;
+    for (field, member) in fields.iter().zip(fields.members()) {
+        if let Some(width) = FieldAttributes::parse(attributes, field)?.bit_pack
+        {
+            result.push(PackedField {
+                field,
+                member,
+                offset,
+                width,
+            });
+            offset += width;
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the name of the archived field which stores the packed bits.
+pub fn bits_field() -> Ident {
+    Ident::new("__rkyv_bit_pack", Span::call_site())
+}
+
+/// Returns the number of bytes needed to store the packed fields.
+pub fn packed_len(packed: &[PackedField<'_>]) -> usize {
+    packed
+        .last()
+        .map_or(0, |last| (last.offset + last.width).div_ceil(8) as usize)
+}
+
+pub fn generate_resolve_statement(
+    printing: &Printing,
+    packed: &[PackedField<'_>],
+    this: &Ident,
+) -> TokenStream {
+    let rkyv_path = &printing.rkyv_path;
+    let bits_field = bits_field();
+    let len = packed_len(packed);
+
+    let writes = packed.iter().map(
+        |PackedField {
+             field,
+             member,
+             offset,
+             width,
+         }| {
+            let ty = &field.ty;
+            quote! {
+                #rkyv_path::bitpack::write_bits(
+                    &mut bits,
+                    #offset,
+                    #width,
+                    <#ty as #rkyv_path::bitpack::BitPackable>::to_bits(
+                        #this.#member,
+                    ),
+                );
+            }
+        },
+    );
+
+    quote! {
+        let mut bits = [0u8; #len];
+        #(#writes)*
+        let field_ptr = unsafe {
+            ::core::ptr::addr_of_mut!((*out.ptr()).#bits_field)
+        };
+        let field_out = unsafe {
+            #rkyv_path::Place::from_field_unchecked(out, field_ptr)
+        };
+        field_out.write(bits);
+    }
+}
+
+pub fn generate_accessors(
+    printing: &Printing,
+    generics: &Generics,
+    packed: &[PackedField<'_>],
+) -> TokenStream {
+    let Printing {
+        rkyv_path,
+        archived_name,
+        ..
+    } = printing;
+    let bits_field = bits_field();
+
+    let accessors = packed.iter().map(
+        |PackedField {
+             field,
+             member,
+             offset,
+             width,
+         }| {
+            let vis = &field.vis;
+            let ty = &field.ty;
+            let doc = format!(
+                "Returns the value of the packed `{}` field.",
+                quote!(#member),
+            );
+            quote! {
+                #[doc = #doc]
+                #[inline]
+                #vis fn #member(&self) -> #ty {
+                    const {
+                        assert!(
+                            #width
+                                <= <#ty as #rkyv_path::bitpack::BitPackable>
+                                    ::BITS,
+                            "`bits` is larger than the size of the field",
+                        );
+                    }
+                    <#ty as #rkyv_path::bitpack::BitPackable>::from_bits(
+                        #rkyv_path::bitpack::read_bits(
+                            &self.#bits_field,
+                            #offset,
+                            #width,
+                        ),
+                        #width,
+                    )
+                }
+            }
+        },
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics #archived_name #ty_generics #where_clause {
+            #(#accessors)*
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Data, DataStruct, DeriveInput, Error, Field, Fields, Generics,
+    Ident, Path,
+};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+    util::strip_raw,
+};
+
+pub fn columns_resolver_name(name: &Ident) -> Ident {
+    format_ident!("{}ColumnsResolver", strip_raw(name))
+}
+
+/// Returns the fields of a type which archives as columns, or an error if the
+/// type can't be archived as columns.
+pub fn columns_fields<'a>(
+    input: &'a DeriveInput,
+    columns: &Path,
+) -> Result<&'a Fields, Error> {
+    match &input.data {
+        Data::Struct(DataStruct { fields, .. }) if !fields.is_empty() => {
+            Ok(fields)
+        }
+        _ => Err(Error::new_spanned(
+            columns,
+            "`columns` may only be used on structs with at least one field",
+        )),
+    }
+}
+
+pub fn impl_columns(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        vis,
+        name,
+        archived_name,
+        ..
+    } = printing;
+
+    let columns_name = format_ident!("{}Columns", archived_name);
+    let resolver_name = columns_resolver_name(name);
+
+    let mut columns_fields = TokenStream::new();
+    let mut resolver_fields = TokenStream::new();
+    let mut resolve_statements = TokenStream::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let Field {
+            vis,
+            ident,
+            colon_token,
+            ..
+        } = field;
+
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let field_metas = field_attrs.metas();
+        let archived = field_attrs.archived(rkyv_path, field);
+
+        columns_fields.extend(quote! {
+            #field_metas
+            #vis #ident #colon_token #rkyv_path::vec::ArchivedVec<#archived>,
+        });
+        resolver_fields.extend(quote! {
+            #ident #colon_token #rkyv_path::vec::VecResolver,
+        });
+        resolve_statements.extend(quote! {
+            let field_ptr = unsafe {
+                ::core::ptr::addr_of_mut!((*out.ptr()).#member)
+            };
+            let field_out = unsafe {
+                #rkyv_path::Place::from_field_unchecked(out, field_ptr)
+            };
+            #rkyv_path::vec::ArchivedVec::<#archived>::resolve_from_len(
+                len,
+                resolver.#member,
+                field_out,
+            );
+        });
+    }
+
+    let first_member = fields.members().next().unwrap();
+
+    let where_clause = &generics.where_clause;
+    // The resolver only contains vec resolvers, so it isn't generic.
+    let (columns_body, resolver_body) = match fields {
+        Fields::Named(_) => (
+            quote! { #where_clause { #columns_fields } },
+            quote! { { #resolver_fields } },
+        ),
+        _ => (
+            quote! { (#columns_fields) #where_clause; },
+            quote! { (#resolver_fields); },
+        ),
+    };
+
+    // Attributes and derives for the archived type are also applied to the
+    // archived columns.
+    #[cfg(not(feature = "bytecheck"))]
+    let (columns_metas, verify_impl) =
+        (attributes.metas.clone(), TokenStream::new());
+    #[cfg(feature = "bytecheck")]
+    let (columns_metas, verify_impl) = {
+        let mut metas = attributes.metas.clone();
+        metas.push(parse_quote! {
+            derive(#rkyv_path::bytecheck::CheckBytes)
+        });
+        metas.push(parse_quote! {
+            bytecheck(crate = #rkyv_path::bytecheck, verify)
+        });
+        if let Some(attrs) = &attributes.bytecheck {
+            metas.push(parse_quote! { bytecheck(#attrs) });
+        }
+        (
+            metas,
+            generate_verify_impl(printing, generics, fields, &columns_name),
+        )
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut portable_where = where_clause.unwrap().clone();
+    for field in fields.iter() {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived = field_attrs.archived(rkyv_path, field);
+        portable_where.predicates.push(parse_quote! {
+            #archived: #rkyv_path::Portable
+        });
+    }
+
+    let columns_doc =
+        format!("The archived columns of a sequence of [`{}`]", name);
+    let resolver_doc = format!(
+        "The resolver for the archived columns of a sequence of [`{}`]",
+        name
+    );
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[doc = #columns_doc]
+        #(#[#columns_metas])*
+        #[repr(C)]
+        #vis struct #columns_name #generics #columns_body
+
+        #[automatically_derived]
+        #[doc = #resolver_doc]
+        #vis struct #resolver_name #resolver_body
+
+        // SAFETY: The archived columns are `repr(C)` and only contain archived
+        // vecs of portable types.
+        unsafe impl #impl_generics #rkyv_path::Portable
+            for #columns_name #ty_generics
+        #portable_where
+        {}
+
+        #verify_impl
+
+        impl #impl_generics #columns_name #ty_generics #where_clause {
+            /// Returns the number of rows in the archived columns.
+            pub fn len(&self) -> usize {
+                self.#first_member.len()
+            }
+
+            /// Returns whether the archived columns have no rows.
+            pub fn is_empty(&self) -> bool {
+                self.len() == 0
+            }
+        }
+
+        impl #impl_generics #rkyv_path::columns::ArchiveColumns
+            for #name #ty_generics
+        #portable_where
+        {
+            type ArchivedColumns = #columns_name #ty_generics;
+            type ColumnsResolver = #resolver_name;
+
+            fn columns_len(columns: &Self::ArchivedColumns) -> usize {
+                columns.len()
+            }
+
+            fn resolve_columns(
+                len: usize,
+                resolver: Self::ColumnsResolver,
+                out: #rkyv_path::Place<Self::ArchivedColumns>,
+            ) {
+                #resolve_statements
+            }
+        }
+    })
+}
+
+#[cfg(feature = "bytecheck")]
+fn generate_verify_impl(
+    printing: &Printing,
+    generics: &Generics,
+    fields: &Fields,
+    columns_name: &Ident,
+) -> TokenStream {
+    let rkyv_path = &printing.rkyv_path;
+    let members = fields.members();
+
+    let mut verify_generics = generics.clone();
+    verify_generics.params.push(parse_quote! { __C });
+    verify_generics
+        .make_where_clause()
+        .predicates
+        .extend::<[syn::WherePredicate; 2]>([
+            parse_quote! { __C: #rkyv_path::rancor::Fallible + ?Sized },
+            parse_quote! {
+                <__C as #rkyv_path::rancor::Fallible>::Error:
+                    #rkyv_path::rancor::Source
+            },
+        ]);
+    let (impl_generics, _, where_clause) = verify_generics.split_for_impl();
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    quote! {
+        // SAFETY: `verify` only checks that the lengths of all of the columns
+        // are the same.
+        unsafe impl #impl_generics #rkyv_path::bytecheck::Verify<__C>
+            for #columns_name #ty_generics
+        #where_clause
+        {
+            fn verify(
+                &self,
+                _: &mut __C,
+            ) -> ::core::result::Result<
+                (),
+                <__C as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                #rkyv_path::columns::check_column_lengths(&[
+                    #(self.#members.len(),)*
+                ])
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+#[cfg(feature = "bytecheck")]
+use syn::parse_quote;
+use syn::{
+    Data, DataStruct, DeriveInput, Error, Expr, Field, Generics, Member,
+};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+    util::iter_fields,
+};
+
+pub struct CompatField<'a> {
+    pub field: &'a Field,
+    pub member: Member,
+    /// The version that the field was added in, if it's a `default` field.
+    pub since: Option<Expr>,
+}
+
+/// Returns the fields of a versioned struct with `#[rkyv(default)]` fields, or
+/// `None` if the type has no `default` fields.
+pub fn compat_fields<'a>(
+    input: &'a DeriveInput,
+    attributes: &Attributes,
+) -> Result<Option<Vec<CompatField<'a>>>, Error> {
+    let mut first_default = None;
+    for field in iter_fields(&input.data) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if let Some(default) = field_attrs.default {
+            first_default = Some(default);
+            break;
+        }
+    }
+    let Some(default) = first_default else {
+        return Ok(None);
+    };
+
+    let Data::Struct(DataStruct { fields, .. }) = &input.data else {
+        return Err(Error::new_spanned(
+            default,
+            "`default` may only be used on struct fields",
+        ));
+    };
+    let Some(version) = &attributes.version else {
+        return Err(Error::new_spanned(
+            default,
+            "`default` fields require `#[rkyv(version = ..)]`",
+        ));
+    };
+    if attributes.as_type.is_some() || attributes.remote.is_some() {
+        return Err(Error::new_spanned(
+            default,
+            "`default` may not be used with `as = ...` or remote derive",
+        ));
+    }
+
+    let mut result = Vec::new();
+    let mut seen_default = false;
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let since = if field_attrs.default.is_some() {
+            seen_default = true;
+            Some(field_attrs.since.unwrap_or_else(|| version.clone()))
+        } else if seen_default {
+            return Err(Error::new_spanned(
+                field,
+                "fields after a `default` field must also be `default`",
+            ));
+        } else {
+            None
+        };
+
+        result.push(CompatField {
+            field,
+            member,
+            since,
+        });
+    }
+
+    Ok(Some(result))
+}
+
+/// Returns the oldest version which can be read as a type with the given
+/// fields, which is one less than the version the first `default` field was
+/// added in.
+pub fn min_version(fields: &[CompatField<'_>]) -> Option<TokenStream> {
+    fields.iter().find_map(|field| {
+        let since = field.since.as_ref()?;
+        Some(quote! { u32::saturating_sub(#since, 1) })
+    })
+}
+
+pub fn impl_compat(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &[CompatField<'_>],
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut size_checks = TokenStream::new();
+    let mut prev_end = quote! { 0 };
+    for CompatField {
+        field,
+        member,
+        since,
+    } in fields.iter()
+    {
+        if let Some(since) = since {
+            size_checks.extend(quote! {
+                if version < #since {
+                    return #prev_end;
+                }
+            });
+        }
+
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived = field_attrs.archived(rkyv_path, field);
+        prev_end = quote! {
+            ::core::mem::offset_of!(Self, #member)
+                + ::core::mem::size_of::<#archived>()
+        };
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    #[cfg(not(feature = "bytecheck"))]
+    let check_compat_impl = TokenStream::new();
+    #[cfg(feature = "bytecheck")]
+    let check_compat_impl =
+        generate_check_compat_impl(printing, generics, attributes, fields)?;
+
+    Ok(quote! {
+        // SAFETY: `MIN_VERSION` is one less than the version the first
+        // `default` field was added in. `size_at_version` returns the end of
+        // the last field present at each version, and the size of the whole
+        // archived type once all of the fields are present.
+        unsafe impl #impl_generics #rkyv_path::version::ArchivedCompat
+            for #archived_type
+        #where_clause
+        {
+            fn size_at_version(version: u32) -> usize {
+                #size_checks
+                ::core::mem::size_of::<Self>()
+            }
+        }
+
+        #check_compat_impl
+    })
+}
+
+#[cfg(feature = "bytecheck")]
+fn generate_check_compat_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &[CompatField<'_>],
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut check_generics = generics.clone();
+    check_generics.params.push(parse_quote! { __C });
+    let where_clause = check_generics.make_where_clause();
+    where_clause.predicates.extend::<[syn::WherePredicate; 2]>([
+        parse_quote! { __C: #rkyv_path::rancor::Fallible + ?Sized },
+        parse_quote! {
+            <__C as #rkyv_path::rancor::Fallible>::Error:
+                #rkyv_path::rancor::Source
+        },
+    ]);
+
+    let mut check_fields = TokenStream::new();
+    for CompatField {
+        field,
+        member,
+        since,
+    } in fields.iter()
+    {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived = field_attrs.archived(rkyv_path, field);
+        if field_attrs.omit_bounds.is_none() {
+            where_clause.predicates.push(parse_quote! {
+                #archived: #rkyv_path::bytecheck::CheckBytes<__C>
+            });
+        }
+
+        let check = quote! {
+            // SAFETY: The caller has guaranteed that `value` points to enough
+            // bytes for all of the fields present at `version`.
+            unsafe {
+                <
+                    #archived as #rkyv_path::bytecheck::CheckBytes<__C>
+                >::check_bytes(
+                    ::core::ptr::addr_of!((*value).#member),
+                    context,
+                )?;
+            }
+        };
+        if let Some(since) = since {
+            check_fields.extend(quote! {
+                if version >= #since {
+                    #check
+                }
+            });
+        } else {
+            check_fields.extend(check);
+        }
+    }
+
+    let (impl_generics, _, where_clause) = check_generics.split_for_impl();
+
+    Ok(quote! {
+        // SAFETY: `check_compat` checks every field present at `version`.
+        unsafe impl #impl_generics #rkyv_path::version::CheckCompat<__C>
+            for #archived_type
+        #where_clause
+        {
+            unsafe fn check_compat(
+                value: *const Self,
+                version: u32,
+                context: &mut __C,
+            ) -> ::core::result::Result<
+                (),
+                <__C as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                #check_fields
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
@@ -1,8 +1,9 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{
-    parse_quote, spanned::Spanned as _, DataEnum, Error, Field, Fields,
-    Generics, Ident, Index, Member, Path,
+    parse_quote, punctuated::Punctuated, spanned::Spanned as _, Attribute,
+    DataEnum, Error, Field, Fields, Generics, Ident, Index, Member, Meta, Path,
+    Token, Variant, WhereClause,
 };
 
 use crate::{
@@ -10,14 +11,167 @@ use crate::{
         archived_doc, printing::Printing, resolver_doc, resolver_variant_doc,
         variant_doc,
     },
-    attributes::{Attributes, FieldAttributes},
+    attributes::{Attributes, FieldAttributes, VariantAttributes},
     util::{strip_generics_from_path, strip_raw},
 };
 
+/// The `#[rkyv(other)]` variant of an enum and the hidden variants which cover
+/// all of the discriminants that the archived enum does not otherwise use.
+struct OtherVariant<'a> {
+    variant: &'a Ident,
+    unknown: Vec<Ident>,
+}
+
+impl<'a> OtherVariant<'a> {
+    fn parse(
+        attributes: &Attributes,
+        data: &'a DataEnum,
+    ) -> Result<Option<Self>, Error> {
+        let mut other = None;
+        for variant in data.variants.iter() {
+            let variant_attrs = VariantAttributes::parse(attributes, variant)?;
+            if let Some((ref path, _)) = other {
+                return Err(Error::new_spanned(
+                    path,
+                    "Only the very last variant may be denoted with \
+                     `#[rkyv(other)]`",
+                ));
+            }
+            if variant_attrs.other.is_some() {
+                other = variant_attrs.other.map(|path| (path, &variant.ident));
+            }
+        }
+
+        let Some((_, variant)) = other else {
+            return Ok(None);
+        };
+
+        if let Some((_, expr)) =
+            data.variants.iter().find_map(|v| v.discriminant.as_ref())
+        {
+            return Err(Error::new_spanned(
+                expr,
+                "enums with an `#[rkyv(other)]` variant may not have explicit \
+                 discriminants",
+            ));
+        }
+
+        // Types archived `as` another type don't generate an archived enum, so
+        // there are no unused discriminants to cover.
+        let unknown = if attributes.as_type.is_none() {
+            (data.variants.len()..256)
+                .map(|i| format_ident!("__RkyvUnknown{}", i))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(Some(Self { variant, unknown }))
+    }
+}
+
+/// Returns a pattern which matches the given unit variant of the archived enum.
+/// The `other` variant also matches all of the unknown variants.
+fn unit_pattern(
+    archived_name: &Ident,
+    variant: &Ident,
+    other: Option<&OtherVariant<'_>>,
+) -> TokenStream {
+    match other {
+        Some(other) if other.variant == variant => {
+            let unknown = &other.unknown;
+            quote! {
+                #archived_name::#variant #(| #archived_name::#unknown)*
+            }
+        }
+        _ => quote! { #archived_name::#variant },
+    }
+}
+
+/// Returns a pattern which matches the archived counterpart of `variant` and
+/// binds its fields to `bindings`.
+fn archived_variant_pattern(
+    archived_name: &Ident,
+    variant: &Variant,
+    bindings: &[Ident],
+    other: Option<&OtherVariant<'_>>,
+) -> TokenStream {
+    let variant_name = &variant.ident;
+    match variant.fields {
+        Fields::Named(ref fields) => {
+            let field_names = fields.named.iter().map(|f| &f.ident);
+            quote! {
+                #archived_name::#variant_name {
+                    #(#field_names: #bindings,)*
+                }
+            }
+        }
+        Fields::Unnamed(_) => quote! {
+            #archived_name::#variant_name(#(#bindings,)*)
+        },
+        Fields::Unit => unit_pattern(archived_name, variant_name, other),
+    }
+}
+
+fn field_bindings(variant: &Variant, prefix: &str) -> Vec<Ident> {
+    variant
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| Ident::new(&format!("{}_{}", prefix, i), f.span()))
+        .collect()
+}
+
+/// Returns the type of the discriminant of the original enum, which is the
+/// integer type named by its `#[repr(..)]` or `isize` if there is none.
+fn native_repr(attrs: &[Attribute]) -> Result<TokenStream, Error> {
+    const INTS: [&str; 12] = [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+        "i128", "isize",
+    ];
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        let metas = attr
+            .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        for meta in metas {
+            if let Meta::Path(path) = meta {
+                if INTS.iter().any(|int| path.is_ident(int)) {
+                    return Ok(quote! { #path });
+                }
+            }
+        }
+    }
+
+    Ok(quote! { isize })
+}
+
+/// Adds `bound` to the where clause for the archived type of each field which
+/// does not omit its bounds.
+fn archived_field_bounds(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+    bound: TokenStream,
+) -> Result<WhereClause, Error> {
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in data.variants.iter().flat_map(|v| v.fields.iter()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived = field_attrs.archived(&printing.rkyv_path, field);
+            where_clause
+                .predicates
+                .push(parse_quote! { #archived: #bound });
+        }
+    }
+    Ok(where_clause)
+}
+
 pub fn impl_enum(
     printing: &Printing,
     generics: &Generics,
     attributes: &Attributes,
+    attrs: &[Attribute],
     data: &DataEnum,
 ) -> Result<TokenStream, Error> {
     let Printing {
@@ -35,12 +189,39 @@ pub fn impl_enum(
         ));
     }
 
+    if let Some(ref seal) = attributes.seal {
+        return Err(Error::new_spanned(
+            seal,
+            "`seal` may only be used on structs",
+        ));
+    }
+
+    if let Some(ref field_offsets) = attributes.field_offsets {
+        return Err(Error::new_spanned(
+            field_offsets,
+            "`field_offsets` may only be used on structs",
+        ));
+    }
+
+    if let Some(ref getters) = attributes.getters {
+        return Err(Error::new_spanned(
+            getters,
+            "`getters` may only be used on structs",
+        ));
+    }
+
+    let other = OtherVariant::parse(attributes, data)?;
+
     let mut public = TokenStream::new();
     let mut private = TokenStream::new();
 
     if attributes.as_type.is_none() {
         public.extend(generate_archived_type(
-            printing, attributes, generics, data,
+            printing,
+            attributes,
+            generics,
+            data,
+            other.as_ref(),
         )?);
 
         private.extend(generate_niching_impls(
@@ -61,11 +242,16 @@ pub fn impl_enum(
             .unzip();
         quote! { #ident #eq #expr }
     });
+    let unknown_tags = other.iter().flat_map(|other| &other.unknown);
     private.extend(quote! {
         #[derive(PartialEq, PartialOrd)]
         #[repr(u8)]
         enum ArchivedTag {
             #(#archived_variant_tags,)*
+            #(
+                #[allow(dead_code)]
+                #unknown_tags,
+            )*
         }
     });
 
@@ -73,21 +259,87 @@ pub fn impl_enum(
         printing, attributes, generics, data,
     )?);
 
+    if attributes.diff.is_some() {
+        public
+            .extend(generate_diff_impl(printing, attributes, generics, data)?);
+    }
+
+    if attributes.type_hash.is_some() {
+        public.extend(generate_type_hash_impl(
+            printing, attributes, generics, data,
+        )?);
+    }
+
+    if attributes.stable_format.is_some() {
+        public.extend(generate_stable_format_impl(
+            printing, attributes, generics, data,
+        )?);
+    }
+
+    if attributes.layout.is_some() {
+        private.extend(generate_layout_impl(
+            printing, attributes, generics, data,
+        )?);
+    }
+
+    if attributes.derive_serde.is_some() {
+        public.extend(generate_serde_impls(
+            printing,
+            attributes,
+            generics,
+            data,
+            other.as_ref(),
+        )?);
+    }
+
     if let Some(ref compares) = attributes.compares {
         for compare in compares {
             if compare.is_ident("PartialEq") {
                 public.extend(generate_partial_eq_impl(
-                    printing, attributes, generics, data,
+                    printing,
+                    attributes,
+                    generics,
+                    data,
+                    other.as_ref(),
                 )?);
             } else if compare.is_ident("PartialOrd") {
                 private.extend(generate_partial_ord_impl(
-                    printing, attributes, generics, data,
+                    printing,
+                    attributes,
+                    generics,
+                    data,
+                    other.as_ref(),
+                )?);
+            } else if compare.is_ident("Eq") {
+                public.extend(generate_eq_impl(
+                    printing,
+                    attributes,
+                    generics,
+                    data,
+                    other.as_ref(),
+                )?);
+            } else if compare.is_ident("Ord") {
+                private.extend(generate_ord_impl(
+                    printing,
+                    attributes,
+                    generics,
+                    data,
+                    other.as_ref(),
+                )?);
+            } else if compare.is_ident("Hash") {
+                private.extend(generate_hash_impl(
+                    printing,
+                    attributes,
+                    generics,
+                    data,
+                    other.as_ref(),
+                    &native_repr(attrs)?,
                 )?);
             } else {
                 return Err(Error::new_spanned(
                     compare,
                     "unrecognized compare argument, supported compares are \
-                     PartialEq (PartialOrd is not supported for enums)",
+                     PartialEq, PartialOrd, Eq, Ord, and Hash",
                 ));
             }
         }
@@ -176,6 +428,7 @@ fn generate_archived_type(
     attributes: &Attributes,
     generics: &Generics,
     data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
 ) -> Result<TokenStream, Error> {
     let Printing {
         rkyv_path,
@@ -236,6 +489,16 @@ fn generate_archived_type(
         });
     }
 
+    // Fill the remaining discriminants with hidden variants so that archived
+    // data written by a newer version of the enum still passes validation.
+    for unknown in other.iter().flat_map(|other| &other.unknown) {
+        archived_variants.extend(quote! {
+            #[doc(hidden)]
+            #[allow(dead_code)]
+            #unknown,
+        });
+    }
+
     let where_clause = &generics.where_clause;
     let archived_doc = archived_doc(name);
     Ok(quote! {
@@ -531,11 +794,394 @@ fn generate_variant_structs(
     Ok(result)
 }
 
+fn generate_diff_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_name,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in data.variants.iter().flat_map(|v| v.fields.iter()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived = field_attrs.archived(rkyv_path, field);
+            where_clause
+                .predicates
+                .push(parse_quote! { #archived: #rkyv_path::diff::Diff });
+        }
+    }
+
+    let variant_arms = data.variants.iter().map(|v| {
+        let variant = &v.ident;
+        let members = v.fields.members().collect::<Vec<_>>();
+        let names = members.iter().map(|member| match member {
+            Member::Named(ident) => strip_raw(ident),
+            Member::Unnamed(index) => index.index.to_string(),
+        });
+        let (self_fields, other_fields) = (0..members.len())
+            .map(|i| {
+                (format_ident!("self_{}", i), format_ident!("other_{}", i))
+            })
+            .unzip::<_, _, Vec<_>, Vec<_>>();
+
+        quote! {
+            (
+                #archived_name::#variant { #(#members: #self_fields,)* },
+                #archived_name::#variant { #(#members: #other_fields,)* },
+            ) => {
+                #(differ.field(#names, #self_fields, #other_fields);)*
+            }
+        }
+    });
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::diff::Diff for #archived_type
+        #where_clause
+        {
+            fn diff(
+                &self,
+                other: &Self,
+                differ: &mut #rkyv_path::diff::Differ,
+            ) {
+                match (self, other) {
+                    #(#variant_arms)*
+                    #[allow(unreachable_patterns)]
+                    _ => differ.changed(),
+                }
+            }
+        }
+    })
+}
+
+fn generate_type_hash_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    let mut variant_hashes = Vec::new();
+    for variant in data.variants.iter() {
+        let variant_name = strip_raw(&variant.ident);
+        let field_count = variant.fields.len() as u64;
+        let mut field_hashes = Vec::new();
+        for (field, member) in
+            variant.fields.iter().zip(variant.fields.members())
+        {
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+            let archived = field_attrs.archived(rkyv_path, field);
+            if field_attrs.omit_bounds.is_none() {
+                where_clause.predicates.push(parse_quote! {
+                    #archived: #rkyv_path::type_hash::TypeHash
+                });
+            }
+
+            let name = match member {
+                Member::Named(ref ident) => strip_raw(ident),
+                Member::Unnamed(ref index) => index.index.to_string(),
+            };
+            field_hashes.push(quote! {
+                .write_str(#name)
+                .write_type::<#archived>()
+            });
+        }
+
+        variant_hashes.push(quote! {
+            .write_str(#variant_name)
+            .write_u64(#field_count)
+            #(#field_hashes)*
+        });
+    }
+    let variant_count = variant_hashes.len() as u64;
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::type_hash::TypeHash for #archived_type
+        #where_clause
+        {
+            const TYPE_HASH: u64 = #rkyv_path::type_hash::TypeHasher::new()
+                .write_str("enum")
+                .write_u64(#variant_count)
+                #(#variant_hashes)*
+                .write_layout::<Self>()
+                .finish();
+        }
+    })
+}
+
+fn generate_stable_format_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in data.variants.iter().flat_map(|v| v.fields.iter()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived = field_attrs.archived(rkyv_path, field);
+            where_clause.predicates.push(parse_quote! {
+                #archived: #rkyv_path::stable::StableFormat
+            });
+        }
+    }
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::stable::StableFormat for #archived_type
+        #where_clause
+        {
+        }
+    })
+}
+
+fn generate_layout_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        name,
+        archived_type,
+        ..
+    } = printing;
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    let mut variant_layouts = Vec::new();
+    for variant in data.variants.iter() {
+        let variant_name = strip_raw(&variant.ident);
+        let archived_variant_name =
+            format_ident!("ArchivedVariant{}", variant_name);
+        let mut field_layouts = Vec::new();
+        for (field, member) in
+            variant.fields.iter().zip(variant.fields.members())
+        {
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+            let archived = field_attrs.archived(rkyv_path, field);
+            if field_attrs.omit_bounds.is_none() {
+                where_clause.predicates.push(parse_quote! {
+                    #archived: #rkyv_path::layout::DescribeLayout
+                });
+            }
+
+            // The first field of each variant struct is the tag, so the
+            // fields of tuple variants are offset by one.
+            let (field_name, variant_member) = match member {
+                Member::Named(ref ident) => {
+                    (strip_raw(ident), Member::Named(ident.clone()))
+                }
+                Member::Unnamed(ref index) => (
+                    index.index.to_string(),
+                    Member::Unnamed(Index::from(index.index as usize + 1)),
+                ),
+            };
+            field_layouts.push(quote! {
+                #rkyv_path::layout::FieldLayout::new(
+                    #field_name,
+                    ::core::mem::offset_of!(
+                        #archived_variant_name #ty_generics,
+                        #variant_member
+                    ),
+                    #rkyv_path::layout::layout_of::<#archived>(),
+                )
+            });
+        }
+
+        variant_layouts.push(quote! {
+            #rkyv_path::layout::VariantLayout::new(
+                #variant_name,
+                ::core::convert::From::from([#(#field_layouts,)*]),
+            )
+        });
+    }
+    let name = strip_raw(name);
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::layout::DescribeLayout
+            for #archived_type
+        #where_clause
+        {
+            fn describe_layout() -> #rkyv_path::layout::LayoutDescriptor {
+                #rkyv_path::layout::LayoutDescriptor::new::<Self>(
+                    #name,
+                    #rkyv_path::layout::LayoutKind::Enum {
+                        variants: ::core::convert::From::from([
+                            #(#variant_layouts,)*
+                        ]),
+                    },
+                )
+            }
+        }
+    })
+}
+
+fn generate_serde_impls(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        name,
+        archived_name,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in data.variants.iter().flat_map(|v| v.fields.iter()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived = field_attrs.archived(rkyv_path, field);
+            where_clause.predicates.push(parse_quote! {
+                #archived: #rkyv_path::serde::SerializeArchived
+            });
+        }
+    }
+
+    let serde = quote! { #rkyv_path::serde::__serde };
+    let name = strip_raw(name);
+    let variant_arms = data.variants.iter().enumerate().map(|(index, v)| {
+        let variant = &v.ident;
+        let variant_name = strip_raw(variant);
+        let index = index as u32;
+        let len = v.fields.len();
+        let members = v.fields.members().collect::<Vec<_>>();
+        let binds = (0..len)
+            .map(|i| format_ident!("field_{}", i))
+            .collect::<Vec<_>>();
+
+        match v.fields {
+            Fields::Named(_) => {
+                let names = members.iter().map(|member| match member {
+                    Member::Named(ident) => strip_raw(ident),
+                    Member::Unnamed(_) => unreachable!(),
+                });
+                quote! {
+                    #archived_name::#variant { #(#members: #binds,)* } => {
+                        use #serde::ser::SerializeStructVariant as _;
+
+                        let mut state = serializer.serialize_struct_variant(
+                            #name,
+                            #index,
+                            #variant_name,
+                            #len,
+                        )?;
+                        #(
+                            state.serialize_field(
+                                #names,
+                                &#rkyv_path::serde::Serde(#binds),
+                            )?;
+                        )*
+                        state.end()
+                    }
+                }
+            }
+            Fields::Unnamed(_) if len == 1 => quote! {
+                #archived_name::#variant(field_0) => {
+                    serializer.serialize_newtype_variant(
+                        #name,
+                        #index,
+                        #variant_name,
+                        &#rkyv_path::serde::Serde(field_0),
+                    )
+                }
+            },
+            Fields::Unnamed(_) => quote! {
+                #archived_name::#variant(#(#binds,)*) => {
+                    use #serde::ser::SerializeTupleVariant as _;
+
+                    let mut state = serializer.serialize_tuple_variant(
+                        #name,
+                        #index,
+                        #variant_name,
+                        #len,
+                    )?;
+                    #(
+                        state.serialize_field(
+                            &#rkyv_path::serde::Serde(#binds),
+                        )?;
+                    )*
+                    state.end()
+                }
+            },
+            Fields::Unit => {
+                let pattern = unit_pattern(archived_name, variant, other);
+                quote! {
+                    #pattern => serializer.serialize_unit_variant(
+                        #name,
+                        #index,
+                        #variant_name,
+                    ),
+                }
+            }
+        }
+    });
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::serde::SerializeArchived
+            for #archived_type
+        #where_clause
+        {
+            fn serialize_archived<__S: #serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                match self {
+                    #(#variant_arms)*
+                }
+            }
+        }
+
+        impl #impl_generics #serde::Serialize for #archived_type
+        #where_clause
+        {
+            fn serialize<__S: #serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #rkyv_path::serde::SerializeArchived::serialize_archived(
+                    self,
+                    serializer,
+                )
+            }
+        }
+    })
+}
+
 fn generate_partial_eq_impl(
     printing: &Printing,
     attributes: &Attributes,
     generics: &Generics,
     data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
 ) -> Result<TokenStream, Error> {
     let Printing {
         archived_name,
@@ -601,13 +1247,16 @@ fn generate_partial_eq_impl(
                     }
                 }
             }
-            Fields::Unit => quote! {
-                #name::#variant => match other {
-                    #archived_name::#variant => true,
-                    #[allow(unreachable_patterns)]
-                    _ => false,
+            Fields::Unit => {
+                let pattern = unit_pattern(archived_name, variant, other);
+                quote! {
+                    #name::#variant => match other {
+                        #pattern => true,
+                        #[allow(unreachable_patterns)]
+                        _ => false,
+                    }
                 }
-            },
+            }
         }
     });
 
@@ -637,6 +1286,7 @@ fn generate_partial_ord_impl(
     attributes: &Attributes,
     generics: &Generics,
     data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
 ) -> Result<TokenStream, Error> {
     let Printing {
         archived_name,
@@ -681,9 +1331,10 @@ fn generate_partial_ord_impl(
             Fields::Unnamed(_) => quote! {
                 #archived_name::#variant ( .. ) => ArchivedTag::#variant
             },
-            Fields::Unit => quote! {
-                #archived_name::#variant => ArchivedTag::#variant
-            },
+            Fields::Unit => {
+                let pattern = unit_pattern(archived_name, variant, other);
+                quote! { #pattern => ArchivedTag::#variant }
+            }
         }
     });
 
@@ -748,15 +1399,16 @@ fn generate_partial_ord_impl(
                     }
                 }
             }
-            Fields::Unit => quote! {
-                #name::#variant => match other {
-                    #archived_name::#variant => {
-                        Some(::core::cmp::Ordering::Equal)
+            Fields::Unit => {
+                let pattern = unit_pattern(archived_name, variant, other);
+                quote! {
+                    #name::#variant => match other {
+                        #pattern => Some(::core::cmp::Ordering::Equal),
+                        #[allow(unreachable_patterns)]
+                        _ => unsafe { ::core::hint::unreachable_unchecked() },
                     }
-                    #[allow(unreachable_patterns)]
-                    _ => unsafe { ::core::hint::unreachable_unchecked() },
                 }
-            },
+            }
         }
     });
 
@@ -801,6 +1453,201 @@ fn generate_partial_ord_impl(
     })
 }
 
+fn generate_eq_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        archived_name,
+        archived_type,
+        ..
+    } = printing;
+    let (impl_generics, ..) = generics.split_for_impl();
+    let where_clause = archived_field_bounds(
+        printing,
+        attributes,
+        generics,
+        data,
+        quote! { Eq },
+    )?;
+
+    let variant_impls = data.variants.iter().map(|v| {
+        let self_fields = field_bindings(v, "self");
+        let other_fields = field_bindings(v, "other");
+        let self_pattern =
+            archived_variant_pattern(archived_name, v, &self_fields, other);
+        let other_pattern =
+            archived_variant_pattern(archived_name, v, &other_fields, other);
+
+        quote! {
+            #self_pattern => match other {
+                #other_pattern => {
+                    true #(&& #self_fields == #other_fields)*
+                }
+                #[allow(unreachable_patterns)]
+                _ => false,
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics PartialEq for #archived_type #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                match self {
+                    #(#variant_impls,)*
+                }
+            }
+        }
+
+        impl #impl_generics Eq for #archived_type #where_clause {}
+    })
+}
+
+fn generate_ord_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        archived_name,
+        archived_type,
+        ..
+    } = printing;
+    let (impl_generics, ..) = generics.split_for_impl();
+    let where_clause = archived_field_bounds(
+        printing,
+        attributes,
+        generics,
+        data,
+        quote! { Ord },
+    )?;
+
+    let tags = data.variants.iter().map(|v| {
+        let variant = &v.ident;
+        let pattern = match v.fields {
+            Fields::Named(_) => quote! { #archived_name::#variant { .. } },
+            Fields::Unnamed(_) => quote! { #archived_name::#variant(..) },
+            Fields::Unit => unit_pattern(archived_name, variant, other),
+        };
+        quote! { #pattern => ArchivedTag::#variant as u8 }
+    });
+
+    let variant_impls = data.variants.iter().map(|v| {
+        let self_fields = field_bindings(v, "self");
+        let other_fields = field_bindings(v, "other");
+        let self_pattern =
+            archived_variant_pattern(archived_name, v, &self_fields, other);
+        let other_pattern =
+            archived_variant_pattern(archived_name, v, &other_fields, other);
+
+        quote! {
+            #self_pattern => match other {
+                #other_pattern => {
+                    #(
+                        match #self_fields.cmp(#other_fields) {
+                            ::core::cmp::Ordering::Equal => (),
+                            cmp => return cmp,
+                        }
+                    )*
+                    ::core::cmp::Ordering::Equal
+                }
+                #[allow(unreachable_patterns)]
+                _ => unsafe { ::core::hint::unreachable_unchecked() },
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics PartialOrd for #archived_type #where_clause {
+            fn partial_cmp(
+                &self,
+                other: &Self,
+            ) -> Option<::core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl #impl_generics Ord for #archived_type #where_clause {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                let tag = |value: &Self| match value { #(#tags,)* };
+                let (self_tag, other_tag) = (tag(self), tag(other));
+                if self_tag != other_tag {
+                    return self_tag.cmp(&other_tag);
+                }
+                match self {
+                    #(#variant_impls,)*
+                }
+            }
+        }
+    })
+}
+
+fn generate_hash_impl(
+    printing: &Printing,
+    attributes: &Attributes,
+    generics: &Generics,
+    data: &DataEnum,
+    other: Option<&OtherVariant<'_>>,
+    repr: &TokenStream,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        archived_name,
+        archived_type,
+        ..
+    } = printing;
+    let (impl_generics, ..) = generics.split_for_impl();
+    let where_clause = archived_field_bounds(
+        printing,
+        attributes,
+        generics,
+        data,
+        quote! { ::core::hash::Hash },
+    )?;
+
+    // `derive(Hash)` hashes the discriminant of the original enum as its
+    // `repr` type, but skips it entirely for enums with a single variant.
+    let hash_tag = data.variants.len() > 1;
+    let variant_impls = data.variants.iter().map(|v| {
+        let variant = &v.ident;
+        let fields = field_bindings(v, "field");
+        let pattern =
+            archived_variant_pattern(archived_name, v, &fields, other);
+        let tag = hash_tag.then(|| {
+            quote! {
+                ::core::hash::Hash::hash(
+                    &(ArchivedTag::#variant as #repr),
+                    state,
+                );
+            }
+        });
+
+        quote! {
+            #pattern => {
+                #tag
+                #(::core::hash::Hash::hash(#fields, state);)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #archived_type
+        #where_clause
+        {
+            #[allow(unused_variables)]
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                match self {
+                    #(#variant_impls,)*
+                }
+            }
+        }
+    })
+}
+
 fn generate_niching_impls(
     printing: &Printing,
     attributes: &Attributes,
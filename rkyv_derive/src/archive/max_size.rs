@@ -0,0 +1,100 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Path};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+    util::iter_fields,
+};
+
+pub fn impl_max_size(
+    input: &DeriveInput,
+    printing: &Printing,
+    attributes: &Attributes,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path, name, ..
+    } = printing;
+
+    let mut where_clause = input.generics.where_clause.clone().unwrap();
+    for field in iter_fields(&input.data) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() && field_attrs.bit_pack.is_none() {
+            let ty = &field.ty;
+            where_clause.predicates.push(match field_attrs.with {
+                Some(ref with) => parse_quote! {
+                    #with: #rkyv_path::max_size::MaxSizeWith<#ty>
+                },
+                None => parse_quote! {
+                    #ty: #rkyv_path::max_size::MaxSize
+                },
+            });
+        }
+    }
+
+    let size = match &input.data {
+        Data::Struct(data) => fields_size(rkyv_path, attributes, &data.fields)?,
+        Data::Enum(data) => {
+            let variant_sizes = data
+                .variants
+                .iter()
+                .map(|v| fields_size(rkyv_path, attributes, &v.fields))
+                .collect::<Result<Vec<_>, Error>>()?;
+            quote! {
+                {
+                    let mut max = 0;
+                    #(
+                        let size = #variant_sizes;
+                        if size > max {
+                            max = size;
+                        }
+                    )*
+                    max
+                }
+            }
+        }
+        // Unions are rejected when deriving `Archive`.
+        Data::Union(_) => return Ok(TokenStream::new()),
+    };
+
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::max_size::MaxSize for #name #ty_generics
+        #where_clause
+        {
+            const MAX_DEPENDENCIES_SIZE: usize = #size;
+        }
+    })
+}
+
+fn fields_size(
+    rkyv_path: &Path,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let sizes = fields
+        .iter()
+        .map(|field| {
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+            let ty = &field.ty;
+            // Packed fields are stored inline and have no dependencies.
+            if field_attrs.bit_pack.is_some() {
+                return Ok(quote! { 0 });
+            }
+            Ok(match field_attrs.with {
+                Some(ref with) => quote! {
+                    <
+                        #with as #rkyv_path::max_size::MaxSizeWith<#ty>
+                    >::MAX_DEPENDENCIES_SIZE
+                },
+                None => quote! {
+                    <#ty as #rkyv_path::max_size::MaxSize>::MAX_DEPENDENCIES_SIZE
+                },
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(quote! { 0 #(+ #sizes)* })
+}
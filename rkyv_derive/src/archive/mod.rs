@@ -1,15 +1,21 @@
+mod bit_pack;
+pub mod columns;
+pub mod compat;
 mod r#enum;
+mod max_size;
+mod niche_discriminant;
 pub mod printing;
 mod r#struct;
+mod transparent;
 
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
-use syn::{parse_quote, Data, DataStruct, DeriveInput, Error, Ident};
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Error, Expr, Ident};
 
 use crate::{
     archive::printing::Printing,
     attributes::{Attributes, FieldAttributes},
-    util::iter_fields,
+    util::{iter_fields, niched_variants, single_field},
 };
 
 pub fn derive(input: &mut DeriveInput) -> Result<TokenStream, Error> {
@@ -50,6 +56,36 @@ fn derive_archive_impl(
             .extend(field_attrs.archive_bound(&printing.rkyv_path, field));
     }
 
+    let max_size = match attributes.max_size {
+        Some(_) => max_size::impl_max_size(input, &printing, attributes)?,
+        None => TokenStream::new(),
+    };
+
+    if attributes.transparent.is_some() {
+        let (field, member) = single_field(&input.data).unwrap();
+        let mut result = transparent::impl_transparent(
+            &printing,
+            &input.generics,
+            attributes,
+            field,
+            &member,
+        )?;
+        result.extend(max_size);
+        return Ok(result);
+    }
+
+    if attributes.niche_discriminant.is_some() {
+        let variants = niched_variants(&input.data).unwrap();
+        let mut result = niche_discriminant::impl_niche_discriminant(
+            &printing,
+            &input.generics,
+            attributes,
+            &variants,
+        )?;
+        result.extend(max_size);
+        return Ok(result);
+    }
+
     let mut result = match &input.data {
         Data::Struct(DataStruct { fields, .. }) => r#struct::impl_struct(
             &printing,
@@ -57,9 +93,13 @@ fn derive_archive_impl(
             attributes,
             fields,
         )?,
-        Data::Enum(enm) => {
-            r#enum::impl_enum(&printing, &input.generics, attributes, enm)?
-        }
+        Data::Enum(enm) => r#enum::impl_enum(
+            &printing,
+            &input.generics,
+            attributes,
+            &input.attrs,
+            enm,
+        )?,
         Data::Union(_) => {
             return Err(Error::new_spanned(
                 input,
@@ -68,14 +108,119 @@ fn derive_archive_impl(
         }
     };
 
+    if let Some(ref columns) = attributes.columns {
+        let fields = columns::columns_fields(input, columns)?;
+        result.extend(columns::impl_columns(
+            &printing,
+            &input.generics,
+            attributes,
+            fields,
+        )?);
+    }
+
     if attributes.as_type.is_none() {
         result
             .extend(impl_auto_trait(input, &printing, attributes, "Portable")?);
+        result.extend(impl_as_native(input, &printing));
+    }
+
+    let compat_fields = compat::compat_fields(input, attributes)?;
+
+    if let Some(ref version) = attributes.version {
+        let min_version =
+            compat_fields.as_deref().and_then(compat::min_version);
+        result.extend(impl_versioned(
+            input,
+            &printing,
+            attributes,
+            version,
+            min_version,
+        ));
+    }
+
+    result.extend(max_size);
+
+    if let Some(fields) = compat_fields {
+        result.extend(compat::impl_compat(
+            &printing,
+            &input.generics,
+            attributes,
+            &fields,
+        )?);
     }
 
     Ok(result)
 }
 
+fn impl_versioned(
+    input: &DeriveInput,
+    printing: &Printing,
+    attributes: &Attributes,
+    version: &Expr,
+    min_version: Option<TokenStream>,
+) -> TokenStream {
+    let Printing {
+        rkyv_path,
+        name,
+        archived_name,
+        ..
+    } = printing;
+
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+
+    let min_version = min_version.map(|min_version| {
+        quote! { const MIN_VERSION: u32 = #min_version; }
+    });
+
+    let mut result = quote! {
+        impl #impl_generics #rkyv_path::traits::Versioned
+            for #name #ty_generics
+        #where_clause
+        {
+            const VERSION: u32 = #version;
+            #min_version
+        }
+    };
+
+    if attributes.as_type.is_none() {
+        result.extend(quote! {
+            impl #impl_generics #rkyv_path::traits::Versioned
+                for #archived_name #ty_generics
+            #where_clause
+            {
+                const VERSION: u32 = #version;
+                #min_version
+            }
+        });
+    }
+
+    result
+}
+
+fn impl_as_native(input: &DeriveInput, printing: &Printing) -> TokenStream {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics #rkyv_path::native::AsNative for #archived_type
+        #where_clause
+        {
+            type Native<'__a> = &'__a Self where Self: '__a;
+
+            #[inline]
+            fn as_native(&self) -> Self::Native<'_> {
+                self
+            }
+        }
+    }
+}
+
 fn impl_auto_trait(
     input: &DeriveInput,
     printing: &Printing,
@@ -90,6 +235,9 @@ fn impl_auto_trait(
 
     for field in iter_fields(&input.data) {
         let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.bit_pack.is_some() {
+            continue;
+        }
         let archived_field_ty = field_attrs.archived(rkyv_path, field);
 
         where_clause.predicates.push(parse_quote! {
@@ -0,0 +1,85 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Error, Generics};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+    util::NichedVariants,
+};
+
+pub fn impl_niche_discriminant(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    variants: &NichedVariants<'_>,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path, name, ..
+    } = printing;
+    let NichedVariants {
+        unit,
+        payload,
+        field,
+        member,
+    } = variants;
+
+    let field_attrs = FieldAttributes::parse(attributes, field)?;
+    if field_attrs.niches.len() > 1 {
+        return Err(Error::new_spanned(
+            field,
+            "the payload of an enum with `niche_discriminant` may only have \
+             one niche",
+        ));
+    }
+
+    let archived = field_attrs.archived(rkyv_path, field);
+    let resolver = field_attrs.resolver(rkyv_path, field);
+    let resolve = field_attrs.resolve(rkyv_path, field);
+    let niching = field_attrs.discriminant_niching(rkyv_path);
+
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.push(parse_quote! {
+        #niching: #rkyv_path::niche::niching::Niching<#archived>
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let unit = &unit.ident;
+    let payload = &payload.ident;
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::Archive for #name #ty_generics
+        #where_clause
+        {
+            type Archived =
+                #rkyv_path::niche::niched_option::NichedOption<
+                    #archived,
+                    #niching,
+                >;
+            type Resolver = ::core::option::Option<#resolver>;
+
+            // Some resolvers will be (), this allow is to prevent clippy
+            // from complaining.
+            #[allow(clippy::unit_arg)]
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: #rkyv_path::Place<Self::Archived>,
+            ) {
+                let out = #rkyv_path::niche::niched_option::NichedOption
+                    ::munge_place(out);
+                match self {
+                    #name::#payload { #member: __field } => {
+                        let resolver = resolver.expect("non-niched resolver");
+                        #resolve(__field, resolver, out);
+                    }
+                    #name::#unit => <
+                        #niching as #rkyv_path::niche::niching::Niching<
+                            #archived,
+                        >
+                    >::resolve_niched(out),
+                }
+            }
+        }
+    })
+}
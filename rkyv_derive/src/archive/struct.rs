@@ -1,13 +1,14 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     parse_quote, punctuated::Punctuated, Error, Field, Fields, Generics, Index,
-    Member,
+    Member, WhereClause,
 };
 
 use crate::{
-    archive::{archived_doc, printing::Printing, resolver_doc},
+    archive::{archived_doc, bit_pack, printing::Printing, resolver_doc},
     attributes::{Attributes, FieldAttributes},
+    util::strip_raw,
 };
 
 pub fn impl_struct(
@@ -34,12 +35,25 @@ pub fn impl_struct(
         result.extend(generate_niching_impls(
             printing, generics, attributes, fields,
         )?);
+
+        let packed = bit_pack::packed_fields(attributes, fields)?;
+        if !packed.is_empty() {
+            result.extend(bit_pack::generate_accessors(
+                printing, generics, &packed,
+            ));
+        }
     }
 
     result.extend(generate_resolver_type(
         printing, generics, attributes, fields,
     )?);
 
+    if attributes.resolver_builder.is_some() {
+        result.extend(generate_resolver_builder(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let archive_impl = if let Some(ref remote) = attributes.remote {
@@ -106,6 +120,53 @@ pub fn impl_struct(
 
     result.extend(archive_impl);
 
+    if attributes.diff.is_some() {
+        result.extend(generate_diff_impl(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.type_hash.is_some() {
+        result.extend(generate_type_hash_impl(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.layout.is_some() {
+        result.extend(generate_layout_impl(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.stable_format.is_some() {
+        result.extend(generate_stable_format_impl(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.seal.is_some() {
+        result.extend(generate_seal_projections(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.field_offsets.is_some() {
+        result.extend(generate_field_offsets(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
+    if attributes.getters.is_some() {
+        result
+            .extend(generate_getters(printing, generics, attributes, fields)?);
+    }
+
+    if attributes.derive_serde.is_some() {
+        result.extend(generate_serde_impls(
+            printing, generics, attributes, fields,
+        )?);
+    }
+
     for compare in attributes.compares.iter().flat_map(Punctuated::iter) {
         if compare.is_ident("PartialEq") {
             result.extend(generate_partial_eq_impl(
@@ -115,11 +176,23 @@ pub fn impl_struct(
             result.extend(generate_partial_ord_impl(
                 printing, generics, attributes, fields,
             )?);
+        } else if compare.is_ident("Eq") {
+            result.extend(generate_eq_impl(
+                printing, generics, attributes, fields,
+            )?);
+        } else if compare.is_ident("Ord") {
+            result.extend(generate_ord_impl(
+                printing, generics, attributes, fields,
+            )?);
+        } else if compare.is_ident("Hash") {
+            result.extend(generate_hash_impl(
+                printing, generics, attributes, fields,
+            )?);
         } else {
             return Err(Error::new_spanned(
                 compare,
                 "unrecognized compare argument, supported compares are \
-                 PartialEq and PartialOrd",
+                 PartialEq, PartialOrd, Eq, Ord, and Hash",
             ));
         }
     }
@@ -137,6 +210,9 @@ fn generate_resolve_statements(
     let mut resolve_statements = TokenStream::new();
     for (field, member) in fields.iter().zip(fields.members()) {
         let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.bit_pack.is_some() {
+            continue;
+        }
         let resolves = field_attrs.resolve(rkyv_path, field);
         let access_field = field_attrs.access_field(&this, &member);
         resolve_statements.extend(quote! {
@@ -149,6 +225,14 @@ fn generate_resolve_statements(
             #resolves(#access_field, resolver.#member, field_out);
         });
     }
+
+    let packed = bit_pack::packed_fields(attributes, fields)?;
+    if !packed.is_empty() {
+        resolve_statements.extend(bit_pack::generate_resolve_statement(
+            printing, &packed, &this,
+        ));
+    }
+
     Ok(resolve_statements)
 }
 
@@ -177,6 +261,9 @@ fn generate_archived_type(
         } = field;
 
         let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.bit_pack.is_some() {
+            continue;
+        }
         let field_metas = field_attrs.metas();
         let ty = field_attrs.archived(rkyv_path, field);
 
@@ -186,6 +273,13 @@ fn generate_archived_type(
         });
     }
 
+    let packed = bit_pack::packed_fields(attributes, fields)?;
+    if !packed.is_empty() {
+        let bits_field = bit_pack::bits_field();
+        let len = bit_pack::packed_len(&packed);
+        archived_fields.extend(quote! { #bits_field: [u8; #len], });
+    }
+
     let where_clause = &generics.where_clause;
     let body = match fields {
         Fields::Named(_) => quote! { #where_clause { #archived_fields } },
@@ -223,6 +317,9 @@ fn generate_resolver_type(
             ident, colon_token, ..
         } = field;
         let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.bit_pack.is_some() {
+            continue;
+        }
 
         let ty = field_attrs.resolver(rkyv_path, field);
 
@@ -244,6 +341,147 @@ fn generate_resolver_type(
     })
 }
 
+fn generate_resolver_builder(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        vis,
+        name,
+        resolver_name,
+        ..
+    } = printing;
+
+    let builder_name = format_ident!("{}Builder", strip_raw(resolver_name));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let value_ty = match attributes.remote {
+        Some(ref remote) => quote! { #remote },
+        None => quote! { #name #ty_generics },
+    };
+    let value = Ident::new("value", Span::call_site());
+
+    let mut builder_fields = TokenStream::new();
+    let mut empty_fields = TokenStream::new();
+    let mut built_fields = Vec::new();
+    let mut built_members = Vec::new();
+    let mut methods = TokenStream::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.bit_pack.is_some() {
+            continue;
+        }
+
+        let (builder_field, description) = match member {
+            Member::Named(ref ident) => {
+                (ident.clone(), format!("the `{}` field", strip_raw(ident)))
+            }
+            Member::Unnamed(ref index) => (
+                format_ident!("field_{}", index.index),
+                format!("field {}", index.index),
+            ),
+        };
+        let base_name = strip_raw(&builder_field);
+        let set = format_ident!("set_{}", base_name);
+        let serialize_field = format_ident!("serialize_{}", base_name);
+        let set_doc = format!("Sets the resolver for {}.", description);
+        let serialize_doc =
+            format!("Serializes {} and sets its resolver.", description);
+
+        let resolver_ty = field_attrs.resolver(rkyv_path, field);
+        let serialize = field_attrs.serialize(rkyv_path, field);
+        let serialize_bound = field_attrs.serialize_bound(rkyv_path, field);
+        let access_field = field_attrs.access_field(&value, &member);
+
+        builder_fields.extend(quote! {
+            #builder_field: ::core::option::Option<#resolver_ty>,
+        });
+        empty_fields.extend(quote! {
+            #builder_field: ::core::option::Option::None,
+        });
+        built_fields.push(quote! { self.#builder_field? });
+        built_members.push(member);
+        methods.extend(quote! {
+            #[doc = #set_doc]
+            #vis fn #set(&mut self, resolver: #resolver_ty) -> &mut Self {
+                self.#builder_field = ::core::option::Option::Some(resolver);
+                self
+            }
+
+            #[doc = #serialize_doc]
+            #vis fn #serialize_field<__S>(
+                &mut self,
+                #value: &#value_ty,
+                serializer: &mut __S,
+            ) -> ::core::result::Result<
+                &mut Self,
+                <__S as #rkyv_path::rancor::Fallible>::Error,
+            >
+            where
+                __S: #rkyv_path::rancor::Fallible + ?Sized,
+                #serialize_bound
+            {
+                self.#builder_field = ::core::option::Option::Some(
+                    #serialize(#access_field, serializer)?,
+                );
+                ::core::result::Result::Ok(self)
+            }
+        });
+    }
+
+    let built = match fields {
+        Fields::Named(_) => {
+            quote! { #resolver_name { #(#built_members: #built_fields,)* } }
+        }
+        Fields::Unnamed(_) => quote! { #resolver_name(#(#built_fields,)*) },
+        Fields::Unit => quote! { #resolver_name },
+    };
+
+    let builder_doc = format!("A builder for [`{}`]", resolver_name);
+    let body = match fields {
+        Fields::Unit => quote! { #where_clause; },
+        _ => quote! { #where_clause { #builder_fields } },
+    };
+    let empty = match fields {
+        Fields::Unit => quote! { #builder_name },
+        _ => quote! { #builder_name { #empty_fields } },
+    };
+
+    Ok(quote! {
+        #[automatically_derived]
+        #[doc = #builder_doc]
+        #vis struct #builder_name #generics #body
+
+        impl #impl_generics #builder_name #ty_generics #where_clause {
+            /// Returns a builder with none of the resolvers set.
+            #vis fn new() -> Self {
+                #empty
+            }
+
+            #methods
+
+            /// Returns the built resolver, or `None` if any of the resolvers
+            /// have not been set.
+            #vis fn build(self) -> ::core::option::Option<
+                #resolver_name #ty_generics
+            > {
+                ::core::option::Option::Some(#built)
+            }
+        }
+
+        impl #impl_generics ::core::default::Default
+            for #builder_name #ty_generics
+        #where_clause
+        {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    })
+}
+
 fn generate_partial_eq_impl(
     printing: &Printing,
     generics: &Generics,
@@ -291,6 +529,466 @@ fn generate_partial_eq_impl(
     })
 }
 
+fn generate_diff_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in fields.iter() {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived_ty = field_attrs.archived(rkyv_path, field);
+            where_clause
+                .predicates
+                .push(parse_quote! { #archived_ty: #rkyv_path::diff::Diff });
+        }
+    }
+
+    let diff_fields = fields.members().map(|member| {
+        let name = match member {
+            Member::Named(ref ident) => strip_raw(ident),
+            Member::Unnamed(ref index) => index.index.to_string(),
+        };
+        quote! {
+            differ.field(#name, &self.#member, &other.#member);
+        }
+    });
+    let unused = fields
+        .is_empty()
+        .then(|| quote! { let _ = (other, differ); });
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::diff::Diff for #archived_type
+        #where_clause
+        {
+            fn diff(
+                &self,
+                other: &Self,
+                differ: &mut #rkyv_path::diff::Differ,
+            ) {
+                #(#diff_fields)*
+                #unused
+            }
+        }
+    })
+}
+
+fn generate_type_hash_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    let mut field_hashes = Vec::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived_ty = field_attrs.archived(rkyv_path, field);
+        if field_attrs.omit_bounds.is_none() {
+            where_clause.predicates.push(parse_quote! {
+                #archived_ty: #rkyv_path::type_hash::TypeHash
+            });
+        }
+
+        let name = match member {
+            Member::Named(ref ident) => strip_raw(ident),
+            Member::Unnamed(ref index) => index.index.to_string(),
+        };
+        field_hashes.push(quote! {
+            .write_str(#name)
+            .write_type::<#archived_ty>()
+        });
+    }
+    let field_count = field_hashes.len() as u64;
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::type_hash::TypeHash for #archived_type
+        #where_clause
+        {
+            const TYPE_HASH: u64 = #rkyv_path::type_hash::TypeHasher::new()
+                .write_str("struct")
+                .write_u64(#field_count)
+                #(#field_hashes)*
+                .write_layout::<Self>()
+                .finish();
+        }
+    })
+}
+
+fn generate_stable_format_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in fields.iter() {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived_ty = field_attrs.archived(rkyv_path, field);
+            where_clause.predicates.push(parse_quote! {
+                #archived_ty: #rkyv_path::stable::StableFormat
+            });
+        }
+    }
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::stable::StableFormat for #archived_type
+        #where_clause
+        {
+        }
+    })
+}
+
+fn generate_layout_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        name,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    let mut field_layouts = Vec::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let archived_ty = field_attrs.archived(rkyv_path, field);
+        if field_attrs.omit_bounds.is_none() {
+            where_clause.predicates.push(parse_quote! {
+                #archived_ty: #rkyv_path::layout::DescribeLayout
+            });
+        }
+
+        let field_name = match member {
+            Member::Named(ref ident) => strip_raw(ident),
+            Member::Unnamed(ref index) => index.index.to_string(),
+        };
+        field_layouts.push(quote! {
+            #rkyv_path::layout::FieldLayout::new(
+                #field_name,
+                ::core::mem::offset_of!(Self, #member),
+                #rkyv_path::layout::layout_of::<#archived_ty>(),
+            )
+        });
+    }
+    let name = strip_raw(name);
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::layout::DescribeLayout
+            for #archived_type
+        #where_clause
+        {
+            fn describe_layout() -> #rkyv_path::layout::LayoutDescriptor {
+                #rkyv_path::layout::LayoutDescriptor::new::<Self>(
+                    #name,
+                    #rkyv_path::layout::LayoutKind::Struct {
+                        fields: ::core::convert::From::from([
+                            #(#field_layouts,)*
+                        ]),
+                    },
+                )
+            }
+        }
+    })
+}
+
+fn generate_serde_impls(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        name,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in fields.iter() {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived_ty = field_attrs.archived(rkyv_path, field);
+            where_clause.predicates.push(parse_quote! {
+                #archived_ty: #rkyv_path::serde::SerializeArchived
+            });
+        }
+    }
+
+    let serde = quote! { #rkyv_path::serde::__serde };
+    let name = strip_raw(name);
+    let len = fields.len();
+    let members = fields.members();
+    let body = match fields {
+        Fields::Named(_) => {
+            let names = fields.members().map(|member| match member {
+                Member::Named(ref ident) => strip_raw(ident),
+                Member::Unnamed(_) => unreachable!(),
+            });
+            quote! {
+                use #serde::ser::SerializeStruct as _;
+
+                let mut state = serializer.serialize_struct(#name, #len)?;
+                #(
+                    state.serialize_field(
+                        #names,
+                        &#rkyv_path::serde::Serde(&self.#members),
+                    )?;
+                )*
+                state.end()
+            }
+        }
+        Fields::Unnamed(_) if len == 1 => quote! {
+            serializer.serialize_newtype_struct(
+                #name,
+                &#rkyv_path::serde::Serde(&self.0),
+            )
+        },
+        Fields::Unnamed(_) => quote! {
+            use #serde::ser::SerializeTupleStruct as _;
+
+            let mut state =
+                serializer.serialize_tuple_struct(#name, #len)?;
+            #(
+                state.serialize_field(
+                    &#rkyv_path::serde::Serde(&self.#members),
+                )?;
+            )*
+            state.end()
+        },
+        Fields::Unit => quote! {
+            serializer.serialize_unit_struct(#name)
+        },
+    };
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::serde::SerializeArchived
+            for #archived_type
+        #where_clause
+        {
+            fn serialize_archived<__S: #serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #body
+            }
+        }
+
+        impl #impl_generics #serde::Serialize for #archived_type
+        #where_clause
+        {
+            fn serialize<__S: #serde::Serializer>(
+                &self,
+                serializer: __S,
+            ) -> ::core::result::Result<__S::Ok, __S::Error> {
+                #rkyv_path::serde::SerializeArchived::serialize_archived(
+                    self,
+                    serializer,
+                )
+            }
+        }
+    })
+}
+
+fn generate_seal_projections(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut projections = TokenStream::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let ty = field_attrs.archived(rkyv_path, field);
+        let vis = &field.vis;
+        let (method, doc) = match member {
+            Member::Named(ref ident) => {
+                let name = strip_raw(ident);
+                (
+                    format_ident!("{}_seal", name),
+                    format!("Projects a sealed value to its `{}` field.", name),
+                )
+            }
+            Member::Unnamed(ref index) => (
+                format_ident!("field_{}_seal", index.index),
+                format!(
+                    "Projects a sealed value to its field {}.",
+                    index.index
+                ),
+            ),
+        };
+
+        projections.extend(quote! {
+            #[doc = #doc]
+            #vis fn #method(
+                this: #rkyv_path::seal::Seal<'_, Self>,
+            ) -> #rkyv_path::seal::Seal<'_, #ty> {
+                // SAFETY: The field is only projected and not moved, and it
+                // remains sealed.
+                let this = unsafe {
+                    #rkyv_path::seal::Seal::unseal_unchecked(this)
+                };
+                #rkyv_path::seal::Seal::new(&mut this.#member)
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #where_clause {
+            #projections
+        }
+    })
+}
+
+fn generate_field_offsets(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut offsets = TokenStream::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let ty = field_attrs.archived(rkyv_path, field);
+        let vis = &field.vis;
+        let (name, doc) = match member {
+            Member::Named(ref ident) => {
+                let name = strip_raw(ident);
+                (
+                    format_ident!("{}", name.to_uppercase()),
+                    format!("The offset of the `{}` field.", name),
+                )
+            }
+            Member::Unnamed(ref index) => (
+                format_ident!("FIELD_{}", index.index),
+                format!("The offset of field {}.", index.index),
+            ),
+        };
+
+        offsets.extend(quote! {
+            #[doc = #doc]
+            #vis const #name: #rkyv_path::projection::FieldOffset<Self, #ty> =
+                // SAFETY: `offset_of!` returns the offset of the field in
+                // `Self`, and the field has the given type.
+                unsafe {
+                    #rkyv_path::projection::FieldOffset::new_unchecked(
+                        ::core::mem::offset_of!(Self, #member),
+                    )
+                };
+        });
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #where_clause {
+            #offsets
+        }
+    })
+}
+
+fn generate_getters(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path,
+        archived_type,
+        ..
+    } = printing;
+
+    let mut getters = TokenStream::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.skip_getter.is_some() {
+            continue;
+        }
+
+        let ty = field_attrs.archived(rkyv_path, field);
+        let vis = &field.vis;
+        let (method, doc) = match member {
+            Member::Named(ref ident) => (
+                ident.clone(),
+                format!(
+                    "Returns a native view of the `{}` field.",
+                    strip_raw(ident),
+                ),
+            ),
+            Member::Unnamed(ref index) => (
+                format_ident!("field_{}", index.index),
+                format!("Returns a native view of field {}.", index.index),
+            ),
+        };
+
+        getters.extend(quote! {
+            #[doc = #doc]
+            #vis fn #method(
+                &self,
+            ) -> <#ty as #rkyv_path::native::AsNative>::Native<'_>
+            where
+                #ty: #rkyv_path::native::AsNative,
+            {
+                #rkyv_path::native::AsNative::as_native(&self.#member)
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #archived_type #where_clause {
+            #getters
+        }
+    })
+}
+
 fn generate_partial_ord_impl(
     printing: &Printing,
     generics: &Generics,
@@ -352,6 +1050,131 @@ fn generate_partial_ord_impl(
     })
 }
 
+/// Adds `bound` to the where clause for the archived type of each field which
+/// does not omit its bounds.
+fn archived_field_bounds(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+    bound: TokenStream,
+) -> Result<WhereClause, Error> {
+    let mut where_clause = generics.where_clause.clone().unwrap();
+    for field in fields.iter() {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        if field_attrs.omit_bounds.is_none() {
+            let archived_ty = field_attrs.archived(&printing.rkyv_path, field);
+            where_clause
+                .predicates
+                .push(parse_quote! { #archived_ty: #bound });
+        }
+    }
+    Ok(where_clause)
+}
+
+fn generate_eq_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let archived_type = &printing.archived_type;
+    let where_clause = archived_field_bounds(
+        printing,
+        generics,
+        attributes,
+        fields,
+        quote! { Eq },
+    )?;
+
+    let members = fields.members();
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics PartialEq for #archived_type #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                true #(&& self.#members == other.#members)*
+            }
+        }
+
+        impl #impl_generics Eq for #archived_type #where_clause {}
+    })
+}
+
+fn generate_ord_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let archived_type = &printing.archived_type;
+    let where_clause = archived_field_bounds(
+        printing,
+        generics,
+        attributes,
+        fields,
+        quote! { Ord },
+    )?;
+
+    let members = fields.members();
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics PartialOrd for #archived_type #where_clause {
+            fn partial_cmp(
+                &self,
+                other: &Self,
+            ) -> Option<::core::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl #impl_generics Ord for #archived_type #where_clause {
+            fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                #(
+                    match self.#members.cmp(&other.#members) {
+                        ::core::cmp::Ordering::Equal => (),
+                        cmp => return cmp,
+                    }
+                )*
+                ::core::cmp::Ordering::Equal
+            }
+        }
+    })
+}
+
+fn generate_hash_impl(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    fields: &Fields,
+) -> Result<TokenStream, Error> {
+    let archived_type = &printing.archived_type;
+    let where_clause = archived_field_bounds(
+        printing,
+        generics,
+        attributes,
+        fields,
+        quote! { ::core::hash::Hash },
+    )?;
+
+    let members = fields.members();
+    let (impl_generics, ..) = generics.split_for_impl();
+
+    // Fields are hashed in declaration order, which is what `derive(Hash)`
+    // does for the original type.
+    Ok(quote! {
+        impl #impl_generics ::core::hash::Hash for #archived_type
+        #where_clause
+        {
+            #[allow(unused_variables)]
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                #(::core::hash::Hash::hash(&self.#members, state);)*
+            }
+        }
+    })
+}
+
 fn generate_copy_optimization(
     printing: &Printing,
     generics: &Generics,
@@ -0,0 +1,71 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, Field, Generics, Member};
+
+use crate::{
+    archive::printing::Printing,
+    attributes::{Attributes, FieldAttributes},
+};
+
+pub fn impl_transparent(
+    printing: &Printing,
+    generics: &Generics,
+    attributes: &Attributes,
+    field: &Field,
+    member: &Member,
+) -> Result<TokenStream, Error> {
+    let Printing {
+        rkyv_path, name, ..
+    } = printing;
+
+    let field_attrs = FieldAttributes::parse(attributes, field)?;
+    let archived = field_attrs.archived(rkyv_path, field);
+    let resolver = field_attrs.resolver(rkyv_path, field);
+    let resolve = field_attrs.resolve(rkyv_path, field);
+
+    // The struct has the same layout as its archived field if it has the same
+    // size as its field, the field is at the start of the struct, and the
+    // field itself is copy-optimized.
+    let copy_optimization =
+        if generics.params.is_empty() && field_attrs.with.is_none() {
+            let ty = &field.ty;
+            Some(quote! {
+                const COPY_OPTIMIZATION:
+                    #rkyv_path::traits::CopyOptimization<Self> = unsafe {
+                    #rkyv_path::traits::CopyOptimization::enable_if(
+                        <#ty as #rkyv_path::Archive>::COPY_OPTIMIZATION
+                            .is_enabled()
+                        && ::core::mem::size_of::<#ty>()
+                            == ::core::mem::size_of::<#name>()
+                        && ::core::mem::offset_of!(#name, #member) == 0
+                    )
+                };
+            })
+        } else {
+            None
+        };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #rkyv_path::Archive for #name #ty_generics
+        #where_clause
+        {
+            type Archived = #archived;
+            type Resolver = #resolver;
+
+            #copy_optimization
+
+            // Some resolvers will be (), this allow is to prevent clippy
+            // from complaining.
+            #[allow(clippy::unit_arg)]
+            fn resolve(
+                &self,
+                resolver: Self::Resolver,
+                out: #rkyv_path::Place<Self::Archived>,
+            ) {
+                #resolve(&self.#member, resolver, out);
+            }
+        }
+    })
+}
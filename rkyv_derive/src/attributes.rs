@@ -2,10 +2,12 @@ use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{quote, ToTokens};
 use syn::{
     meta::ParseNestedMeta, parenthesized, parse::Parse, parse_quote,
-    punctuated::Punctuated, DeriveInput, Error, Field, Fields, Ident, Meta,
-    Path, Token, Type, Variant, WherePredicate,
+    punctuated::Punctuated, Data, DeriveInput, Error, Expr, Field, Fields,
+    Ident, LitInt, Meta, Path, Token, Type, Variant, WherePredicate,
 };
 
+use crate::util::{iter_fields, niched_variants, single_field};
+
 fn try_set_attribute<T: ToTokens>(
     attribute: &mut Option<T>,
     value: T,
@@ -28,6 +30,8 @@ pub struct Attributes {
     pub archived: Option<Ident>,
     pub resolver: Option<Ident>,
     pub remote: Option<Path>,
+    pub from: Option<Path>,
+    pub non_exhaustive: Option<Path>,
     pub metas: Vec<Meta>,
     pub compares: Option<Punctuated<Path, Token![,]>>,
     pub archive_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
@@ -35,6 +39,21 @@ pub struct Attributes {
     pub deserialize_bounds: Option<Punctuated<WherePredicate, Token![,]>>,
     pub bytecheck: Option<TokenStream>,
     pub crate_path: Option<Path>,
+    pub version: Option<Expr>,
+    pub columns: Option<Path>,
+    pub transparent: Option<Path>,
+    pub niche_discriminant: Option<Path>,
+    pub diff: Option<Path>,
+    pub type_hash: Option<Path>,
+    pub layout: Option<Path>,
+    pub stable_format: Option<Path>,
+    pub seal: Option<Path>,
+    pub field_offsets: Option<Path>,
+    pub derive_serde: Option<Path>,
+    pub max_size: Option<Path>,
+    pub getters: Option<Path>,
+    pub resolver_builder: Option<Path>,
+    pub iterative: Option<usize>,
 }
 
 impl Attributes {
@@ -144,6 +163,62 @@ impl Attributes {
                 meta.value()?.parse()?,
                 "remote",
             )
+        } else if meta.path.is_ident("from") {
+            try_set_attribute(&mut self.from, meta.value()?.parse()?, "from")
+        } else if meta.path.is_ident("non_exhaustive") {
+            try_set_attribute(
+                &mut self.non_exhaustive,
+                meta.path,
+                "non_exhaustive",
+            )
+        } else if meta.path.is_ident("columns") {
+            try_set_attribute(&mut self.columns, meta.path, "columns")
+        } else if meta.path.is_ident("transparent") {
+            try_set_attribute(&mut self.transparent, meta.path, "transparent")
+        } else if meta.path.is_ident("niche_discriminant") {
+            try_set_attribute(
+                &mut self.niche_discriminant,
+                meta.path,
+                "niche_discriminant",
+            )
+        } else if meta.path.is_ident("diff") {
+            try_set_attribute(&mut self.diff, meta.path, "diff")
+        } else if meta.path.is_ident("type_hash") {
+            try_set_attribute(&mut self.type_hash, meta.path, "type_hash")
+        } else if meta.path.is_ident("layout") {
+            try_set_attribute(&mut self.layout, meta.path, "layout")
+        } else if meta.path.is_ident("stable_format") {
+            try_set_attribute(
+                &mut self.stable_format,
+                meta.path,
+                "stable_format",
+            )
+        } else if meta.path.is_ident("seal") {
+            try_set_attribute(&mut self.seal, meta.path, "seal")
+        } else if meta.path.is_ident("field_offsets") {
+            try_set_attribute(
+                &mut self.field_offsets,
+                meta.path,
+                "field_offsets",
+            )
+        } else if meta.path.is_ident("derive_serde") {
+            try_set_attribute(&mut self.derive_serde, meta.path, "derive_serde")
+        } else if meta.path.is_ident("max_size") {
+            try_set_attribute(&mut self.max_size, meta.path, "max_size")
+        } else if meta.path.is_ident("getters") {
+            try_set_attribute(&mut self.getters, meta.path, "getters")
+        } else if meta.path.is_ident("resolver_builder") {
+            try_set_attribute(
+                &mut self.resolver_builder,
+                meta.path,
+                "resolver_builder",
+            )
+        } else if meta.path.is_ident("version") {
+            try_set_attribute(
+                &mut self.version,
+                meta.value()?.parse()?,
+                "version",
+            )
         } else {
             Err(meta.error("unrecognized rkyv argument"))
         }
@@ -182,11 +257,360 @@ impl Attributes {
                      does not generate an archived type",
                 ));
             }
+
+            if let Some(ref diff) = result.diff {
+                return Err(Error::new_spanned(
+                    diff,
+                    "cannot generate a `Diff` impl because `as = ...` does \
+                     not generate an archived type",
+                ));
+            }
+
+            if let Some(ref type_hash) = result.type_hash {
+                return Err(Error::new_spanned(
+                    type_hash,
+                    "cannot generate a `TypeHash` impl because `as = ...` \
+                     does not generate an archived type",
+                ));
+            }
+
+            if let Some(ref layout) = result.layout {
+                return Err(Error::new_spanned(
+                    layout,
+                    "cannot generate a `DescribeLayout` impl because \
+                     `as = ...` does not generate an archived type",
+                ));
+            }
+
+            if let Some(ref stable_format) = result.stable_format {
+                return Err(Error::new_spanned(
+                    stable_format,
+                    "cannot generate a `StableFormat` impl because \
+                     `as = ...` does not generate an archived type",
+                ));
+            }
+
+            if let Some(ref seal) = result.seal {
+                return Err(Error::new_spanned(
+                    seal,
+                    "cannot generate seal projections because `as = ...` does \
+                     not generate an archived type",
+                ));
+            }
+
+            if let Some(ref field_offsets) = result.field_offsets {
+                return Err(Error::new_spanned(
+                    field_offsets,
+                    "cannot generate field offsets because `as = ...` does \
+                     not generate an archived type",
+                ));
+            }
+
+            if let Some(ref derive_serde) = result.derive_serde {
+                return Err(Error::new_spanned(
+                    derive_serde,
+                    "cannot generate serde impls because `as = ...` does not \
+                     generate an archived type",
+                ));
+            }
+
+            if let Some(ref getters) = result.getters {
+                return Err(Error::new_spanned(
+                    getters,
+                    "cannot generate getters because `as = ...` does not \
+                     generate an archived type",
+                ));
+            }
+        }
+
+        if let Some(ref transparent) = result.transparent {
+            result.check_transparent(input, transparent)?;
+        }
+
+        if let Some(ref niche_discriminant) = result.niche_discriminant {
+            result.check_niche_discriminant(input, niche_discriminant)?;
+        }
+
+        result.check_bit_pack(input)?;
+        result.check_iterative(input)?;
+
+        if result.remote.is_some() {
+            if let Some(ref columns) = result.columns {
+                return Err(Error::new_spanned(
+                    columns,
+                    "`columns` may not be used with remote derive",
+                ));
+            }
+
+            if let Some(ref max_size) = result.max_size {
+                return Err(Error::new_spanned(
+                    max_size,
+                    "`max_size` may not be used with remote derive",
+                ));
+            }
+        }
+
+        if let Some(ref from) = result.from {
+            if result.remote.is_none() {
+                return Err(Error::new_spanned(
+                    from,
+                    "`from` may only be used with remote derive",
+                ));
+            }
+
+            if !matches!(input.data, Data::Struct(_)) {
+                return Err(Error::new_spanned(
+                    from,
+                    "`from` may only be used on structs",
+                ));
+            }
+        }
+
+        if let Some(ref resolver_builder) = result.resolver_builder {
+            if !matches!(input.data, Data::Struct(_)) {
+                return Err(Error::new_spanned(
+                    resolver_builder,
+                    "`resolver_builder` may only be used on structs",
+                ));
+            }
+        }
+
+        if let Some(ref non_exhaustive) = result.non_exhaustive {
+            if result.remote.is_none() {
+                return Err(Error::new_spanned(
+                    non_exhaustive,
+                    "`non_exhaustive` may only be used with remote derive",
+                ));
+            }
+
+            if !matches!(input.data, Data::Enum(_)) {
+                return Err(Error::new_spanned(
+                    non_exhaustive,
+                    "`non_exhaustive` may only be used on enums",
+                ));
+            }
         }
 
         Ok(result)
     }
 
+    fn check_transparent(
+        &self,
+        input: &DeriveInput,
+        transparent: &Path,
+    ) -> Result<(), Error> {
+        if single_field(&input.data).is_none() {
+            return Err(Error::new_spanned(
+                transparent,
+                "`transparent` may only be used on structs with exactly one \
+                 field",
+            ));
+        }
+
+        if let Some(conflict) = self.generated_type_conflict() {
+            return Err(Error::new_spanned(
+                transparent,
+                format!(
+                    "`transparent` may not be used with `{}` because no \
+                     archived or resolver type is generated",
+                    conflict,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the first attribute which requires an archived or resolver type
+    /// to be generated.
+    fn generated_type_conflict(&self) -> Option<&'static str> {
+        if self.as_type.is_some() {
+            Some("as = ...")
+        } else if self.archived.is_some() {
+            Some("archived = ...")
+        } else if self.resolver.is_some() {
+            Some("resolver = ...")
+        } else if !self.metas.is_empty() {
+            Some("attr(...)` or `derive(...)")
+        } else if self.bytecheck.is_some() {
+            Some("bytecheck(...)")
+        } else if self.compares.is_some() {
+            Some("compare(...)")
+        } else if self.diff.is_some() {
+            Some("diff")
+        } else if self.type_hash.is_some() {
+            Some("type_hash")
+        } else if self.layout.is_some() {
+            Some("layout")
+        } else if self.stable_format.is_some() {
+            Some("stable_format")
+        } else if self.seal.is_some() {
+            Some("seal")
+        } else if self.field_offsets.is_some() {
+            Some("field_offsets")
+        } else if self.derive_serde.is_some() {
+            Some("derive_serde")
+        } else if self.getters.is_some() {
+            Some("getters")
+        } else if self.resolver_builder.is_some() {
+            Some("resolver_builder")
+        } else if self.remote.is_some() {
+            Some("remote = ...")
+        } else if self.version.is_some() {
+            Some("version = ...")
+        } else if self.columns.is_some() {
+            Some("columns")
+        } else {
+            None
+        }
+    }
+
+    fn check_niche_discriminant(
+        &self,
+        input: &DeriveInput,
+        niche_discriminant: &Path,
+    ) -> Result<(), Error> {
+        if niched_variants(&input.data).is_none() {
+            return Err(Error::new_spanned(
+                niche_discriminant,
+                "`niche_discriminant` may only be used on enums with one unit \
+                 variant and one variant with exactly one field",
+            ));
+        }
+
+        if let Some(conflict) = self.generated_type_conflict() {
+            return Err(Error::new_spanned(
+                niche_discriminant,
+                format!(
+                    "`niche_discriminant` may not be used with `{}` because no \
+                     archived or resolver type is generated",
+                    conflict,
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_bit_pack(&self, input: &DeriveInput) -> Result<(), Error> {
+        let mut packed = None;
+        for field in iter_fields(&input.data) {
+            if FieldAttributes::parse(self, field)?.bit_pack.is_some() {
+                packed = Some(field);
+                break;
+            }
+        }
+        let Some(packed) = packed else {
+            return Ok(());
+        };
+
+        let is_named_struct = match input.data {
+            Data::Struct(ref data) => matches!(data.fields, Fields::Named(_)),
+            _ => false,
+        };
+        if !is_named_struct {
+            return Err(Error::new_spanned(
+                packed,
+                "`BitPack` may only be used on structs with named fields",
+            ));
+        }
+
+        let conflict = if self.as_type.is_some() {
+            Some("as = ...")
+        } else if self.remote.is_some() {
+            Some("remote = ...")
+        } else if self.transparent.is_some() {
+            Some("transparent")
+        } else if self.compares.is_some() {
+            Some("compare(...)")
+        } else if self.diff.is_some() {
+            Some("diff")
+        } else if self.type_hash.is_some() {
+            Some("type_hash")
+        } else if self.layout.is_some() {
+            Some("layout")
+        } else if self.stable_format.is_some() {
+            Some("stable_format")
+        } else if self.seal.is_some() {
+            Some("seal")
+        } else if self.field_offsets.is_some() {
+            Some("field_offsets")
+        } else if self.getters.is_some() {
+            Some("getters")
+        } else if self.derive_serde.is_some() {
+            Some("derive_serde")
+        } else if self.columns.is_some() {
+            Some("columns")
+        } else if self.version.is_some() {
+            Some("version = ...")
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            return Err(Error::new_spanned(
+                packed,
+                format!("`BitPack` fields may not be used with `{}`", conflict),
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn check_iterative(&mut self, input: &DeriveInput) -> Result<(), Error> {
+        let mut iterative = None;
+        for (i, field) in iter_fields(&input.data).enumerate() {
+            let field_attrs = FieldAttributes::parse(self, field)?;
+            if let Some(path) = field_attrs.iterative {
+                if iterative.is_some() {
+                    return Err(Error::new_spanned(
+                        path,
+                        "only one field may be marked with `iterative`",
+                    ));
+                }
+                iterative = Some((i, path));
+            }
+        }
+        let Some((index, iterative)) = iterative else {
+            return Ok(());
+        };
+
+        let Data::Struct(ref data) = input.data else {
+            return Err(Error::new_spanned(
+                iterative,
+                "`iterative` may only be used on struct fields",
+            ));
+        };
+
+        let conflict = if self.as_type.is_some() {
+            Some("as = ...")
+        } else if self.remote.is_some() {
+            Some("remote = ...")
+        } else if self.transparent.is_some() {
+            Some("transparent")
+        } else {
+            None
+        };
+        if let Some(conflict) = conflict {
+            return Err(Error::new_spanned(
+                iterative,
+                format!("`iterative` may not be used with `{}`", conflict),
+            ));
+        }
+
+        for field in data.fields.iter() {
+            if FieldAttributes::parse(self, field)?.bit_pack.is_some() {
+                return Err(Error::new_spanned(
+                    iterative,
+                    "`iterative` may not be used with `BitPack` fields",
+                ));
+            }
+        }
+
+        self.iterative = Some(index);
+
+        Ok(())
+    }
+
     pub fn crate_path(&self) -> Path {
         self.crate_path
             .clone()
@@ -199,8 +623,15 @@ pub struct FieldAttributes {
     pub attrs: Punctuated<Meta, Token![,]>,
     pub omit_bounds: Option<Path>,
     pub with: Option<Type>,
+    pub hasher: Option<Type>,
     pub getter: Option<Path>,
+    pub skip_getter: Option<Path>,
     pub niches: Vec<Niche>,
+    pub default: Option<Path>,
+    pub since: Option<Expr>,
+    pub bits: Option<LitInt>,
+    pub bit_pack: Option<u32>,
+    pub iterative: Option<Path>,
 }
 
 impl FieldAttributes {
@@ -217,10 +648,16 @@ impl FieldAttributes {
             meta.input.parse::<Token![=]>()?;
             self.with = Some(meta.input.parse::<Type>()?);
             Ok(())
+        } else if meta.path.is_ident("hasher") {
+            meta.input.parse::<Token![=]>()?;
+            self.hasher = Some(meta.input.parse::<Type>()?);
+            Ok(())
         } else if meta.path.is_ident("getter") {
             meta.input.parse::<Token![=]>()?;
             self.getter = Some(meta.input.parse::<Path>()?);
             Ok(())
+        } else if meta.path.is_ident("skip_getter") {
+            try_set_attribute(&mut self.skip_getter, meta.path, "skip_getter")
         } else if meta.path.is_ident("niche") {
             let niche = if meta.input.is_empty() {
                 Niche::Default
@@ -233,6 +670,14 @@ impl FieldAttributes {
             self.niches.push(niche);
 
             Ok(())
+        } else if meta.path.is_ident("default") {
+            try_set_attribute(&mut self.default, meta.path, "default")
+        } else if meta.path.is_ident("since") {
+            try_set_attribute(&mut self.since, meta.value()?.parse()?, "since")
+        } else if meta.path.is_ident("bits") {
+            try_set_attribute(&mut self.bits, meta.value()?.parse()?, "bits")
+        } else if meta.path.is_ident("iterative") {
+            try_set_attribute(&mut self.iterative, meta.path, "iterative")
         } else {
             Err(meta.error("unrecognized rkyv arguments"))
         }
@@ -257,6 +702,62 @@ impl FieldAttributes {
             ));
         }
 
+        if let Some(hasher) = result.hasher.take() {
+            if result.with.is_some() {
+                return Err(Error::new_spanned(
+                    hasher,
+                    "`hasher` may not be used with `with`",
+                ));
+            }
+            let rkyv_path = attributes.crate_path();
+            result.with = Some(parse_quote! {
+                #rkyv_path::with::HashWith<#hasher>
+            });
+        }
+
+        if result.skip_getter.is_some() && attributes.getters.is_none() {
+            return Err(Error::new_spanned(
+                result.skip_getter,
+                "`skip_getter` may only be used with `getters`",
+            ));
+        }
+
+        if result.since.is_some() && result.default.is_none() {
+            return Err(Error::new_spanned(
+                result.since,
+                "`since` may only be used on `default` fields",
+            ));
+        }
+
+        if let Some(ref iterative) = result.iterative {
+            if result.with.is_some() {
+                return Err(Error::new_spanned(
+                    iterative,
+                    "`iterative` may not be used with `with`",
+                ));
+            }
+            // The iterative field always refers back to the type itself, so
+            // its bounds would be recursive.
+            if result.omit_bounds.is_none() {
+                result.omit_bounds = Some(iterative.clone());
+            }
+        }
+
+        if result.with.as_ref().is_some_and(is_bit_pack) {
+            if !result.niches.is_empty() {
+                return Err(Error::new_spanned(
+                    input,
+                    "`BitPack` fields may not be niched",
+                ));
+            }
+            result.bit_pack = Some(bit_pack_width(input, &result.bits)?);
+        } else if let Some(bits) = result.bits {
+            return Err(Error::new_spanned(
+                bits,
+                "`bits` may only be used with `with = BitPack`",
+            ));
+        }
+
         Ok(result)
     }
 
@@ -265,7 +766,7 @@ impl FieldAttributes {
         rkyv_path: &Path,
         field: &Field,
     ) -> Option<WherePredicate> {
-        if self.omit_bounds.is_some() {
+        if self.omit_bounds.is_some() || self.bit_pack.is_some() {
             return None;
         }
 
@@ -286,7 +787,7 @@ impl FieldAttributes {
         rkyv_path: &Path,
         field: &Field,
     ) -> Option<WherePredicate> {
-        if self.omit_bounds.is_some() {
+        if self.omit_bounds.is_some() || self.bit_pack.is_some() {
             return None;
         }
 
@@ -307,7 +808,7 @@ impl FieldAttributes {
         rkyv_path: &Path,
         field: &Field,
     ) -> Option<WherePredicate> {
-        if self.omit_bounds.is_some() {
+        if self.omit_bounds.is_some() || self.bit_pack.is_some() {
             return None;
         }
 
@@ -406,6 +907,15 @@ impl FieldAttributes {
         }
     }
 
+    /// Returns the niching for the payload of an enum with a
+    /// `niche_discriminant`, which defaults to `DefaultNiche`.
+    pub fn discriminant_niching(&self, rkyv_path: &Path) -> TokenStream {
+        self.niches
+            .first()
+            .unwrap_or(&Niche::Default)
+            .to_tokens(rkyv_path)
+    }
+
     pub fn metas(&self) -> TokenStream {
         let mut result = TokenStream::new();
 
@@ -422,6 +932,42 @@ impl FieldAttributes {
     }
 }
 
+fn is_bit_pack(with: &Type) -> bool {
+    match with {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "BitPack"),
+        _ => false,
+    }
+}
+
+fn bit_pack_width(field: &Field, bits: &Option<LitInt>) -> Result<u32, Error> {
+    let width = match bits {
+        Some(bits) => bits.base10_parse::<u32>()?,
+        None => match field.ty {
+            Type::Path(ref path) if path.path.is_ident("bool") => 1,
+            _ => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "`BitPack` fields other than `bool`s must specify the \
+                     number of bits with `bits = ..`",
+                ))
+            }
+        },
+    };
+
+    if !(1..=64).contains(&width) {
+        return Err(Error::new_spanned(
+            bits,
+            "`bits` must be between 1 and 64",
+        ));
+    }
+
+    Ok(width)
+}
+
 #[derive(Default)]
 pub struct VariantAttributes {
     pub other: Option<Path>,
@@ -460,6 +1006,12 @@ impl VariantAttributes {
                     result.other,
                     "`#[rkyv(other)]` may only be used on unit variants",
                 ));
+            } else if attributes.non_exhaustive.is_some() {
+                return Err(Error::new_spanned(
+                    result.other,
+                    "`#[rkyv(other)]` may not be used with `non_exhaustive` \
+                     because it already covers unknown variants",
+                ));
             }
         }
 
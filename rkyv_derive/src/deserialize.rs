@@ -6,8 +6,14 @@ use syn::{
 };
 
 use crate::{
-    archive::printing::Printing,
-    attributes::{Attributes, FieldAttributes},
+    archive::{
+        columns::columns_fields,
+        compat::{compat_fields, CompatField},
+        printing::Printing,
+    },
+    attributes::{Attributes, FieldAttributes, VariantAttributes},
+    repr::Repr,
+    util::{niched_variants, single_field, NichedVariants},
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -65,6 +71,18 @@ fn derive_deserialize_impl(
             name,
         )?;
 
+        let convert = match (&attributes.from, &input.data) {
+            (Some(from), Data::Struct(data)) => {
+                let members = data.fields.members();
+                quote! {
+                    |__local: #name #ty_generics| #from(
+                        #(__local.#members,)*
+                    )
+                }
+            }
+            _ => quote! { <#remote as From<#name #ty_generics>>::from },
+        };
+
         Ok(quote! {
             #[automatically_derived]
             impl #impl_generics
@@ -90,7 +108,7 @@ fn derive_deserialize_impl(
                     <__D as #rkyv_path::rancor::Fallible>::Error,
                 > {
                     let __this = field;
-                    #body.map(<#remote as From<#name #ty_generics>>::from)
+                    #body.map(#convert)
                 }
             }
         })
@@ -104,7 +122,14 @@ fn derive_deserialize_impl(
             name,
         )?;
 
-        Ok(quote! {
+        let deserialize_into = generate_deserialize_into(
+            &input,
+            attributes,
+            &rkyv_path,
+            quote! { #name #ty_generics },
+        )?;
+
+        let mut result = quote! {
             #[automatically_derived]
             impl #impl_generics #rkyv_path::Deserialize<#name #ty_generics, __D>
                 for #rkyv_path::Archived<#name #ty_generics>
@@ -120,11 +145,224 @@ fn derive_deserialize_impl(
                     let __this = self;
                     #body
                 }
+
+                #deserialize_into
             }
-        })
+        };
+
+        if let Some(ref columns) = attributes.columns {
+            let fields = columns_fields(&input, columns)?;
+            let deserialize_fields = fields
+                .iter()
+                .zip(fields.members())
+                .map(|(field, member)| {
+                    let field_attrs =
+                        FieldAttributes::parse(attributes, field)?;
+                    let deserialize =
+                        field_attrs.deserialize(&rkyv_path, field);
+                    Ok(quote! {
+                        #member: #deserialize(
+                            &self.#member[index],
+                            deserializer,
+                        )?
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            result.extend(quote! {
+                #[automatically_derived]
+                impl #impl_generics
+                    #rkyv_path::columns::DeserializeColumns<
+                        #name #ty_generics,
+                        __D,
+                    >
+                    for <
+                        #name #ty_generics as
+                            #rkyv_path::columns::ArchiveColumns
+                    >::ArchivedColumns
+                #deserialize_where
+                {
+                    fn deserialize_row(
+                        &self,
+                        index: usize,
+                        deserializer: &mut __D,
+                    ) -> ::core::result::Result<
+                        #name #ty_generics,
+                        <__D as #rkyv_path::rancor::Fallible>::Error,
+                    > {
+                        ::core::result::Result::Ok(#name {
+                            #(#deserialize_fields,)*
+                        })
+                    }
+                }
+            });
+        }
+
+        if let Some(fields) = compat_fields(&input, attributes)? {
+            let mut compat_where = deserialize_where.clone();
+            let mut deserialize_fields = Vec::new();
+            for CompatField {
+                field,
+                member,
+                since,
+            } in fields.iter()
+            {
+                let field_attrs = FieldAttributes::parse(attributes, field)?;
+                let deserialize = field_attrs.deserialize(&rkyv_path, field);
+                let present = quote! {
+                    #deserialize(
+                        // SAFETY: The caller has guaranteed that `value`
+                        // points to a valid archived value with all of the
+                        // fields present at `version`.
+                        unsafe { &(*value).#member },
+                        deserializer,
+                    )?
+                };
+
+                if let Some(since) = since {
+                    let ty = &field.ty;
+                    compat_where.predicates.push(parse_quote! {
+                        #ty: ::core::default::Default
+                    });
+                    deserialize_fields.push(quote! {
+                        #member: if version >= #since {
+                            #present
+                        } else {
+                            ::core::default::Default::default()
+                        }
+                    });
+                } else {
+                    deserialize_fields.push(quote! { #member: #present });
+                }
+            }
+
+            result.extend(quote! {
+                #[automatically_derived]
+                impl #impl_generics
+                    #rkyv_path::version::DeserializeCompat<
+                        #name #ty_generics,
+                        __D,
+                    >
+                    for #rkyv_path::Archived<#name #ty_generics>
+                #compat_where
+                {
+                    unsafe fn deserialize_compat(
+                        value: *const Self,
+                        version: u32,
+                        deserializer: &mut __D,
+                    ) -> ::core::result::Result<
+                        #name #ty_generics,
+                        <__D as #rkyv_path::rancor::Fallible>::Error,
+                    > {
+                        ::core::result::Result::Ok(#name {
+                            #(#deserialize_fields,)*
+                        })
+                    }
+                }
+            });
+        }
+
+        Ok(result)
     }
 }
 
+/// Generates a `deserialize_into` which deserializes each field of a struct
+/// directly into its place in `out`. Returns nothing for types which use the
+/// default implementation instead.
+fn generate_deserialize_into(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    rkyv_path: &Path,
+    ty: TokenStream,
+) -> Result<TokenStream, Error> {
+    let Data::Struct(ref data) = input.data else {
+        return Ok(TokenStream::new());
+    };
+    // Fields of packed structs may not be aligned, so they can't be written
+    // through pointers.
+    let is_packed =
+        Repr::from_attrs(&input.attrs).map_or(true, |repr| repr.is_packed());
+    if data.fields.is_empty()
+        || is_packed
+        || attributes.transparent.is_some()
+        || attributes.iterative.is_some()
+    {
+        return Ok(TokenStream::new());
+    }
+
+    let this = Ident::new("__this", Span::call_site());
+    let mut deserialize_fields = Vec::new();
+    let mut drop_fields = Vec::new();
+    for (i, (field, member)) in
+        data.fields.iter().zip(data.fields.members()).enumerate()
+    {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let place = quote! { ::core::ptr::addr_of_mut!((*out).#member) };
+
+        let deserialize = if field_attrs.bit_pack.is_some() {
+            quote! {
+                #place.write(#this.#member());
+                ::core::result::Result::Ok(())
+            }
+        } else if field_attrs.with.is_some() {
+            let deserialize = field_attrs.deserialize(rkyv_path, field);
+            quote! {
+                #deserialize(&#this.#member, deserializer)
+                    .map(|__value| #place.write(__value))
+            }
+        } else {
+            let archived = field_attrs.archived(rkyv_path, field);
+            let ty = &field.ty;
+            quote! {
+                <#archived as #rkyv_path::Deserialize<#ty, __D>>
+                    ::deserialize_into(&#this.#member, deserializer, #place)
+            }
+        };
+
+        deserialize_fields.push(quote! {
+            // SAFETY: The caller has guaranteed that `out` is valid for
+            // writes, so a pointer to any of its fields is as well.
+            if let ::core::result::Result::Err(__error) =
+                unsafe { #deserialize }
+            {
+                break 'deserialize ::core::result::Result::Err(__error);
+            }
+            __initialized += 1;
+        });
+        drop_fields.push(quote! {
+            if __initialized > #i {
+                ::core::ptr::drop_in_place(#place);
+            }
+        });
+    }
+
+    Ok(quote! {
+        unsafe fn deserialize_into(
+            &self,
+            deserializer: &mut __D,
+            out: *mut #ty,
+        ) -> ::core::result::Result<
+            (),
+            <__D as #rkyv_path::rancor::Fallible>::Error,
+        > {
+            let #this = self;
+            let mut __initialized = 0usize;
+            let __result = 'deserialize: {
+                #(#deserialize_fields)*
+                ::core::result::Result::Ok(())
+            };
+            if __result.is_err() {
+                // SAFETY: Exactly the first `__initialized` fields of `out`
+                // were deserialized.
+                unsafe {
+                    #(#drop_fields)*
+                }
+            }
+            __result
+        }
+    })
+}
+
 fn generate_deserialize_body(
     input: &DeriveInput,
     attributes: &Attributes,
@@ -135,6 +373,65 @@ fn generate_deserialize_body(
 ) -> Result<TokenStream, Error> {
     let this = Ident::new("__this", Span::call_site());
     let body = match input.data {
+        _ if attributes.transparent.is_some() => {
+            let (field, member) = single_field(&input.data).unwrap();
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+
+            deserialize_where
+                .predicates
+                .extend(field_attrs.archive_bound(rkyv_path, field));
+            deserialize_where
+                .predicates
+                .extend(field_attrs.deserialize_bound(rkyv_path, field));
+
+            let deserialize = field_attrs.deserialize(rkyv_path, field);
+            quote! {
+                #return_type { #member: #deserialize(#this, deserializer)? }
+            }
+        }
+        _ if attributes.niche_discriminant.is_some() => {
+            let NichedVariants {
+                unit,
+                payload,
+                field,
+                member,
+            } = niched_variants(&input.data).unwrap();
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+
+            deserialize_where
+                .predicates
+                .extend(field_attrs.archive_bound(rkyv_path, field));
+            deserialize_where
+                .predicates
+                .extend(field_attrs.deserialize_bound(rkyv_path, field));
+            let archived = field_attrs.archived(rkyv_path, field);
+            let niching = field_attrs.discriminant_niching(rkyv_path);
+            deserialize_where.predicates.push(parse_quote! {
+                #niching: #rkyv_path::niche::niching::Niching<#archived>
+            });
+
+            let (unit, payload) = (&unit.ident, &payload.ident);
+            let deserialize = field_attrs.deserialize(rkyv_path, field);
+            quote! {
+                match #this.as_ref() {
+                    ::core::option::Option::Some(__field) => {
+                        #return_type::#payload {
+                            #member: #deserialize(__field, deserializer)?
+                        }
+                    }
+                    ::core::option::Option::None => #return_type::#unit,
+                }
+            }
+        }
+        Data::Struct(ref data) if attributes.iterative.is_some() => {
+            return generate_iterative_deserialize_body(
+                &data.fields,
+                attributes,
+                deserialize_where,
+                rkyv_path,
+                return_type,
+            );
+        }
         Data::Struct(ref data) => match data.fields {
             Fields::Named(ref fields) => {
                 let deserialize_fields = fields
@@ -143,6 +440,11 @@ fn generate_deserialize_body(
                     .map(|field| {
                         let field_attrs =
                             FieldAttributes::parse(attributes, field)?;
+                        let name = &field.ident;
+
+                        if field_attrs.bit_pack.is_some() {
+                            return Ok(quote! { #name: #this.#name() });
+                        }
 
                         deserialize_where.predicates.extend(
                             field_attrs.archive_bound(rkyv_path, field),
@@ -151,7 +453,6 @@ fn generate_deserialize_body(
                             field_attrs.deserialize_bound(rkyv_path, field),
                         );
 
-                        let name = &field.ident;
                         let deserialize =
                             field_attrs.deserialize(rkyv_path, field);
                         Ok(quote! {
@@ -284,9 +585,20 @@ fn generate_deserialize_body(
                                 ) => #return_type::#variant(#(#fields,)*)
                             })
                         }
-                        Fields::Unit => Ok(quote! {
-                            #self_type::#variant => #return_type::#variant
-                        }),
+                        Fields::Unit => {
+                            let variant_attrs =
+                                VariantAttributes::parse(attributes, v)?;
+                            if variant_attrs.other.is_some() {
+                                // Unrecognized discriminants written by newer
+                                // versions of the enum fall back to `other`.
+                                Ok(quote! { _ => #return_type::#variant })
+                            } else {
+                                Ok(quote! {
+                                    #self_type::#variant
+                                        => #return_type::#variant
+                                })
+                            }
+                        }
                     }
                 })
                 .collect::<Result<Vec<_>, Error>>()?;
@@ -307,3 +619,67 @@ fn generate_deserialize_body(
 
     Ok(quote! { ::core::result::Result::Ok(#body) })
 }
+
+/// Deserializes a struct with an `iterative` field by walking the chain of
+/// archived values linked through that field with `deserialize_chain`.
+fn generate_iterative_deserialize_body(
+    fields: &Fields,
+    attributes: &Attributes,
+    deserialize_where: &mut WhereClause,
+    rkyv_path: &Path,
+    return_type: &Ident,
+) -> Result<TokenStream, Error> {
+    let this = Ident::new("__this", Span::call_site());
+    let link_index = attributes.iterative.unwrap();
+
+    let mut link = None;
+    let mut deserialize_fields = Vec::new();
+    for (i, (field, member)) in fields.iter().zip(fields.members()).enumerate()
+    {
+        if i == link_index {
+            deserialize_fields.push(quote! {
+                #member: ::core::option::Option::None
+            });
+            link = Some(member);
+            continue;
+        }
+
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        deserialize_where
+            .predicates
+            .extend(field_attrs.archive_bound(rkyv_path, field));
+        deserialize_where
+            .predicates
+            .extend(field_attrs.deserialize_bound(rkyv_path, field));
+
+        let deserialize = field_attrs.deserialize(rkyv_path, field);
+        deserialize_fields.push(quote! {
+            #member: #deserialize(&#this.#member, deserializer)?
+        });
+    }
+    let link = link.unwrap();
+
+    let deserialize_node = if deserialize_fields.len() > 1 {
+        quote! {
+            |#this, deserializer| ::core::result::Result::Ok(#return_type {
+                #(#deserialize_fields,)*
+            })
+        }
+    } else {
+        quote! {
+            |_, _| ::core::result::Result::Ok(#return_type {
+                #(#deserialize_fields,)*
+            })
+        }
+    };
+
+    Ok(quote! {
+        #rkyv_path::iterative::deserialize_chain(
+            #this,
+            |#this| #this.#link.as_ref().map(|__link| __link.get()),
+            #deserialize_node,
+            |__value| &mut __value.#link,
+            deserializer,
+        )
+    })
+}
@@ -51,7 +51,45 @@ pub fn derive_portable(
 /// - `crate = ..`: Chooses an alternative crate path to import rkyv from.
 /// - `compare(..)`: Implements common comparison operators between the original
 ///   and archived types. Supported comparisons are `PartialEq` and `PartialOrd`
-///   (i.e. `#[rkyv(compare(PartialEq, PartialOrd))]`).
+///   (i.e. `#[rkyv(compare(PartialEq, PartialOrd))]`). Additionally, `Eq`,
+///   `Ord`, and `Hash` implement `PartialEq + Eq`, `PartialOrd + Ord`, and
+///   `Hash` for the archived type so that they agree with the derived impls of
+///   the original type. As long as the archived types of all fields also hash
+///   like their originals, an archived value hashes the same as the value it
+///   was serialized from.
+/// - `diff`: Implements `rkyv::diff::Diff` for the archived type, which reports
+///   the paths of the fields that changed between two archived values. The
+///   archived types of all fields must also implement `Diff`.
+/// - `type_hash`: Implements `rkyv::type_hash::TypeHash` for the archived type,
+///   which hashes the names, archived types, and layout of its fields. The
+///   archived types of all fields must also implement `TypeHash`.
+/// - `layout`: Implements `rkyv::layout::DescribeLayout` for the archived type,
+///   which describes the offsets, sizes, and byte order of its fields. The
+///   archived types of all fields must also implement `DescribeLayout`.
+/// - `stable_format`: Implements `rkyv::stable::StableFormat` for the archived
+///   type, which marks it as having a stable format. The archived types of all
+///   fields must also implement `StableFormat`, or the impl fails to compile.
+/// - `seal`: Generates a method for each field of a struct which projects a
+///   sealed archived value to that field (e.g. `ArchivedFoo::bar_seal` for a
+///   field named `bar`, or `ArchivedFoo::field_0_seal` for the first field of a
+///   tuple struct). This avoids having to `munge` sealed values to mutate their
+///   fields.
+/// - `field_offsets`: Generates an associated constant for each field of a
+///   struct with its `rkyv::projection::FieldOffset` (e.g. `ArchivedFoo::BAR`
+///   for a field named `bar`, or `ArchivedFoo::FIELD_0` for the first field of
+///   a tuple struct). These can be passed to `access_field` to validate and
+///   access just one field of an archive.
+/// - `derive_serde`: Implements `serde::Serialize` and
+///   `rkyv::serde::SerializeArchived` for the archived type, using the names of
+///   the original type and its fields. Requires rkyv's `serde-1` feature.
+/// - `max_size`: Implements `rkyv::max_size::MaxSize` for the type, which
+///   bounds its serialized size at compile time. All fields must implement
+///   `MaxSize`, or use a wrapper which implements `MaxSizeWith`.
+/// - `getters`: Generates a method for each field of a struct which returns a
+///   native view of that field (e.g. `u32` instead of `u32_le`, or `&str`
+///   instead of `&ArchivedString`). Methods are named after their fields, or
+///   `field_0` and so on for tuple structs. The archived types of all fields
+///   must implement `rkyv::native::AsNative`.
 /// - `{archive, serialize, deserialize}_bounds(..)`: Adds additional bounds to
 ///   trait implementations. This can be useful for recursive types, where
 ///   bounds may need to be omitted to prevent recursive trait impls.
@@ -64,14 +102,75 @@ pub fn derive_portable(
 ///   default, archived types are named "Archived" + `the name of the type`.
 /// - `resolver = ..`: Changes the name of the generated resolver type. By
 ///   default, resolver types are named `the name of the type` + "Resolver".
+/// - `resolver_builder`: Generates a builder for the resolver type of a
+///   struct, named after the resolver type + "Builder". The builder has a
+///   `set_` method to set the resolver for each field and a `serialize_`
+///   method to serialize a field and set its resolver, which makes it easier
+///   to write `Serialize` impls by hand. Fields of tuple structs are named
+///   `field_0` and so on.
 /// - `remote = ..`: Generate a remote derive for the annotated type instead of
-///   a regular derive.
+///   a regular derive. By default, the remote type must implement
+///   `From<{Self}>` to be deserialized.
+/// - `from = ..`: Constructs the remote type of a remote derive with the given
+///   function instead of `From`. The function is called with the deserialized
+///   fields in declaration order, so a constructor like `Duration::new` can be
+///   used directly. Only supported for structs.
+/// - `non_exhaustive`: Allows a remote derive to cover an enum which is
+///   `#[non_exhaustive]` upstream. Serializing a remote variant which is not
+///   part of the local definition returns an error instead of failing to
+///   compile. Use an `#[rkyv(other)]` variant instead to archive unknown
+///   variants as a fallback.
+/// - `version = ..`: Implements `Versioned` for the type and its archived type
+///   with the given schema version.
+/// - `columns`: Generates an `Archived{Name}Columns` type which stores each
+///   field of a sequence of values in its own archived vec. `Vec`s of the type
+///   can then be archived as columns with `rkyv::with::AsColumns`.
+/// - `transparent`: Archives a struct with exactly one field as that field, so
+///   the archived type is the archived type of the field and no archived or
+///   resolver types are generated. This is similar to `#[serde(transparent)]`.
+/// - `niche_discriminant`: Archives an enum with one unit variant and one
+///   variant with exactly one field as a `NichedOption` of that field, storing
+///   the unit variant in a niche of the field instead of in a separate
+///   discriminant. The field's niching can be chosen with `#[rkyv(niche = ..)]`
+///   and defaults to `DefaultNiche`. No archived or resolver types are
+///   generated.
 ///
 /// ## Fields only
 ///
 /// - `with = ..`: Applies the given wrapper type to the field.
+/// - `hasher = ..`: Archives a hash map or set field with the given hasher.
+///   This is shorthand for `with = HashWith<..>`.
 /// - `omit_bounds`: Omits trait bounds for the annotated field in the generated
 ///   impl.
+/// - `getter = ..`: Reads the field from the remote type of a remote derive by
+///   calling the given function with a reference to the remote value. The
+///   function may return the field by value or by reference. This allows
+///   remote types with private fields to be archived.
+/// - `skip_getter`: Does not generate a getter for the field when used with
+///   `getters`.
+/// - `default`: Marks a field of a versioned struct as absent from archives of
+///   older versions. `default` fields must come after all other fields, and the
+///   field type must implement `Default`. Archives which are missing the field
+///   can be accessed with `access_compat` and deserialized with
+///   `from_bytes_compat`, which fills the field with its default value.
+/// - `since = ..`: Sets the version a `default` field was added in. Defaults to
+///   the version of the type.
+/// - `bits = ..`: Sets the number of bits a field packed with `with = BitPack`
+///   takes up. May be omitted for `bool` fields.
+/// - `iterative`: Marks an `Option<Box<Self>>` field of a struct as the link
+///   of a chain. `Serialize` and `Deserialize` follow the link with a loop
+///   instead of recursing, so long chains don't overflow the stack. Implies
+///   `omit_bounds`. At most one field of a struct may be marked. See
+///   `rkyv::iterative` for more information.
+///
+/// ## Variants only
+///
+/// - `other`: Marks the last unit variant of a remote enum as the fallback for
+///   remote variants which are not part of the local definition. The archived
+///   enum accepts every discriminant, and unrecognized discriminants (e.g. from
+///   a newer version of the enum) deserialize to this variant. Remote variants
+///   which are not part of the local definition also serialize as this
+///   variant.
 ///
 /// # Recursive types
 ///
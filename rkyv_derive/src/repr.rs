@@ -129,6 +129,18 @@ impl Repr {
         }
     }
 
+    pub fn is_packed(&self) -> bool {
+        matches!(
+            self,
+            Self::C {
+                modifier: Some(Modifier::Packed(_)),
+                ..
+            } | Self::Rust {
+                modifier: Some(Modifier::Packed(_)),
+            }
+        )
+    }
+
     pub fn is_struct_well_defined(&self) -> bool {
         !matches!(self, Self::Rust { .. })
     }
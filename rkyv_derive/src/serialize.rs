@@ -6,8 +6,12 @@ use syn::{
 };
 
 use crate::{
+    archive::columns::{columns_fields, columns_resolver_name},
     attributes::{Attributes, FieldAttributes, VariantAttributes},
-    util::{strip_generics_from_path, strip_raw},
+    util::{
+        niched_variants, single_field, strip_generics_from_path, strip_raw,
+        NichedVariants,
+    },
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -96,7 +100,7 @@ fn derive_serialize_impl(
             parse_quote!(#name),
         )?;
 
-        Ok(quote! {
+        let mut result = quote! {
             #[automatically_derived]
             impl #impl_generics #rkyv_path::Serialize<__S>
                 for #name #ty_generics
@@ -113,8 +117,91 @@ fn derive_serialize_impl(
                     #body
                 }
             }
-        })
+        };
+
+        if let Some(ref columns) = attributes.columns {
+            let fields = columns_fields(&input, columns)?;
+            result.extend(generate_serialize_columns_impl(
+                &input,
+                attributes,
+                fields,
+                &rkyv_path,
+                serialize_where,
+            )?);
+        }
+
+        Ok(result)
+    }
+}
+
+fn generate_serialize_columns_impl(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    fields: &Fields,
+    rkyv_path: &Path,
+    mut serialize_where: WhereClause,
+) -> Result<TokenStream, Error> {
+    serialize_where.predicates.push(parse_quote! {
+        __S: #rkyv_path::ser::Writer + #rkyv_path::ser::Allocator
+    });
+
+    let mut resolver_values = Vec::new();
+    for (field, member) in fields.iter().zip(fields.members()) {
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        let ty = &field.ty;
+        let archived = field_attrs.archived(rkyv_path, field);
+        let (value_ty, access_value) = if let Some(ref with) = field_attrs.with
+        {
+            (
+                quote! { #rkyv_path::with::With<#ty, #with> },
+                quote! {
+                    #rkyv_path::with::With::<#ty, #with>::cast(&value.#member)
+                },
+            )
+        } else {
+            (quote! { #ty }, quote! { &value.#member })
+        };
+
+        resolver_values.push(quote! {
+            #member: #rkyv_path::vec::ArchivedVec::<#archived>
+                ::serialize_from_iter::<#value_ty, _, _>(
+                    values.iter().map(|value| #access_value),
+                    serializer,
+                )?
+        });
     }
+
+    let name = &input.ident;
+    let resolver = columns_resolver_name(name);
+    let mut impl_generics = input.generics.clone();
+    impl_generics.params.insert(
+        0,
+        parse_quote! { __S: #rkyv_path::rancor::Fallible + ?Sized },
+    );
+    let (impl_generics, ..) = impl_generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #rkyv_path::columns::SerializeColumns<__S>
+            for #name #ty_generics
+        #serialize_where
+        {
+            fn serialize_columns(
+                values: &[Self],
+                serializer: &mut __S,
+            ) -> ::core::result::Result<
+                <
+                    Self as #rkyv_path::columns::ArchiveColumns
+                >::ColumnsResolver,
+                <__S as #rkyv_path::rancor::Fallible>::Error,
+            > {
+                ::core::result::Result::Ok(#resolver {
+                    #(#resolver_values,)*
+                })
+            }
+        }
+    })
 }
 
 fn generate_serialize_body(
@@ -127,30 +214,99 @@ fn generate_serialize_body(
 ) -> Result<TokenStream, Error> {
     let this = Ident::new("__this", Span::call_site());
     let body = match input.data {
+        _ if attributes.transparent.is_some() => {
+            let (field, member) = single_field(&input.data).unwrap();
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+
+            serialize_where
+                .predicates
+                .extend(field_attrs.serialize_bound(rkyv_path, field));
+
+            let access_field = field_attrs.access_field(&this, &member);
+            let serialize = field_attrs.serialize(rkyv_path, field);
+            return Ok(quote! { #serialize(#access_field, serializer) });
+        }
+        _ if attributes.niche_discriminant.is_some() => {
+            let NichedVariants {
+                unit,
+                payload,
+                field,
+                member,
+            } = niched_variants(&input.data).unwrap();
+            let field_attrs = FieldAttributes::parse(attributes, field)?;
+
+            serialize_where
+                .predicates
+                .extend(field_attrs.serialize_bound(rkyv_path, field));
+
+            let (unit, payload) = (&unit.ident, &payload.ident);
+            let serialize = field_attrs.serialize(rkyv_path, field);
+            quote! {
+                match __this {
+                    #name::#payload { #member: __field } => {
+                        ::core::option::Option::Some(
+                            #serialize(__field, serializer)?
+                        )
+                    }
+                    #name::#unit => ::core::option::Option::None,
+                }
+            }
+        }
+        Data::Struct(ref data) if attributes.iterative.is_some() => {
+            return generate_iterative_serialize_body(
+                &data.fields,
+                attributes,
+                serialize_where,
+                rkyv_path,
+                resolver,
+            );
+        }
         Data::Struct(ref data) => match data.fields {
             Fields::Named(ref fields) => {
-                let resolver_values = fields
-                    .named
-                    .iter()
-                    .map(|field| {
-                        let field_attrs =
-                            FieldAttributes::parse(attributes, field)?;
+                let mut bit_pack_checks = Vec::new();
+                let mut resolver_values = Vec::new();
+                for field in fields.named.iter() {
+                    let field_attrs =
+                        FieldAttributes::parse(attributes, field)?;
+                    let name = &field.ident;
 
-                        serialize_where.predicates.extend(
-                            field_attrs.serialize_bound(rkyv_path, field),
-                        );
+                    if let Some(width) = field_attrs.bit_pack {
+                        let ty = &field.ty;
+                        let with = &field_attrs.with;
+                        bit_pack_checks.push(quote! {
+                            <#with>::check_fits::<
+                                #ty,
+                                <__S as #rkyv_path::rancor::Fallible>::Error,
+                            >(#this.#name, #width)?;
+                        });
+                        continue;
+                    }
 
-                        let name = &field.ident;
-                        let access_field =
-                            field_attrs.access_field(&this, name);
-                        let serialize = field_attrs.serialize(rkyv_path, field);
-                        Ok(quote! {
-                            #name: #serialize(#access_field, serializer)?
-                        })
-                    })
-                    .collect::<Result<Vec<_>, Error>>()?;
+                    serialize_where
+                        .predicates
+                        .extend(field_attrs.serialize_bound(rkyv_path, field));
 
-                quote! { #resolver { #(#resolver_values,)* } }
+                    let access_field = field_attrs.access_field(&this, name);
+                    let serialize = field_attrs.serialize(rkyv_path, field);
+                    resolver_values.push(quote! {
+                        #name: #serialize(#access_field, serializer)?
+                    });
+                }
+
+                if bit_pack_checks.is_empty() {
+                    quote! { #resolver { #(#resolver_values,)* } }
+                } else {
+                    serialize_where.predicates.push(parse_quote! {
+                        <__S as #rkyv_path::rancor::Fallible>::Error:
+                            #rkyv_path::rancor::Source
+                    });
+                    quote! {
+                        {
+                            #(#bit_pack_checks)*
+                            #resolver { #(#resolver_values,)* }
+                        }
+                    }
+                }
             }
             Fields::Unnamed(ref fields) => {
                 let resolver_values = fields
@@ -279,9 +435,33 @@ fn generate_serialize_body(
                 })
                 .collect::<Result<Vec<_>, Error>>()?;
 
+            let unknown_arm = attributes.remote.as_ref().and_then(|remote| {
+                attributes.non_exhaustive.as_ref()?;
+                serialize_where.predicates.push(parse_quote! {
+                    <__S as #rkyv_path::rancor::Fallible>::Error:
+                        #rkyv_path::rancor::Source
+                });
+                Some(quote! {
+                    #[allow(unreachable_patterns)]
+                    _ => {
+                        return ::core::result::Result::Err(
+                            <
+                                <__S as #rkyv_path::rancor::Fallible>::Error
+                                    as #rkyv_path::rancor::Source
+                            >::new(
+                                #rkyv_path::with::UnknownRemoteVariant::new::<
+                                    #remote
+                                >(),
+                            ),
+                        );
+                    }
+                })
+            });
+
             quote! {
                 match __this {
                     #(#serialize_arms,)*
+                    #unknown_arm
                 }
             }
         }
@@ -295,3 +475,89 @@ fn generate_serialize_body(
 
     Ok(quote! { ::core::result::Result::Ok(#body) })
 }
+
+/// Serializes a struct with an `iterative` field by walking the chain of
+/// values linked through that field with `serialize_chain`.
+fn generate_iterative_serialize_body(
+    fields: &Fields,
+    attributes: &Attributes,
+    serialize_where: &mut WhereClause,
+    rkyv_path: &Path,
+    resolver: Ident,
+) -> Result<TokenStream, Error> {
+    let this = Ident::new("__this", Span::call_site());
+    let link_index = attributes.iterative.unwrap();
+
+    serialize_where.predicates.push(parse_quote! {
+        __S: #rkyv_path::ser::Writer
+    });
+
+    let mut link = None;
+    let mut prefix_bindings = Vec::new();
+    let mut prefix_values = Vec::new();
+    let mut resolver_values = Vec::new();
+    for (i, (field, member)) in fields.iter().zip(fields.members()).enumerate()
+    {
+        if i == link_index {
+            link = Some(member.clone());
+            resolver_values.push(quote! { __link });
+            continue;
+        }
+
+        let field_attrs = FieldAttributes::parse(attributes, field)?;
+        serialize_where
+            .predicates
+            .extend(field_attrs.serialize_bound(rkyv_path, field));
+
+        let access_field = field_attrs.access_field(&this, &member);
+        let serialize = field_attrs.serialize(rkyv_path, field);
+        let value = quote! { #serialize(#access_field, serializer)? };
+        if i < link_index {
+            let binding = Ident::new(&format!("__prefix_{}", i), field.span());
+            prefix_values.push(value);
+            resolver_values.push(quote! { #binding });
+            prefix_bindings.push(binding);
+        } else {
+            resolver_values.push(value);
+        }
+    }
+    let link = link.unwrap();
+
+    let prefix = if prefix_values.is_empty() {
+        quote! { |_, _| ::core::result::Result::Ok(()) }
+    } else {
+        quote! {
+            |#this, serializer| ::core::result::Result::Ok((
+                #(#prefix_values,)*
+            ))
+        }
+    };
+
+    let (finish_this, finish_serializer) = if link_index + 1 < fields.len() {
+        (quote! { #this }, quote! { serializer })
+    } else {
+        (quote! { _ }, quote! { _ })
+    };
+    let construct = match fields {
+        Fields::Named(_) => {
+            let names = fields.members();
+            quote! { #resolver { #(#names: #resolver_values,)* } }
+        }
+        _ => quote! { #resolver(#(#resolver_values,)*) },
+    };
+
+    Ok(quote! {
+        #rkyv_path::iterative::serialize_chain(
+            #this,
+            |#this| #this.#link.as_deref(),
+            #prefix,
+            |
+                #finish_this,
+                (#(#prefix_bindings,)*),
+                __link,
+                #finish_serializer,
+            | ::core::result::Result::Ok(#construct),
+            serializer,
+        )
+    })
+}
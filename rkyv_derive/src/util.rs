@@ -2,8 +2,8 @@ use core::iter::FlatMap;
 
 use proc_macro2::Ident;
 use syn::{
-    punctuated::Iter, Data, DataEnum, DataStruct, DataUnion, Field, Path,
-    PathArguments, Variant,
+    punctuated::Iter, Data, DataEnum, DataStruct, DataUnion, Field, Fields,
+    Member, Path, PathArguments, Variant,
 };
 
 pub fn strip_raw(ident: &Ident) -> String {
@@ -50,6 +50,55 @@ pub fn iter_fields(data: &Data) -> FieldsIter<'_> {
     }
 }
 
+/// Returns the field of a struct with exactly one field, or `None` if the data
+/// is not a struct with exactly one field.
+pub fn single_field(data: &Data) -> Option<(&Field, Member)> {
+    match data {
+        Data::Struct(DataStruct { fields, .. }) if fields.len() == 1 => {
+            fields.iter().zip(fields.members()).next()
+        }
+        _ => None,
+    }
+}
+
+/// The variants of an enum which can store its discriminant in a niche of its
+/// only field.
+pub struct NichedVariants<'a> {
+    pub unit: &'a Variant,
+    pub payload: &'a Variant,
+    pub field: &'a Field,
+    pub member: Member,
+}
+
+/// Returns the variants of an enum with one unit variant and one variant with
+/// exactly one field, or `None` if the data is not such an enum.
+pub fn niched_variants(data: &Data) -> Option<NichedVariants<'_>> {
+    let Data::Enum(DataEnum { variants, .. }) = data else {
+        return None;
+    };
+    if variants.len() != 2 {
+        return None;
+    }
+
+    let (unit, payload) = if variants[0].fields.is_empty() {
+        (&variants[0], &variants[1])
+    } else {
+        (&variants[1], &variants[0])
+    };
+    if !matches!(unit.fields, Fields::Unit) || payload.fields.len() != 1 {
+        return None;
+    }
+
+    let (field, member) =
+        payload.fields.iter().zip(payload.fields.members()).next()?;
+    Some(NichedVariants {
+        unit,
+        payload,
+        field,
+        member,
+    })
+}
+
 pub fn strip_generics_from_path(mut path: Path) -> Path {
     for segment in path.segments.iter_mut() {
         segment.arguments = PathArguments::None;
@@ -0,0 +1,127 @@
+//! Link-time registration of trait impls by type fingerprint.
+
+use std::sync::OnceLock;
+
+use crate::{ImplId, TraitImpl};
+
+/// A trait impl registered at link time.
+///
+/// Entries are created with [`register_trait_impl`](crate::register_trait_impl)
+/// and collected into [`TRAIT_IMPL_ENTRIES`].
+pub struct TraitImplEntry {
+    /// The fingerprint of the implementing type and trait.
+    pub impl_id: ImplId,
+    /// The name that the fingerprint was computed from.
+    pub name: &'static str,
+    /// Returns the trait object metadata for the trait impl.
+    pub trait_impl: fn() -> TraitImpl,
+}
+
+/// All trait impls registered with
+/// [`register_trait_impl`](crate::register_trait_impl).
+///
+/// This slice is assembled by the linker, so it does not need to be initialized
+/// at runtime.
+#[linkme::distributed_slice]
+pub static TRAIT_IMPL_ENTRIES: [TraitImplEntry];
+
+/// Returns the link-time trait impl entries sorted by impl ID.
+///
+/// # Panics
+///
+/// Panics if two of the entries have the same impl ID. This is checked the
+/// first time that any entry is looked up.
+fn sorted_entries() -> &'static [&'static TraitImplEntry] {
+    static SORTED_ENTRIES: OnceLock<Box<[&'static TraitImplEntry]>> =
+        OnceLock::new();
+
+    SORTED_ENTRIES.get_or_init(|| {
+        let mut entries = TRAIT_IMPL_ENTRIES.iter().collect::<Box<[_]>>();
+        entries.sort_unstable_by_key(|entry| entry.impl_id);
+        for pair in entries.windows(2) {
+            if pair[0].impl_id == pair[1].impl_id {
+                panic!(
+                    "trait impls `{}` and `{}` both hash to impl ID {:#x}; \
+                     register one of them with a different name",
+                    pair[0].name, pair[1].name, pair[0].impl_id,
+                );
+            }
+        }
+        entries
+    })
+}
+
+/// Returns the link-time entry with the given fingerprint, if any.
+pub(crate) fn find_entry(impl_id: ImplId) -> Option<&'static TraitImplEntry> {
+    let entries = sorted_entries();
+    let index = entries
+        .binary_search_by_key(&impl_id, |entry| entry.impl_id)
+        .ok()?;
+    Some(entries[index])
+}
+
+/// Returns an iterator over all of the trait impls registered at link time.
+pub(crate) fn entry_trait_impls() -> impl Iterator<Item = TraitImpl> {
+    sorted_entries().iter().map(|entry| (entry.trait_impl)())
+}
+
+/// Registers a trait impl at link time under a stable fingerprint.
+///
+/// The fingerprint is computed from the same name as
+/// `register_trait_impls!(hashed; ..)`, which is the module path of the
+/// invocation followed by `"::$type as $trait"` spelled as written. Trait impls
+/// can be moved between the two forms of registration without changing their
+/// impl IDs. To keep archived data readable after renaming or moving a type,
+/// pass the original name explicitly with `= "..."`. Like hashed impl IDs, the
+/// fingerprint is truncated to an [`ImplId`].
+///
+/// Unlike [`register_trait_impls!`](crate::register_trait_impls), no runtime
+/// initialization is required before accessing archived trait objects. Both
+/// forms may be used in the same program, and registration panics if an impl
+/// ID is used by both. If two trait impls registered with this macro hash to
+/// the same impl ID, the first lookup of any archived trait object panics.
+///
+/// This macro is available with the `linkme` feature.
+///
+/// # Example
+///
+/// ```
+/// use rkyv_dyn::register_trait_impl;
+///
+/// struct MyType;
+///
+/// #[ptr_meta::pointee]
+/// trait MyTrait {}
+///
+/// impl MyTrait for MyType {}
+///
+/// register_trait_impl!(MyType as dyn MyTrait);
+/// ```
+#[macro_export]
+macro_rules! register_trait_impl {
+    ($type:ty as $trait:ty) => {
+        $crate::register_trait_impl!(
+            $type as $trait =
+                $crate::register_trait_impls!(@name $type as $trait,)
+        );
+    };
+    ($type:ty as $trait:ty = $name:expr) => {
+        const _: () = {
+            // SAFETY: Fingerprints are derived from the names of trait impls,
+            // and duplicate fingerprints are rejected before any lookup.
+            unsafe impl $crate::RegisteredImpl<$trait> for $type {
+                const IMPL_ID: $crate::ImplId =
+                    $crate::fingerprint($name) as $crate::ImplId;
+            }
+
+            #[$crate::linkme::distributed_slice($crate::TRAIT_IMPL_ENTRIES)]
+            #[linkme(crate = $crate::linkme)]
+            static TRAIT_IMPL_ENTRY: $crate::TraitImplEntry =
+                $crate::TraitImplEntry {
+                    impl_id: <$type as $crate::RegisteredImpl<$trait>>::IMPL_ID,
+                    name: $name,
+                    trait_impl: || $crate::trait_impl!($type as $trait),
+                };
+        };
+    };
+}
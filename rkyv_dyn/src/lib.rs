@@ -8,32 +8,68 @@
 //! ## Features
 //!
 //! - `bytecheck`: Enables validation support through `bytecheck`.
+//! - `linkme`: Adds `register_trait_impl!`, which registers trait impls at link
+//!   time under stable fingerprints. Archived trait objects of those impls can
+//!   be accessed without calling [`register_trait_impls!`] first.
 
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(missing_docs)]
 #![deny(rustdoc::missing_crate_level_docs)]
 
+#[cfg(feature = "linkme")]
+mod fingerprint;
 mod lazy_static;
-// TODO: re-enable
-// #[cfg(feature = "bytecheck")]
-// mod bytecheck;
+#[cfg(feature = "bytecheck")]
+pub mod validation;
 
-use core::{hash, marker::PhantomData};
+use core::{any::TypeId, hash, marker::PhantomData, mem::transmute};
 
+#[cfg(feature = "linkme")]
+pub use fingerprint::*;
 pub use lazy_static::LazyStatic;
+#[cfg(feature = "linkme")]
+#[doc(hidden)]
+pub use linkme;
 use ptr_meta::{DynMetadata, Pointee};
 use rancor::Fallible;
 use rkyv::{
     de::Pooling,
-    place::Initialized,
-    primitive::FixedUsize,
     ser::{Allocator, Sharing, Writer},
+    traits::NoUndef,
     Archived, Portable, Serialize, SerializeUnsized,
 };
-pub use rkyv_dyn_derive::archive_dyn;
+pub use rkyv_dyn_derive::{archive_dyn, archive_dyn_impls};
 
 /// The type of trait impl IDs.
-pub type ImplId = FixedUsize;
+///
+/// Trait impl IDs are assigned by [`register_trait_impls`], either in the order
+/// that trait impls are registered or by hashing their names. With the `linkme`
+/// feature, `register_trait_impl!` also assigns them by hashing their names.
+pub type ImplId = rkyv::primitive::FixedUsize;
+
+/// Computes the stable 64-bit fingerprint of a trait impl from its name.
+///
+/// This is the 64-bit FNV-1a hash of the name. It does not depend on the
+/// compiler version or the layout of the program, so archived fingerprints
+/// remain valid across builds.
+///
+/// [`ImplId`] has the configured pointer width, so hashed impl IDs are the low
+/// bits of the fingerprint (`fingerprint(name) as ImplId`). With the default
+/// 32-bit pointer width, only 32 bits of the fingerprint are archived.
+pub const fn fingerprint(name: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let bytes = name.as_bytes();
+    let mut hash = OFFSET_BASIS;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        i += 1;
+    }
+    hash
+}
 
 /// An object-safe version of `Serializer`.
 ///
@@ -77,13 +113,22 @@ impl<E> AsDynSerializer<E> for dyn DynSerializer<E> {
 /// 2. Implement your trait for your type and add the attribute `#[archive_dyn]`
 ///    to it. Make sure to implement your trait for your archived type as well.
 ///    This invocation must have the same attributes as the trait invocation.
+///    For generic impls, list the concrete instantiations with
+///    [`archive_dyn_impls`] instead.
 /// 3. If deserialization support is desired, add `deserialize` or `deserialize
 ///    = "..."` as parameters and implement `Deserialize` for the type. By
 ///    default, the deserialize trait will be named "Deserialize" + your trait
 ///    name. Passing a trait name will use that name instead.
+/// 4. Register the archived implementations with [`register_trait_impls`]
+///    before accessing any archived trait objects.
 ///
 /// Then you're ready to serialize boxed trait objects!
 ///
+/// The generated traits are generic over the error type of the serializer and,
+/// if deserialization is enabled, the error type of the deserializer. Archived
+/// implementations must be registered for each combination of error types
+/// that they are used with.
+///
 /// Even though your deserialized values are boxed as serialize trait objects,
 /// your archived values are boxed as regular trait objects. This is because
 /// your deserialized values have to implement `SerializeDyn` but your archived
@@ -95,9 +140,8 @@ impl<E> AsDynSerializer<E> for dyn DynSerializer<E> {
 ///
 /// ```
 /// use rkyv::{
-///     archived_value,
-///     ser::{serializers::AllocSerializer, Serializer},
-///     AlignedVec, Archive, Archived, Deserialize, Infallible, Serialize,
+///     access_unchecked, deserialize, rancor::Error, to_bytes, Archive,
+///     Archived, Deserialize, Serialize,
 /// };
 /// use rkyv_dyn::archive_dyn;
 ///
@@ -138,30 +182,29 @@ impl<E> AsDynSerializer<E> for dyn DynSerializer<E> {
 ///     }
 /// }
 ///
-/// let boxed_int = Box::new(IntStruct(42)) as Box<dyn SerializeExampleTrait>;
-/// let boxed_string = Box::new(StringStruct("hello world".to_string()))
-///     as Box<dyn SerializeExampleTrait>;
-/// let mut serializer = AllocSerializer::<256>::default();
-/// let int_pos = serializer
-///     .serialize_value(&boxed_int)
-///     .expect("failed to archive boxed int");
-/// let str_pos = serializer
-///     .serialize_value(&boxed_string)
-///     .expect("failed to archive boxed string");
-/// let buf = serializer.into_serializer().into_inner();
-/// let archived_int = unsafe {
-///     archived_value::<Box<dyn SerializeExampleTrait>>(buf.as_ref(), int_pos)
-/// };
-/// let archived_string = unsafe {
-///     archived_value::<Box<dyn SerializeExampleTrait>>(buf.as_ref(), str_pos)
-/// };
+/// rkyv_dyn::register_trait_impls! {
+///     ArchivedStringStruct as dyn DeserializeExampleTrait<Error, Error>,
+///     ArchivedIntStruct as dyn DeserializeExampleTrait<Error, Error>,
+/// }
+///
+/// type Example = Box<dyn SerializeExampleTrait<Error, Error>>;
+///
+/// let boxed_int = Box::new(IntStruct(42)) as Example;
+/// let boxed_string =
+///     Box::new(StringStruct("hello world".to_string())) as Example;
+/// let int_bytes = to_bytes::<Error>(&boxed_int).unwrap();
+/// let string_bytes = to_bytes::<Error>(&boxed_string).unwrap();
+/// let archived_int =
+///     unsafe { access_unchecked::<Archived<Example>>(&int_bytes) };
+/// let archived_string =
+///     unsafe { access_unchecked::<Archived<Example>>(&string_bytes) };
 /// assert_eq!(archived_int.value(), "42");
 /// assert_eq!(archived_string.value(), "hello world");
 ///
-/// let deserialized_int: Box<dyn SerializeExampleTrait> =
-///     archived_int.deserialize(&mut Infallible).unwrap();
-/// let deserialized_string: Box<dyn SerializeExampleTrait> =
-///     archived_string.deserialize(&mut Infallible).unwrap();
+/// let deserialized_int =
+///     deserialize::<Example, Error>(archived_int).unwrap();
+/// let deserialized_string =
+///     deserialize::<Example, Error>(archived_string).unwrap();
 /// assert_eq!(deserialized_int.value(), "42");
 /// assert_eq!(deserialized_string.value(), "hello world");
 /// ```
@@ -241,8 +284,8 @@ pub struct ArchivedDynMetadata<T: ?Sized> {
 // SAFETY: `ArchivedDynMetadata<T>` is a transparent wrapper around an archived
 // `ImplId`, so if that archived type is initialized then so is
 // `ArchivedDynMetadata<T>`.
-unsafe impl<T: ?Sized> Initialized for ArchivedDynMetadata<T> where
-    Archived<ImplId>: Initialized
+unsafe impl<T: ?Sized> NoUndef for ArchivedDynMetadata<T> where
+    Archived<ImplId>: NoUndef
 {
 }
 
@@ -271,14 +314,23 @@ impl<T: ?Sized> ArchivedDynMetadata<T> {
 
     /// Returns the pointer metadata for the trait object this metadata refers
     /// to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no trait impl was registered with the impl ID of this
+    /// metadata, or if the trait impl was registered for a different trait.
     pub fn lookup_metadata(&self) -> DynMetadata<T> {
-        unsafe {
-            TRAIT_IMPLS
-                .get()
-                .expect("TRAIT_IMPLS was not initialized for rkyv_dyn")
-                [self.impl_id() as usize]
-                .downcast_metadata()
-        }
+        let impl_id = self.impl_id();
+        let trait_impl = find_trait_impl(impl_id).unwrap_or_else(|| {
+            panic!("no trait impl was registered for impl ID {:#x}", impl_id)
+        });
+        trait_impl.try_downcast_metadata().unwrap_or_else(|| {
+            panic!(
+                "the trait impl registered for impl ID {:#x} is for a \
+                 different trait",
+                impl_id,
+            )
+        })
     }
 }
 
@@ -288,6 +340,12 @@ impl<T: ?Sized> Clone for ArchivedDynMetadata<T> {
     }
 }
 
+impl<T: ?Sized> Default for ArchivedDynMetadata<T> {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
 impl<T: ?Sized> hash::Hash for ArchivedDynMetadata<T> {
     fn hash<H: hash::Hasher>(&self, state: &mut H) {
         self.impl_id.hash(state);
@@ -314,12 +372,46 @@ impl<T: ?Sized> Ord for ArchivedDynMetadata<T> {
     }
 }
 
+/// Returns the `TypeId` of `T` with all of its lifetimes erased.
+///
+/// Trait objects may have non-`'static` generic parameters, so they can't be
+/// required to be `'static`.
+fn erased_type_id<T: ?Sized>() -> TypeId {
+    trait NonStaticAny {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+
+    let phantom = PhantomData::<T>;
+    // SAFETY: `PhantomData` has no data, so extending the lifetime of the
+    // trait object can't cause any borrows to outlive their referents. Only the
+    // lifetimes of `T` are erased from the returned `TypeId`.
+    let phantom = unsafe {
+        transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom)
+    };
+    phantom.type_id()
+}
+
 /// The trait object metadata for a trait implementation.
 #[derive(Clone, Copy, Debug)]
 pub struct TraitImpl {
+    // The `dyn Trait` that this is an implementation of. Impl IDs are shared
+    // between all traits, so this is checked before downcasting `metadata`.
+    trait_id: TypeId,
     // The type of this `DynMetadata` is erased. Whatever uses it will
     // transmute it to the correct `DynMetadata<T>`.
     metadata: DynMetadata<()>,
+    check_bytes: Option<__private::CheckBytesFn>,
 }
 
 impl TraitImpl {
@@ -339,13 +431,43 @@ impl TraitImpl {
     /// Creates a new trait impl from its trait object metadata.
     pub fn from_metadata<T: ?Sized>(metadata: DynMetadata<T>) -> Self {
         Self {
+            trait_id: erased_type_id::<T>(),
             // SAFETY: All `DynMetadata<T>` have the same layout and validity.
             // They all contain a single erased `&'static VTable` reference and
             // a `PhantomData<T>`.
-            metadata: unsafe { core::mem::transmute(metadata) },
+            metadata: unsafe {
+                transmute::<DynMetadata<T>, DynMetadata<()>>(metadata)
+            },
+            check_bytes: None,
         }
     }
 
+    /// Sets the function used to validate the concrete type of this trait
+    /// implementation.
+    ///
+    /// This is called by [`trait_impl!`] when the concrete type implements
+    /// `CheckBytes` for [`DynValidator`](validation::DynValidator).
+    #[doc(hidden)]
+    pub fn with_check_bytes(
+        mut self,
+        check_bytes: Option<__private::CheckBytesFn>,
+    ) -> Self {
+        self.check_bytes = check_bytes;
+        self
+    }
+
+    /// Returns whether this is an implementation of the given `dyn Trait`.
+    pub fn is_trait<T: ?Sized>(&self) -> bool {
+        self.trait_id == erased_type_id::<T>()
+    }
+
+    /// Returns whether this trait implementation has the given trait object
+    /// metadata.
+    pub fn is_metadata<T: ?Sized>(&self, metadata: DynMetadata<T>) -> bool {
+        self.is_trait::<T>()
+            && Self::from_metadata(metadata).metadata == self.metadata
+    }
+
     /// Returns the trait object metadata of this trait implementation downcast
     /// to the given type.
     ///
@@ -353,19 +475,38 @@ impl TraitImpl {
     ///
     /// `T` must be the `dyn Trait` that this `TraitImpl` corresponds to.
     pub unsafe fn downcast_metadata<T: ?Sized>(&self) -> DynMetadata<T> {
-        unsafe { core::mem::transmute(self.metadata) }
+        unsafe { transmute(self.metadata) }
+    }
+
+    /// Returns the trait object metadata of this trait implementation downcast
+    /// to the given type, or `None` if `T` is not the `dyn Trait` that this
+    /// `TraitImpl` corresponds to.
+    pub fn try_downcast_metadata<T: ?Sized>(&self) -> Option<DynMetadata<T>> {
+        if self.is_trait::<T>() {
+            // SAFETY: We checked that `T` is the `dyn Trait` that this
+            // `TraitImpl` corresponds to.
+            Some(unsafe { self.downcast_metadata() })
+        } else {
+            None
+        }
     }
 }
 
 /// Creates a new [`TraitImpl`] from the given type and dyn trait.
 ///
+/// With the `bytecheck` feature, the trait impl can be validated if the type
+/// implements `CheckBytes` for [`DynValidator`](validation::DynValidator).
+///
 /// See [`register_trait_impls`] for a macro that registers these trait impls
 /// globally.
 ///
 /// # Example
 /// ```
+/// use rkyv_dyn::trait_impl;
+///
 /// struct MyType;
 ///
+/// #[ptr_meta::pointee]
 /// trait MyTrait {}
 ///
 /// impl MyTrait for MyType {}
@@ -382,14 +523,168 @@ macro_rules! trait_impl {
                 ::core::ptr::null::<$type>() as *const $trait
             )
         }
+        .with_check_bytes({
+            #[allow(unused_imports)]
+            use $crate::__private::{ProbeCheckBytes as _, ProbeFallback as _};
+            let probe =
+                $crate::__private::Probe::<$type>(::core::marker::PhantomData);
+            (&probe).check_bytes_fn()
+        })
     };
 }
 
-/// All registered trait impls for `rkyv_dyn`.
+#[doc(hidden)]
+pub mod __private {
+    use core::marker::PhantomData;
+
+    #[cfg(feature = "bytecheck")]
+    pub use crate::validation::CheckBytesFn;
+
+    /// Uninhabited stand-in so that `TraitImpl` has the same shape in every
+    /// feature configuration.
+    #[cfg(not(feature = "bytecheck"))]
+    pub type CheckBytesFn = core::convert::Infallible;
+
+    // `trait_impl!` calls `check_bytes_fn` on a `&Probe<T>`. Method resolution
+    // picks `ProbeCheckBytes` if `T` can be validated, and falls back to
+    // autoref'ing into `ProbeFallback` otherwise.
+    pub struct Probe<T: ?Sized>(pub PhantomData<T>);
+
+    pub trait ProbeCheckBytes {
+        fn check_bytes_fn(&self) -> Option<CheckBytesFn>;
+    }
+
+    #[cfg(feature = "bytecheck")]
+    impl<T> ProbeCheckBytes for Probe<T>
+    where
+        T: for<'a> rkyv::bytecheck::CheckBytes<
+            crate::validation::DynValidator<'a>,
+        >,
+    {
+        fn check_bytes_fn(&self) -> Option<CheckBytesFn> {
+            Some(crate::validation::check_bytes_erased::<T>)
+        }
+    }
+
+    pub trait ProbeFallback {
+        fn check_bytes_fn(&self) -> Option<CheckBytesFn>;
+    }
+
+    impl<T: ?Sized> ProbeFallback for &Probe<T> {
+        fn check_bytes_fn(&self) -> Option<CheckBytesFn> {
+            None
+        }
+    }
+
+    /// A trait impl registered with `register_trait_impls!(hashed; ..)`.
+    #[derive(Clone, Copy, Debug)]
+    pub struct HashedTraitImpl {
+        pub impl_id: crate::ImplId,
+        pub name: &'static str,
+        pub trait_impl: crate::TraitImpl,
+    }
+
+    /// All trait impls registered with hashed impl IDs, sorted by impl ID.
+    pub static HASHED_TRAIT_IMPLS: crate::LazyStatic<
+        &'static [HashedTraitImpl],
+    > = crate::LazyStatic::new();
+
+    /// Sorts hashed trait impls by impl ID.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two of the trait impls have the same impl ID.
+    pub fn sort_hashed_trait_impls(trait_impls: &mut [HashedTraitImpl]) {
+        trait_impls.sort_unstable_by_key(|trait_impl| trait_impl.impl_id);
+        for pair in trait_impls.windows(2) {
+            if pair[0].impl_id == pair[1].impl_id {
+                panic!(
+                    "trait impls `{}` and `{}` both hash to impl ID {:#x}; \
+                     register one of them with a different name",
+                    pair[0].name, pair[1].name, pair[0].impl_id,
+                );
+            }
+        }
+    }
+
+    /// Checks that none of the given impl IDs were also registered at link
+    /// time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the impl IDs were registered with
+    /// `register_trait_impl!`.
+    pub fn check_link_time_conflicts(
+        impl_ids: impl IntoIterator<Item = crate::ImplId>,
+    ) {
+        #[cfg(feature = "linkme")]
+        for impl_id in impl_ids {
+            if let Some(entry) = crate::fingerprint::find_entry(impl_id) {
+                panic!(
+                    "impl ID {:#x} was registered by `register_trait_impls!` \
+                     and by `register_trait_impl!` for `{}`",
+                    impl_id, entry.name,
+                );
+            }
+        }
+        #[cfg(not(feature = "linkme"))]
+        let _ = impl_ids;
+    }
+}
+
+/// All registered trait impls for `rkyv_dyn`, indexed by impl ID.
 ///
-/// This can be initialized with
+/// This can be initialized with [`register_trait_impls`]. Trait impls
+/// registered with hashed impl IDs or at link time are stored separately.
 pub static TRAIT_IMPLS: LazyStatic<&'static [TraitImpl]> = LazyStatic::new();
 
+fn find_registered_trait_impl(impl_id: ImplId) -> Option<TraitImpl> {
+    if let Some(trait_impls) = TRAIT_IMPLS.get() {
+        return trait_impls.get(impl_id as usize).copied();
+    }
+
+    let hashed = __private::HASHED_TRAIT_IMPLS.get()?;
+    let index = hashed
+        .binary_search_by_key(&impl_id, |trait_impl| trait_impl.impl_id)
+        .ok()?;
+    Some(hashed[index].trait_impl)
+}
+
+/// Returns the trait impl registered with the given impl ID, if any.
+///
+/// Trait impls registered with [`register_trait_impls`] are searched first,
+/// followed by those registered at link time with the `linkme` feature.
+pub fn find_trait_impl(impl_id: ImplId) -> Option<TraitImpl> {
+    if let Some(trait_impl) = find_registered_trait_impl(impl_id) {
+        return Some(trait_impl);
+    }
+
+    #[cfg(feature = "linkme")]
+    if let Some(entry) = fingerprint::find_entry(impl_id) {
+        return Some((entry.trait_impl)());
+    }
+
+    None
+}
+
+/// Returns an iterator over all of the registered trait impls.
+pub fn registered_trait_impls() -> impl Iterator<Item = TraitImpl> {
+    let hashed = __private::HASHED_TRAIT_IMPLS.get().copied().unwrap_or(&[]);
+    #[cfg(feature = "linkme")]
+    let linked = fingerprint::entry_trait_impls();
+    #[cfg(not(feature = "linkme"))]
+    let linked = core::iter::empty();
+
+    TRAIT_IMPLS
+        .get()
+        .copied()
+        .unwrap_or(&[])
+        .iter()
+        .copied()
+        .chain(hashed.iter().map(|hashed| hashed.trait_impl))
+        .chain(linked)
+}
+
 /// Globally registers the given trait impls. This macro performs three basic
 /// functions:
 ///
@@ -399,8 +694,101 @@ pub static TRAIT_IMPLS: LazyStatic<&'static [TraitImpl]> = LazyStatic::new();
 ///    trait impl argument.
 /// 3. Initializing [`TRAIT_IMPLS`] with a reference to the array of
 ///    [`TraitImpl`]s.
+///
+/// By default, impl IDs are assigned in the order that trait impls are
+/// registered, starting from zero. An impl ID can be chosen explicitly with
+/// `= id`. Because the impl IDs are written into archives, archives are only
+/// compatible with binaries that register the same trait impls in the same
+/// order.
+///
+/// Starting the arguments with `hashed;` instead derives each impl ID from a
+/// stable hash of the name of the trait impl, which is the module path of the
+/// invocation followed by `"::$type as $trait"` spelled as written. Archives
+/// then remain compatible when trait impls are added, removed, or reordered. To
+/// keep archived data readable after renaming or moving a type or the
+/// registration, pass its original name explicitly with `= "..."`.
+///
+/// Hashed impl IDs are the 64-bit [`fingerprint`] of the name truncated to an
+/// [`ImplId`], which is only 32 bits wide with the default pointer width. This
+/// makes collisions more likely than the full fingerprint would suggest, so
+/// they are checked for: if two trait impls hash to the same impl ID,
+/// registration panics and one of them must be registered with a different
+/// name.
+///
+/// Trait impls can only be registered once, so only one form may be used in a
+/// program.
+///
+/// With the `linkme` feature, trait impls can also be registered at link time
+/// with `register_trait_impl!`. Registration panics if any of the impl IDs it
+/// assigns were already registered that way.
+///
+/// # Example
+///
+/// ```
+/// use rkyv_dyn::{register_trait_impls, RegisteredImpl};
+///
+/// struct Circle;
+/// struct Square;
+///
+/// #[ptr_meta::pointee]
+/// trait Shape {}
+///
+/// impl Shape for Circle {}
+/// impl Shape for Square {}
+///
+/// register_trait_impls! {
+///     hashed;
+///     Circle as dyn Shape,
+///     Square as dyn Shape = "shapes::Square as dyn Shape",
+/// }
+///
+/// assert_eq!(
+///     <Circle as RegisteredImpl<dyn Shape>>::IMPL_ID,
+///     rkyv_dyn::fingerprint(concat!(module_path!(), "::Circle as dyn Shape"))
+///         as rkyv_dyn::ImplId,
+/// );
+/// ```
 #[macro_export]
 macro_rules! register_trait_impls {
+    (hashed; $($type:ty as $trait:ty $(= $name:expr)?),* $(,)?) => {
+        let _: () = {
+            $(
+                unsafe impl $crate::RegisteredImpl<$trait> for $type {
+                    const IMPL_ID: $crate::ImplId = $crate::fingerprint(
+                        $crate::register_trait_impls!(
+                            @name $type as $trait, $($name)?
+                        )
+                    ) as $crate::ImplId;
+                }
+            )*
+            const TRAIT_IMPL_COUNT: usize = 0
+                $(+ { let _ = ::core::marker::PhantomData::<$type>; 1 })*;
+            static HASHED_TRAIT_IMPLS: $crate::LazyStatic<[
+                $crate::__private::HashedTraitImpl;
+                TRAIT_IMPL_COUNT
+            ]> = $crate::LazyStatic::new();
+            let mut trait_impls = [
+                $(
+                    $crate::__private::HashedTraitImpl {
+                        impl_id:
+                            <$type as $crate::RegisteredImpl<$trait>>::IMPL_ID,
+                        name: $crate::register_trait_impls!(
+                            @name $type as $trait, $($name)?
+                        ),
+                        trait_impl: $crate::trait_impl!($type as $trait),
+                    },
+                )*
+            ];
+            $crate::__private::sort_hashed_trait_impls(&mut trait_impls);
+            $crate::__private::check_link_time_conflicts(
+                trait_impls.iter().map(|trait_impl| trait_impl.impl_id),
+            );
+            let trait_impls = HASHED_TRAIT_IMPLS.init(trait_impls).unwrap();
+            $crate::__private::HASHED_TRAIT_IMPLS
+                .init(trait_impls)
+                .unwrap();
+        };
+    };
     ($($type:ty as $trait:ty $(= $id:expr)?),* $(,)?) => {
         let _: () = {
             $crate::register_trait_impls!(
@@ -412,6 +800,9 @@ macro_rules! register_trait_impls {
                 $crate::TraitImpl;
                 TRAIT_IMPL_COUNT
             ]> = $crate::LazyStatic::new();
+            $crate::__private::check_link_time_conflicts([
+                $(<$type as $crate::RegisteredImpl<$trait>>::IMPL_ID,)*
+            ]);
             let trait_impls = TRAIT_IMPLS.init([
                 $(
                     $crate::trait_impl!($type as $trait),
@@ -425,8 +816,12 @@ macro_rules! register_trait_impls {
         $first_type:ty as $first_trait:ty $(= $first_id:expr)?,
         $($rest_type:ty as $rest_trait:ty $(= $rest_id:expr)?,)*
     ) => {
+        // Registering two impls with the same ID implements `Registered` for
+        // `ImplIds` twice, which fails to compile.
+        #[allow(dead_code)]
         struct ImplIds;
 
+        #[allow(dead_code)]
         trait Registered<const ID: $crate::ImplId> {}
 
         unsafe impl $crate::RegisteredImpl<$first_trait> for $first_type {
@@ -439,7 +834,7 @@ macro_rules! register_trait_impls {
 
         $crate::register_trait_impls!(
             @register_rest $first_type as $first_trait,
-            $($rest_type:ty as $rest_trait:ty $(= $rest_id:expr)?,)*
+            $($rest_type as $rest_trait $(= $rest_id)?,)*
         );
     };
     (@register_rest $prev_type:ty as $prev_trait:ty,) => {};
@@ -469,6 +864,16 @@ macro_rules! register_trait_impls {
     };
     (@choose_id $default:expr, $explicit:expr) => { $explicit };
     (@choose_id $default:expr,) => { $default };
+    (@name $type:ty as $trait:ty, $name:expr) => { $name };
+    (@name $type:ty as $trait:ty,) => {
+        concat!(
+            module_path!(),
+            "::",
+            stringify!($type),
+            " as ",
+            stringify!($trait),
+        )
+    };
 }
 
 /// A trait impl that has a globally-unique ID.
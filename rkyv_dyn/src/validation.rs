@@ -0,0 +1,498 @@
+//! Validation support for archived trait objects.
+
+use core::{alloc::Layout, any::TypeId, error::Error, fmt, ops::Range};
+
+use ptr_meta::{DynMetadata, Pointee};
+use rancor::{fail, BoxedError, Fallible, Source, Strategy};
+use rkyv::{
+    bytecheck::{CheckBytes, Verify},
+    validation::{shared::ValidationState, ArchiveContext, SharedContext},
+};
+
+use crate::{
+    find_trait_impl, registered_trait_impls, ArchivedDynMetadata, ImplId,
+};
+
+/// The error type used to validate archived trait objects.
+///
+/// Errors from the outer validation context are boxed into this type while
+/// validating the concrete type behind a trait object, then converted back
+/// once validation returns.
+pub type DynError = BoxedError;
+
+/// An object-safe validation context.
+///
+/// Any context that implements [`ArchiveContext`] and [`SharedContext`] with
+/// [`DynError`] automatically implements `DynContext`. Because
+/// [`ArchiveContext`] has generic methods, `dyn DynContext` implements it by
+/// forwarding to the methods of this trait instead. Checks are never deferred
+/// through a `dyn DynContext`.
+///
+/// # Safety
+///
+/// The methods of this trait must uphold the same requirements as the
+/// [`ArchiveContext`] and [`SharedContext`] methods they are named after.
+pub unsafe trait DynContext {
+    /// Checks that the given data address and layout is located completely
+    /// within the subtree range.
+    fn check_subtree_ptr_dyn(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), DynError>;
+
+    /// Pushes a new subtree range onto the validator and starts validating it.
+    ///
+    /// # Safety
+    ///
+    /// `root` and `end` must be located inside the archive.
+    unsafe fn push_subtree_range_dyn(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, DynError>;
+
+    /// Pops the given range, restoring the original state with the pushed range
+    /// removed.
+    ///
+    /// # Safety
+    ///
+    /// `range` must be a range returned from this validator.
+    unsafe fn pop_subtree_range_dyn(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), DynError>;
+
+    /// Starts validating the value associated with the given address.
+    fn start_shared_dyn(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<ValidationState, DynError>;
+
+    /// Finishes validating the value associated with the given address.
+    fn finish_shared_dyn(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<(), DynError>;
+}
+
+// SAFETY: Each method forwards to the method it is named after.
+unsafe impl<C> DynContext for C
+where
+    C: ArchiveContext<DynError> + SharedContext<DynError>,
+{
+    fn check_subtree_ptr_dyn(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), DynError> {
+        self.check_subtree_ptr(ptr, layout)
+    }
+
+    unsafe fn push_subtree_range_dyn(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, DynError> {
+        // SAFETY: The caller has upheld the preconditions of
+        // `push_subtree_range`.
+        unsafe { self.push_subtree_range(root, end) }
+    }
+
+    unsafe fn pop_subtree_range_dyn(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), DynError> {
+        // SAFETY: The caller has upheld the preconditions of
+        // `pop_subtree_range`.
+        unsafe { self.pop_subtree_range(range) }
+    }
+
+    fn start_shared_dyn(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<ValidationState, DynError> {
+        self.start_shared(address, type_id)
+    }
+
+    fn finish_shared_dyn(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<(), DynError> {
+        self.finish_shared(address, type_id)
+    }
+}
+
+// SAFETY: `DynContext` upholds the requirements of `ArchiveContext` for each
+// method, and checks are never deferred.
+unsafe impl ArchiveContext<DynError> for dyn DynContext + '_ {
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), DynError> {
+        self.check_subtree_ptr_dyn(ptr, layout)
+    }
+
+    unsafe fn push_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, DynError> {
+        // SAFETY: `push_subtree_range_dyn` has the same preconditions as
+        // `push_subtree_range`.
+        unsafe { self.push_subtree_range_dyn(root, end) }
+    }
+
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), DynError> {
+        // SAFETY: `pop_subtree_range_dyn` has the same preconditions as
+        // `pop_subtree_range`.
+        unsafe { self.pop_subtree_range_dyn(range) }
+    }
+}
+
+impl SharedContext<DynError> for dyn DynContext + '_ {
+    fn start_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<ValidationState, DynError> {
+        self.start_shared_dyn(address, type_id)
+    }
+
+    fn finish_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<(), DynError> {
+        self.finish_shared_dyn(address, type_id)
+    }
+}
+
+/// The validator passed to the `CheckBytes` impls of the concrete types behind
+/// archived trait objects.
+pub type DynValidator<'a> = Strategy<dyn DynContext + 'a, DynError>;
+
+/// A type-erased `CheckBytes` function for the concrete type behind an archived
+/// trait object.
+pub type CheckBytesFn =
+    unsafe fn(*const (), &mut DynValidator<'_>) -> Result<(), DynError>;
+
+/// Checks the bytes of a `T` behind a type-erased pointer.
+///
+/// # Safety
+///
+/// `value` must point to a `T` which satisfies the preconditions of
+/// [`CheckBytes::check_bytes`].
+pub unsafe fn check_bytes_erased<T>(
+    value: *const (),
+    context: &mut DynValidator<'_>,
+) -> Result<(), DynError>
+where
+    T: for<'a> CheckBytes<DynValidator<'a>>,
+{
+    // SAFETY: The caller has guaranteed that `value` points to a `T` which
+    // satisfies the preconditions of `check_bytes`.
+    unsafe { T::check_bytes(value.cast(), context) }
+}
+
+/// Adapts a validation context with any error type into a [`DynContext`].
+struct ErasedContext<'a, C: ?Sized> {
+    inner: &'a mut C,
+}
+
+unsafe impl<C> ArchiveContext<DynError> for ErasedContext<'_, C>
+where
+    C: Fallible + ArchiveContext + ?Sized,
+    C::Error: Source,
+{
+    fn check_subtree_ptr(
+        &mut self,
+        ptr: *const u8,
+        layout: &Layout,
+    ) -> Result<(), DynError> {
+        self.inner
+            .check_subtree_ptr(ptr, layout)
+            .map_err(DynError::new)
+    }
+
+    unsafe fn push_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Range<usize>, DynError> {
+        // SAFETY: The caller has upheld the preconditions of
+        // `push_subtree_range`.
+        unsafe { self.inner.push_subtree_range(root, end) }
+            .map_err(DynError::new)
+    }
+
+    unsafe fn pop_subtree_range(
+        &mut self,
+        range: Range<usize>,
+    ) -> Result<(), DynError> {
+        // SAFETY: The caller has upheld the preconditions of
+        // `pop_subtree_range`.
+        unsafe { self.inner.pop_subtree_range(range) }.map_err(DynError::new)
+    }
+}
+
+impl<C> SharedContext<DynError> for ErasedContext<'_, C>
+where
+    C: Fallible + SharedContext + ?Sized,
+    C::Error: Source,
+{
+    fn start_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<ValidationState, DynError> {
+        self.inner
+            .start_shared(address, type_id)
+            .map_err(DynError::new)
+    }
+
+    fn finish_shared(
+        &mut self,
+        address: usize,
+        type_id: TypeId,
+    ) -> Result<(), DynError> {
+        self.inner
+            .finish_shared(address, type_id)
+            .map_err(DynError::new)
+    }
+}
+
+#[derive(Debug)]
+struct UnregisteredImplId {
+    impl_id: ImplId,
+}
+
+impl fmt::Display for UnregisteredImplId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived trait object has impl ID {:#x} which was not registered",
+            self.impl_id,
+        )
+    }
+}
+
+impl Error for UnregisteredImplId {}
+
+#[derive(Debug)]
+struct MismatchedTrait {
+    impl_id: ImplId,
+}
+
+impl fmt::Display for MismatchedTrait {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "archived trait object has impl ID {:#x} which was registered for \
+             a different trait",
+            self.impl_id,
+        )
+    }
+}
+
+impl Error for MismatchedTrait {}
+
+#[derive(Debug)]
+struct UncheckedTraitImpl;
+
+impl fmt::Display for UncheckedTraitImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the concrete type of an archived trait object does not implement \
+             `CheckBytes` for `DynValidator`",
+        )
+    }
+}
+
+impl Error for UncheckedTraitImpl {}
+
+unsafe impl<T, C> Verify<C> for ArchivedDynMetadata<T>
+where
+    T: ?Sized,
+    C: Fallible + ?Sized,
+    C::Error: Source,
+{
+    fn verify(&self, _: &mut C) -> Result<(), C::Error> {
+        let impl_id = self.impl_id();
+        let Some(trait_impl) = find_trait_impl(impl_id) else {
+            fail!(UnregisteredImplId { impl_id });
+        };
+        if !trait_impl.is_trait::<T>() {
+            fail!(MismatchedTrait { impl_id });
+        }
+        Ok(())
+    }
+}
+
+/// Checks the concrete value behind an archived trait object.
+///
+/// The concrete type is found by looking up the registered trait impl with the
+/// same metadata as `value`, then its `CheckBytes` impl is called through
+/// [`DynValidator`]. This is what the `CheckBytes` impls generated by
+/// [`archive_dyn`](macro@crate::archive_dyn) call.
+///
+/// # Safety
+///
+/// `value` must be aligned and point to enough bytes to represent the value
+/// described by its metadata.
+pub unsafe fn check_bytes_dyn<T, C>(
+    value: *const T,
+    context: &mut C,
+) -> Result<(), C::Error>
+where
+    T: Pointee<Metadata = DynMetadata<T>> + ?Sized,
+    C: Fallible + ArchiveContext + SharedContext + ?Sized,
+    C::Error: Source,
+{
+    let metadata = ptr_meta::metadata(value);
+    let trait_impl = registered_trait_impls()
+        .find(|trait_impl| trait_impl.is_metadata(metadata));
+    let Some(check_bytes) = trait_impl.and_then(|t| t.check_bytes) else {
+        fail!(UncheckedTraitImpl);
+    };
+
+    let mut erased = ErasedContext { inner: context };
+    let validator =
+        Strategy::<_, DynError>::wrap(&mut erased as &mut dyn DynContext);
+    // SAFETY: `check_bytes` was registered for the trait impl with the same
+    // metadata as `value`, so `value` points to the type it checks. The
+    // caller has guaranteed that `value` is aligned and points to enough
+    // bytes.
+    unsafe { check_bytes(value.cast::<()>(), validator) }
+        .map_err(<C::Error as Source>::new)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use rancor::{Error, Failure, Strategy};
+    use rkyv::{
+        bytecheck::CheckBytes,
+        primitive::ArchivedU32,
+        string::ArchivedString,
+        to_bytes,
+        validation::{
+            archive::ArchiveValidator, shared::SharedValidator, Validator,
+        },
+    };
+
+    use super::check_bytes_dyn;
+    use crate::{register_trait_impls, ArchivedDynMetadata, TRAIT_IMPLS};
+
+    #[ptr_meta::pointee]
+    trait Describe {
+        fn describe(&self) -> String;
+    }
+
+    impl Describe for ArchivedU32 {
+        fn describe(&self) -> String {
+            format!("u32 {self}")
+        }
+    }
+
+    impl Describe for ArchivedString {
+        fn describe(&self) -> String {
+            format!("string {self}")
+        }
+    }
+
+    #[ptr_meta::pointee]
+    trait Name {}
+
+    impl Name for ArchivedU32 {}
+
+    struct Unchecked;
+
+    impl Describe for Unchecked {
+        fn describe(&self) -> String {
+            "unchecked".to_string()
+        }
+    }
+
+    #[test]
+    #[allow(non_local_definitions)]
+    fn check_trait_objects() {
+        register_trait_impls! {
+            ArchivedU32 as dyn Describe,
+            ArchivedString as dyn Describe,
+            Unchecked as dyn Describe,
+            ArchivedU32 as dyn Name,
+        }
+
+        let trait_impls = *TRAIT_IMPLS.get().unwrap();
+        assert!(trait_impls[0].check_bytes.is_some());
+        assert!(trait_impls[1].check_bytes.is_some());
+        assert!(trait_impls[2].check_bytes.is_none());
+
+        // Registered impl IDs pass and unregistered ones fail
+        let check_metadata = |impl_id| unsafe {
+            ArchivedDynMetadata::<dyn Describe>::check_bytes(
+                &ArchivedDynMetadata::new(impl_id),
+                Strategy::<_, Failure>::wrap(&mut ()),
+            )
+        };
+        assert!(check_metadata(1).is_ok());
+        assert!(check_metadata(4).is_err());
+
+        // Impl IDs registered for another trait fail
+        assert!(check_metadata(3).is_err());
+        let check_name_metadata = |impl_id| unsafe {
+            ArchivedDynMetadata::<dyn Name>::check_bytes(
+                &ArchivedDynMetadata::new(impl_id),
+                Strategy::<_, Failure>::wrap(&mut ()),
+            )
+        };
+        assert!(check_name_metadata(3).is_ok());
+        assert!(check_name_metadata(0).is_err());
+        assert!(!trait_impls[0].is_trait::<dyn Name>());
+        assert!(trait_impls[3].try_downcast_metadata::<dyn Name>().is_some());
+
+        let mut bytes = to_bytes::<Error>(
+            &"a string long enough to be stored out of line".to_string(),
+        )
+        .unwrap();
+        let root_pos = bytes.len() - size_of::<ArchivedString>();
+
+        let check = |bytes: &[u8], impl_id: usize| {
+            let value = ptr_meta::from_raw_parts::<dyn Describe>(
+                bytes[root_pos..].as_ptr().cast(),
+                unsafe { trait_impls[impl_id].downcast_metadata() },
+            );
+            let mut validator = Validator::new(
+                ArchiveValidator::new(bytes),
+                SharedValidator::new(),
+            );
+            unsafe {
+                check_bytes_dyn(
+                    value,
+                    Strategy::<_, Failure>::wrap(&mut validator),
+                )
+            }
+            .map(|()| unsafe { &*value })
+        };
+
+        let value = check(&bytes, 1).unwrap();
+        assert_eq!(
+            value.describe(),
+            "string a string long enough to be stored out of line",
+        );
+        assert!(check(&bytes, 2).is_err());
+
+        // Point the string past the end of the buffer
+        bytes[root_pos + 4] = 0xff;
+        assert!(check(&bytes, 1).is_err());
+    }
+}
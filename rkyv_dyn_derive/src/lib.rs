@@ -8,14 +8,14 @@
 
 extern crate proc_macro;
 
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{
     parse::{Parse, ParseStream, Result},
-    parse_macro_input,
-    spanned::Spanned,
-    Attribute, Error, Ident, ItemImpl, ItemTrait, LitStr, Path, Token,
-    Visibility,
+    parse_macro_input, parse_quote,
+    punctuated::Punctuated,
+    Attribute, Error, GenericParam, Generics, Ident, ItemImpl, ItemTrait,
+    LitStr, Path, PathArguments, Token, Type, Visibility,
 };
 
 enum Input {
@@ -112,22 +112,81 @@ impl Parse for Args {
     }
 }
 
-/// Creates archivable trait objects and registers implementations.
+struct Instantiation {
+    ty: Type,
+    trait_: Path,
+}
+
+impl Parse for Instantiation {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ty = input.parse::<Type>()?;
+        input.parse::<Token![as]>()?;
+        let trait_ = input.parse::<Path>()?;
+        Ok(Instantiation { ty, trait_ })
+    }
+}
+
+struct Instantiations {
+    args: Args,
+    instantiations: Punctuated<Instantiation, Token![,]>,
+}
+
+impl Parse for Instantiations {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = Attribute::parse_outer(input)?;
+        let args = match attrs.as_slice() {
+            [attr] if attr.path().is_ident("archive_dyn") => {
+                attr.parse_args::<Args>()?
+            }
+            _ => {
+                return Err(input
+                    .error("expected a single #[archive_dyn(...)] attribute"))
+            }
+        };
+        if args.deserialize.is_none() {
+            return Err(Error::new_spanned(
+                &attrs[0],
+                "archive_dyn_impls! only implements DeserializeDyn, so it \
+                 requires the deserialize argument",
+            ));
+        }
+
+        Ok(Instantiations {
+            args,
+            instantiations: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+/// Creates archivable trait objects.
+///
+/// Prepend to trait definitions and implementations. See `SerializeDyn` for
+/// usage information and examples.
 ///
-/// Prepend to trait definitions and implementations. For generic
-/// implementations, you may need to manually register impls with the trait
-/// object system. See `register_impl` for more information.
+/// On a trait, this generates a serialize trait and (optionally) a deserialize
+/// trait. Both are generic over the trait's own parameters followed by the
+/// error type of the serializer and, if deserialization is enabled, the error
+/// type of the deserializer. For example, `#[archive_dyn(deserialize)]` on
+/// `trait Shape` generates `SerializeShape<SE, DE>` and
+/// `DeserializeShape<SE, DE>`.
 ///
-/// See `ArchiveDyn` for usage information and examples.
+/// On an implementation, this implements `DeserializeDyn` for the archived type
+/// if deserialization is enabled. Generic implementations are left as-is; use
+/// `archive_dyn_impls!` to implement `DeserializeDyn` for each of their
+/// concrete instantiations.
+///
+/// Archived implementations still need to be registered with
+/// `register_trait_impls!` for every combination of error types that they are
+/// used with.
 ///
 /// # Parameters
 ///
 /// - `serialize = "..."`: Chooses the name of the serialize trait. By default,
 ///   it will be named "Serialize" + your trait name.
 /// - `deserialize`, `deserialize = "..."`: Adds deserialization support to the
-///   archived trait. Similarly to the `name` parameter, you can choose the name
-///   of the deserialize trait and by default it will be named "Deserialize" +
-///   your trait name.
+///   archived trait. Similarly to the `serialize` parameter, you can choose the
+///   name of the deserialize trait and by default it will be named
+///   "Deserialize" + your trait name.
 #[proc_macro_attribute]
 pub fn archive_dyn(
     attr: proc_macro::TokenStream,
@@ -142,292 +201,361 @@ pub fn archive_dyn(
     }
 }
 
+/// Implements `DeserializeDyn` for concrete instantiations of generic trait
+/// impls.
+///
+/// `#[archive_dyn]` can't implement `DeserializeDyn` for generic impls because
+/// it doesn't know which archived types they will be used with. Instead, list
+/// each instantiation as `Type as Trait` after an `#[archive_dyn(...)]`
+/// attribute with the same parameters as the trait invocation:
+///
+/// ```ignore
+/// #[archive_dyn(deserialize)]
+/// impl<T: Float> Shape for Circle<T> {
+///     // ...
+/// }
+///
+/// archive_dyn_impls! {
+///     #[archive_dyn(deserialize)]
+///     Circle<f32> as Shape,
+///     Circle<f64> as Shape,
+/// }
+/// ```
+///
+/// The archived instantiations must still be registered with
+/// `register_trait_impls!`.
+#[proc_macro]
+pub fn archive_dyn_impls(
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as Instantiations);
+
+    let mut result = TokenStream::new();
+    for Instantiation { ty, trait_ } in input.instantiations.iter() {
+        match impl_deserialize_dyn(ty, trait_, &input.args) {
+            Ok(tokens) => result.extend(tokens),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+    result.into()
+}
+
 fn apply_archive_dyn(input: &Input, args: &Args) -> Result<TokenStream> {
-    let input_impl = match input {
-        Input::Impl(ref input) => {
+    match input {
+        Input::Impl(input) => {
+            // Generic impls are instantiated with `archive_dyn_impls!`.
             if !input.generics.params.is_empty() {
-                Error::new(
-                    input.generics.span(),
-                    "#[archive_dyn] can only register non-generic impls; call \
-                     register_impl! with the concrete types to register and \
-                     manually implement DeserializeDyn for archived types if \
-                     necessary",
-                )
-                .to_compile_error()
-            } else if let Some((_, ref trait_, _)) = input.trait_ {
-                register_impl(&input, &args, trait_)?
-            } else {
-                Error::new(
-                    input.span(),
-                    "#[archive_dyn] is only valid on trait implementations",
-                )
-                .to_compile_error()
+                return Ok(quote! { #input });
             }
-        }
-        Input::Trait(input) => generate_traits(input, args)?,
-    };
 
-    Ok(input_impl)
+            // `Input` only parses impls which have a trait.
+            let (_, trait_, _) = input.trait_.as_ref().unwrap();
+            let deserialize_dyn =
+                impl_deserialize_dyn(&input.self_ty, trait_, args)?;
+            Ok(quote! {
+                #input
+                #deserialize_dyn
+            })
+        }
+        Input::Trait(input) => generate_traits(input, args),
+    }
 }
 
-fn register_impl(
-    input: &ItemImpl,
-    args: &Args,
-    trait_: &Path,
-) -> Result<TokenStream> {
-    let ty = &input.self_ty;
-
-    let mut ser_trait = trait_.clone();
-    let last = ser_trait.segments.last_mut().unwrap();
-    if let Some(ar_name) = &args.serialize {
-        last.ident = Ident::new(&ar_name.value(), ar_name.span());
+fn serialize_trait_name(name: &Ident, args: &Args) -> Ident {
+    if let Some(ser_name) = &args.serialize {
+        Ident::new(&ser_name.value(), ser_name.span())
     } else {
-        last.ident =
-            Ident::new(&format!("Serialize{}", last.ident), trait_.span());
-    };
+        Ident::new(&format!("Serialize{}", name), name.span())
+    }
+}
 
-    let (de_trait, de_impl) = if let Some(deserialize) = &args.deserialize {
-        let mut de_trait = trait_.clone();
-        let last = de_trait.segments.last_mut().unwrap();
-        if let Some(ua_name) = deserialize {
-            last.ident = Ident::new(&ua_name.value(), ua_name.span());
+fn deserialize_trait_name(name: &Ident, args: &Args) -> Option<Ident> {
+    args.deserialize.as_ref().map(|deserialize| {
+        if let Some(de_name) = deserialize {
+            Ident::new(&de_name.value(), de_name.span())
         } else {
-            last.ident = Ident::new(
-                &format!("Deserialize{}", last.ident),
-                trait_.span(),
-            );
-        };
+            Ident::new(&format!("Deserialize{}", name), name.span())
+        }
+    })
+}
 
-        (
-            de_trait,
-            quote! {
-                impl DeserializeDyn<dyn #ser_trait> for Archived<#ty>
-                where
-                    Archived<#ty>:
-                        for<'a> Deserialize<#ty, (dyn DynDeserializer + 'a)>,
-                {
-                    unsafe fn deserialize_dyn(
-                        &self,
-                        deserializer: &mut dyn DynDeserializer,
-                        alloc: &mut dyn FnMut(Layout) -> *mut u8,
-                    ) -> Result<*mut (), DynError> {
-                        let result =
-                            alloc(core::alloc::Layout::new::<#ty>())
-                            .cast::<#ty>();
-                        assert!(!result.is_null());
-                        result.write(self.deserialize(deserializer)?);
-                        Ok(result as *mut ())
-                    }
+/// Returns the generic parameters for the serializer and deserializer error
+/// types.
+fn error_params(args: &Args) -> Vec<Ident> {
+    let mut result = vec![Ident::new("__SE", Span::call_site())];
+    if args.deserialize.is_some() {
+        result.push(Ident::new("__DE", Span::call_site()));
+    }
+    result
+}
 
-                    fn deserialize_dyn_metadata(
-                        &self,
-                        deserializer: &mut dyn DynDeserializer,
-                    ) -> Result<
-                        <dyn #ser_trait as ptr_meta::Pointee>::Metadata,
-                        DynError,
-                    > {
-                        let null_trait_object_ptr =
-                            core::ptr::null::<#ty>() as *const dyn #ser_trait;
-                        unsafe {
-                            Ok(core::mem::transmute(
-                                ptr_meta::metadata(null_trait_object_ptr)
-                            ))
-                        }
-                    }
-                }
-            },
-        )
-    } else {
-        (trait_.clone(), quote! {})
+/// Renames the last segment of `path` and appends `extra` to its generic
+/// arguments.
+fn with_extra_args(path: &Path, ident: Ident, extra: &[Ident]) -> Result<Path> {
+    let mut path = path.clone();
+    let last = path.segments.last_mut().unwrap();
+    last.ident = ident;
+    match &mut last.arguments {
+        PathArguments::None => {
+            last.arguments =
+                PathArguments::AngleBracketed(parse_quote! { <#(#extra),*> });
+        }
+        PathArguments::AngleBracketed(args) => {
+            for ident in extra {
+                args.args.push(parse_quote! { #ident });
+            }
+        }
+        PathArguments::Parenthesized(args) => {
+            return Err(Error::new_spanned(
+                args,
+                "#[archive_dyn] does not support parenthesized arguments",
+            ));
+        }
+    }
+    Ok(path)
+}
+
+fn impl_deserialize_dyn(
+    ty: &Type,
+    trait_: &Path,
+    args: &Args,
+) -> Result<TokenStream> {
+    let name = &trait_.segments.last().unwrap().ident;
+
+    let Some(de_name) = deserialize_trait_name(name, args) else {
+        return Ok(TokenStream::new());
     };
 
-    Ok(quote! {
-        #input
+    let errors = error_params(args);
+    let ser_trait =
+        with_extra_args(trait_, serialize_trait_name(name, args), &errors)?;
+    let de_trait = with_extra_args(trait_, de_name, &errors)?;
 
+    Ok(quote! {
         const _: () = {
-            use core::alloc::{Layout, LayoutError};
-            use rkyv::{
-                Archived,
-                Deserialize,
-            };
+            use ptr_meta::DynMetadata;
+            use rkyv::{Archived, Deserialize, DeserializeUnsized, Serialize};
             use rkyv_dyn::{
                 DeserializeDyn,
                 DynDeserializer,
-                DynError,
+                DynSerializer,
+                RegisteredImpl,
             };
 
-            rkyv_dyn::register_impl!(Archived<#ty> as dyn #de_trait);
+            impl<__SE, __DE> DeserializeDyn<dyn #ser_trait, __DE>
+                for Archived<#ty>
+            where
+                #ty: for<'a> Serialize<dyn DynSerializer<__SE> + 'a>,
+                Archived<#ty>:
+                    for<'a> Deserialize<#ty, dyn DynDeserializer<__DE> + 'a>
+                    + RegisteredImpl<dyn #de_trait>,
+            {
+                fn deserialize_dyn(
+                    &self,
+                    deserializer: &mut dyn DynDeserializer<__DE>,
+                    out: *mut dyn #ser_trait,
+                ) -> Result<(), __DE> {
+                    // SAFETY: The metadata of `out` is the metadata returned
+                    // by `deserialized_pointer_metadata`, so `out` points to
+                    // memory allocated for the deserialized type.
+                    unsafe {
+                        <
+                            Self as DeserializeUnsized<#ty, _>
+                        >::deserialize_unsized(self, deserializer, out.cast())
+                    }
+                }
 
-            #de_impl
+                fn deserialized_pointer_metadata(
+                    &self,
+                ) -> DynMetadata<dyn #ser_trait> {
+                    ptr_meta::metadata(
+                        core::ptr::null::<#ty>() as *const dyn #ser_trait
+                    )
+                }
+            }
         };
     })
 }
 
+/// Returns the generic parameters of `generics` without defaults or
+/// attributes, along with the arguments to name them.
+fn split_params(generics: &Generics) -> (Vec<TokenStream>, Vec<TokenStream>) {
+    generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                let bounds = &param.bounds;
+                if bounds.is_empty() {
+                    (quote! { #lifetime }, quote! { #lifetime })
+                } else {
+                    (quote! { #lifetime: #bounds }, quote! { #lifetime })
+                }
+            }
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                let bounds = &param.bounds;
+                if bounds.is_empty() {
+                    (quote! { #ident }, quote! { #ident })
+                } else {
+                    (quote! { #ident: #bounds }, quote! { #ident })
+                }
+            }
+            GenericParam::Const(param) => {
+                let ident = &param.ident;
+                let ty = &param.ty;
+                (quote! { const #ident: #ty }, quote! { #ident })
+            }
+        })
+        .unzip()
+}
+
 fn generate_traits(input: &ItemTrait, args: &Args) -> Result<TokenStream> {
     let vis = &input.vis;
-
-    let generic_params = input.generics.params.iter().map(|p| quote! { #p });
-    let generic_params = quote! { #(#generic_params),* };
-
-    let generic_args = input.generics.type_params().map(|p| {
-        let name = &p.ident;
-        quote! { #name }
-    });
-    let generic_args = quote! { #(#generic_args),* };
-
     let name = &input.ident;
-    let ser_trait = args
-        .serialize
+    let ser_trait = serialize_trait_name(name, args);
+    let de_name = deserialize_trait_name(name, args);
+
+    let (generic_params, generic_args) = split_params(&input.generics);
+    let predicates: Vec<_> = input
+        .generics
+        .where_clause
         .as_ref()
-        .map(|ar_name| Ident::new(&ar_name.value(), ar_name.span()))
-        .unwrap_or_else(|| {
-            Ident::new(&format!("Serialize{}", name), name.span())
-        });
-
-    let type_name_wheres = input.generics.type_params().map(|p| {
-        let name = &p.ident;
-        quote! { #name: TypeName }
-    });
-    let type_name_wheres = quote! { #(#type_name_wheres,)* };
-
-    let (de_trait, de_trait_def, de_trait_impl, pointee_input) = if let Some(
-        deserialize,
-    ) =
-        &args.deserialize
-    {
-        let de_trait = if let Some(ua_name) = deserialize {
-            Ident::new(&ua_name.value(), ua_name.span())
-        } else {
-            Ident::new(&format!("Deserialize{}", name), name.span())
-        };
+        .map(|where_clause| where_clause.predicates.iter().collect())
+        .unwrap_or_default();
+    let errors = error_params(args);
+
+    // The generic parameters and arguments of the generated traits
+    let ser_params = quote! { #(#generic_params,)* #(#errors),* };
+    let ser_args = quote! { #(#generic_args,)* #(#errors),* };
+
+    let (archived_trait, de_trait_def, de_trait_impl, pointee_input) =
+        if let Some(de_trait) = &de_name {
+            let archived_trait = quote! { #de_trait<#ser_args> };
+            (
+                archived_trait.clone(),
+                quote! {
+                    #[ptr_meta::pointee]
+                    #vis trait #de_trait<#ser_params>:
+                        #name<#(#generic_args),*>
+                        + rkyv_dyn::DeserializeDyn<
+                            dyn #ser_trait<#ser_args>,
+                            __DE,
+                        >
+                        + rkyv::Portable
+                    where
+                        #(#predicates,)*
+                    {}
+                },
+                quote! {
+                    use rkyv::DeserializeUnsized;
+                    use rkyv_dyn::{AsDynDeserializer, DeserializeDyn};
+
+                    impl<__T, #ser_params> #de_trait<#ser_args> for __T
+                    where
+                        __T: #name<#(#generic_args),*>
+                            + DeserializeDyn<dyn #ser_trait<#ser_args>, __DE>
+                            + rkyv::Portable,
+                        #(#predicates,)*
+                    {}
+
+                    impl<__D, #(#generic_params,)* __SE>
+                        DeserializeUnsized<
+                            dyn #ser_trait<#(#generic_args,)* __SE, __D::Error>,
+                            __D,
+                        >
+                        for dyn #de_trait<#(#generic_args,)* __SE, __D::Error>
+                    where
+                        __D: Fallible + AsDynDeserializer<__D::Error> + ?Sized,
+                        #(#predicates,)*
+                    {
+                        unsafe fn deserialize_unsized(
+                            &self,
+                            deserializer: &mut __D,
+                            out: *mut dyn #ser_trait<
+                                #(#generic_args,)* __SE, __D::Error
+                            >,
+                        ) -> Result<(), __D::Error> {
+                            self.deserialize_dyn(
+                                deserializer.as_dyn_deserializer(),
+                                out,
+                            )
+                        }
 
-        (
-            de_trait.clone(),
-            quote! {
-                #[ptr_meta::pointee]
-                #vis trait #de_trait<#generic_params>:
-                    #name<#generic_args>
-                    + rkyv_dyn::DeserializeDyn<dyn #ser_trait<#generic_args>>
-                {}
-            },
-            quote! {
-                impl<__T, #generic_params> #de_trait<#generic_args> for __T
-                where
-                    __T:
-                        #name<#generic_args>
-                        + DeserializeDyn<dyn #ser_trait<#generic_args>>,
-                {}
-
-                impl<__D, #generic_params>
-                    DeserializeUnsized<dyn #ser_trait<#generic_args>, __D>
-                    for dyn #de_trait<#generic_args>
-                where
-                    __D: Fallible + ?Sized,
-                {
-                    unsafe fn deserialize_unsized(
-                        &self,
-                        mut deserializer: &mut __D,
-                        mut alloc: impl FnMut(Layout) -> *mut u8,
-                    ) -> Result<*mut (), __E> {
-                        self.deserialize_dyn(
-                            &mut deserializer,
-                            &mut alloc,
-                        ).map_err(|e| *e.downcast().unwrap())
+                        fn deserialize_metadata(&self) -> DynMetadata<
+                            dyn #ser_trait<#(#generic_args,)* __SE, __D::Error>
+                        > {
+                            self.deserialized_pointer_metadata()
+                        }
                     }
+                },
+                quote! {},
+            )
+        } else {
+            (
+                quote! { #name<#(#generic_args),*> },
+                quote! {},
+                quote! {
+                    // SAFETY: Only archived types are registered as
+                    // implementations of the trait, and those are all
+                    // portable.
+                    unsafe impl<#(#generic_params),*> rkyv::Portable
+                        for dyn #name<#(#generic_args),*>
+                    where
+                        #(#predicates,)*
+                    {}
+                },
+                quote! { #[ptr_meta::pointee] },
+            )
+        };
 
-                    fn deserialize_metadata(
-                        &self,
-                        mut deserializer: &mut __D,
-                    ) -> Result<
-                        <
-                            dyn #ser_trait<#generic_args> as ptr_meta::Pointee
-                        >::Metadata,
-                        __E,
-                    > {
-                        self.deserialize_dyn_metadata(&mut deserializer)
-                            .map_err(|e| *e.downcast().unwrap())
-                    }
-                }
-            },
-            quote! {},
-        )
-    } else {
-        (
-            name.clone(),
-            quote! {},
-            quote! {},
-            quote! { #[ptr_meta::pointee] },
-        )
+    // `SerializeUnsized` names the serializer error `__S::Error` instead of
+    // `__SE`, which is always the first error parameter.
+    let serialize_errors = &errors[1..];
+    let serialize_args = quote! {
+        #(#generic_args,)* __S::Error #(, #serialize_errors)*
     };
-
-    let build_type_name = if !input.generics.params.is_empty() {
-        let dyn_name = format!("dyn {}<", de_trait);
-        let mut results = input.generics.type_params().map(|p| {
-            let name = &p.ident;
-            quote! { #name::build_type_name(&mut f) }
-        });
-        let first = results.next().unwrap();
-        quote! {
-            f(#dyn_name);
-            #first;
-            #(f(", "); #results;)*
-            f(">");
-        }
+    let archived_params = if de_name.is_some() {
+        ser_params.clone()
     } else {
-        quote! { f(stringify!(dyn #de_trait)); }
+        quote! { #(#generic_params),* }
     };
 
     #[cfg(feature = "bytecheck")]
     let validation_impl = quote! {
-        use bytecheck::CheckBytes;
-        use rkyv::validation::LayoutRaw;
-        use rkyv_dyn::validation::{
-            CHECK_BYTES_REGISTRY,
-            CheckDynError,
-            DynContext,
+        use rkyv::{
+            bytecheck::CheckBytes,
+            rancor::Source,
+            validation::{ArchiveContext, SharedContext},
         };
 
-        impl<#generic_params> LayoutRaw
-            for (dyn #de_trait<#generic_args> + '_)
+        impl<#archived_params> LayoutRaw for dyn #archived_trait
+        where
+            #(#predicates,)*
         {
             fn layout_raw(
-                metadata: <Self as ptr_meta::Pointee>::Metadata,
+                metadata: DynMetadata<Self>,
             ) -> Result<Layout, LayoutError> {
                 Ok(metadata.layout())
             }
         }
 
-        impl<#generic_params> CheckBytes<dyn DynContext + '_>
-            for (dyn #de_trait<#generic_args> + '_)
-        {
-            type Error = CheckDynError;
-
-            unsafe fn check_bytes<'a>(
-                value: *const Self,
-                context: &mut (dyn DynContext + '_),
-            ) -> Result<&'a Self, Self::Error> {
-                let vtable = core::mem::transmute(ptr_meta::metadata(value));
-                if let Some(validation) = CHECK_BYTES_REGISTRY.get(vtable) {
-                    (validation.check_bytes_dyn)(value.cast(), context)?;
-                    Ok(&*value)
-                } else {
-                    Err(CheckDynError::InvalidMetadata(vtable as usize as u64))
-                }
-            }
-        }
-
-        impl<__C, #generic_params> CheckBytes<__C>
-            for (dyn #de_trait<#generic_args> + '_)
+        unsafe impl<__C, #archived_params> CheckBytes<__C>
+            for dyn #archived_trait
         where
-            __C: DynContext,
+            __C: Fallible + ArchiveContext + SharedContext + ?Sized,
+            <__C as Fallible>::Error: Source,
+            #(#predicates,)*
         {
-            type Error = CheckDynError;
-
-            unsafe fn check_bytes<'a>(
+            unsafe fn check_bytes(
                 value: *const Self,
                 context: &mut __C,
-            ) -> Result<&'a Self, Self::Error> {
-                Self::check_bytes(value, context as &mut dyn DynContext)
+            ) -> Result<(), <__C as Fallible>::Error> {
+                // SAFETY: The caller has guaranteed that `value` is aligned
+                // and points to enough bytes to represent the value described
+                // by its metadata.
+                unsafe { rkyv_dyn::validation::check_bytes_dyn(value, context) }
             }
         }
     };
@@ -440,98 +568,98 @@ fn generate_traits(input: &ItemTrait, args: &Args) -> Result<TokenStream> {
         #input
 
         #[ptr_meta::pointee]
-        #vis trait #ser_trait<#generic_params>:
-            #name<#generic_args> + rkyv_dyn::SerializeDyn
-        {}
+        #vis trait #ser_trait<#ser_params>:
+            #name<#(#generic_args),*> + rkyv_dyn::SerializeDyn<__SE>
+        where
+            #(#predicates,)*
+        {
+            /// Returns the impl ID of the archived type.
+            fn archived_impl_id(&self) -> rkyv_dyn::ImplId;
+        }
 
         #de_trait_def
 
-        const _: ()  = {
+        const _: () = {
             use core::alloc::{Layout, LayoutError};
+            use ptr_meta::DynMetadata;
             use rkyv::{
-                ser::{ScratchSpace, Serializer},
-                Archive,
-                Archived,
-                ArchivedMetadata,
-                ArchivePointee,
+                rancor::Fallible,
+                traits::{ArchivePointee, LayoutRaw},
                 ArchiveUnsized,
-                DeserializeUnsized,
-                Fallible,
+                ArchivedMetadata,
+                Serialize,
                 SerializeUnsized,
             };
             use rkyv_dyn::{
                 ArchivedDynMetadata,
-                DynDeserializer,
-                RegisteredImpl,
-                SerializeDyn,
-                DeserializeDyn,
+                AsDynSerializer,
                 DynSerializer,
+                ImplId,
+                RegisteredImpl,
             };
-            use rkyv_typename::TypeName;
 
-            impl<__T, #generic_params> #ser_trait<#generic_args> for __T
+            impl<__T, #ser_params> #ser_trait<#ser_args> for __T
             where
-                __T: Archive + SerializeDyn + #name<#generic_args>,
-                __T::Archived: RegisteredImpl<dyn #de_trait<#generic_args>>
-            {}
-
-            #de_trait_impl
-
-            impl<#generic_params> TypeName for dyn #de_trait<#generic_args> + '_
-            where
-                #type_name_wheres
+                __T: #name<#(#generic_args),*>
+                    + for<'a> Serialize<dyn DynSerializer<__SE> + 'a>,
+                __T::Archived: RegisteredImpl<dyn #archived_trait>,
+                #(#predicates,)*
             {
-                fn build_type_name<F: FnMut(&str)>(mut f: F) {
-                    #build_type_name
+                fn archived_impl_id(&self) -> ImplId {
+                    <
+                        __T::Archived as RegisteredImpl<dyn #archived_trait>
+                    >::IMPL_ID
                 }
             }
 
-            impl<#generic_params> ArchiveUnsized
-                for dyn #ser_trait<#generic_args>
+            #de_trait_impl
+
+            impl<#ser_params> ArchiveUnsized for dyn #ser_trait<#ser_args>
+            where
+                #(#predicates,)*
             {
-                type Archived = dyn #de_trait<#generic_args>;
-                type MetadataResolver = ();
+                type Archived = dyn #archived_trait;
 
-                unsafe fn resolve_metadata(
-                    &self,
-                    _: usize,
-                    _: Self::MetadataResolver,
-                    out: *mut ArchivedMetadata<Self>.
-                ) {
-                    ArchivedDynMetadata::emplace(self.archived_type_id(), out);
+                fn archived_metadata(&self) -> ArchivedMetadata<Self> {
+                    ArchivedDynMetadata::new(self.archived_impl_id())
                 }
             }
 
-            impl<#generic_params> ArchivePointee
-                for dyn #de_trait<#generic_args>
+            impl<#ser_params> LayoutRaw for dyn #ser_trait<#ser_args>
+            where
+                #(#predicates,)*
             {
-                type ArchivedMetadata = ArchivedDynMetadata<Self>;
-
-                fn pointer_metadata(
-                    archived: &Self::ArchivedMetadata,
-                ) -> <Self as ptr_meta::Pointee>::Metadata {
-                    archived.pointer_metadata()
+                fn layout_raw(
+                    metadata: DynMetadata<Self>,
+                ) -> Result<Layout, LayoutError> {
+                    Ok(metadata.layout())
                 }
             }
 
-            impl<__S, #generic_params> SerializeUnsized<__S>
-                for dyn #ser_trait<#generic_args>
+            impl<__S, #(#generic_params,)* #(#serialize_errors),*>
+                SerializeUnsized<__S> for dyn #ser_trait<#serialize_args>
             where
-                __S: ScratchSpace + Serializer + ?Sized,
+                __S: Fallible + AsDynSerializer<__S::Error> + ?Sized,
+                #(#predicates,)*
             {
                 fn serialize_unsized(
                     &self,
-                    mut serializer: &mut __S,
-                ) -> Result<usize, __E> {
-                    self.serialize_dyn(&mut serializer)
-                        .map_err(|e| *e.downcast::<__E>().unwrap())
+                    serializer: &mut __S,
+                ) -> Result<usize, __S::Error> {
+                    self.serialize_dyn(serializer.as_dyn_serializer())
                 }
+            }
 
-                fn serialize_metadata(
-                    &self,
-                    _: &mut __S,
-                ) -> Result<Self::MetadataResolver, __E> {
-                    Ok(())
+            impl<#archived_params> ArchivePointee for dyn #archived_trait
+            where
+                #(#predicates,)*
+            {
+                type ArchivedMetadata = ArchivedDynMetadata<Self>;
+
+                fn pointer_metadata(
+                    archived: &Self::ArchivedMetadata,
+                ) -> DynMetadata<Self> {
+                    archived.lookup_metadata()
                 }
             }
 
@@ -1,182 +1,203 @@
-// TODO: uncomment and fix
-// #[cfg(feature = "bytecheck")]
-// mod validation;
+#[cfg(test)]
+mod validation;
 
 #[cfg(test)]
 mod tests {
-    // #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    // use core::pin::Pin;
-    // #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    // use rkyv::{
-    //     access_unchecked, access_unchecked_mut,
-    //     ser::{serializers::AllocSerializer, Serializer},
-    //     Archive, Archived, Deserialize, Serialize,
-    // };
-    // #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    // use rkyv_dyn::archive_dyn;
+    use std::sync::Once;
 
-    mod isolate {
-        #[test]
-        #[cfg(not(feature = "wasm"))]
-        fn manual_archive_dyn() {
-            use ptr_meta::{DynMetadata, Pointee};
-            use rkyv::{
-                access_unchecked,
-                de::pooling::Pool,
-                deserialize,
-                rancor::{Error, Fallible, Strategy},
-                to_bytes, Archive, ArchivePointee, ArchiveUnsized, Archived,
-                ArchivedMetadata, Deserialize, DeserializeUnsized, LayoutRaw,
-                Portable, Serialize, SerializeUnsized,
-            };
-            use rkyv_dyn::{
-                register_trait_impls, ArchivedDynMetadata, AsDynDeserializer,
-                AsDynSerializer, DeserializeDyn, DynDeserializer,
-                DynSerializer, ImplId, RegisteredImpl, SerializeDyn,
-            };
+    use rkyv::rancor::Error;
+    use rkyv_dyn::register_trait_impls;
 
-            pub trait Id {
-                fn get_id(&self) -> i32;
-            }
+    /// Registers the trait impls for all of the tests.
+    ///
+    /// Trait impls can only be registered once per process, so every test which
+    /// uses archived trait objects must call this first.
+    #[allow(non_local_definitions)]
+    pub fn register_impls() {
+        static REGISTER: Once = Once::new();
 
-            #[ptr_meta::pointee]
-            pub trait SerializeId<SE, DE>: Id + SerializeDyn<SE> {
-                fn archived_impl_id(&self) -> ImplId;
+        REGISTER.call_once(|| {
+            register_trait_impls! {
+                isolate::ArchivedTest
+                    as dyn isolate::DeserializeId<Error, Error>,
+                derive::ArchivedTest
+                    as dyn derive::DTestTrait<Error, Error>,
+                generic::ArchivedTest<i32>
+                    as dyn generic::DTestTrait<i32, Error, Error>,
+                generic::ArchivedTest<String>
+                    as dyn generic::DTestTrait<String, Error, Error>,
+                mutable::ArchivedTest as dyn mutable::TestTrait,
+                instantiations::ArchivedCircle<f32>
+                    as dyn instantiations::DeserializeShape<Error, Error>,
+                instantiations::ArchivedCircle<f64>
+                    as dyn instantiations::DeserializeShape<Error, Error>,
+                crate::validation::ArchivedTest
+                    as dyn crate::validation::TestTrait,
+                crate::validation::ArchivedTestUnchecked
+                    as dyn crate::validation::TestTrait,
             }
+        });
+    }
 
-            impl<T, SE, DE> SerializeId<SE, DE> for T
-            where
-                T: Id + for<'a> Serialize<dyn DynSerializer<SE> + 'a>,
-                T::Archived: RegisteredImpl<dyn DeserializeId<SE, DE>>,
-            {
-                fn archived_impl_id(&self) -> ImplId {
-                    T::Archived::IMPL_ID
-                }
-            }
+    mod isolate {
+        use ptr_meta::{DynMetadata, Pointee};
+        use rkyv::{
+            access_unchecked, deserialize,
+            rancor::{Error, Fallible},
+            to_bytes,
+            traits::{ArchivePointee, LayoutRaw},
+            Archive, ArchiveUnsized, Archived, ArchivedMetadata, Deserialize,
+            DeserializeUnsized, Portable, Serialize, SerializeUnsized,
+        };
+        use rkyv_dyn::{
+            ArchivedDynMetadata, AsDynDeserializer, AsDynSerializer,
+            DeserializeDyn, DynDeserializer, DynSerializer, ImplId,
+            RegisteredImpl, SerializeDyn,
+        };
+
+        use super::register_impls;
+
+        pub trait Id {
+            fn get_id(&self) -> i32;
+        }
 
-            impl<SE, DE> ArchiveUnsized for dyn SerializeId<SE, DE> {
-                type Archived = dyn DeserializeId<SE, DE>;
+        #[ptr_meta::pointee]
+        pub trait SerializeId<SE, DE>: Id + SerializeDyn<SE> {
+            fn archived_impl_id(&self) -> ImplId;
+        }
 
-                fn archived_metadata(&self) -> ArchivedMetadata<Self> {
-                    ArchivedDynMetadata::new(self.archived_impl_id())
-                }
+        impl<T, SE, DE> SerializeId<SE, DE> for T
+        where
+            T: Id + for<'a> Serialize<dyn DynSerializer<SE> + 'a>,
+            T::Archived: RegisteredImpl<dyn DeserializeId<SE, DE>>,
+        {
+            fn archived_impl_id(&self) -> ImplId {
+                T::Archived::IMPL_ID
             }
+        }
 
-            impl<SE, DE> LayoutRaw for dyn SerializeId<SE, DE> {
-                fn layout_raw(
-                    metadata: <Self as Pointee>::Metadata,
-                ) -> Result<core::alloc::Layout, core::alloc::LayoutError>
-                {
-                    Ok(metadata.layout())
-                }
+        impl<SE, DE> ArchiveUnsized for dyn SerializeId<SE, DE> {
+            type Archived = dyn DeserializeId<SE, DE>;
+
+            fn archived_metadata(&self) -> ArchivedMetadata<Self> {
+                ArchivedDynMetadata::new(self.archived_impl_id())
             }
+        }
 
-            impl<S, DE> SerializeUnsized<S> for dyn SerializeId<S::Error, DE>
-            where
-                S: Fallible + AsDynSerializer<S::Error> + ?Sized,
+        impl<SE, DE> LayoutRaw for dyn SerializeId<SE, DE> {
+            fn layout_raw(
+                metadata: <Self as Pointee>::Metadata,
+            ) -> Result<core::alloc::Layout, core::alloc::LayoutError>
             {
-                fn serialize_unsized(
-                    &self,
-                    serializer: &mut S,
-                ) -> Result<usize, S::Error> {
-                    self.serialize_dyn(serializer.as_dyn_serializer())
-                }
+                Ok(metadata.layout())
             }
+        }
 
-            #[ptr_meta::pointee]
-            pub trait DeserializeId<SE, DE>:
-                Id
-                + DeserializeDyn<dyn SerializeId<SE, DE>, DE>
-                + Portable
-            {
+        impl<S, DE> SerializeUnsized<S> for dyn SerializeId<S::Error, DE>
+        where
+            S: Fallible + AsDynSerializer<S::Error> + ?Sized,
+        {
+            fn serialize_unsized(
+                &self,
+                serializer: &mut S,
+            ) -> Result<usize, S::Error> {
+                self.serialize_dyn(serializer.as_dyn_serializer())
             }
+        }
 
-            impl<SE, DE> ArchivePointee for dyn DeserializeId<SE, DE> {
-                type ArchivedMetadata = ArchivedDynMetadata<Self>;
+        #[ptr_meta::pointee]
+        pub trait DeserializeId<SE, DE>:
+            Id + DeserializeDyn<dyn SerializeId<SE, DE>, DE> + Portable
+        {
+        }
 
-                fn pointer_metadata(
-                    archived: &Self::ArchivedMetadata,
-                ) -> <Self as Pointee>::Metadata {
-                    archived.lookup_metadata()
-                }
-            }
+        impl<SE, DE> ArchivePointee for dyn DeserializeId<SE, DE> {
+            type ArchivedMetadata = ArchivedDynMetadata<Self>;
 
-            impl<T, SE, DE> DeserializeId<SE, DE> for T where
-                T: Id + DeserializeDyn<dyn SerializeId<SE, DE>, DE> + Portable
-            {
+            fn pointer_metadata(
+                archived: &Self::ArchivedMetadata,
+            ) -> <Self as Pointee>::Metadata {
+                archived.lookup_metadata()
             }
+        }
 
-            impl<SE, D> DeserializeUnsized<dyn SerializeId<SE, D::Error>, D>
-                for dyn DeserializeId<SE, D::Error>
-            where
-                D: Fallible + AsDynDeserializer<D::Error> + ?Sized,
-            {
-                unsafe fn deserialize_unsized(
-                    &self,
-                    deserializer: &mut D,
-                    out: *mut dyn SerializeId<SE, D::Error>,
-                ) -> Result<(), <D as Fallible>::Error> {
-                    self.deserialize_dyn(
-                        deserializer.as_dyn_deserializer(),
-                        out,
-                    )
-                }
+        impl<T, SE, DE> DeserializeId<SE, DE> for T where
+            T: Id + DeserializeDyn<dyn SerializeId<SE, DE>, DE> + Portable
+        {
+        }
 
-                fn deserialize_metadata(&self) -> <
-                    dyn SerializeId<SE, D::Error> as ptr_meta::Pointee
-                >::Metadata{
-                    self.deserialized_pointer_metadata()
-                }
+        impl<SE, D> DeserializeUnsized<dyn SerializeId<SE, D::Error>, D>
+            for dyn DeserializeId<SE, D::Error>
+        where
+            D: Fallible + AsDynDeserializer<D::Error> + ?Sized,
+        {
+            unsafe fn deserialize_unsized(
+                &self,
+                deserializer: &mut D,
+                out: *mut dyn SerializeId<SE, D::Error>,
+            ) -> Result<(), <D as Fallible>::Error> {
+                self.deserialize_dyn(deserializer.as_dyn_deserializer(), out)
             }
 
-            #[derive(Archive, Serialize, Deserialize)]
-            pub struct Test {
-                id: i32,
+            fn deserialize_metadata(
+                &self,
+            ) -> <dyn SerializeId<SE, D::Error> as ptr_meta::Pointee>::Metadata
+            {
+                self.deserialized_pointer_metadata()
             }
+        }
 
-            impl Id for Test {
-                fn get_id(&self) -> i32 {
-                    self.id
-                }
-            }
+        #[derive(Archive, Serialize, Deserialize)]
+        pub struct Test {
+            id: i32,
+        }
 
-            register_trait_impls! {
-                Archived<Test> as dyn DeserializeId<Error, Error>,
+        impl Id for Test {
+            fn get_id(&self) -> i32 {
+                self.id
             }
+        }
 
-            // Hack to make rustfmt happy
-            type SId<SE, DE> = dyn SerializeId<SE, DE>;
-            impl<SE, DE> DeserializeDyn<SId<SE, DE>, DE> for ArchivedTest
-            where
-                Archived<Test>:
-                    for<'a> Deserialize<Test, dyn DynDeserializer<DE> + 'a>,
-                Archived<Test>: RegisteredImpl<dyn DeserializeId<SE, DE>>,
-            {
-                fn deserialize_dyn(
-                    &self,
-                    deserializer: &mut dyn DynDeserializer<DE>,
-                    out: *mut dyn SerializeId<SE, DE>,
-                ) -> Result<(), DE> {
-                    unsafe {
-                        <
-                            Self as DeserializeUnsized<Test, _>
-                        >::deserialize_unsized(self, deserializer, out.cast())
-                    }
+        // Hack to make rustfmt happy
+        type SId<SE, DE> = dyn SerializeId<SE, DE>;
+        impl<SE, DE> DeserializeDyn<SId<SE, DE>, DE> for ArchivedTest
+        where
+            Archived<Test>:
+                for<'a> Deserialize<Test, dyn DynDeserializer<DE> + 'a>,
+            Archived<Test>: RegisteredImpl<dyn DeserializeId<SE, DE>>,
+        {
+            fn deserialize_dyn(
+                &self,
+                deserializer: &mut dyn DynDeserializer<DE>,
+                out: *mut dyn SerializeId<SE, DE>,
+            ) -> Result<(), DE> {
+                unsafe {
+                    <Self as DeserializeUnsized<Test, _>>::deserialize_unsized(
+                        self,
+                        deserializer,
+                        out.cast(),
+                    )
                 }
+            }
 
-                fn deserialized_pointer_metadata(
-                    &self,
-                ) -> DynMetadata<dyn SerializeId<SE, DE>> {
-                    ptr_meta::metadata(core::ptr::null::<Test>()
-                        as *const dyn SerializeId<SE, DE>)
-                }
+            fn deserialized_pointer_metadata(
+                &self,
+            ) -> DynMetadata<dyn SerializeId<SE, DE>> {
+                ptr_meta::metadata(
+                    core::ptr::null::<Test>() as *const dyn SerializeId<SE, DE>
+                )
             }
+        }
 
-            impl Id for ArchivedTest {
-                fn get_id(&self) -> i32 {
-                    self.id.into()
-                }
+        impl Id for ArchivedTest {
+            fn get_id(&self) -> i32 {
+                self.id.into()
             }
+        }
+
+        #[test]
+        fn manual_archive_dyn() {
+            register_impls();
 
             let value: Box<dyn SerializeId<Error, Error>> =
                 Box::new(Test { id: 42 });
@@ -192,253 +213,347 @@ mod tests {
             assert_eq!(value.get_id(), archived_value.get_id());
 
             let deserialized_value: Box<dyn SerializeId<Error, Error>> =
-                deserialize::<Box<dyn SerializeId<Error, Error>>, _, Error>(
+                deserialize::<Box<dyn SerializeId<Error, Error>>, Error>(
                     archived_value,
-                    Strategy::wrap(&mut Pool::new()),
                 )
                 .unwrap();
             assert_eq!(value.get_id(), deserialized_value.get_id());
         }
     }
 
-    // TODO: uncomment and fix
-    // #[test]
-    // #[cfg(not(feature = "wasm"))]
-    // fn archive_dyn() {
-    //     #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
-    //     pub trait TestTrait {
-    //         fn get_id(&self) -> i32;
-    //     }
-
-    //     #[derive(Archive, Serialize, Deserialize)]
-    //     #[rkyv_derive(TypeName)]
-    //     pub struct Test {
-    //         id: i32,
-    //     }
-
-    //     #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
-    //     impl TestTrait for Test {
-    //         fn get_id(&self) -> i32 {
-    //             self.id
-    //         }
-    //     }
-
-    //     impl TestTrait for ArchivedTest {
-    //         fn get_id(&self) -> i32 {
-    //             self.id.into()
-    //         }
-    //     }
-
-    //     let value: Box<dyn STestTrait> = Box::new(Test { id: 42 });
-
-    //     let mut serializer = AllocSerializer::<256>::default();
-    //     serializer.serialize_value(&value).unwrap();
-    //     let buf = serializer.into_serializer().into_inner();
-    //     let archived_value =
-    //         unsafe { archived_root::<Box<dyn STestTrait>>(buf.as_ref()) };
-    //     assert_eq!(value.get_id(), archived_value.get_id());
-
-    //     // exercise vtable cache
-    //     assert_eq!(value.get_id(), archived_value.get_id());
-    //     assert_eq!(value.get_id(), archived_value.get_id());
-
-    //     // deserialize
-    //     let deserialized_value: Box<dyn STestTrait> =
-    //         archived_value.deserialize(&mut Infallible).unwrap();
-    //     assert_eq!(value.get_id(), deserialized_value.get_id());
-    //     assert_eq!(value.get_id(), deserialized_value.get_id());
-    // }
-
-    // #[test]
-    // #[cfg(not(feature = "wasm"))]
-    // fn archive_dyn_generic() {
-    //     use core::alloc::Layout;
-    //     use rkyv::archived_value;
-    //     use rkyv_dyn::archive_dyn;
-    //     use rkyv_typename::TypeName;
-
-    //     use rkyv_dyn::{
-    //         trait_impl, DynDeserializer, DynError, DynSerializer,
-    //     };
-
-    //     #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
-    //     pub trait TestTrait<T: TypeName> {
-    //         fn get_value(&self) -> T;
-    //     }
-
-    //     #[derive(Archive, Serialize, Deserialize)]
-    //     #[rkyv_derive(TypeName)]
-    //     pub struct Test<T> {
-    //         value: T,
-    //     }
-
-    //     #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
-    //     impl TestTrait<i32> for Test<i32> {
-    //         fn get_value(&self) -> i32 {
-    //             self.value
-    //         }
-    //     }
-
-    //     impl TestTrait<i32> for ArchivedTest<i32> {
-    //         fn get_value(&self) -> i32 {
-    //             self.value.into()
-    //         }
-    //     }
-
-    //     impl<T: core::fmt::Display> TestTrait<String> for Test<T> {
-    //         fn get_value(&self) -> String {
-    //             format!("{}", self.value)
-    //         }
-    //     }
-
-    //     impl<T> rkyv_dyn::DeserializeDyn<dyn STestTrait<String>> for
-    // ArchivedTest<T>     where
-    //         T: Archive
-    //             + for<'a> Serialize<dyn DynSerializer + 'a>
-    //             + core::fmt::Display
-    //             + TypeName
-    //             + 'static,
-    //         ArchivedTest<T>: for<'a> Deserialize<Test<T>, (dyn
-    // DynDeserializer + 'a)>
-    //             + rkyv_dyn::RegisteredImpl<dyn DTestTrait<String>>,
-    //     {
-    //         unsafe fn deserialize_dyn(
-    //             &self,
-    //             deserializer: &mut dyn DynDeserializer,
-    //             alloc: &mut dyn FnMut(Layout) -> *mut u8,
-    //         ) -> Result<*mut (), DynError> {
-    //             let result = alloc(core::alloc::Layout::new::<Test<T>>())
-    //                 .cast::<Test<T>>();
-    //             assert!(!result.is_null());
-    //             result.write(self.deserialize(deserializer)?);
-    //             Ok(result as *mut ())
-    //         }
-
-    //         fn deserialize_dyn_metadata(
-    //             &self,
-    //             _: &mut dyn DynDeserializer,
-    //         ) -> Result<
-    //             <dyn STestTrait<String> as ptr_meta::Pointee>::Metadata,
-    //             DynError,
-    //         > { unsafe { Ok(core::mem::transmute(ptr_meta::metadata(
-    //         > core::ptr::null::<Test<T>>() as *const dyn STestTrait<String>,
-    //         > ))) }
-    //         }
-    //     }
-
-    //     impl<T: Archive> TestTrait<String> for ArchivedTest<T>
-    //     where
-    //         T::Archived: core::fmt::Display,
-    //     {
-    //         fn get_value(&self) -> String {
-    //             format!("{}", self.value)
-    //         }
-    //     }
-
-    //     trait_impl!(Archived<Test<String>> as dyn DTestTrait<String>);
-
-    //     let i32_value: Box<dyn STestTrait<i32>> = Box::new(Test { value: 42
-    // });     let string_value: Box<dyn STestTrait<String>> = Box::new(Test
-    // {         value: "hello world".to_string(),
-    //     });
-
-    //     let mut serializer = AllocSerializer::<256>::default();
-    //     let i32_pos = serializer.serialize_value(&i32_value).unwrap();
-    //     let string_pos = serializer.serialize_value(&string_value).unwrap();
-    //     let buf = serializer.into_serializer().into_inner();
-    //     let i32_archived_value = unsafe {
-    //         archived_value::<Box<dyn STestTrait<i32>>>(buf.as_ref(), i32_pos)
-    //     };
-    //     let string_archived_value = unsafe {
-    //         archived_value::<Box<dyn STestTrait<String>>>(
-    //             buf.as_ref(),
-    //             string_pos,
-    //         )
-    //     };
-    //     assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
-    //     assert_eq!(string_value.get_value(),
-    // string_archived_value.get_value());
-
-    //     // exercise vtable cache
-    //     assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
-    //     assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
-
-    //     let i32_deserialized_value: Box<dyn STestTrait<i32>> =
-    //         i32_archived_value.deserialize(&mut Infallible).unwrap();
-    //     assert_eq!(i32_value.get_value(),
-    // i32_deserialized_value.get_value());     assert_eq!(i32_value.
-    // get_value(), i32_deserialized_value.get_value());
-
-    //     assert_eq!(string_value.get_value(),
-    // string_archived_value.get_value());     assert_eq!(string_value.
-    // get_value(), string_archived_value.get_value());
-
-    //     let string_deserialized_value: Box<dyn STestTrait<String>> =
-    //         string_archived_value.deserialize(&mut Infallible).unwrap();
-    //     assert_eq!(
-    //         string_value.get_value(),
-    //         string_deserialized_value.get_value()
-    //     );
-    //     assert_eq!(
-    //         string_value.get_value(),
-    //         string_deserialized_value.get_value()
-    //     );
-    // }
-
-    // #[test]
-    // #[cfg(not(feature = "wasm"))]
-    // fn mutable_dyn_ref() {
-    //     use rkyv_dyn::archive_dyn;
-    //     use rkyv_typename::TypeName;
-
-    //     #[archive_dyn]
-    //     trait TestTrait {
-    //         fn value(&self) -> i32;
-    //         fn set_value(self: Pin<&mut Self>, value: i32);
-    //     }
-
-    //     #[derive(Archive, Serialize)]
-    //     #[rkyv_derive(TypeName)]
-    //     struct Test(i32);
-
-    //     #[archive_dyn]
-    //     impl TestTrait for Test {
-    //         fn value(&self) -> i32 {
-    //             self.0
-    //         }
-    //         fn set_value(self: Pin<&mut Self>, value: i32) {
-    //             unsafe {
-    //                 let s = self.get_unchecked_mut();
-    //                 s.0 = value;
-    //             }
-    //         }
-    //     }
-
-    //     impl TestTrait for ArchivedTest {
-    //         fn value(&self) -> i32 {
-    //             self.0.into()
-    //         }
-    //         fn set_value(self: Pin<&mut Self>, value: i32) {
-    //             unsafe {
-    //                 let s = self.get_unchecked_mut();
-    //                 s.0 = value.into();
-    //             }
-    //         }
-    //     }
-
-    //     let value = Box::new(Test(10)) as Box<dyn SerializeTestTrait>;
-
-    //     let mut serializer = AllocSerializer::<256>::default();
-    //     serializer.serialize_value(&value).unwrap();
-    //     let mut buf = serializer.into_serializer().into_inner();
-    //     let mut value = unsafe {
-    //         archived_root_mut::<Box<dyn SerializeTestTrait>>(Pin::new(
-    //             buf.as_mut(),
-    //         ))
-    //     };
-
-    //     assert_eq!(value.value(), 10);
-    //     value.as_mut().get_pin_mut().set_value(64);
-    //     assert_eq!(value.value(), 64);
-    // }
+    mod derive {
+        use rkyv::{
+            access_unchecked, deserialize, rancor::Error, to_bytes, Archive,
+            Archived, Deserialize, Serialize,
+        };
+        use rkyv_dyn::archive_dyn;
+
+        use super::register_impls;
+
+        #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
+        pub trait TestTrait {
+            fn get_id(&self) -> i32;
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        pub struct Test {
+            id: i32,
+        }
+
+        #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
+        impl TestTrait for Test {
+            fn get_id(&self) -> i32 {
+                self.id
+            }
+        }
+
+        impl TestTrait for ArchivedTest {
+            fn get_id(&self) -> i32 {
+                self.id.into()
+            }
+        }
+
+        #[test]
+        fn archive_dyn() {
+            register_impls();
+
+            let value: Box<dyn STestTrait<Error, Error>> =
+                Box::new(Test { id: 42 });
+
+            let buf = to_bytes::<Error>(&value).unwrap();
+            type ArchivedType = Archived<Box<dyn STestTrait<Error, Error>>>;
+            let archived_value =
+                unsafe { access_unchecked::<ArchivedType>(buf.as_ref()) };
+            assert_eq!(value.get_id(), archived_value.get_id());
+
+            // exercise vtable cache
+            assert_eq!(value.get_id(), archived_value.get_id());
+            assert_eq!(value.get_id(), archived_value.get_id());
+
+            // deserialize
+            let deserialized_value = deserialize::<
+                Box<dyn STestTrait<Error, Error>>,
+                Error,
+            >(archived_value)
+            .unwrap();
+            assert_eq!(value.get_id(), deserialized_value.get_id());
+            assert_eq!(value.get_id(), deserialized_value.get_id());
+        }
+    }
+
+    mod generic {
+        use core::fmt::Display;
+
+        use ptr_meta::DynMetadata;
+        use rkyv::{
+            access_unchecked, deserialize, rancor::Error, to_bytes, Archive,
+            Archived, Deserialize, DeserializeUnsized, Serialize,
+        };
+        use rkyv_dyn::{
+            archive_dyn, DeserializeDyn, DynDeserializer, DynSerializer,
+            RegisteredImpl,
+        };
+
+        use super::register_impls;
+
+        #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
+        pub trait TestTrait<T> {
+            fn get_value(&self) -> T;
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        pub struct Test<T> {
+            value: T,
+        }
+
+        #[archive_dyn(serialize = "STestTrait", deserialize = "DTestTrait")]
+        impl TestTrait<i32> for Test<i32> {
+            fn get_value(&self) -> i32 {
+                self.value
+            }
+        }
+
+        impl TestTrait<i32> for ArchivedTest<i32> {
+            fn get_value(&self) -> i32 {
+                self.value.into()
+            }
+        }
+
+        impl<T: Display> TestTrait<String> for Test<T> {
+            fn get_value(&self) -> String {
+                format!("{}", self.value)
+            }
+        }
+
+        impl<T, SE, DE> DeserializeDyn<dyn STestTrait<String, SE, DE>, DE>
+            for ArchivedTest<T>
+        where
+            T: Archive
+                + for<'a> Serialize<dyn DynSerializer<SE> + 'a>
+                + Display
+                + 'static,
+            T::Archived: Display,
+            Self: for<'a> Deserialize<Test<T>, dyn DynDeserializer<DE> + 'a>
+                + RegisteredImpl<dyn DTestTrait<String, SE, DE>>,
+        {
+            fn deserialize_dyn(
+                &self,
+                deserializer: &mut dyn DynDeserializer<DE>,
+                out: *mut dyn STestTrait<String, SE, DE>,
+            ) -> Result<(), DE> {
+                unsafe {
+                    <
+                        Self as DeserializeUnsized<Test<T>, _>
+                    >::deserialize_unsized(self, deserializer, out.cast())
+                }
+            }
+
+            fn deserialized_pointer_metadata(
+                &self,
+            ) -> DynMetadata<dyn STestTrait<String, SE, DE>> {
+                ptr_meta::metadata(core::ptr::null::<Test<T>>()
+                    as *const dyn STestTrait<String, SE, DE>)
+            }
+        }
+
+        impl<T: Archive> TestTrait<String> for ArchivedTest<T>
+        where
+            T::Archived: Display,
+        {
+            fn get_value(&self) -> String {
+                format!("{}", self.value)
+            }
+        }
+
+        #[test]
+        fn archive_dyn_generic() {
+            register_impls();
+
+            let i32_value: Box<dyn STestTrait<i32, Error, Error>> =
+                Box::new(Test { value: 42 });
+            let string_value: Box<dyn STestTrait<String, Error, Error>> =
+                Box::new(Test {
+                    value: "hello world".to_string(),
+                });
+
+            let i32_buf = to_bytes::<Error>(&i32_value).unwrap();
+            let string_buf = to_bytes::<Error>(&string_value).unwrap();
+            let i32_archived_value = unsafe {
+                access_unchecked::<
+                    Archived<Box<dyn STestTrait<i32, Error, Error>>>,
+                >(i32_buf.as_ref())
+            };
+            let string_archived_value = unsafe {
+                access_unchecked::<
+                    Archived<Box<dyn STestTrait<String, Error, Error>>>,
+                >(string_buf.as_ref())
+            };
+            assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
+            assert_eq!(
+                string_value.get_value(),
+                string_archived_value.get_value(),
+            );
+
+            // exercise vtable cache
+            assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
+            assert_eq!(i32_value.get_value(), i32_archived_value.get_value());
+
+            let i32_deserialized_value = deserialize::<
+                Box<dyn STestTrait<i32, Error, Error>>,
+                Error,
+            >(i32_archived_value)
+            .unwrap();
+            assert_eq!(
+                i32_value.get_value(),
+                i32_deserialized_value.get_value()
+            );
+
+            let string_deserialized_value = deserialize::<
+                Box<dyn STestTrait<String, Error, Error>>,
+                Error,
+            >(string_archived_value)
+            .unwrap();
+            assert_eq!(
+                string_value.get_value(),
+                string_deserialized_value.get_value(),
+            );
+        }
+    }
+
+    mod mutable {
+        use core::pin::Pin;
+
+        use rkyv::{
+            access_unchecked_mut, boxed::ArchivedBox, rancor::Error, to_bytes,
+            Archive, Archived, Serialize,
+        };
+        use rkyv_dyn::archive_dyn;
+
+        use super::register_impls;
+
+        #[archive_dyn]
+        pub trait TestTrait {
+            fn value(&self) -> i32;
+            fn set_value(self: Pin<&mut Self>, value: i32);
+        }
+
+        #[derive(Archive, Serialize)]
+        pub struct Test(i32);
+
+        #[archive_dyn]
+        impl TestTrait for Test {
+            fn value(&self) -> i32 {
+                self.0
+            }
+
+            fn set_value(self: Pin<&mut Self>, value: i32) {
+                self.get_mut().0 = value;
+            }
+        }
+
+        impl TestTrait for ArchivedTest {
+            fn value(&self) -> i32 {
+                self.0.into()
+            }
+
+            fn set_value(self: Pin<&mut Self>, value: i32) {
+                unsafe {
+                    self.get_unchecked_mut().0 = value.into();
+                }
+            }
+        }
+
+        #[test]
+        fn mutable_dyn_ref() {
+            register_impls();
+
+            let value =
+                Box::new(Test(10)) as Box<dyn SerializeTestTrait<Error>>;
+
+            let mut buf = to_bytes::<Error>(&value).unwrap();
+            let archived = unsafe {
+                access_unchecked_mut::<
+                    Archived<Box<dyn SerializeTestTrait<Error>>>,
+                >(buf.as_mut())
+            };
+            let mut value = unsafe {
+                Pin::new_unchecked(
+                    ArchivedBox::get_seal(archived).unseal_unchecked(),
+                )
+            };
+
+            assert_eq!(value.value(), 10);
+            value.as_mut().set_value(64);
+            assert_eq!(value.value(), 64);
+        }
+    }
+
+    mod instantiations {
+        use rkyv::{
+            access_unchecked, deserialize, rancor::Error, to_bytes, Archive,
+            Archived, Deserialize, Serialize,
+        };
+        use rkyv_dyn::{archive_dyn, archive_dyn_impls};
+
+        use super::register_impls;
+
+        #[archive_dyn(deserialize)]
+        pub trait Shape {
+            fn area(&self) -> f64;
+        }
+
+        #[derive(Archive, Serialize, Deserialize)]
+        pub struct Circle<T> {
+            radius: T,
+        }
+
+        #[archive_dyn(deserialize)]
+        impl<T: Copy + Into<f64>> Shape for Circle<T> {
+            fn area(&self) -> f64 {
+                let radius = self.radius.into();
+                core::f64::consts::PI * radius * radius
+            }
+        }
+
+        impl<T> Shape for ArchivedCircle<T>
+        where
+            T: Archive + Into<f64>,
+            T::Archived: Copy + Into<T>,
+        {
+            fn area(&self) -> f64 {
+                let radius = Into::<T>::into(self.radius).into();
+                core::f64::consts::PI * radius * radius
+            }
+        }
+
+        archive_dyn_impls! {
+            #[archive_dyn(deserialize)]
+            Circle<f32> as Shape,
+            Circle<f64> as Shape,
+        }
+
+        type BoxedShape = Box<dyn SerializeShape<Error, Error>>;
+
+        #[test]
+        fn generic_impl_instantiations() {
+            register_impls();
+
+            let values: [BoxedShape; 2] = [
+                Box::new(Circle { radius: 2.0f32 }),
+                Box::new(Circle { radius: 3.0f64 }),
+            ];
+
+            for value in values {
+                let buf = to_bytes::<Error>(&value).unwrap();
+                let archived_value = unsafe {
+                    access_unchecked::<Archived<BoxedShape>>(buf.as_ref())
+                };
+                assert_eq!(value.area(), archived_value.area());
+
+                let deserialized_value =
+                    deserialize::<BoxedShape, Error>(archived_value).unwrap();
+                assert_eq!(value.area(), deserialized_value.area());
+            }
+        }
+    }
 }
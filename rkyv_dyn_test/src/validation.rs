@@ -1,87 +1,98 @@
-#[cfg(test)]
-mod tests {
-    #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    use bytecheck::CheckBytes;
-    #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    use rkyv::{
-        check_archived_root,
-        ser::{serializers::AllocSerializer, Serializer},
-        validation::validators::DefaultValidator,
-        Archive, Archived, Serialize,
-    };
-    #[cfg_attr(feature = "wasm", allow(unused_imports))]
-    use rkyv_dyn::archive_dyn;
-
-    #[cfg_attr(feature = "wasm", allow(dead_code))]
-    fn serialize_and_check<T: Serialize<AllocSerializer<256>>>(value: &T)
-    where
-        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
-    {
-        let mut serializer = AllocSerializer::<256>::default();
-        serializer.serialize_value(value).unwrap();
-        let buf = serializer.into_serializer().into_inner();
-        check_archived_root::<T>(buf.as_ref()).unwrap();
+#![cfg_attr(not(feature = "bytecheck"), allow(dead_code))]
+
+use rkyv::{
+    munge::munge, rancor::Fallible, Archive, Archived, Place, Portable,
+    Serialize,
+};
+use rkyv_dyn::archive_dyn;
+
+#[archive_dyn]
+pub trait TestTrait {
+    fn get_id(&self) -> i32;
+}
+
+#[derive(Archive, Serialize)]
+pub struct Test {
+    id: i32,
+}
+
+#[archive_dyn]
+impl TestTrait for Test {
+    fn get_id(&self) -> i32 {
+        self.id
     }
+}
 
-    #[test]
-    #[cfg(not(feature = "wasm"))]
-    #[cfg_attr(miri, ignore = "miri does not support ctor, see lib.rs")]
-    fn check_dyn() {
-        #[archive_dyn]
-        pub trait TestTrait {
-            fn get_id(&self) -> i32;
-        }
+impl TestTrait for ArchivedTest {
+    fn get_id(&self) -> i32 {
+        self.id.into()
+    }
+}
 
-        #[derive(Archive, Serialize)]
-        #[rkyv(bytecheck)]
-        pub struct Test {
-            id: i32,
-        }
+pub struct TestUnchecked {
+    id: i32,
+}
 
-        #[archive_dyn]
-        impl TestTrait for Test {
-            fn get_id(&self) -> i32 {
-                self.id
-            }
-        }
+// Deriving `Archive` also derives `CheckBytes`, so this archived type is
+// written by hand.
+#[derive(Portable)]
+#[repr(transparent)]
+pub struct ArchivedTestUnchecked {
+    id: Archived<i32>,
+}
 
-        impl TestTrait for ArchivedTest {
-            fn get_id(&self) -> i32 {
-                self.id.into()
-            }
-        }
+impl Archive for TestUnchecked {
+    type Archived = ArchivedTestUnchecked;
+    type Resolver = ();
 
-        let value: Box<dyn SerializeTestTrait> = Box::new(Test { id: 42 });
+    fn resolve(&self, _: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedTestUnchecked { id } = out);
+        self.id.resolve((), id);
+    }
+}
 
-        serialize_and_check(&value);
+impl<S: Fallible + ?Sized> Serialize<S> for TestUnchecked {
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
 
-        #[derive(Archive, Serialize)]
-        pub struct TestUnchecked {
-            id: i32,
-        }
+#[archive_dyn]
+impl TestTrait for TestUnchecked {
+    fn get_id(&self) -> i32 {
+        self.id
+    }
+}
 
-        #[archive_dyn]
-        impl TestTrait for TestUnchecked {
-            fn get_id(&self) -> i32 {
-                self.id
-            }
-        }
+impl TestTrait for ArchivedTestUnchecked {
+    fn get_id(&self) -> i32 {
+        self.id.into()
+    }
+}
 
-        impl TestTrait for ArchivedTestUnchecked {
-            fn get_id(&self) -> i32 {
-                self.id.into()
-            }
-        }
+#[cfg(feature = "bytecheck")]
+mod tests {
+    use rkyv::{access, rancor::Error, to_bytes, Archived};
 
-        let value: Box<dyn SerializeTestTrait> =
-            Box::new(TestUnchecked { id: 42 });
+    use super::{SerializeTestTrait, Test, TestUnchecked};
+    use crate::tests::register_impls;
 
-        let mut serializer = AllocSerializer::<256>::default();
-        serializer.serialize_value(&value).unwrap();
-        let buf = serializer.into_serializer().into_inner();
-        if let Ok(_) =
-            check_archived_root::<Box<dyn SerializeTestTrait>>(buf.as_ref())
-        {
+    type ArchivedType = Archived<Box<dyn SerializeTestTrait<Error>>>;
+
+    #[test]
+    fn check_dyn() {
+        register_impls();
+
+        let value: Box<dyn SerializeTestTrait<Error>> =
+            Box::new(Test { id: 42 });
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived_value = access::<ArchivedType, Error>(&buf).unwrap();
+        assert_eq!(archived_value.get_id(), 42);
+
+        let value: Box<dyn SerializeTestTrait<Error>> =
+            Box::new(TestUnchecked { id: 42 });
+        let buf = to_bytes::<Error>(&value).unwrap();
+        if access::<ArchivedType, Error>(&buf).is_ok() {
             panic!("check passed for type that does not implement CheckBytes");
         }
     }
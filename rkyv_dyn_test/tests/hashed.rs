@@ -0,0 +1,123 @@
+//! Tests for trait impls registered with hashed impl IDs.
+//!
+//! Trait impls can only be registered once per process, so these tests live in
+//! their own test binary.
+
+use rkyv::{
+    access_unchecked, deserialize, rancor::Error, to_bytes, Archive, Archived,
+    Deserialize, Serialize,
+};
+use rkyv_dyn::{
+    archive_dyn, fingerprint, register_trait_impls, ImplId, RegisteredImpl,
+};
+
+#[archive_dyn(deserialize)]
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+pub struct Square {
+    side: f64,
+}
+
+#[archive_dyn(deserialize)]
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Shape for ArchivedSquare {
+    fn area(&self) -> f64 {
+        let side = self.side.to_native();
+        side * side
+    }
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+pub struct Rectangle {
+    width: f64,
+    height: f64,
+}
+
+#[archive_dyn(deserialize)]
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+}
+
+impl Shape for ArchivedRectangle {
+    fn area(&self) -> f64 {
+        self.width.to_native() * self.height.to_native()
+    }
+}
+
+type BoxedShape = Box<dyn SerializeShape<Error, Error>>;
+
+#[test]
+#[allow(non_local_definitions)]
+fn hashed_impl_ids() {
+    register_trait_impls! {
+        hashed;
+        ArchivedSquare as dyn DeserializeShape<Error, Error>,
+        ArchivedRectangle as dyn DeserializeShape<Error, Error>
+            = "shapes::Rectangle",
+    }
+
+    assert_eq!(
+        <ArchivedSquare as RegisteredImpl<
+            dyn DeserializeShape<Error, Error>,
+        >>::IMPL_ID,
+        fingerprint(concat!(
+            module_path!(),
+            "::ArchivedSquare as dyn DeserializeShape<Error, Error>",
+        )) as ImplId,
+    );
+    assert_eq!(
+        <ArchivedRectangle as RegisteredImpl<
+            dyn DeserializeShape<Error, Error>,
+        >>::IMPL_ID,
+        fingerprint("shapes::Rectangle") as ImplId,
+    );
+
+    let values: [BoxedShape; 2] = [
+        Box::new(Square { side: 2.0 }),
+        Box::new(Rectangle {
+            width: 2.0,
+            height: 3.0,
+        }),
+    ];
+
+    for value in values {
+        let buf = to_bytes::<Error>(&value).unwrap();
+        let archived_value =
+            unsafe { access_unchecked::<Archived<BoxedShape>>(buf.as_ref()) };
+        assert_eq!(value.area(), archived_value.area());
+
+        let deserialized_value =
+            deserialize::<BoxedShape, Error>(archived_value).unwrap();
+        assert_eq!(value.area(), deserialized_value.area());
+    }
+}
+
+#[ptr_meta::pointee]
+trait Marker {}
+
+struct A;
+struct B;
+
+impl Marker for A {}
+impl Marker for B {}
+
+#[test]
+#[should_panic = "both hash to impl ID"]
+#[allow(non_local_definitions)]
+fn hashed_impl_id_collision() {
+    register_trait_impls! {
+        hashed;
+        A as dyn Marker = "marker",
+        B as dyn Marker = "marker",
+    }
+}
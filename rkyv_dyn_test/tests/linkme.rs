@@ -0,0 +1,168 @@
+//! Tests for trait impls registered at link time.
+//!
+//! Trait impls can only be registered once per process, so these tests live in
+//! their own test binary.
+
+#![cfg(feature = "linkme")]
+
+use rkyv::{
+    access_unchecked, deserialize, rancor::Error, to_bytes, Archive, Archived,
+    Deserialize, Serialize,
+};
+use rkyv_dyn::{
+    archive_dyn, find_trait_impl, fingerprint, register_trait_impl,
+    register_trait_impls, ImplId, RegisteredImpl,
+};
+
+#[archive_dyn(deserialize)]
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+pub struct Square {
+    side: f64,
+}
+
+#[archive_dyn(deserialize)]
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.side * self.side
+    }
+}
+
+impl Shape for ArchivedSquare {
+    fn area(&self) -> f64 {
+        let side = self.side.to_native();
+        side * side
+    }
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+pub struct Rectangle {
+    width: f64,
+    height: f64,
+}
+
+#[archive_dyn(deserialize)]
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.width * self.height
+    }
+}
+
+impl Shape for ArchivedRectangle {
+    fn area(&self) -> f64 {
+        self.width.to_native() * self.height.to_native()
+    }
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+pub struct Circle {
+    radius: f64,
+}
+
+#[archive_dyn(deserialize)]
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0 * self.radius * self.radius
+    }
+}
+
+impl Shape for ArchivedCircle {
+    fn area(&self) -> f64 {
+        let radius = self.radius.to_native();
+        3.0 * radius * radius
+    }
+}
+
+register_trait_impl!(ArchivedSquare as dyn DeserializeShape<Error, Error>);
+register_trait_impl!(
+    ArchivedRectangle as dyn DeserializeShape<Error, Error> =
+        "shapes::Rectangle"
+);
+
+type BoxedShape = Box<dyn SerializeShape<Error, Error>>;
+
+fn roundtrip(value: BoxedShape) {
+    let buf = to_bytes::<Error>(&value).unwrap();
+    let archived_value =
+        unsafe { access_unchecked::<Archived<BoxedShape>>(buf.as_ref()) };
+    assert_eq!(value.area(), archived_value.area());
+
+    #[cfg(feature = "bytecheck")]
+    {
+        let checked_value =
+            rkyv::access::<Archived<BoxedShape>, Error>(buf.as_ref()).unwrap();
+        assert_eq!(value.area(), checked_value.area());
+    }
+
+    let deserialized_value =
+        deserialize::<BoxedShape, Error>(archived_value).unwrap();
+    assert_eq!(value.area(), deserialized_value.area());
+}
+
+#[test]
+fn link_time_impl_ids() {
+    assert_eq!(
+        <ArchivedSquare as RegisteredImpl<
+            dyn DeserializeShape<Error, Error>,
+        >>::IMPL_ID,
+        fingerprint(concat!(
+            module_path!(),
+            "::ArchivedSquare as dyn DeserializeShape<Error, Error>",
+        )) as ImplId,
+    );
+    assert_eq!(
+        <ArchivedRectangle as RegisteredImpl<
+            dyn DeserializeShape<Error, Error>,
+        >>::IMPL_ID,
+        fingerprint("shapes::Rectangle") as ImplId,
+    );
+
+    // Link-time trait impls don't need to be registered before lookup.
+    roundtrip(Box::new(Square { side: 2.0 }));
+    roundtrip(Box::new(Rectangle {
+        width: 2.0,
+        height: 3.0,
+    }));
+}
+
+#[test]
+#[allow(non_local_definitions)]
+fn registered_and_link_time_impls() {
+    register_trait_impls! {
+        hashed;
+        ArchivedCircle as dyn DeserializeShape<Error, Error>,
+    }
+
+    let impl_id = <ArchivedCircle as RegisteredImpl<
+        dyn DeserializeShape<Error, Error>,
+    >>::IMPL_ID;
+    assert!(find_trait_impl(impl_id).is_some());
+
+    roundtrip(Box::new(Circle { radius: 1.0 }));
+    roundtrip(Box::new(Square { side: 2.0 }));
+}
+
+#[ptr_meta::pointee]
+trait Marker {}
+
+struct A;
+struct B;
+
+impl Marker for A {}
+impl Marker for B {}
+
+register_trait_impl!(A as dyn Marker = "marker");
+
+#[test]
+#[should_panic = "was registered by `register_trait_impls!` and by \
+                  `register_trait_impl!`"]
+#[allow(non_local_definitions)]
+fn registered_conflicts_with_link_time() {
+    register_trait_impls! {
+        hashed;
+        B as dyn Marker = "marker",
+    }
+}
@@ -0,0 +1,26 @@
+//! Tests for trait impls registered at link time with colliding fingerprints.
+//!
+//! Colliding link-time trait impls make every lookup panic, so these tests live
+//! in their own test binary.
+
+#![cfg(feature = "linkme")]
+
+use rkyv_dyn::{find_trait_impl, register_trait_impl};
+
+#[ptr_meta::pointee]
+trait Marker {}
+
+struct A;
+struct B;
+
+impl Marker for A {}
+impl Marker for B {}
+
+register_trait_impl!(A as dyn Marker = "marker");
+register_trait_impl!(B as dyn Marker = "marker");
+
+#[test]
+#[should_panic = "both hash to impl ID"]
+fn link_time_collision() {
+    find_trait_impl(0);
+}